@@ -106,12 +106,10 @@ mod tests {
         let info = mock_info("creator", &coins(1000, "earth"));
         // we can just call .unwrap() to assert this was a success
         let res = instantiate(deps.as_mut(), mock_env(), info, msg);
-        match res.unwrap_err() {
-            StdError::GenericErr { msg, .. } => {
-                assert_eq!(msg, "You can only use this contract for migrations")
-            }
-            _ => panic!("expected migrate error message"),
-        }
+        assert_eq!(
+            res.unwrap_err(),
+            StdError::generic_err("You can only use this contract for migrations")
+        );
     }
 
     #[test]
@@ -218,7 +218,8 @@ mod tests {
         mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
     };
     use cosmwasm_std::{
-        from_json, Binary, OwnedDeps, RecoverPubkeyError, StdError, VerificationError,
+        from_json, Binary, OwnedDeps, RecoverPubkeyError, StdError, StdErrorPayload,
+        VerificationError,
     };
     use hex_literal::hex;
 
@@ -317,13 +318,10 @@ mod tests {
 
         let res = query(deps.as_ref(), mock_env(), verify_msg);
         assert!(res.is_err());
-        assert!(matches!(
+        assert_eq!(
             res.unwrap_err(),
-            StdError::VerificationErr {
-                source: VerificationError::InvalidPubkeyFormat,
-                ..
-            }
-        ))
+            StdError::verification_err(VerificationError::InvalidPubkeyFormat)
+        )
     }
 
     #[test]
@@ -392,13 +390,14 @@ mod tests {
             signer_address: signer_address.into(),
         };
         let result = query(deps.as_ref(), mock_env(), verify_msg);
-        match result.unwrap_err() {
-            StdError::RecoverPubkeyErr {
-                source: RecoverPubkeyError::UnknownErr { .. },
-                ..
-            } => {}
-            err => panic!("Unexpected error: {err:?}"),
-        }
+        let err = result.unwrap_err();
+        assert!(
+            matches!(
+                err.payload(),
+                StdErrorPayload::RecoverPubkey(RecoverPubkeyError::UnknownErr { .. })
+            ),
+            "Unexpected error: {err:?}"
+        );
     }
 
     #[test]
@@ -606,13 +605,10 @@ mod tests {
         };
         let res = query(deps.as_ref(), mock_env(), verify_msg);
         assert!(res.is_err());
-        assert!(matches!(
+        assert_eq!(
             res.unwrap_err(),
-            StdError::VerificationErr {
-                source: VerificationError::InvalidPubkeyFormat,
-                ..
-            }
-        ))
+            StdError::verification_err(VerificationError::InvalidPubkeyFormat)
+        )
     }
 
     #[test]
@@ -672,13 +668,10 @@ mod tests {
         };
         let res = query(deps.as_ref(), mock_env(), verify_msg);
         assert!(res.is_err());
-        assert!(matches!(
+        assert_eq!(
             res.unwrap_err(),
-            StdError::VerificationErr {
-                source: VerificationError::InvalidPubkeyFormat,
-                ..
-            }
-        ))
+            StdError::verification_err(VerificationError::InvalidPubkeyFormat)
+        )
     }
 
     #[test]
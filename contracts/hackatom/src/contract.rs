@@ -186,7 +186,7 @@ fn do_user_errors_in_api_calls(api: &dyn Api) -> Result<Response, HackError> {
 
     let empty = "";
     match api.addr_canonicalize(empty).unwrap_err() {
-        StdError::GenericErr { .. } => {}
+        err if err.is_generic_err() => {}
         err => {
             return Err(StdError::generic_err(format!(
                 "Unexpected error in do_user_errors_in_api_calls: {err:?}"
@@ -198,7 +198,7 @@ fn do_user_errors_in_api_calls(api: &dyn Api) -> Result<Response, HackError> {
     let too_long =
         "bn9hhssomeltvhzgvuqkwjkpwxojfuigltwedayzxljucefikuieillowaticksoistqoynmgcnj219aewfwefwwegwg";
     match api.addr_canonicalize(too_long).unwrap_err() {
-        StdError::GenericErr { .. } => {}
+        err if err.is_generic_err() => {}
         err => {
             return Err(StdError::generic_err(format!(
                 "Unexpected error in do_user_errors_in_api_calls: {err:?}"
@@ -211,7 +211,7 @@ fn do_user_errors_in_api_calls(api: &dyn Api) -> Result<Response, HackError> {
 
     let empty: CanonicalAddr = vec![].into();
     match api.addr_humanize(&empty).unwrap_err() {
-        StdError::GenericErr { .. } => {}
+        err if err.is_generic_err() => {}
         err => {
             return Err(StdError::generic_err(format!(
                 "Unexpected error in do_user_errors_in_api_calls: {err:?}"
@@ -222,7 +222,7 @@ fn do_user_errors_in_api_calls(api: &dyn Api) -> Result<Response, HackError> {
 
     let too_short: CanonicalAddr = vec![0xAA, 0xBB, 0xCC].into();
     match api.addr_humanize(&too_short).unwrap_err() {
-        StdError::GenericErr { .. } => {}
+        err if err.is_generic_err() => {}
         err => {
             return Err(StdError::generic_err(format!(
                 "Unexpected error in do_user_errors_in_api_calls: {err:?}"
@@ -233,7 +233,7 @@ fn do_user_errors_in_api_calls(api: &dyn Api) -> Result<Response, HackError> {
 
     let wrong_length: CanonicalAddr = vec![0xA6; 17].into();
     match api.addr_humanize(&wrong_length).unwrap_err() {
-        StdError::GenericErr { .. } => {}
+        err if err.is_generic_err() => {}
         err => {
             return Err(StdError::generic_err(format!(
                 "Unexpected error in do_user_errors_in_api_calls: {err:?}"
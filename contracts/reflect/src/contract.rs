@@ -350,8 +350,9 @@ mod tests {
         };
         let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
         match err {
-            ReflectError::Std(StdError::GenericErr { msg, .. }) => {
-                assert!(msg.contains("human address too short"))
+            ReflectError::Std(err) => {
+                assert!(err.is_generic_err());
+                assert!(err.to_string().contains("human address too short"))
             }
             e => panic!("Unexpected error: {e:?}"),
         }
@@ -7,11 +7,11 @@ use std::process::exit;
 use clap::{Arg, ArgAction, Command};
 use colored::Colorize;
 
-use cosmwasm_vm::capabilities_from_csv;
-use cosmwasm_vm::internals::{check_wasm, compile, make_compiling_engine};
+use cosmwasm_vm::checker::{self, CheckLimits};
+use cosmwasm_vm::{capabilities_from_csv, Capability};
 
 const DEFAULT_AVAILABLE_CAPABILITIES: &str =
-    "iterator,staking,stargate,cosmwasm_1_1,cosmwasm_1_2,cosmwasm_1_3,cosmwasm_1_4";
+    "iterator,staking,stargate,cosmwasm_1_1,cosmwasm_1_2,cosmwasm_1_3,cosmwasm_1_4,query_chain_binary,query_batch,chunked_results,db_size,iterator_seek,ed25519_variants,gas_remaining,emit_event,chain_queries,staking_pool_params,ibc_channel_filters";
 
 pub fn main() {
     let matches = Command::new("Contract checking")
@@ -87,7 +87,7 @@ pub fn main() {
 
 fn check_contract(
     path: impl AsRef<Path>,
-    available_capabilities: &HashSet<String>,
+    available_capabilities: &HashSet<Capability>,
 ) -> anyhow::Result<()> {
     let mut file = File::open(path)?;
 
@@ -95,12 +95,14 @@ fn check_contract(
     let mut wasm = Vec::<u8>::new();
     file.read_to_end(&mut wasm)?;
 
-    // Check wasm
-    check_wasm(&wasm, available_capabilities)?;
-
-    // Compile module
-    let engine = make_compiling_engine(None);
-    let _module = compile(&engine, &wasm)?;
+    // Check and compile wasm
+    let report = checker::check_contract(&wasm, available_capabilities, &CheckLimits::default());
+    if let Some(err) = report.validation_error {
+        return Err(err.into());
+    }
+    if let Some(err) = report.compile_error {
+        return Err(err.into());
+    }
 
     Ok(())
 }
@@ -1,10 +1,22 @@
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::IsIdentity;
+use digest::Digest;
 use ed25519_zebra::{batch, Signature, VerificationKey};
 use rand_core::OsRng;
+use sha2::Sha512;
 
 use crate::errors::{CryptoError, CryptoResult};
 
 /// Length of a serialized public key
 pub const EDDSA_PUBKEY_LEN: usize = 32;
+/// Length of the SHA-512 message hash signed in Ed25519ph
+pub const EDDSA_PREHASH_LEN: usize = 64;
+/// Max length of the context of Ed25519ctx and Ed25519ph signatures
+pub const EDDSA_CONTEXT_MAX_LEN: usize = 255;
+
+/// Prefix of the domain separator used by Ed25519ctx and Ed25519ph (see RFC 8032, section 5.1)
+const DOM2_PREFIX: &[u8] = b"SigEd25519 no Ed25519 collisions";
 
 /// EdDSA ed25519 implementation.
 ///
@@ -30,6 +42,101 @@ pub fn ed25519_verify(message: &[u8], signature: &[u8], public_key: &[u8]) -> Cr
     }
 }
 
+/// EdDSA Ed25519ctx implementation.
+///
+/// This function verifies messages against a signature, with the public key of the signer,
+/// using the Ed25519ctx variant from [RFC 8032](https://www.rfc-editor.org/rfc/rfc8032#section-5.1).
+/// The signature is bound to the given context, which must be between 1 and 255 bytes long.
+///
+/// The signature and public key are in the same format as for [`ed25519_verify`].
+pub fn ed25519ctx_verify(
+    message: &[u8],
+    context: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+) -> CryptoResult<bool> {
+    if context.is_empty() {
+        return Err(CryptoError::generic_err(
+            "Ed25519ctx requires a non-empty context",
+        ));
+    }
+    verify_with_domain(false, message, context, signature, public_key)
+}
+
+/// EdDSA Ed25519ph implementation.
+///
+/// This function verifies pre-hashed messages against a signature, with the public key of the signer,
+/// using the Ed25519ph variant from [RFC 8032](https://www.rfc-editor.org/rfc/rfc8032#section-5.1).
+/// This is the variant typically used by hardware wallets, which can not hold the full message in memory.
+///
+/// - message hash: the SHA-512 hash of the message (64 bytes).
+/// - context: an optional context of up to 255 bytes. Use an empty slice for no context.
+///
+/// The signature and public key are in the same format as for [`ed25519_verify`].
+pub fn ed25519ph_verify(
+    message_hash: &[u8],
+    context: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+) -> CryptoResult<bool> {
+    if message_hash.len() != EDDSA_PREHASH_LEN {
+        return Err(CryptoError::invalid_hash_format());
+    }
+    verify_with_domain(true, message_hash, context, signature, public_key)
+}
+
+/// Verifies a signature with the `dom2(phflag, context)` domain separator of RFC 8032.
+///
+/// Like ed25519-zebra, this uses the cofactored verification equation and accepts non-canonical
+/// point encodings as specified in [ZIP 215](https://zips.z.cash/zip-0215), such that all
+/// ed25519 variants behave the same.
+fn verify_with_domain(
+    prehashed: bool,
+    message: &[u8],
+    context: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+) -> CryptoResult<bool> {
+    // Validation
+    if context.len() > EDDSA_CONTEXT_MAX_LEN {
+        return Err(CryptoError::generic_err(format!(
+            "Context must not be longer than {EDDSA_CONTEXT_MAX_LEN} bytes"
+        )));
+    }
+    let signature = read_signature(signature)?;
+    let pubkey = read_pubkey(public_key)?;
+
+    let mut r_bytes = [0u8; 32];
+    r_bytes.copy_from_slice(&signature[..32]);
+    let mut s_bytes = [0u8; 32];
+    s_bytes.copy_from_slice(&signature[32..]);
+
+    let (Some(a), Some(r), Some(s)) = (
+        CompressedEdwardsY(pubkey).decompress(),
+        CompressedEdwardsY(r_bytes).decompress(),
+        Scalar::from_canonical_bytes(s_bytes),
+    ) else {
+        return Ok(false);
+    };
+
+    // Verification
+    let hash = Sha512::new()
+        .chain_update(DOM2_PREFIX)
+        .chain_update([u8::from(prehashed), context.len() as u8])
+        .chain_update(context)
+        .chain_update(r_bytes)
+        .chain_update(pubkey)
+        .chain_update(message)
+        .finalize();
+    let mut hash_bytes = [0u8; 64];
+    hash_bytes.copy_from_slice(&hash);
+    let k = Scalar::from_bytes_mod_order_wide(&hash_bytes);
+
+    // [8][s]B = [8]R + [8][k]A
+    let expected_r = EdwardsPoint::vartime_double_scalar_mul_basepoint(&k, &-a, &s);
+    Ok((r - expected_r).mul_by_cofactor().is_identity())
+}
+
 /// Performs batch Ed25519 signature verification.
 ///
 /// Batch verification asks whether all signatures in some set are valid, rather than asking whether
@@ -573,4 +680,116 @@ mod tests {
         // ed25519_batch_verify() works for empty msgs / sigs
         assert!(ed25519_batch_verify(&messages, &signatures, &public_keys).unwrap());
     }
+
+    // Test vectors from https://www.rfc-editor.org/rfc/rfc8032#section-7.2
+    const ED25519CTX_PUBLIC_KEY_HEX: &str =
+        "dfc9425e4f968f7f0c29f0259cf5f9aed6851c2bb4ad8bfb860cfee0ab248292";
+    const ED25519CTX_MSG_HEX: &str = "f726936d19c800494e3fdaff20b276a8";
+    const ED25519CTX_CONTEXT: &[u8] = b"foo";
+    const ED25519CTX_SIGNATURE_HEX: &str = "55a4cc2f70a54e04288c5f4cd1e45a7bb520b36292911876cada7323198dd87a8b36950b95130022907a7fb7c4e9b2d5f6cca685a587b4b21f4b888e4e7edb0d";
+
+    // Test vector from https://www.rfc-editor.org/rfc/rfc8032#section-7.3
+    const ED25519PH_PUBLIC_KEY_HEX: &str =
+        "ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf";
+    const ED25519PH_MSG: &[u8] = b"abc";
+    const ED25519PH_SIGNATURE_HEX: &str = "98a70222f0b8121aa9d30f813d683f809e462b469c7ff87639499bb94e6dae4131f85042463c2a355a2003d062adf5aaa10b8c61e636062aaad11c2a26083406";
+
+    #[test]
+    fn test_ed25519ctx_verify() {
+        let message = hex::decode(ED25519CTX_MSG_HEX).unwrap();
+        let signature = hex::decode(ED25519CTX_SIGNATURE_HEX).unwrap();
+        let public_key = hex::decode(ED25519CTX_PUBLIC_KEY_HEX).unwrap();
+
+        assert!(ed25519ctx_verify(&message, ED25519CTX_CONTEXT, &signature, &public_key).unwrap());
+
+        // Wrong context fails
+        assert!(!ed25519ctx_verify(&message, b"bar", &signature, &public_key).unwrap());
+
+        // Wrong message fails
+        let bad_message = [message.as_slice(), b"\0"].concat();
+        assert!(
+            !ed25519ctx_verify(&bad_message, ED25519CTX_CONTEXT, &signature, &public_key).unwrap()
+        );
+
+        // Pure Ed25519 does not accept the signature
+        assert!(!ed25519_verify(&message, &signature, &public_key).unwrap());
+    }
+
+    #[test]
+    fn test_ed25519ctx_verify_errors() {
+        let message = hex::decode(ED25519CTX_MSG_HEX).unwrap();
+        let signature = hex::decode(ED25519CTX_SIGNATURE_HEX).unwrap();
+        let public_key = hex::decode(ED25519CTX_PUBLIC_KEY_HEX).unwrap();
+
+        let res = ed25519ctx_verify(&message, b"", &signature, &public_key);
+        assert!(matches!(res, Err(CryptoError::GenericErr { .. })));
+
+        let res = ed25519ctx_verify(&message, &[0u8; 256], &signature, &public_key);
+        assert!(matches!(res, Err(CryptoError::GenericErr { .. })));
+
+        let res = ed25519ctx_verify(&message, ED25519CTX_CONTEXT, &signature[1..], &public_key);
+        assert!(matches!(
+            res,
+            Err(CryptoError::InvalidSignatureFormat { .. })
+        ));
+
+        let res = ed25519ctx_verify(&message, ED25519CTX_CONTEXT, &signature, &public_key[1..]);
+        assert!(matches!(res, Err(CryptoError::InvalidPubkeyFormat { .. })));
+    }
+
+    #[test]
+    fn test_ed25519ph_verify() {
+        use sha2::Sha512;
+
+        let message_hash = Sha512::digest(ED25519PH_MSG);
+        let signature = hex::decode(ED25519PH_SIGNATURE_HEX).unwrap();
+        let public_key = hex::decode(ED25519PH_PUBLIC_KEY_HEX).unwrap();
+
+        assert!(ed25519ph_verify(&message_hash, b"", &signature, &public_key).unwrap());
+
+        // Wrong context fails
+        assert!(!ed25519ph_verify(&message_hash, b"foo", &signature, &public_key).unwrap());
+
+        // Wrong message hash fails
+        let bad_message_hash = Sha512::digest(b"abd");
+        assert!(!ed25519ph_verify(&bad_message_hash, b"", &signature, &public_key).unwrap());
+
+        // Pure Ed25519 does not accept the signature for the hash or the message
+        assert!(!ed25519_verify(&message_hash, &signature, &public_key).unwrap());
+        assert!(!ed25519_verify(ED25519PH_MSG, &signature, &public_key).unwrap());
+    }
+
+    #[test]
+    fn test_ed25519ph_verify_errors() {
+        use sha2::Sha512;
+
+        let message_hash = Sha512::digest(ED25519PH_MSG);
+        let signature = hex::decode(ED25519PH_SIGNATURE_HEX).unwrap();
+        let public_key = hex::decode(ED25519PH_PUBLIC_KEY_HEX).unwrap();
+
+        let res = ed25519ph_verify(ED25519PH_MSG, b"", &signature, &public_key);
+        assert!(matches!(res, Err(CryptoError::InvalidHashFormat { .. })));
+
+        let res = ed25519ph_verify(&message_hash, &[0u8; 256], &signature, &public_key);
+        assert!(matches!(res, Err(CryptoError::GenericErr { .. })));
+
+        let res = ed25519ph_verify(&message_hash, b"", &signature[1..], &public_key);
+        assert!(matches!(
+            res,
+            Err(CryptoError::InvalidSignatureFormat { .. })
+        ));
+
+        let res = ed25519ph_verify(&message_hash, b"", &signature, &public_key[1..]);
+        assert!(matches!(res, Err(CryptoError::InvalidPubkeyFormat { .. })));
+    }
+
+    #[test]
+    fn test_ed25519ctx_verify_rejects_pure_ed25519_signature() {
+        let secret_key = SigningKey::new(OsRng);
+        let signature: [u8; 64] = secret_key.sign(MSG.as_bytes()).into();
+        let public_key: [u8; 32] = VerificationKey::from(&secret_key).into();
+
+        assert!(ed25519_verify(MSG.as_bytes(), &signature, &public_key).unwrap());
+        assert!(!ed25519ctx_verify(MSG.as_bytes(), b"foo", &signature, &public_key).unwrap());
+    }
 }
@@ -7,11 +7,15 @@ mod ed25519;
 mod errors;
 mod identity_digest;
 mod secp256k1;
+#[cfg(feature = "test-utils")]
+pub mod test_vectors;
 
 #[doc(hidden)]
-pub use crate::ed25519::EDDSA_PUBKEY_LEN;
+pub use crate::ed25519::{
+    ed25519_batch_verify, ed25519_verify, ed25519ctx_verify, ed25519ph_verify,
+};
 #[doc(hidden)]
-pub use crate::ed25519::{ed25519_batch_verify, ed25519_verify};
+pub use crate::ed25519::{EDDSA_CONTEXT_MAX_LEN, EDDSA_PREHASH_LEN, EDDSA_PUBKEY_LEN};
 #[doc(hidden)]
 pub use crate::errors::{CryptoError, CryptoResult};
 #[doc(hidden)]
@@ -0,0 +1,401 @@
+//! Test vectors for the crypto host functions.
+//!
+//! Embedders implementing the host functions (e.g. wasmvm or alternative runtimes)
+//! can use those vectors and the `assert_*` helpers to check their implementation
+//! against the same data this crate is tested with.
+//!
+//! This module requires the `test-utils` feature.
+use std::fmt::Debug;
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256, Sha512};
+
+/// Test data from <https://tools.ietf.org/html/rfc8032#section-7.1>
+const ED25519_TESTS_JSON: &str = include_str!("../testdata/ed25519_tests.json");
+
+/// Test data originally from <https://github.com/cosmos/cosmjs/blob/v0.24.0-alpha.22/packages/crypto/src/secp256k1.spec.ts#L195-L394>
+const SECP256K1_TESTS_JSON: &str = include_str!("../testdata/secp256k1_tests.json");
+
+/// Test data from the Wycheproof project (`ecdsa_secp256k1_sha256_test.json`), converted to
+/// 64 byte `r || s` signatures. Cases whose DER encoding cannot be represented in that form
+/// are not included.
+/// See <https://github.com/google/wycheproof/blob/master/testvectors/ecdsa_secp256k1_sha256_test.json>
+const SECP256K1_WYCHEPROOF_JSON: &str = include_str!("../testdata/secp256k1_wycheproof.json");
+
+#[derive(Deserialize)]
+struct EncodedEd25519 {
+    #[serde(rename = "pubkey")]
+    public_key: String,
+    message: String,
+    signature: String,
+}
+
+#[derive(Deserialize)]
+struct EncodedSecp256k1 {
+    message: String,
+    message_hash: String,
+    signature: String,
+    #[serde(rename = "pubkey")]
+    public_key: String,
+}
+
+#[derive(Deserialize)]
+struct EncodedSecp256k1Wycheproof {
+    #[serde(rename = "pubkey")]
+    public_key: String,
+    message: String,
+    signature: String,
+    valid: bool,
+}
+
+/// A valid Ed25519 signature of `message`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ed25519Vector {
+    pub message: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub public_key: Vec<u8>,
+}
+
+/// A valid Ed25519ctx or Ed25519ph signature.
+///
+/// For Ed25519ph, `message` is the SHA-512 hash of the signed message,
+/// as expected by [`ed25519ph_verify`](crate::ed25519ph_verify).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ed25519DomainVector {
+    pub message: Vec<u8>,
+    pub context: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub public_key: Vec<u8>,
+}
+
+/// A valid secp256k1 signature of `message_hash`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Secp256k1Vector {
+    pub message_hash: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub public_key: Vec<u8>,
+}
+
+/// A secp256k1 signature of `message_hash` that must verify if and only if `valid` is set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Secp256k1WycheproofVector {
+    pub message_hash: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub public_key: Vec<u8>,
+    pub valid: bool,
+}
+
+/// A secp256k1 signature with the public key that must be recovered from it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Secp256k1RecoverVector {
+    pub message_hash: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub recovery_param: u8,
+    /// The expected public key in uncompressed form (65 bytes)
+    pub public_key: Vec<u8>,
+}
+
+fn decode(data: &str) -> Vec<u8> {
+    hex::decode(data).expect("Test vector is not valid hex")
+}
+
+/// The Ed25519 test vectors from RFC 8032, section 7.1.
+pub fn ed25519_vectors() -> Vec<Ed25519Vector> {
+    let encoded: Vec<EncodedEd25519> =
+        serde_json::from_str(ED25519_TESTS_JSON).expect("Invalid Ed25519 test vectors");
+    encoded
+        .into_iter()
+        .map(|e| Ed25519Vector {
+            message: decode(&e.message),
+            signature: decode(&e.signature),
+            public_key: decode(&e.public_key),
+        })
+        .collect()
+}
+
+/// The Ed25519ctx test vector from RFC 8032, section 7.2.
+pub fn ed25519ctx_vectors() -> Vec<Ed25519DomainVector> {
+    vec![Ed25519DomainVector {
+        message: decode("f726936d19c800494e3fdaff20b276a8"),
+        context: b"foo".to_vec(),
+        signature: decode("55a4cc2f70a54e04288c5f4cd1e45a7bb520b36292911876cada7323198dd87a8b36950b95130022907a7fb7c4e9b2d5f6cca685a587b4b21f4b888e4e7edb0d"),
+        public_key: decode("dfc9425e4f968f7f0c29f0259cf5f9aed6851c2bb4ad8bfb860cfee0ab248292"),
+    }]
+}
+
+/// The Ed25519ph test vector from RFC 8032, section 7.3.
+pub fn ed25519ph_vectors() -> Vec<Ed25519DomainVector> {
+    vec![Ed25519DomainVector {
+        message: Sha512::digest(b"abc").to_vec(),
+        context: vec![],
+        signature: decode("98a70222f0b8121aa9d30f813d683f809e462b469c7ff87639499bb94e6dae4131f85042463c2a355a2003d062adf5aaa10b8c61e636062aaad11c2a26083406"),
+        public_key: decode("ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf"),
+    }]
+}
+
+/// The secp256k1 test vectors from cosmjs. The message hashes are SHA-256 hashes.
+pub fn secp256k1_vectors() -> Vec<Secp256k1Vector> {
+    let encoded: Vec<EncodedSecp256k1> =
+        serde_json::from_str(SECP256K1_TESTS_JSON).expect("Invalid secp256k1 test vectors");
+    encoded
+        .into_iter()
+        .map(|e| {
+            let message_hash = decode(&e.message_hash);
+            assert_eq!(message_hash, Sha256::digest(decode(&e.message)).to_vec());
+            Secp256k1Vector {
+                message_hash,
+                signature: decode(&e.signature),
+                public_key: decode(&e.public_key),
+            }
+        })
+        .collect()
+}
+
+/// The Wycheproof secp256k1 ECDSA vectors, including invalid signatures.
+/// The message hashes are SHA-256 hashes.
+pub fn secp256k1_wycheproof_vectors() -> Vec<Secp256k1WycheproofVector> {
+    let encoded: Vec<EncodedSecp256k1Wycheproof> = serde_json::from_str(SECP256K1_WYCHEPROOF_JSON)
+        .expect("Invalid secp256k1 Wycheproof test vectors");
+    encoded
+        .into_iter()
+        .map(|e| Secp256k1WycheproofVector {
+            message_hash: Sha256::digest(decode(&e.message)).to_vec(),
+            signature: decode(&e.signature),
+            public_key: decode(&e.public_key),
+            valid: e.valid,
+        })
+        .collect()
+}
+
+/// Public key recovery test vectors, including a high-S signature.
+pub fn secp256k1_recover_vectors() -> Vec<Secp256k1RecoverVector> {
+    vec![
+        // Test data from https://github.com/randombit/botan/blob/2.9.0/src/tests/data/pubkey/ecdsa_key_recovery.vec
+        // This is a high-S signature.
+        Secp256k1RecoverVector {
+            message_hash: decode("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF"),
+            signature: decode("E30F2E6A0F705F4FB5F8501BA79C7C0D3FAC847F1AD70B873E9797B17B89B39081F1A4457589F30D76AB9F89E748A68C8A94C30FE0BAC8FB5C0B54EA70BF6D2F"),
+            recovery_param: 0,
+            public_key: decode("04F3F8BB913AA68589A2C8C607A877AB05252ADBD963E1BE846DDEB8456942AEDCA2ED51F08CA3EF3DAC0A7504613D54CD539FC1B3CBC92453CD704B6A2D012B2C"),
+        },
+        // Test data calculated via Secp256k1.createSignature from @cosmjs/crypto
+        Secp256k1RecoverVector {
+            message_hash: decode("5ae8317d34d1e595e3fa7247db80c0af4320cce1116de187f8f7e2e099c0d8d0"),
+            signature: decode("45c0b7f8c09a9e1f1cea0c25785594427b6bf8f9f878a8af0b1abbb48e16d0920d8becd0c220f67c51217eecfd7184ef0732481c843857e6bc7fc095c4f6b788"),
+            recovery_param: 1,
+            public_key: decode("044a071e8a6e10aada2b8cf39fa3b5fb3400b04e99ea8ae64ceea1a977dbeaf5d5f8c8fbd10b71ab14cd561f7df8eb6da50f8a8d81ba564342244d26d1d4211595"),
+        },
+    ]
+}
+
+/// Returns a copy of the data with the last bit flipped (or a single byte for empty data).
+fn tampered(data: &[u8]) -> Vec<u8> {
+    let mut out = data.to_vec();
+    match out.last_mut() {
+        Some(last) => *last ^= 1,
+        None => out.push(0),
+    }
+    out
+}
+
+/// Returns true if the result is a successful verification. Errors count as a failed
+/// verification, since implementations may reject malformed input either way.
+fn verified<E>(result: Result<bool, E>) -> bool {
+    matches!(result, Ok(true))
+}
+
+/// Checks an implementation of `ed25519_verify(message, signature, public_key)`
+/// against [`ed25519_vectors`]. Every vector must verify and must not verify for a
+/// modified message, a modified signature or malformed inputs.
+pub fn assert_ed25519_verify<E: Debug>(verify: impl Fn(&[u8], &[u8], &[u8]) -> Result<bool, E>) {
+    for (i, v) in ed25519_vectors().iter().enumerate() {
+        assert!(
+            verify(&v.message, &v.signature, &v.public_key).unwrap(),
+            "Ed25519 vector {i} did not verify"
+        );
+        assert!(
+            !verify(&tampered(&v.message), &v.signature, &v.public_key).unwrap(),
+            "Ed25519 vector {i} verified for a modified message"
+        );
+        assert!(
+            !verified(verify(&v.message, &tampered(&v.signature), &v.public_key)),
+            "Ed25519 vector {i} verified for a modified signature"
+        );
+        assert!(
+            !verified(verify(
+                &v.message,
+                &v.signature[..v.signature.len() - 1],
+                &v.public_key
+            )),
+            "Ed25519 vector {i} verified for a truncated signature"
+        );
+        assert!(
+            !verified(verify(
+                &v.message,
+                &v.signature,
+                &v.public_key[..v.public_key.len() - 1]
+            )),
+            "Ed25519 vector {i} verified for a truncated public key"
+        );
+    }
+}
+
+/// Checks an implementation of `ed25519_batch_verify(messages, signatures, public_keys)`
+/// against [`ed25519_vectors`], which are verified as one batch.
+pub fn assert_ed25519_batch_verify<E: Debug>(
+    batch_verify: impl Fn(&[&[u8]], &[&[u8]], &[&[u8]]) -> Result<bool, E>,
+) {
+    let vectors = ed25519_vectors();
+    let messages: Vec<&[u8]> = vectors.iter().map(|v| v.message.as_slice()).collect();
+    let signatures: Vec<&[u8]> = vectors.iter().map(|v| v.signature.as_slice()).collect();
+    let public_keys: Vec<&[u8]> = vectors.iter().map(|v| v.public_key.as_slice()).collect();
+    assert!(
+        batch_verify(&messages, &signatures, &public_keys).unwrap(),
+        "Ed25519 batch did not verify"
+    );
+
+    let bad_message = tampered(messages[0]);
+    let mut bad_messages = messages.clone();
+    bad_messages[0] = &bad_message;
+    assert!(
+        !batch_verify(&bad_messages, &signatures, &public_keys).unwrap(),
+        "Ed25519 batch verified with a modified message"
+    );
+}
+
+/// Checks an implementation of `ed25519ctx_verify(message, context, signature, public_key)`
+/// or `ed25519ph_verify(message_hash, context, signature, public_key)` against the given
+/// vectors, i.e. [`ed25519ctx_vectors`] or [`ed25519ph_vectors`].
+pub fn assert_ed25519_domain_verify<E: Debug>(
+    vectors: &[Ed25519DomainVector],
+    verify: impl Fn(&[u8], &[u8], &[u8], &[u8]) -> Result<bool, E>,
+) {
+    for (i, v) in vectors.iter().enumerate() {
+        assert!(
+            verify(&v.message, &v.context, &v.signature, &v.public_key).unwrap(),
+            "Ed25519 domain vector {i} did not verify"
+        );
+        assert!(
+            !verify(
+                &tampered(&v.message),
+                &v.context,
+                &v.signature,
+                &v.public_key
+            )
+            .unwrap(),
+            "Ed25519 domain vector {i} verified for a modified message"
+        );
+        assert!(
+            !verify(&v.message, b"bar", &v.signature, &v.public_key).unwrap(),
+            "Ed25519 domain vector {i} verified for a different context"
+        );
+    }
+}
+
+/// Checks an implementation of `secp256k1_verify(message_hash, signature, public_key)`
+/// against [`secp256k1_vectors`]. Every vector must verify and must not verify for a
+/// modified message hash.
+pub fn assert_secp256k1_verify<E: Debug>(verify: impl Fn(&[u8], &[u8], &[u8]) -> Result<bool, E>) {
+    for (i, v) in secp256k1_vectors().iter().enumerate() {
+        assert!(
+            verify(&v.message_hash, &v.signature, &v.public_key).unwrap(),
+            "secp256k1 vector {i} did not verify"
+        );
+        assert!(
+            !verify(&tampered(&v.message_hash), &v.signature, &v.public_key).unwrap(),
+            "secp256k1 vector {i} verified for a modified message hash"
+        );
+        assert!(
+            !verified(verify(
+                &v.message_hash,
+                &tampered(&v.signature),
+                &v.public_key
+            )),
+            "secp256k1 vector {i} verified for a modified signature"
+        );
+        assert!(
+            !verified(verify(
+                &v.message_hash,
+                &v.signature[..v.signature.len() - 1],
+                &v.public_key
+            )),
+            "secp256k1 vector {i} verified for a truncated signature"
+        );
+        assert!(
+            !verified(verify(
+                &v.message_hash,
+                &v.signature,
+                &v.public_key[..v.public_key.len() - 1]
+            )),
+            "secp256k1 vector {i} verified for a truncated public key"
+        );
+    }
+}
+
+/// Checks an implementation of `secp256k1_verify(message_hash, signature, public_key)`
+/// against [`secp256k1_wycheproof_vectors`]. Valid vectors must verify. Invalid vectors
+/// must either not verify or return an error.
+pub fn assert_secp256k1_wycheproof<E: Debug>(
+    verify: impl Fn(&[u8], &[u8], &[u8]) -> Result<bool, E>,
+) {
+    for (i, v) in secp256k1_wycheproof_vectors().iter().enumerate() {
+        let result = verify(&v.message_hash, &v.signature, &v.public_key);
+        if v.valid {
+            assert!(
+                result.unwrap(),
+                "secp256k1 Wycheproof vector {i} did not verify"
+            );
+        } else {
+            assert!(
+                !verified(result),
+                "secp256k1 Wycheproof vector {i} is invalid but verified"
+            );
+        }
+    }
+}
+
+/// Checks an implementation of `secp256k1_recover_pubkey(message_hash, signature, recovery_param)`
+/// against [`secp256k1_recover_vectors`]. The recovered public keys must be uncompressed.
+pub fn assert_secp256k1_recover_pubkey<E: Debug>(
+    recover: impl Fn(&[u8], &[u8], u8) -> Result<Vec<u8>, E>,
+) {
+    for (i, v) in secp256k1_recover_vectors().iter().enumerate() {
+        assert_eq!(
+            recover(&v.message_hash, &v.signature, v.recovery_param).unwrap(),
+            v.public_key,
+            "secp256k1 recover vector {i} returned a different public key"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ed25519_batch_verify, ed25519_verify, ed25519ctx_verify, ed25519ph_verify,
+        secp256k1_recover_pubkey, secp256k1_verify,
+    };
+
+    #[test]
+    fn crate_implementation_passes_test_vectors() {
+        assert_ed25519_verify(ed25519_verify);
+        assert_ed25519_batch_verify(ed25519_batch_verify);
+        assert_ed25519_domain_verify(&ed25519ctx_vectors(), ed25519ctx_verify);
+        assert_ed25519_domain_verify(&ed25519ph_vectors(), ed25519ph_verify);
+        assert_secp256k1_verify(secp256k1_verify);
+        assert_secp256k1_wycheproof(secp256k1_verify);
+        assert_secp256k1_recover_pubkey(secp256k1_recover_pubkey);
+    }
+
+    #[test]
+    #[should_panic(expected = "Ed25519 vector 0 did not verify")]
+    fn assert_ed25519_verify_detects_broken_implementation() {
+        assert_ed25519_verify(|_, _, _| -> Result<bool, ()> { Ok(false) });
+    }
+
+    #[test]
+    #[should_panic(expected = "secp256k1 Wycheproof vector")]
+    fn assert_secp256k1_wycheproof_detects_accept_all_implementation() {
+        assert_secp256k1_wycheproof(|_, _, _| -> Result<bool, ()> { Ok(true) });
+    }
+}
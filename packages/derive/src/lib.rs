@@ -2,8 +2,26 @@
 extern crate syn;
 
 use proc_macro::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
+/// All entry points for which `cosmwasm_std::entry_point_check::undeclared` contains defaults
+const KNOWN_ENTRY_POINTS: &[&str] = &[
+    "instantiate",
+    "execute",
+    "migrate",
+    "sudo",
+    "reply",
+    "query",
+    "ibc_channel_open",
+    "ibc_channel_connect",
+    "ibc_channel_close",
+    "ibc_packet_receive",
+    "ibc_packet_ack",
+    "ibc_packet_timeout",
+];
+
 /// This attribute macro generates the boilerplate required to call into the
 /// contract-specific logic from the entry-points to the Wasm module.
 ///
@@ -51,6 +69,37 @@ use std::str::FromStr;
 ///
 /// where `InstantiateMsg`, `ExecuteMsg`, and `QueryMsg` are contract defined
 /// types that implement `DeserializeOwned + JsonSchema`.
+///
+/// All entry points in one module must use the same `CustomQuery` type in `Deps`/`DepsMut`
+/// and the same `CustomMsg` type in their response. Entry points using `Empty` (explicitly
+/// or by omitting the generic argument) are compatible with all others. Mismatches are
+/// reported at compile time as an unsatisfied `AllEntryPointsMustUseTheSameCustomQuery` or
+/// `AllEntryPointsMustUseTheSameCustomMsg` bound:
+///
+/// ```compile_fail
+/// # use cosmwasm_std::{
+/// #     entry_point, CustomMsg, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+/// # };
+/// # use schemars::JsonSchema;
+/// # use serde::{Deserialize, Serialize};
+/// #
+/// # #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// # pub struct MsgA {}
+/// # impl CustomMsg for MsgA {}
+/// # #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// # pub struct MsgB {}
+/// # impl CustomMsg for MsgB {}
+/// #
+/// #[entry_point]
+/// pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: Empty) -> StdResult<Response<MsgA>> {
+/// #   Ok(Default::default())
+/// }
+///
+/// #[entry_point]
+/// pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: Empty) -> StdResult<Response<MsgB>> {
+/// #   Ok(Default::default())
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn entry_point(_attr: TokenStream, mut item: TokenStream) -> TokenStream {
     let cloned = item.clone();
@@ -64,8 +113,12 @@ pub fn entry_point(_attr: TokenStream, mut item: TokenStream) -> TokenStream {
     // E.g. "ptr0, ptr1, ptr2, "
     let ptrs = (0..args).fold(String::new(), |acc, i| format!("{acc}ptr{i}, "));
 
+    let checks = custom_types_checks(&function.sig);
+
     let new_code = format!(
         r##"
+        {checks}
+
         #[cfg(target_arch = "wasm32")]
         mod __wasm_export_{name} {{ // new module to avoid conflict of function name
             #[no_mangle]
@@ -79,3 +132,349 @@ pub fn entry_point(_attr: TokenStream, mut item: TokenStream) -> TokenStream {
     item.extend(entry);
     item
 }
+
+/// This attribute macro implements the boilerplate of a contract error enum.
+///
+/// It generates
+/// - a `Display` implementation based on the `#[error("...")]` attributes of the variants,
+/// - an `std::error::Error` implementation,
+/// - `From<StdError>` for the variant wrapping a `StdError`, which is required, and
+/// - a `code` method returning the error codes given by optional `#[code(...)]` attributes.
+///
+/// The format strings of `#[error("...")]` can refer to the fields of a variant by name
+/// (e.g. `{sender}`) or by position for tuple variants (e.g. `{0}`). Variants without
+/// an `#[error("...")]` attribute are displayed as their name, except for the `StdError`
+/// variant, which is displayed like the wrapped error.
+///
+/// ```
+/// use cosmwasm_std::{cw_error, StdError};
+///
+/// #[cw_error]
+/// #[derive(Debug, PartialEq)]
+/// pub enum ContractError {
+///     Std(StdError),
+///     #[code(1)]
+///     Unauthorized {},
+///     #[error("Insufficient funds: needed {needed}, got {got}")]
+///     #[code(2)]
+///     InsufficientFunds { needed: u128, got: u128 },
+///     #[error("Unknown denom {0}")]
+///     UnknownDenom(String),
+/// }
+///
+/// fn load() -> Result<(), ContractError> {
+///     Err(StdError::not_found("Config"))?
+/// }
+///
+/// assert_eq!(load().unwrap_err().to_string(), "Config not found");
+/// assert_eq!(ContractError::Unauthorized {}.to_string(), "Unauthorized");
+/// assert_eq!(ContractError::Unauthorized {}.code(), Some(1));
+/// let err = ContractError::InsufficientFunds { needed: 5, got: 3 };
+/// assert_eq!(err.to_string(), "Insufficient funds: needed 5, got 3");
+/// assert_eq!(err.code(), Some(2));
+/// let err = ContractError::UnknownDenom("uatom".to_string());
+/// assert_eq!(err.to_string(), "Unknown denom uatom");
+/// assert_eq!(err.code(), None);
+/// ```
+///
+/// A variant wrapping `StdError` is required:
+///
+/// ```compile_fail
+/// # use cosmwasm_std::cw_error;
+/// #[cw_error]
+/// #[derive(Debug)]
+/// pub enum ContractError {
+///     Unauthorized {},
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn cw_error(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut item = parse_macro_input!(item as syn::ItemEnum);
+    match cw_error_impl(&mut item) {
+        Ok(impls) => {
+            let mut output = item.into_token_stream();
+            output.extend(impls);
+            output.into()
+        }
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn cw_error_impl(item: &mut syn::ItemEnum) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+
+    let mut std_variant = None;
+    let mut display_arms = Vec::new();
+    let mut code_arms = Vec::new();
+    let mut codes = BTreeMap::new();
+
+    for variant in &mut item.variants {
+        let ident = &variant.ident;
+        let mut message = None;
+        let mut code = None;
+        let mut attrs = Vec::new();
+        for attr in variant.attrs.drain(..) {
+            if attr.path.is_ident("error") {
+                message = Some(attr.parse_args::<syn::LitStr>()?);
+            } else if attr.path.is_ident("code") {
+                let lit = attr.parse_args::<syn::LitInt>()?;
+                let value = lit.base10_parse::<u32>()?;
+                if let Some(other) = codes.insert(value, ident.clone()) {
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        format!("error code {value} is already used by {other}"),
+                    ));
+                }
+                code = Some(value);
+            } else {
+                attrs.push(attr);
+            }
+        }
+        variant.attrs = attrs;
+
+        if is_std_error_variant(variant) {
+            if std_variant.is_some() {
+                return Err(syn::Error::new_spanned(
+                    &variant.ident,
+                    "only one variant may wrap StdError",
+                ));
+            }
+            std_variant = Some(variant.ident.clone());
+        }
+
+        let (pattern, fields) = match &variant.fields {
+            syn::Fields::Named(fields) => {
+                let idents: Vec<_> = fields.named.iter().map(|f| f.ident.clone()).collect();
+                (quote! { Self::#ident { #(#idents),* } }, idents.len())
+            }
+            syn::Fields::Unnamed(fields) => {
+                let idents: Vec<_> = (0..fields.unnamed.len())
+                    .map(|i| format_ident!("_{}", i))
+                    .collect();
+                (quote! { Self::#ident ( #(#idents),* ) }, idents.len())
+            }
+            syn::Fields::Unit => (quote! { Self::#ident }, 0),
+        };
+
+        let body = match message {
+            Some(message) => {
+                let format = syn::LitStr::new(
+                    &prefix_positional_arguments(&message.value()),
+                    message.span(),
+                );
+                quote! { ::core::write!(f, #format) }
+            }
+            None if std_variant.as_ref() == Some(ident) => {
+                quote! { ::core::fmt::Display::fmt(_0, f) }
+            }
+            None => {
+                let text = ident.to_string();
+                quote! { f.write_str(#text) }
+            }
+        };
+        let allow = (fields > 0).then(|| quote! { #[allow(unused_variables)] });
+        display_arms.push(quote! { #allow #pattern => #body, });
+
+        let code = match code {
+            Some(code) => quote! { ::core::option::Option::Some(#code) },
+            None => quote! { ::core::option::Option::None },
+        };
+        let ignored = match &variant.fields {
+            syn::Fields::Named(_) => quote! { Self::#ident { .. } },
+            syn::Fields::Unnamed(_) => quote! { Self::#ident ( .. ) },
+            syn::Fields::Unit => quote! { Self::#ident },
+        };
+        code_arms.push(quote! { #ignored => #code, });
+    }
+
+    let Some(std_variant) = std_variant else {
+        return Err(syn::Error::new_spanned(
+            &item.ident,
+            "a variant wrapping StdError is required, e.g. `Std(StdError)`",
+        ));
+    };
+    let std_type = match &item
+        .variants
+        .iter()
+        .find(|v| v.ident == std_variant)
+        .unwrap()
+        .fields
+    {
+        syn::Fields::Unnamed(fields) => fields.unnamed[0].ty.clone(),
+        _ => unreachable!(),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match self {
+                    #(#display_arms)*
+                }
+            }
+        }
+
+        impl #impl_generics ::std::error::Error for #name #ty_generics #where_clause {
+            fn source(&self) -> ::core::option::Option<&(dyn ::std::error::Error + 'static)> {
+                match self {
+                    Self::#std_variant(err) => ::core::option::Option::Some(err),
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#std_type> for #name #ty_generics #where_clause {
+            fn from(err: #std_type) -> Self {
+                Self::#std_variant(err)
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns the error code of this error, if one was assigned.
+            pub fn code(&self) -> ::core::option::Option<u32> {
+                match self {
+                    #(#code_arms)*
+                }
+            }
+        }
+    })
+}
+
+/// Returns true if this is a tuple variant with a single `StdError` field.
+fn is_std_error_variant(variant: &syn::Variant) -> bool {
+    match &variant.fields {
+        syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            last_path_segment(&fields.unnamed[0].ty)
+                .is_some_and(|segment| segment.ident == "StdError")
+        }
+        _ => false,
+    }
+}
+
+/// Turns positional arguments like `{0}` or `{1:?}` into references to the bindings
+/// `_0` and `_1`, such that they can be captured by the format string.
+fn prefix_positional_arguments(format: &str) -> String {
+    let mut out = String::with_capacity(format.len());
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        out.push(c);
+        if c == '{' {
+            match chars.peek() {
+                Some('{') => out.push(chars.next().unwrap()),
+                Some(d) if d.is_ascii_digit() => out.push('_'),
+                _ => {}
+            }
+        }
+    }
+    out
+}
+
+/// Generates the compile time checks for the custom types of the entry point.
+/// See `cosmwasm_std::entry_point_check` for details.
+fn custom_types_checks(sig: &syn::Signature) -> String {
+    let name = sig.ident.to_string();
+    let custom_query = sig.inputs.first().and_then(custom_query_type);
+    let custom_msg = match &sig.output {
+        syn::ReturnType::Type(_, ty) => custom_msg_type(ty),
+        syn::ReturnType::Default => None,
+    };
+
+    let mut aliases = String::new();
+    let mut assertions = String::new();
+    if let Some(ty) = &custom_query {
+        aliases.push_str(&format!(
+            "#[allow(non_camel_case_types)] type __cw_custom_query_{name} = cosmwasm_std::entry_point_check::Declared<{ty}>;\n"
+        ));
+        assertions.push_str(&format!(
+            "cosmwasm_std::entry_point_check::custom_query::<{ty}>();\n"
+        ));
+        for other in KNOWN_ENTRY_POINTS.iter().filter(|other| **other != name) {
+            assertions.push_str(&format!(
+                "cosmwasm_std::entry_point_check::same_custom_query::<__cw_custom_query_{name}, __cw_custom_query_{other}>();\n"
+            ));
+        }
+    }
+    if let Some(ty) = &custom_msg {
+        aliases.push_str(&format!(
+            "#[allow(non_camel_case_types)] type __cw_custom_msg_{name} = cosmwasm_std::entry_point_check::Declared<{ty}>;\n"
+        ));
+        assertions.push_str(&format!(
+            "cosmwasm_std::entry_point_check::custom_msg::<{ty}>();\n"
+        ));
+        for other in KNOWN_ENTRY_POINTS.iter().filter(|other| **other != name) {
+            assertions.push_str(&format!(
+                "cosmwasm_std::entry_point_check::same_custom_msg::<__cw_custom_msg_{name}, __cw_custom_msg_{other}>();\n"
+            ));
+        }
+    }
+    if assertions.is_empty() {
+        return String::new();
+    }
+
+    format!(
+        r##"
+        #[allow(unused_imports)]
+        use cosmwasm_std::entry_point_check::undeclared::*;
+        {aliases}
+        #[allow(dead_code)]
+        fn __cw_check_custom_types_{name}() {{
+            {assertions}
+        }}
+    "##
+    )
+}
+
+/// Returns the `CustomQuery` type of a `Deps` or `DepsMut` argument.
+/// `None` if the type is `Empty` or cannot be determined.
+fn custom_query_type(arg: &syn::FnArg) -> Option<String> {
+    let syn::FnArg::Typed(arg) = arg else {
+        return None;
+    };
+    let segment = last_path_segment(&arg.ty)?;
+    if segment.ident != "Deps" && segment.ident != "DepsMut" {
+        return None;
+    }
+    non_empty_type_argument(segment)
+}
+
+/// Returns the `CustomMsg` type of a `Response`, `IbcBasicResponse` or `IbcReceiveResponse`
+/// return type, which may be wrapped in a `Result` or `StdResult`.
+/// `None` if the type is `Empty` or cannot be determined.
+fn custom_msg_type(ty: &syn::Type) -> Option<String> {
+    let segment = last_path_segment(ty)?;
+    if segment.ident == "Result" || segment.ident == "StdResult" {
+        return custom_msg_type(first_type_argument(segment)?);
+    }
+    if segment.ident != "Response"
+        && segment.ident != "IbcBasicResponse"
+        && segment.ident != "IbcReceiveResponse"
+    {
+        return None;
+    }
+    non_empty_type_argument(segment)
+}
+
+fn last_path_segment(ty: &syn::Type) -> Option<&syn::PathSegment> {
+    match ty {
+        syn::Type::Path(ty) if ty.qself.is_none() => ty.path.segments.last(),
+        _ => None,
+    }
+}
+
+fn first_type_argument(segment: &syn::PathSegment) -> Option<&syn::Type> {
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|arg| match arg {
+        syn::GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
+fn non_empty_type_argument(segment: &syn::PathSegment) -> Option<String> {
+    let ty = first_type_argument(segment)?;
+    if last_path_segment(ty).is_some_and(|segment| segment.ident == "Empty") {
+        return None;
+    }
+    Some(ty.to_token_stream().to_string())
+}
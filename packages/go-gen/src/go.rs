@@ -29,6 +29,49 @@ impl Display for GoStruct {
     }
 }
 
+/// A Go string type with a constant for each of its values.
+/// This is used for Rust enums without inner data.
+pub struct GoStringEnum {
+    pub name: String,
+    pub docs: Option<String>,
+    pub values: Vec<GoEnumValue>,
+}
+
+impl Display for GoStringEnum {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // generate documentation
+        format_docs(f, self.docs.as_deref())?;
+        // generate type
+        writeln!(f, "type {} string", self.name)?;
+        f.write_char('\n')?;
+        // generate constants
+        writeln!(f, "const (")?;
+        {
+            let mut f = indented(f);
+            for value in &self.values {
+                format_docs(&mut f, value.docs.as_deref())?;
+                writeln!(
+                    f,
+                    "{}{} {} = \"{}\"",
+                    self.name,
+                    replace_acronyms(value.value.to_pascal_case()),
+                    self.name,
+                    value.value
+                )?;
+            }
+        }
+        f.write_char(')')?;
+        Ok(())
+    }
+}
+
+pub struct GoEnumValue {
+    /// The serialized value
+    pub value: String,
+    /// The documentation of the value
+    pub docs: Option<String>,
+}
+
 pub struct GoField {
     /// The name of the field in Rust (snake_case)
     pub rust_name: String,
@@ -103,7 +146,7 @@ impl Display for GoType {
     }
 }
 
-fn format_docs(f: &mut fmt::Formatter, docs: Option<&str>) -> fmt::Result {
+fn format_docs(f: &mut impl Write, docs: Option<&str>) -> fmt::Result {
     if let Some(docs) = docs {
         for line in docs.lines() {
             f.write_str("// ")?;
@@ -198,6 +241,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn go_string_enum_display_works() {
+        let ty = GoStringEnum {
+            name: "FooBar".to_string(),
+            docs: Some("FooBar is a test enum".to_string()),
+            values: vec![
+                GoEnumValue {
+                    value: "foo".to_string(),
+                    docs: Some("The foo value".to_string()),
+                },
+                GoEnumValue {
+                    value: "try_bar".to_string(),
+                    docs: None,
+                },
+            ],
+        };
+        assert_eq!(
+            format!("{}", ty),
+            "// FooBar is a test enum\ntype FooBar string\n\nconst (\n    // The foo value\n    FooBarFoo FooBar = \"foo\"\n    FooBarTryBar FooBar = \"try_bar\"\n)"
+        );
+    }
+
     #[test]
     fn go_type_def_display_works() {
         let ty = GoStruct {
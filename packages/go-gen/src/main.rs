@@ -29,14 +29,15 @@ fn generate_go(root: RootSchema) -> Result<String> {
         .context("failed to get type name")?;
 
     let mut types = vec![];
-    build_type(title, &root.schema, &mut types)
+    let mut enums = vec![];
+    build_type(title, &root.schema, &mut types, &mut enums)
         .with_context(|| format!("failed to generate {title}"))?;
 
     // go through additional definitions
     for (name, additional_type) in &root.definitions {
         additional_type
             .object()
-            .map(|def| build_type(name, def, &mut types))
+            .map(|def| build_type(name, def, &mut types, &mut enums))
             .and_then(|r| r)
             .context("failed to generate additional definitions")?;
     }
@@ -44,18 +45,36 @@ fn generate_go(root: RootSchema) -> Result<String> {
     for ty in types {
         writeln!(&mut code, "{ty}")?;
     }
+    for enm in enums {
+        writeln!(&mut code, "{enm}")?;
+    }
 
     Ok(code)
 }
 
 /// Generates Go structs for the given schema and adds them to `structs`.
 /// This will add more than one struct if the schema contains object types (anonymous structs).
-fn build_type(name: &str, schema: &SchemaObject, structs: &mut Vec<GoStruct>) -> Result<()> {
+/// Enums without inner data are added to `enums` instead.
+fn build_type(
+    name: &str,
+    schema: &SchemaObject,
+    structs: &mut Vec<GoStruct>,
+    enums: &mut Vec<GoStringEnum>,
+) -> Result<()> {
     if schema::custom_type_of(name).is_some() {
         // ignore custom types
         return Ok(());
     }
 
+    if let Some(values) = schema::string_enum_values(schema) {
+        enums.push(GoStringEnum {
+            name: replace_acronyms(to_pascal_case(name)),
+            docs: documentation(schema),
+            values,
+        });
+        return Ok(());
+    }
+
     // first detect if we have a struct or enum
     if let Some(obj) = schema.object.as_ref() {
         let strct = build_struct(name, schema, obj, structs)
@@ -321,6 +340,41 @@ mod tests {
         assert_code_eq(code, "type Empty struct { }");
     }
 
+    #[test]
+    fn unit_enum_works() {
+        #[cw_serde]
+        struct Outer {
+            state: MyState,
+        }
+
+        #[cw_serde]
+        enum MyState {
+            /// The first state
+            Init,
+            TryOpen,
+            Closed,
+        }
+
+        let schema = schemars::schema_for!(Outer);
+        let code = generate_go(schema).unwrap();
+        assert_code_eq(
+            code,
+            r#"
+            type Outer struct {
+                State MyState `json:"state"`
+            }
+            type MyState string
+
+            const (
+                MyStateTryOpen MyState = "try_open"
+                MyStateClosed MyState = "closed"
+                // The first state
+                MyStateInit MyState = "init"
+            )
+            "#,
+        );
+    }
+
     /// Compares the generated code for a given type with the code in the corresponding file in
     /// `tests/`.
     /// The file name is derived from the type name by replacing `::` with `__` and adding `.go`.
@@ -395,22 +449,12 @@ mod tests {
         enum ShouldFail1 {
             A(),
         }
-        #[cw_serde]
-        enum ShouldFail2 {
-            A,
-        }
         let schema = schemars::schema_for!(ShouldFail1);
         assert!(generate_go(schema)
             .unwrap_err()
             .root_cause()
             .to_string()
             .contains("array type with non-singular item type is not supported"));
-        let schema = schemars::schema_for!(ShouldFail2);
-        assert!(generate_go(schema)
-            .unwrap_err()
-            .root_cause()
-            .to_string()
-            .contains("failed to determine type for 'ShouldFail2'"));
     }
 
     #[test]
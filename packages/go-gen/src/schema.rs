@@ -4,7 +4,7 @@ use inflector::Inflector;
 use schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec};
 
 use crate::{
-    go::{GoField, GoStruct, GoType},
+    go::{GoEnumValue, GoField, GoStruct, GoType},
     utils::{replace_acronyms, suffixes},
 };
 
@@ -29,6 +29,46 @@ pub fn enum_variants(schema: &SchemaObject) -> Option<impl Iterator<Item = &Sche
     Some(schema.subschemas.as_ref()?.one_of.as_ref()?.iter())
 }
 
+/// Returns the values and their documentation if the schema is an enum without inner data,
+/// i.e. all variants are serialized as plain strings.
+/// Returns `None` if the schema is not such an enum.
+pub fn string_enum_values(schema: &SchemaObject) -> Option<Vec<GoEnumValue>> {
+    fn string_values(schema: &SchemaObject) -> Option<Vec<String>> {
+        schema
+            .enum_values
+            .as_ref()?
+            .iter()
+            .map(|v| v.as_str().map(ToString::to_string))
+            .collect()
+    }
+
+    if let Some(values) = string_values(schema) {
+        return Some(
+            values
+                .into_iter()
+                .map(|value| GoEnumValue { value, docs: None })
+                .collect(),
+        );
+    }
+
+    // if variants are documented, each of them is a separate subschema,
+    // while the undocumented ones are grouped together
+    let mut values = vec![];
+    for variant in enum_variants(schema)? {
+        let variant = variant.object().ok()?;
+        let docs = documentation(variant);
+        values.extend(
+            string_values(variant)?
+                .into_iter()
+                .map(|value| GoEnumValue {
+                    value,
+                    docs: docs.clone(),
+                }),
+        );
+    }
+    Some(values)
+}
+
 /// Returns the Go type for the given schema object and whether it is nullable.
 /// May also add additional structs to the given `Vec` that need to be generated for this type.
 pub fn schema_object_type(
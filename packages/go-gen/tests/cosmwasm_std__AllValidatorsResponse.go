@@ -7,8 +7,25 @@ type Validator struct {
 	Address string `json:"address"`
 	// decimal string, eg "0.02"
 	Commission string `json:"commission"`
+	// Whether the validator is jailed. Omitted if the chain does not provide it.
+	Jailed bool `json:"jailed,omitempty"`
 	// decimal string, eg "0.02"
 	MaxChangeRate string `json:"max_change_rate"`
 	// decimal string, eg "0.02"
 	MaxCommission string `json:"max_commission"`
-}
\ No newline at end of file
+	// The bonding status. Omitted if the chain does not provide it.
+	Status *BondStatus `json:"status,omitempty"`
+	// Bonded tokens in the staking denom. Omitted if the chain does not provide it.
+	Tokens string `json:"tokens,omitempty"`
+	// Height at which unbonding started. Omitted if the chain does not provide it.
+	UnbondingHeight uint64 `json:"unbonding_height,omitempty"`
+}
+
+// BondStatus is the bonding status of a validator.
+type BondStatus string
+
+const (
+	BondStatusUnbonded  BondStatus = "unbonded"
+	BondStatusUnbonding BondStatus = "unbonding"
+	BondStatusBonded    BondStatus = "bonded"
+)
\ No newline at end of file
@@ -10,6 +10,22 @@ type ListChannelsQuery struct {
 	PortID string `json:"port_id,omitempty"`
 }
 
+// ListChannelsFilteredQuery is like ListChannelsQuery, but with optional filters
+// which are applied by the chain.
+// Returns a `ListChannelsResponse`.
+type ListChannelsFilteredQuery struct {
+	// optional argument
+	ConnectionID string `json:"connection_id,omitempty"`
+	// optional argument
+	CounterpartyPortID string `json:"counterparty_port_id,omitempty"`
+	// optional argument
+	Pagination *PageRequest `json:"pagination,omitempty"`
+	// optional argument
+	PortID string `json:"port_id,omitempty"`
+	// optional argument
+	State *IBCChannelState `json:"state,omitempty"`
+}
+
 type ChannelQuery struct {
 	ChannelID string `json:"channel_id"`
 	// optional argument
@@ -19,7 +35,28 @@ type ChannelQuery struct {
 // IBCQuery defines a query request from the contract into the chain.
 // This is the counterpart of [IbcQuery](https://github.com/CosmWasm/cosmwasm/blob/v0.14.0-beta1/packages/std/src/ibc.rs#L61-L83).
 type IBCQuery struct {
-	PortID       *PortIDQuery       `json:"port_id,omitempty"`
-	ListChannels *ListChannelsQuery `json:"list_channels,omitempty"`
-	Channel      *ChannelQuery      `json:"channel,omitempty"`
+	PortID               *PortIDQuery               `json:"port_id,omitempty"`
+	ListChannels         *ListChannelsQuery         `json:"list_channels,omitempty"`
+	ListChannelsFiltered *ListChannelsFilteredQuery `json:"list_channels_filtered,omitempty"`
+	Channel              *ChannelQuery              `json:"channel,omitempty"`
+}
+
+// Simplified version of the cosmos-sdk PageRequest type
+type PageRequest struct {
+	Key     *[]byte `json:"key,omitempty"`
+	Limit   uint32  `json:"limit"`
+	Reverse bool    `json:"reverse"`
 }
+
+// IBCChannelState is the state of an IBC channel in the channel handshake
+type IBCChannelState string
+
+const (
+	IBCChannelStateClosed IBCChannelState = "closed"
+	// The channel was opened on this chain but not yet on the counterparty
+	IBCChannelStateInit IBCChannelState = "init"
+	// The channel was opened on this chain in response to an open init on the counterparty
+	IBCChannelStateTryOpen IBCChannelState = "try_open"
+	// The handshake is complete and packets can be sent
+	IBCChannelStateOpen IBCChannelState = "open"
+)
\ No newline at end of file
@@ -18,10 +18,18 @@ type ValidatorQuery struct {
 	Address string `json:"address"`
 }
 
+type PoolQuery struct {
+}
+
+type ParamsQuery struct {
+}
+
 type StakingQuery struct {
 	BondedDenom    *BondedDenomQuery    `json:"bonded_denom,omitempty"`
 	AllDelegations *AllDelegationsQuery `json:"all_delegations,omitempty"`
 	Delegation     *DelegationQuery     `json:"delegation,omitempty"`
 	AllValidators  *AllValidatorsQuery  `json:"all_validators,omitempty"`
 	Validator      *ValidatorQuery      `json:"validator,omitempty"`
+	Pool           *PoolQuery           `json:"pool,omitempty"`
+	Params         *ParamsQuery         `json:"params,omitempty"`
 }
\ No newline at end of file
@@ -0,0 +1,97 @@
+use syn::{parse_quote, Expr, ItemEnum, ItemImpl, LitStr, Variant};
+
+use crate::doc_comments::doc_comment;
+use crate::utils::optional_string;
+
+pub fn contract_errors_derive_impl(input: ItemEnum) -> ItemImpl {
+    let ident = input.ident;
+    let errors = input.variants.into_iter().map(parse_error);
+
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    parse_quote! {
+        #[automatically_derived]
+        #[cfg(not(target_arch = "wasm32"))]
+        impl #impl_generics ::cosmwasm_schema::ContractErrors for #ident #type_generics #where_clause {
+            fn errors() -> ::std::vec::Vec<::cosmwasm_schema::ErrorDescription> {
+                ::std::vec![
+                    #( #errors, )*
+                ]
+            }
+        }
+    }
+}
+
+/// Creates the error description for an enum variant. The message is taken from the
+/// `#[error("...")]` attribute used by `thiserror`, if it contains a format string.
+fn parse_error(v: Variant) -> Expr {
+    let name = v.ident.to_string();
+    let message = optional_string(
+        v.attrs
+            .iter()
+            .find(|attr| attr.path.is_ident("error"))
+            .and_then(|attr| attr.parse_args::<LitStr>().ok())
+            .map(|message| message.value()),
+    );
+    let description = optional_string(doc_comment(&v.attrs));
+
+    parse_quote! {
+        ::cosmwasm_schema::ErrorDescription {
+            name: #name.to_string(),
+            message: #message,
+            description: #description,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn happy_path() {
+        let input: ItemEnum = parse_quote! {
+            #[derive(Error, Debug, ContractErrors)]
+            pub enum ContractError {
+                #[error("{0}")]
+                Std(#[from] StdError),
+                /// The sender is not the admin
+                #[error("Unauthorized: {sender}")]
+                Unauthorized { sender: String },
+                #[error(transparent)]
+                Other(OtherError),
+            }
+        };
+
+        assert_eq!(
+            contract_errors_derive_impl(input),
+            parse_quote! {
+                #[automatically_derived]
+                #[cfg(not(target_arch = "wasm32"))]
+                impl ::cosmwasm_schema::ContractErrors for ContractError {
+                    fn errors() -> ::std::vec::Vec<::cosmwasm_schema::ErrorDescription> {
+                        ::std::vec![
+                            ::cosmwasm_schema::ErrorDescription {
+                                name: "Std".to_string(),
+                                message: ::std::option::Option::Some("{0}".to_string()),
+                                description: ::std::option::Option::None,
+                            },
+                            ::cosmwasm_schema::ErrorDescription {
+                                name: "Unauthorized".to_string(),
+                                message: ::std::option::Option::Some("Unauthorized: {sender}".to_string()),
+                                description: ::std::option::Option::Some("The sender is not the admin".to_string()),
+                            },
+                            ::cosmwasm_schema::ErrorDescription {
+                                name: "Other".to_string(),
+                                message: ::std::option::Option::None,
+                                description: ::std::option::Option::None,
+                            },
+                        ]
+                    }
+                }
+            }
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use syn::{parse_quote, Expr, ItemEnum, ItemImpl, Lit, Meta, NestedMeta, Variant};
+
+use crate::doc_comments::doc_comment;
+use crate::utils::{optional_string, to_snake_case};
+
+pub fn contract_events_derive_impl(input: ItemEnum) -> ItemImpl {
+    let ident = input.ident;
+    let events = input.variants.into_iter().map(parse_event);
+
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    parse_quote! {
+        #[automatically_derived]
+        #[cfg(not(target_arch = "wasm32"))]
+        impl #impl_generics ::cosmwasm_schema::ContractEvents for #ident #type_generics #where_clause {
+            fn events() -> ::std::vec::Vec<::cosmwasm_schema::EventDescription> {
+                ::std::vec![
+                    #( #events, )*
+                ]
+            }
+        }
+    }
+}
+
+/// Creates the event description for an enum variant.
+/// The event name is the variant name in snake_case unless overridden with `#[event(name = "...")]`.
+fn parse_event(v: Variant) -> Expr {
+    let name = event_name(&v).unwrap_or_else(|| to_snake_case(&v.ident.to_string()));
+    let description = optional_string(doc_comment(&v.attrs));
+    let attributes: Vec<_> = match v.fields {
+        syn::Fields::Named(fields) => fields
+            .named
+            .into_iter()
+            .map(|field| field.ident.unwrap().to_string())
+            .collect(),
+        syn::Fields::Unnamed(_) => {
+            panic!("attributes of event {} must be named fields", v.ident)
+        }
+        syn::Fields::Unit => vec![],
+    };
+
+    parse_quote! {
+        ::cosmwasm_schema::EventDescription {
+            name: #name.to_string(),
+            description: #description,
+            attributes: ::std::vec![ #( #attributes.to_string(), )* ],
+        }
+    }
+}
+
+fn event_name(v: &Variant) -> Option<String> {
+    let attr = v.attrs.iter().find(|attr| attr.path.is_ident("event"))?;
+    let Ok(Meta::List(list)) = attr.parse_meta() else {
+        panic!("event attribute must contain a meta list");
+    };
+    let mut name = None;
+    for nested in list.nested {
+        match nested {
+            NestedMeta::Meta(Meta::NameValue(meta)) if meta.path.is_ident("name") => {
+                let Lit::Str(value) = meta.lit else {
+                    panic!("event name must be a string literal");
+                };
+                name = Some(value.value());
+            }
+            _ => panic!("unrecognized event param for {}", v.ident),
+        }
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn happy_path() {
+        let input: ItemEnum = parse_quote! {
+            #[derive(ContractEvents)]
+            pub enum Event {
+                /// Tokens were sent
+                Transfer { from: String, to: String, amount: Uint128 },
+                #[event(name = "custom-name")]
+                Paused,
+            }
+        };
+
+        assert_eq!(
+            contract_events_derive_impl(input),
+            parse_quote! {
+                #[automatically_derived]
+                #[cfg(not(target_arch = "wasm32"))]
+                impl ::cosmwasm_schema::ContractEvents for Event {
+                    fn events() -> ::std::vec::Vec<::cosmwasm_schema::EventDescription> {
+                        ::std::vec![
+                            ::cosmwasm_schema::EventDescription {
+                                name: "transfer".to_string(),
+                                description: ::std::option::Option::Some("Tokens were sent".to_string()),
+                                attributes: ::std::vec![ "from".to_string(), "to".to_string(), "amount".to_string(), ],
+                            },
+                            ::cosmwasm_schema::EventDescription {
+                                name: "custom-name".to_string(),
+                                description: ::std::option::Option::None,
+                                attributes: ::std::vec![],
+                            },
+                        ]
+                    }
+                }
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "attributes of event Transfer must be named fields")]
+    fn tuple_variant_fails() {
+        let input: ItemEnum = parse_quote! {
+            pub enum Event {
+                Transfer(String),
+            }
+        };
+
+        contract_events_derive_impl(input);
+    }
+
+    #[test]
+    #[should_panic(expected = "unrecognized event param for Transfer")]
+    fn invalid_param_fails() {
+        let input: ItemEnum = parse_quote! {
+            pub enum Event {
+                #[event(foo = "bar")]
+                Transfer {},
+            }
+        };
+
+        contract_events_derive_impl(input);
+    }
+}
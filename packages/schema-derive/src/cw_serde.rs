@@ -1,37 +1,115 @@
-use syn::{parse_quote, DeriveInput};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_quote,
+    punctuated::Punctuated,
+    DeriveInput, Lit, LitStr, Meta, NestedMeta, Token,
+};
 
-pub fn cw_serde_impl(input: DeriveInput) -> DeriveInput {
+/// Options of the `#[cw_serde(...)]` attribute
+#[derive(Debug)]
+pub struct Options {
+    /// The path of the `cosmwasm_schema` crate
+    crate_path: String,
+    /// Do not add `#[serde(deny_unknown_fields)]`
+    allow_unknown_fields: bool,
+    /// Add `#[serde(untagged)]` (enums only)
+    untagged: bool,
+    /// Overrides the `rename_all` rule, which is `snake_case` for enums by default
+    rename_all: Option<LitStr>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            crate_path: "::cosmwasm_schema".to_string(),
+            allow_unknown_fields: false,
+            untagged: false,
+            rename_all: None,
+        }
+    }
+}
+
+impl Parse for Options {
+    fn parse(input: ParseStream) -> syn::parse::Result<Self> {
+        let params = Punctuated::<NestedMeta, Token![,]>::parse_terminated(input)?;
+
+        let mut options = Options::default();
+        for param in params {
+            match param {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("allow_unknown_fields") => {
+                    options.allow_unknown_fields = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("untagged") => {
+                    options.untagged = true;
+                }
+                NestedMeta::Meta(Meta::NameValue(meta)) if meta.path.is_ident("crate") => {
+                    let Lit::Str(value) = meta.lit else {
+                        panic!("crate must be a string literal");
+                    };
+                    value.parse::<syn::Path>()?;
+                    options.crate_path = value.value();
+                }
+                NestedMeta::Meta(Meta::NameValue(meta)) if meta.path.is_ident("rename_all") => {
+                    let Lit::Str(value) = meta.lit else {
+                        panic!("rename_all must be a string literal");
+                    };
+                    options.rename_all = Some(value);
+                }
+                _ => panic!("unrecognized cw_serde param"),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+pub fn cw_serde_impl(options: Options, input: DeriveInput) -> DeriveInput {
+    let crate_path: syn::Path = syn::parse_str(&options.crate_path).unwrap();
+    let serde_crate = format!("{}::serde", options.crate_path);
+    let schemars_crate = format!("{}::schemars", options.crate_path);
+
+    let mut serde_args: Vec<TokenStream> = vec![];
+    if !options.allow_unknown_fields {
+        serde_args.push(quote! { deny_unknown_fields });
+    }
     match input.data {
-        syn::Data::Struct(_) => parse_quote! {
-            #[derive(
-                ::cosmwasm_schema::serde::Serialize,
-                ::cosmwasm_schema::serde::Deserialize,
-                ::std::clone::Clone,
-                ::std::fmt::Debug,
-                ::std::cmp::PartialEq,
-                ::cosmwasm_schema::schemars::JsonSchema
-            )]
-            #[allow(clippy::derive_partial_eq_without_eq)] // Allow users of `#[cw_serde]` to not implement Eq without clippy complaining
-            #[serde(deny_unknown_fields, crate = "::cosmwasm_schema::serde")]
-            #[schemars(crate = "::cosmwasm_schema::schemars")]
-            #input
-        },
-        syn::Data::Enum(_) => parse_quote! {
-            #[derive(
-                ::cosmwasm_schema::serde::Serialize,
-                ::cosmwasm_schema::serde::Deserialize,
-                ::std::clone::Clone,
-                ::std::fmt::Debug,
-                ::std::cmp::PartialEq,
-                ::cosmwasm_schema::schemars::JsonSchema
-            )]
-            #[allow(clippy::derive_partial_eq_without_eq)] // Allow users of `#[cw_serde]` to not implement Eq without clippy complaining
-            #[serde(deny_unknown_fields, rename_all = "snake_case", crate = "::cosmwasm_schema::serde")]
-            #[schemars(crate = "::cosmwasm_schema::schemars")]
-            #input
-        },
+        syn::Data::Struct(_) => {
+            if options.untagged {
+                panic!("untagged is only supported for enums");
+            }
+            if let Some(rename_all) = &options.rename_all {
+                serde_args.push(quote! { rename_all = #rename_all });
+            }
+        }
+        syn::Data::Enum(_) => {
+            if options.untagged {
+                serde_args.push(quote! { untagged });
+            }
+            let rename_all = options
+                .rename_all
+                .unwrap_or_else(|| parse_quote! { "snake_case" });
+            serde_args.push(quote! { rename_all = #rename_all });
+        }
         syn::Data::Union(_) => panic!("unions are not supported"),
     }
+    serde_args.push(quote! { crate = #serde_crate });
+
+    parse_quote! {
+        #[derive(
+            #crate_path::serde::Serialize,
+            #crate_path::serde::Deserialize,
+            ::std::clone::Clone,
+            ::std::fmt::Debug,
+            ::std::cmp::PartialEq,
+            #crate_path::schemars::JsonSchema
+        )]
+        #[allow(clippy::derive_partial_eq_without_eq)] // Allow users of `#[cw_serde]` to not implement Eq without clippy complaining
+        #[serde(#(#serde_args),*)]
+        #[schemars(crate = #schemars_crate)]
+        #input
+    }
 }
 
 #[cfg(test)]
@@ -40,12 +118,15 @@ mod tests {
 
     #[test]
     fn structs() {
-        let expanded = cw_serde_impl(parse_quote! {
-            pub struct InstantiateMsg {
-                pub verifier: String,
-                pub beneficiary: String,
-            }
-        });
+        let expanded = cw_serde_impl(
+            Options::default(),
+            parse_quote! {
+                pub struct InstantiateMsg {
+                    pub verifier: String,
+                    pub beneficiary: String,
+                }
+            },
+        );
 
         let expected = parse_quote! {
             #[derive(
@@ -70,9 +151,12 @@ mod tests {
 
     #[test]
     fn empty_struct() {
-        let expanded = cw_serde_impl(parse_quote! {
-            pub struct InstantiateMsg {}
-        });
+        let expanded = cw_serde_impl(
+            Options::default(),
+            parse_quote! {
+                pub struct InstantiateMsg {}
+            },
+        );
 
         let expected = parse_quote! {
             #[derive(
@@ -94,14 +178,17 @@ mod tests {
 
     #[test]
     fn enums() {
-        let expanded = cw_serde_impl(parse_quote! {
-            pub enum SudoMsg {
-                StealFunds {
-                    recipient: String,
-                    amount: Vec<Coin>,
-                },
-            }
-        });
+        let expanded = cw_serde_impl(
+            Options::default(),
+            parse_quote! {
+                pub enum SudoMsg {
+                    StealFunds {
+                        recipient: String,
+                        amount: Vec<Coin>,
+                    },
+                }
+            },
+        );
 
         let expected = parse_quote! {
             #[derive(
@@ -129,11 +216,95 @@ mod tests {
     #[test]
     #[should_panic(expected = "unions are not supported")]
     fn unions() {
-        cw_serde_impl(parse_quote! {
-            pub union SudoMsg {
-                x: u32,
-                y: u32,
+        cw_serde_impl(
+            Options::default(),
+            parse_quote! {
+                pub union SudoMsg {
+                    x: u32,
+                    y: u32,
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn options_work() {
+        let options: Options = parse_quote! {
+            crate = "::my_crate::cw_schema", allow_unknown_fields, untagged, rename_all = "camelCase"
+        };
+        let expanded = cw_serde_impl(
+            options,
+            parse_quote! {
+                pub enum CustomMsg {
+                    Foo(FooMsg),
+                    Bar(BarMsg),
+                }
+            },
+        );
+
+        let expected = parse_quote! {
+            #[derive(
+                ::my_crate::cw_schema::serde::Serialize,
+                ::my_crate::cw_schema::serde::Deserialize,
+                ::std::clone::Clone,
+                ::std::fmt::Debug,
+                ::std::cmp::PartialEq,
+                ::my_crate::cw_schema::schemars::JsonSchema
+            )]
+            #[allow(clippy::derive_partial_eq_without_eq)]
+            #[serde(untagged, rename_all = "camelCase", crate = "::my_crate::cw_schema::serde")]
+            #[schemars(crate = "::my_crate::cw_schema::schemars")]
+            pub enum CustomMsg {
+                Foo(FooMsg),
+                Bar(BarMsg),
+            }
+        };
+
+        assert_eq!(expanded, expected);
+    }
+
+    #[test]
+    fn struct_options_work() {
+        let options: Options = parse_quote! { rename_all = "camelCase" };
+        let expanded = cw_serde_impl(
+            options,
+            parse_quote! {
+                pub struct Config {
+                    pub max_size: u32,
+                }
+            },
+        );
+
+        let expected = parse_quote! {
+            #[derive(
+                ::cosmwasm_schema::serde::Serialize,
+                ::cosmwasm_schema::serde::Deserialize,
+                ::std::clone::Clone,
+                ::std::fmt::Debug,
+                ::std::cmp::PartialEq,
+                ::cosmwasm_schema::schemars::JsonSchema
+            )]
+            #[allow(clippy::derive_partial_eq_without_eq)]
+            #[serde(deny_unknown_fields, rename_all = "camelCase", crate = "::cosmwasm_schema::serde")]
+            #[schemars(crate = "::cosmwasm_schema::schemars")]
+            pub struct Config {
+                pub max_size: u32,
             }
-        });
+        };
+
+        assert_eq!(expanded, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "untagged is only supported for enums")]
+    fn untagged_struct() {
+        let options: Options = parse_quote! { untagged };
+        cw_serde_impl(options, parse_quote! { pub struct Config {} });
+    }
+
+    #[test]
+    #[should_panic(expected = "unrecognized cw_serde param")]
+    fn invalid_option() {
+        let _options: Options = parse_quote! { deny_all };
     }
 }
@@ -0,0 +1,48 @@
+use syn::{Attribute, Lit, Meta};
+
+/// Joins the lines of all `///` doc comments, `None` if there are none
+pub fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<_> = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(meta)) => match meta.lit {
+                Lit::Str(doc) => Some(doc.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, Variant};
+
+    use super::*;
+
+    #[test]
+    fn doc_comment_works() {
+        let variant: Variant = parse_quote! {
+            Foo {}
+        };
+        assert_eq!(doc_comment(&variant.attrs), None);
+
+        let variant: Variant = parse_quote! {
+            /// First line
+            ///  second line
+            #[returns(Foo)]
+            Foo {}
+        };
+        assert_eq!(
+            doc_comment(&variant.attrs),
+            Some("First line\nsecond line".to_string())
+        );
+    }
+}
@@ -10,6 +10,15 @@ use syn::{
 pub fn write_api_impl(input: Options) -> Block {
     let api_object = generate_api_impl(&input);
     let name = input.name;
+    let typescript = if input.typescript {
+        quote! {
+            let path = out_dir.join(concat!(#name, ".d.ts"));
+            write(&path, api.to_typescript()).unwrap();
+            println!("Exported TypeScript definitions as {}", path.to_str().unwrap());
+        }
+    } else {
+        quote! {}
+    };
 
     parse_quote! {
         {
@@ -34,6 +43,8 @@ pub fn write_api_impl(input: Options) -> Block {
             write(&path, json + "\n").unwrap();
             println!("Exported the full API as {}", path.to_str().unwrap());
 
+            #typescript
+
             let raw_dir = out_dir.join("raw");
             create_dir_all(&raw_dir).unwrap();
 
@@ -57,6 +68,11 @@ pub fn generate_api_impl(input: &Options) -> ExprStruct {
         migrate,
         sudo,
         responses,
+        sudo_responses,
+        migrate_responses,
+        events,
+        errors,
+        ..
     } = input;
 
     parse_quote! {
@@ -69,6 +85,10 @@ pub fn generate_api_impl(input: &Options) -> ExprStruct {
             migrate: #migrate,
             sudo: #sudo,
             responses: #responses,
+            sudo_responses: #sudo_responses,
+            migrate_responses: #migrate_responses,
+            events: #events,
+            errors: #errors,
         }
     }
 }
@@ -77,6 +97,7 @@ pub fn generate_api_impl(input: &Options) -> ExprStruct {
 enum Value {
     Type(syn::Path),
     Str(syn::LitStr),
+    Bool(syn::LitBool),
 }
 
 impl Value {
@@ -95,11 +116,21 @@ impl Value {
             panic!("expected a string literal");
         }
     }
+
+    fn unwrap_bool(self) -> bool {
+        if let Self::Bool(b) = self {
+            b.value
+        } else {
+            panic!("expected a boolean literal");
+        }
+    }
 }
 
 impl Parse for Value {
     fn parse(input: ParseStream) -> syn::parse::Result<Self> {
-        if let Ok(p) = input.parse::<syn::Path>() {
+        if input.peek(syn::LitBool) {
+            Ok(Self::Bool(input.parse::<syn::LitBool>()?))
+        } else if let Ok(p) = input.parse::<syn::Path>() {
             Ok(Self::Type(p))
         } else {
             Ok(Self::Str(input.parse::<syn::LitStr>()?))
@@ -130,6 +161,11 @@ pub struct Options {
     migrate: TokenStream,
     sudo: TokenStream,
     responses: TokenStream,
+    sudo_responses: TokenStream,
+    migrate_responses: TokenStream,
+    events: TokenStream,
+    errors: TokenStream,
+    typescript: bool,
 }
 
 impl Parse for Options {
@@ -186,22 +222,58 @@ impl Parse for Options {
             None => (quote! { None }, quote! { None }),
         };
 
-        let migrate = match map.remove(&parse_quote!(migrate)) {
+        let with_migrate_responses = map
+            .remove(&parse_quote!(migrate_responses))
+            .is_some_and(Value::unwrap_bool);
+        let (migrate, migrate_responses) = match map.remove(&parse_quote!(migrate)) {
             Some(ty) => {
                 let ty = ty.unwrap_type();
-                quote! {Some(::cosmwasm_schema::schema_for!(#ty))}
+                (
+                    quote! {Some(::cosmwasm_schema::schema_for!(#ty))},
+                    responses_for(&ty, with_migrate_responses),
+                )
+            }
+            None if with_migrate_responses => {
+                panic!("migrate_responses requires a migrate msg type")
+            }
+            None => (quote! { None }, quote! { None }),
+        };
+
+        let with_sudo_responses = map
+            .remove(&parse_quote!(sudo_responses))
+            .is_some_and(Value::unwrap_bool);
+        let (sudo, sudo_responses) = match map.remove(&parse_quote!(sudo)) {
+            Some(ty) => {
+                let ty = ty.unwrap_type();
+                (
+                    quote! {Some(::cosmwasm_schema::schema_for!(#ty))},
+                    responses_for(&ty, with_sudo_responses),
+                )
+            }
+            None if with_sudo_responses => panic!("sudo_responses requires a sudo msg type"),
+            None => (quote! { None }, quote! { None }),
+        };
+
+        let events = match map.remove(&parse_quote!(events)) {
+            Some(ty) => {
+                let ty = ty.unwrap_type();
+                quote! { Some(<#ty as ::cosmwasm_schema::ContractEvents>::events()) }
             }
             None => quote! { None },
         };
 
-        let sudo = match map.remove(&parse_quote!(sudo)) {
+        let errors = match map.remove(&parse_quote!(errors)) {
             Some(ty) => {
                 let ty = ty.unwrap_type();
-                quote! {Some(::cosmwasm_schema::schema_for!(#ty))}
+                quote! { Some(<#ty as ::cosmwasm_schema::ContractErrors>::errors()) }
             }
             None => quote! { None },
         };
 
+        let typescript = map
+            .remove(&parse_quote!(typescript))
+            .is_some_and(Value::unwrap_bool);
+
         if let Some((invalid_option, _)) = map.into_iter().next() {
             panic!("unknown generate_api option: {invalid_option}");
         }
@@ -215,10 +287,24 @@ impl Parse for Options {
             migrate,
             sudo,
             responses,
+            sudo_responses,
+            migrate_responses,
+            events,
+            errors,
+            typescript,
         })
     }
 }
 
+/// The response schemas of a msg type deriving `QueryResponses` if `enabled`
+fn responses_for(ty: &syn::Path, enabled: bool) -> TokenStream {
+    if enabled {
+        quote! { Some(<#ty as ::cosmwasm_schema::QueryResponses>::response_schemas().unwrap()) }
+    } else {
+        quote! { None }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -237,6 +323,10 @@ mod tests {
                     migrate: None,
                     sudo: None,
                     responses: None,
+                    sudo_responses: None,
+                    migrate_responses: None,
+                    events: None,
+                    errors: None,
                 }
             }
         );
@@ -258,6 +348,10 @@ mod tests {
                     migrate: None,
                     sudo: None,
                     responses: None,
+                    sudo_responses: None,
+                    migrate_responses: None,
+                    events: None,
+                    errors: None,
                 }
             }
         );
@@ -281,6 +375,10 @@ mod tests {
                     migrate: None,
                     sudo: None,
                     responses: None,
+                    sudo_responses: None,
+                    migrate_responses: None,
+                    events: None,
+                    errors: None,
                 }
             }
         );
@@ -306,11 +404,108 @@ mod tests {
                     migrate: Some(::cosmwasm_schema::schema_for!(MigrateMsg)),
                     sudo: Some(::cosmwasm_schema::schema_for!(SudoMsg)),
                     responses: Some(<QueryMsg as ::cosmwasm_schema::QueryResponses>::response_schemas().unwrap()),
+                    sudo_responses: None,
+                    migrate_responses: None,
+                    events: None,
+                    errors: None,
                 }
             }
         );
     }
 
+    #[test]
+    fn api_object_sudo_and_migrate_responses() {
+        assert_eq!(
+            generate_api_impl(&parse_quote! {
+                instantiate: InstantiateMsg,
+                migrate: MigrateMsg,
+                migrate_responses: true,
+                sudo: SudoMsg,
+                sudo_responses: true,
+            }),
+            parse_quote! {
+                ::cosmwasm_schema::Api {
+                    contract_name: ::std::env!("CARGO_PKG_NAME").to_string(),
+                    contract_version: ::std::env!("CARGO_PKG_VERSION").to_string(),
+                    instantiate: Some(::cosmwasm_schema::schema_for!(InstantiateMsg)),
+                    execute: None,
+                    query: None,
+                    migrate: Some(::cosmwasm_schema::schema_for!(MigrateMsg)),
+                    sudo: Some(::cosmwasm_schema::schema_for!(SudoMsg)),
+                    responses: None,
+                    sudo_responses: Some(<SudoMsg as ::cosmwasm_schema::QueryResponses>::response_schemas().unwrap()),
+                    migrate_responses: Some(<MigrateMsg as ::cosmwasm_schema::QueryResponses>::response_schemas().unwrap()),
+                    events: None,
+                    errors: None,
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn api_object_events_and_errors() {
+        assert_eq!(
+            generate_api_impl(&parse_quote! {
+                instantiate: InstantiateMsg,
+                events: Event,
+                errors: ContractError,
+            }),
+            parse_quote! {
+                ::cosmwasm_schema::Api {
+                    contract_name: ::std::env!("CARGO_PKG_NAME").to_string(),
+                    contract_version: ::std::env!("CARGO_PKG_VERSION").to_string(),
+                    instantiate: Some(::cosmwasm_schema::schema_for!(InstantiateMsg)),
+                    execute: None,
+                    query: None,
+                    migrate: None,
+                    sudo: None,
+                    responses: None,
+                    sudo_responses: None,
+                    migrate_responses: None,
+                    events: Some(<Event as ::cosmwasm_schema::ContractEvents>::events()),
+                    errors: Some(<ContractError as ::cosmwasm_schema::ContractErrors>::errors()),
+                }
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "sudo_responses requires a sudo msg type")]
+    fn sudo_responses_without_sudo() {
+        let _options: Options = parse_quote! {
+            instantiate: InstantiateMsg,
+            sudo_responses: true,
+        };
+    }
+
+    #[test]
+    fn typescript_option_works() {
+        let options: Options = parse_quote! {
+            instantiate: InstantiateMsg,
+        };
+        assert!(!options.typescript);
+
+        let options: Options = parse_quote! {
+            instantiate: InstantiateMsg,
+            typescript: true,
+        };
+        assert!(options.typescript);
+        // does not affect the API object
+        assert_eq!(
+            generate_api_impl(&options),
+            generate_api_impl(&parse_quote! { instantiate: InstantiateMsg })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a boolean literal")]
+    fn typescript_option_must_be_bool() {
+        let _options: Options = parse_quote! {
+            instantiate: InstantiateMsg,
+            typescript: "yes",
+        };
+    }
+
     #[test]
     #[should_panic(expected = "unknown generate_api option: asd")]
     fn invalid_option() {
@@ -1,6 +1,10 @@
+mod contract_errors;
+mod contract_events;
 mod cw_serde;
+mod doc_comments;
 mod generate_api;
 mod query_responses;
+mod utils;
 
 use quote::ToTokens;
 use syn::{parse_macro_input, DeriveInput, ItemEnum};
@@ -14,6 +18,24 @@ pub fn query_responses_derive(input: proc_macro::TokenStream) -> proc_macro::Tok
     proc_macro::TokenStream::from(expanded)
 }
 
+#[proc_macro_derive(ContractEvents, attributes(event))]
+pub fn contract_events_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as ItemEnum);
+
+    let expanded = contract_events::contract_events_derive_impl(input).into_token_stream();
+
+    proc_macro::TokenStream::from(expanded)
+}
+
+#[proc_macro_derive(ContractErrors, attributes(error))]
+pub fn contract_errors_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as ItemEnum);
+
+    let expanded = contract_errors::contract_errors_derive_impl(input).into_token_stream();
+
+    proc_macro::TokenStream::from(expanded)
+}
+
 #[proc_macro]
 pub fn write_api(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as generate_api::Options);
@@ -34,12 +56,13 @@ pub fn generate_api(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
 #[proc_macro_attribute]
 pub fn cw_serde(
-    _attr: proc_macro::TokenStream,
+    attr: proc_macro::TokenStream,
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
+    let options = parse_macro_input!(attr as cw_serde::Options);
     let input = parse_macro_input!(input as DeriveInput);
 
-    let expanded = cw_serde::cw_serde_impl(input).into_token_stream();
+    let expanded = cw_serde::cw_serde_impl(options, input).into_token_stream();
 
     proc_macro::TokenStream::from(expanded)
 }
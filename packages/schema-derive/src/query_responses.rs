@@ -1,10 +1,12 @@
 mod context;
 
 use syn::{
-    parse_quote, Expr, ExprTuple, Generics, ItemEnum, ItemImpl, Type, TypeParamBound, Variant,
+    parse_quote, Expr, ExprTuple, Field, Generics, ItemEnum, ItemImpl, Meta, NestedMeta, Type,
+    TypeParamBound, Variant,
 };
 
 use self::context::Context;
+use crate::utils::to_snake_case;
 
 pub fn query_responses_derive_impl(input: ItemEnum) -> ItemImpl {
     let ctx = context::get_context(&input);
@@ -85,7 +87,7 @@ fn parse_query(v: Variant) -> (String, Expr) {
     let response_ty: Type = v
         .attrs
         .iter()
-        .find(|a| a.path.get_ident().unwrap() == "returns")
+        .find(|a| a.path.is_ident("returns"))
         .unwrap_or_else(|| panic!("missing return type for query: {}", v.ident))
         .parse_args()
         .unwrap_or_else(|_| panic!("return for {} must be a type", v.ident));
@@ -97,9 +99,17 @@ fn parse_query(v: Variant) -> (String, Expr) {
 }
 
 /// Extract the nested query  -> response mapping out of an enum variant.
+///
+/// The nested query is either the only field of a tuple variant or the
+/// only field of a struct variant if that field is marked `#[serde(flatten)]`.
 fn parse_subquery(v: Variant) -> Expr {
     let submsg = match v.fields {
-        syn::Fields::Named(_) => panic!("a struct variant is not a valid subquery"),
+        syn::Fields::Named(fields) => match fields.named.first() {
+            Some(field) if fields.named.len() == 1 && is_flattened(field) => field.ty.clone(),
+            _ => panic!(
+                "a struct variant is not a valid subquery, unless its only field is `#[serde(flatten)]`"
+            ),
+        },
         syn::Fields::Unnamed(fields) => {
             if fields.unnamed.len() != 1 {
                 panic!("invalid number of subquery parameters");
@@ -112,24 +122,26 @@ fn parse_subquery(v: Variant) -> Expr {
     parse_quote!(<#submsg as ::cosmwasm_schema::QueryResponses>::response_schemas_impl())
 }
 
+/// Checks if the field is annotated with `#[serde(flatten)]`
+fn is_flattened(field: &Field) -> bool {
+    field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("serde"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(l)) => Some(l.nested),
+            _ => None,
+        })
+        .flatten()
+        .any(|nested| matches!(nested, NestedMeta::Meta(Meta::Path(p)) if p.is_ident("flatten")))
+}
+
 fn parse_tuple((q, r): (String, Expr)) -> ExprTuple {
     parse_quote! {
         (#q.to_string(), #r)
     }
 }
 
-fn to_snake_case(input: &str) -> String {
-    // this was stolen from serde for consistent behavior
-    let mut snake = String::new();
-    for (i, ch) in input.char_indices() {
-        if i > 0 && ch.is_uppercase() {
-            snake.push('_');
-        }
-        snake.push(ch.to_ascii_lowercase());
-    }
-    snake
-}
-
 #[cfg(test)]
 mod tests {
     use syn::parse_quote;
@@ -293,6 +305,23 @@ mod tests {
         query_responses_derive_impl(input);
     }
 
+    #[test]
+    #[should_panic(expected = "missing return type for query: Supply")]
+    fn missing_return_generic() {
+        let input: ItemEnum = parse_quote! {
+            #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
+            #[serde(rename_all = "snake_case")]
+            pub enum QueryMsg<T> {
+                #[returns(T)]
+                Balance { key: T },
+                #[schemars::skip]
+                Supply {},
+            }
+        };
+
+        query_responses_derive_impl(input);
+    }
+
     #[test]
     #[should_panic(expected = "return for Supply must be a type")]
     fn invalid_return() {
@@ -335,12 +364,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn to_snake_case_works() {
-        assert_eq!(to_snake_case("SnakeCase"), "snake_case");
-        assert_eq!(to_snake_case("Wasm123AndCo"), "wasm123_and_co");
-    }
-
     #[test]
     fn nested_works() {
         let input: ItemEnum = parse_quote! {
@@ -443,4 +466,82 @@ mod tests {
         };
         query_responses_derive_impl(input);
     }
+
+    #[test]
+    fn untagged_is_nested() {
+        let input: ItemEnum = parse_quote! {
+            #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
+            #[serde(untagged)]
+            pub enum ContractQueryMsg {
+                Cw721(cw721::QueryMsg),
+                Extension(ExtensionQueryMsg),
+            }
+        };
+        let result = query_responses_derive_impl(input);
+        assert_eq!(
+            result,
+            parse_quote! {
+                #[automatically_derived]
+                #[cfg(not(target_arch = "wasm32"))]
+                impl ::cosmwasm_schema::QueryResponses for ContractQueryMsg {
+                    fn response_schemas_impl() -> ::std::collections::BTreeMap<String, ::cosmwasm_schema::schemars::schema::RootSchema> {
+                        let subqueries = [
+                            <cw721::QueryMsg as ::cosmwasm_schema::QueryResponses>::response_schemas_impl(),
+                            <ExtensionQueryMsg as ::cosmwasm_schema::QueryResponses>::response_schemas_impl(),
+                        ];
+                        ::cosmwasm_schema::combine_subqueries::<2usize, ContractQueryMsg>(subqueries)
+                    }
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn nested_flattened() {
+        let input: ItemEnum = parse_quote! {
+            #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
+            #[serde(untagged)]
+            pub enum ContractQueryMsg<T> {
+                Base {
+                    #[serde(flatten)]
+                    base: cw721::QueryMsg<T>,
+                },
+                Extension(ExtensionQueryMsg),
+            }
+        };
+        let result = query_responses_derive_impl(input);
+        assert_eq!(
+            result,
+            parse_quote! {
+                #[automatically_derived]
+                #[cfg(not(target_arch = "wasm32"))]
+                impl<T: ::cosmwasm_schema::schemars::JsonSchema + ::cosmwasm_schema::QueryResponses> ::cosmwasm_schema::QueryResponses for ContractQueryMsg<T> {
+                    fn response_schemas_impl() -> ::std::collections::BTreeMap<String, ::cosmwasm_schema::schemars::schema::RootSchema> {
+                        let subqueries = [
+                            <cw721::QueryMsg<T> as ::cosmwasm_schema::QueryResponses>::response_schemas_impl(),
+                            <ExtensionQueryMsg as ::cosmwasm_schema::QueryResponses>::response_schemas_impl(),
+                        ];
+                        ::cosmwasm_schema::combine_subqueries::<2usize, ContractQueryMsg<T> >(subqueries)
+                    }
+                }
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "a struct variant is not a valid subquery")]
+    fn nested_struct_variant_with_many_fields() {
+        let input: ItemEnum = parse_quote! {
+            #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
+            #[serde(untagged)]
+            pub enum ContractQueryMsg {
+                Base {
+                    #[serde(flatten)]
+                    base: cw721::QueryMsg,
+                    extra: bool,
+                },
+            }
+        };
+        query_responses_derive_impl(input);
+    }
 }
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use syn::{Ident, ItemEnum, Meta, NestedMeta};
+use syn::{Attribute, Ident, ItemEnum, Meta, NestedMeta};
 
 const ATTR_PATH: &str = "query_responses";
 
@@ -8,6 +8,7 @@ pub struct Context {
     /// If the enum we're trying to derive QueryResponses for collects other QueryMsgs,
     /// setting this flag will derive the implementation appropriately, collecting all
     /// KV pairs from the nested enums rather than expecting `#[return]` annotations.
+    /// This is implied by `#[serde(untagged)]`.
     pub is_nested: bool,
     /// Disable infering the `JsonSchema` trait bound for chosen type parameters.
     pub no_bounds_for: HashSet<Ident>,
@@ -38,6 +39,11 @@ pub fn get_context(input: &ItemEnum) -> Context {
         no_bounds_for: HashSet::new(),
     };
 
+    // untagged enums can only be composed of other query enums
+    if input.attrs.iter().any(is_serde_untagged) {
+        ctx.is_nested = true;
+    }
+
     for param in params {
         match param.path().get_ident().unwrap().to_string().as_str() {
             "no_bounds_for" => {
@@ -61,3 +67,15 @@ pub fn get_context(input: &ItemEnum) -> Context {
 
     ctx
 }
+
+fn is_serde_untagged(attr: &Attribute) -> bool {
+    if !attr.path.is_ident("serde") {
+        return false;
+    }
+    match attr.parse_meta() {
+        Ok(Meta::List(l)) => l.nested.iter().any(
+            |nested| matches!(nested, NestedMeta::Meta(Meta::Path(p)) if p.is_ident("untagged")),
+        ),
+        _ => false,
+    }
+}
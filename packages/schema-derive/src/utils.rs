@@ -0,0 +1,35 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Converts an optional string into the tokens of an `Option<String>` expression
+pub fn optional_string(value: Option<String>) -> TokenStream {
+    match value {
+        Some(value) => quote! { ::std::option::Option::Some(#value.to_string()) },
+        None => quote! { ::std::option::Option::None },
+    }
+}
+
+/// Converts a `CamelCase` identifier to `snake_case` the same way serde's
+/// `rename_all = "snake_case"` does
+pub fn to_snake_case(input: &str) -> String {
+    // this was stolen from serde for consistent behavior
+    let mut snake = String::new();
+    for (i, ch) in input.char_indices() {
+        if i > 0 && ch.is_uppercase() {
+            snake.push('_');
+        }
+        snake.push(ch.to_ascii_lowercase());
+    }
+    snake
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_snake_case_works() {
+        assert_eq!(to_snake_case("SnakeCase"), "snake_case");
+        assert_eq!(to_snake_case("Wasm123AndCo"), "wasm123_and_co");
+    }
+}
@@ -9,6 +9,22 @@ pub fn to_snake_case(name: &str) -> String {
     out
 }
 
+pub fn to_pascal_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut uppercase_next = true;
+    for ch in name.chars() {
+        if ch == '_' {
+            uppercase_next = true;
+        } else if uppercase_next {
+            out.push(ch.to_ascii_uppercase());
+            uppercase_next = false;
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -27,4 +43,13 @@ mod tests {
         assert_eq!(to_snake_case("FooBar"), "foo_bar");
         assert_eq!(to_snake_case("ABC"), "a_b_c");
     }
+
+    #[test]
+    fn to_pascal_case_works() {
+        assert_eq!(to_pascal_case(""), "");
+        assert_eq!(to_pascal_case("a"), "A");
+        assert_eq!(to_pascal_case("foo_bar"), "FooBar");
+        assert_eq!(to_pascal_case("FooBar"), "FooBar");
+        assert_eq!(to_pascal_case("foo__bar_"), "FooBar");
+    }
 }
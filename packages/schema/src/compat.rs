@@ -0,0 +1,577 @@
+//! Backwards compatibility checks between two versions of a contract's API
+//!
+//! Messages sent to the contract are compatible if every message accepted by the old
+//! version is accepted by the new version. Responses are compatible if every response
+//! produced by the new version can be understood by clients of the old version.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use schemars::schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec};
+use schemars::Map;
+use serde::Deserialize;
+use thiserror::Error;
+
+/// A change between two versions of an API that breaks existing users
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Incompatibility {
+    #[error("{msg} was removed")]
+    RemovedMessage { msg: String },
+    #[error("{path}: variant `{variant}` was removed")]
+    RemovedVariant { path: String, variant: String },
+    #[error("{path}: field `{field}` was removed")]
+    RemovedField { path: String, field: String },
+    #[error("{path}: field `{old}` was renamed to `{new}`")]
+    RenamedField {
+        path: String,
+        old: String,
+        new: String,
+    },
+    #[error("{path}: new field `{field}` is required")]
+    NewRequiredField { path: String, field: String },
+    #[error("{path}: type was narrowed from `{old}` to `{new}`")]
+    NarrowedType {
+        path: String,
+        old: String,
+        new: String,
+    },
+    #[error("{path}: type was widened from `{old}` to `{new}`")]
+    WidenedType {
+        path: String,
+        old: String,
+        new: String,
+    },
+}
+
+#[derive(Error, Debug)]
+pub enum DecodeError {
+    #[error("{0}")]
+    JsonError(#[from] serde_json::Error),
+}
+
+/// The parts of the JSON API (see [`JsonApi`](crate::idl::JsonApi)) that are compared
+#[derive(Deserialize)]
+struct ApiSchemas {
+    instantiate: Option<RootSchema>,
+    execute: Option<RootSchema>,
+    query: Option<RootSchema>,
+    migrate: Option<RootSchema>,
+    sudo: Option<RootSchema>,
+    responses: Option<BTreeMap<String, RootSchema>>,
+    #[serde(default)]
+    sudo_responses: Option<BTreeMap<String, RootSchema>>,
+    #[serde(default)]
+    migrate_responses: Option<BTreeMap<String, RootSchema>>,
+}
+
+/// Returns all incompatibilities of `new_api` with `old_api`.
+/// Both arguments are full JSON APIs as written by [`write_api`](crate::write_api).
+pub fn check_backward_compatible(
+    old_api: &str,
+    new_api: &str,
+) -> Result<Vec<Incompatibility>, DecodeError> {
+    let old: ApiSchemas = serde_json::from_str(old_api)?;
+    let new: ApiSchemas = serde_json::from_str(new_api)?;
+
+    let mut incompatibilities = Vec::new();
+
+    let messages = [
+        ("instantiate", &old.instantiate, &new.instantiate),
+        ("execute", &old.execute, &new.execute),
+        ("query", &old.query, &new.query),
+        ("migrate", &old.migrate, &new.migrate),
+        ("sudo", &old.sudo, &new.sudo),
+    ];
+    for (name, old, new) in messages {
+        match (old, new) {
+            (Some(old), Some(new)) => {
+                Comparison::new(Mode::Message, old, new).run(name, &mut incompatibilities)
+            }
+            (Some(_), None) => incompatibilities.push(Incompatibility::RemovedMessage {
+                msg: name.to_string(),
+            }),
+            (None, _) => {}
+        }
+    }
+
+    let responses = [
+        ("response_to", &old.responses, &new.responses),
+        ("sudo_response_to", &old.sudo_responses, &new.sudo_responses),
+        (
+            "migrate_response_to",
+            &old.migrate_responses,
+            &new.migrate_responses,
+        ),
+    ];
+    for (prefix, old, new) in responses {
+        let (Some(old), Some(new)) = (old, new) else {
+            // removed variants are reported for the message
+            continue;
+        };
+        for (name, old) in old {
+            if let Some(new) = new.get(name) {
+                Comparison::new(Mode::Response, old, new)
+                    .run(&format!("{prefix}_{name}"), &mut incompatibilities);
+            }
+        }
+    }
+
+    Ok(incompatibilities)
+}
+
+/// Panics with a list of all incompatibilities if `new_api` is not backwards compatible
+/// with `old_api`. This is meant to be used in tests, e.g. to compare the API of the
+/// deployed version of a contract with the current one before a migration.
+///
+/// Both arguments are full JSON APIs as written by [`write_api`](crate::write_api).
+pub fn assert_backward_compatible(old_api: &str, new_api: &str) {
+    let incompatibilities = check_backward_compatible(old_api, new_api)
+        .unwrap_or_else(|err| panic!("invalid API: {err}"));
+    if !incompatibilities.is_empty() {
+        let list: Vec<_> = incompatibilities
+            .iter()
+            .map(|incompatibility| format!("- {incompatibility}"))
+            .collect();
+        panic!("API is not backwards compatible:\n{}", list.join("\n"));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// The new schema must accept all values of the old schema
+    Message,
+    /// The old schema must accept all values of the new schema
+    Response,
+}
+
+struct Comparison<'a> {
+    mode: Mode,
+    old: &'a RootSchema,
+    new: &'a RootSchema,
+    /// Pairs of references that were already compared, to support recursive types
+    visited: BTreeSet<(String, String)>,
+}
+
+impl<'a> Comparison<'a> {
+    fn new(mode: Mode, old: &'a RootSchema, new: &'a RootSchema) -> Self {
+        Self {
+            mode,
+            old,
+            new,
+            visited: BTreeSet::new(),
+        }
+    }
+
+    fn run(mut self, path: &str, out: &mut Vec<Incompatibility>) {
+        self.compare(path, &self.old.schema, &self.new.schema, out);
+    }
+
+    fn compare(
+        &mut self,
+        path: &str,
+        old: &SchemaObject,
+        new: &SchemaObject,
+        out: &mut Vec<Incompatibility>,
+    ) {
+        if old.reference.is_some() || new.reference.is_some() {
+            if let (Some(old_ref), Some(new_ref)) = (&old.reference, &new.reference) {
+                if !self.visited.insert((old_ref.clone(), new_ref.clone())) {
+                    return;
+                }
+            }
+            let (Some(old), Some(new)) = (
+                resolve(&self.old.definitions, old),
+                resolve(&self.new.definitions, new),
+            ) else {
+                // external references cannot be compared
+                return;
+            };
+            return self.compare(path, &old, &new, out);
+        }
+
+        let (old_nullable, new_nullable) = (is_nullable(old), is_nullable(new));
+        if old_nullable || new_nullable {
+            let breaking = match self.mode {
+                Mode::Message => old_nullable && !new_nullable,
+                Mode::Response => new_nullable && !old_nullable,
+            };
+            if breaking {
+                out.push(self.type_change(
+                    path,
+                    type_name(old, old_nullable),
+                    type_name(new, new_nullable),
+                ));
+            }
+            return self.compare(path, &without_null(old), &without_null(new), out);
+        }
+
+        if let (Some(old_variants), Some(new_variants)) = (variants(old), variants(new)) {
+            for (variant, old_schema) in &old_variants {
+                match new_variants.get(variant) {
+                    Some(new_schema) => {
+                        if let (Some(old_schema), Some(new_schema)) = (old_schema, new_schema) {
+                            self.compare(&format!("{path}.{variant}"), old_schema, new_schema, out);
+                        }
+                    }
+                    None if self.mode == Mode::Message => {
+                        out.push(Incompatibility::RemovedVariant {
+                            path: path.to_string(),
+                            variant: variant.clone(),
+                        })
+                    }
+                    None => {}
+                }
+            }
+            return;
+        }
+
+        let (old_types, new_types) = (instance_types(old), instance_types(new));
+        if !old_types.is_empty() && !new_types.is_empty() {
+            let accepted = match self.mode {
+                Mode::Message => is_subset(&old_types, &new_types),
+                Mode::Response => is_subset(&new_types, &old_types),
+            };
+            if !accepted {
+                out.push(self.type_change(path, type_name(old, false), type_name(new, false)));
+                return;
+            }
+        }
+
+        self.compare_objects(path, old, new, out);
+
+        let old_items = old.array.as_ref().and_then(|array| array.items.as_ref());
+        let new_items = new.array.as_ref().and_then(|array| array.items.as_ref());
+        if let (Some(SingleOrVec::Single(old_items)), Some(SingleOrVec::Single(new_items))) =
+            (old_items, new_items)
+        {
+            self.compare(
+                &format!("{path}[]"),
+                &old_items.clone().into_object(),
+                &new_items.clone().into_object(),
+                out,
+            );
+        }
+    }
+
+    fn compare_objects(
+        &mut self,
+        path: &str,
+        old: &SchemaObject,
+        new: &SchemaObject,
+        out: &mut Vec<Incompatibility>,
+    ) {
+        let (Some(old_object), Some(new_object)) = (&old.object, &new.object) else {
+            return;
+        };
+
+        let (removed, added): (Vec<_>, Vec<_>) = match self.mode {
+            Mode::Message => {
+                // fields that are no longer accepted
+                let denies_unknown = matches!(
+                    new_object.additional_properties.as_deref(),
+                    Some(Schema::Bool(false))
+                );
+                let removed = old_object
+                    .properties
+                    .keys()
+                    .filter(|field| denies_unknown && !new_object.properties.contains_key(*field))
+                    .collect();
+                // fields old messages do not contain
+                let added = new_object
+                    .required
+                    .iter()
+                    .filter(|field| !old_object.required.contains(*field))
+                    .collect();
+                (removed, added)
+            }
+            Mode::Response => {
+                // fields old clients rely on
+                let removed = old_object
+                    .required
+                    .iter()
+                    .filter(|field| !new_object.required.contains(*field))
+                    .collect();
+                let added = new_object
+                    .properties
+                    .keys()
+                    .filter(|field| !old_object.properties.contains_key(*field))
+                    .collect();
+                (removed, added)
+            }
+        };
+
+        match (removed.as_slice(), added.as_slice()) {
+            ([old_field], [new_field])
+                if !old_object.properties.contains_key(*new_field)
+                    && !new_object.properties.contains_key(*old_field) =>
+            {
+                out.push(Incompatibility::RenamedField {
+                    path: path.to_string(),
+                    old: old_field.to_string(),
+                    new: new_field.to_string(),
+                });
+            }
+            _ => {
+                out.extend(removed.iter().map(|field| Incompatibility::RemovedField {
+                    path: path.to_string(),
+                    field: field.to_string(),
+                }));
+                if self.mode == Mode::Message {
+                    out.extend(added.iter().map(|field| Incompatibility::NewRequiredField {
+                        path: path.to_string(),
+                        field: field.to_string(),
+                    }));
+                }
+            }
+        }
+
+        for (field, old_schema) in &old_object.properties {
+            if let Some(new_schema) = new_object.properties.get(field) {
+                self.compare(
+                    &format!("{path}.{field}"),
+                    &old_schema.clone().into_object(),
+                    &new_schema.clone().into_object(),
+                    out,
+                );
+            }
+        }
+    }
+
+    fn type_change(&self, path: &str, old: String, new: String) -> Incompatibility {
+        let path = path.to_string();
+        match self.mode {
+            Mode::Message => Incompatibility::NarrowedType { path, old, new },
+            Mode::Response => Incompatibility::WidenedType { path, old, new },
+        }
+    }
+}
+
+/// Follows references until a schema without reference is found
+fn resolve(definitions: &Map<String, Schema>, schema: &SchemaObject) -> Option<SchemaObject> {
+    let mut schema = schema.clone();
+    // limit the depth to avoid looping forever on definitions that only reference each other
+    for _ in 0..16 {
+        let Some(reference) = &schema.reference else {
+            return Some(schema);
+        };
+        let name = reference.strip_prefix("#/definitions/")?;
+        schema = definitions.get(name)?.clone().into_object();
+    }
+    None
+}
+
+fn is_null(schema: &Schema) -> bool {
+    matches!(
+        schema,
+        Schema::Object(SchemaObject {
+            instance_type: Some(SingleOrVec::Single(instance_type)),
+            ..
+        }) if **instance_type == InstanceType::Null
+    )
+}
+
+fn is_nullable(schema: &SchemaObject) -> bool {
+    let nullable_instance = match &schema.instance_type {
+        Some(SingleOrVec::Vec(types)) => types.contains(&InstanceType::Null),
+        _ => false,
+    };
+    let nullable_union = schema
+        .subschemas
+        .as_ref()
+        .and_then(|subschemas| subschemas.any_of.as_ref())
+        .is_some_and(|schemas| schemas.iter().any(is_null));
+    nullable_instance || nullable_union
+}
+
+/// Removes `null` from the accepted types, e.g. to compare `Option<T>` with `T`
+fn without_null(schema: &SchemaObject) -> SchemaObject {
+    let mut schema = schema.clone();
+    if let Some(SingleOrVec::Vec(types)) = &schema.instance_type {
+        let types: Vec<_> = types
+            .iter()
+            .filter(|instance_type| **instance_type != InstanceType::Null)
+            .cloned()
+            .collect();
+        schema.instance_type = match types.as_slice() {
+            [single] => Some(SingleOrVec::Single(Box::new(*single))),
+            _ => Some(SingleOrVec::Vec(types)),
+        };
+    }
+    if let Some(any_of) = schema
+        .subschemas
+        .as_ref()
+        .and_then(|subschemas| subschemas.any_of.as_ref())
+    {
+        let rest: Vec<_> = any_of.iter().filter(|s| !is_null(s)).cloned().collect();
+        if let [single] = rest.as_slice() {
+            let metadata = schema.metadata.take();
+            schema = single.clone().into_object();
+            schema.metadata = metadata;
+        } else if let Some(subschemas) = &mut schema.subschemas {
+            subschemas.any_of = Some(rest);
+        }
+    }
+    schema
+}
+
+/// Returns the variants of an enum schema, mapping the name of each variant to
+/// the schema of its content (`None` for unit variants)
+fn variants(schema: &SchemaObject) -> Option<BTreeMap<String, Option<SchemaObject>>> {
+    let mut variants = BTreeMap::new();
+    if let Some(values) = &schema.enum_values {
+        for value in values {
+            variants.insert(value.as_str()?.to_string(), None);
+        }
+        return Some(variants);
+    }
+    let one_of = schema.subschemas.as_ref()?.one_of.as_ref()?;
+    for variant in one_of {
+        let variant = variant.clone().into_object();
+        if let Some(values) = &variant.enum_values {
+            for value in values {
+                variants.insert(value.as_str()?.to_string(), None);
+            }
+        } else {
+            let properties = &variant.object.as_ref()?.properties;
+            if properties.len() != 1 {
+                return None;
+            }
+            let (name, content) = properties.iter().next()?;
+            variants.insert(name.clone(), Some(content.clone().into_object()));
+        }
+    }
+    Some(variants)
+}
+
+fn instance_types(schema: &SchemaObject) -> BTreeSet<InstanceType> {
+    match &schema.instance_type {
+        Some(SingleOrVec::Single(instance_type)) => BTreeSet::from([**instance_type]),
+        Some(SingleOrVec::Vec(types)) => types.iter().copied().collect(),
+        None => BTreeSet::new(),
+    }
+}
+
+/// Returns true if all values of the `narrow` types are accepted by the `wide` types
+fn is_subset(narrow: &BTreeSet<InstanceType>, wide: &BTreeSet<InstanceType>) -> bool {
+    narrow.iter().all(|instance_type| {
+        wide.contains(instance_type)
+            || (*instance_type == InstanceType::Integer && wide.contains(&InstanceType::Number))
+    })
+}
+
+fn type_name(schema: &SchemaObject, nullable: bool) -> String {
+    let mut names: Vec<_> = instance_types(schema)
+        .into_iter()
+        .filter(|instance_type| *instance_type != InstanceType::Null)
+        .map(|instance_type| {
+            serde_json::to_value(instance_type)
+                .ok()
+                .and_then(|value| value.as_str().map(str::to_string))
+                .unwrap_or_default()
+        })
+        .collect();
+    if names.is_empty() {
+        names.push("object".to_string());
+    }
+    if nullable {
+        names.push("null".to_string());
+    }
+    names.join(" | ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{schema_for, Api};
+
+    fn api_json(execute: RootSchema) -> String {
+        Api {
+            contract_name: "test".to_string(),
+            contract_version: "1.0.0".to_string(),
+            instantiate: None,
+            execute: Some(execute),
+            query: None,
+            migrate: None,
+            sudo: None,
+            responses: None,
+            sudo_responses: None,
+            migrate_responses: None,
+            events: None,
+            errors: None,
+        }
+        .render()
+        .to_string()
+        .unwrap()
+    }
+
+    #[test]
+    fn identical_apis_are_compatible() {
+        #[derive(schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct Msg {
+            a: String,
+            b: Option<u64>,
+        }
+
+        let api = api_json(schema_for!(Msg));
+        assert_eq!(check_backward_compatible(&api, &api).unwrap(), []);
+        assert_backward_compatible(&api, &api);
+    }
+
+    #[test]
+    fn narrowed_type_is_detected() {
+        #[derive(schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct Old {
+            a: Option<String>,
+            b: f64,
+        }
+
+        #[derive(schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct New {
+            a: String,
+            b: u64,
+        }
+
+        let old = api_json(schema_for!(Old));
+        let new = api_json(schema_for!(New));
+        assert_eq!(
+            check_backward_compatible(&old, &new).unwrap(),
+            [
+                Incompatibility::NewRequiredField {
+                    path: "execute".to_string(),
+                    field: "a".to_string(),
+                },
+                Incompatibility::NarrowedType {
+                    path: "execute.a".to_string(),
+                    old: "string | null".to_string(),
+                    new: "string".to_string(),
+                },
+                Incompatibility::NarrowedType {
+                    path: "execute.b".to_string(),
+                    old: "number".to_string(),
+                    new: "integer".to_string(),
+                },
+            ]
+        );
+
+        // widening is fine
+        assert_eq!(check_backward_compatible(&new, &old).unwrap(), []);
+    }
+
+    #[test]
+    fn invalid_json_fails() {
+        let api = api_json(schema_for!(String));
+        check_backward_compatible("{", &api).unwrap_err();
+    }
+
+    #[test]
+    #[should_panic(expected = "API is not backwards compatible:\n- execute was removed")]
+    fn assert_backward_compatible_panics() {
+        let old = api_json(schema_for!(String));
+        let mut new: serde_json::Value = serde_json::from_str(&old).unwrap();
+        new["execute"] = serde_json::Value::Null;
+        assert_backward_compatible(&old, &new.to_string());
+    }
+}
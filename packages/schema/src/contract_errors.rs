@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+pub use cosmwasm_schema_derive::ContractErrors;
+
+/// Description of an error returned by a contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ErrorDescription {
+    /// The name of the error variant
+    pub name: String,
+    /// The format string of the error message, e.g. `"Unauthorized: {sender}"`
+    pub message: Option<String>,
+    pub description: Option<String>,
+}
+
+/// A trait for describing the errors returned by a contract.
+/// This is mostly useful for the generated contract API description when using `cargo schema`.
+///
+/// Using the derive macro is the preferred way of implementing this trait. It works
+/// together with `thiserror` and takes the messages from the `#[error("...")]` attributes.
+///
+/// # Examples
+/// ```
+/// use cosmwasm_schema::{ContractErrors, ErrorDescription};
+///
+/// #[derive(Debug, ContractErrors)]
+/// enum ContractError {
+///     /// The sender is not the admin of the contract
+///     #[error("Unauthorized: {sender}")]
+///     Unauthorized { sender: String },
+/// }
+///
+/// assert_eq!(
+///     ContractError::errors(),
+///     [ErrorDescription {
+///         name: "Unauthorized".to_string(),
+///         message: Some("Unauthorized: {sender}".to_string()),
+///         description: Some("The sender is not the admin of the contract".to_string()),
+///     }]
+/// );
+/// ```
+pub trait ContractErrors {
+    fn errors() -> Vec<ErrorDescription>;
+}
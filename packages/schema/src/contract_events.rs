@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+pub use cosmwasm_schema_derive::ContractEvents;
+
+/// Description of an event emitted by a contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EventDescription {
+    /// The type of the event as passed to `Event::new`
+    pub name: String,
+    pub description: Option<String>,
+    /// The keys of the attributes of the event
+    pub attributes: Vec<String>,
+}
+
+/// A trait for describing the events emitted by a contract.
+/// This is mostly useful for the generated contract API description when using `cargo schema`,
+/// such that indexers and explorers know which events and attributes to expect.
+///
+/// Using the derive macro is the preferred way of implementing this trait. Each variant
+/// describes one event, with the field names as attribute keys. The event name is the
+/// variant name in snake_case, unless set with `#[event(name = "...")]`.
+///
+/// # Examples
+/// ```
+/// use cosmwasm_schema::{ContractEvents, EventDescription};
+///
+/// #[derive(ContractEvents)]
+/// enum Event {
+///     /// Tokens were sent to the recipient
+///     Transfer {
+///         recipient: String,
+///         amount: u128,
+///     },
+///     #[event(name = "paused")]
+///     Pause,
+/// }
+///
+/// assert_eq!(
+///     Event::events(),
+///     [
+///         EventDescription {
+///             name: "transfer".to_string(),
+///             description: Some("Tokens were sent to the recipient".to_string()),
+///             attributes: vec!["recipient".to_string(), "amount".to_string()],
+///         },
+///         EventDescription {
+///             name: "paused".to_string(),
+///             description: None,
+///             attributes: vec![],
+///         },
+///     ]
+/// );
+/// ```
+pub trait ContractEvents {
+    fn events() -> Vec<EventDescription>;
+}
@@ -5,11 +5,16 @@ use std::collections::BTreeMap;
 use schemars::schema::RootSchema;
 use thiserror::Error;
 
+use crate::casing::to_pascal_case;
+use crate::contract_errors::ErrorDescription;
+use crate::contract_events::EventDescription;
+use crate::typescript::TypeScriptWriter;
+
 /// The version of the CosmWasm IDL.
 ///
 /// Follows Semantic Versioning 2.0.0: <https://semver.org/>
 // To determine if a change is breaking, assume consumers allow unknown fields and bump accordingly.
-pub const IDL_VERSION: &str = "1.0.0";
+pub const IDL_VERSION: &str = "2.0.0";
 
 /// Rust representation of a contract's API.
 pub struct Api {
@@ -22,6 +27,14 @@ pub struct Api {
     pub sudo: Option<RootSchema>,
     /// A mapping of query variants to response types
     pub responses: Option<BTreeMap<String, RootSchema>>,
+    /// A mapping of sudo variants to the types of their response data
+    pub sudo_responses: Option<BTreeMap<String, RootSchema>>,
+    /// A mapping of migrate variants to the types of their response data
+    pub migrate_responses: Option<BTreeMap<String, RootSchema>>,
+    /// The events emitted by the contract
+    pub events: Option<Vec<EventDescription>>,
+    /// The errors returned by the contract
+    pub errors: Option<Vec<ErrorDescription>>,
 }
 
 impl Api {
@@ -36,6 +49,10 @@ impl Api {
             migrate: self.migrate,
             sudo: self.sudo,
             responses: self.responses,
+            sudo_responses: self.sudo_responses,
+            migrate_responses: self.migrate_responses,
+            events: self.events,
+            errors: self.errors,
         };
 
         if let Some(instantiate) = &mut json_api.instantiate {
@@ -80,6 +97,14 @@ pub struct JsonApi {
     migrate: Option<RootSchema>,
     sudo: Option<RootSchema>,
     responses: Option<BTreeMap<String, RootSchema>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sudo_responses: Option<BTreeMap<String, RootSchema>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    migrate_responses: Option<BTreeMap<String, RootSchema>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    events: Option<Vec<EventDescription>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    errors: Option<Vec<ErrorDescription>>,
 }
 
 impl JsonApi {
@@ -129,10 +154,81 @@ impl JsonApi {
                 ));
             }
         }
+        if let Some(responses) = &self.sudo_responses {
+            for (name, response) in responses {
+                result.push((
+                    format!("sudo_response_to_{name}.json"),
+                    serde_json::to_string_pretty(&response)?,
+                ));
+            }
+        }
+        if let Some(responses) = &self.migrate_responses {
+            for (name, response) in responses {
+                result.push((
+                    format!("migrate_response_to_{name}.json"),
+                    serde_json::to_string_pretty(&response)?,
+                ));
+            }
+        }
+
+        if let Some(events) = &self.events {
+            result.push((
+                "events.json".to_string(),
+                serde_json::to_string_pretty(&events)?,
+            ));
+        }
+        if let Some(errors) = &self.errors {
+            result.push((
+                "errors.json".to_string(),
+                serde_json::to_string_pretty(&errors)?,
+            ));
+        }
 
         Ok(result)
     }
 
+    /// Renders TypeScript definitions for all messages and query responses of the API.
+    ///
+    /// Query responses are named after the query variant, e.g. `BalanceResponse`
+    /// for the response to the `balance` query. Sudo and migrate responses are named
+    /// `<Variant>SudoResponse` and `<Variant>MigrateResponse` respectively.
+    pub fn to_typescript(&self) -> String {
+        let mut writer = TypeScriptWriter::new();
+
+        let messages = [
+            ("InstantiateMsg", &self.instantiate),
+            ("ExecuteMsg", &self.execute),
+            ("QueryMsg", &self.query),
+            ("MigrateMsg", &self.migrate),
+            ("SudoMsg", &self.sudo),
+        ];
+        for (name, schema) in messages {
+            if let Some(schema) = schema {
+                writer.add_root_schema(name, schema);
+            }
+        }
+        if let Some(responses) = &self.responses {
+            for (name, response) in responses {
+                writer.add_root_schema(&format!("{}Response", to_pascal_case(name)), response);
+            }
+        }
+        if let Some(responses) = &self.sudo_responses {
+            for (name, response) in responses {
+                writer.add_root_schema(&format!("{}SudoResponse", to_pascal_case(name)), response);
+            }
+        }
+        if let Some(responses) = &self.migrate_responses {
+            for (name, response) in responses {
+                writer.add_root_schema(
+                    &format!("{}MigrateResponse", to_pascal_case(name)),
+                    response,
+                );
+            }
+        }
+
+        writer.finish()
+    }
+
     pub fn to_writer(&self, writer: impl std::io::Write) -> Result<(), EncodeError> {
         serde_json::to_writer_pretty(writer, self).map_err(Into::into)
     }
@@ -166,6 +262,10 @@ mod tests {
             migrate: None,
             sudo: None,
             responses: None,
+            sudo_responses: None,
+            migrate_responses: None,
+            events: None,
+            errors: None,
         };
 
         let files = empty.render().to_schema_files().unwrap();
@@ -186,15 +286,63 @@ mod tests {
                 "TestMsg".to_string(),
                 schema_for!(TestMsg),
             )])),
+            sudo_responses: Some(BTreeMap::from([(
+                "TestMsg".to_string(),
+                schema_for!(TestMsg),
+            )])),
+            migrate_responses: Some(BTreeMap::from([(
+                "TestMsg".to_string(),
+                schema_for!(TestMsg),
+            )])),
+            events: Some(vec![]),
+            errors: Some(vec![]),
         };
 
         let files = full.render().to_schema_files().unwrap();
-        assert_eq!(files.len(), 6);
+        assert_eq!(files.len(), 10);
         assert_eq!(files[0].0, "instantiate.json");
         assert_eq!(files[1].0, "execute.json");
         assert_eq!(files[2].0, "query.json");
         assert_eq!(files[3].0, "migrate.json");
         assert_eq!(files[4].0, "sudo.json");
         assert_eq!(files[5].0, "response_to_TestMsg.json");
+        assert_eq!(files[6].0, "sudo_response_to_TestMsg.json");
+        assert_eq!(files[7].0, "migrate_response_to_TestMsg.json");
+        assert_eq!(files[8].0, "events.json");
+        assert_eq!(files[9].0, "errors.json");
+    }
+
+    #[test]
+    fn to_typescript_works() {
+        #[derive(schemars::JsonSchema)]
+        struct TestMsg {}
+
+        let api = Api {
+            contract_name: "my_contract".to_string(),
+            contract_version: "1.2.3".to_string(),
+            instantiate: Some(schema_for!(TestMsg)),
+            execute: None,
+            query: Some(schema_for!(TestMsg)),
+            migrate: None,
+            sudo: None,
+            responses: Some(BTreeMap::from([(
+                "get_count".to_string(),
+                schema_for!(u64),
+            )])),
+            sudo_responses: Some(BTreeMap::from([(
+                "set_count".to_string(),
+                schema_for!(u64),
+            )])),
+            migrate_responses: None,
+            events: None,
+            errors: None,
+        };
+
+        let ts = api.render().to_typescript();
+        assert!(ts.contains("export type InstantiateMsg = "));
+        assert!(ts.contains("export type QueryMsg = "));
+        assert!(ts.contains("export type GetCountResponse = number;"));
+        assert!(ts.contains("export type SetCountSudoResponse = number;"));
+        assert!(!ts.contains("ExecuteMsg"));
     }
 }
@@ -1,10 +1,16 @@
 mod casing;
+pub mod compat;
+mod contract_errors;
+mod contract_events;
 mod export;
 mod idl;
 mod query_response;
 mod remove;
 mod schema_for;
+mod typescript;
 
+pub use contract_errors::{ContractErrors, ErrorDescription};
+pub use contract_events::{ContractEvents, EventDescription};
 pub use export::{export_schema, export_schema_with_title};
 pub use idl::{Api, IDL_VERSION};
 pub use query_response::{combine_subqueries, IntegrityError, QueryResponses};
@@ -35,6 +41,23 @@ pub use remove::remove_schemas;
 ///     AccountName { account: String },
 /// }
 /// ```
+///
+/// # Options
+/// - `crate = "..."` - path of the `cosmwasm_schema` crate, `::cosmwasm_schema` by default
+/// - `allow_unknown_fields` - do not reject unknown fields during deserialization
+/// - `untagged` - serialize enum variants without a tag (enums only)
+/// - `rename_all = "..."` - overrides the case of variants and fields, which is `snake_case`
+///   for enum variants by default
+///
+/// ```
+/// use cosmwasm_schema::cw_serde;
+///
+/// #[cw_serde(allow_unknown_fields, rename_all = "camelCase")]
+/// pub enum CustomMsg {
+///     SetMetadata { denom: String, name: String },
+///     BurnFrom { denom: String, amount: u64 },
+/// }
+/// ```
 pub use cosmwasm_schema_derive::cw_serde;
 /// Generates an [`Api`](crate::Api) for the contract. The body describes the message
 /// types exported in the schema and allows setting contract name and version overrides.
@@ -74,6 +97,18 @@ pub use cosmwasm_schema_derive::generate_api;
 /// - `execute` - execute msg type, empty by default
 /// - `migrate` - migrate msg type, empty by default
 /// - `sudo` - sudo msg type, empty by default
+/// - `migrate_responses` - if `true`, the migrate msg type must implement
+///   [`QueryResponses`](crate::QueryResponses) and the response data types are included,
+///   `false` by default
+/// - `sudo_responses` - if `true`, the sudo msg type must implement
+///   [`QueryResponses`](crate::QueryResponses) and the response data types are included,
+///   `false` by default
+/// - `events` - a type implementing [`ContractEvents`](crate::ContractEvents) describing
+///   the emitted events, none by default
+/// - `errors` - a type implementing [`ContractErrors`](crate::ContractErrors) describing
+///   the returned errors, none by default
+/// - `typescript` - if `true`, TypeScript definitions for all messages and query responses
+///   are written to `schema/<name>.d.ts` (`write_api` only), `false` by default
 ///
 /// # Example
 /// ```
@@ -1,6 +1,9 @@
 use std::collections::{BTreeMap, BTreeSet};
 
-use schemars::{schema::RootSchema, JsonSchema};
+use schemars::{
+    schema::{RootSchema, Schema, SchemaObject},
+    JsonSchema, Map,
+};
 use thiserror::Error;
 
 pub use cosmwasm_schema_derive::QueryResponses;
@@ -62,10 +65,49 @@ pub use cosmwasm_schema_derive::QueryResponses;
 /// #     IcqHandle: String,
 /// # }
 /// ```
+///
+/// Enums annotated with `#[serde(untagged)]` are always treated as nested. Besides newtype
+/// variants, a struct variant whose only field is marked `#[serde(flatten)]` can be used
+/// to embed another query enum, e.g. a shared base query of a contract standard.
+///
+/// ```
+/// # use cosmwasm_schema::QueryResponses;
+/// # use schemars::JsonSchema;
+/// #[derive(JsonSchema, QueryResponses)]
+/// #[serde(untagged)]
+/// enum QueryMsg {
+///     Base {
+///         #[serde(flatten)]
+///         base: BaseQuery,
+///     },
+///     Extension(ExtensionQuery),
+/// }
+///
+/// #[derive(JsonSchema, QueryResponses)]
+/// enum BaseQuery {
+///     #[returns(String)]
+///     Owner {},
+/// }
+///
+/// #[derive(JsonSchema, QueryResponses)]
+/// enum ExtensionQuery {
+///     #[returns(u64)]
+///     Royalties {},
+/// }
+/// ```
+///
+/// The derive macro can also be used for `SudoMsg` and `MigrateMsg` enums to describe the
+/// type of the data returned in `Response::data` by each variant.
+/// See `sudo_responses` and `migrate_responses` in [`write_api`](crate::write_api).
 pub trait QueryResponses: JsonSchema {
+    /// Returns the response schemas and ensures that every variant of the message
+    /// has exactly one response type.
     fn response_schemas() -> Result<BTreeMap<String, RootSchema>, IntegrityError> {
         let response_schemas = Self::response_schemas_impl();
 
+        let queries: BTreeSet<_> = response_schemas.keys().cloned().collect();
+        check_api_integrity::<Self>(queries)?;
+
         Ok(response_schemas)
     }
 
@@ -88,6 +130,76 @@ pub fn combine_subqueries<const N: usize, T>(
     map
 }
 
+/// Compares the variants found in the schema of `T` with the variants that have a response
+fn check_api_integrity<T: QueryResponses + ?Sized>(
+    generated_queries: BTreeSet<String>,
+) -> Result<(), IntegrityError> {
+    let schema = crate::schema_for!(T);
+
+    let mut schema_queries = BTreeSet::new();
+    collect_variants(&schema.schema, &schema.definitions, &mut schema_queries)?;
+
+    if schema_queries != generated_queries {
+        return Err(IntegrityError::InconsistentQueries {
+            query_msg: schema_queries,
+            responses: generated_queries,
+        });
+    }
+
+    Ok(())
+}
+
+/// Collects the names of all variants of an enum schema. References are resolved, such that
+/// untagged enums composed of other enums (see `#[query_responses(nested)]`) are supported.
+fn collect_variants(
+    schema: &SchemaObject,
+    definitions: &Map<String, Schema>,
+    variants: &mut BTreeSet<String>,
+) -> Result<(), IntegrityError> {
+    if let Some(reference) = &schema.reference {
+        let definition = reference
+            .strip_prefix("#/definitions/")
+            .and_then(|name| definitions.get(name))
+            .ok_or_else(|| IntegrityError::ExternalReference {
+                reference: reference.clone(),
+            })?;
+        return collect_variants(&definition.clone().into_object(), definitions, variants);
+    }
+
+    // unit variants
+    if let Some(values) = &schema.enum_values {
+        for value in values {
+            let variant = value
+                .as_str()
+                .ok_or(IntegrityError::InvalidQueryMsgSchema)?;
+            variants.insert(variant.to_string());
+        }
+        return Ok(());
+    }
+
+    if let Some(subschemas) = &schema.subschemas {
+        if let Some(schemas) = subschemas.one_of.as_ref().or(subschemas.any_of.as_ref()) {
+            for schema in schemas {
+                collect_variants(&schema.clone().into_object(), definitions, variants)?;
+            }
+            return Ok(());
+        }
+        // documented newtype variants of untagged enums wrap the reference
+        if let Some([schema]) = subschemas.all_of.as_deref() {
+            return collect_variants(&schema.clone().into_object(), definitions, variants);
+        }
+    }
+
+    // struct and tuple variants are objects with a single property
+    match schema.object.as_ref().map(|object| &object.properties) {
+        Some(properties) if properties.len() == 1 => {
+            variants.extend(properties.keys().cloned());
+            Ok(())
+        }
+        _ => Err(IntegrityError::InvalidQueryMsgSchema),
+    }
+}
+
 #[derive(Debug, Error, PartialEq, Eq)]
 pub enum IntegrityError {
     #[error("the structure of the QueryMsg schema was unexpected")]
@@ -177,6 +289,67 @@ mod tests {
         }
     }
 
+    #[test]
+    fn bad_msg_fails() {
+        let err = BadMsg::response_schemas().unwrap_err();
+        assert_eq!(
+            err,
+            IntegrityError::InconsistentQueries {
+                query_msg: BTreeSet::from(["balance-for".to_string()]),
+                responses: BTreeSet::from(["balance_for".to_string()]),
+            }
+        );
+    }
+
+    #[derive(Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    #[allow(dead_code)]
+    pub enum MissingMsg {
+        BalanceFor { account: String },
+        Supply {},
+    }
+
+    impl QueryResponses for MissingMsg {
+        fn response_schemas_impl() -> BTreeMap<String, RootSchema> {
+            BTreeMap::from([("balance_for".to_string(), schema_for!(u128))])
+        }
+    }
+
+    #[test]
+    fn missing_response_fails() {
+        let err = MissingMsg::response_schemas().unwrap_err();
+        assert_eq!(
+            err,
+            IntegrityError::InconsistentQueries {
+                query_msg: BTreeSet::from(["balance_for".to_string(), "supply".to_string()]),
+                responses: BTreeSet::from(["balance_for".to_string()]),
+            }
+        );
+    }
+
+    #[derive(Debug, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    #[allow(dead_code)]
+    pub enum GenericMsg<T> {
+        Get { key: T },
+        Unit,
+    }
+
+    impl<T: JsonSchema> QueryResponses for GenericMsg<T> {
+        fn response_schemas_impl() -> BTreeMap<String, RootSchema> {
+            BTreeMap::from([
+                ("get".to_string(), schema_for!(u128)),
+                ("unit".to_string(), schema_for!(u128)),
+            ])
+        }
+    }
+
+    #[test]
+    fn generic_msg_works() {
+        let response_schemas = GenericMsg::<String>::response_schemas().unwrap();
+        assert_eq!(response_schemas.len(), 2);
+    }
+
     #[derive(Debug, JsonSchema)]
     #[serde(rename_all = "snake_case")]
     #[allow(dead_code)]
@@ -18,13 +18,20 @@ fn is_json(path: &path::Path) -> bool {
     }
 }
 
+fn is_typescript_definition(path: &path::Path) -> bool {
+    match path.file_name() {
+        Some(name) => name.to_os_string().to_string_lossy().ends_with(".d.ts"),
+        None => false, // a path without filename is no *.d.ts
+    }
+}
+
 pub fn remove_schemas(schemas_dir: &path::Path) -> Result<(), io::Error> {
     let file_paths = fs::read_dir(schemas_dir)?
         .filter_map(Result::ok) // skip read errors on entries
         .map(|entry| entry.path())
         .filter(|path| is_regular_file(path).unwrap_or(false)) // skip directories and symlinks
         .filter(|path| !is_hidden(path)) // skip hidden
-        .filter(|path| is_json(path)) // skip non JSON
+        .filter(|path| is_json(path) || is_typescript_definition(path)) // skip other files
         ;
 
     for file_path in file_paths {
@@ -96,4 +103,18 @@ mod tests {
             assert!(is_json(Path::new(hidden)));
         }
     }
+
+    #[test]
+    fn is_typescript_definition_works() {
+        assert!(!is_typescript_definition(Path::new("/foo")));
+        assert!(!is_typescript_definition(Path::new("/foo/bar.ts")));
+        assert!(!is_typescript_definition(Path::new("foo.json")));
+
+        assert!(is_typescript_definition(Path::new("/foo/bar.d.ts")));
+        assert!(is_typescript_definition(Path::new("foo.d.ts")));
+
+        // no filename
+        assert!(!is_typescript_definition(Path::new("/")));
+        assert!(!is_typescript_definition(Path::new("")));
+    }
 }
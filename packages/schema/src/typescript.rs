@@ -0,0 +1,340 @@
+//! Generation of TypeScript definitions from JSON Schema
+
+use std::collections::BTreeSet;
+
+use schemars::schema::{
+    InstanceType, ObjectValidation, RootSchema, Schema, SchemaObject, SingleOrVec,
+};
+
+const INDENT: &str = "  ";
+
+/// Collects TypeScript type declarations. Every declared name is emitted only once,
+/// such that definitions shared between multiple schemas do not collide.
+#[derive(Default)]
+pub struct TypeScriptWriter {
+    declared: BTreeSet<String>,
+    out: String,
+}
+
+impl TypeScriptWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares `name` as the root type of the schema, followed by all definitions
+    /// of the schema that have not been declared yet.
+    pub fn add_root_schema(&mut self, name: &str, schema: &RootSchema) {
+        self.declare(name, &schema.schema);
+        for (definition_name, definition) in &schema.definitions {
+            self.declare(definition_name, &definition.clone().into_object());
+        }
+    }
+
+    pub fn finish(self) -> String {
+        let mut out = String::from(
+            "// This file was automatically generated by cosmwasm-schema. Do not modify it by hand.\n",
+        );
+        out.push_str(&self.out);
+        out
+    }
+
+    fn declare(&mut self, name: &str, schema: &SchemaObject) {
+        if !self.declared.insert(name.to_string()) {
+            return;
+        }
+        self.out.push('\n');
+        if let Some(description) = schema
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.description.as_ref())
+        {
+            self.out.push_str(&doc_comment(description, 0));
+        }
+        self.out.push_str(&format!(
+            "export type {name} = {};\n",
+            object_type(schema, 0)
+        ));
+    }
+}
+
+fn schema_type(schema: &Schema, indent: usize) -> String {
+    match schema {
+        Schema::Bool(true) => "unknown".to_string(),
+        Schema::Bool(false) => "never".to_string(),
+        Schema::Object(schema) => object_type(schema, indent),
+    }
+}
+
+fn object_type(schema: &SchemaObject, indent: usize) -> String {
+    if let Some(reference) = &schema.reference {
+        return reference
+            .strip_prefix("#/definitions/")
+            .unwrap_or(reference)
+            .to_string();
+    }
+    if let Some(value) = &schema.const_value {
+        return value.to_string();
+    }
+    if let Some(values) = &schema.enum_values {
+        return union(values.iter().map(|value| value.to_string()));
+    }
+    if let Some(subschemas) = &schema.subschemas {
+        if let Some(schemas) = subschemas.one_of.as_ref().or(subschemas.any_of.as_ref()) {
+            return union(schemas.iter().map(|schema| schema_type(schema, indent)));
+        }
+        if let Some(schemas) = &subschemas.all_of {
+            let types: Vec<_> = schemas
+                .iter()
+                .map(|schema| schema_type(schema, indent))
+                .collect();
+            return types.join(" & ");
+        }
+    }
+
+    match &schema.instance_type {
+        Some(SingleOrVec::Single(instance_type)) => {
+            instance_type_name(schema, instance_type, indent)
+        }
+        Some(SingleOrVec::Vec(instance_types)) => union(
+            instance_types
+                .iter()
+                .map(|instance_type| instance_type_name(schema, instance_type, indent)),
+        ),
+        None => "unknown".to_string(),
+    }
+}
+
+fn instance_type_name(
+    schema: &SchemaObject,
+    instance_type: &InstanceType,
+    indent: usize,
+) -> String {
+    match instance_type {
+        InstanceType::Null => "null".to_string(),
+        InstanceType::Boolean => "boolean".to_string(),
+        InstanceType::Integer | InstanceType::Number => "number".to_string(),
+        InstanceType::String => "string".to_string(),
+        InstanceType::Array => array_type(schema, indent),
+        InstanceType::Object => match &schema.object {
+            Some(object) => object_validation_type(object, indent),
+            None => "{ [key: string]: unknown }".to_string(),
+        },
+    }
+}
+
+fn array_type(schema: &SchemaObject, indent: usize) -> String {
+    match schema.array.as_ref().and_then(|array| array.items.as_ref()) {
+        Some(SingleOrVec::Single(item)) => {
+            let item = schema_type(item, indent);
+            if item.contains(['|', '&']) {
+                format!("({item})[]")
+            } else {
+                format!("{item}[]")
+            }
+        }
+        Some(SingleOrVec::Vec(items)) => {
+            let items: Vec<_> = items.iter().map(|item| schema_type(item, indent)).collect();
+            format!("[{}]", items.join(", "))
+        }
+        None => "unknown[]".to_string(),
+    }
+}
+
+fn object_validation_type(object: &ObjectValidation, indent: usize) -> String {
+    let additional = match object.additional_properties.as_deref() {
+        Some(Schema::Bool(false)) => None,
+        Some(schema) => Some(schema_type(schema, indent + 1)),
+        None => Some("unknown".to_string()),
+    };
+    if object.properties.is_empty() {
+        return match additional {
+            Some(additional) => format!("{{ [key: string]: {additional} }}"),
+            None => "Record<string, never>".to_string(),
+        };
+    }
+
+    let inner = INDENT.repeat(indent + 1);
+    let mut out = String::from("{\n");
+    for (name, property) in &object.properties {
+        if let Some(description) = property
+            .clone()
+            .into_object()
+            .metadata
+            .and_then(|metadata| metadata.description)
+        {
+            out.push_str(&doc_comment(&description, indent + 1));
+        }
+        let optional = if object.required.contains(name) {
+            ""
+        } else {
+            "?"
+        };
+        out.push_str(&format!(
+            "{inner}{}{optional}: {};\n",
+            property_name(name),
+            schema_type(property, indent + 1)
+        ));
+    }
+    if let Some(additional) = additional {
+        out.push_str(&format!("{inner}[key: string]: {additional};\n"));
+    }
+    out.push_str(&INDENT.repeat(indent));
+    out.push('}');
+    out
+}
+
+fn union(types: impl Iterator<Item = String>) -> String {
+    let types: Vec<_> = types.collect();
+    if types.is_empty() {
+        return "never".to_string();
+    }
+    types.join(" | ")
+}
+
+/// Quotes property names that are not valid identifiers
+fn property_name(name: &str) -> String {
+    let is_identifier = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        name.to_string()
+    } else {
+        serde_json::Value::from(name).to_string()
+    }
+}
+
+fn doc_comment(description: &str, indent: usize) -> String {
+    let prefix = INDENT.repeat(indent);
+    let mut out = format!("{prefix}/**\n");
+    for line in description.lines() {
+        let line = line.replace("*/", "*\\/");
+        if line.is_empty() {
+            out.push_str(&format!("{prefix} *\n"));
+        } else {
+            out.push_str(&format!("{prefix} * {line}\n"));
+        }
+    }
+    out.push_str(&format!("{prefix} */\n"));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::schema_for;
+
+    fn render(name: &str, schema: &RootSchema) -> String {
+        let mut writer = TypeScriptWriter::new();
+        writer.add_root_schema(name, schema);
+        writer.finish()
+    }
+
+    #[test]
+    fn property_name_works() {
+        assert_eq!(property_name("foo"), "foo");
+        assert_eq!(property_name("foo_bar1"), "foo_bar1");
+        assert_eq!(property_name("foo-bar"), r#""foo-bar""#);
+        assert_eq!(property_name("1foo"), r#""1foo""#);
+    }
+
+    #[test]
+    fn doc_comment_works() {
+        assert_eq!(doc_comment("Foo", 0), "/**\n * Foo\n */\n");
+        assert_eq!(
+            doc_comment("Foo\n\nBar */", 1),
+            "  /**\n   * Foo\n   *\n   * Bar *\\/\n   */\n"
+        );
+    }
+
+    #[test]
+    fn struct_works() {
+        #[allow(dead_code)]
+        #[derive(schemars::JsonSchema)]
+        #[serde(deny_unknown_fields)]
+        struct TestMsg {
+            /// The owner
+            owner: String,
+            amounts: Vec<Option<u64>>,
+            memo: Option<String>,
+            pair: (bool, u8),
+        }
+
+        assert_eq!(
+            render("TestMsg", &schema_for!(TestMsg)),
+            r#"// This file was automatically generated by cosmwasm-schema. Do not modify it by hand.
+
+export type TestMsg = {
+  amounts: (number | null)[];
+  memo?: string | null;
+  /**
+   * The owner
+   */
+  owner: string;
+  pair: [boolean, number];
+};
+"#
+        );
+    }
+
+    #[test]
+    fn enum_works() {
+        #[allow(dead_code)]
+        #[derive(schemars::JsonSchema)]
+        #[serde(rename_all = "snake_case", deny_unknown_fields)]
+        enum TestMsg {
+            Reset,
+            Transfer { recipient: Recipient },
+            Release {},
+        }
+
+        #[allow(dead_code)]
+        #[derive(schemars::JsonSchema)]
+        struct Recipient(String);
+
+        assert_eq!(
+            render("TestMsg", &schema_for!(TestMsg)),
+            r#"// This file was automatically generated by cosmwasm-schema. Do not modify it by hand.
+
+export type TestMsg = "reset" | {
+  transfer: {
+    recipient: Recipient;
+  };
+} | {
+  release: Record<string, never>;
+};
+
+export type Recipient = string;
+"#
+        );
+    }
+
+    #[test]
+    fn shared_definitions_are_declared_once() {
+        #[allow(dead_code)]
+        #[derive(schemars::JsonSchema)]
+        struct Shared {}
+
+        #[allow(dead_code)]
+        #[derive(schemars::JsonSchema)]
+        struct A {
+            shared: Shared,
+        }
+
+        #[allow(dead_code)]
+        #[derive(schemars::JsonSchema)]
+        struct B {
+            shared: Shared,
+        }
+
+        let mut writer = TypeScriptWriter::new();
+        writer.add_root_schema("A", &schema_for!(A));
+        writer.add_root_schema("B", &schema_for!(B));
+        let ts = writer.finish();
+        assert_eq!(ts.matches("export type Shared =").count(), 1);
+        assert!(ts.contains("export type A ="));
+        assert!(ts.contains("export type B ="));
+    }
+}
@@ -0,0 +1,128 @@
+use cosmwasm_schema::compat::{check_backward_compatible, Incompatibility};
+use cosmwasm_schema::generate_api;
+
+mod v1 {
+    use cosmwasm_schema::{cw_serde, QueryResponses};
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub admin: String,
+    }
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        Mint { recipient: String, amount: u64 },
+        Burn { amount: u64 },
+        Pause {},
+    }
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum QueryMsg {
+        #[returns(ConfigResponse)]
+        Config {},
+    }
+
+    #[cw_serde]
+    pub struct ConfigResponse {
+        pub admin: String,
+        pub paused: bool,
+    }
+}
+
+mod v2 {
+    use cosmwasm_schema::{cw_serde, QueryResponses};
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub admin: String,
+        /// Added as optional field, which is compatible
+        pub label: Option<String>,
+    }
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        Mint {
+            receiver: String,
+            amount: u64,
+        },
+        Pause {},
+        /// Added variant, which is compatible
+        Unpause {},
+    }
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum QueryMsg {
+        #[returns(ConfigResponse)]
+        Config {},
+    }
+
+    #[cw_serde]
+    pub struct ConfigResponse {
+        pub admin: Option<String>,
+        pub paused: bool,
+    }
+}
+
+fn v1_api() -> String {
+    generate_api! {
+        instantiate: v1::InstantiateMsg,
+        execute: v1::ExecuteMsg,
+        query: v1::QueryMsg,
+    }
+    .render()
+    .to_string()
+    .unwrap()
+}
+
+fn v2_api() -> String {
+    generate_api! {
+        instantiate: v2::InstantiateMsg,
+        execute: v2::ExecuteMsg,
+        query: v2::QueryMsg,
+    }
+    .render()
+    .to_string()
+    .unwrap()
+}
+
+#[test]
+fn compatible_apis_pass() {
+    cosmwasm_schema::compat::assert_backward_compatible(&v1_api(), &v1_api());
+    cosmwasm_schema::compat::assert_backward_compatible(&v2_api(), &v2_api());
+}
+
+#[test]
+fn incompatibilities_are_detected() {
+    let incompatibilities = check_backward_compatible(&v1_api(), &v2_api()).unwrap();
+    assert_eq!(
+        incompatibilities,
+        [
+            Incompatibility::RemovedVariant {
+                path: "execute".to_string(),
+                variant: "burn".to_string(),
+            },
+            Incompatibility::RenamedField {
+                path: "execute.mint".to_string(),
+                old: "recipient".to_string(),
+                new: "receiver".to_string(),
+            },
+            Incompatibility::RemovedField {
+                path: "response_to_config".to_string(),
+                field: "admin".to_string(),
+            },
+            Incompatibility::WidenedType {
+                path: "response_to_config.admin".to_string(),
+                old: "string".to_string(),
+                new: "string | null".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "execute: variant `burn` was removed")]
+fn assert_backward_compatible_panics() {
+    cosmwasm_schema::compat::assert_backward_compatible(&v1_api(), &v2_api());
+}
@@ -73,6 +73,135 @@ fn test_basic_structure() {
     );
 }
 
+#[test]
+fn test_typescript() {
+    let ts = generate_api! {
+        name: "test",
+        version: "0.1.0",
+        instantiate: InstantiateMsg,
+        query: QueryMsg,
+        execute: ExecuteMsg,
+        sudo: SudoMsg,
+        migrate: MigrateMsg,
+    }
+    .render()
+    .to_typescript();
+
+    assert!(ts.contains(
+        r#"export type InstantiateMsg = {
+  admin: string;
+  cap: number;
+};"#
+    ));
+    assert!(ts.contains(
+        r#"export type ExecuteMsg = {
+  mint: {
+    amount: number;
+  };
+};"#
+    ));
+    assert!(ts.contains(
+        r#"export type QueryMsg = {
+  balance: {
+    account: string;
+  };
+};"#
+    ));
+    assert!(ts.contains("export type MigrateMsg = "));
+    assert!(ts.contains("export type SudoMsg = "));
+    assert!(ts.contains("export type BalanceResponse = number;"));
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum SudoMsgWithResponses {
+    #[returns(u128)]
+    SetAdmin { new_admin: String },
+}
+
+#[test]
+fn test_sudo_and_migrate_responses() {
+    let api_str = generate_api! {
+        instantiate: InstantiateMsg,
+        sudo: SudoMsgWithResponses,
+        sudo_responses: true,
+        migrate: MigrateMsg,
+    }
+    .render()
+    .to_string()
+    .unwrap();
+
+    let api: Value = serde_json::from_str(&api_str).unwrap();
+    assert_eq!(
+        api.get("sudo_responses")
+            .unwrap()
+            .get("set_admin")
+            .unwrap()
+            .get("title")
+            .unwrap(),
+        "uint128"
+    );
+    // omitted when not enabled
+    assert!(api.get("migrate_responses").is_none());
+}
+
+#[derive(cosmwasm_schema::ContractEvents)]
+pub enum Event {
+    /// Tokens were minted
+    Mint { recipient: String, amount: u128 },
+}
+
+#[derive(thiserror::Error, Debug, cosmwasm_schema::ContractErrors)]
+pub enum ContractError {
+    #[error("Unauthorized")]
+    Unauthorized {},
+    #[error("Cap of {cap} exceeded")]
+    CapExceeded { cap: u128 },
+}
+
+#[test]
+fn test_events_and_errors() {
+    // thiserror and ContractErrors share the `error` attribute
+    assert_eq!(
+        ContractError::CapExceeded { cap: 5 }.to_string(),
+        "Cap of 5 exceeded"
+    );
+
+    let api_str = generate_api! {
+        instantiate: InstantiateMsg,
+        events: Event,
+        errors: ContractError,
+    }
+    .render()
+    .to_string()
+    .unwrap();
+
+    let api: Value = serde_json::from_str(&api_str).unwrap();
+    assert_eq!(
+        api.get("events").unwrap(),
+        &serde_json::json!([{
+            "name": "mint",
+            "description": "Tokens were minted",
+            "attributes": ["recipient", "amount"],
+        }])
+    );
+    assert_eq!(
+        api.get("errors").unwrap(),
+        &serde_json::json!([
+            {
+                "name": "Unauthorized",
+                "message": "Unauthorized",
+                "description": null,
+            },
+            {
+                "name": "CapExceeded",
+                "message": "Cap of {cap} exceeded",
+                "description": null,
+            },
+        ])
+    );
+}
+
 // Test to reproduce https://github.com/CosmWasm/cosmwasm/issues/1527
 #[test]
 fn generate_api_works_when_only_types_are_imported() {
@@ -261,6 +390,43 @@ fn test_nested_query_responses_with_generics() {
     .unwrap();
 }
 
+#[cw_serde]
+#[derive(QueryResponses)]
+#[serde(untagged)]
+pub enum FlattenedQueryMsg {
+    Base {
+        #[serde(flatten)]
+        base: QueryMsg,
+    },
+    Extension(SubQueryMsg1),
+}
+
+#[test]
+fn test_flattened_query_responses() {
+    let api_str = generate_api! {
+        instantiate: InstantiateMsg,
+        query: FlattenedQueryMsg,
+    }
+    .render()
+    .to_string()
+    .unwrap();
+
+    let api: Value = serde_json::from_str(&api_str).unwrap();
+    let responses = api.get("responses").unwrap();
+    responses.get("balance").unwrap();
+    responses.get("variant1").unwrap();
+
+    let msg: FlattenedQueryMsg = serde_json::from_str(r#"{"balance":{"account":"foo"}}"#).unwrap();
+    assert_eq!(
+        msg,
+        FlattenedQueryMsg::Base {
+            base: QueryMsg::Balance {
+                account: "foo".to_string()
+            }
+        }
+    );
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 enum QueryMsg2 {
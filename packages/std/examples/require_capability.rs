@@ -0,0 +1,9 @@
+//! Declares capabilities with `require_capability!` next to the ones cosmwasm-std declares
+//! for its enabled features. This is built for the wasm target in CI to ensure the marker
+//! exports of both do not conflict.
+
+use cosmwasm_std::require_capability;
+
+#[cfg(not(feature = "randomness"))]
+require_capability!(randomness);
+require_capability!("token_factory:v1");
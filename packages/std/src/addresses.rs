@@ -72,6 +72,107 @@ impl Addr {
     pub fn into_string(self) -> String {
         self.0
     }
+
+    /// Checks that the input is a lowercase bech32 address in the given format and
+    /// creates an `Addr` from it.
+    ///
+    /// In contrast to `Api::addr_validate`, this works without an `Api`, e.g. for
+    /// sanity-checking addresses of other chains carried in messages. It only
+    /// validates the format and does not guarantee that the address is in use.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{Addr, AddressFormat};
+    /// let format = AddressFormat::bech32(&["cosmos", "osmo"]);
+    /// let addr = Addr::validate_format("cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu", &format).unwrap();
+    /// assert_eq!(addr.as_str(), "cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu");
+    ///
+    /// Addr::validate_format("juno1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5tf9t6p", &format).unwrap_err();
+    /// ```
+    pub fn validate_format(input: &str, format: &AddressFormat) -> Result<Addr, AddrFormatError> {
+        if input.len() > format.max_length {
+            return Err(AddrFormatError::TooLong {
+                length: input.len(),
+                max: format.max_length,
+            });
+        }
+        if input.chars().any(|c| c.is_ascii_uppercase()) {
+            return Err(AddrFormatError::NotLowercase);
+        }
+        let (prefix, _, variant) = bech32::decode(input).map_err(|err| match err {
+            bech32::Error::MissingSeparator => AddrFormatError::MissingSeparator,
+            // empty prefix or less data than the checksum (too long prefixes exceed the max length)
+            bech32::Error::InvalidLength => AddrFormatError::TooShort,
+            bech32::Error::InvalidChar(character) => {
+                AddrFormatError::InvalidCharacter { character }
+            }
+            bech32::Error::MixedCase => AddrFormatError::NotLowercase,
+            _ => AddrFormatError::InvalidChecksum,
+        })?;
+        // Cosmos addresses use the original bech32 checksum, not bech32m
+        if variant != bech32::Variant::Bech32 {
+            return Err(AddrFormatError::InvalidChecksum);
+        }
+        if !format.prefixes.is_empty() && !format.prefixes.contains(&prefix.as_str()) {
+            return Err(AddrFormatError::InvalidPrefix { prefix });
+        }
+
+        Ok(Addr(input.to_string()))
+    }
+}
+
+/// The limits used by [`Addr::validate_format`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AddressFormat<'a> {
+    /// The allowed bech32 prefixes. An empty list allows any prefix.
+    pub prefixes: &'a [&'a str],
+    /// The maximum length of the address in bytes
+    pub max_length: usize,
+}
+
+impl<'a> AddressFormat<'a> {
+    /// Creates a format for bech32 addresses with one of the given prefixes and the
+    /// maximum length of 90 characters defined by bech32.
+    pub const fn bech32(prefixes: &'a [&'a str]) -> Self {
+        Self {
+            prefixes,
+            max_length: 90,
+        }
+    }
+
+    /// Sets the maximum length of the address.
+    pub const fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+}
+
+/// Errors returned by [`Addr::validate_format`]
+#[derive(Error, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AddrFormatError {
+    #[error("Address is too long: {length} > {max}")]
+    TooLong { length: usize, max: usize },
+    #[error("Address is too short")]
+    TooShort,
+    #[error("Address must be lowercase")]
+    NotLowercase,
+    #[error("Address is missing the bech32 separator")]
+    MissingSeparator,
+    #[error("Address prefix '{prefix}' is not allowed")]
+    InvalidPrefix { prefix: String },
+    #[error("Invalid character '{character}' in address")]
+    InvalidCharacter { character: char },
+    #[error("Invalid address checksum")]
+    InvalidChecksum,
+}
+
+impl From<AddrFormatError> for crate::StdError {
+    fn from(value: AddrFormatError) -> Self {
+        Self::generic_err(format!("Invalid address: {value}"))
+    }
 }
 
 impl fmt::Display for Addr {
@@ -252,22 +353,42 @@ impl fmt::Display for CanonicalAddr {
 /// Errors related to the instantiation of contracts and generation of addresses.
 /// Ensures the integrity and validity of newly created contracts.
 #[derive(Error, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Instantiate2AddressError {
     /// Checksum must be 32 bytes
-    InvalidChecksumLength,
-    /// Salt must be between 1 and 64 bytes
-    InvalidSaltLength,
+    InvalidChecksumLength { length: usize },
+    /// Salt must not be empty
+    EmptySalt,
+    /// Salt must not be longer than 64 bytes
+    SaltTooLong { length: usize },
+    /// The canonical creator address must be between 1 and 255 bytes
+    InvalidCreatorLength { length: usize },
 }
 
 impl fmt::Display for Instantiate2AddressError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Instantiate2AddressError::InvalidChecksumLength => write!(f, "invalid checksum length"),
-            Instantiate2AddressError::InvalidSaltLength => write!(f, "invalid salt length"),
+            Instantiate2AddressError::InvalidChecksumLength { length } => {
+                write!(f, "invalid checksum length: {length} bytes, expected 32")
+            }
+            Instantiate2AddressError::EmptySalt => write!(f, "salt must not be empty"),
+            Instantiate2AddressError::SaltTooLong { length } => {
+                write!(f, "salt too long: {length} bytes, max {INSTANTIATE2_MAX_SALT_LEN}")
+            }
+            Instantiate2AddressError::InvalidCreatorLength { length } => write!(
+                f,
+                "invalid creator address length: {length} bytes, must be between 1 and {INSTANTIATE2_MAX_CREATOR_LEN}"
+            ),
         }
     }
 }
 
+/// The max length of the salt in [`instantiate2_address`]
+const INSTANTIATE2_MAX_SALT_LEN: usize = 64;
+
+/// The max length of a canonical address in the Cosmos SDK (`address.MaxAddrLen`)
+const INSTANTIATE2_MAX_CREATOR_LEN: usize = 255;
+
 /// Creates a contract address using the predictable address format introduced with
 /// wasmd 0.29. When using instantiate2, this is a way to precompute the address.
 /// When using instantiate, the contract address will use a different algorithm and
@@ -283,7 +404,7 @@ impl fmt::Display for Instantiate2AddressError {
 ///
 /// ```
 /// # use cosmwasm_std::{
-/// #     HexBinary,
+/// #     Checksum,
 /// #     Storage, Api, Querier, DepsMut, Deps, entry_point, Env, StdError, MessageInfo,
 /// #     Response, QueryResponse,
 /// # };
@@ -298,9 +419,10 @@ impl fmt::Display for Instantiate2AddressError {
 ///     msg: ExecuteMsg,
 /// ) -> Result<Response, StdError> {
 ///     let canonical_creator = deps.api.addr_canonicalize(env.contract.address.as_str())?;
-///     let checksum = HexBinary::from_hex("9af782a3a1bcbcd22dbb6a45c751551d9af782a3a1bcbcd22dbb6a45c751551d")?;
+///     // usually obtained via `deps.querier.query_wasm_code_info(code_id)?.checksum`
+///     let checksum = Checksum::from_hex("9af782a3a1bcbcd22dbb6a45c751551d9af782a3a1bcbcd22dbb6a45c751551d")?;
 ///     let salt = b"instance 1231";
-///     let canonical_addr = instantiate2_address(&checksum, &canonical_creator, salt)
+///     let canonical_addr = instantiate2_address(checksum.as_slice(), &canonical_creator, salt)
 ///         .map_err(|_| StdError::generic_err("Could not calculate addr"))?;
 ///     let addr = deps.api.addr_humanize(&canonical_addr)?;
 ///
@@ -329,12 +451,25 @@ fn instantiate2_address_impl(
     msg: &[u8],
 ) -> Result<CanonicalAddr, Instantiate2AddressError> {
     if checksum.len() != 32 {
-        return Err(Instantiate2AddressError::InvalidChecksumLength);
+        return Err(Instantiate2AddressError::InvalidChecksumLength {
+            length: checksum.len(),
+        });
     }
 
-    if salt.is_empty() || salt.len() > 64 {
-        return Err(Instantiate2AddressError::InvalidSaltLength);
-    };
+    if salt.is_empty() {
+        return Err(Instantiate2AddressError::EmptySalt);
+    }
+    if salt.len() > INSTANTIATE2_MAX_SALT_LEN {
+        return Err(Instantiate2AddressError::SaltTooLong { length: salt.len() });
+    }
+
+    // Creators of any length supported by the SDK are valid, e.g. 20 byte account
+    // addresses or 32 byte module and contract addresses.
+    if creator.is_empty() || creator.len() > INSTANTIATE2_MAX_CREATOR_LEN {
+        return Err(Instantiate2AddressError::InvalidCreatorLength {
+            length: creator.len(),
+        });
+    }
 
     let mut key = Vec::<u8>::new();
     key.extend_from_slice(b"wasm\0");
@@ -372,6 +507,75 @@ mod tests {
         assert_ne!(a, b);
     }
 
+    #[test]
+    fn addr_validate_format_works() {
+        let api = crate::testing::MockApi::default();
+        let valid = api.addr_make("creator").into_string();
+        let format = AddressFormat::bech32(&["cosmwasm"]);
+        assert_eq!(
+            Addr::validate_format(&valid, &format).unwrap().as_str(),
+            valid
+        );
+        // any prefix
+        Addr::validate_format(&valid, &AddressFormat::bech32(&[])).unwrap();
+        // BIP-173 test vectors
+        Addr::validate_format("a12uel5l", &AddressFormat::bech32(&["a"])).unwrap();
+        Addr::validate_format(
+            "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+            &AddressFormat::bech32(&[]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            Addr::validate_format(&valid, &AddressFormat::bech32(&["osmo"])),
+            Err(AddrFormatError::InvalidPrefix {
+                prefix: "cosmwasm".to_string()
+            })
+        );
+        assert_eq!(
+            Addr::validate_format(&valid, &format.clone().with_max_length(20)),
+            Err(AddrFormatError::TooLong {
+                length: valid.len(),
+                max: 20
+            })
+        );
+        assert_eq!(
+            Addr::validate_format(&valid.to_uppercase(), &AddressFormat::bech32(&[])),
+            Err(AddrFormatError::NotLowercase)
+        );
+        assert_eq!(
+            Addr::validate_format("cosmwasm", &format),
+            Err(AddrFormatError::MissingSeparator)
+        );
+        assert_eq!(
+            Addr::validate_format("cosmwasm1qqq", &format),
+            Err(AddrFormatError::TooShort)
+        );
+        assert_eq!(
+            Addr::validate_format("1qqqqqqqq", &AddressFormat::bech32(&[])),
+            Err(AddrFormatError::TooShort)
+        );
+        assert_eq!(
+            Addr::validate_format("cosmwasm1qqqqqqqqb", &format),
+            Err(AddrFormatError::InvalidCharacter { character: 'b' })
+        );
+
+        // bech32m checksums are not accepted (BIP-350 test vector)
+        assert_eq!(
+            Addr::validate_format("a1lqfn3a", &AddressFormat::bech32(&["a"])),
+            Err(AddrFormatError::InvalidChecksum)
+        );
+
+        // changing a single character breaks the checksum
+        let last = valid.chars().last().unwrap();
+        let replacement = if last == 'q' { 'p' } else { 'q' };
+        let tampered = format!("{}{replacement}", &valid[..valid.len() - 1]);
+        assert_eq!(
+            Addr::validate_format(&tampered, &format),
+            Err(AddrFormatError::InvalidChecksum)
+        );
+    }
+
     #[test]
     fn addr_as_str_works() {
         let addr = Addr::unchecked("literal-string");
@@ -683,32 +887,83 @@ mod tests {
 
         // Salt too short or too long
         let empty = Vec::<u8>::new();
-        assert!(matches!(
+        assert_eq!(
             instantiate2_address_impl(&checksum1, &creator1, &empty, b"").unwrap_err(),
-            Instantiate2AddressError::InvalidSaltLength
-        ));
+            Instantiate2AddressError::EmptySalt
+        );
         let too_long = vec![0x11; 65];
-        assert!(matches!(
+        assert_eq!(
             instantiate2_address_impl(&checksum1, &creator1, &too_long, b"").unwrap_err(),
-            Instantiate2AddressError::InvalidSaltLength
-        ));
+            Instantiate2AddressError::SaltTooLong { length: 65 }
+        );
 
         // invalid checksum length
         let broken_cs = hex!("13a1fc994cc6d1c81b746ee0c0ff6f90043875e0bf1d9be6b7d779fc978dc2");
-        assert!(matches!(
+        assert_eq!(
             instantiate2_address_impl(&broken_cs, &creator1, &salt1, b"").unwrap_err(),
-            Instantiate2AddressError::InvalidChecksumLength
-        ));
+            Instantiate2AddressError::InvalidChecksumLength { length: 31 }
+        );
         let broken_cs = hex!("");
-        assert!(matches!(
+        assert_eq!(
             instantiate2_address_impl(&broken_cs, &creator1, &salt1, b"").unwrap_err(),
-            Instantiate2AddressError::InvalidChecksumLength
-        ));
+            Instantiate2AddressError::InvalidChecksumLength { length: 0 }
+        );
         let broken_cs = hex!("13a1fc994cc6d1c81b746ee0c0ff6f90043875e0bf1d9be6b7d779fc978dc2aaaa");
-        assert!(matches!(
+        assert_eq!(
             instantiate2_address_impl(&broken_cs, &creator1, &salt1, b"").unwrap_err(),
-            Instantiate2AddressError::InvalidChecksumLength
-        ));
+            Instantiate2AddressError::InvalidChecksumLength { length: 33 }
+        );
+
+        // invalid creator length
+        let empty_creator = CanonicalAddr::from(vec![]);
+        assert_eq!(
+            instantiate2_address_impl(&checksum1, &empty_creator, &salt1, b"").unwrap_err(),
+            Instantiate2AddressError::InvalidCreatorLength { length: 0 }
+        );
+        let long_creator = CanonicalAddr::from(vec![0x9e; 256]);
+        assert_eq!(
+            instantiate2_address_impl(&checksum1, &long_creator, &salt1, b"").unwrap_err(),
+            Instantiate2AddressError::InvalidCreatorLength { length: 256 }
+        );
+    }
+
+    #[test]
+    fn instantiate2_address_works_for_creators_of_any_length() {
+        let checksum = hex!("13a1fc994cc6d1c81b746ee0c0ff6f90043875e0bf1d9be6b7d779fc978dc2a5");
+        let salt = b"salt";
+
+        use std::collections::BTreeSet;
+
+        let mut addresses = Vec::new();
+        for len in [1, 20, 32, 33, 255] {
+            let creator = CanonicalAddr::from(vec![0x9e; len]);
+            let address = instantiate2_address(&checksum, &creator, salt).unwrap();
+            assert_eq!(address.len(), 32);
+            addresses.push(address);
+        }
+        // the creator length is part of the key
+        let unique: BTreeSet<Vec<u8>> = addresses.iter().map(|a| a.to_vec()).collect();
+        assert_eq!(unique.len(), 5);
+    }
+
+    #[test]
+    fn instantiate2_address_error_display() {
+        assert_eq!(
+            Instantiate2AddressError::InvalidChecksumLength { length: 31 }.to_string(),
+            "invalid checksum length: 31 bytes, expected 32"
+        );
+        assert_eq!(
+            Instantiate2AddressError::EmptySalt.to_string(),
+            "salt must not be empty"
+        );
+        assert_eq!(
+            Instantiate2AddressError::SaltTooLong { length: 65 }.to_string(),
+            "salt too long: 65 bytes, max 64"
+        );
+        assert_eq!(
+            Instantiate2AddressError::InvalidCreatorLength { length: 0 }.to_string(),
+            "invalid creator address length: 0 bytes, must be between 1 and 255"
+        );
     }
 
     #[test]
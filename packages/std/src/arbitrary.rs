@@ -0,0 +1,139 @@
+//! Implementations of [`arbitrary::Arbitrary`](::arbitrary::Arbitrary) for fuzzing and
+//! [`proptest::arbitrary::Arbitrary`](::proptest::arbitrary::Arbitrary) for property testing.
+//!
+//! All values are created from the underlying primitive representation, so every valid
+//! value of a type can be generated. Strings like denoms and addresses are not validated.
+
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+use ::proptest::arbitrary::{any, Arbitrary as PropArbitrary};
+use ::proptest::strategy::{Map, Strategy};
+
+use crate::{
+    Addr, Binary, Coin, Decimal, Decimal256, Int128, Int256, Int512, Int64, SignedDecimal,
+    SignedDecimal256, Timestamp, Uint128, Uint256, Uint512, Uint64,
+};
+
+/// Implements both `Arbitrary` traits for `$ty` by generating a value of `$inner`
+/// and converting it using `$constructor`.
+macro_rules! impl_arbitrary {
+    ($ty:ty, $inner:ty, $constructor:expr) => {
+        impl<'a> Arbitrary<'a> for $ty {
+            fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+                let constructor: fn($inner) -> $ty = $constructor;
+                u.arbitrary().map(constructor)
+            }
+
+            fn size_hint(depth: usize) -> (usize, Option<usize>) {
+                <$inner as Arbitrary>::size_hint(depth)
+            }
+        }
+
+        impl PropArbitrary for $ty {
+            type Parameters = ();
+            type Strategy = Map<<$inner as PropArbitrary>::Strategy, fn($inner) -> $ty>;
+
+            fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+                any::<$inner>().prop_map($constructor)
+            }
+        }
+    };
+}
+
+impl_arbitrary!(Uint64, u64, Uint64::new);
+impl_arbitrary!(Uint128, u128, Uint128::new);
+impl_arbitrary!(Uint256, [u8; 32], Uint256::from_be_bytes);
+impl_arbitrary!(Uint512, ([u8; 32], [u8; 32]), |(high, low)| {
+    Uint512::from_be_bytes(concat(high, low))
+});
+impl_arbitrary!(Int64, i64, Int64::new);
+impl_arbitrary!(Int128, i128, Int128::new);
+impl_arbitrary!(Int256, [u8; 32], Int256::from_be_bytes);
+impl_arbitrary!(Int512, ([u8; 32], [u8; 32]), |(high, low)| {
+    Int512::from_be_bytes(concat(high, low))
+});
+impl_arbitrary!(Decimal, u128, Decimal::raw);
+impl_arbitrary!(Decimal256, [u8; 32], |bytes| {
+    Decimal256::new(Uint256::from_be_bytes(bytes))
+});
+impl_arbitrary!(SignedDecimal, i128, SignedDecimal::raw);
+impl_arbitrary!(SignedDecimal256, [u8; 32], |bytes| {
+    SignedDecimal256::new(Int256::from_be_bytes(bytes))
+});
+impl_arbitrary!(Coin, (String, u128), |(denom, amount)| {
+    Coin::new(amount, denom)
+});
+impl_arbitrary!(Addr, String, Addr::unchecked);
+impl_arbitrary!(Binary, Vec<u8>, Binary::new);
+impl_arbitrary!(Timestamp, u64, Timestamp::from_nanos);
+
+/// Arrays larger than 32 elements do not implement proptest's `Arbitrary`,
+/// so 64 byte integers are generated from two halves.
+fn concat(high: [u8; 32], low: [u8; 32]) -> [u8; 64] {
+    let mut out = [0u8; 64];
+    out[..32].copy_from_slice(&high);
+    out[32..].copy_from_slice(&low);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use ::arbitrary::{Arbitrary, Unstructured};
+    use ::proptest::prelude::{prop_assert_eq, proptest};
+
+    use crate::{
+        from_json, to_json_vec, Coin, Decimal, Int512, Timestamp, Uint128, Uint256, Uint512,
+    };
+
+    #[test]
+    fn arbitrary_works() {
+        let data = [0xAB; 256];
+        let mut u = Unstructured::new(&data);
+
+        assert_eq!(
+            u.arbitrary::<Uint128>().unwrap(),
+            Uint128::new(0xABAB_ABAB_ABAB_ABAB_ABAB_ABAB_ABAB_ABAB)
+        );
+        assert_eq!(
+            u.arbitrary::<Uint256>().unwrap(),
+            Uint256::from_be_bytes([0xAB; 32])
+        );
+        assert_eq!(
+            u.arbitrary::<Uint512>().unwrap(),
+            Uint512::from_be_bytes([0xAB; 64])
+        );
+        assert_eq!(
+            u.arbitrary::<Timestamp>().unwrap(),
+            Timestamp::from_nanos(0xABAB_ABAB_ABAB_ABAB)
+        );
+    }
+
+    #[test]
+    fn arbitrary_size_hint_works() {
+        assert_eq!(<Uint128 as Arbitrary>::size_hint(0), (16, Some(16)));
+        assert_eq!(<Uint512 as Arbitrary>::size_hint(0), (64, Some(64)));
+    }
+
+    proptest! {
+        #[test]
+        fn proptest_decimal_json_roundtrip(value: Decimal) {
+            let json = to_json_vec(&value).unwrap();
+            prop_assert_eq!(from_json::<Decimal>(json).unwrap(), value);
+        }
+
+        #[test]
+        fn proptest_uint128_checked_add(a: Uint128, b: Uint128) {
+            prop_assert_eq!(a.checked_add(b).ok(), a.u128().checked_add(b.u128()).map(Uint128::new));
+        }
+
+        #[test]
+        fn proptest_coin_json_roundtrip(coin: Coin) {
+            let json = to_json_vec(&coin).unwrap();
+            prop_assert_eq!(from_json::<Coin>(json).unwrap(), coin);
+        }
+
+        #[test]
+        fn proptest_int512_sign(value: Int512) {
+            prop_assert_eq!(value.is_negative(), value < Int512::zero());
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! A bump allocator for the memory regions the host writes into.
+//!
+//! During an entry point call the host allocates a lot of short-lived regions through the
+//! `allocate` export: the entry point arguments, every storage value read and every query
+//! response. With the `arena_allocator` feature, those regions are carved out of a fixed-size
+//! arena instead of going through the global allocator. The arena is reset once the result of
+//! the entry point has been handed over to the host, which is when no region can be alive anymore.
+//!
+//! Freeing arena memory is a no-op, so a [`Vec`] created from an arena region can be dropped
+//! (or grown) like any other vector. This is implemented by a global allocator that wraps
+//! [`System`] and recognizes arena pointers.
+//!
+//! Data read from the host must not be kept in statics across entry point calls without copying it,
+//! since the arena memory is reused by the next call.
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::ptr::{self, addr_of_mut};
+use std::alloc::System;
+
+/// The size of the arena in bytes. Regions that do not fit in the remaining space are
+/// allocated by the global allocator as usual.
+const ARENA_SIZE: usize = 64 * 1024;
+
+/// Alignment of arena allocations. This matches the alignment of the global allocator for
+/// small allocations on wasm32.
+const ARENA_ALIGN: usize = 8;
+
+struct Arena {
+    /// Start of the arena, or null if the arena was not yet allocated
+    start: *mut u8,
+    /// Offset of the next free byte relative to `start`
+    offset: usize,
+}
+
+/// Contracts are executed single-threaded, so no synchronization is needed.
+static mut ARENA: Arena = Arena {
+    start: ptr::null_mut(),
+    offset: 0,
+};
+
+/// Allocates `size` bytes from the arena. Returns `None` if the arena is exhausted
+/// (or could not be allocated), in which case the caller should fall back to the
+/// global allocator.
+pub fn arena_alloc(size: usize) -> Option<*mut u8> {
+    if size == 0 {
+        return None;
+    }
+    let arena = unsafe { &mut *addr_of_mut!(ARENA) };
+    if arena.start.is_null() {
+        let layout = Layout::from_size_align(ARENA_SIZE, ARENA_ALIGN).ok()?;
+        // The arena lives for the lifetime of the instance and is never freed.
+        let start = unsafe { System.alloc(layout) };
+        if start.is_null() {
+            return None;
+        }
+        arena.start = start;
+    }
+
+    let aligned = arena.offset.checked_add(ARENA_ALIGN - 1)? & !(ARENA_ALIGN - 1);
+    let end = aligned.checked_add(size)?;
+    if end > ARENA_SIZE {
+        return None;
+    }
+    arena.offset = end;
+    Some(unsafe { arena.start.add(aligned) })
+}
+
+/// Returns true if the given pointer points into the arena.
+fn contains(ptr: *mut u8) -> bool {
+    let arena = unsafe { &*addr_of_mut!(ARENA) };
+    if arena.start.is_null() {
+        return false;
+    }
+    let start = arena.start as usize;
+    let ptr = ptr as usize;
+    ptr >= start && ptr < start + ARENA_SIZE
+}
+
+/// Makes the whole arena available again.
+///
+/// This must only be called when no region allocated from the arena is in use anymore,
+/// i.e. at the end of an entry point call.
+pub fn reset() {
+    unsafe { (*addr_of_mut!(ARENA)).offset = 0 }
+}
+
+/// The global allocator used with the `arena_allocator` feature.
+///
+/// It delegates to [`System`] but ignores deallocations of arena memory and moves arena
+/// memory to the system allocator when it is grown.
+struct ArenaAwareAllocator;
+
+unsafe impl GlobalAlloc for ArenaAwareAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if !contains(ptr) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if !contains(ptr) {
+            return System.realloc(ptr, layout, new_size);
+        }
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        let new_ptr = System.alloc(new_layout);
+        if !new_ptr.is_null() {
+            ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static GLOBAL: ArenaAwareAllocator = ArenaAwareAllocator;
@@ -117,7 +117,7 @@ mod tests {
         }
 
         let err = check(5, 6).unwrap_err();
-        assert!(matches!(err, StdError::GenericErr { .. }));
+        assert!(err.is_generic_err());
 
         check(5, 5).unwrap();
     }
@@ -129,8 +129,8 @@ mod tests {
             Ok(())
         };
 
-        let err = check(5, 6).unwrap_err();
-        assert!(matches!(err, StdError::GenericErr { .. }));
+        let err: StdError = check(5, 6).unwrap_err();
+        assert!(err.is_generic_err());
 
         check(5, 5).unwrap();
     }
@@ -164,8 +164,8 @@ mod tests {
             Ok(())
         };
 
-        let err = check("123", "456").unwrap_err();
-        assert!(matches!(err, StdError::GenericErr { .. }));
+        let err: StdError = check("123", "456").unwrap_err();
+        assert!(err.is_generic_err());
         check("123", "123").unwrap();
     }
 
@@ -190,8 +190,8 @@ mod tests {
             Ok(())
         };
 
-        let err = check("123", "123").unwrap_err();
-        assert!(matches!(err, StdError::GenericErr { .. }));
+        let err: StdError = check("123", "123").unwrap_err();
+        assert!(err.is_generic_err());
         check("123", "456").unwrap();
     }
 
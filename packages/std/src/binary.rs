@@ -1,8 +1,8 @@
+use alloc::{format, string::String, vec::Vec};
 use core::fmt;
-use core::ops::Deref;
+use core::ops::{Bound, Deref, RangeBounds};
 
 use base64::engine::{Engine, GeneralPurpose};
-use schemars::JsonSchema;
 use serde::{de, ser, Deserialize, Deserializer, Serialize};
 
 use crate::errors::{StdError, StdResult};
@@ -12,8 +12,9 @@ use crate::errors::{StdError, StdResult};
 ///
 /// This is only needed as serde-json-{core,wasm} has a horrible encoding for Vec<u8>.
 /// See also <https://github.com/CosmWasm/cosmwasm/blob/main/docs/MESSAGE_TYPES.md>.
-#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord, JsonSchema)]
-pub struct Binary(#[schemars(with = "String")] Vec<u8>);
+#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+pub struct Binary(#[cfg_attr(feature = "std", schemars(with = "String"))] Vec<u8>);
 
 impl Binary {
     /// Base64 encoding engine used in conversion to/from base64.
@@ -80,6 +81,65 @@ impl Binary {
         out.copy_from_slice(&self.0);
         Ok(out)
     }
+
+    /// Returns a reference to the content as a fixed-sized array without copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::Binary;
+    /// let data = Binary::from(&[0xfb, 0x1f, 0x37]);
+    /// let array: &[u8; 3] = data.as_array().unwrap();
+    /// assert_eq!(array, &[0xfb, 0x1f, 0x37]);
+    /// ```
+    pub fn as_array<const LENGTH: usize>(&self) -> StdResult<&[u8; LENGTH]> {
+        self.0
+            .as_slice()
+            .try_into()
+            .map_err(|_| StdError::invalid_data_size(LENGTH, self.len()))
+    }
+
+    /// Returns the given range of the content without copying.
+    /// Fails if the range is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::Binary;
+    /// let data = Binary::from(&[1, 2, 3, 4]);
+    /// assert_eq!(data.subslice(1..3).unwrap(), &[2, 3]);
+    /// assert_eq!(data.subslice(2..).unwrap(), &[3, 4]);
+    /// data.subslice(3..5).unwrap_err();
+    /// ```
+    pub fn subslice(&self, range: impl RangeBounds<usize>) -> StdResult<&[u8]> {
+        let bounds: (Bound<usize>, Bound<usize>) =
+            (range.start_bound().cloned(), range.end_bound().cloned());
+        self.0.get(bounds).ok_or_else(|| {
+            StdError::generic_err(format!(
+                "Range {bounds:?} out of bounds for data of length {}",
+                self.len()
+            ))
+        })
+    }
+
+    /// Compares the content to the given data in constant time, i.e. the duration of the
+    /// comparison does not depend on the position of the first differing byte.
+    /// Use this to compare secrets like commitments or MACs.
+    ///
+    /// Only the content is protected, the lengths of the inputs are not secret.
+    pub fn ct_eq(&self, other: impl AsRef<[u8]>) -> bool {
+        crate::binary::constant_time_eq(&self.0, other.as_ref())
+    }
+}
+
+/// Compares two byte slices in constant time for equal lengths.
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y));
+    // prevent the compiler from short-circuiting the loop
+    core::hint::black_box(diff) == 0
 }
 
 impl fmt::Display for Binary {
@@ -260,6 +320,51 @@ mod tests {
     use crate::errors::StdError;
     use crate::serde::{from_json, to_json_vec};
 
+    #[test]
+    fn as_array_works() {
+        let binary = Binary::from(&[1, 2, 3]);
+        let array: &[u8; 3] = binary.as_array().unwrap();
+        assert_eq!(array, &[1, 2, 3]);
+        assert!(binary.as_array::<0>().is_err());
+
+        let error = binary.as_array::<8>().unwrap_err();
+        assert_eq!(error, StdError::invalid_data_size(8, 3));
+
+        let binary = Binary::from(&[]);
+        let array: &[u8; 0] = binary.as_array().unwrap();
+        assert_eq!(array, &[] as &[u8; 0]);
+    }
+
+    #[test]
+    fn subslice_works() {
+        let binary = Binary::from(&[1, 2, 3, 4]);
+        assert_eq!(binary.subslice(..).unwrap(), &[1, 2, 3, 4]);
+        assert_eq!(binary.subslice(1..3).unwrap(), &[2, 3]);
+        assert_eq!(binary.subslice(1..=3).unwrap(), &[2, 3, 4]);
+        assert_eq!(binary.subslice(..2).unwrap(), &[1, 2]);
+        assert_eq!(binary.subslice(4..).unwrap(), &[] as &[u8]);
+
+        let err = binary.subslice(3..5).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("out of bounds for data of length 4"));
+        binary.subslice(5..).unwrap_err();
+        #[allow(clippy::reversed_empty_ranges)]
+        binary.subslice(3..1).unwrap_err();
+    }
+
+    #[test]
+    fn ct_eq_works() {
+        let binary = Binary::from(&[1, 2, 3]);
+        assert!(binary.ct_eq([1, 2, 3]));
+        assert!(binary.ct_eq(Binary::from(&[1, 2, 3])));
+        assert!(!binary.ct_eq([1, 2, 4]));
+        assert!(!binary.ct_eq([0, 2, 3]));
+        assert!(!binary.ct_eq([1, 2]));
+        assert!(!binary.ct_eq([1, 2, 3, 4]));
+        assert!(Binary::default().ct_eq([]));
+    }
+
     #[test]
     fn to_array_works() {
         // simple
@@ -275,15 +380,7 @@ mod tests {
         // invalid size
         let binary = Binary::from(&[1, 2, 3]);
         let error = binary.to_array::<8>().unwrap_err();
-        match error {
-            StdError::InvalidDataSize {
-                expected, actual, ..
-            } => {
-                assert_eq!(expected, 8);
-                assert_eq!(actual, 3);
-            }
-            err => panic!("Unexpected error: {err:?}"),
-        }
+        assert_eq!(error, StdError::invalid_data_size(8, 3));
 
         // long array (32 bytes)
         let binary = Binary::from_base64("t119JOQox4WUQEmO/nyqOZfO+wjJm91YG2sfn4ZglvA=").unwrap();
@@ -334,10 +431,10 @@ mod tests {
             ("cm%uZG9taVo", "Invalid byte 37, offset 2."),
             ("cmFuZ", "Encoded text cannot have a 6-bit remainder."),
         ] {
-            match Binary::from_base64(invalid_base64) {
-                Err(StdError::InvalidBase64 { msg, .. }) => assert_eq!(want, msg),
-                result => panic!("Unexpected result: {result:?}"),
-            }
+            assert_eq!(
+                Binary::from_base64(invalid_base64).unwrap_err(),
+                StdError::invalid_base64(want)
+            );
         }
     }
 
@@ -0,0 +1,172 @@
+//! Implementations of [`BorshSerialize`] and [`BorshDeserialize`] for compact storage of state.
+//!
+//! Integers are encoded as little-endian bytes of their fixed size, decimals as their atomics
+//! and strings and byte arrays with a length prefix. This is the same encoding Borsh uses for
+//! the underlying primitive types.
+
+use alloc::{string::String, vec::Vec};
+
+use ::borsh::io::{Read, Result, Write};
+use ::borsh::{BorshDeserialize, BorshSerialize};
+
+#[cfg(feature = "std")]
+use crate::Addr;
+use crate::{
+    Binary, Coin, Decimal, Decimal256, HexBinary, Int128, Int256, Int512, Int64, SignedDecimal,
+    SignedDecimal256, Timestamp, Uint128, Uint256, Uint512, Uint64,
+};
+
+/// Implements both Borsh traits for `$ty` by encoding it as `$inner`.
+/// `$into` converts a reference to `$ty` into `$inner` and `$from` converts back.
+macro_rules! impl_borsh {
+    ($ty:ty, $inner:ty, $into:expr, $from:expr) => {
+        impl BorshSerialize for $ty {
+            fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+                let into: fn(&$ty) -> $inner = $into;
+                into(self).serialize(writer)
+            }
+        }
+
+        impl BorshDeserialize for $ty {
+            fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+                let from: fn($inner) -> $ty = $from;
+                <$inner>::deserialize_reader(reader).map(from)
+            }
+        }
+    };
+}
+
+impl_borsh!(Uint64, u64, |value| value.u64(), Uint64::new);
+impl_borsh!(Uint128, u128, |value| value.u128(), Uint128::new);
+impl_borsh!(
+    Uint256,
+    [u8; 32],
+    |value| value.to_le_bytes(),
+    Uint256::from_le_bytes
+);
+impl_borsh!(
+    Uint512,
+    [u8; 64],
+    |value| value.to_le_bytes(),
+    Uint512::from_le_bytes
+);
+impl_borsh!(Int64, i64, |value| value.i64(), Int64::new);
+impl_borsh!(Int128, i128, |value| value.i128(), Int128::new);
+impl_borsh!(
+    Int256,
+    [u8; 32],
+    |value| value.to_le_bytes(),
+    Int256::from_le_bytes
+);
+impl_borsh!(
+    Int512,
+    [u8; 64],
+    |value| value.to_le_bytes(),
+    Int512::from_le_bytes
+);
+impl_borsh!(Decimal, Uint128, |value| value.atomics(), Decimal::new);
+impl_borsh!(
+    Decimal256,
+    Uint256,
+    |value| value.atomics(),
+    Decimal256::new
+);
+impl_borsh!(
+    SignedDecimal,
+    Int128,
+    |value| value.atomics(),
+    SignedDecimal::new
+);
+impl_borsh!(
+    SignedDecimal256,
+    Int256,
+    |value| value.atomics(),
+    SignedDecimal256::new
+);
+impl_borsh!(Timestamp, u64, |value| value.nanos(), Timestamp::from_nanos);
+impl_borsh!(Binary, Vec<u8>, |value| value.to_vec(), Binary::new);
+impl_borsh!(HexBinary, Vec<u8>, |value| value.to_vec(), HexBinary::from);
+#[cfg(feature = "std")]
+impl_borsh!(Addr, String, |value| value.to_string(), Addr::unchecked);
+
+impl BorshSerialize for Coin {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.denom.serialize(writer)?;
+        self.amount.serialize(writer)
+    }
+}
+
+impl BorshDeserialize for Coin {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let denom = String::deserialize_reader(reader)?;
+        let amount = Uint128::deserialize_reader(reader)?;
+        Ok(Coin { denom, amount })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ::borsh::{from_slice, to_vec};
+
+    fn roundtrip<T: BorshSerialize + BorshDeserialize + PartialEq + core::fmt::Debug>(value: T) {
+        let encoded = to_vec(&value).unwrap();
+        assert_eq!(from_slice::<T>(&encoded).unwrap(), value);
+    }
+
+    #[test]
+    fn encoding_works() {
+        assert_eq!(
+            to_vec(&Uint128::new(258)).unwrap(),
+            [2, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            to_vec(&Uint256::from(258u32)).unwrap(),
+            to_vec(&Uint128::new(258))
+                .unwrap()
+                .into_iter()
+                .chain([0; 16])
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            to_vec(&Decimal::one()).unwrap(),
+            to_vec(&1_000_000_000_000_000_000u128).unwrap()
+        );
+        assert_eq!(
+            to_vec(&Binary::from([1, 2, 3])).unwrap(),
+            [3, 0, 0, 0, 1, 2, 3]
+        );
+        assert_eq!(
+            to_vec(&Coin::new(1u128, "a")).unwrap(),
+            [1, 0, 0, 0, b'a', 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn roundtrip_works() {
+        roundtrip(Uint64::MAX);
+        roundtrip(Uint128::MAX);
+        roundtrip(Uint256::MAX);
+        roundtrip(Uint512::MAX);
+        roundtrip(Int64::MIN);
+        roundtrip(Int128::MIN);
+        roundtrip(Int256::MIN);
+        roundtrip(Int512::MIN);
+        roundtrip(Decimal::percent(42));
+        roundtrip(Decimal256::MAX);
+        roundtrip(SignedDecimal::negative_one());
+        roundtrip(SignedDecimal256::MIN);
+        roundtrip(Timestamp::from_seconds(1_700_000_000));
+        roundtrip(Binary::from(b"state"));
+        roundtrip(HexBinary::from(b"state"));
+        roundtrip(Addr::unchecked("cosmwasm1contract"));
+        roundtrip(Coin::new(123u128, "uatom"));
+    }
+
+    #[test]
+    fn deserialize_fails_for_truncated_input() {
+        from_slice::<Uint256>(&[0; 31]).unwrap_err();
+        from_slice::<Coin>(&[5, 0, 0, 0, b'u']).unwrap_err();
+    }
+}
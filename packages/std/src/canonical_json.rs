@@ -0,0 +1,430 @@
+//! Helpers for the canonical JSON serialization, see [`to_json_vec_canonical`](crate::to_json_vec_canonical).
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+use serde::ser::{self, Serialize};
+
+/// Checks that the data does not contain values without a canonical JSON representation.
+pub(crate) fn check_representable<T: Serialize + ?Sized>(data: &T) -> Result<(), String> {
+    data.serialize(Checker).map_err(|e| e.0)
+}
+
+/// Rewrites compact JSON with all object keys sorted by their Unicode code points.
+/// Errors if an object contains the same key twice.
+pub(crate) fn sort_keys(json: &[u8]) -> Result<Vec<u8>, String> {
+    let mut parser = Parser { data: json, pos: 0 };
+    let mut out = Vec::with_capacity(json.len());
+    parser.value(&mut out)?;
+    if parser.pos != json.len() {
+        return Err("Trailing data after JSON value".to_string());
+    }
+    Ok(out)
+}
+
+struct Parser<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Result<u8, String> {
+        self.data
+            .get(self.pos)
+            .copied()
+            .ok_or_else(|| "Unexpected end of JSON".to_string())
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        if self.peek()? != byte {
+            return Err(format!(
+                "Expected '{}' at offset {}",
+                byte as char, self.pos
+            ));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn value(&mut self, out: &mut Vec<u8>) -> Result<(), String> {
+        match self.peek()? {
+            b'{' => self.object(out),
+            b'[' => self.array(out),
+            b'"' => {
+                out.extend_from_slice(self.string()?);
+                Ok(())
+            }
+            _ => {
+                let start = self.pos;
+                while let Some(b) = self.data.get(self.pos) {
+                    if matches!(b, b',' | b'}' | b']') {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                out.extend_from_slice(&self.data[start..self.pos]);
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the raw string including the quotes
+    fn string(&mut self) -> Result<&'a [u8], String> {
+        let start = self.pos;
+        self.expect(b'"')?;
+        loop {
+            match self.peek()? {
+                b'\\' => self.pos += 2,
+                b'"' => {
+                    self.pos += 1;
+                    return Ok(&self.data[start..self.pos]);
+                }
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    fn array(&mut self, out: &mut Vec<u8>) -> Result<(), String> {
+        self.expect(b'[')?;
+        out.push(b'[');
+        if self.peek()? != b']' {
+            loop {
+                self.value(out)?;
+                if self.peek()? != b',' {
+                    break;
+                }
+                self.pos += 1;
+                out.push(b',');
+            }
+        }
+        self.expect(b']')?;
+        out.push(b']');
+        Ok(())
+    }
+
+    fn object(&mut self, out: &mut Vec<u8>) -> Result<(), String> {
+        self.expect(b'{')?;
+        let mut entries: Vec<(String, &'a [u8], Vec<u8>)> = Vec::new();
+        if self.peek()? != b'}' {
+            loop {
+                let raw_key = self.string()?;
+                let key: String =
+                    serde_json_wasm::from_slice(raw_key).map_err(|e| e.to_string())?;
+                self.expect(b':')?;
+                let mut value = Vec::new();
+                self.value(&mut value)?;
+                entries.push((key, raw_key, value));
+                if self.peek()? != b',' {
+                    break;
+                }
+                self.pos += 1;
+            }
+        }
+        self.expect(b'}')?;
+
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        if let Some(pair) = entries.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(format!("Duplicate key \"{}\"", pair[0].0));
+        }
+
+        out.push(b'{');
+        for (i, (_, raw_key, value)) in entries.iter().enumerate() {
+            if i > 0 {
+                out.push(b',');
+            }
+            out.extend_from_slice(raw_key);
+            out.push(b':');
+            out.extend_from_slice(value);
+        }
+        out.push(b'}');
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct CheckError(String);
+
+impl fmt::Display for CheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl ser::StdError for CheckError {}
+
+impl ser::Error for CheckError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        CheckError(msg.to_string())
+    }
+}
+
+/// A serializer that produces no output and only fails for unsupported values.
+struct Checker;
+
+type CheckResult = Result<(), CheckError>;
+
+fn unsupported(kind: &str) -> CheckResult {
+    Err(CheckError(format!(
+        "{kind} values have no canonical JSON representation"
+    )))
+}
+
+impl ser::Serializer for Checker {
+    type Ok = ();
+    type Error = CheckError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, _v: bool) -> CheckResult {
+        Ok(())
+    }
+    fn serialize_i8(self, _v: i8) -> CheckResult {
+        Ok(())
+    }
+    fn serialize_i16(self, _v: i16) -> CheckResult {
+        Ok(())
+    }
+    fn serialize_i32(self, _v: i32) -> CheckResult {
+        Ok(())
+    }
+    fn serialize_i64(self, _v: i64) -> CheckResult {
+        Ok(())
+    }
+    fn serialize_i128(self, _v: i128) -> CheckResult {
+        Ok(())
+    }
+    fn serialize_u8(self, _v: u8) -> CheckResult {
+        Ok(())
+    }
+    fn serialize_u16(self, _v: u16) -> CheckResult {
+        Ok(())
+    }
+    fn serialize_u32(self, _v: u32) -> CheckResult {
+        Ok(())
+    }
+    fn serialize_u64(self, _v: u64) -> CheckResult {
+        Ok(())
+    }
+    fn serialize_u128(self, _v: u128) -> CheckResult {
+        Ok(())
+    }
+    fn serialize_f32(self, _v: f32) -> CheckResult {
+        unsupported("Floating point")
+    }
+    fn serialize_f64(self, _v: f64) -> CheckResult {
+        unsupported("Floating point")
+    }
+    fn serialize_char(self, _v: char) -> CheckResult {
+        unsupported("Char")
+    }
+    fn serialize_str(self, _v: &str) -> CheckResult {
+        Ok(())
+    }
+    fn serialize_bytes(self, _v: &[u8]) -> CheckResult {
+        Ok(())
+    }
+    fn serialize_none(self) -> CheckResult {
+        Ok(())
+    }
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> CheckResult {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> CheckResult {
+        Ok(())
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> CheckResult {
+        Ok(())
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> CheckResult {
+        Ok(())
+    }
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> CheckResult {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> CheckResult {
+        value.serialize(self)
+    }
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self, CheckError> {
+        Ok(self)
+    }
+    fn serialize_tuple(self, _len: usize) -> Result<Self, CheckError> {
+        Ok(self)
+    }
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self, CheckError> {
+        Ok(self)
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, CheckError> {
+        Ok(self)
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self, CheckError> {
+        Ok(self)
+    }
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, CheckError> {
+        Ok(self)
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, CheckError> {
+        Ok(self)
+    }
+    fn collect_str<T: fmt::Display + ?Sized>(self, _value: &T) -> CheckResult {
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for Checker {
+    type Ok = ();
+    type Error = CheckError;
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> CheckResult {
+        value.serialize(Checker)
+    }
+    fn end(self) -> CheckResult {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for Checker {
+    type Ok = ();
+    type Error = CheckError;
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> CheckResult {
+        value.serialize(Checker)
+    }
+    fn end(self) -> CheckResult {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for Checker {
+    type Ok = ();
+    type Error = CheckError;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> CheckResult {
+        value.serialize(Checker)
+    }
+    fn end(self) -> CheckResult {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for Checker {
+    type Ok = ();
+    type Error = CheckError;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> CheckResult {
+        value.serialize(Checker)
+    }
+    fn end(self) -> CheckResult {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for Checker {
+    type Ok = ();
+    type Error = CheckError;
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> CheckResult {
+        key.serialize(Checker)
+    }
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> CheckResult {
+        value.serialize(Checker)
+    }
+    fn end(self) -> CheckResult {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for Checker {
+    type Ok = ();
+    type Error = CheckError;
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> CheckResult {
+        value.serialize(Checker)
+    }
+    fn end(self) -> CheckResult {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for Checker {
+    type Ok = ();
+    type Error = CheckError;
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> CheckResult {
+        value.serialize(Checker)
+    }
+    fn end(self) -> CheckResult {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_keys_works() {
+        assert_eq!(sort_keys(br#"{}"#).unwrap(), br#"{}"#);
+        assert_eq!(sort_keys(br#"[]"#).unwrap(), br#"[]"#);
+        assert_eq!(
+            sort_keys(br#"{"b":1,"a":{"d":[{"y":null,"x":"}"}],"c":true}}"#).unwrap(),
+            br#"{"a":{"c":true,"d":[{"x":"}","y":null}]},"b":1}"#
+        );
+        // escaped keys are compared by their decoded value
+        assert_eq!(
+            sort_keys(br#"{"b":1,"a":2,"\"":3}"#).unwrap(),
+            br#"{"\"":3,"a":2,"b":1}"#
+        );
+    }
+
+    #[test]
+    fn sort_keys_rejects_duplicate_keys() {
+        let err = sort_keys(br#"{"a":1,"b":2,"a":3}"#).unwrap_err();
+        assert_eq!(err, "Duplicate key \"a\"");
+    }
+
+    #[test]
+    fn check_representable_rejects_floats() {
+        check_representable(&(1u64, "foo", Some(-3i32))).unwrap();
+        let err = check_representable(&(1u64, 1.5f64)).unwrap_err();
+        assert_eq!(
+            err,
+            "Floating point values have no canonical JSON representation"
+        );
+        check_representable(&[f32::NAN]).unwrap_err();
+    }
+}
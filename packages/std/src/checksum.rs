@@ -5,7 +5,7 @@ use serde::{de, ser, Deserialize, Deserializer, Serialize};
 use sha2::{Digest, Sha256};
 use thiserror::Error;
 
-use crate::{StdError, StdResult};
+use crate::{HexBinary, StdError, StdResult};
 
 /// A SHA-256 checksum of a Wasm blob, used to identify a Wasm code.
 /// This must remain stable since this checksum is stored in the blockchain state.
@@ -121,12 +121,26 @@ impl TryFrom<&[u8]> for Checksum {
     }
 }
 
+impl TryFrom<HexBinary> for Checksum {
+    type Error = ChecksumError;
+
+    fn try_from(value: HexBinary) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_slice())
+    }
+}
+
 impl From<Checksum> for Vec<u8> {
     fn from(original: Checksum) -> Vec<u8> {
         original.0.into()
     }
 }
 
+impl From<Checksum> for HexBinary {
+    fn from(original: Checksum) -> HexBinary {
+        HexBinary::from(original.0.to_vec())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,6 +206,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn try_from_hex_binary_works() {
+        let checksum = Checksum::generate(b"hij");
+        let hex_binary = HexBinary::from(checksum);
+        assert_eq!(hex_binary.to_hex(), checksum.to_hex());
+        assert_eq!(Checksum::try_from(hex_binary).unwrap(), checksum);
+
+        let too_short = HexBinary::from(vec![0u8; 31]);
+        assert!(matches!(Checksum::try_from(too_short), Err(ChecksumError)));
+    }
+
     #[test]
     fn into_vec_works() {
         let checksum = Checksum::generate(&[12u8; 17]);
@@ -1,4 +1,8 @@
 use alloc::collections::BTreeMap;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
 use core::fmt;
 use core::str::FromStr;
 
@@ -314,6 +318,7 @@ impl<'a> ExactSizeIterator for CoinsIter<'a> {
 mod tests {
     use super::*;
     use crate::coin;
+    use crate::StdErrorKind;
 
     /// Sort a Vec<Coin> by denom alphabetically
     fn sort_by_denom(vec: &mut [Coin]) {
@@ -454,11 +459,11 @@ mod tests {
 
         // sub more than available
         let err = coins.sub(coin(12346, "uatom")).unwrap_err();
-        assert!(matches!(err, StdError::Overflow { .. }));
+        assert_eq!(err.kind(), StdErrorKind::Overflow);
 
         // sub non-existent denom
         let err = coins.sub(coin(12345, "uusd")).unwrap_err();
-        assert!(matches!(err, StdError::Overflow { .. }));
+        assert_eq!(err.kind(), StdErrorKind::Overflow);
 
         // partial sub
         coins.sub(coin(1, "uatom")).unwrap();
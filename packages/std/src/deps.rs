@@ -93,6 +93,67 @@ impl<'a, C: CustomQuery> Deps<'a, C> {
     }
 }
 
+/// The host environment a contract runs in: storage, API and querier.
+///
+/// In a Wasm build those are provided by the VM through the `ExternalStorage`,
+/// `ExternalApi` and `ExternalQuerier` imports. Off-chain simulation engines that embed
+/// contract logic natively implement this trait instead and call the contract's entry point
+/// functions directly with the `Deps`/`DepsMut` created from it. Such contracts usually
+/// disable their Wasm exports with a `library` feature of their own, i.e.
+/// `#[cfg_attr(not(feature = "library"), entry_point)]`.
+///
+/// This trait is object safe. `Deps` and `DepsMut` are created from a `&dyn HostApi`
+/// or `&mut dyn HostApi` using [`deps`](#method.deps) and [`deps_mut`](#method.deps_mut).
+pub trait HostApi {
+    fn storage(&self) -> &dyn Storage;
+
+    fn api(&self) -> &dyn Api;
+
+    fn querier(&self) -> &dyn Querier;
+
+    /// Returns all parts at once, with mutable access to the storage.
+    fn parts_mut(&mut self) -> (&mut dyn Storage, &dyn Api, &dyn Querier);
+}
+
+impl dyn HostApi + '_ {
+    /// Creates read-only dependencies for calling a query style entry point.
+    pub fn deps<C: CustomQuery>(&self) -> Deps<'_, C> {
+        Deps {
+            storage: self.storage(),
+            api: self.api(),
+            querier: QuerierWrapper::new(self.querier()),
+        }
+    }
+
+    /// Creates mutable dependencies for calling an execute style entry point.
+    pub fn deps_mut<C: CustomQuery>(&mut self) -> DepsMut<'_, C> {
+        let (storage, api, querier) = self.parts_mut();
+        DepsMut {
+            storage,
+            api,
+            querier: QuerierWrapper::new(querier),
+        }
+    }
+}
+
+impl<S: Storage, A: Api, Q: Querier, C: CustomQuery> HostApi for OwnedDeps<S, A, Q, C> {
+    fn storage(&self) -> &dyn Storage {
+        &self.storage
+    }
+
+    fn api(&self) -> &dyn Api {
+        &self.api
+    }
+
+    fn querier(&self) -> &dyn Querier {
+        &self.querier
+    }
+
+    fn parts_mut(&mut self) -> (&mut dyn Storage, &dyn Api, &dyn Querier) {
+        (&mut self.storage, &self.api, &self.querier)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,4 +227,27 @@ mod tests {
         let deps: Deps<MyQuery> = owned.as_ref();
         let _: Deps<Empty> = deps.into_empty();
     }
+
+    #[test]
+    fn host_api_works() {
+        let mut owned = mock_dependencies();
+        let host: &mut dyn HostApi = &mut owned;
+        execute(host.deps_mut());
+        query(host.deps());
+
+        host.deps_mut::<Empty>().storage.set(b"foo", b"bar");
+        assert_eq!(
+            host.deps::<Empty>().storage.get(b"foo"),
+            Some(b"bar".to_vec())
+        );
+
+        // the host interfaces can be used as trait objects too
+        let mut storage = MockStorage::new();
+        let storage: &mut dyn Storage = &mut storage;
+        storage.set(b"foo", b"baz");
+        let api: Box<dyn Api> = Box::new(MockApi::default());
+        api.debug("hello");
+        let querier: Box<dyn Querier> = Box::new(MockQuerier::<Empty>::new(&[]));
+        let _ = querier.raw_query(b"{}");
+    }
 }
@@ -0,0 +1,78 @@
+//! Compile time checks for the code generated by the `#[entry_point]` macro.
+//!
+//! For every entry point the macro emits two type aliases in the module of the entry point,
+//! `__cw_custom_query_<name>` and `__cw_custom_msg_<name>`, which shadow the [`Undeclared`]
+//! defaults from [`undeclared`]. A function that is never called then ensures that the custom
+//! types of all entry points of the module agree with each other.
+//!
+//! This is not part of the public API and may change at any time.
+
+use core::marker::PhantomData;
+
+use crate::query::CustomQuery;
+use crate::results::CustomMsg;
+
+/// The custom type `T` declared in the signature of an entry point
+pub struct Declared<T>(PhantomData<T>);
+
+/// Used for entry points that do not exist, that use `Empty`
+/// or whose custom type cannot be determined from the signature
+pub struct Undeclared;
+
+/// Implemented for all pairs of entry points that can be used in the same contract
+pub trait AllEntryPointsMustUseTheSameCustomQuery<Other> {}
+
+impl<T> AllEntryPointsMustUseTheSameCustomQuery<Declared<T>> for Declared<T> {}
+impl<T> AllEntryPointsMustUseTheSameCustomQuery<Undeclared> for Declared<T> {}
+impl<T> AllEntryPointsMustUseTheSameCustomQuery<Declared<T>> for Undeclared {}
+impl AllEntryPointsMustUseTheSameCustomQuery<Undeclared> for Undeclared {}
+
+/// Implemented for all pairs of entry points that can be used in the same contract
+pub trait AllEntryPointsMustUseTheSameCustomMsg<Other> {}
+
+impl<T> AllEntryPointsMustUseTheSameCustomMsg<Declared<T>> for Declared<T> {}
+impl<T> AllEntryPointsMustUseTheSameCustomMsg<Undeclared> for Declared<T> {}
+impl<T> AllEntryPointsMustUseTheSameCustomMsg<Declared<T>> for Undeclared {}
+impl AllEntryPointsMustUseTheSameCustomMsg<Undeclared> for Undeclared {}
+
+pub fn custom_query<Q: CustomQuery>() {}
+
+pub fn custom_msg<C: CustomMsg>() {}
+
+pub fn same_custom_query<A: AllEntryPointsMustUseTheSameCustomQuery<B>, B>() {}
+
+pub fn same_custom_msg<A: AllEntryPointsMustUseTheSameCustomMsg<B>, B>() {}
+
+/// Defaults for all entry points known to the `#[entry_point]` macro.
+/// This module is glob imported by the generated code, such that explicitly
+/// generated aliases take precedence.
+#[allow(non_camel_case_types)]
+pub mod undeclared {
+    use super::Undeclared;
+
+    pub type __cw_custom_query_instantiate = Undeclared;
+    pub type __cw_custom_query_execute = Undeclared;
+    pub type __cw_custom_query_migrate = Undeclared;
+    pub type __cw_custom_query_sudo = Undeclared;
+    pub type __cw_custom_query_reply = Undeclared;
+    pub type __cw_custom_query_query = Undeclared;
+    pub type __cw_custom_query_ibc_channel_open = Undeclared;
+    pub type __cw_custom_query_ibc_channel_connect = Undeclared;
+    pub type __cw_custom_query_ibc_channel_close = Undeclared;
+    pub type __cw_custom_query_ibc_packet_receive = Undeclared;
+    pub type __cw_custom_query_ibc_packet_ack = Undeclared;
+    pub type __cw_custom_query_ibc_packet_timeout = Undeclared;
+
+    pub type __cw_custom_msg_instantiate = Undeclared;
+    pub type __cw_custom_msg_execute = Undeclared;
+    pub type __cw_custom_msg_migrate = Undeclared;
+    pub type __cw_custom_msg_sudo = Undeclared;
+    pub type __cw_custom_msg_reply = Undeclared;
+    pub type __cw_custom_msg_query = Undeclared;
+    pub type __cw_custom_msg_ibc_channel_open = Undeclared;
+    pub type __cw_custom_msg_ibc_channel_connect = Undeclared;
+    pub type __cw_custom_msg_ibc_channel_close = Undeclared;
+    pub type __cw_custom_msg_ibc_packet_receive = Undeclared;
+    pub type __cw_custom_msg_ibc_packet_ack = Undeclared;
+    pub type __cw_custom_msg_ibc_packet_timeout = Undeclared;
+}
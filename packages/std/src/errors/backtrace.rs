@@ -1,3 +1,4 @@
+use alloc::boxed::Box;
 use core::fmt::{Debug, Display, Formatter, Result};
 
 /// This wraps an actual backtrace to achieve two things:
@@ -6,13 +7,32 @@ use core::fmt::{Debug, Display, Formatter, Result};
 pub struct BT(Box<dyn Printable>);
 
 impl BT {
+    /// Captures a backtrace if the `backtraces` feature is enabled. Otherwise this
+    /// returns a stub, which does not allocate.
     #[track_caller]
     pub fn capture() -> Self {
-        // in case of no_std, we can fill with a stub here
-        #[cfg(target_arch = "wasm32")]
-        return BT(Box::new(std::backtrace::Backtrace::disabled()));
-        #[cfg(not(target_arch = "wasm32"))]
+        #[cfg(all(feature = "backtraces", not(target_arch = "wasm32")))]
         return BT(Box::new(std::backtrace::Backtrace::capture()));
+        #[cfg(not(all(feature = "backtraces", not(target_arch = "wasm32"))))]
+        return BT(Box::new(Stub));
+    }
+}
+
+/// Used when backtraces are disabled or not available, e.g. in `no_std` environments
+#[cfg(not(all(feature = "backtraces", not(target_arch = "wasm32"))))]
+struct Stub;
+
+#[cfg(not(all(feature = "backtraces", not(target_arch = "wasm32"))))]
+impl Debug for Stub {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str("<disabled>")
+    }
+}
+
+#[cfg(not(all(feature = "backtraces", not(target_arch = "wasm32"))))]
+impl Display for Stub {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str("disabled backtrace")
     }
 }
 
@@ -31,24 +51,6 @@ impl Display for BT {
     }
 }
 
-/// This macro implements `From` for a given error type to a given error type where
-/// the target error has a `backtrace` field.
-/// This is meant as a replacement for `thiserror`'s `#[from]` attribute, which does not
-/// work with our custom backtrace wrapper.
-macro_rules! impl_from_err {
-    ($from:ty, $to:ty, $map:path) => {
-        impl From<$from> for $to {
-            fn from(err: $from) -> Self {
-                $map {
-                    source: err,
-                    backtrace: $crate::errors::backtrace::BT::capture(),
-                }
-            }
-        }
-    };
-}
-pub(crate) use impl_from_err;
-
 #[cfg(test)]
 mod tests {
     use super::*;
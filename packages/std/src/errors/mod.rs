@@ -4,13 +4,13 @@ mod std_error;
 mod system_error;
 mod verification_error;
 
-pub(crate) use backtrace::{impl_from_err, BT};
+pub(crate) use backtrace::BT;
 pub use recover_pubkey_error::RecoverPubkeyError;
 pub use std_error::{
     CheckedFromRatioError, CheckedMultiplyFractionError, CheckedMultiplyRatioError,
     CoinFromStrError, CoinsError, ConversionOverflowError, DivideByZeroError, DivisionError,
-    OverflowError, OverflowOperation, RoundDownOverflowError, RoundUpOverflowError, StdError,
-    StdResult,
+    IbcTimeoutError, OverflowError, OverflowOperation, RoundDownOverflowError,
+    RoundUpOverflowError, RoundingError, StdError, StdErrorKind, StdErrorPayload, StdResult,
 };
 pub use system_error::SystemError;
 pub use verification_error::VerificationError;
@@ -1,5 +1,5 @@
 use core::fmt::Debug;
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
 use cosmwasm_crypto::CryptoError;
 
 use super::BT;
@@ -55,7 +55,7 @@ impl PartialEq<RecoverPubkeyError> for RecoverPubkeyError {
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
 impl From<CryptoError> for RecoverPubkeyError {
     fn from(original: CryptoError) -> Self {
         match original {
@@ -1,362 +1,328 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+};
 use core::fmt;
 
-use super::{impl_from_err, BT};
+use super::BT;
 use thiserror::Error;
 
 use crate::errors::{RecoverPubkeyError, VerificationError};
+use crate::Timestamp;
 
 /// Structured error type for init, execute and query.
 ///
-/// This can be serialized and passed over the Wasm/VM boundary, which allows us to use structured
-/// error types in e.g. integration tests. In that process backtraces are stripped off.
+/// An error consists of a [`StdErrorKind`], which is cheap to copy and match on, and a
+/// [`StdErrorPayload`] holding the details of the error. When the `backtraces` feature is
+/// enabled, a backtrace is captured on creation and shown in the `Debug` output.
 ///
 /// The prefix "Std" means "the standard error within the standard library". This is not the only
 /// result/error type in cosmwasm-std.
 ///
-/// When new cases are added, they should describe the problem rather than what was attempted (e.g.
-/// InvalidBase64 is preferred over Base64DecodingErr). In the long run this allows us to get rid of
-/// the duplication in "StdError::FooErr".
+/// When new kinds are added, they should describe the problem rather than what was attempted (e.g.
+/// InvalidBase64 is preferred over Base64DecodingErr).
 ///
 /// Checklist for adding a new error:
-/// - Add enum case
-/// - Add creator function in std_error_helpers.rs
-#[derive(Error, Debug)]
-pub enum StdError {
-    #[error("Verification error: {source}")]
-    VerificationErr {
-        source: VerificationError,
-        backtrace: BT,
-    },
-    #[error("Recover pubkey error: {source}")]
-    RecoverPubkeyErr {
-        source: RecoverPubkeyError,
-        backtrace: BT,
-    },
+/// - Add a case to [`StdErrorKind`] and, if needed, to [`StdErrorPayload`]
+/// - Add a creator function and the message in the `Display` implementation
+pub struct StdError {
+    kind: StdErrorKind,
+    payload: StdErrorPayload,
+    backtrace: BT,
+}
+
+/// The kind of a [`StdError`], without its payload.
+///
+/// This is cheap to obtain through [`StdError::kind`] and allows matching on the
+/// type of an error without comparing formatted messages.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum StdErrorKind {
+    VerificationErr,
+    RecoverPubkeyErr,
     /// Whenever there is no specific error type available
-    #[error("Generic error: {msg}")]
-    GenericErr { msg: String, backtrace: BT },
-    #[error("Invalid Base64 string: {msg}")]
-    InvalidBase64 { msg: String, backtrace: BT },
-    #[error("Invalid data size: expected={expected} actual={actual}")]
-    InvalidDataSize {
-        expected: u64,
-        actual: u64,
-        backtrace: BT,
-    },
-    #[error("Invalid hex string: {msg}")]
-    InvalidHex { msg: String, backtrace: BT },
+    GenericErr,
+    InvalidBase64,
+    InvalidDataSize,
+    InvalidHex,
     /// Whenever UTF-8 bytes cannot be decoded into a unicode string, e.g. in String::from_utf8 or str::from_utf8.
-    #[error("Cannot decode UTF8 bytes into string: {msg}")]
-    InvalidUtf8 { msg: String, backtrace: BT },
-    #[error("{kind} not found")]
-    NotFound { kind: String, backtrace: BT },
-    #[error("Error parsing into type {target_type}: {msg}")]
-    ParseErr {
-        /// the target type that was attempted
-        target_type: String,
-        msg: String,
-        backtrace: BT,
-    },
-    #[error("Error serializing type {source_type}: {msg}")]
-    SerializeErr {
-        /// the source type that was attempted
-        source_type: String,
+    InvalidUtf8,
+    NotFound,
+    ParseErr,
+    SerializeErr,
+    Overflow,
+    DivideByZero,
+    ConversionOverflow,
+}
+
+/// The details of a [`StdError`]. Which payload is used depends on the [`StdErrorKind`].
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum StdErrorPayload {
+    /// A message describing the problem. Used by [`StdErrorKind::GenericErr`],
+    /// [`StdErrorKind::InvalidBase64`], [`StdErrorKind::InvalidHex`] and [`StdErrorKind::InvalidUtf8`].
+    Message(String),
+    /// The name of the type that was not found. Used by [`StdErrorKind::NotFound`].
+    TypeName(String),
+    /// The type that was (de)serialized and a message describing the problem.
+    /// Used by [`StdErrorKind::ParseErr`] and [`StdErrorKind::SerializeErr`].
+    TypeAndMessage {
+        type_name: String,
         msg: String,
-        backtrace: BT,
-    },
-    #[error("Overflow: {source}")]
-    Overflow {
-        source: OverflowError,
-        backtrace: BT,
     },
-    #[error("Divide by zero: {source}")]
-    DivideByZero {
-        source: DivideByZeroError,
-        backtrace: BT,
-    },
-    #[error("Conversion error: ")]
-    ConversionOverflow {
-        source: ConversionOverflowError,
-        backtrace: BT,
+    /// Used by [`StdErrorKind::InvalidDataSize`].
+    DataSize {
+        expected: u64,
+        actual: u64,
     },
+    Verification(VerificationError),
+    RecoverPubkey(RecoverPubkeyError),
+    Overflow(OverflowError),
+    DivideByZero(DivideByZeroError),
+    ConversionOverflow(ConversionOverflowError),
 }
 
-impl_from_err!(
-    ConversionOverflowError,
-    StdError,
-    StdError::ConversionOverflow
-);
-
 impl StdError {
-    pub fn verification_err(source: VerificationError) -> Self {
-        StdError::VerificationErr {
-            source,
+    #[track_caller]
+    fn new(kind: StdErrorKind, payload: StdErrorPayload) -> Self {
+        StdError {
+            kind,
+            payload,
             backtrace: BT::capture(),
         }
     }
 
+    #[track_caller]
+    pub fn verification_err(source: VerificationError) -> Self {
+        Self::new(
+            StdErrorKind::VerificationErr,
+            StdErrorPayload::Verification(source),
+        )
+    }
+
+    #[track_caller]
     pub fn recover_pubkey_err(source: RecoverPubkeyError) -> Self {
-        StdError::RecoverPubkeyErr {
-            source,
-            backtrace: BT::capture(),
-        }
+        Self::new(
+            StdErrorKind::RecoverPubkeyErr,
+            StdErrorPayload::RecoverPubkey(source),
+        )
     }
 
+    #[track_caller]
     pub fn generic_err(msg: impl Into<String>) -> Self {
-        StdError::GenericErr {
-            msg: msg.into(),
-            backtrace: BT::capture(),
-        }
+        Self::new(
+            StdErrorKind::GenericErr,
+            StdErrorPayload::Message(msg.into()),
+        )
     }
 
+    #[track_caller]
     pub fn invalid_base64(msg: impl ToString) -> Self {
-        StdError::InvalidBase64 {
-            msg: msg.to_string(),
-            backtrace: BT::capture(),
-        }
+        Self::new(
+            StdErrorKind::InvalidBase64,
+            StdErrorPayload::Message(msg.to_string()),
+        )
     }
 
+    #[track_caller]
     pub fn invalid_data_size(expected: usize, actual: usize) -> Self {
-        StdError::InvalidDataSize {
-            // Cast is safe because usize is 32 or 64 bit large in all environments we support
-            expected: expected as u64,
-            actual: actual as u64,
-            backtrace: BT::capture(),
-        }
+        Self::new(
+            StdErrorKind::InvalidDataSize,
+            StdErrorPayload::DataSize {
+                // Cast is safe because usize is 32 or 64 bit large in all environments we support
+                expected: expected as u64,
+                actual: actual as u64,
+            },
+        )
     }
 
+    #[track_caller]
     pub fn invalid_hex(msg: impl ToString) -> Self {
-        StdError::InvalidHex {
-            msg: msg.to_string(),
-            backtrace: BT::capture(),
-        }
+        Self::new(
+            StdErrorKind::InvalidHex,
+            StdErrorPayload::Message(msg.to_string()),
+        )
     }
 
+    #[track_caller]
     pub fn invalid_utf8(msg: impl ToString) -> Self {
-        StdError::InvalidUtf8 {
-            msg: msg.to_string(),
-            backtrace: BT::capture(),
-        }
+        Self::new(
+            StdErrorKind::InvalidUtf8,
+            StdErrorPayload::Message(msg.to_string()),
+        )
     }
 
+    #[track_caller]
     pub fn not_found(kind: impl Into<String>) -> Self {
-        StdError::NotFound {
-            kind: kind.into(),
-            backtrace: BT::capture(),
-        }
+        Self::new(
+            StdErrorKind::NotFound,
+            StdErrorPayload::TypeName(kind.into()),
+        )
     }
 
+    #[track_caller]
     pub fn parse_err(target: impl Into<String>, msg: impl ToString) -> Self {
-        StdError::ParseErr {
-            target_type: target.into(),
-            msg: msg.to_string(),
-            backtrace: BT::capture(),
-        }
+        Self::new(
+            StdErrorKind::ParseErr,
+            StdErrorPayload::TypeAndMessage {
+                type_name: target.into(),
+                msg: msg.to_string(),
+            },
+        )
     }
 
+    #[track_caller]
     pub fn serialize_err(source: impl Into<String>, msg: impl ToString) -> Self {
-        StdError::SerializeErr {
-            source_type: source.into(),
-            msg: msg.to_string(),
-            backtrace: BT::capture(),
-        }
+        Self::new(
+            StdErrorKind::SerializeErr,
+            StdErrorPayload::TypeAndMessage {
+                type_name: source.into(),
+                msg: msg.to_string(),
+            },
+        )
     }
 
+    #[track_caller]
     pub fn overflow(source: OverflowError) -> Self {
-        StdError::Overflow {
-            source,
-            backtrace: BT::capture(),
-        }
+        Self::new(StdErrorKind::Overflow, StdErrorPayload::Overflow(source))
     }
 
+    #[track_caller]
     pub fn divide_by_zero(source: DivideByZeroError) -> Self {
-        StdError::DivideByZero {
-            source,
-            backtrace: BT::capture(),
-        }
+        Self::new(
+            StdErrorKind::DivideByZero,
+            StdErrorPayload::DivideByZero(source),
+        )
+    }
+
+    #[track_caller]
+    pub fn conversion_overflow(source: ConversionOverflowError) -> Self {
+        Self::new(
+            StdErrorKind::ConversionOverflow,
+            StdErrorPayload::ConversionOverflow(source),
+        )
+    }
+
+    /// Returns the kind of this error.
+    pub fn kind(&self) -> StdErrorKind {
+        self.kind
+    }
+
+    /// Returns the details of this error.
+    pub fn payload(&self) -> &StdErrorPayload {
+        &self.payload
+    }
+
+    /// Returns `true` if this is a [`StdErrorKind::NotFound`], e.g. because a storage
+    /// item does not exist.
+    pub fn is_not_found(&self) -> bool {
+        self.kind == StdErrorKind::NotFound
+    }
+
+    /// Returns `true` if this is a [`StdErrorKind::GenericErr`].
+    pub fn is_generic_err(&self) -> bool {
+        self.kind == StdErrorKind::GenericErr
+    }
+
+    /// Returns `true` if this is a [`StdErrorKind::ParseErr`], i.e. deserialization failed.
+    pub fn is_parse_err(&self) -> bool {
+        self.kind == StdErrorKind::ParseErr
+    }
+
+    /// Returns `true` if this is a [`StdErrorKind::SerializeErr`].
+    pub fn is_serialize_err(&self) -> bool {
+        self.kind == StdErrorKind::SerializeErr
+    }
+
+    /// Returns `true` if this is the result of a failed arithmetic operation, i.e. an
+    /// [`StdErrorKind::Overflow`], [`StdErrorKind::DivideByZero`] or
+    /// [`StdErrorKind::ConversionOverflow`].
+    pub fn is_math_err(&self) -> bool {
+        matches!(
+            self.kind,
+            StdErrorKind::Overflow | StdErrorKind::DivideByZero | StdErrorKind::ConversionOverflow
+        )
     }
 }
 
-impl PartialEq<StdError> for StdError {
-    fn eq(&self, rhs: &StdError) -> bool {
-        match self {
-            StdError::VerificationErr {
-                source,
-                backtrace: _,
-            } => {
-                if let StdError::VerificationErr {
-                    source: rhs_source,
-                    backtrace: _,
-                } = rhs
-                {
-                    source == rhs_source
-                } else {
-                    false
-                }
-            }
-            StdError::RecoverPubkeyErr {
-                source,
-                backtrace: _,
-            } => {
-                if let StdError::RecoverPubkeyErr {
-                    source: rhs_source,
-                    backtrace: _,
-                } = rhs
-                {
-                    source == rhs_source
-                } else {
-                    false
-                }
-            }
-            StdError::GenericErr { msg, backtrace: _ } => {
-                if let StdError::GenericErr {
-                    msg: rhs_msg,
-                    backtrace: _,
-                } = rhs
-                {
-                    msg == rhs_msg
-                } else {
-                    false
-                }
-            }
-            StdError::InvalidBase64 { msg, backtrace: _ } => {
-                if let StdError::InvalidBase64 {
-                    msg: rhs_msg,
-                    backtrace: _,
-                } = rhs
-                {
-                    msg == rhs_msg
-                } else {
-                    false
-                }
-            }
-            StdError::InvalidDataSize {
-                expected,
-                actual,
-                backtrace: _,
-            } => {
-                if let StdError::InvalidDataSize {
-                    expected: rhs_expected,
-                    actual: rhs_actual,
-                    backtrace: _,
-                } = rhs
-                {
-                    expected == rhs_expected && actual == rhs_actual
-                } else {
-                    false
-                }
+impl fmt::Debug for StdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StdError")
+            .field("kind", &self.kind)
+            .field("payload", &self.payload)
+            .field("backtrace", &self.backtrace)
+            .finish()
+    }
+}
+
+impl fmt::Display for StdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use StdErrorPayload::*;
+
+        match (self.kind, &self.payload) {
+            (StdErrorKind::VerificationErr, Verification(source)) => {
+                write!(f, "Verification error: {source}")
             }
-            StdError::InvalidHex { msg, backtrace: _ } => {
-                if let StdError::InvalidHex {
-                    msg: rhs_msg,
-                    backtrace: _,
-                } = rhs
-                {
-                    msg == rhs_msg
-                } else {
-                    false
-                }
+            (StdErrorKind::RecoverPubkeyErr, RecoverPubkey(source)) => {
+                write!(f, "Recover pubkey error: {source}")
             }
-            StdError::InvalidUtf8 { msg, backtrace: _ } => {
-                if let StdError::InvalidUtf8 {
-                    msg: rhs_msg,
-                    backtrace: _,
-                } = rhs
-                {
-                    msg == rhs_msg
-                } else {
-                    false
-                }
+            (StdErrorKind::GenericErr, Message(msg)) => write!(f, "Generic error: {msg}"),
+            (StdErrorKind::InvalidBase64, Message(msg)) => {
+                write!(f, "Invalid Base64 string: {msg}")
             }
-            StdError::NotFound { kind, backtrace: _ } => {
-                if let StdError::NotFound {
-                    kind: rhs_kind,
-                    backtrace: _,
-                } = rhs
-                {
-                    kind == rhs_kind
-                } else {
-                    false
-                }
+            (StdErrorKind::InvalidDataSize, DataSize { expected, actual }) => {
+                write!(f, "Invalid data size: expected={expected} actual={actual}")
             }
-            StdError::ParseErr {
-                target_type,
-                msg,
-                backtrace: _,
-            } => {
-                if let StdError::ParseErr {
-                    target_type: rhs_target_type,
-                    msg: rhs_msg,
-                    backtrace: _,
-                } = rhs
-                {
-                    target_type == rhs_target_type && msg == rhs_msg
-                } else {
-                    false
-                }
+            (StdErrorKind::InvalidHex, Message(msg)) => write!(f, "Invalid hex string: {msg}"),
+            (StdErrorKind::InvalidUtf8, Message(msg)) => {
+                write!(f, "Cannot decode UTF8 bytes into string: {msg}")
             }
-            StdError::SerializeErr {
-                source_type,
-                msg,
-                backtrace: _,
-            } => {
-                if let StdError::SerializeErr {
-                    source_type: rhs_source_type,
-                    msg: rhs_msg,
-                    backtrace: _,
-                } = rhs
-                {
-                    source_type == rhs_source_type && msg == rhs_msg
-                } else {
-                    false
-                }
+            (StdErrorKind::NotFound, TypeName(kind)) => write!(f, "{kind} not found"),
+            (StdErrorKind::ParseErr, TypeAndMessage { type_name, msg }) => {
+                write!(f, "Error parsing into type {type_name}: {msg}")
             }
-            StdError::Overflow {
-                source,
-                backtrace: _,
-            } => {
-                if let StdError::Overflow {
-                    source: rhs_source,
-                    backtrace: _,
-                } = rhs
-                {
-                    source == rhs_source
-                } else {
-                    false
-                }
+            (StdErrorKind::SerializeErr, TypeAndMessage { type_name, msg }) => {
+                write!(f, "Error serializing type {type_name}: {msg}")
             }
-            StdError::DivideByZero {
-                source,
-                backtrace: _,
-            } => {
-                if let StdError::DivideByZero {
-                    source: rhs_source,
-                    backtrace: _,
-                } = rhs
-                {
-                    source == rhs_source
-                } else {
-                    false
-                }
+            (StdErrorKind::Overflow, Overflow(source)) => write!(f, "Overflow: {source}"),
+            (StdErrorKind::DivideByZero, DivideByZero(source)) => {
+                write!(f, "Divide by zero: {source}")
             }
-            StdError::ConversionOverflow {
-                source,
-                backtrace: _,
-            } => {
-                if let StdError::ConversionOverflow {
-                    source: rhs_source,
-                    backtrace: _,
-                } = rhs
-                {
-                    source == rhs_source
-                } else {
-                    false
-                }
+            (StdErrorKind::ConversionOverflow, ConversionOverflow(source)) => {
+                write!(f, "Conversion error: {source}")
             }
+            // The constructors ensure that kind and payload match
+            (kind, payload) => write!(f, "{kind:?}: {payload:?}"),
         }
     }
 }
 
+// `core::error::Error` requires Rust 1.81, which is only needed for `no_std` builds
+#[cfg(not(feature = "std"))]
+use core::error::Error as ErrorTrait;
+#[cfg(feature = "std")]
+use std::error::Error as ErrorTrait;
+
+impl ErrorTrait for StdError {
+    fn source(&self) -> Option<&(dyn ErrorTrait + 'static)> {
+        match &self.payload {
+            StdErrorPayload::Verification(source) => Some(source),
+            StdErrorPayload::RecoverPubkey(source) => Some(source),
+            StdErrorPayload::Overflow(source) => Some(source),
+            StdErrorPayload::DivideByZero(source) => Some(source),
+            StdErrorPayload::ConversionOverflow(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq<StdError> for StdError {
+    /// Compares kind and payload. The backtrace is ignored.
+    fn eq(&self, rhs: &StdError) -> bool {
+        self.kind == rhs.kind && self.payload == rhs.payload
+    }
+}
+
 impl From<core::str::Utf8Error> for StdError {
     fn from(source: core::str::Utf8Error) -> Self {
         Self::invalid_utf8(source)
@@ -393,6 +359,12 @@ impl From<DivideByZeroError> for StdError {
     }
 }
 
+impl From<ConversionOverflowError> for StdError {
+    fn from(source: ConversionOverflowError) -> Self {
+        Self::conversion_overflow(source)
+    }
+}
+
 /// `StdResult` is typically used in scenarios where a function within the contract needs to return a result or indicate an error
 /// that is specific to the contract's internal operations. Since the error component of `StdResult` is not meant for JSON serialization,
 /// it should be used with caution when designing functions that interact with external systems or data formats.
@@ -471,6 +443,7 @@ pub enum DivisionError {
 /// Errors that occur when multiplying a value by a [Fraction](crate::Fraction) in a checked manner.
 /// Ensures overflow safety in the operation.
 #[derive(Error, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum CheckedMultiplyFractionError {
     #[error("{0}")]
     DivideByZero(#[from] DivideByZeroError),
@@ -480,6 +453,9 @@ pub enum CheckedMultiplyFractionError {
 
     #[error("{0}")]
     Overflow(#[from] OverflowError),
+
+    #[error("{0}")]
+    Rounding(#[from] RoundingError),
 }
 /// Represents errors during safe multiplication of a number by a fraction.
 /// Ensures accuracy and overflow prevention in calculations.
@@ -509,6 +485,16 @@ pub struct RoundUpOverflowError;
 #[derive(Error, Debug, PartialEq, Eq)]
 #[error("Round down operation failed because of overflow")]
 pub struct RoundDownOverflowError;
+
+/// Errors that occur when rounding the result of an operation, e.g. in
+/// [`Uint128::checked_mul_ceil`](crate::Uint128::checked_mul_ceil).
+/// This allows distinguishing a failed rounding step from other overflows.
+#[derive(Error, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RoundingError {
+    #[error("{0}")]
+    RoundUpOverflow(#[from] RoundUpOverflowError),
+}
 /// General errors related to operations with coins,
 /// such as invalid amounts or unsupported denominations.
 #[derive(Error, Debug, PartialEq, Eq)]
@@ -546,6 +532,24 @@ impl From<CoinFromStrError> for StdError {
     }
 }
 
+/// Errors returned by [`IbcTimeout::validate`](crate::IbcTimeout::validate).
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum IbcTimeoutError {
+    #[error("Neither a block nor a timestamp is set")]
+    NoTimeout,
+    #[error("Timeout timestamp {timestamp} is before the earliest allowed timeout {earliest}")]
+    TooSoon {
+        timestamp: Timestamp,
+        earliest: Timestamp,
+    },
+}
+
+impl From<IbcTimeoutError> for StdError {
+    fn from(value: IbcTimeoutError) -> Self {
+        Self::generic_err(format!("Invalid IBC timeout: {value}"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -558,189 +562,224 @@ mod tests {
     fn generic_err_owned() {
         let guess = 7;
         let error = StdError::generic_err(format!("{guess} is too low"));
-        match error {
-            StdError::GenericErr { msg, .. } => {
-                assert_eq!(msg, String::from("7 is too low"));
-            }
-            e => panic!("unexpected error, {e:?}"),
-        }
+        assert_eq!(error.kind(), StdErrorKind::GenericErr);
+        assert_eq!(
+            error.payload(),
+            &StdErrorPayload::Message(String::from("7 is too low"))
+        );
     }
 
     // example of reporting static contract errors
     #[test]
     fn generic_err_ref() {
         let error = StdError::generic_err("not implemented");
-        match error {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "not implemented"),
-            e => panic!("unexpected error, {e:?}"),
-        }
+        assert_eq!(error.kind(), StdErrorKind::GenericErr);
+        assert_eq!(
+            error.payload(),
+            &StdErrorPayload::Message("not implemented".to_string())
+        );
     }
 
     #[test]
     fn invalid_base64_works_for_strings() {
         let error = StdError::invalid_base64("my text");
-        match error {
-            StdError::InvalidBase64 { msg, .. } => {
-                assert_eq!(msg, "my text");
-            }
-            _ => panic!("expect different error"),
-        }
+        assert_eq!(error.kind(), StdErrorKind::InvalidBase64);
+        assert_eq!(
+            error.payload(),
+            &StdErrorPayload::Message("my text".to_string())
+        );
     }
 
     #[test]
     fn invalid_base64_works_for_errors() {
         let original = base64::DecodeError::InvalidLength;
         let error = StdError::invalid_base64(original);
-        match error {
-            StdError::InvalidBase64 { msg, .. } => {
-                assert_eq!(msg, "Encoded text cannot have a 6-bit remainder.");
-            }
-            _ => panic!("expect different error"),
-        }
+        assert_eq!(error.kind(), StdErrorKind::InvalidBase64);
+        assert_eq!(
+            error.payload(),
+            &StdErrorPayload::Message("Encoded text cannot have a 6-bit remainder.".to_string())
+        );
     }
 
     #[test]
     fn invalid_data_size_works() {
         let error = StdError::invalid_data_size(31, 14);
-        match error {
-            StdError::InvalidDataSize {
-                expected, actual, ..
-            } => {
-                assert_eq!(expected, 31);
-                assert_eq!(actual, 14);
+        assert_eq!(error.kind(), StdErrorKind::InvalidDataSize);
+        assert_eq!(
+            error.payload(),
+            &StdErrorPayload::DataSize {
+                expected: 31,
+                actual: 14
             }
-            _ => panic!("expect different error"),
-        }
+        );
     }
 
     #[test]
     fn invalid_hex_works_for_strings() {
         let error = StdError::invalid_hex("my text");
-        match error {
-            StdError::InvalidHex { msg, .. } => {
-                assert_eq!(msg, "my text");
-            }
-            _ => panic!("expect different error"),
-        }
+        assert_eq!(error.kind(), StdErrorKind::InvalidHex);
+        assert_eq!(
+            error.payload(),
+            &StdErrorPayload::Message("my text".to_string())
+        );
     }
 
     #[test]
     fn invalid_hex_works_for_errors() {
         let original = hex::FromHexError::OddLength;
         let error = StdError::invalid_hex(original);
-        match error {
-            StdError::InvalidHex { msg, .. } => {
-                assert_eq!(msg, "Odd number of digits");
-            }
-            _ => panic!("expect different error"),
-        }
+        assert_eq!(error.kind(), StdErrorKind::InvalidHex);
+        assert_eq!(
+            error.payload(),
+            &StdErrorPayload::Message("Odd number of digits".to_string())
+        );
     }
 
     #[test]
     fn invalid_utf8_works_for_strings() {
         let error = StdError::invalid_utf8("my text");
-        match error {
-            StdError::InvalidUtf8 { msg, .. } => {
-                assert_eq!(msg, "my text");
-            }
-            _ => panic!("expect different error"),
-        }
+        assert_eq!(error.kind(), StdErrorKind::InvalidUtf8);
+        assert_eq!(
+            error.payload(),
+            &StdErrorPayload::Message("my text".to_string())
+        );
     }
 
     #[test]
     fn invalid_utf8_works_for_errors() {
         let original = String::from_utf8(vec![0x80]).unwrap_err();
         let error = StdError::invalid_utf8(original);
-        match error {
-            StdError::InvalidUtf8 { msg, .. } => {
-                assert_eq!(msg, "invalid utf-8 sequence of 1 bytes from index 0");
-            }
-            _ => panic!("expect different error"),
-        }
+        assert_eq!(error.kind(), StdErrorKind::InvalidUtf8);
+        assert_eq!(
+            error.payload(),
+            &StdErrorPayload::Message("invalid utf-8 sequence of 1 bytes from index 0".to_string())
+        );
     }
 
     #[test]
     fn not_found_works() {
         let error = StdError::not_found("gold");
-        match error {
-            StdError::NotFound { kind, .. } => assert_eq!(kind, "gold"),
-            _ => panic!("expect different error"),
-        }
+        assert_eq!(error.kind(), StdErrorKind::NotFound);
+        assert_eq!(
+            error.payload(),
+            &StdErrorPayload::TypeName("gold".to_string())
+        );
     }
 
     #[test]
     fn parse_err_works() {
         let error = StdError::parse_err("Book", "Missing field: title");
-        match error {
-            StdError::ParseErr {
-                target_type, msg, ..
-            } => {
-                assert_eq!(target_type, "Book");
-                assert_eq!(msg, "Missing field: title");
+        assert_eq!(error.kind(), StdErrorKind::ParseErr);
+        assert_eq!(
+            error.payload(),
+            &StdErrorPayload::TypeAndMessage {
+                type_name: "Book".to_string(),
+                msg: "Missing field: title".to_string()
             }
-            _ => panic!("expect different error"),
-        }
+        );
     }
 
     #[test]
     fn serialize_err_works() {
         let error = StdError::serialize_err("Book", "Content too long");
-        match error {
-            StdError::SerializeErr {
-                source_type, msg, ..
-            } => {
-                assert_eq!(source_type, "Book");
-                assert_eq!(msg, "Content too long");
+        assert_eq!(error.kind(), StdErrorKind::SerializeErr);
+        assert_eq!(
+            error.payload(),
+            &StdErrorPayload::TypeAndMessage {
+                type_name: "Book".to_string(),
+                msg: "Content too long".to_string()
             }
-            _ => panic!("expect different error"),
-        }
+        );
     }
 
     #[test]
     fn underflow_works_for_u128() {
         let error = StdError::overflow(OverflowError::new(OverflowOperation::Sub));
-        assert!(matches!(
-            error,
-            StdError::Overflow {
-                source: OverflowError {
-                    operation: OverflowOperation::Sub
-                },
-                ..
-            }
-        ));
+        assert_eq!(error.kind(), StdErrorKind::Overflow);
+        assert_eq!(
+            error.payload(),
+            &StdErrorPayload::Overflow(OverflowError::new(OverflowOperation::Sub))
+        );
     }
 
     #[test]
     fn overflow_works_for_i64() {
         let error = StdError::overflow(OverflowError::new(OverflowOperation::Sub));
-        assert!(matches!(
-            error,
-            StdError::Overflow {
-                source: OverflowError {
-                    operation: OverflowOperation::Sub
-                },
-                ..
-            }
-        ));
+        assert_eq!(error.kind(), StdErrorKind::Overflow);
+        assert_eq!(
+            error.payload(),
+            &StdErrorPayload::Overflow(OverflowError::new(OverflowOperation::Sub))
+        );
     }
 
     #[test]
     fn divide_by_zero_works() {
         let error = StdError::divide_by_zero(DivideByZeroError);
-        assert!(matches!(
-            error,
-            StdError::DivideByZero {
-                source: DivideByZeroError,
-                ..
-            }
-        ));
+        assert_eq!(error.kind(), StdErrorKind::DivideByZero);
+        assert_eq!(
+            error.payload(),
+            &StdErrorPayload::DivideByZero(DivideByZeroError)
+        );
+    }
+
+    #[test]
+    fn conversion_overflow_works() {
+        let error =
+            StdError::conversion_overflow(ConversionOverflowError::new("Uint256", "Uint128"));
+        assert_eq!(error.kind(), StdErrorKind::ConversionOverflow);
+        assert_eq!(
+            error.to_string(),
+            "Conversion error: Error converting Uint256 to Uint128"
+        );
+    }
+
+    #[test]
+    fn kind_works() {
+        assert_eq!(
+            StdError::generic_err("oh no").kind(),
+            StdErrorKind::GenericErr
+        );
+        assert_eq!(StdError::not_found("Config").kind(), StdErrorKind::NotFound);
+        assert_eq!(
+            StdError::invalid_data_size(31, 14).kind(),
+            StdErrorKind::InvalidDataSize
+        );
+        assert_eq!(
+            StdError::from(OverflowError::new(OverflowOperation::Sub)).kind(),
+            StdErrorKind::Overflow
+        );
+        assert_eq!(
+            StdError::from(ConversionOverflowError::new("Uint256", "Uint128")).kind(),
+            StdErrorKind::ConversionOverflow
+        );
     }
 
     #[test]
+    fn is_helpers_work() {
+        let not_found = StdError::not_found("Config");
+        assert!(not_found.is_not_found());
+        assert!(!not_found.is_generic_err());
+        assert!(!not_found.is_math_err());
+
+        let generic = StdError::generic_err("oh no");
+        assert!(generic.is_generic_err());
+        assert!(!generic.is_not_found());
+
+        assert!(StdError::parse_err("Config", "missing field").is_parse_err());
+        assert!(StdError::serialize_err("Config", "oh no").is_serialize_err());
+
+        assert!(StdError::from(OverflowError::new(OverflowOperation::Sub)).is_math_err());
+        assert!(StdError::from(DivideByZeroError::new()).is_math_err());
+        assert!(StdError::from(ConversionOverflowError::new("Uint256", "Uint128")).is_math_err());
+        assert!(!StdError::invalid_hex("oh no").is_math_err());
+    }
+
+    #[test]
+    #[cfg(not(feature = "backtraces"))]
     fn implements_debug() {
         let error: StdError = StdError::from(OverflowError::new(OverflowOperation::Sub));
         let embedded = format!("Debug: {error:?}");
-        let expected = r#"Debug: Overflow { source: OverflowError { operation: Sub }, backtrace: <disabled> }"#;
+        let expected = r#"Debug: StdError { kind: Overflow, payload: Overflow(OverflowError { operation: Sub }), backtrace: <disabled> }"#;
         assert_eq!(embedded, expected);
     }
 
@@ -767,16 +806,26 @@ mod tests {
         assert_ne!(s1, s3);
     }
 
+    #[test]
+    fn implements_error_source() {
+        use super::ErrorTrait;
+
+        let error = StdError::from(OverflowError::new(OverflowOperation::Sub));
+        assert_eq!(
+            error.source().unwrap().to_string(),
+            "Cannot Sub with given operands"
+        );
+        assert!(StdError::generic_err("oh no").source().is_none());
+    }
+
     #[test]
     fn from_std_str_utf8error_works() {
         let broken = b"Hello \xF0\x90\x80World";
         let error: StdError = str::from_utf8(broken).unwrap_err().into();
-        match error {
-            StdError::InvalidUtf8 { msg, .. } => {
-                assert_eq!(msg, "invalid utf-8 sequence of 3 bytes from index 6")
-            }
-            err => panic!("Unexpected error: {err:?}"),
-        }
+        assert_eq!(
+            error,
+            StdError::invalid_utf8("invalid utf-8 sequence of 3 bytes from index 6")
+        );
     }
 
     #[test]
@@ -784,11 +833,9 @@ mod tests {
         let error: StdError = String::from_utf8(b"Hello \xF0\x90\x80World".to_vec())
             .unwrap_err()
             .into();
-        match error {
-            StdError::InvalidUtf8 { msg, .. } => {
-                assert_eq!(msg, "invalid utf-8 sequence of 3 bytes from index 6")
-            }
-            err => panic!("Unexpected error: {err:?}"),
-        }
+        assert_eq!(
+            error,
+            StdError::invalid_utf8("invalid utf-8 sequence of 3 bytes from index 6")
+        );
     }
-}
\ No newline at end of file
+}
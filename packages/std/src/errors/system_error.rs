@@ -1,4 +1,4 @@
-use schemars::JsonSchema;
+use alloc::string::String;
 use serde::{Deserialize, Serialize};
 
 use crate::Binary;
@@ -12,7 +12,8 @@ use crate::Binary;
 ///
 /// Such errors are only created by the VM. The error type is defined in the standard library, to ensure
 /// the contract understands the error format without creating a dependency on cosmwasm-vm.
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 #[non_exhaustive]
 pub enum SystemError {
@@ -37,8 +38,15 @@ pub enum SystemError {
     UnsupportedRequest {
         kind: String,
     },
+    /// A query that was executed with an explicit gas limit ran out of gas.
+    /// The gas used up to this point is still charged to the caller.
+    OutOfGas {
+        /// The gas limit of the query
+        gas_limit: u64,
+    },
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for SystemError {}
 
 impl core::fmt::Display for SystemError {
@@ -62,6 +70,9 @@ impl core::fmt::Display for SystemError {
             SystemError::UnsupportedRequest { kind } => {
                 write!(f, "Unsupported query type: {kind}")
             }
+            SystemError::OutOfGas { gas_limit } => {
+                write!(f, "Query ran out of gas. Limit: {gas_limit}")
+            }
         }
     }
 }
@@ -109,4 +120,20 @@ mod tests {
         let err: SystemError = from_json(br#"{"no_such_code":{"code_id":987}}"#).unwrap();
         assert_eq!(err, SystemError::NoSuchCode { code_id: 987 },);
     }
+
+    #[test]
+    fn system_error_out_of_gas_serialization() {
+        let err = SystemError::OutOfGas { gas_limit: 5000 };
+
+        // ser
+        let json = to_json_vec(&err).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"out_of_gas":{"gas_limit":5000}}"#,
+        );
+
+        // de
+        let err: SystemError = from_json(br#"{"out_of_gas":{"gas_limit":42}}"#).unwrap();
+        assert_eq!(err, SystemError::OutOfGas { gas_limit: 42 });
+    }
 }
@@ -3,7 +3,7 @@ use core::fmt::Debug;
 use super::BT;
 use thiserror::Error;
 
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
 use cosmwasm_crypto::CryptoError;
 
 #[derive(Error, Debug)]
@@ -20,11 +20,17 @@ pub enum VerificationError {
     InvalidPubkeyFormat,
     #[error("Invalid recovery parameter. Supported values: 0 and 1.")]
     InvalidRecoveryParam,
+    #[error("Verification is not implemented by this Api")]
+    Unimplemented,
     #[error("Unknown error: {error_code}")]
     UnknownErr { error_code: u32, backtrace: BT },
 }
 
 impl VerificationError {
+    pub fn unimplemented() -> Self {
+        VerificationError::Unimplemented
+    }
+
     pub fn unknown_err(error_code: u32) -> Self {
         VerificationError::UnknownErr {
             error_code,
@@ -51,6 +57,7 @@ impl PartialEq<VerificationError> for VerificationError {
             VerificationError::InvalidRecoveryParam => {
                 matches!(rhs, VerificationError::InvalidRecoveryParam)
             }
+            VerificationError::Unimplemented => matches!(rhs, VerificationError::Unimplemented),
             VerificationError::UnknownErr { error_code, .. } => {
                 if let VerificationError::UnknownErr {
                     error_code: rhs_error_code,
@@ -66,7 +73,7 @@ impl PartialEq<VerificationError> for VerificationError {
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
 impl From<CryptoError> for VerificationError {
     fn from(original: CryptoError) -> Self {
         match original {
@@ -93,4 +100,10 @@ mod tests {
             _ => panic!("wrong error type!"),
         }
     }
+
+    #[test]
+    fn unimplemented_works() {
+        let error = VerificationError::unimplemented();
+        assert_eq!(error, VerificationError::Unimplemented);
+    }
 }
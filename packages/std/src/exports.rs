@@ -19,6 +19,8 @@ use crate::ibc::{
     IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse,
 };
 use crate::ibc::{IbcChannelOpenMsg, IbcChannelOpenResponse};
+#[cfg(feature = "chunked_results")]
+use crate::imports::write_result_chunked;
 use crate::imports::{ExternalApi, ExternalQuerier, ExternalStorage};
 use crate::memory::{alloc, consume_region, release_buffer, Region};
 #[cfg(feature = "abort")]
@@ -41,6 +43,14 @@ extern "C" fn requires_staking() -> () {}
 #[no_mangle]
 extern "C" fn requires_stargate() -> () {}
 
+#[cfg(feature = "randomness")]
+#[no_mangle]
+extern "C" fn requires_randomness() -> () {}
+
+#[cfg(feature = "token_factory")]
+#[no_mangle]
+extern "C" fn requires_token_factory() -> () {}
+
 #[cfg(feature = "cosmwasm_1_1")]
 #[no_mangle]
 extern "C" fn requires_cosmwasm_1_1() -> () {}
@@ -57,6 +67,59 @@ extern "C" fn requires_cosmwasm_1_3() -> () {}
 #[no_mangle]
 extern "C" fn requires_cosmwasm_1_4() -> () {}
 
+#[cfg(feature = "query_chain_binary")]
+#[no_mangle]
+extern "C" fn requires_query_chain_binary() -> () {}
+
+#[cfg(feature = "query_batch")]
+#[no_mangle]
+extern "C" fn requires_query_batch() -> () {}
+
+#[cfg(feature = "chunked_results")]
+#[no_mangle]
+extern "C" fn requires_chunked_results() -> () {}
+
+#[cfg(feature = "db_size")]
+#[no_mangle]
+extern "C" fn requires_db_size() -> () {}
+
+#[cfg(feature = "iterator_seek")]
+#[no_mangle]
+extern "C" fn requires_iterator_seek() -> () {}
+
+#[cfg(feature = "ed25519_variants")]
+#[no_mangle]
+extern "C" fn requires_ed25519_variants() -> () {}
+
+#[cfg(feature = "gas_remaining")]
+#[no_mangle]
+extern "C" fn requires_gas_remaining() -> () {}
+
+#[cfg(feature = "emit_event")]
+#[no_mangle]
+extern "C" fn requires_emit_event() -> () {}
+
+#[cfg(feature = "chain_queries")]
+#[no_mangle]
+extern "C" fn requires_chain_queries() -> () {}
+
+#[cfg(feature = "staking_pool_params")]
+#[no_mangle]
+extern "C" fn requires_staking_pool_params() -> () {}
+
+#[cfg(feature = "ibc_channel_filters")]
+#[no_mangle]
+extern "C" fn requires_ibc_channel_filters() -> () {}
+
+/// Results longer than this are written to the host in chunks instead of a single region
+/// because the host does not read longer regions.
+#[cfg(feature = "chunked_results")]
+const MAX_LENGTH_RESULT_REGION: usize = 64 * 1024 * 1024;
+
+/// The length of the chunks used to write long results
+#[cfg(feature = "chunked_results")]
+const RESULT_CHUNK_LENGTH: usize = 8 * 1024 * 1024;
+
 /// interface_version_* exports mark which Wasm VM interface level this contract is compiled for.
 /// They can be checked by cosmwasm_vm.
 /// Update this whenever the Wasm VM interface breaks.
@@ -79,6 +142,27 @@ extern "C" fn deallocate(pointer: u32) {
     let _ = unsafe { consume_region(pointer as *mut Region) };
 }
 
+/// Hands over the serialized result of an export to the host and returns the value
+/// the export should return.
+fn release_result(result: Vec<u8>) -> u32 {
+    let region = write_result(result);
+    // All regions written by the host during this call have been consumed at this point
+    #[cfg(feature = "arena_allocator")]
+    crate::arena::reset();
+    region
+}
+
+/// Writes the result either into a region or in chunks through the host.
+/// Returns the region pointer or 0 if the result was written in chunks.
+fn write_result(result: Vec<u8>) -> u32 {
+    #[cfg(feature = "chunked_results")]
+    if result.len() > MAX_LENGTH_RESULT_REGION {
+        write_result_chunked(&result, RESULT_CHUNK_LENGTH);
+        return 0;
+    }
+    release_buffer(result) as u32
+}
+
 // TODO: replace with https://doc.rust-lang.org/std/ops/trait.Try.html once stabilized
 macro_rules! r#try_into_contract_result {
     ($expr:expr) => {
@@ -121,7 +205,7 @@ where
         msg_ptr as *mut Region,
     );
     let v = to_json_vec(&res).unwrap();
-    release_buffer(v) as u32
+    release_result(v)
 }
 
 /// do_execute should be wrapped in an external "C" export, containing a contract-specific function as arg
@@ -151,7 +235,7 @@ where
         msg_ptr as *mut Region,
     );
     let v = to_json_vec(&res).unwrap();
-    release_buffer(v) as u32
+    release_result(v)
 }
 
 /// do_migrate should be wrapped in an external "C" export, containing a contract-specific function as arg
@@ -175,7 +259,7 @@ where
     install_panic_handler();
     let res = _do_migrate(migrate_fn, env_ptr as *mut Region, msg_ptr as *mut Region);
     let v = to_json_vec(&res).unwrap();
-    release_buffer(v) as u32
+    release_result(v)
 }
 
 /// do_sudo should be wrapped in an external "C" export, containing a contract-specific function as arg
@@ -199,7 +283,7 @@ where
     install_panic_handler();
     let res = _do_sudo(sudo_fn, env_ptr as *mut Region, msg_ptr as *mut Region);
     let v = to_json_vec(&res).unwrap();
-    release_buffer(v) as u32
+    release_result(v)
 }
 
 /// do_reply should be wrapped in an external "C" export, containing a contract-specific function as arg
@@ -222,7 +306,7 @@ where
     install_panic_handler();
     let res = _do_reply(reply_fn, env_ptr as *mut Region, msg_ptr as *mut Region);
     let v = to_json_vec(&res).unwrap();
-    release_buffer(v) as u32
+    release_result(v)
 }
 
 /// do_query should be wrapped in an external "C" export, containing a contract-specific function as arg
@@ -244,7 +328,7 @@ where
     install_panic_handler();
     let res = _do_query(query_fn, env_ptr as *mut Region, msg_ptr as *mut Region);
     let v = to_json_vec(&res).unwrap();
-    release_buffer(v) as u32
+    release_result(v)
 }
 
 /// do_ibc_channel_open is designed for use with #[entry_point] to make a "C" extern
@@ -267,7 +351,7 @@ where
     install_panic_handler();
     let res = _do_ibc_channel_open(contract_fn, env_ptr as *mut Region, msg_ptr as *mut Region);
     let v = to_json_vec(&res).unwrap();
-    release_buffer(v) as u32
+    release_result(v)
 }
 
 /// do_ibc_channel_connect is designed for use with #[entry_point] to make a "C" extern
@@ -292,7 +376,7 @@ where
     install_panic_handler();
     let res = _do_ibc_channel_connect(contract_fn, env_ptr as *mut Region, msg_ptr as *mut Region);
     let v = to_json_vec(&res).unwrap();
-    release_buffer(v) as u32
+    release_result(v)
 }
 
 /// do_ibc_channel_close is designed for use with #[entry_point] to make a "C" extern
@@ -317,7 +401,7 @@ where
     install_panic_handler();
     let res = _do_ibc_channel_close(contract_fn, env_ptr as *mut Region, msg_ptr as *mut Region);
     let v = to_json_vec(&res).unwrap();
-    release_buffer(v) as u32
+    release_result(v)
 }
 
 /// do_ibc_packet_receive is designed for use with #[entry_point] to make a "C" extern
@@ -343,7 +427,7 @@ where
     install_panic_handler();
     let res = _do_ibc_packet_receive(contract_fn, env_ptr as *mut Region, msg_ptr as *mut Region);
     let v = to_json_vec(&res).unwrap();
-    release_buffer(v) as u32
+    release_result(v)
 }
 
 /// do_ibc_packet_ack is designed for use with #[entry_point] to make a "C" extern
@@ -369,7 +453,7 @@ where
     install_panic_handler();
     let res = _do_ibc_packet_ack(contract_fn, env_ptr as *mut Region, msg_ptr as *mut Region);
     let v = to_json_vec(&res).unwrap();
-    release_buffer(v) as u32
+    release_result(v)
 }
 
 /// do_ibc_packet_timeout is designed for use with #[entry_point] to make a "C" extern
@@ -396,7 +480,7 @@ where
     install_panic_handler();
     let res = _do_ibc_packet_timeout(contract_fn, env_ptr as *mut Region, msg_ptr as *mut Region);
     let v = to_json_vec(&res).unwrap();
-    release_buffer(v) as u32
+    release_result(v)
 }
 
 fn _do_instantiate<Q, M, C, E>(
@@ -0,0 +1,256 @@
+//! Names of the built-in capabilities and the [`require_capability!`](crate::require_capability)
+//! macro to declare that a contract requires a capability.
+//!
+//! A contract requires a capability by exporting a marker function named `requires_<capability>`.
+//! The VM checks those markers when the contract is stored and rejects contracts requiring
+//! capabilities that the chain does not provide. See
+//! [CAPABILITIES.md](https://github.com/CosmWasm/cosmwasm/blob/main/docs/CAPABILITIES.md)
+//! for details.
+//!
+//! The built-in capabilities below are declared automatically when the cosmwasm-std Cargo
+//! feature of the same name is enabled. Declaring them again with `require_capability!` fails
+//! to compile, since the marker export would be defined twice.
+
+/// Storage iterators (range queries)
+pub const fn iterator() -> &'static str {
+    "iterator"
+}
+
+/// The Cosmos SDK staking module
+pub const fn staking() -> &'static str {
+    "staking"
+}
+
+/// Protobuf messages and queries as well as IBC
+pub const fn stargate() -> &'static str {
+    "stargate"
+}
+
+/// A randomness beacon module
+pub const fn randomness() -> &'static str {
+    "randomness"
+}
+
+/// A token factory module
+pub const fn token_factory() -> &'static str {
+    "token_factory"
+}
+
+/// Functionality of CosmWasm 1.1 chains
+pub const fn cosmwasm_1_1() -> &'static str {
+    "cosmwasm_1_1"
+}
+
+/// Functionality of CosmWasm 1.2 chains
+pub const fn cosmwasm_1_2() -> &'static str {
+    "cosmwasm_1_2"
+}
+
+/// Functionality of CosmWasm 1.3 chains
+pub const fn cosmwasm_1_3() -> &'static str {
+    "cosmwasm_1_3"
+}
+
+/// Functionality of CosmWasm 1.4 chains
+pub const fn cosmwasm_1_4() -> &'static str {
+    "cosmwasm_1_4"
+}
+
+/// Query responses without JSON and base64 encoding through the `query_chain_binary` import
+pub const fn query_chain_binary() -> &'static str {
+    "query_chain_binary"
+}
+
+/// Executing multiple queries in one call through the `query_chain_batch` imports
+pub const fn query_batch() -> &'static str {
+    "query_batch"
+}
+
+/// Writing long entry point results in chunks through the `write_region_chunked` import
+pub const fn chunked_results() -> &'static str {
+    "chunked_results"
+}
+
+/// Storage usage of the contract through the `db_size` import
+pub const fn db_size() -> &'static str {
+    "db_size"
+}
+
+/// Prefix scans and seekable storage iterators through the `db_scan_prefix` and `db_seek` imports
+pub const fn iterator_seek() -> &'static str {
+    "iterator_seek"
+}
+
+/// The Ed25519ctx and Ed25519ph signature schemes through the `ed25519ctx_verify` and
+/// `ed25519ph_verify` imports
+pub const fn ed25519_variants() -> &'static str {
+    "ed25519_variants"
+}
+
+/// The gas left for the current call through the `gas_remaining` import
+pub const fn gas_remaining() -> &'static str {
+    "gas_remaining"
+}
+
+/// Emitting events while the contract is executing through the `emit_event` import
+pub const fn emit_event() -> &'static str {
+    "emit_event"
+}
+
+/// Querying the capabilities of the chain
+pub const fn chain_queries() -> &'static str {
+    "chain_queries"
+}
+
+/// Querying the pool and the parameters of the staking module
+pub const fn staking_pool_params() -> &'static str {
+    "staking_pool_params"
+}
+
+/// Filtering the IBC channels listed by the IBC querier
+pub const fn ibc_channel_filters() -> &'static str {
+    "ibc_channel_filters"
+}
+
+/// Returns true if the `requires_*` marker export of the given capability is emitted by
+/// cosmwasm-std because the Cargo feature of the same name is enabled.
+///
+/// This is used by [`require_capability!`](crate::require_capability) to reject such
+/// capabilities at compile time.
+#[doc(hidden)]
+pub const fn is_required_by_feature(capability: &str) -> bool {
+    const REQUIRED: &[&str] = &[
+        #[cfg(feature = "iterator")]
+        iterator(),
+        #[cfg(feature = "staking")]
+        staking(),
+        #[cfg(feature = "stargate")]
+        stargate(),
+        #[cfg(feature = "randomness")]
+        randomness(),
+        #[cfg(feature = "token_factory")]
+        token_factory(),
+        #[cfg(feature = "cosmwasm_1_1")]
+        cosmwasm_1_1(),
+        #[cfg(feature = "cosmwasm_1_2")]
+        cosmwasm_1_2(),
+        #[cfg(feature = "cosmwasm_1_3")]
+        cosmwasm_1_3(),
+        #[cfg(feature = "cosmwasm_1_4")]
+        cosmwasm_1_4(),
+        #[cfg(feature = "query_chain_binary")]
+        query_chain_binary(),
+        #[cfg(feature = "query_batch")]
+        query_batch(),
+        #[cfg(feature = "chunked_results")]
+        chunked_results(),
+        #[cfg(feature = "db_size")]
+        db_size(),
+        #[cfg(feature = "iterator_seek")]
+        iterator_seek(),
+        #[cfg(feature = "ed25519_variants")]
+        ed25519_variants(),
+        #[cfg(feature = "gas_remaining")]
+        gas_remaining(),
+        #[cfg(feature = "emit_event")]
+        emit_event(),
+        #[cfg(feature = "chain_queries")]
+        chain_queries(),
+        #[cfg(feature = "staking_pool_params")]
+        staking_pool_params(),
+        #[cfg(feature = "ibc_channel_filters")]
+        ibc_channel_filters(),
+    ];
+
+    let capability = capability.as_bytes();
+    let mut i = 0;
+    while i < REQUIRED.len() {
+        let required = REQUIRED[i].as_bytes();
+        if required.len() == capability.len() {
+            let mut j = 0;
+            while j < required.len() && required[j] == capability[j] {
+                j += 1;
+            }
+            if j == required.len() {
+                return true;
+            }
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Declares that the contract requires a capability by emitting the `requires_*` marker export.
+///
+/// Built-in capabilities are passed by name and checked at compile time against the functions
+/// in [`features`](crate::features). Capabilities defined by a chain, including versioned ones,
+/// are passed as a string literal. The marker is only emitted when compiling to Wasm.
+///
+/// ```
+/// use cosmwasm_std::require_capability;
+///
+/// # #[cfg(not(feature = "randomness"))]
+/// require_capability!(randomness);
+/// require_capability!("token_factory:v1");
+/// ```
+///
+/// Built-in capabilities whose Cargo feature is enabled are declared by cosmwasm-std already.
+/// Declaring them again fails to compile instead of producing a duplicate export.
+#[macro_export]
+macro_rules! require_capability {
+    ($capability:ident) => {
+        // Fails to compile for unknown built-in capabilities
+        const _: &str = $crate::features::$capability();
+        $crate::require_capability!(@marker stringify!($capability));
+    };
+    ($capability:literal) => {
+        $crate::require_capability!(@marker $capability);
+    };
+    (@marker $name:expr) => {
+        const _: () = assert!(
+            !$crate::features::is_required_by_feature($name),
+            "The capability is already required by the cosmwasm-std feature of the same name"
+        );
+        #[cfg(target_arch = "wasm32")]
+        const _: () = {
+            #[export_name = concat!("requires_", $name)]
+            extern "C" fn requires() {}
+        };
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "randomness"))]
+    require_capability!(randomness);
+    require_capability!("custom:v2");
+
+    #[test]
+    fn capability_names_work() {
+        assert_eq!(iterator(), "iterator");
+        assert_eq!(token_factory(), "token_factory");
+        assert_eq!(cosmwasm_1_1(), "cosmwasm_1_1");
+        assert_eq!(query_chain_binary(), "query_chain_binary");
+        assert_eq!(ibc_channel_filters(), "ibc_channel_filters");
+
+        const NAME: &str = staking();
+        assert_eq!(NAME, "staking");
+    }
+
+    #[test]
+    fn is_required_by_feature_works() {
+        assert_eq!(
+            is_required_by_feature("iterator"),
+            cfg!(feature = "iterator")
+        );
+        assert_eq!(
+            is_required_by_feature("cosmwasm_1_4"),
+            cfg!(feature = "cosmwasm_1_4")
+        );
+        assert!(!is_required_by_feature("custom:v2"));
+        assert!(!is_required_by_feature("iterator2"));
+        assert!(!is_required_by_feature(""));
+    }
+}
@@ -23,3 +23,56 @@ macro_rules! forward_ref_partial_eq {
         }
     };
 }
+
+/// Given an implementation of `T op U`, implements:
+/// - `&T op U`
+/// - `T op &U`
+/// - `&T op &U`
+///
+/// This is a copy of the macro from the `forward_ref` crate, which does not support `no_std`.
+macro_rules! forward_ref_binop {
+    (impl $imp:ident, $method:ident for $t:ty, $u:ty) => {
+        impl<'a> $imp<$u> for &'a $t {
+            type Output = <$t as $imp<$u>>::Output;
+
+            #[inline]
+            fn $method(self, other: $u) -> <$t as $imp<$u>>::Output {
+                $imp::$method(*self, other)
+            }
+        }
+
+        impl $imp<&$u> for $t {
+            type Output = <$t as $imp<$u>>::Output;
+
+            #[inline]
+            fn $method(self, other: &$u) -> <$t as $imp<$u>>::Output {
+                $imp::$method(self, *other)
+            }
+        }
+
+        impl $imp<&$u> for &$t {
+            type Output = <$t as $imp<$u>>::Output;
+
+            #[inline]
+            fn $method(self, other: &$u) -> <$t as $imp<$u>>::Output {
+                $imp::$method(*self, *other)
+            }
+        }
+    };
+}
+pub(crate) use forward_ref_binop;
+
+/// Given an implementation of `T op= U`, implements `T op= &U`.
+///
+/// This is a copy of the macro from the `forward_ref` crate, which does not support `no_std`.
+macro_rules! forward_ref_op_assign {
+    (impl $imp:ident, $method:ident for $t:ty, $u:ty) => {
+        impl $imp<&$u> for $t {
+            #[inline]
+            fn $method(&mut self, other: &$u) {
+                $imp::$method(self, *other);
+            }
+        }
+    };
+}
+pub(crate) use forward_ref_op_assign;
@@ -1,7 +1,7 @@
+use alloc::{format, string::String, vec::Vec};
 use core::fmt;
-use core::ops::Deref;
+use core::ops::{Bound, Deref, RangeBounds};
 
-use schemars::JsonSchema;
 use serde::{de, ser, Deserialize, Deserializer, Serialize};
 
 use crate::{Binary, StdError, StdResult};
@@ -12,8 +12,9 @@ use crate::{Binary, StdError, StdResult};
 ///
 /// This is similar to `cosmwasm_std::Binary` but uses hex.
 /// See also <https://github.com/CosmWasm/cosmwasm/blob/main/docs/MESSAGE_TYPES.md>.
-#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord, JsonSchema)]
-pub struct HexBinary(#[schemars(with = "String")] Vec<u8>);
+#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+pub struct HexBinary(#[cfg_attr(feature = "std", schemars(with = "String"))] Vec<u8>);
 
 impl HexBinary {
     pub fn from_hex(input: &str) -> StdResult<Self> {
@@ -59,6 +60,55 @@ impl HexBinary {
         out.copy_from_slice(&self.0);
         Ok(out)
     }
+
+    /// Returns a reference to the content as a fixed-sized array without copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::HexBinary;
+    /// let data = HexBinary::from(&[0xfb, 0x1f, 0x37]);
+    /// let array: &[u8; 3] = data.as_array().unwrap();
+    /// assert_eq!(array, &[0xfb, 0x1f, 0x37]);
+    /// ```
+    pub fn as_array<const LENGTH: usize>(&self) -> StdResult<&[u8; LENGTH]> {
+        self.0
+            .as_slice()
+            .try_into()
+            .map_err(|_| StdError::invalid_data_size(LENGTH, self.len()))
+    }
+
+    /// Returns the given range of the content without copying.
+    /// Fails if the range is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::HexBinary;
+    /// let data = HexBinary::from(&[1, 2, 3, 4]);
+    /// assert_eq!(data.subslice(1..3).unwrap(), &[2, 3]);
+    /// assert_eq!(data.subslice(2..).unwrap(), &[3, 4]);
+    /// data.subslice(3..5).unwrap_err();
+    /// ```
+    pub fn subslice(&self, range: impl RangeBounds<usize>) -> StdResult<&[u8]> {
+        let bounds: (Bound<usize>, Bound<usize>) =
+            (range.start_bound().cloned(), range.end_bound().cloned());
+        self.0.get(bounds).ok_or_else(|| {
+            StdError::generic_err(format!(
+                "Range {bounds:?} out of bounds for data of length {}",
+                self.len()
+            ))
+        })
+    }
+
+    /// Compares the content to the given data in constant time, i.e. the duration of the
+    /// comparison does not depend on the position of the first differing byte.
+    /// Use this to compare secrets like commitments or MACs.
+    ///
+    /// Only the content is protected, the lengths of the inputs are not secret.
+    pub fn ct_eq(&self, other: impl AsRef<[u8]>) -> bool {
+        crate::binary::constant_time_eq(&self.0, other.as_ref())
+    }
 }
 
 impl fmt::Display for HexBinary {
@@ -247,9 +297,34 @@ impl<'de> de::Visitor<'de> for HexVisitor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::StdErrorKind;
 
     use crate::{assert_hash_works, from_json, to_json_vec, StdError};
 
+    #[test]
+    fn as_array_works() {
+        let data = HexBinary::from(&[1, 2, 3]);
+        let array: &[u8; 3] = data.as_array().unwrap();
+        assert_eq!(array, &[1, 2, 3]);
+        assert_eq!(data.as_array::<8>(), Err(StdError::invalid_data_size(8, 3)));
+    }
+
+    #[test]
+    fn subslice_works() {
+        let data = HexBinary::from(&[1, 2, 3, 4]);
+        assert_eq!(data.subslice(1..3).unwrap(), &[2, 3]);
+        assert_eq!(data.subslice(..).unwrap(), &[1, 2, 3, 4]);
+        data.subslice(..5).unwrap_err();
+    }
+
+    #[test]
+    fn ct_eq_works() {
+        let data = HexBinary::from(&[1, 2, 3]);
+        assert!(data.ct_eq([1, 2, 3]));
+        assert!(!data.ct_eq([1, 2, 4]));
+        assert!(!data.ct_eq([1, 2]));
+    }
+
     #[test]
     fn from_hex_works() {
         let data = HexBinary::from_hex("").unwrap();
@@ -267,43 +342,37 @@ mod tests {
         assert_eq!(data.as_slice(), b"randomiZ");
 
         // odd
-        match HexBinary::from_hex("123").unwrap_err() {
-            StdError::InvalidHex { msg, .. } => {
-                assert_eq!(msg, "Odd number of digits")
-            }
-            _ => panic!("Unexpected error type"),
-        }
+        assert_eq!(
+            HexBinary::from_hex("123").unwrap_err(),
+            StdError::invalid_hex("Odd number of digits")
+        );
         // non-hex
-        match HexBinary::from_hex("efgh").unwrap_err() {
-            StdError::InvalidHex { msg, .. } => {
-                assert_eq!(msg, "Invalid character 'g' at position 2")
-            }
-            _ => panic!("Unexpected error type"),
-        }
+        assert_eq!(
+            HexBinary::from_hex("efgh").unwrap_err(),
+            StdError::invalid_hex("Invalid character 'g' at position 2")
+        );
         // 0x prefixed
-        match HexBinary::from_hex("0xaa").unwrap_err() {
-            StdError::InvalidHex { msg, .. } => {
-                assert_eq!(msg, "Invalid character 'x' at position 1")
-            }
-            _ => panic!("Unexpected error type"),
-        }
+        assert_eq!(
+            HexBinary::from_hex("0xaa").unwrap_err(),
+            StdError::invalid_hex("Invalid character 'x' at position 1")
+        );
         // spaces
-        assert!(matches!(
-            HexBinary::from_hex("aa ").unwrap_err(),
-            StdError::InvalidHex { .. }
-        ));
-        assert!(matches!(
-            HexBinary::from_hex(" aa").unwrap_err(),
-            StdError::InvalidHex { .. }
-        ));
-        assert!(matches!(
-            HexBinary::from_hex("a a").unwrap_err(),
-            StdError::InvalidHex { .. }
-        ));
-        assert!(matches!(
-            HexBinary::from_hex(" aa ").unwrap_err(),
-            StdError::InvalidHex { .. }
-        ));
+        assert_eq!(
+            HexBinary::from_hex("aa ").unwrap_err().kind(),
+            StdErrorKind::InvalidHex
+        );
+        assert_eq!(
+            HexBinary::from_hex(" aa").unwrap_err().kind(),
+            StdErrorKind::InvalidHex
+        );
+        assert_eq!(
+            HexBinary::from_hex("a a").unwrap_err().kind(),
+            StdErrorKind::InvalidHex
+        );
+        assert_eq!(
+            HexBinary::from_hex(" aa ").unwrap_err().kind(),
+            StdErrorKind::InvalidHex
+        );
     }
 
     #[test]
@@ -336,15 +405,7 @@ mod tests {
         // invalid size
         let binary = HexBinary::from(&[1, 2, 3]);
         let error = binary.to_array::<8>().unwrap_err();
-        match error {
-            StdError::InvalidDataSize {
-                expected, actual, ..
-            } => {
-                assert_eq!(expected, 8);
-                assert_eq!(actual, 3);
-            }
-            err => panic!("Unexpected error: {err:?}"),
-        }
+        assert_eq!(error, StdError::invalid_data_size(8, 3));
 
         // long array (32 bytes)
         let binary =
@@ -2,16 +2,18 @@
 // The rest of the IBC related functionality is defined here
 
 use core::cmp::{Ord, Ordering, PartialOrd};
+use core::time::Duration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::addresses::Addr;
 use crate::binary::Binary;
 use crate::coin::Coin;
-use crate::errors::StdResult;
+use crate::errors::{IbcTimeoutError, StdResult};
 use crate::results::{Attribute, CosmosMsg, Empty, Event, SubMsg};
 use crate::serde::to_json_binary;
 use crate::timestamp::Timestamp;
+use crate::types::Env;
 
 /// Messages pertaining to the IBC lifecycle, specifically for contracts with IBC capabilities.
 /// Manages cross-chain communications and other IBC protocol interactions.
@@ -97,6 +99,70 @@ impl IbcTimeout {
     pub fn timestamp(&self) -> Option<Timestamp> {
         self.timestamp
     }
+
+    /// Creates a timeout at the given duration after the current block time.
+    ///
+    /// Panics if the resulting timestamp does not fit into a [`Timestamp`].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use core::time::Duration;
+    /// # use cosmwasm_std::{testing::mock_env, IbcTimeout};
+    /// let env = mock_env();
+    /// let timeout = IbcTimeout::from_now(&env, Duration::from_secs(600));
+    /// assert_eq!(timeout.timestamp(), Some(env.block.time.plus_minutes(10)));
+    /// ```
+    pub fn from_now(env: &Env, duration: Duration) -> Self {
+        let nanos = u64::try_from(duration.as_nanos()).expect("Duration too large");
+        IbcTimeout::with_timestamp(env.block.time.plus_nanos(nanos))
+    }
+
+    /// Checks that at least one of block and timestamp is set and that a timestamp,
+    /// if set, is at least `min_delta` after the current block time.
+    ///
+    /// As in IBC, a zero block and a zero timestamp mean that the respective timeout
+    /// is disabled and are treated like unset values.
+    ///
+    /// The block timeout refers to the height of the counterparty chain and thus cannot
+    /// be checked against the current block.
+    ///
+    /// This is useful for timeouts provided by users, since a timeout that is too early
+    /// causes the packet to time out before it can be relayed.
+    pub fn validate(&self, env: &Env, min_delta: Duration) -> Result<(), IbcTimeoutError> {
+        let block = self.block.filter(|block| !block.is_zero());
+        let timestamp = self.timestamp.filter(|timestamp| timestamp.nanos() != 0);
+        if block.is_none() && timestamp.is_none() {
+            return Err(IbcTimeoutError::NoTimeout);
+        }
+        if let Some(timestamp) = timestamp {
+            let min_delta = u64::try_from(min_delta.as_nanos()).unwrap_or(u64::MAX);
+            let earliest = Timestamp::from_nanos(env.block.time.nanos().saturating_add(min_delta));
+            if timestamp < earliest {
+                return Err(IbcTimeoutError::TooSoon {
+                    timestamp,
+                    earliest,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if a packet with this timeout would be timed out on a counterparty
+    /// chain at the given block and time.
+    ///
+    /// As in IBC, a packet is timed out once the counterparty's block reaches the timeout
+    /// block or its time reaches the timeout timestamp. Zero blocks and zero timestamps
+    /// are ignored.
+    pub fn is_expired(&self, block: IbcTimeoutBlock, time: Timestamp) -> bool {
+        let block_expired = self
+            .block
+            .is_some_and(|timeout| !timeout.is_zero() && block >= timeout);
+        let time_expired = self
+            .timestamp
+            .is_some_and(|timeout| timeout.nanos() != 0 && time >= timeout);
+        block_expired || time_expired
+    }
 }
 
 impl From<Timestamp> for IbcTimeout {
@@ -145,6 +211,11 @@ impl IbcChannel {
             connection_id: connection_id.into(),
         }
     }
+
+    /// Returns true if packets on this channel are delivered in the order they were sent.
+    pub fn is_ordered(&self) -> bool {
+        self.order == IbcOrder::Ordered
+    }
 }
 
 /// Enumerates the ordering of messages in an IBC channel, such as ORDERED or UNORDERED.
@@ -224,6 +295,26 @@ impl IbcPacket {
             timeout,
         }
     }
+
+    /// The port ID on the sending chain
+    pub fn src_port_id(&self) -> &str {
+        &self.src.port_id
+    }
+
+    /// The channel ID on the sending chain
+    pub fn src_channel_id(&self) -> &str {
+        &self.src.channel_id
+    }
+
+    /// The port ID on the receiving chain
+    pub fn dest_port_id(&self) -> &str {
+        &self.dest.port_id
+    }
+
+    /// The channel ID on the receiving chain
+    pub fn dest_channel_id(&self) -> &str {
+        &self.dest.channel_id
+    }
 }
 ///Represents an acknowledgement message in the IBC protocol.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -279,6 +370,11 @@ impl IbcChannelOpenMsg {
         }
     }
 
+    /// Returns true if the channel is ordered. See [`IbcChannel::is_ordered`].
+    pub fn is_ordered(&self) -> bool {
+        self.channel().is_ordered()
+    }
+
     pub fn counterparty_version(&self) -> Option<&str> {
         match self {
             Self::OpenTry {
@@ -344,6 +440,11 @@ impl IbcChannelConnectMsg {
         }
     }
 
+    /// Returns true if the channel is ordered. See [`IbcChannel::is_ordered`].
+    pub fn is_ordered(&self) -> bool {
+        self.channel().is_ordered()
+    }
+
     pub fn counterparty_version(&self) -> Option<&str> {
         match self {
             Self::OpenAck {
@@ -389,6 +490,11 @@ impl IbcChannelCloseMsg {
             Self::CloseConfirm { channel } => channel,
         }
     }
+
+    /// Returns true if the channel is ordered. See [`IbcChannel::is_ordered`].
+    pub fn is_ordered(&self) -> bool {
+        self.channel().is_ordered()
+    }
 }
 
 impl From<IbcChannelCloseMsg> for IbcChannel {
@@ -400,6 +506,49 @@ impl From<IbcChannelCloseMsg> for IbcChannel {
     }
 }
 
+/// Implements accessors for the packet and relayer of the IBC packet messages,
+/// such that all of them can be handled uniformly.
+macro_rules! impl_packet_msg_accessors {
+    ($msg:ident, $packet:ident) => {
+        impl $msg {
+            /// The packet this message is about
+            pub fn packet(&self) -> &IbcPacket {
+                &self.$packet
+            }
+
+            /// The address of the relayer that submitted this message
+            pub fn relayer(&self) -> &Addr {
+                &self.relayer
+            }
+
+            /// The sequence number of the packet on its channel
+            pub fn sequence(&self) -> u64 {
+                self.$packet.sequence
+            }
+
+            /// The port ID on the sending chain
+            pub fn src_port_id(&self) -> &str {
+                self.$packet.src_port_id()
+            }
+
+            /// The channel ID on the sending chain
+            pub fn src_channel_id(&self) -> &str {
+                self.$packet.src_channel_id()
+            }
+
+            /// The port ID on the receiving chain
+            pub fn dest_port_id(&self) -> &str {
+                self.$packet.dest_port_id()
+            }
+
+            /// The channel ID on the receiving chain
+            pub fn dest_channel_id(&self) -> &str {
+                self.$packet.dest_channel_id()
+            }
+        }
+    };
+}
+
 /// The message that is passed into `ibc_packet_receive`
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[non_exhaustive]
@@ -450,6 +599,10 @@ impl IbcPacketTimeoutMsg {
     }
 }
 
+impl_packet_msg_accessors!(IbcPacketReceiveMsg, packet);
+impl_packet_msg_accessors!(IbcPacketAckMsg, original_packet);
+impl_packet_msg_accessors!(IbcPacketTimeoutMsg, packet);
+
 /// This is the return value for the majority of the ibc handlers.
 /// That are able to dispatch messages / events on their own,
 /// but have no meaningful return value to the calling code.
@@ -748,6 +901,8 @@ impl<T> IbcReceiveResponse<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::from_json;
+    use crate::testing::mock_env;
     use serde_json_wasm::to_string;
 
     #[test]
@@ -789,6 +944,216 @@ mod tests {
         assert_eq!(to_string(&both).unwrap(), expected);
     }
 
+    #[test]
+    fn ibc_timeout_from_now_works() {
+        let env = mock_env();
+        let timeout = IbcTimeout::from_now(&env, Duration::from_secs(90));
+        assert_eq!(timeout.timestamp(), Some(env.block.time.plus_seconds(90)));
+        assert_eq!(timeout.block(), None);
+
+        let timeout = IbcTimeout::from_now(&env, Duration::from_nanos(1));
+        assert_eq!(timeout.timestamp(), Some(env.block.time.plus_nanos(1)));
+    }
+
+    #[test]
+    fn ibc_timeout_validate_works() {
+        let env = mock_env();
+        let min_delta = Duration::from_secs(60);
+        let block = IbcTimeoutBlock {
+            revision: 1,
+            height: 100,
+        };
+
+        IbcTimeout::with_block(block)
+            .validate(&env, min_delta)
+            .unwrap();
+        IbcTimeout::from_now(&env, min_delta)
+            .validate(&env, min_delta)
+            .unwrap();
+        IbcTimeout::with_both(block, env.block.time.plus_minutes(2))
+            .validate(&env, min_delta)
+            .unwrap();
+
+        // no timeout
+        let zero = IbcTimeoutBlock {
+            revision: 0,
+            height: 0,
+        };
+        assert_eq!(
+            IbcTimeout::with_block(zero).validate(&env, min_delta),
+            Err(IbcTimeoutError::NoTimeout)
+        );
+        assert_eq!(
+            IbcTimeout::with_both(zero, Timestamp::from_nanos(0)).validate(&env, min_delta),
+            Err(IbcTimeoutError::NoTimeout)
+        );
+        let none: IbcTimeout = from_json(r#"{"block":null,"timestamp":null}"#).unwrap();
+        assert_eq!(
+            none.validate(&env, min_delta),
+            Err(IbcTimeoutError::NoTimeout)
+        );
+
+        // zero timestamp is ignored
+        IbcTimeout::with_both(block, Timestamp::from_nanos(0))
+            .validate(&env, min_delta)
+            .unwrap();
+
+        // too soon, also if a block is set
+        let timestamp = env.block.time.plus_seconds(59);
+        let expected = Err(IbcTimeoutError::TooSoon {
+            timestamp,
+            earliest: env.block.time.plus_seconds(60),
+        });
+        assert_eq!(
+            IbcTimeout::with_timestamp(timestamp).validate(&env, min_delta),
+            expected
+        );
+        assert_eq!(
+            IbcTimeout::with_both(block, timestamp).validate(&env, min_delta),
+            expected
+        );
+        assert_eq!(
+            IbcTimeout::with_timestamp(env.block.time.minus_seconds(1))
+                .validate(&env, Duration::ZERO)
+                .unwrap_err()
+                .to_string(),
+            "Timeout timestamp 1571797418.879305533 is before the earliest allowed timeout 1571797419.879305533"
+        );
+    }
+
+    #[test]
+    fn ibc_timeout_is_expired_works() {
+        let block = IbcTimeoutBlock {
+            revision: 1,
+            height: 100,
+        };
+        let before = IbcTimeoutBlock {
+            revision: 1,
+            height: 99,
+        };
+        let timestamp = Timestamp::from_seconds(1000);
+
+        let timeout = IbcTimeout::with_block(block);
+        assert!(!timeout.is_expired(before, timestamp));
+        assert!(timeout.is_expired(block, timestamp));
+
+        let timeout = IbcTimeout::with_timestamp(timestamp);
+        assert!(!timeout.is_expired(block, timestamp.minus_nanos(1)));
+        assert!(timeout.is_expired(before, timestamp));
+
+        let timeout = IbcTimeout::with_both(block, timestamp);
+        assert!(!timeout.is_expired(before, timestamp.minus_nanos(1)));
+        assert!(timeout.is_expired(block, timestamp.minus_nanos(1)));
+        assert!(timeout.is_expired(before, timestamp));
+
+        // zero block is ignored
+        let zero = IbcTimeoutBlock {
+            revision: 0,
+            height: 0,
+        };
+        assert!(!IbcTimeout::with_block(zero).is_expired(before, timestamp));
+
+        // zero timestamp is ignored
+        let timeout = IbcTimeout::with_timestamp(Timestamp::from_nanos(0));
+        assert!(!timeout.is_expired(block, timestamp));
+        let timeout = IbcTimeout::with_both(block, Timestamp::from_nanos(0));
+        assert!(!timeout.is_expired(before, timestamp));
+        assert!(timeout.is_expired(block, timestamp));
+    }
+
+    fn packet() -> IbcPacket {
+        IbcPacket::new(
+            b"foo",
+            IbcEndpoint {
+                port_id: "their-port".to_string(),
+                channel_id: "channel-1234".to_string(),
+            },
+            IbcEndpoint {
+                port_id: "our-port".to_string(),
+                channel_id: "chan33".to_string(),
+            },
+            27,
+            IbcTimeout::with_timestamp(Timestamp::from_nanos(4611686018427387904)),
+        )
+    }
+
+    #[test]
+    fn ibc_packet_msg_accessors_work() {
+        let relayer = Addr::unchecked("relayer");
+        let receive = IbcPacketReceiveMsg::new(packet(), relayer.clone());
+        let ack = IbcPacketAckMsg::new(IbcAcknowledgement::new(b"ack"), packet(), relayer.clone());
+        let timeout = IbcPacketTimeoutMsg::new(packet(), relayer.clone());
+
+        for (msg_packet, msg_relayer, sequence, src_port, src_channel, dest_port, dest_channel) in [
+            (
+                receive.packet(),
+                receive.relayer(),
+                receive.sequence(),
+                receive.src_port_id(),
+                receive.src_channel_id(),
+                receive.dest_port_id(),
+                receive.dest_channel_id(),
+            ),
+            (
+                ack.packet(),
+                ack.relayer(),
+                ack.sequence(),
+                ack.src_port_id(),
+                ack.src_channel_id(),
+                ack.dest_port_id(),
+                ack.dest_channel_id(),
+            ),
+            (
+                timeout.packet(),
+                timeout.relayer(),
+                timeout.sequence(),
+                timeout.src_port_id(),
+                timeout.src_channel_id(),
+                timeout.dest_port_id(),
+                timeout.dest_channel_id(),
+            ),
+        ] {
+            assert_eq!(msg_packet, &packet());
+            assert_eq!(msg_relayer, &relayer);
+            assert_eq!(sequence, 27);
+            assert_eq!(src_port, "their-port");
+            assert_eq!(src_channel, "channel-1234");
+            assert_eq!(dest_port, "our-port");
+            assert_eq!(dest_channel, "chan33");
+        }
+    }
+
+    #[test]
+    fn ibc_channel_is_ordered_works() {
+        let endpoint = IbcEndpoint {
+            port_id: "our-port".to_string(),
+            channel_id: "channel-1".to_string(),
+        };
+        let ordered = IbcChannel::new(
+            endpoint.clone(),
+            endpoint.clone(),
+            IbcOrder::Ordered,
+            "v1",
+            "connection-1",
+        );
+        let unordered = IbcChannel::new(
+            endpoint.clone(),
+            endpoint,
+            IbcOrder::Unordered,
+            "v1",
+            "connection-1",
+        );
+        assert!(ordered.is_ordered());
+        assert!(!unordered.is_ordered());
+
+        assert!(IbcChannelOpenMsg::new_init(ordered.clone()).is_ordered());
+        assert!(!IbcChannelOpenMsg::new_try(unordered.clone(), "v1").is_ordered());
+        assert!(IbcChannelConnectMsg::new_ack(ordered.clone(), "v1").is_ordered());
+        assert!(!IbcChannelConnectMsg::new_confirm(unordered.clone()).is_ordered());
+        assert!(IbcChannelCloseMsg::new_init(ordered).is_ordered());
+        assert!(!IbcChannelCloseMsg::new_confirm(unordered).is_ordered());
+    }
+
     #[test]
     #[allow(clippy::eq_op)]
     fn ibc_timeout_block_ord() {
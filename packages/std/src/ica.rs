@@ -0,0 +1,258 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::binary::Binary;
+use crate::errors::StdResult;
+use crate::results::AnyMsg;
+use crate::serde::{from_json, to_json_binary, to_json_string};
+
+/// The version of the interchain accounts protocol
+pub const ICA_VERSION: &str = "ics27-1";
+
+/// The version metadata of an [ICS-27] interchain accounts channel.
+///
+/// A controller contract registers an interchain account by opening a channel with
+/// this metadata as the version. The host fills in the `address` of the account,
+/// which the controller can read from the counterparty version in the open ack.
+///
+/// [ICS-27]: https://github.com/cosmos/ibc/tree/main/spec/app/ics-027-interchain-accounts
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[non_exhaustive]
+pub struct IcaMetadata {
+    pub version: String,
+    pub controller_connection_id: String,
+    pub host_connection_id: String,
+    /// The address of the interchain account on the host chain.
+    /// Empty when opening the channel.
+    pub address: String,
+    pub encoding: String,
+    pub tx_type: String,
+}
+
+impl IcaMetadata {
+    /// Creates the metadata for registering a new interchain account with
+    /// protobuf encoded transactions.
+    pub fn new(
+        controller_connection_id: impl Into<String>,
+        host_connection_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            version: ICA_VERSION.to_string(),
+            controller_connection_id: controller_connection_id.into(),
+            host_connection_id: host_connection_id.into(),
+            address: String::new(),
+            encoding: "proto3".to_string(),
+            tx_type: "sdk_multi_msg".to_string(),
+        }
+    }
+
+    /// Parses the metadata from a channel version.
+    pub fn from_version(version: &str) -> StdResult<Self> {
+        from_json(version)
+    }
+
+    /// Encodes the metadata as a channel version.
+    pub fn to_version(&self) -> StdResult<String> {
+        to_json_string(self)
+    }
+}
+
+/// The type of an [`InterchainAccountPacketData`]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+pub enum IcaPacketType {
+    #[serde(rename = "TYPE_UNSPECIFIED")]
+    Unspecified,
+    /// Executes the messages of a [`CosmosTx`] on the host chain
+    #[serde(rename = "TYPE_EXECUTE_TX")]
+    ExecuteTx,
+}
+
+/// The packet data sent from the controller to the host of an interchain account.
+///
+/// This matches the protobuf JSON encoding used by ibc-go, which sorts the fields
+/// alphabetically and leaves out fields with default values, such as an empty memo.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{AnyMsg, CosmosTx, InterchainAccountPacketData};
+/// let tx = CosmosTx::new(vec![AnyMsg {
+///     type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+///     value: b"\x0a\x03abc".into(),
+/// }]);
+/// let data = InterchainAccountPacketData::execute_tx(&tx).to_binary().unwrap();
+/// // send data in an `IbcMsg::SendPacket` on the interchain account channel
+/// # let _ = data;
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[non_exhaustive]
+pub struct InterchainAccountPacketData {
+    /// The data to be processed by the host, e.g. a protobuf encoded [`CosmosTx`]
+    pub data: Binary,
+    /// Arbitrary data for the host chain, e.g. for middleware. Not serialized if empty.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub memo: String,
+    #[serde(rename = "type")]
+    pub packet_type: IcaPacketType,
+}
+
+impl InterchainAccountPacketData {
+    /// Creates the packet data for executing the given transaction on the host chain.
+    /// The transaction is protobuf encoded, which requires the channel to use the
+    /// "proto3" encoding.
+    pub fn execute_tx(tx: &CosmosTx) -> Self {
+        Self {
+            data: tx.to_proto(),
+            memo: String::new(),
+            packet_type: IcaPacketType::ExecuteTx,
+        }
+    }
+
+    /// Sets the memo of the packet.
+    pub fn with_memo(mut self, memo: impl Into<String>) -> Self {
+        self.memo = memo.into();
+        self
+    }
+
+    /// Encodes the packet data for use in `IbcMsg::SendPacket`.
+    pub fn to_binary(&self) -> StdResult<Binary> {
+        to_json_binary(self)
+    }
+}
+
+/// The messages executed by an interchain account in one transaction on the host chain
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[non_exhaustive]
+pub struct CosmosTx {
+    pub messages: Vec<AnyMsg>,
+}
+
+impl CosmosTx {
+    pub fn new(messages: impl IntoIterator<Item = AnyMsg>) -> Self {
+        Self {
+            messages: messages.into_iter().collect(),
+        }
+    }
+
+    /// Encodes the transaction as a protobuf `ibc.applications.interchain_accounts.v1.CosmosTx`.
+    pub fn to_proto(&self) -> Binary {
+        let mut out = Vec::new();
+        for msg in &self.messages {
+            let mut any = Vec::new();
+            encode_bytes_field(1, msg.type_url.as_bytes(), &mut any);
+            encode_bytes_field(2, msg.value.as_slice(), &mut any);
+            encode_bytes_field(1, &any, &mut out);
+        }
+        out.into()
+    }
+}
+
+/// Encodes a length-delimited protobuf field. Empty fields are omitted as in proto3,
+/// so this must not be used for embedded messages that need to be present.
+fn encode_bytes_field(field_number: u64, data: &[u8], out: &mut Vec<u8>) {
+    if data.is_empty() {
+        return;
+    }
+    encode_varint((field_number << 3) | 2, out);
+    encode_varint(data.len() as u64, out);
+    out.extend_from_slice(data);
+}
+
+fn encode_varint(mut value: u64, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn send_msg() -> AnyMsg {
+        AnyMsg {
+            type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+            value: Binary::from([1, 2, 3]),
+        }
+    }
+
+    #[test]
+    fn ica_metadata_works() {
+        let metadata = IcaMetadata::new("connection-0", "connection-12");
+        let version = metadata.to_version().unwrap();
+        assert_eq!(
+            version,
+            r#"{"version":"ics27-1","controller_connection_id":"connection-0","host_connection_id":"connection-12","address":"","encoding":"proto3","tx_type":"sdk_multi_msg"}"#
+        );
+        assert_eq!(IcaMetadata::from_version(&version).unwrap(), metadata);
+
+        // counterparty version with the address set by the host
+        let counterparty = IcaMetadata::from_version(
+            r#"{"version":"ics27-1","controller_connection_id":"connection-0","host_connection_id":"connection-12","address":"cosmos1ica","encoding":"proto3","tx_type":"sdk_multi_msg"}"#,
+        )
+        .unwrap();
+        assert_eq!(counterparty.address, "cosmos1ica");
+
+        IcaMetadata::from_version("ics20-1").unwrap_err();
+    }
+
+    #[test]
+    fn cosmos_tx_to_proto_works() {
+        assert_eq!(CosmosTx::new([]).to_proto(), Binary::default());
+
+        let any = [
+            &[0x0a, 28][..],
+            b"/cosmos.bank.v1beta1.MsgSend",
+            &[0x12, 3, 1, 2, 3],
+        ]
+        .concat();
+        let expected = [
+            &[0x0a, any.len() as u8][..],
+            &any,
+            &[0x0a, any.len() as u8],
+            &any,
+        ]
+        .concat();
+        assert_eq!(
+            CosmosTx::new([send_msg(), send_msg()]).to_proto(),
+            Binary::from(expected)
+        );
+
+        // multi-byte lengths
+        let long = AnyMsg {
+            type_url: "/a".to_string(),
+            value: Binary::from(vec![7; 300]),
+        };
+        let proto = CosmosTx::new([long]).to_proto();
+        assert_eq!(
+            &proto[..9],
+            &[0x0a, 0xb3, 0x02, 0x0a, 0x02, b'/', b'a', 0x12, 0xac]
+        );
+        assert_eq!(proto[9], 0x02);
+        assert_eq!(proto.len(), 3 + 4 + 3 + 300);
+    }
+
+    #[test]
+    fn interchain_account_packet_data_serialization_works() {
+        let data =
+            InterchainAccountPacketData::execute_tx(&CosmosTx::new([send_msg()])).with_memo("hi");
+        let json = r#"{"data":"CiMKHC9jb3Ntb3MuYmFuay52MWJldGExLk1zZ1NlbmQSAwECAw==","memo":"hi","type":"TYPE_EXECUTE_TX"}"#;
+        assert_eq!(data.to_binary().unwrap(), json.as_bytes());
+        assert_eq!(
+            from_json::<InterchainAccountPacketData>(json).unwrap(),
+            data
+        );
+
+        // memo is optional
+        let data: InterchainAccountPacketData =
+            from_json(r#"{"type":"TYPE_UNSPECIFIED","data":""}"#).unwrap();
+        assert_eq!(data.packet_type, IcaPacketType::Unspecified);
+        assert_eq!(data.memo, "");
+
+        // empty memo is not serialized
+        let data = InterchainAccountPacketData::execute_tx(&CosmosTx::new([send_msg()]));
+        let json = r#"{"data":"CiMKHC9jb3Ntb3MuYmFuay52MWJldGExLk1zZ1NlbmQSAwECAw==","type":"TYPE_EXECUTE_TX"}"#;
+        assert_eq!(data.to_binary().unwrap(), json.as_bytes());
+    }
+}
@@ -0,0 +1,233 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::errors::{StdError, StdResult};
+use crate::ibc::IbcEndpoint;
+use crate::math::Uint256;
+
+/// The packet data of an [ICS-20] fungible token transfer.
+///
+/// This matches the JSON encoding of `FungibleTokenPacketData` used by ibc-go on the wire,
+/// which sorts the fields alphabetically and omits the memo if it is empty.
+///
+/// [ICS-20]: https://github.com/cosmos/ibc/tree/main/spec/app/ics-020-fungible-token-transfer
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{from_json, Ics20Packet, Uint256};
+/// let packet: Ics20Packet = from_json(
+///     br#"{"amount":"100","denom":"transfer/channel-0/uatom","receiver":"bob","sender":"alice"}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(packet.amount, Uint256::from(100u32));
+/// assert_eq!(packet.memo, "");
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[non_exhaustive]
+pub struct Ics20Packet {
+    /// The amount of tokens to transfer, encoded as a string
+    pub amount: Uint256,
+    /// The denom of the tokens, prefixed with the trace path if the tokens are not native
+    /// to the sending chain, e.g. `transfer/channel-0/uatom`
+    pub denom: String,
+    /// An optional memo, which is an empty string if not set. An empty memo is not serialized.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub memo: String,
+    /// The recipient address on the receiving chain
+    pub receiver: String,
+    /// The sender address on the sending chain
+    pub sender: String,
+}
+
+impl Ics20Packet {
+    pub fn new(
+        denom: impl Into<String>,
+        amount: impl Into<Uint256>,
+        sender: impl Into<String>,
+        receiver: impl Into<String>,
+    ) -> Self {
+        Self {
+            amount: amount.into(),
+            denom: denom.into(),
+            memo: String::new(),
+            receiver: receiver.into(),
+            sender: sender.into(),
+        }
+    }
+
+    /// Sets the memo of the packet.
+    pub fn with_memo(mut self, memo: impl Into<String>) -> Self {
+        self.memo = memo.into();
+        self
+    }
+}
+
+/// Strips the trace prefix `{port_id}/{channel_id}/` of the given remote endpoint from
+/// a voucher denom and returns the remaining denom.
+///
+/// Use this for the denom of a received packet to find out whether tokens are sent back
+/// to the chain they originated from. An error is returned if the denom is not prefixed
+/// with the remote endpoint, i.e. the tokens are not returning.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{parse_voucher_denom, IbcEndpoint};
+/// let remote = IbcEndpoint {
+///     port_id: "transfer".to_string(),
+///     channel_id: "channel-7".to_string(),
+/// };
+/// assert_eq!(parse_voucher_denom("transfer/channel-7/ucosm", &remote).unwrap(), "ucosm");
+/// assert!(parse_voucher_denom("transfer/channel-8/ucosm", &remote).is_err());
+/// ```
+pub fn parse_voucher_denom<'a>(
+    voucher_denom: &'a str,
+    remote_endpoint: &IbcEndpoint,
+) -> StdResult<&'a str> {
+    let mut parts = voucher_denom.splitn(3, '/');
+    let (Some(port_id), Some(channel_id), Some(denom)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(StdError::generic_err(format!(
+            "Denom '{voucher_denom}' is not a voucher denom"
+        )));
+    };
+    if port_id != remote_endpoint.port_id {
+        return Err(StdError::generic_err(format!(
+            "Voucher denom is from port '{port_id}', expected '{}'",
+            remote_endpoint.port_id
+        )));
+    }
+    if channel_id != remote_endpoint.channel_id {
+        return Err(StdError::generic_err(format!(
+            "Voucher denom is from channel '{channel_id}', expected '{}'",
+            remote_endpoint.channel_id
+        )));
+    }
+    Ok(denom)
+}
+
+/// Returns the denom `ibc/{hash}` under which tokens with the given trace path and base
+/// denom (e.g. `transfer/channel-0/uatom`) are held in the bank module.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::ibc_voucher_denom;
+/// assert_eq!(
+///     ibc_voucher_denom("transfer/channel-0/uatom"),
+///     "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+/// );
+/// ```
+pub fn ibc_voucher_denom(denom_trace: &str) -> String {
+    let hash = Sha256::digest(denom_trace.as_bytes());
+    format!("ibc/{}", hex::encode_upper(hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_json, to_json_string};
+
+    fn endpoint() -> IbcEndpoint {
+        IbcEndpoint {
+            port_id: "transfer".to_string(),
+            channel_id: "channel-7".to_string(),
+        }
+    }
+
+    #[test]
+    fn ics20_packet_serialization_works() {
+        let packet = Ics20Packet::new("uatom", 12345u128, "alice", "bob");
+        assert_eq!(
+            to_json_string(&packet).unwrap(),
+            r#"{"amount":"12345","denom":"uatom","receiver":"bob","sender":"alice"}"#
+        );
+
+        let packet = packet.with_memo(r#"{"wasm":{}}"#);
+        let json = r#"{"amount":"12345","denom":"uatom","memo":"{\"wasm\":{}}","receiver":"bob","sender":"alice"}"#;
+        assert_eq!(to_json_string(&packet).unwrap(), json);
+        assert_eq!(from_json::<Ics20Packet>(json).unwrap(), packet);
+    }
+
+    #[test]
+    fn ics20_packet_deserialization_works() {
+        // field order and a missing memo do not matter
+        let packet: Ics20Packet = from_json(
+            r#"{"denom":"transfer/channel-0/uatom","amount":"340282366920938463463374607431768211456","sender":"alice","receiver":"bob"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            packet,
+            Ics20Packet::new(
+                "transfer/channel-0/uatom",
+                Uint256::from(u128::MAX) + Uint256::one(),
+                "alice",
+                "bob"
+            )
+        );
+
+        // invalid amounts
+        from_json::<Ics20Packet>(
+            r#"{"amount":"-1","denom":"uatom","receiver":"bob","sender":"alice"}"#,
+        )
+        .unwrap_err();
+        from_json::<Ics20Packet>(
+            r#"{"amount":1,"denom":"uatom","receiver":"bob","sender":"alice"}"#,
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn parse_voucher_denom_works() {
+        assert_eq!(
+            parse_voucher_denom("transfer/channel-7/ucosm", &endpoint()).unwrap(),
+            "ucosm"
+        );
+        // multi-hop denoms only strip the first hop
+        assert_eq!(
+            parse_voucher_denom("transfer/channel-7/transfer/channel-3/ucosm", &endpoint())
+                .unwrap(),
+            "transfer/channel-3/ucosm"
+        );
+        // denoms containing slashes
+        assert_eq!(
+            parse_voucher_denom("transfer/channel-7/factory/creator/sub", &endpoint()).unwrap(),
+            "factory/creator/sub"
+        );
+    }
+
+    #[test]
+    fn parse_voucher_denom_errors_for_foreign_denoms() {
+        let err = parse_voucher_denom("ucosm", &endpoint()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Denom 'ucosm' is not a voucher denom"
+        );
+        let err = parse_voucher_denom("transfer/ucosm", &endpoint()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Denom 'transfer/ucosm' is not a voucher denom"
+        );
+        let err = parse_voucher_denom("other/channel-7/ucosm", &endpoint()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Voucher denom is from port 'other', expected 'transfer'"
+        );
+        let err = parse_voucher_denom("transfer/channel-8/ucosm", &endpoint()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Voucher denom is from channel 'channel-8', expected 'channel-7'"
+        );
+    }
+
+    #[test]
+    fn ibc_voucher_denom_works() {
+        // ATOM on Osmosis, see https://github.com/osmosis-labs/assetlists
+        assert_eq!(
+            ibc_voucher_denom("transfer/channel-0/uatom"),
+            "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+        );
+    }
+}
@@ -1,3 +1,12 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use crate::binary::Binary;
+use crate::errors::SystemError;
+use crate::results::{ContractResult, SystemResult};
+use crate::serde::from_json;
+use crate::traits::QuerierResult;
+
 /// Returns the four most significant bytes
 #[allow(dead_code)] // only used in Wasm builds
 #[inline]
@@ -12,6 +21,37 @@ pub fn from_low_half(data: u64) -> u32 {
     (data & 0xFFFFFFFF).try_into().unwrap()
 }
 
+/// Decodes the binary encoded result of the `query_chain_binary` import.
+///
+/// The first byte is a tag. 0 means success and is followed by the response data,
+/// 1 means the query returned an error and is followed by a UTF-8 error message,
+/// 2 means a system error and is followed by the JSON encoded [`SystemError`].
+#[allow(dead_code)] // only used in Wasm builds
+pub fn decode_query_result(response: Vec<u8>) -> QuerierResult {
+    let invalid_response = |error: &str, response: Vec<u8>| {
+        SystemResult::Err(SystemError::InvalidResponse {
+            error: error.to_string(),
+            response: response.into(),
+        })
+    };
+
+    let Some((&tag, payload)) = response.split_first() else {
+        return invalid_response("Empty query response", response);
+    };
+    match tag {
+        0 => SystemResult::Ok(ContractResult::Ok(Binary::from(payload))),
+        1 => match core::str::from_utf8(payload) {
+            Ok(msg) => SystemResult::Ok(ContractResult::Err(msg.to_string())),
+            Err(err) => invalid_response(&err.to_string(), response),
+        },
+        2 => match from_json::<SystemError>(payload) {
+            Ok(err) => SystemResult::Err(err),
+            Err(err) => invalid_response(&err.to_string(), response),
+        },
+        _ => invalid_response("Unknown query response tag", response),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -27,4 +67,41 @@ mod tests {
         assert_eq!(from_low_half(0), 0);
         assert_eq!(from_low_half(0x1122334455667788), 0x55667788);
     }
+
+    #[test]
+    fn decode_query_result_works() {
+        assert_eq!(
+            decode_query_result(b"\x00\xFF\x00".to_vec()),
+            SystemResult::Ok(ContractResult::Ok(Binary::from(b"\xFF\x00")))
+        );
+        assert_eq!(
+            decode_query_result(b"\x00".to_vec()),
+            SystemResult::Ok(ContractResult::Ok(Binary::default()))
+        );
+        assert_eq!(
+            decode_query_result(b"\x01broken".to_vec()),
+            SystemResult::Ok(ContractResult::Err("broken".to_string()))
+        );
+        assert_eq!(
+            decode_query_result(b"\x02{\"unknown\":{}}".to_vec()),
+            SystemResult::Err(SystemError::Unknown {})
+        );
+    }
+
+    #[test]
+    fn decode_query_result_handles_invalid_responses() {
+        for response in [
+            b"".to_vec(),
+            b"\x03abc".to_vec(),
+            b"\x01\xFF".to_vec(),
+            b"\x02{}".to_vec(),
+        ] {
+            match decode_query_result(response.clone()) {
+                SystemResult::Err(SystemError::InvalidResponse {
+                    response: returned, ..
+                }) => assert_eq!(returned, response),
+                other => panic!("Unexpected result: {other:?}"),
+            }
+        }
+    }
 }
@@ -1,14 +1,30 @@
 use alloc::vec::Vec;
 
 use crate::addresses::{Addr, CanonicalAddr};
-use crate::errors::{RecoverPubkeyError, StdError, StdResult, SystemError, VerificationError};
+#[cfg(not(feature = "query_chain_binary"))]
+use crate::errors::SystemError;
+use crate::errors::{RecoverPubkeyError, StdError, StdResult, VerificationError};
+#[cfg(feature = "query_chain_binary")]
+use crate::import_helpers::decode_query_result;
 use crate::import_helpers::{from_high_half, from_low_half};
-use crate::memory::{alloc, build_region, consume_region, Region};
+use crate::memory::{
+    alloc, build_region, consume_region, set_spare_buffer, take_spare_buffer, Region,
+};
+#[cfg(feature = "emit_event")]
+use crate::results::Event;
+#[cfg(not(feature = "query_chain_binary"))]
 use crate::results::SystemResult;
+#[cfg(feature = "query_batch")]
+use crate::sections::decode_sections;
 #[cfg(feature = "iterator")]
 use crate::sections::decode_sections2;
 use crate::sections::encode_sections;
+#[cfg(not(feature = "query_chain_binary"))]
 use crate::serde::from_json;
+#[cfg(feature = "emit_event")]
+use crate::serde::to_json_vec;
+#[cfg(feature = "db_size")]
+use crate::traits::StorageSize;
 use crate::traits::{Api, Querier, QuerierResult, Storage};
 #[cfg(feature = "iterator")]
 use crate::{
@@ -31,6 +47,11 @@ extern "C" {
     fn db_read(key: u32) -> u32;
     fn db_write(key: u32, value: u32);
     fn db_remove(key: u32);
+    /// Returns a region with the approximate storage usage of the contract,
+    /// encoded as `keys || bytes` (both u64 big endian).
+    /// Returns 0 if the host does not support this.
+    #[cfg(feature = "db_size")]
+    fn db_size() -> u32;
 
     // scan creates an iterator, which can be read by consecutive next() calls
     #[cfg(feature = "iterator")]
@@ -41,6 +62,10 @@ extern "C" {
     fn db_next_key(iterator_id: u32) -> u32;
     #[cfg(all(feature = "iterator", feature = "cosmwasm_1_4"))]
     fn db_next_value(iterator_id: u32) -> u32;
+    #[cfg(feature = "iterator_seek")]
+    fn db_scan_prefix(prefix_ptr: u32, order: i32) -> u32;
+    #[cfg(feature = "iterator_seek")]
+    fn db_seek(iterator_id: u32, key_ptr: u32);
 
     fn addr_validate(source_ptr: u32) -> u32;
     fn addr_canonicalize(source_ptr: u32, destination_ptr: u32) -> u32;
@@ -70,6 +95,38 @@ extern "C" {
     /// greater than 1 in case of error.
     fn ed25519_batch_verify(messages_ptr: u32, signatures_ptr: u32, public_keys_ptr: u32) -> u32;
 
+    /// Verifies a message against a signature with a public key and a context, using the
+    /// Ed25519ctx variant of the ed25519 EdDSA scheme.
+    /// Returns 0 on verification success, 1 on verification failure, and values
+    /// greater than 1 in case of error.
+    #[cfg(feature = "ed25519_variants")]
+    fn ed25519ctx_verify(
+        message_ptr: u32,
+        context_ptr: u32,
+        signature_ptr: u32,
+        public_key_ptr: u32,
+    ) -> u32;
+
+    /// Verifies a SHA-512 message hash against a signature with a public key and an optional
+    /// context, using the Ed25519ph variant of the ed25519 EdDSA scheme.
+    /// Returns 0 on verification success, 1 on verification failure, and values
+    /// greater than 1 in case of error.
+    #[cfg(feature = "ed25519_variants")]
+    fn ed25519ph_verify(
+        message_hash_ptr: u32,
+        context_ptr: u32,
+        signature_ptr: u32,
+        public_key_ptr: u32,
+    ) -> u32;
+
+    /// Returns the gas that is left for the current call.
+    #[cfg(feature = "gas_remaining")]
+    fn gas_remaining() -> u64;
+
+    /// Passes a JSON encoded event to the host while the contract is executing.
+    #[cfg(feature = "emit_event")]
+    fn emit_event(event_ptr: u32);
+
     /// Writes a debug message (UFT-8 encoded) to the host for debugging purposes.
     /// The host is free to log or process this in any way it considers appropriate.
     /// In production environments it is expected that those messages are discarded.
@@ -77,7 +134,31 @@ extern "C" {
 
     /// Executes a query on the chain (import). Not to be confused with the
     /// query export, which queries the state of the contract.
+    #[cfg(not(feature = "query_chain_binary"))]
     fn query_chain(request: u32) -> u32;
+
+    /// Like `query_chain` but the result is returned in a binary encoding
+    /// that does not require JSON and base64 encoding the response data.
+    #[cfg(feature = "query_chain_binary")]
+    fn query_chain_binary(request: u32) -> u32;
+
+    /// Executes multiple queries like `query_chain_binary` in one call.
+    /// The requests are encoded as sections (see `encode_sections`). The results are
+    /// returned as sections in the same order, each in the encoding of `query_chain_binary`.
+    #[cfg(feature = "query_batch")]
+    fn query_chain_batch(requests: u32) -> u32;
+
+    /// Executes multiple queries like `query_chain_batch`, but each query can use at most
+    /// `gas_limit` gas. A query exceeding its limit results in `SystemError::OutOfGas`
+    /// instead of aborting the execution.
+    #[cfg(feature = "query_batch")]
+    fn query_chain_batch_with_gas_limit(requests: u32, gas_limit: u64) -> u32;
+
+    /// Appends a chunk to the result of the current call. The result is only used
+    /// if the export returns 0 instead of a pointer to a result region.
+    /// Returns the number of bytes that can still be written.
+    #[cfg(feature = "chunked_results")]
+    fn write_region_chunked(chunk_ptr: u32) -> u32;
 }
 
 /// A stateless convenience wrapper around database imports provided by the VM.
@@ -88,6 +169,21 @@ impl ExternalStorage {
     pub fn new() -> ExternalStorage {
         ExternalStorage {}
     }
+
+    /// Like [`Storage::range`] but returns an iterator that can be moved to
+    /// a given key using [`SeekableIterator::seek`].
+    #[cfg(feature = "iterator_seek")]
+    pub fn range_seekable(
+        &self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> SeekableIterator {
+        let iterator_id = create_iter(start, end, order);
+        SeekableIterator {
+            inner: ExternalIterator { iterator_id },
+        }
+    }
 }
 
 impl Storage for ExternalStorage {
@@ -106,6 +202,27 @@ impl Storage for ExternalStorage {
         Some(data)
     }
 
+    fn get_into(&self, key: &[u8], buffer: &mut Vec<u8>) -> bool {
+        let key = build_region(key);
+        let key_ptr = &*key as *const Region as u32;
+
+        // The host allocates the memory for the value by calling `allocate`,
+        // which uses the buffer if the value fits into it
+        set_spare_buffer(core::mem::take(buffer));
+        let read = unsafe { db_read(key_ptr) };
+        // still set if the key does not exist or the value did not fit
+        let unused = take_spare_buffer();
+        if read == 0 {
+            // key does not exist in external storage
+            *buffer = unused.unwrap_or_default();
+            buffer.clear();
+            return false;
+        }
+
+        *buffer = unsafe { consume_region(read as *mut Region) };
+        true
+    }
+
     fn set(&mut self, key: &[u8], value: &[u8]) {
         if value.is_empty() {
             panic!("TL;DR: Value must not be empty in Storage::set but in most cases you can use Storage::remove instead. Long story: Getting empty values from storage is not well supported at the moment. Some of our internal interfaces cannot differentiate between a non-existent key and an empty value. Right now, you cannot rely on the behaviour of empty values. To protect you from trouble later on, we stop here. Sorry for the inconvenience! We highly welcome you to contribute to CosmWasm, making this more solid one way or the other.");
@@ -126,6 +243,22 @@ impl Storage for ExternalStorage {
         unsafe { db_remove(key_ptr) };
     }
 
+    #[cfg(feature = "db_size")]
+    fn size_hint(&self) -> Option<StorageSize> {
+        let result = unsafe { db_size() };
+        if result == 0 {
+            // the host does not support storage size hints
+            return None;
+        }
+        let data: [u8; 16] = unsafe { consume_region(result as *mut Region) }
+            .try_into()
+            .ok()?;
+        Some(StorageSize {
+            keys: u64::from_be_bytes(data[..8].try_into().unwrap()),
+            bytes: u64::from_be_bytes(data[8..].try_into().unwrap()),
+        })
+    }
+
     #[cfg(feature = "iterator")]
     fn range(
         &self,
@@ -138,6 +271,18 @@ impl Storage for ExternalStorage {
         Box::new(iter)
     }
 
+    #[cfg(feature = "iterator_seek")]
+    fn range_prefix<'a>(
+        &'a self,
+        prefix: &[u8],
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        let prefix = build_region(prefix);
+        let prefix_ptr = &*prefix as *const Region as u32;
+        let iterator_id = unsafe { db_scan_prefix(prefix_ptr, order as i32) };
+        Box::new(ExternalIterator { iterator_id })
+    }
+
     #[cfg(all(feature = "cosmwasm_1_4", feature = "iterator"))]
     fn range_keys<'a>(
         &'a self,
@@ -260,6 +405,39 @@ impl Iterator for ExternalIterator {
     }
 }
 
+/// An iterator over storage records that can be moved to a given key.
+/// This is created by [`ExternalStorage::range_seekable`].
+#[cfg(feature = "iterator_seek")]
+pub struct SeekableIterator {
+    inner: ExternalIterator,
+}
+
+#[cfg(feature = "iterator_seek")]
+impl SeekableIterator {
+    /// Moves the iterator such that the next element is the first one whose key is at or
+    /// after `key` in iteration order, i.e. the first key `>= key` for ascending and the
+    /// first key `<= key` for descending iteration. Keys outside of the range the iterator
+    /// was created with are never returned.
+    pub fn seek(&mut self, key: &[u8]) {
+        let key = build_region(key);
+        let key_ptr = &*key as *const Region as u32;
+        unsafe { db_seek(self.inner.iterator_id, key_ptr) };
+    }
+}
+
+#[cfg(feature = "iterator_seek")]
+impl Iterator for SeekableIterator {
+    type Item = Record;
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.inner.nth(n)
+    }
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
 /// Helper function to skip `count` elements of an iterator.
 #[cfg(all(feature = "iterator", feature = "cosmwasm_1_4"))]
 fn skip_iter(iter_id: u32, count: usize) {
@@ -465,6 +643,92 @@ impl Api for ExternalApi {
         }
     }
 
+    #[cfg(feature = "ed25519_variants")]
+    fn ed25519ctx_verify(
+        &self,
+        message: &[u8],
+        context: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        let msg_send = build_region(message);
+        let msg_send_ptr = &*msg_send as *const Region as u32;
+        let context_send = build_region(context);
+        let context_send_ptr = &*context_send as *const Region as u32;
+        let sig_send = build_region(signature);
+        let sig_send_ptr = &*sig_send as *const Region as u32;
+        let pubkey_send = build_region(public_key);
+        let pubkey_send_ptr = &*pubkey_send as *const Region as u32;
+
+        let result = unsafe {
+            ed25519ctx_verify(
+                msg_send_ptr,
+                context_send_ptr,
+                sig_send_ptr,
+                pubkey_send_ptr,
+            )
+        };
+        match result {
+            0 => Ok(true),
+            1 => Ok(false),
+            3 => panic!("InvalidHashFormat must not happen. This is a bug in the VM."),
+            4 => Err(VerificationError::InvalidSignatureFormat),
+            5 => Err(VerificationError::InvalidPubkeyFormat),
+            10 => Err(VerificationError::GenericErr),
+            error_code => Err(VerificationError::unknown_err(error_code)),
+        }
+    }
+
+    #[cfg(feature = "ed25519_variants")]
+    fn ed25519ph_verify(
+        &self,
+        message_hash: &[u8],
+        context: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        let hash_send = build_region(message_hash);
+        let hash_send_ptr = &*hash_send as *const Region as u32;
+        let context_send = build_region(context);
+        let context_send_ptr = &*context_send as *const Region as u32;
+        let sig_send = build_region(signature);
+        let sig_send_ptr = &*sig_send as *const Region as u32;
+        let pubkey_send = build_region(public_key);
+        let pubkey_send_ptr = &*pubkey_send as *const Region as u32;
+
+        let result = unsafe {
+            ed25519ph_verify(
+                hash_send_ptr,
+                context_send_ptr,
+                sig_send_ptr,
+                pubkey_send_ptr,
+            )
+        };
+        match result {
+            0 => Ok(true),
+            1 => Ok(false),
+            3 => Err(VerificationError::InvalidHashFormat),
+            4 => Err(VerificationError::InvalidSignatureFormat),
+            5 => Err(VerificationError::InvalidPubkeyFormat),
+            10 => Err(VerificationError::GenericErr),
+            error_code => Err(VerificationError::unknown_err(error_code)),
+        }
+    }
+
+    #[cfg(feature = "gas_remaining")]
+    fn gas_remaining(&self) -> StdResult<u64> {
+        Ok(unsafe { gas_remaining() })
+    }
+
+    #[cfg(feature = "emit_event")]
+    fn emit_event(&self, event: &Event) -> StdResult<()> {
+        let event = to_json_vec(event)?;
+        let region = build_region(&event);
+        let region_ptr = region.as_ref() as *const Region as u32;
+        unsafe { emit_event(region_ptr) };
+        Ok(())
+    }
+
     fn debug(&self, message: &str) {
         // keep the boxes in scope, so we free it at the end (don't cast to pointers same line as build_region)
         let region = build_region(message.as_bytes());
@@ -491,6 +755,7 @@ impl ExternalQuerier {
 }
 
 impl Querier for ExternalQuerier {
+    #[cfg(not(feature = "query_chain_binary"))]
     fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
         let req = build_region(bin_request);
         let request_ptr = &*req as *const Region as u32;
@@ -505,6 +770,51 @@ impl Querier for ExternalQuerier {
             })
         })
     }
+
+    #[cfg(feature = "query_chain_binary")]
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let req = build_region(bin_request);
+        let request_ptr = &*req as *const Region as u32;
+
+        let response_ptr = unsafe { query_chain_binary(request_ptr) };
+        let response = unsafe { consume_region(response_ptr as *mut Region) };
+
+        decode_query_result(response)
+    }
+
+    #[cfg(feature = "query_batch")]
+    fn raw_query_batch(&self, bin_requests: &[&[u8]]) -> Vec<QuerierResult> {
+        let requests_encoded = encode_sections(bin_requests);
+        let req = build_region(&requests_encoded);
+        let request_ptr = &*req as *const Region as u32;
+
+        let response_ptr = unsafe { query_chain_batch(request_ptr) };
+        let response = unsafe { consume_region(response_ptr as *mut Region) };
+
+        decode_sections(response)
+            .into_iter()
+            .map(decode_query_result)
+            .collect()
+    }
+
+    #[cfg(feature = "query_batch")]
+    fn raw_query_batch_with_gas_limit(
+        &self,
+        bin_requests: &[&[u8]],
+        gas_limit: u64,
+    ) -> Vec<QuerierResult> {
+        let requests_encoded = encode_sections(bin_requests);
+        let req = build_region(&requests_encoded);
+        let request_ptr = &*req as *const Region as u32;
+
+        let response_ptr = unsafe { query_chain_batch_with_gas_limit(request_ptr, gas_limit) };
+        let response = unsafe { consume_region(response_ptr as *mut Region) };
+
+        decode_sections(response)
+            .into_iter()
+            .map(decode_query_result)
+            .collect()
+    }
 }
 
 #[cfg(feature = "abort")]
@@ -514,3 +824,15 @@ pub fn handle_panic(message: &str) {
     let region_ptr = region.as_ref() as *const Region as u32;
     unsafe { abort(region_ptr) };
 }
+
+/// Writes the result of the current call to the host in chunks of at most `chunk_length` bytes.
+/// The export must return 0 afterwards to make the host use the written chunks.
+#[cfg(feature = "chunked_results")]
+pub fn write_result_chunked(result: &[u8], chunk_length: usize) {
+    for chunk in result.chunks(chunk_length) {
+        // keep the boxes in scope, so we free it at the end (don't cast to pointers same line as build_region)
+        let region = build_region(chunk);
+        let region_ptr = region.as_ref() as *const Region as u32;
+        unsafe { write_region_chunked(region_ptr) };
+    }
+}
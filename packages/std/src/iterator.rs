@@ -1,3 +1,6 @@
+#[cfg(feature = "iterator")]
+use alloc::vec::Vec;
+
 use crate::errors::StdError;
 
 /// A record of a key-value storage that is created through an iterator API.
@@ -30,3 +33,107 @@ impl From<Order> for i32 {
         original as _
     }
 }
+
+/// A bound of a raw storage range, used by [`Storage::range_bounded`](crate::Storage::range_bounded).
+///
+/// In contrast to the `start` and `end` arguments of [`Storage::range`](crate::Storage::range),
+/// both lower and upper bounds can be inclusive or exclusive. This makes it easy to continue
+/// a paginated query after the last returned key, no matter of the order.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{testing::MockStorage, Bound, Order, Storage};
+/// let mut storage = MockStorage::new();
+/// storage.set(b"a", b"1");
+/// storage.set(b"b", b"2");
+/// storage.set(b"c", b"3");
+///
+/// // next page after "b" in descending order
+/// let last_key = b"b".to_vec();
+/// let page: Vec<_> = storage
+///     .range_bounded(None, Some(Bound::exclusive(last_key)), Order::Descending)
+///     .map(|(key, _)| key)
+///     .collect();
+/// assert_eq!(page, vec![b"a".to_vec()]);
+/// ```
+#[cfg(feature = "iterator")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Bound {
+    Inclusive(Vec<u8>),
+    Exclusive(Vec<u8>),
+}
+
+#[cfg(feature = "iterator")]
+impl Bound {
+    pub fn inclusive(key: impl Into<Vec<u8>>) -> Self {
+        Bound::Inclusive(key.into())
+    }
+
+    pub fn exclusive(key: impl Into<Vec<u8>>) -> Self {
+        Bound::Exclusive(key.into())
+    }
+
+    /// Converts a lower bound into the inclusive `start` argument of [`Storage::range`](crate::Storage::range)
+    pub(crate) fn to_start(&self) -> Vec<u8> {
+        match self {
+            Bound::Inclusive(key) => key.clone(),
+            Bound::Exclusive(key) => key_successor(key),
+        }
+    }
+
+    /// Converts an upper bound into the exclusive `end` argument of [`Storage::range`](crate::Storage::range)
+    pub(crate) fn to_end(&self) -> Vec<u8> {
+        match self {
+            Bound::Inclusive(key) => key_successor(key),
+            Bound::Exclusive(key) => key.clone(),
+        }
+    }
+}
+
+/// Returns the smallest key that is greater than `key`
+#[cfg(feature = "iterator")]
+fn key_successor(key: &[u8]) -> Vec<u8> {
+    let mut next = Vec::with_capacity(key.len() + 1);
+    next.extend_from_slice(key);
+    next.push(0);
+    next
+}
+
+/// Returns the smallest key that is greater than all keys starting with `prefix`,
+/// or `None` if there is no such key (i.e. the prefix is empty or consists of 0xFF bytes only).
+#[cfg(feature = "iterator")]
+pub fn prefix_upper_bound(prefix: &[u8]) -> Option<alloc::vec::Vec<u8>> {
+    let mut end = prefix.to_vec();
+    while let Some(last) = end.pop() {
+        if last < 0xFF {
+            end.push(last + 1);
+            return Some(end);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn bound_conversion_works() {
+        assert_eq!(Bound::inclusive(b"ab".to_vec()).to_start(), b"ab");
+        assert_eq!(Bound::exclusive(b"ab".to_vec()).to_start(), b"ab\0");
+        assert_eq!(Bound::inclusive(b"ab".to_vec()).to_end(), b"ab\0");
+        assert_eq!(Bound::exclusive(b"ab".to_vec()).to_end(), b"ab");
+        assert_eq!(Bound::exclusive(vec![]).to_start(), b"\0");
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn prefix_upper_bound_works() {
+        assert_eq!(prefix_upper_bound(b""), None);
+        assert_eq!(prefix_upper_bound(b"ab"), Some(b"ac".to_vec()));
+        assert_eq!(prefix_upper_bound(b"a\xff"), Some(b"b".to_vec()));
+        assert_eq!(prefix_upper_bound(b"\xff\xff"), None);
+    }
+}
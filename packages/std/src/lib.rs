@@ -1,127 +1,216 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
 extern crate alloc;
 
 // Exposed on all platforms
 
+#[cfg(feature = "std")]
 mod addresses;
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+#[cfg(feature = "std")]
 mod assertions;
 mod binary;
+#[cfg(feature = "borsh")]
+mod borsh;
+mod canonical_json;
+#[cfg(feature = "std")]
 mod checksum;
 mod coin;
 mod coins;
+#[cfg(feature = "std")]
 mod conversion;
+#[cfg(feature = "std")]
 mod deps;
 mod errors;
+pub mod features;
 mod forward_ref;
 mod hex_binary;
+#[cfg(feature = "std")]
 mod ibc;
+#[cfg(feature = "stargate")]
+mod ica;
+#[cfg(feature = "std")]
+mod ics20;
+#[cfg(feature = "std")]
 mod import_helpers;
 #[cfg(feature = "iterator")]
 mod iterator;
 mod math;
+#[cfg(feature = "std")]
 mod metadata;
+#[cfg(feature = "std")]
 mod never;
+#[cfg(feature = "std")]
 mod pagination;
+#[cfg(feature = "std")]
 mod panic;
+#[cfg(feature = "std")]
 mod query;
+#[cfg(feature = "std")]
 mod results;
+mod secp256k1;
+#[cfg(feature = "std")]
 mod sections;
 mod serde;
+#[cfg(feature = "std")]
 mod stdack;
+#[cfg(feature = "std")]
 mod storage;
 mod timestamp;
+#[cfg(feature = "std")]
 mod traits;
+#[cfg(feature = "std")]
 mod types;
 
 /// This modules is very advanced and will not be used directly by the vast majority of users.
 /// We want to offer it to ensure a stable storage key composition system but don't encourage
 /// contract devs to use it directly.
+#[cfg(feature = "std")]
 pub mod storage_keys;
 
-pub use crate::addresses::{instantiate2_address, Addr, CanonicalAddr, Instantiate2AddressError};
+#[cfg(feature = "std")]
+pub use crate::addresses::{
+    instantiate2_address, Addr, AddrFormatError, AddressFormat, CanonicalAddr,
+    Instantiate2AddressError,
+};
 pub use crate::binary::Binary;
+#[cfg(feature = "std")]
 pub use crate::checksum::{Checksum, ChecksumError};
 pub use crate::coin::{coin, coins, has_coins, Coin};
 pub use crate::coins::Coins;
-pub use crate::deps::{Deps, DepsMut, OwnedDeps};
+#[cfg(feature = "std")]
+pub use crate::deps::{Deps, DepsMut, HostApi, OwnedDeps};
 pub use crate::errors::{
     CheckedFromRatioError, CheckedMultiplyFractionError, CheckedMultiplyRatioError,
     CoinFromStrError, CoinsError, ConversionOverflowError, DivideByZeroError, DivisionError,
-    OverflowError, OverflowOperation, RecoverPubkeyError, StdError, StdResult, SystemError,
-    VerificationError,
+    IbcTimeoutError, OverflowError, OverflowOperation, RecoverPubkeyError, RoundDownOverflowError,
+    RoundUpOverflowError, RoundingError, StdError, StdErrorKind, StdErrorPayload, StdResult,
+    SystemError, VerificationError,
 };
 pub use crate::hex_binary::HexBinary;
+#[cfg(feature = "std")]
 pub use crate::ibc::IbcChannelOpenResponse;
+#[cfg(feature = "std")]
 pub use crate::ibc::{
     Ibc3ChannelOpenResponse, IbcAcknowledgement, IbcBasicResponse, IbcChannel, IbcChannelCloseMsg,
     IbcChannelConnectMsg, IbcChannelOpenMsg, IbcEndpoint, IbcMsg, IbcOrder, IbcPacket,
     IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, IbcTimeout,
     IbcTimeoutBlock,
 };
+#[cfg(feature = "stargate")]
+pub use crate::ica::{
+    CosmosTx, IcaMetadata, IcaPacketType, InterchainAccountPacketData, ICA_VERSION,
+};
+#[cfg(feature = "std")]
+pub use crate::ics20::{ibc_voucher_denom, parse_voucher_denom, Ics20Packet};
+#[cfg(feature = "iterator")]
+pub use crate::iterator::Bound;
 #[cfg(feature = "iterator")]
-pub use crate::iterator::{Order, Record};
+pub use crate::iterator::{prefix_upper_bound, Order, Record};
 pub use crate::math::{
     Decimal, Decimal256, Decimal256RangeExceeded, DecimalRangeExceeded, Fraction, Int128, Int256,
-    Int512, Int64, Isqrt, SignedDecimal, SignedDecimal256, SignedDecimal256RangeExceeded,
-    SignedDecimalRangeExceeded, Uint128, Uint256, Uint512, Uint64,
+    Int512, Int64, Isqrt, NumericUint128, SignedDecimal, SignedDecimal256,
+    SignedDecimal256RangeExceeded, SignedDecimalRangeExceeded, StrictDecimal, Uint128, Uint256,
+    Uint512, Uint64,
 };
+#[cfg(feature = "std")]
 pub use crate::metadata::{DenomMetadata, DenomUnit};
+#[cfg(feature = "std")]
 pub use crate::never::Never;
+#[cfg(feature = "std")]
 pub use crate::pagination::PageRequest;
+#[cfg(feature = "ibc_channel_filters")]
+pub use crate::query::IbcChannelState;
+#[cfg(feature = "std")]
 pub use crate::query::{
     AllBalanceResponse, AllDelegationsResponse, AllDenomMetadataResponse, AllValidatorsResponse,
-    BalanceResponse, BankQuery, BondedDenomResponse, ChannelResponse, CodeInfoResponse,
+    BalanceResponse, BankQuery, BondStatus, BondedDenomResponse, ChannelResponse, CodeInfoResponse,
     ContractInfoResponse, CustomQuery, DecCoin, Delegation, DelegationResponse,
     DelegationRewardsResponse, DelegationTotalRewardsResponse, DelegatorReward,
     DelegatorValidatorsResponse, DelegatorWithdrawAddressResponse, DenomMetadataResponse,
     DistributionQuery, FullDelegation, IbcQuery, ListChannelsResponse, PortIdResponse,
     QueryRequest, StakingQuery, SupplyResponse, Validator, ValidatorResponse, WasmQuery,
 };
+#[cfg(feature = "chain_queries")]
+pub use crate::query::{CapabilitiesResponse, ChainQuery};
+#[cfg(feature = "token_factory")]
+pub use crate::query::{
+    DenomAdminResponse, DenomsByCreatorResponse, FullDenomResponse, TokenFactoryParamsResponse,
+    TokenFactoryQuery,
+};
+#[cfg(feature = "randomness")]
+pub use crate::query::{RandomnessQuery, RandomnessResponse};
+#[cfg(feature = "staking_pool_params")]
+pub use crate::query::{StakingParamsResponse, StakingPoolResponse};
+#[cfg(feature = "token_factory")]
+pub use crate::results::TokenFactoryMsg;
 #[cfg(all(feature = "stargate", feature = "cosmwasm_1_2"))]
 pub use crate::results::WeightedVoteOption;
+#[cfg(feature = "std")]
 pub use crate::results::{
-    attr, wasm_execute, wasm_instantiate, AnyMsg, Attribute, BankMsg, ContractResult, CosmosMsg,
-    CustomMsg, Empty, Event, QueryResponse, Reply, ReplyOn, Response, SubMsg, SubMsgResponse,
-    SubMsgResult, SystemResult, WasmMsg,
+    attr, attr_amount, attr_coin, wasm_execute, wasm_instantiate, AnyMsg, Attribute,
+    AttributeValue, BankMsg, ContractResult, CosmosMsg, CustomMsg, Empty, Event, MessagePlan,
+    QueryResponse, Reply, ReplyId, ReplyOn, Response, SubMsg, SubMsgResponse, SubMsgResult,
+    SystemResult, WasmMsg, IBC_ACK_TYPE_URL,
 };
 #[cfg(feature = "staking")]
 pub use crate::results::{DistributionMsg, StakingMsg};
 #[cfg(feature = "stargate")]
 pub use crate::results::{GovMsg, VoteOption};
+pub use crate::secp256k1::{
+    secp256k1_normalize_signature, secp256k1_signature_from_der, Secp256k1SignatureError,
+};
 #[allow(deprecated)]
 pub use crate::serde::{
-    from_binary, from_json, from_slice, to_binary, to_json_binary, to_json_string, to_json_vec,
-    to_vec,
+    from_binary, from_json, from_slice, to_binary, to_json_binary, to_json_binary_canonical,
+    to_json_string, to_json_vec, to_json_vec_canonical, to_vec,
 };
+#[cfg(feature = "std")]
 pub use crate::stdack::StdAck;
+#[cfg(feature = "std")]
 pub use crate::storage::MemoryStorage;
 pub use crate::timestamp::Timestamp;
-pub use crate::traits::{Api, Querier, QuerierResult, QuerierWrapper, Storage};
+#[cfg(feature = "std")]
+pub use crate::traits::{Api, Querier, QuerierResult, QuerierWrapper, Storage, StorageSize};
+#[cfg(feature = "std")]
 pub use crate::types::{BlockInfo, ContractInfo, Env, MessageInfo, TransactionInfo};
 
 // Exposed in wasm build only
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(feature = "arena_allocator", target_arch = "wasm32"))]
+mod arena;
+#[cfg(all(feature = "std", target_arch = "wasm32"))]
 mod exports;
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(feature = "std", target_arch = "wasm32"))]
 mod imports;
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(feature = "std", target_arch = "wasm32"))]
 mod memory; // Used by exports and imports only. This assumes pointers are 32 bit long, which makes it untestable on dev machines.
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(feature = "std", target_arch = "wasm32"))]
 pub use crate::exports::{do_execute, do_instantiate, do_migrate, do_query, do_reply, do_sudo};
 #[cfg(all(feature = "stargate", target_arch = "wasm32"))]
 pub use crate::exports::{
     do_ibc_channel_close, do_ibc_channel_connect, do_ibc_channel_open, do_ibc_packet_ack,
     do_ibc_packet_receive, do_ibc_packet_timeout,
 };
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(feature = "iterator_seek", target_arch = "wasm32"))]
+pub use crate::imports::SeekableIterator;
+#[cfg(all(feature = "std", target_arch = "wasm32"))]
 pub use crate::imports::{ExternalApi, ExternalQuerier, ExternalStorage};
 
+// Used by the code generated by the entry_point macro
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub mod entry_point_check;
+
 /// Exposed for testing only
 /// Both unit tests and integration tests are compiled to native code, so everything in here does not need to compile to Wasm.
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
 pub mod testing;
 
 // Re-exports
 
-pub use cosmwasm_derive::entry_point;
+#[cfg(feature = "std")]
+pub use cosmwasm_derive::{cw_error, entry_point};
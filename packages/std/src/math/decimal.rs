@@ -1,9 +1,9 @@
+use crate::forward_ref::{forward_ref_binop, forward_ref_op_assign};
+use alloc::{format, string::ToString};
 use core::cmp::Ordering;
 use core::fmt::{self, Write};
 use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign};
 use core::str::FromStr;
-use forward_ref::{forward_ref_binop, forward_ref_op_assign};
-use schemars::JsonSchema;
 use serde::{de, ser, Deserialize, Deserializer, Serialize};
 use thiserror::Error;
 
@@ -20,8 +20,9 @@ use super::{Uint128, Uint256};
 /// A fixed-point decimal value with 18 fractional digits, i.e. Decimal(1_000_000_000_000_000_000) == 1.0
 ///
 /// The greatest possible value that can be represented is 340282366920938463463.374607431768211455 (which is (2^128 - 1) / 10^18)
-#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
-pub struct Decimal(#[schemars(with = "String")] Uint128);
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+pub struct Decimal(#[cfg_attr(feature = "std", schemars(with = "String"))] Uint128);
 
 forward_ref_partial_eq!(Decimal, Decimal);
 
@@ -125,7 +126,9 @@ impl Decimal {
     /// the decimal 1.23.
     ///
     /// Using 18 decimal places is slightly more efficient than other values
-    /// as no internal conversion is necessary.
+    /// as no internal conversion is necessary. With more than 18 decimal places,
+    /// the additional digits are truncated, i.e. the value is rounded down.
+    /// An error is returned if the value exceeds the range of the type.
     ///
     /// ## Examples
     ///
@@ -139,6 +142,9 @@ impl Decimal {
     ///
     /// let a = Decimal::from_atomics(1u64, 18).unwrap();
     /// assert_eq!(a.to_string(), "0.000000000000000001");
+    ///
+    /// let a = Decimal::from_atomics(1234u64, 20).unwrap();
+    /// assert_eq!(a.to_string(), "0.000000000000000012");
     /// ```
     pub fn from_atomics(
         atomics: impl Into<Uint128>,
@@ -171,6 +177,29 @@ impl Decimal {
         })
     }
 
+    /// Creates a decimal from a number of atomic units given as a [`Uint256`]
+    /// and the number of decimal places. This works like [`Decimal::from_atomics`],
+    /// but accepts atomics exceeding the `Uint128` range as long as the resulting
+    /// decimal fits into a `Decimal`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{Decimal, Uint256};
+    /// // 1.5 with 40 decimal places
+    /// let atomics = Uint256::from(15u128) * Uint256::from(10u128).pow(39);
+    /// let a = Decimal::from_atomics_u256(atomics, 40).unwrap();
+    /// assert_eq!(a.to_string(), "1.5");
+    /// ```
+    pub fn from_atomics_u256(
+        atomics: impl Into<Uint256>,
+        decimal_places: u32,
+    ) -> Result<Self, DecimalRangeExceeded> {
+        Decimal256::from_atomics(atomics, decimal_places)
+            .map_err(|_| DecimalRangeExceeded)?
+            .try_into()
+    }
+
     /// Returns the ratio (numerator / denominator) as a Decimal
     pub fn from_ratio(numerator: impl Into<Uint128>, denominator: impl Into<Uint128>) -> Self {
         match Decimal::checked_from_ratio(numerator, denominator) {
@@ -230,6 +259,24 @@ impl Decimal {
         self.0
     }
 
+    /// Returns the atomic units as a [`Uint256`]. Since `Decimal` and [`Decimal256`]
+    /// have the same number of decimal places, the result equals the atomics of the
+    /// corresponding `Decimal256`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{Decimal, Decimal256, Uint256};
+    /// let a = Decimal::percent(150);
+    /// assert_eq!(a.atomics_u256(), Uint256::from(1500000000000000000u128));
+    /// assert_eq!(a.atomics_u256(), Decimal256::from(a).atomics());
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn atomics_u256(&self) -> Uint256 {
+        Uint256::from_u128(self.0.u128())
+    }
+
     /// The number of decimal places. This is a constant value for now
     /// but this could potentially change as the type evolves.
     ///
@@ -377,6 +424,54 @@ impl Decimal {
         Self(self.0.abs_diff(other.0))
     }
 
+    /// Linearly interpolates between `a` and `b`, i.e. returns `a + (b - a) * t`.
+    ///
+    /// `t` is clamped to the range from zero to one, such that the result always lies
+    /// between `a` and `b`. The offset from `a` is rounded down, i.e. the result is rounded
+    /// towards `a`. This never overflows.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::Decimal;
+    /// let start = Decimal::percent(300);
+    /// let end = Decimal::percent(100);
+    /// assert_eq!(Decimal::interpolate(start, end, Decimal::percent(25)), Decimal::percent(250));
+    /// assert_eq!(Decimal::interpolate(start, end, Decimal::percent(150)), end);
+    /// ```
+    #[must_use = "this returns the result of the operation, without modifying the original"]
+    pub fn interpolate(a: Self, b: Self, t: Self) -> Self {
+        let t = t.min(Self::one());
+        // Cannot overflow since the product is at most the difference
+        let offset = a.abs_diff(b) * t;
+        if a <= b {
+            a + offset
+        } else {
+            a - offset
+        }
+    }
+
+    /// Restricts the value to the range between `a` and `b` (inclusive), which can be
+    /// passed in any order. Unlike [`Ord::clamp`], this does not panic if `a` is greater
+    /// than `b`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::Decimal;
+    /// let price = Decimal::percent(120);
+    /// assert_eq!(price.clamp_between(Decimal::one(), Decimal::percent(110)), Decimal::percent(110));
+    /// assert_eq!(price.clamp_between(Decimal::percent(110), Decimal::one()), Decimal::percent(110));
+    /// ```
+    #[must_use = "this returns the result of the operation, without modifying the original"]
+    pub fn clamp_between(self, a: Self, b: Self) -> Self {
+        if a <= b {
+            self.clamp(a, b)
+        } else {
+            self.clamp(b, a)
+        }
+    }
+
     #[must_use = "this returns the result of the operation, without modifying the original"]
     pub fn saturating_add(self, other: Self) -> Self {
         match self.checked_add(other) {
@@ -954,6 +1049,39 @@ mod tests {
         assert_eq!(result.unwrap_err(), DecimalRangeExceeded);
     }
 
+    #[test]
+    fn decimal_from_atomics_u256_works() {
+        let one = Decimal::one();
+
+        assert_eq!(
+            Decimal::from_atomics_u256(Uint256::from(1234u128), 3).unwrap(),
+            Decimal::from_atomics(1234u128, 3).unwrap()
+        );
+        assert_eq!(Decimal::from_atomics_u256(1u128, 0).unwrap(), one);
+        // atomics exceeding the Uint128 range
+        assert_eq!(
+            Decimal::from_atomics_u256(Uint256::from(10u128).pow(40), 40).unwrap(),
+            one
+        );
+        assert_eq!(
+            Decimal::from_atomics_u256(Uint256::MAX, 100).unwrap(),
+            Decimal::zero()
+        );
+        // Uint128::MAX atomics with 18 decimal places is the max
+        assert_eq!(
+            Decimal::from_atomics_u256(Uint256::from(u128::MAX), 18).unwrap(),
+            Decimal::MAX
+        );
+        assert_eq!(
+            Decimal::from_atomics_u256(Uint256::from(u128::MAX) + Uint256::one(), 18).unwrap_err(),
+            DecimalRangeExceeded
+        );
+        assert_eq!(
+            Decimal::from_atomics_u256(Uint256::MAX, 18).unwrap_err(),
+            DecimalRangeExceeded
+        );
+    }
+
     #[test]
     fn decimal_from_ratio_works() {
         // 1.0
@@ -1072,93 +1200,89 @@ mod tests {
 
     #[test]
     fn decimal_from_str_errors_for_broken_whole_part() {
-        match Decimal::from_str("").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Error parsing whole"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal::from_str("").unwrap_err(),
+            StdError::generic_err("Error parsing whole")
+        );
 
-        match Decimal::from_str(" ").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Error parsing whole"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal::from_str(" ").unwrap_err(),
+            StdError::generic_err("Error parsing whole")
+        );
 
-        match Decimal::from_str("-1").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Error parsing whole"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal::from_str("-1").unwrap_err(),
+            StdError::generic_err("Error parsing whole")
+        );
     }
 
     #[test]
     fn decimal_from_str_errors_for_broken_fractional_part() {
-        match Decimal::from_str("1.").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Error parsing fractional"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal::from_str("1.").unwrap_err(),
+            StdError::generic_err("Error parsing fractional")
+        );
 
-        match Decimal::from_str("1. ").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Error parsing fractional"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal::from_str("1. ").unwrap_err(),
+            StdError::generic_err("Error parsing fractional")
+        );
 
-        match Decimal::from_str("1.e").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Error parsing fractional"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal::from_str("1.e").unwrap_err(),
+            StdError::generic_err("Error parsing fractional")
+        );
 
-        match Decimal::from_str("1.2e3").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Error parsing fractional"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal::from_str("1.2e3").unwrap_err(),
+            StdError::generic_err("Error parsing fractional")
+        );
     }
 
     #[test]
     fn decimal_from_str_errors_for_more_than_18_fractional_digits() {
-        match Decimal::from_str("7.1234567890123456789").unwrap_err() {
-            StdError::GenericErr { msg, .. } => {
-                assert_eq!(msg, "Cannot parse more than 18 fractional digits",)
-            }
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal::from_str("7.1234567890123456789").unwrap_err(),
+            StdError::generic_err("Cannot parse more than 18 fractional digits")
+        );
 
         // No special rules for trailing zeros. This could be changed but adds gas cost for the happy path.
-        match Decimal::from_str("7.1230000000000000000").unwrap_err() {
-            StdError::GenericErr { msg, .. } => {
-                assert_eq!(msg, "Cannot parse more than 18 fractional digits")
-            }
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal::from_str("7.1230000000000000000").unwrap_err(),
+            StdError::generic_err("Cannot parse more than 18 fractional digits")
+        );
     }
 
     #[test]
     fn decimal_from_str_errors_for_invalid_number_of_dots() {
-        match Decimal::from_str("1.2.3").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Unexpected number of dots"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal::from_str("1.2.3").unwrap_err(),
+            StdError::generic_err("Unexpected number of dots")
+        );
 
-        match Decimal::from_str("1.2.3.4").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Unexpected number of dots"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal::from_str("1.2.3.4").unwrap_err(),
+            StdError::generic_err("Unexpected number of dots")
+        );
     }
 
     #[test]
     fn decimal_from_str_errors_for_more_than_max_value() {
         // Integer
-        match Decimal::from_str("340282366920938463464").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Value too big"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal::from_str("340282366920938463464").unwrap_err(),
+            StdError::generic_err("Value too big")
+        );
 
         // Decimal
-        match Decimal::from_str("340282366920938463464.0").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Value too big"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
-        match Decimal::from_str("340282366920938463463.374607431768211456").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Value too big"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal::from_str("340282366920938463464.0").unwrap_err(),
+            StdError::generic_err("Value too big")
+        );
+        assert_eq!(
+            Decimal::from_str("340282366920938463463.374607431768211456").unwrap_err(),
+            StdError::generic_err("Value too big")
+        );
     }
 
     #[test]
@@ -1176,6 +1300,24 @@ mod tests {
         assert_eq!(max.atomics(), Uint128::MAX);
     }
 
+    #[test]
+    fn decimal_atomics_u256_works() {
+        for value in [
+            Decimal::zero(),
+            Decimal::one(),
+            Decimal::percent(50),
+            Decimal::percent(200),
+            Decimal::MAX,
+        ] {
+            assert_eq!(value.atomics_u256(), Uint256::from(value.atomics()));
+            assert_eq!(value.atomics_u256(), Decimal256::from(value).atomics());
+            assert_eq!(
+                Decimal::from_atomics_u256(value.atomics_u256(), value.decimal_places()).unwrap(),
+                value
+            );
+        }
+    }
+
     #[test]
     fn decimal_decimal_places_works() {
         let zero = Decimal::zero();
@@ -1924,6 +2066,63 @@ mod tests {
         assert_eq!(b.abs_diff(a), expected);
     }
 
+    #[test]
+    fn decimal_interpolate_works() {
+        let a = Decimal::percent(100);
+        let b = Decimal::percent(300);
+
+        assert_eq!(Decimal::interpolate(a, b, Decimal::zero()), a);
+        assert_eq!(
+            Decimal::interpolate(a, b, Decimal::percent(50)),
+            Decimal::percent(200)
+        );
+        assert_eq!(Decimal::interpolate(a, b, Decimal::one()), b);
+        assert_eq!(
+            Decimal::interpolate(b, a, Decimal::percent(25)),
+            Decimal::percent(250)
+        );
+        assert_eq!(Decimal::interpolate(a, a, Decimal::percent(50)), a);
+
+        // t is clamped to one
+        assert_eq!(Decimal::interpolate(a, b, Decimal::percent(200)), b);
+        assert_eq!(Decimal::interpolate(b, a, Decimal::MAX), a);
+
+        // rounds towards a
+        let t = Decimal::from_ratio(1u128, 3u128);
+        let one_atomic = Decimal::from_atomics(1u128, 18).unwrap();
+        let up = Decimal::interpolate(Decimal::zero(), one_atomic * Decimal::percent(200), t);
+        assert_eq!(up, Decimal::zero());
+        let down = Decimal::interpolate(one_atomic * Decimal::percent(200), Decimal::zero(), t);
+        assert_eq!(down, one_atomic * Decimal::percent(200));
+
+        // no overflow at the limits
+        assert_eq!(
+            Decimal::interpolate(Decimal::zero(), Decimal::MAX, Decimal::one()),
+            Decimal::MAX
+        );
+        assert_eq!(
+            Decimal::interpolate(Decimal::MAX, Decimal::zero(), Decimal::one()),
+            Decimal::zero()
+        );
+        assert!(
+            Decimal::interpolate(Decimal::zero(), Decimal::MAX, Decimal::percent(50))
+                <= Decimal::MAX
+        );
+    }
+
+    #[test]
+    fn decimal_clamp_between_works() {
+        let low = Decimal::percent(50);
+        let high = Decimal::percent(150);
+
+        for (min, max) in [(low, high), (high, low)] {
+            assert_eq!(Decimal::zero().clamp_between(min, max), low);
+            assert_eq!(Decimal::one().clamp_between(min, max), Decimal::one());
+            assert_eq!(Decimal::MAX.clamp_between(min, max), high);
+        }
+        assert_eq!(Decimal::MAX.clamp_between(low, low), low);
+    }
+
     #[test]
     #[allow(clippy::op_ref)]
     fn decimal_rem_works() {
@@ -1,9 +1,9 @@
+use crate::forward_ref::{forward_ref_binop, forward_ref_op_assign};
+use alloc::{format, string::ToString};
 use core::cmp::Ordering;
 use core::fmt::{self, Write};
 use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign};
 use core::str::FromStr;
-use forward_ref::{forward_ref_binop, forward_ref_op_assign};
-use schemars::JsonSchema;
 use serde::{de, ser, Deserialize, Deserializer, Serialize};
 use thiserror::Error;
 
@@ -22,8 +22,9 @@ use super::Uint256;
 /// The greatest possible value that can be represented is
 /// 115792089237316195423570985008687907853269984665640564039457.584007913129639935
 /// (which is (2^256 - 1) / 10^18)
-#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
-pub struct Decimal256(#[schemars(with = "String")] Uint256);
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+pub struct Decimal256(#[cfg_attr(feature = "std", schemars(with = "String"))] Uint256);
 
 forward_ref_partial_eq!(Decimal256, Decimal256);
 
@@ -128,7 +129,9 @@ impl Decimal256 {
     /// the decimal 1.23.
     ///
     /// Using 18 decimal places is slightly more efficient than other values
-    /// as no internal conversion is necessary.
+    /// as no internal conversion is necessary. With more than 18 decimal places,
+    /// the additional digits are truncated, i.e. the value is rounded down.
+    /// An error is returned if the value exceeds the range of the type.
     ///
     /// ## Examples
     ///
@@ -143,6 +146,9 @@ impl Decimal256 {
     /// let a = Decimal256::from_atomics(1u64, 18).unwrap();
     /// assert_eq!(a.to_string(), "0.000000000000000001");
     ///
+    /// let a = Decimal256::from_atomics(1234u64, 20).unwrap();
+    /// assert_eq!(a.to_string(), "0.000000000000000012");
+    ///
     /// let a = Decimal256::from_atomics(Uint256::MAX, 18).unwrap();
     /// assert_eq!(a, Decimal256::MAX);
     /// ```
@@ -390,6 +396,54 @@ impl Decimal256 {
         }
     }
 
+    /// Linearly interpolates between `a` and `b`, i.e. returns `a + (b - a) * t`.
+    ///
+    /// `t` is clamped to the range from zero to one, such that the result always lies
+    /// between `a` and `b`. The offset from `a` is rounded down, i.e. the result is rounded
+    /// towards `a`. This never overflows.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::Decimal256;
+    /// let start = Decimal256::percent(300);
+    /// let end = Decimal256::percent(100);
+    /// assert_eq!(Decimal256::interpolate(start, end, Decimal256::percent(25)), Decimal256::percent(250));
+    /// assert_eq!(Decimal256::interpolate(start, end, Decimal256::percent(150)), end);
+    /// ```
+    #[must_use = "this returns the result of the operation, without modifying the original"]
+    pub fn interpolate(a: Self, b: Self, t: Self) -> Self {
+        let t = t.min(Self::one());
+        // Cannot overflow since the product is at most the difference
+        let offset = a.abs_diff(b) * t;
+        if a <= b {
+            a + offset
+        } else {
+            a - offset
+        }
+    }
+
+    /// Restricts the value to the range between `a` and `b` (inclusive), which can be
+    /// passed in any order. Unlike [`Ord::clamp`], this does not panic if `a` is greater
+    /// than `b`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::Decimal256;
+    /// let price = Decimal256::percent(120);
+    /// assert_eq!(price.clamp_between(Decimal256::one(), Decimal256::percent(110)), Decimal256::percent(110));
+    /// assert_eq!(price.clamp_between(Decimal256::percent(110), Decimal256::one()), Decimal256::percent(110));
+    /// ```
+    #[must_use = "this returns the result of the operation, without modifying the original"]
+    pub fn clamp_between(self, a: Self, b: Self) -> Self {
+        if a <= b {
+            self.clamp(a, b)
+        } else {
+            self.clamp(b, a)
+        }
+    }
+
     #[must_use = "this returns the result of the operation, without modifying the original"]
     pub fn saturating_add(self, other: Self) -> Self {
         match self.checked_add(other) {
@@ -540,11 +594,20 @@ impl FromStr for Decimal256 {
 
     /// Converts the decimal string to a Decimal256
     /// Possible inputs: "1.23", "1", "000012", "1.123000000"
-    /// Disallowed: "", ".23"
+    /// Disallowed: "", ".23", "+1", "1e3"
     ///
     /// This never performs any kind of rounding.
     /// More than DECIMAL_PLACES fractional digits, even zeros, result in an error.
     fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.contains(['e', 'E']) {
+            return Err(StdError::generic_err(
+                "Scientific notation is not supported",
+            ));
+        }
+        if input.starts_with('+') {
+            return Err(StdError::generic_err("Leading '+' is not allowed"));
+        }
+
         let mut parts_iter = input.split('.');
 
         let whole_part = parts_iter.next().unwrap(); // split always returns at least one element
@@ -556,6 +619,10 @@ impl FromStr for Decimal256 {
             .map_err(|_| StdError::generic_err("Value too big"))?;
 
         if let Some(fractional_part) = parts_iter.next() {
+            // `Uint256` parsing accepts a sign, which must not end up in the fractional part
+            if fractional_part.starts_with('+') {
+                return Err(StdError::generic_err("Error parsing fractional"));
+            }
             let fractional = fractional_part
                 .parse::<Uint256>()
                 .map_err(|_| StdError::generic_err("Error parsing fractional"))?;
@@ -779,6 +846,93 @@ impl<'de> de::Visitor<'de> for Decimal256Visitor {
     }
 }
 
+/// A [`Decimal256`] that only accepts its canonical string representation, i.e. the
+/// output of its `Display` implementation.
+///
+/// In contrast to [`Decimal256`], leading zeros in the whole part (e.g. "01.5") and
+/// trailing zeros in the fractional part (e.g. "1.50" or "1.0") are rejected. This way
+/// every value has exactly one accepted encoding, which avoids differences between
+/// client libraries when messages are hashed or signed.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+pub struct StrictDecimal(#[cfg_attr(feature = "std", schemars(with = "String"))] pub Decimal256);
+
+impl From<Decimal256> for StrictDecimal {
+    fn from(value: Decimal256) -> Self {
+        Self(value)
+    }
+}
+
+impl From<StrictDecimal> for Decimal256 {
+    fn from(value: StrictDecimal) -> Self {
+        value.0
+    }
+}
+
+impl FromStr for StrictDecimal {
+    type Err = StdError;
+
+    /// Converts the decimal string to a StrictDecimal
+    /// Possible inputs: "1.23", "1", "0", "0.5"
+    /// Disallowed: all inputs disallowed by [`Decimal256::from_str`], "000012", "1.10", "1.0"
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let decimal = Decimal256::from_str(input)?;
+        let canonical = decimal.to_string();
+        if input != canonical {
+            return Err(StdError::generic_err(format!(
+                "Decimal is not in canonical form, expected '{canonical}'"
+            )));
+        }
+        Ok(Self(decimal))
+    }
+}
+
+impl fmt::Display for StrictDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Serializes as a decimal string
+impl Serialize for StrictDecimal {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+/// Deserializes from a canonical decimal string
+impl<'de> Deserialize<'de> for StrictDecimal {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(StrictDecimalVisitor)
+    }
+}
+
+struct StrictDecimalVisitor;
+
+impl<'de> de::Visitor<'de> for StrictDecimalVisitor {
+    type Value = StrictDecimal;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("string-encoded canonical decimal")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match Self::Value::from_str(v) {
+            Ok(d) => Ok(d),
+            Err(e) => Err(E::custom(format!("Error parsing decimal '{v}': {e}"))),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1114,101 +1268,114 @@ mod tests {
 
     #[test]
     fn decimal256_from_str_errors_for_broken_whole_part() {
-        match Decimal256::from_str("").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Error parsing whole"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal256::from_str("").unwrap_err(),
+            StdError::generic_err("Error parsing whole")
+        );
 
-        match Decimal256::from_str(" ").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Error parsing whole"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal256::from_str(" ").unwrap_err(),
+            StdError::generic_err("Error parsing whole")
+        );
 
-        match Decimal256::from_str("-1").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Error parsing whole"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal256::from_str("-1").unwrap_err(),
+            StdError::generic_err("Error parsing whole")
+        );
     }
 
     #[test]
     fn decimal256_from_str_errors_for_broken_fractional_part() {
-        match Decimal256::from_str("1.").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Error parsing fractional"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal256::from_str("1.").unwrap_err(),
+            StdError::generic_err("Error parsing fractional")
+        );
 
-        match Decimal256::from_str("1. ").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Error parsing fractional"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal256::from_str("1. ").unwrap_err(),
+            StdError::generic_err("Error parsing fractional")
+        );
 
-        match Decimal256::from_str("1.e").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Error parsing fractional"),
-            e => panic!("Unexpected error: {e:?}"),
+        assert_eq!(
+            Decimal256::from_str("1.x").unwrap_err(),
+            StdError::generic_err("Error parsing fractional")
+        );
+
+        assert_eq!(
+            Decimal256::from_str("1.+5").unwrap_err(),
+            StdError::generic_err("Error parsing fractional")
+        );
+    }
+
+    #[test]
+    fn decimal256_from_str_errors_for_scientific_notation() {
+        for input in ["1e3", "1E3", "1.2e3", "1.2E-3", "1.e", "e"] {
+            assert_eq!(
+                Decimal256::from_str(input).unwrap_err(),
+                StdError::generic_err("Scientific notation is not supported")
+            );
         }
+    }
 
-        match Decimal256::from_str("1.2e3").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Error parsing fractional"),
-            e => panic!("Unexpected error: {e:?}"),
+    #[test]
+    fn decimal256_from_str_errors_for_leading_plus() {
+        for input in ["+1", "+1.5", "+0", "+"] {
+            assert_eq!(
+                Decimal256::from_str(input).unwrap_err(),
+                StdError::generic_err("Leading '+' is not allowed")
+            );
         }
     }
 
     #[test]
     fn decimal256_from_str_errors_for_more_than_36_fractional_digits() {
-        match Decimal256::from_str("7.1234567890123456789").unwrap_err() {
-            StdError::GenericErr { msg, .. } => {
-                assert_eq!(msg, "Cannot parse more than 18 fractional digits")
-            }
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal256::from_str("7.1234567890123456789").unwrap_err(),
+            StdError::generic_err("Cannot parse more than 18 fractional digits")
+        );
 
         // No special rules for trailing zeros. This could be changed but adds gas cost for the happy path.
-        match Decimal256::from_str("7.1230000000000000000").unwrap_err() {
-            StdError::GenericErr { msg, .. } => {
-                assert_eq!(msg, "Cannot parse more than 18 fractional digits")
-            }
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal256::from_str("7.1230000000000000000").unwrap_err(),
+            StdError::generic_err("Cannot parse more than 18 fractional digits")
+        );
     }
 
     #[test]
     fn decimal256_from_str_errors_for_invalid_number_of_dots() {
-        match Decimal256::from_str("1.2.3").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Unexpected number of dots"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal256::from_str("1.2.3").unwrap_err(),
+            StdError::generic_err("Unexpected number of dots")
+        );
 
-        match Decimal256::from_str("1.2.3.4").unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Unexpected number of dots"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal256::from_str("1.2.3.4").unwrap_err(),
+            StdError::generic_err("Unexpected number of dots")
+        );
     }
 
     #[test]
     fn decimal256_from_str_errors_for_more_than_max_value() {
         // Integer
-        match Decimal256::from_str("115792089237316195423570985008687907853269984665640564039458")
-            .unwrap_err()
-        {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Value too big"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal256::from_str("115792089237316195423570985008687907853269984665640564039458")
+                .unwrap_err(),
+            StdError::generic_err("Value too big")
+        );
 
         // Decimal
-        match Decimal256::from_str("115792089237316195423570985008687907853269984665640564039458.0")
-            .unwrap_err()
-        {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Value too big"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
-        match Decimal256::from_str(
-            "115792089237316195423570985008687907853269984665640564039457.584007913129639936",
-        )
-        .unwrap_err()
-        {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "Value too big"),
-            e => panic!("Unexpected error: {e:?}"),
-        }
+        assert_eq!(
+            Decimal256::from_str("115792089237316195423570985008687907853269984665640564039458.0")
+                .unwrap_err(),
+            StdError::generic_err("Value too big")
+        );
+        assert_eq!(
+            Decimal256::from_str(
+                "115792089237316195423570985008687907853269984665640564039457.584007913129639936",
+            )
+            .unwrap_err(),
+            StdError::generic_err("Value too big")
+        );
     }
 
     #[test]
@@ -2011,6 +2178,83 @@ mod tests {
         );
     }
 
+    #[test]
+    fn decimal256_deserialize_errors_for_invalid_input() {
+        let err = from_json::<Decimal256>(br#""1e3""#)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains(
+                "Error parsing decimal '1e3': Generic error: Scientific notation is not supported"
+            ),
+            "{err}"
+        );
+        let err = from_json::<Decimal256>(br#""+1""#).unwrap_err().to_string();
+        assert!(err.contains("Leading '+' is not allowed"), "{err}");
+        let err = from_json::<Decimal256>(br#""0.1234567890123456789""#)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("Cannot parse more than 18 fractional digits"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn strict_decimal_from_str_works() {
+        for (input, expected) in [
+            ("0", Decimal256::zero()),
+            ("1", Decimal256::one()),
+            ("0.5", Decimal256::percent(50)),
+            ("87.65", Decimal256::percent(8765)),
+        ] {
+            assert_eq!(
+                StrictDecimal::from_str(input).unwrap(),
+                StrictDecimal(expected)
+            );
+        }
+        assert_eq!(
+            StrictDecimal::from_str(&Decimal256::MAX.to_string()).unwrap(),
+            StrictDecimal(Decimal256::MAX)
+        );
+
+        for (input, canonical) in [
+            ("00", "0"),
+            ("01", "1"),
+            ("1.0", "1"),
+            ("1.50", "1.5"),
+            ("0.000000000000000000", "0"),
+        ] {
+            assert_eq!(
+                StrictDecimal::from_str(input).unwrap_err(),
+                StdError::generic_err(format!(
+                    "Decimal is not in canonical form, expected '{canonical}'"
+                ))
+            );
+        }
+
+        // errors of Decimal256 are passed through
+        assert_eq!(
+            StrictDecimal::from_str("1e3").unwrap_err(),
+            StdError::generic_err("Scientific notation is not supported")
+        );
+    }
+
+    #[test]
+    fn strict_decimal_serde_works() {
+        let value = StrictDecimal(Decimal256::percent(8765));
+        assert_eq!(to_json_vec(&value).unwrap(), br#""87.65""#);
+        assert_eq!(from_json::<StrictDecimal>(br#""87.65""#).unwrap(), value);
+
+        let err = from_json::<StrictDecimal>(br#""87.650""#)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("Decimal is not in canonical form, expected '87.65'"),
+            "{err}"
+        );
+    }
+
     #[test]
     fn decimal256_abs_diff_works() {
         let a = Decimal256::percent(285);
@@ -2020,6 +2264,65 @@ mod tests {
         assert_eq!(b.abs_diff(a), expected);
     }
 
+    #[test]
+    fn decimal256_interpolate_works() {
+        let a = Decimal256::percent(100);
+        let b = Decimal256::percent(300);
+
+        assert_eq!(Decimal256::interpolate(a, b, Decimal256::zero()), a);
+        assert_eq!(
+            Decimal256::interpolate(a, b, Decimal256::percent(50)),
+            Decimal256::percent(200)
+        );
+        assert_eq!(Decimal256::interpolate(a, b, Decimal256::one()), b);
+        assert_eq!(
+            Decimal256::interpolate(b, a, Decimal256::percent(25)),
+            Decimal256::percent(250)
+        );
+        assert_eq!(Decimal256::interpolate(a, a, Decimal256::percent(50)), a);
+
+        // t is clamped to one
+        assert_eq!(Decimal256::interpolate(a, b, Decimal256::percent(200)), b);
+        assert_eq!(Decimal256::interpolate(b, a, Decimal256::MAX), a);
+
+        // rounds towards a
+        let t = Decimal256::from_ratio(1u128, 3u128);
+        let one_atomic = Decimal256::from_atomics(1u128, 18).unwrap();
+        let up =
+            Decimal256::interpolate(Decimal256::zero(), one_atomic * Decimal256::percent(200), t);
+        assert_eq!(up, Decimal256::zero());
+        let down =
+            Decimal256::interpolate(one_atomic * Decimal256::percent(200), Decimal256::zero(), t);
+        assert_eq!(down, one_atomic * Decimal256::percent(200));
+
+        // no overflow at the limits
+        assert_eq!(
+            Decimal256::interpolate(Decimal256::zero(), Decimal256::MAX, Decimal256::one()),
+            Decimal256::MAX
+        );
+        assert_eq!(
+            Decimal256::interpolate(Decimal256::MAX, Decimal256::zero(), Decimal256::one()),
+            Decimal256::zero()
+        );
+        assert!(
+            Decimal256::interpolate(Decimal256::zero(), Decimal256::MAX, Decimal256::percent(50))
+                <= Decimal256::MAX
+        );
+    }
+
+    #[test]
+    fn decimal256_clamp_between_works() {
+        let low = Decimal256::percent(50);
+        let high = Decimal256::percent(150);
+
+        for (min, max) in [(low, high), (high, low)] {
+            assert_eq!(Decimal256::zero().clamp_between(min, max), low);
+            assert_eq!(Decimal256::one().clamp_between(min, max), Decimal256::one());
+            assert_eq!(Decimal256::MAX.clamp_between(min, max), high);
+        }
+        assert_eq!(Decimal256::MAX.clamp_between(low, low), low);
+    }
+
     #[test]
     #[allow(clippy::op_ref)]
     fn decimal256_rem_works() {
@@ -82,10 +82,12 @@ macro_rules! impl_mul_fraction {
             ) -> Result<Self, CheckedMultiplyFractionError> {
                 let dividend = self.full_mul(rhs.numerator().into());
                 let divisor = rhs.denominator().into().into();
-                let floor_result = dividend.checked_div(divisor)?.try_into()?;
+                let floor_result: Self = dividend.checked_div(divisor)?.try_into()?;
                 let remainder = dividend.checked_rem(divisor)?;
                 if !remainder.is_zero() {
-                    Ok($Uint::one().checked_add(floor_result)?)
+                    floor_result.checked_add($Uint::one()).map_err(|_| {
+                        CheckedMultiplyFractionError::Rounding(RoundUpOverflowError.into())
+                    })
                 } else {
                     Ok(floor_result)
                 }
@@ -151,10 +153,12 @@ macro_rules! impl_mul_fraction {
             {
                 let dividend = self.full_mul(rhs.denominator().into());
                 let divisor = rhs.numerator().into().into();
-                let floor_result = dividend.checked_div(divisor)?.try_into()?;
+                let floor_result: Self = dividend.checked_div(divisor)?.try_into()?;
                 let remainder = dividend.checked_rem(divisor)?;
                 if !remainder.is_zero() {
-                    Ok($Uint::one().checked_add(floor_result)?)
+                    floor_result.checked_add($Uint::one()).map_err(|_| {
+                        CheckedMultiplyFractionError::Rounding(RoundUpOverflowError.into())
+                    })
                 } else {
                     Ok(floor_result)
                 }
@@ -1,11 +1,14 @@
+use crate::forward_ref::{forward_ref_binop, forward_ref_op_assign};
+use alloc::{
+    format,
+    string::{String, ToString},
+};
 use core::fmt;
 use core::ops::{
     Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Not, Rem, RemAssign, Shl, ShlAssign, Shr,
     ShrAssign, Sub, SubAssign,
 };
 use core::str::FromStr;
-use forward_ref::{forward_ref_binop, forward_ref_op_assign};
-use schemars::JsonSchema;
 use serde::{de, ser, Deserialize, Deserializer, Serialize};
 
 use crate::errors::{DivideByZeroError, DivisionError, OverflowError, OverflowOperation, StdError};
@@ -42,8 +45,9 @@ use super::num_consts::NumConsts;
 /// ]);
 /// assert_eq!(a, b);
 /// ```
-#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
-pub struct Int512(#[schemars(with = "String")] pub(crate) I512);
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+pub struct Int512(#[cfg_attr(feature = "std", schemars(with = "String"))] pub(crate) I512);
 
 forward_ref_partial_eq!(Int512, Int512);
 
@@ -132,6 +136,26 @@ impl Int512 {
         Self(I512::from_bits(U512::from_digits(words)))
     }
 
+    /// Creates the number from big endian bytes.
+    ///
+    /// Returns an error if the slice is not exactly 64 bytes long.
+    pub fn from_be_slice(data: &[u8]) -> Result<Self, StdError> {
+        let data: [u8; 64] = data
+            .try_into()
+            .map_err(|_| StdError::invalid_data_size(64, data.len()))?;
+        Ok(Self::from_be_bytes(data))
+    }
+
+    /// Creates the number from little endian bytes.
+    ///
+    /// Returns an error if the slice is not exactly 64 bytes long.
+    pub fn from_le_slice(data: &[u8]) -> Result<Self, StdError> {
+        let data: [u8; 64] = data
+            .try_into()
+            .map_err(|_| StdError::invalid_data_size(64, data.len()))?;
+        Ok(Self::from_le_bytes(data))
+    }
+
     /// Returns a copy of the number as big endian bytes.
     #[must_use = "this returns the result of the operation, without modifying the original"]
     pub const fn to_be_bytes(self) -> [u8; 64] {
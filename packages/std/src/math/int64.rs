@@ -1,11 +1,14 @@
+use crate::forward_ref::{forward_ref_binop, forward_ref_op_assign};
+use alloc::{
+    format,
+    string::{String, ToString},
+};
 use core::fmt;
 use core::ops::{
     Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Not, Rem, RemAssign, Shl, ShlAssign, Shr,
     ShrAssign, Sub, SubAssign,
 };
 use core::str::FromStr;
-use forward_ref::{forward_ref_binop, forward_ref_op_assign};
-use schemars::JsonSchema;
 use serde::{de, ser, Deserialize, Deserializer, Serialize};
 
 use crate::errors::{DivideByZeroError, DivisionError, OverflowError, OverflowOperation, StdError};
@@ -30,8 +33,9 @@ use super::num_consts::NumConsts;
 /// let a = Int64::from(258i64);
 /// assert_eq!(a.i64(), 258);
 /// ```
-#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
-pub struct Int64(#[schemars(with = "String")] pub(crate) i64);
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+pub struct Int64(#[cfg_attr(feature = "std", schemars(with = "String"))] pub(crate) i64);
 
 forward_ref_partial_eq!(Int64, Int64);
 
@@ -74,6 +78,26 @@ impl Int64 {
         Self(i64::from_le_bytes(data))
     }
 
+    /// Creates the number from big endian bytes.
+    ///
+    /// Returns an error if the slice is not exactly 8 bytes long.
+    pub fn from_be_slice(data: &[u8]) -> Result<Self, StdError> {
+        let data: [u8; 8] = data
+            .try_into()
+            .map_err(|_| StdError::invalid_data_size(8, data.len()))?;
+        Ok(Self::from_be_bytes(data))
+    }
+
+    /// Creates the number from little endian bytes.
+    ///
+    /// Returns an error if the slice is not exactly 8 bytes long.
+    pub fn from_le_slice(data: &[u8]) -> Result<Self, StdError> {
+        let data: [u8; 8] = data
+            .try_into()
+            .map_err(|_| StdError::invalid_data_size(8, data.len()))?;
+        Ok(Self::from_le_bytes(data))
+    }
+
     /// Returns a copy of the number as big endian bytes.
     #[must_use = "this returns the result of the operation, without modifying the original"]
     pub const fn to_be_bytes(self) -> [u8; 8] {
@@ -16,7 +16,7 @@ mod uint512;
 mod uint64;
 
 pub use decimal::{Decimal, DecimalRangeExceeded};
-pub use decimal256::{Decimal256, Decimal256RangeExceeded};
+pub use decimal256::{Decimal256, Decimal256RangeExceeded, StrictDecimal};
 pub use fraction::Fraction;
 pub use int128::Int128;
 pub use int256::Int256;
@@ -25,7 +25,7 @@ pub use int64::Int64;
 pub use isqrt::Isqrt;
 pub use signed_decimal::{SignedDecimal, SignedDecimalRangeExceeded};
 pub use signed_decimal_256::{SignedDecimal256, SignedDecimal256RangeExceeded};
-pub use uint128::Uint128;
+pub use uint128::{NumericUint128, Uint128};
 pub use uint256::Uint256;
 pub use uint512::Uint512;
 pub use uint64::Uint64;
@@ -107,4 +107,25 @@ mod tests {
     impl SignedImpl<'_> for Int128 {}
     impl SignedImpl<'_> for Int256 {}
     impl SignedImpl<'_> for Int512 {}
+
+    #[test]
+    fn from_slice_works() {
+        macro_rules! test_from_slice {
+            ($($ty:ty),+) => {$(
+                let value = <$ty>::from_be_bytes(core::array::from_fn(|i| i as u8 + 1));
+                let be_bytes = value.to_be_bytes();
+                let le_bytes = value.to_le_bytes();
+                assert_eq!(<$ty>::from_be_slice(&be_bytes).unwrap(), value);
+                assert_eq!(<$ty>::from_le_slice(&le_bytes).unwrap(), value);
+
+                let len = be_bytes.len();
+                assert_eq!(
+                    <$ty>::from_be_slice(&be_bytes[1..]).unwrap_err(),
+                    crate::StdError::invalid_data_size(len, len - 1)
+                );
+            )+};
+        }
+
+        test_from_slice!(Uint64, Uint128, Uint256, Uint512, Int64, Int128, Int256, Int512);
+    }
 }
@@ -1,4 +1,5 @@
 /// Crate internal trait for all our signed and unsigned number types
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
 pub(crate) trait NumConsts {
     const MAX: Self;
     const MIN: Self;
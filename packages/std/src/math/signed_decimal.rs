@@ -1,11 +1,11 @@
+use crate::forward_ref::{forward_ref_binop, forward_ref_op_assign};
+use alloc::{format, string::ToString};
 use core::cmp::Ordering;
 use core::fmt::{self, Write};
 use core::ops::{
     Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
 };
 use core::str::FromStr;
-use forward_ref::{forward_ref_binop, forward_ref_op_assign};
-use schemars::JsonSchema;
 use serde::{de, ser, Deserialize, Deserializer, Serialize};
 use thiserror::Error;
 
@@ -22,8 +22,9 @@ use super::Int128;
 ///
 /// The greatest possible value that can be represented is 170141183460469231731.687303715884105727 (which is (2^127 - 1) / 10^18)
 /// and the smallest is -170141183460469231731.687303715884105728 (which is -2^127 / 10^18).
-#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
-pub struct SignedDecimal(#[schemars(with = "String")] Int128);
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+pub struct SignedDecimal(#[cfg_attr(feature = "std", schemars(with = "String"))] Int128);
 
 forward_ref_partial_eq!(SignedDecimal, SignedDecimal);
 ///Similar to `SignedDecimal256RangeExceeded`, but for standard `SignedDecimal` operations.
@@ -1,11 +1,11 @@
+use crate::forward_ref::{forward_ref_binop, forward_ref_op_assign};
+use alloc::{format, string::ToString};
 use core::cmp::Ordering;
 use core::fmt::{self, Write};
 use core::ops::{
     Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
 };
 use core::str::FromStr;
-use forward_ref::{forward_ref_binop, forward_ref_op_assign};
-use schemars::JsonSchema;
 use serde::{de, ser, Deserialize, Deserializer, Serialize};
 use thiserror::Error;
 
@@ -27,8 +27,9 @@ use super::Int256;
 /// and the smallest is
 /// -57896044618658097711785492504343953926634992332820282019728.792003956564819968
 /// (which is -2^255 / 10^18).
-#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
-pub struct SignedDecimal256(#[schemars(with = "String")] Int256);
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+pub struct SignedDecimal256(#[cfg_attr(feature = "std", schemars(with = "String"))] Int256);
 
 forward_ref_partial_eq!(SignedDecimal256, SignedDecimal256);
 /// Indicates an error when a SignedDecimal256 operation exceeds its range.
@@ -1,18 +1,20 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+};
 use core::fmt::{self};
 use core::ops::{
-    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign,
-    Sub, SubAssign,
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Not, Rem, RemAssign, Shl, ShlAssign, Shr,
+    ShrAssign, Sub, SubAssign,
 };
 use core::str::FromStr;
-use std::ops::Not;
 
-use forward_ref::{forward_ref_binop, forward_ref_op_assign};
-use schemars::JsonSchema;
+use crate::forward_ref::{forward_ref_binop, forward_ref_op_assign};
 use serde::{de, ser, Deserialize, Deserializer, Serialize};
 
 use crate::errors::{
     CheckedMultiplyFractionError, CheckedMultiplyRatioError, DivideByZeroError, OverflowError,
-    OverflowOperation, StdError,
+    OverflowOperation, RoundUpOverflowError, StdError,
 };
 use crate::{
     forward_ref_partial_eq, impl_mul_fraction, Fraction, Int128, Int256, Int512, Int64, Uint256,
@@ -41,8 +43,9 @@ use super::num_consts::NumConsts;
 /// let c = Uint128::from(70u32);
 /// assert_eq!(c.u128(), 70);
 /// ```
-#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
-pub struct Uint128(#[schemars(with = "String")] pub(crate) u128);
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+pub struct Uint128(#[cfg_attr(feature = "std", schemars(with = "String"))] pub(crate) u128);
 
 forward_ref_partial_eq!(Uint128, Uint128);
 
@@ -74,6 +77,36 @@ impl Uint128 {
         self.0
     }
 
+    #[must_use]
+    pub const fn from_be_bytes(data: [u8; 16]) -> Self {
+        Self(u128::from_be_bytes(data))
+    }
+
+    #[must_use]
+    pub const fn from_le_bytes(data: [u8; 16]) -> Self {
+        Self(u128::from_le_bytes(data))
+    }
+
+    /// Creates the number from big endian bytes.
+    ///
+    /// Returns an error if the slice is not exactly 16 bytes long.
+    pub fn from_be_slice(data: &[u8]) -> Result<Self, StdError> {
+        let data: [u8; 16] = data
+            .try_into()
+            .map_err(|_| StdError::invalid_data_size(16, data.len()))?;
+        Ok(Self::from_be_bytes(data))
+    }
+
+    /// Creates the number from little endian bytes.
+    ///
+    /// Returns an error if the slice is not exactly 16 bytes long.
+    pub fn from_le_slice(data: &[u8]) -> Result<Self, StdError> {
+        let data: [u8; 16] = data
+            .try_into()
+            .map_err(|_| StdError::invalid_data_size(16, data.len()))?;
+        Ok(Self::from_le_bytes(data))
+    }
+
     /// Returns a copy of the number as big endian bytes.
     #[must_use = "this returns the result of the operation, without modifying the original"]
     pub const fn to_be_bytes(self) -> [u8; 16] {
@@ -604,6 +637,137 @@ impl<'de> de::Visitor<'de> for Uint128Visitor {
     }
 }
 
+/// A [`Uint128`] that is encoded as a JSON number instead of a string where this is safe,
+/// for interoperability with tooling that expects numbers, e.g. EVM-oriented libraries.
+///
+/// Values up to [`NumericUint128::MAX_SAFE`] (2^53 - 1) are serialized as numbers.
+/// Larger values cannot be represented exactly by clients that convert JSON numbers to
+/// floats and are serialized as strings instead. Deserialization accepts strings for the
+/// full range and numbers up to [`NumericUint128::MAX_SAFE`].
+///
+/// # Examples
+///
+/// ```
+/// # use cosmwasm_std::{to_json_string, NumericUint128, Uint128};
+/// let small = NumericUint128(Uint128::new(123));
+/// assert_eq!(to_json_string(&small).unwrap(), "123");
+///
+/// let big = NumericUint128(Uint128::new(1 << 60));
+/// assert_eq!(to_json_string(&big).unwrap(), "\"1152921504606846976\"");
+/// ```
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NumericUint128(pub Uint128);
+
+impl NumericUint128 {
+    /// The largest value that is encoded as a JSON number, i.e. `Number.MAX_SAFE_INTEGER`
+    /// in JavaScript.
+    pub const MAX_SAFE: u64 = (1 << 53) - 1;
+}
+
+impl From<Uint128> for NumericUint128 {
+    fn from(value: Uint128) -> Self {
+        Self(value)
+    }
+}
+
+impl From<NumericUint128> for Uint128 {
+    fn from(value: NumericUint128) -> Self {
+        value.0
+    }
+}
+
+impl fmt::Display for NumericUint128 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Serialize for NumericUint128 {
+    /// Serializes as a number up to [`NumericUint128::MAX_SAFE`] and as an integer
+    /// string using base 10 above
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        match u64::try_from(self.0.u128()) {
+            Ok(value) if value <= Self::MAX_SAFE => serializer.serialize_u64(value),
+            _ => serializer.serialize_str(&self.0.to_string()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for NumericUint128 {
+    /// Deserialized from a number up to [`NumericUint128::MAX_SAFE`] or an integer
+    /// string using base 10
+    fn deserialize<D>(deserializer: D) -> Result<NumericUint128, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(NumericUint128Visitor)
+    }
+}
+
+struct NumericUint128Visitor;
+
+impl<'de> de::Visitor<'de> for NumericUint128Visitor {
+    type Value = NumericUint128;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("integer or string-encoded integer")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if v > NumericUint128::MAX_SAFE {
+            return Err(E::custom(format!(
+                "invalid NumericUint128 {v} - numbers above 2^53 - 1 must be encoded as strings"
+            )));
+        }
+        Ok(NumericUint128(Uint128::from(v)))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match u64::try_from(v) {
+            Ok(v) => self.visit_u64(v),
+            Err(_) => Err(E::custom(format!(
+                "invalid NumericUint128 {v} - must not be negative"
+            ))),
+        }
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Uint128Visitor.visit_str(v).map(NumericUint128)
+    }
+}
+
+#[cfg(feature = "std")]
+impl schemars::JsonSchema for NumericUint128 {
+    fn schema_name() -> String {
+        "NumericUint128".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{InstanceType, SchemaObject, SingleOrVec};
+
+        SchemaObject {
+            instance_type: Some(SingleOrVec::Vec(vec![
+                InstanceType::Integer,
+                InstanceType::String,
+            ])),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 impl<A> core::iter::Sum<A> for Uint128
 where
     Self: Add<A, Output = Self>,
@@ -616,6 +780,7 @@ where
 #[cfg(test)]
 mod tests {
     use crate::errors::CheckedMultiplyFractionError::{ConversionOverflow, DivideByZero};
+    use crate::errors::RoundingError;
     use crate::math::conversion::test_try_from_int_to_uint;
     use crate::{from_json, to_json_vec, ConversionOverflowError, Decimal};
 
@@ -777,6 +942,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn uint128_from_be_bytes_works() {
+        assert_eq!(Uint128::from_be_bytes([0; 16]), Uint128::zero());
+        assert_eq!(Uint128::from_be_bytes([0xff; 16]), Uint128::MAX);
+        let mut one = [0; 16];
+        one[15] = 1;
+        assert_eq!(Uint128::from_be_bytes(one), Uint128::new(1));
+    }
+
+    #[test]
+    fn uint128_from_le_bytes_works() {
+        assert_eq!(Uint128::from_le_bytes([0; 16]), Uint128::zero());
+        assert_eq!(Uint128::from_le_bytes([0xff; 16]), Uint128::MAX);
+        let mut one = [0; 16];
+        one[0] = 1;
+        assert_eq!(Uint128::from_le_bytes(one), Uint128::new(1));
+    }
+
     #[test]
     fn uint128_is_zero_works() {
         assert!(Uint128::zero().is_zero());
@@ -786,6 +969,52 @@ mod tests {
         assert!(!Uint128(123).is_zero());
     }
 
+    #[test]
+    fn numeric_uint128_serialize_works() {
+        let cases: [(u128, &str); 5] = [
+            (0, "0"),
+            (123, "123"),
+            (NumericUint128::MAX_SAFE as u128, "9007199254740991"),
+            (1 << 53, r#""9007199254740992""#),
+            (u128::MAX, r#""340282366920938463463374607431768211455""#),
+        ];
+        for (value, json) in cases {
+            let value = NumericUint128(Uint128::new(value));
+            assert_eq!(to_json_vec(&value).unwrap(), json.as_bytes());
+            assert_eq!(from_json::<NumericUint128>(json).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn numeric_uint128_deserialize_works() {
+        // strings are accepted for small values as well
+        assert_eq!(
+            from_json::<NumericUint128>(r#""123""#).unwrap(),
+            NumericUint128(Uint128::new(123))
+        );
+
+        let err = from_json::<NumericUint128>("9007199254740992")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains(
+                "invalid NumericUint128 9007199254740992 - numbers above 2^53 - 1 must be encoded as strings"
+            ),
+            "{err}"
+        );
+        let err = from_json::<NumericUint128>("-1").unwrap_err().to_string();
+        assert!(
+            err.contains("invalid NumericUint128 -1 - must not be negative"),
+            "{err}"
+        );
+        let err = from_json::<NumericUint128>(r#""1.5""#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("invalid Uint128 '1.5'"), "{err}");
+        assert!(from_json::<NumericUint128>("1.5").is_err());
+        assert!(from_json::<NumericUint128>("true").is_err());
+    }
+
     #[test]
     fn uint128_json() {
         let orig = Uint128(1234567890987654321);
@@ -1334,6 +1563,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn checked_mul_ceil_returns_rounding_error_on_round_up_overflow() {
+        // 97223533405982418132392744980505203273 * 7 / 2 = Uint128::MAX + 0.5
+        let fraction = (7u128, 2u128);
+        let value = Uint128::new(97223533405982418132392744980505203273);
+        assert_eq!(value.mul_floor(fraction), Uint128::MAX);
+        assert_eq!(
+            value.checked_mul_ceil(fraction),
+            Err(CheckedMultiplyFractionError::Rounding(
+                RoundingError::RoundUpOverflow(RoundUpOverflowError)
+            )),
+        );
+
+        // same for division
+        let fraction = (2u128, 7u128);
+        assert_eq!(value.div_floor(fraction), Uint128::MAX);
+        assert_eq!(
+            value.checked_div_ceil(fraction),
+            Err(CheckedMultiplyFractionError::Rounding(
+                RoundingError::RoundUpOverflow(RoundUpOverflowError)
+            )),
+        );
+    }
+
     #[test]
     #[should_panic(expected = "DivideByZeroError")]
     fn mul_ceil_panics_on_zero_div() {
@@ -1,17 +1,19 @@
+use crate::forward_ref::{forward_ref_binop, forward_ref_op_assign};
+use alloc::{
+    format,
+    string::{String, ToString},
+};
 use core::fmt;
 use core::ops::{
-    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign,
-    Sub, SubAssign,
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Not, Rem, RemAssign, Shl, ShlAssign, Shr,
+    ShrAssign, Sub, SubAssign,
 };
 use core::str::FromStr;
-use forward_ref::{forward_ref_binop, forward_ref_op_assign};
-use schemars::JsonSchema;
 use serde::{de, ser, Deserialize, Deserializer, Serialize};
-use std::ops::Not;
 
 use crate::errors::{
     CheckedMultiplyFractionError, CheckedMultiplyRatioError, ConversionOverflowError,
-    DivideByZeroError, OverflowError, OverflowOperation, StdError,
+    DivideByZeroError, OverflowError, OverflowOperation, RoundUpOverflowError, StdError,
 };
 use crate::{
     forward_ref_partial_eq, impl_mul_fraction, Fraction, Int128, Int256, Int512, Int64, Uint128,
@@ -45,8 +47,9 @@ use super::num_consts::NumConsts;
 /// ]);
 /// assert_eq!(a, b);
 /// ```
-#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
-pub struct Uint256(#[schemars(with = "String")] pub(crate) U256);
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+pub struct Uint256(#[cfg_attr(feature = "std", schemars(with = "String"))] pub(crate) U256);
 
 forward_ref_partial_eq!(Uint256, Uint256);
 
@@ -112,6 +115,26 @@ impl Uint256 {
         Self(U256::from_digits(words))
     }
 
+    /// Creates the number from big endian bytes.
+    ///
+    /// Returns an error if the slice is not exactly 32 bytes long.
+    pub fn from_be_slice(data: &[u8]) -> Result<Self, StdError> {
+        let data: [u8; 32] = data
+            .try_into()
+            .map_err(|_| StdError::invalid_data_size(32, data.len()))?;
+        Ok(Self::from_be_bytes(data))
+    }
+
+    /// Creates the number from little endian bytes.
+    ///
+    /// Returns an error if the slice is not exactly 32 bytes long.
+    pub fn from_le_slice(data: &[u8]) -> Result<Self, StdError> {
+        let data: [u8; 32] = data
+            .try_into()
+            .map_err(|_| StdError::invalid_data_size(32, data.len()))?;
+        Ok(Self::from_le_bytes(data))
+    }
+
     /// A conversion from `u128` that, unlike the one provided by the `From` trait,
     /// can be used in a `const` context.
     #[must_use]
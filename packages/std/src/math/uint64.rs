@@ -1,16 +1,18 @@
+use crate::forward_ref::{forward_ref_binop, forward_ref_op_assign};
+use alloc::{
+    format,
+    string::{String, ToString},
+};
 use core::fmt::{self};
 use core::ops::{
-    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign,
-    Sub, SubAssign,
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Not, Rem, RemAssign, Shl, ShlAssign, Shr,
+    ShrAssign, Sub, SubAssign,
 };
-use forward_ref::{forward_ref_binop, forward_ref_op_assign};
-use schemars::JsonSchema;
 use serde::{de, ser, Deserialize, Deserializer, Serialize};
-use std::ops::Not;
 
 use crate::errors::{
     CheckedMultiplyFractionError, CheckedMultiplyRatioError, DivideByZeroError, OverflowError,
-    OverflowOperation, StdError,
+    OverflowOperation, RoundUpOverflowError, StdError,
 };
 use crate::{
     forward_ref_partial_eq, impl_mul_fraction, Fraction, Int128, Int256, Int512, Int64, Uint128,
@@ -35,8 +37,9 @@ use super::num_consts::NumConsts;
 /// let b = Uint64::from(70u32);
 /// assert_eq!(b.u64(), 70);
 /// ```
-#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
-pub struct Uint64(#[schemars(with = "String")] pub(crate) u64);
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
+pub struct Uint64(#[cfg_attr(feature = "std", schemars(with = "String"))] pub(crate) u64);
 
 forward_ref_partial_eq!(Uint64, Uint64);
 
@@ -68,6 +71,36 @@ impl Uint64 {
         self.0
     }
 
+    #[must_use]
+    pub const fn from_be_bytes(data: [u8; 8]) -> Self {
+        Self(u64::from_be_bytes(data))
+    }
+
+    #[must_use]
+    pub const fn from_le_bytes(data: [u8; 8]) -> Self {
+        Self(u64::from_le_bytes(data))
+    }
+
+    /// Creates the number from big endian bytes.
+    ///
+    /// Returns an error if the slice is not exactly 8 bytes long.
+    pub fn from_be_slice(data: &[u8]) -> Result<Self, StdError> {
+        let data: [u8; 8] = data
+            .try_into()
+            .map_err(|_| StdError::invalid_data_size(8, data.len()))?;
+        Ok(Self::from_be_bytes(data))
+    }
+
+    /// Creates the number from little endian bytes.
+    ///
+    /// Returns an error if the slice is not exactly 8 bytes long.
+    pub fn from_le_slice(data: &[u8]) -> Result<Self, StdError> {
+        let data: [u8; 8] = data
+            .try_into()
+            .map_err(|_| StdError::invalid_data_size(8, data.len()))?;
+        Ok(Self::from_le_bytes(data))
+    }
+
     /// Returns a copy of the number as big endian bytes.
     #[must_use = "this returns the result of the operation, without modifying the original"]
     pub const fn to_be_bytes(self) -> [u8; 8] {
@@ -698,6 +731,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn uint64_from_be_bytes_works() {
+        assert_eq!(Uint64::from_be_bytes([0; 8]), Uint64::zero());
+        assert_eq!(Uint64::from_be_bytes([0xff; 8]), Uint64::MAX);
+        let mut one = [0; 8];
+        one[7] = 1;
+        assert_eq!(Uint64::from_be_bytes(one), Uint64::new(1));
+    }
+
+    #[test]
+    fn uint64_from_le_bytes_works() {
+        assert_eq!(Uint64::from_le_bytes([0; 8]), Uint64::zero());
+        assert_eq!(Uint64::from_le_bytes([0xff; 8]), Uint64::MAX);
+        let mut one = [0; 8];
+        one[0] = 1;
+        assert_eq!(Uint64::from_le_bytes(one), Uint64::new(1));
+    }
+
     #[test]
     fn uint64_is_zero_works() {
         assert!(Uint64::zero().is_zero());
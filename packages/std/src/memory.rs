@@ -1,5 +1,6 @@
 use alloc::vec::Vec;
 use core::mem;
+use core::ptr::addr_of_mut;
 
 /// Describes some data allocated in Wasm's linear memory.
 /// A pointer to an instance of this can be returned over FFI boundaries.
@@ -15,10 +16,36 @@ pub struct Region {
     pub length: u32,
 }
 
+/// A buffer that is used by the next call to [`alloc`] if its capacity is sufficient.
+/// This allows the host to write into an existing allocation, see [`set_spare_buffer`].
+///
+/// Contracts are executed single-threaded, so no synchronization is needed.
+static mut SPARE_BUFFER: Option<Vec<u8>> = None;
+
+/// Offers a buffer to be reused by the next call to [`alloc`] (or the `allocate` export).
+/// Use [`take_spare_buffer`] afterwards to get the buffer back in case it was not used.
+pub fn set_spare_buffer(buffer: Vec<u8>) {
+    unsafe { *addr_of_mut!(SPARE_BUFFER) = Some(buffer) }
+}
+
+/// Takes the buffer offered via [`set_spare_buffer`] if it was not used yet.
+pub fn take_spare_buffer() -> Option<Vec<u8>> {
+    unsafe { (*addr_of_mut!(SPARE_BUFFER)).take() }
+}
+
 /// Creates a memory region of capacity `size` and length 0. Returns a pointer to the Region.
 /// This is the same as the `allocate` export, but designed to be called internally.
+///
+/// If a spare buffer with sufficient capacity is available, it is used instead of a new allocation.
+/// With the `arena_allocator` feature, the memory is taken from the arena if possible.
 pub fn alloc(size: usize) -> *mut Region {
-    let data: Vec<u8> = Vec::with_capacity(size);
+    let data: Vec<u8> = match take_spare_buffer() {
+        Some(mut buffer) if buffer.capacity() >= size => {
+            buffer.clear();
+            buffer
+        }
+        _ => new_buffer(size),
+    };
     let data_ptr = data.as_ptr() as usize;
 
     let region = build_region_from_components(
@@ -30,6 +57,21 @@ pub fn alloc(size: usize) -> *mut Region {
     Box::into_raw(region)
 }
 
+#[cfg(not(feature = "arena_allocator"))]
+fn new_buffer(size: usize) -> Vec<u8> {
+    Vec::with_capacity(size)
+}
+
+#[cfg(feature = "arena_allocator")]
+fn new_buffer(size: usize) -> Vec<u8> {
+    match crate::arena::arena_alloc(size) {
+        // Safety: the arena returns a non-null pointer to `size` unused bytes, which the
+        // global allocator knows not to free.
+        Some(ptr) => unsafe { Vec::from_raw_parts(ptr, 0, size) },
+        None => Vec::with_capacity(size),
+    }
+}
+
 /// Similar to alloc, but instead of creating a new vector it consumes an existing one and returns
 /// a pointer to the Region (preventing the memory from being freed until explicitly called later).
 ///
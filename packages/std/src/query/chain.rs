@@ -0,0 +1,68 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::query_response::QueryResponseType;
+
+/// Queries information about the chain and the CosmWasm VM the contract is running on.
+#[non_exhaustive]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainQuery {
+    /// Returns the capabilities available on the chain and the version of the VM.
+    /// The response type is [`CapabilitiesResponse`].
+    Capabilities {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct CapabilitiesResponse {
+    /// The capabilities available on the chain, such as "iterator" or "cosmwasm_1_4".
+    /// See [CAPABILITIES.md](https://github.com/CosmWasm/cosmwasm/blob/main/docs/CAPABILITIES.md).
+    pub capabilities: Vec<String>,
+    /// The version of the CosmWasm VM, e.g. "1.5.0"
+    pub vm_version: String,
+}
+
+impl_response_constructor!(
+    CapabilitiesResponse,
+    capabilities: Vec<String>,
+    vm_version: String
+);
+impl QueryResponseType for CapabilitiesResponse {}
+
+impl CapabilitiesResponse {
+    /// Returns true if and only if the given capability is available on the chain
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|c| c == capability)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_json, to_json_binary};
+
+    #[test]
+    fn chain_query_serializes_correctly() {
+        let query = ChainQuery::Capabilities {};
+        let json = to_json_binary(&query).unwrap();
+        assert_eq!(json.as_slice(), br#"{"capabilities":{}}"#);
+    }
+
+    #[test]
+    fn capabilities_response_works() {
+        let response: CapabilitiesResponse =
+            from_json(br#"{"capabilities":["iterator","cosmwasm_1_4"],"vm_version":"1.5.0"}"#)
+                .unwrap();
+        assert_eq!(
+            response,
+            CapabilitiesResponse::new(
+                vec!["iterator".to_string(), "cosmwasm_1_4".to_string()],
+                "1.5.0".to_string()
+            )
+        );
+        assert!(response.has_capability("cosmwasm_1_4"));
+        assert!(!response.has_capability("chain_queries"));
+    }
+}
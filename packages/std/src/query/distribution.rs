@@ -1,7 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::{Addr, Decimal256};
+use crate::{Addr, Coin, ConversionOverflowError, Decimal256, Uint128};
 
 use super::query_response::QueryResponseType;
 /// Query types for interacting with the distribution module.
@@ -75,6 +75,38 @@ impl DecCoin {
             amount: amount.into(),
         }
     }
+
+    /// Splits the amount into a whole [`Coin`] and the remaining decimal change.
+    ///
+    /// This matches the truncation of the Cosmos SDK's `DecCoin.TruncateDecimal`, which is
+    /// used when rewards are withdrawn. The change is what stays in the distribution module.
+    ///
+    /// Fails if the truncated amount does not fit into a [`Uint128`].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{coin, DecCoin, Decimal256};
+    /// # use core::str::FromStr;
+    /// let reward = DecCoin::new(Decimal256::from_str("123.456").unwrap(), "ucosm");
+    /// let (coin_part, change) = reward.truncate().unwrap();
+    /// assert_eq!(coin_part, coin(123, "ucosm"));
+    /// assert_eq!(change, DecCoin::new(Decimal256::from_str("0.456").unwrap(), "ucosm"));
+    /// ```
+    pub fn truncate(&self) -> Result<(Coin, DecCoin), ConversionOverflowError> {
+        let whole = self.amount.floor();
+        let amount = Uint128::try_from(whole.to_uint_floor())?;
+        Ok((
+            Coin::new(amount, self.denom.clone()),
+            DecCoin::new(self.amount - whole, self.denom.clone()),
+        ))
+    }
+}
+
+impl From<Coin> for DecCoin {
+    fn from(coin: Coin) -> Self {
+        DecCoin::new(Decimal256::from_ratio(coin.amount, 1u128), coin.denom)
+    }
 }
 
 /// See <https://github.com/cosmos/cosmos-sdk/blob/c74e2887b0b73e81d48c2f33e6b1020090089ee0/proto/cosmos/distribution/v1beta1/query.proto#L189-L200>
@@ -113,3 +145,57 @@ pub struct DelegatorValidatorsResponse {
 
 impl_response_constructor!(DelegatorValidatorsResponse, validators: Vec<String>);
 impl QueryResponseType for DelegatorValidatorsResponse {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{coin, from_json, to_json_vec, Uint256};
+    use core::str::FromStr;
+
+    #[test]
+    fn dec_coin_serializes_with_decimal_amount() {
+        let dec_coin = DecCoin::new(Decimal256::from_str("1.5").unwrap(), "ucosm");
+        let json = to_json_vec(&dec_coin).unwrap();
+        assert_eq!(json, br#"{"denom":"ucosm","amount":"1.5"}"#);
+        assert_eq!(from_json::<DecCoin>(&json).unwrap(), dec_coin);
+    }
+
+    #[test]
+    fn dec_coin_truncate_works() {
+        let dec_coin = DecCoin::new(Decimal256::from_str("7.000000000000000001").unwrap(), "a");
+        let (coin_part, change) = dec_coin.truncate().unwrap();
+        assert_eq!(coin_part, coin(7, "a"));
+        assert_eq!(
+            change,
+            DecCoin::new(Decimal256::from_atomics(1u128, 18).unwrap(), "a")
+        );
+
+        // whole amounts have no change
+        let (coin_part, change) = DecCoin::new(Decimal256::percent(300), "a")
+            .truncate()
+            .unwrap();
+        assert_eq!(coin_part, coin(3, "a"));
+        assert_eq!(change, DecCoin::new(Decimal256::zero(), "a"));
+
+        // amounts below 1 are truncated to zero
+        let dec_coin = DecCoin::new(Decimal256::from_str("0.999").unwrap(), "a");
+        let (coin_part, change) = dec_coin.truncate().unwrap();
+        assert_eq!(coin_part, coin(0, "a"));
+        assert_eq!(change, dec_coin);
+    }
+
+    #[test]
+    fn dec_coin_truncate_fails_for_overflow() {
+        let amount = Uint256::from(Uint128::MAX) + Uint256::one();
+        let dec_coin = DecCoin::new(Decimal256::from_atomics(amount, 0).unwrap(), "a");
+        dec_coin.truncate().unwrap_err();
+    }
+
+    #[test]
+    fn dec_coin_from_coin_works() {
+        let dec_coin = DecCoin::from(coin(u128::MAX, "a"));
+        assert_eq!(dec_coin.amount.to_uint_floor(), Uint256::from(u128::MAX));
+        assert_eq!(dec_coin.denom, "a");
+        assert_eq!(dec_coin.truncate().unwrap().0, coin(u128::MAX, "a"));
+    }
+}
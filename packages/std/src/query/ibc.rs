@@ -2,6 +2,9 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::ibc::IbcChannel;
+use crate::Binary;
+#[cfg(feature = "ibc_channel_filters")]
+use crate::PageRequest;
 
 /// These are queries to the various IBC modules to see the state of the contract's
 /// IBC connection. These will return errors if the contract is not "ibc enabled"
@@ -18,6 +21,24 @@ pub enum IbcQuery {
     ///
     /// Returns a `ListChannelsResponse`.
     ListChannels { port_id: Option<String> },
+    /// Like [`IbcQuery::ListChannels`], but with optional filters which are applied by the
+    /// chain, such that contracts with many channels do not have to load all of them.
+    ///
+    /// Returns a `ListChannelsResponse`, which contains the key of the next page
+    /// if there are more channels.
+    #[cfg(feature = "ibc_channel_filters")]
+    ListChannelsFiltered {
+        /// Defaults to the contract's port if omitted
+        port_id: Option<String>,
+        /// Only lists channels whose counterparty is bound to this port
+        counterparty_port_id: Option<String>,
+        /// Only lists channels created on this connection
+        connection_id: Option<String>,
+        /// Only lists channels in this state
+        state: Option<IbcChannelState>,
+        /// Default pagination will be used if this is omitted
+        pagination: Option<PageRequest>,
+    },
     /// Lists all information for a (portID, channelID) pair.
     /// If port_id is omitted, it will default to the contract's own channel.
     /// (To save a PortId{} call)
@@ -29,6 +50,22 @@ pub enum IbcQuery {
     },
     // TODO: Add more
 }
+
+/// The state of an IBC channel in the channel handshake
+#[cfg(feature = "ibc_channel_filters")]
+#[non_exhaustive]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcChannelState {
+    /// The channel was opened on this chain but not yet on the counterparty
+    Init,
+    /// The channel was opened on this chain in response to an open init on the counterparty
+    TryOpen,
+    /// The handshake is complete and packets can be sent
+    Open,
+    Closed,
+}
+
 /// Contains the response information for a query about a port ID in IBC.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[non_exhaustive]
@@ -42,9 +79,16 @@ impl_response_constructor!(PortIdResponse, port_id: String);
 #[non_exhaustive]
 pub struct ListChannelsResponse {
     pub channels: Vec<IbcChannel>,
+    /// The key to pass in the pagination of the next query.
+    /// `None` if this is the last page.
+    pub next_key: Option<Binary>,
 }
 
-impl_response_constructor!(ListChannelsResponse, channels: Vec<IbcChannel>);
+impl_response_constructor!(
+    ListChannelsResponse,
+    channels: Vec<IbcChannel>,
+    next_key: Option<Binary>
+);
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 ///Contains details about a channel, typically used in IBC (Inter-Blockchain Communication) queries.
@@ -16,7 +16,7 @@ macro_rules! impl_response_constructor {
             ///
             /// Warning: This can change in breaking ways in minor versions.
             #[doc(hidden)]
-            #[allow(dead_code)]
+            #[allow(dead_code, clippy::too_many_arguments)]
             pub fn new($( $field: $t),*) -> Self {
                 Self { $( $field ),* }
             }
@@ -25,16 +25,28 @@ macro_rules! impl_response_constructor {
 }
 
 mod bank;
+#[cfg(feature = "chain_queries")]
+mod chain;
 mod distribution;
 mod ibc;
 mod query_response;
+#[cfg(feature = "randomness")]
+mod randomness;
 mod staking;
+#[cfg(feature = "token_factory")]
+mod token_factory;
 mod wasm;
 
 pub use bank::*;
+#[cfg(feature = "chain_queries")]
+pub use chain::*;
 pub use distribution::*;
 pub use ibc::*;
+#[cfg(feature = "randomness")]
+pub use randomness::*;
 pub use staking::*;
+#[cfg(feature = "token_factory")]
+pub use token_factory::*;
 pub use wasm::*;
 /// Enumerates different types of query requests.
 /// Facilitates data retrieval from various modules or contracts in a structured manner.
@@ -63,7 +75,63 @@ pub enum QueryRequest<C> {
     #[cfg(feature = "stargate")]
     Ibc(IbcQuery),
     Wasm(WasmQuery),
+    #[cfg(feature = "chain_queries")]
+    Chain(ChainQuery),
+    #[cfg(feature = "randomness")]
+    Randomness(RandomnessQuery),
+    #[cfg(feature = "token_factory")]
+    TokenFactory(TokenFactoryQuery),
 }
+
+impl<C> QueryRequest<C> {
+    /// The names of all variants available with the enabled features,
+    /// as used in the JSON encoding.
+    pub const VARIANT_NAMES: &'static [&'static str] = &[
+        "bank",
+        "custom",
+        #[cfg(feature = "staking")]
+        "staking",
+        #[cfg(feature = "cosmwasm_1_3")]
+        "distribution",
+        #[cfg(feature = "stargate")]
+        "stargate",
+        #[cfg(feature = "stargate")]
+        "ibc",
+        "wasm",
+        #[cfg(feature = "chain_queries")]
+        "chain",
+        #[cfg(feature = "randomness")]
+        "randomness",
+        #[cfg(feature = "token_factory")]
+        "token_factory",
+    ];
+
+    /// Returns the name of this variant as used in the JSON encoding, e.g. `"bank"`.
+    ///
+    /// This allows building allow-lists or metrics for queries without serializing them.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            QueryRequest::Bank(_) => "bank",
+            QueryRequest::Custom(_) => "custom",
+            #[cfg(feature = "staking")]
+            QueryRequest::Staking(_) => "staking",
+            #[cfg(feature = "cosmwasm_1_3")]
+            QueryRequest::Distribution(_) => "distribution",
+            #[cfg(feature = "stargate")]
+            QueryRequest::Stargate { .. } => "stargate",
+            #[cfg(feature = "stargate")]
+            QueryRequest::Ibc(_) => "ibc",
+            QueryRequest::Wasm(_) => "wasm",
+            #[cfg(feature = "chain_queries")]
+            QueryRequest::Chain(_) => "chain",
+            #[cfg(feature = "randomness")]
+            QueryRequest::Randomness(_) => "randomness",
+            #[cfg(feature = "token_factory")]
+            QueryRequest::TokenFactory(_) => "token_factory",
+        }
+    }
+}
+
 /// A trait that is required to avoid conflicts with other query types like BankQuery and WasmQuery
 /// in generic implementations.
 /// You need to implement it in your custom query type.
@@ -83,7 +151,24 @@ pub enum QueryRequest<C> {
 ///
 /// impl CustomQuery for MyCustomQuery {}
 /// ```
-pub trait CustomQuery: Serialize + Clone {}
+///
+/// Optionally the chain module handling the queries can be specified via [`CustomQuery::MODULE`]:
+///
+/// ```
+/// # use cosmwasm_std::CustomQuery;
+/// # use serde::{Deserialize, Serialize};
+/// # #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+/// # pub enum MyCustomQuery {}
+/// impl CustomQuery for MyCustomQuery {
+///     const MODULE: &'static str = "mymodule";
+/// }
+/// ```
+pub trait CustomQuery: Serialize + Clone {
+    /// The name of the chain module these queries are routed to.
+    /// This is metadata for tooling like schema generators or multi-test
+    /// and empty if not specified.
+    const MODULE: &'static str = "";
+}
 // We require `Clone` because `Clone` in `QueryRequest<C>` is only derived for
 // `C: Clone` and we want consistent behaviour for all `QueryRequest<C>`
 
@@ -127,3 +212,60 @@ impl<C: CustomQuery> From<DistributionQuery> for QueryRequest<C> {
         QueryRequest::Distribution(msg)
     }
 }
+
+#[cfg(feature = "chain_queries")]
+impl<C: CustomQuery> From<ChainQuery> for QueryRequest<C> {
+    fn from(msg: ChainQuery) -> Self {
+        QueryRequest::Chain(msg)
+    }
+}
+
+#[cfg(feature = "randomness")]
+impl<C: CustomQuery> From<RandomnessQuery> for QueryRequest<C> {
+    fn from(msg: RandomnessQuery) -> Self {
+        QueryRequest::Randomness(msg)
+    }
+}
+
+#[cfg(feature = "token_factory")]
+impl<C: CustomQuery> From<TokenFactoryQuery> for QueryRequest<C> {
+    fn from(msg: TokenFactoryQuery) -> Self {
+        QueryRequest::TokenFactory(msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::to_json_string;
+
+    #[test]
+    fn variant_name_matches_json_encoding() {
+        let queries: Vec<QueryRequest<Empty>> = vec![
+            BankQuery::AllBalances {
+                address: "foo".to_string(),
+            }
+            .into(),
+            QueryRequest::Custom(Empty {}),
+            #[cfg(feature = "staking")]
+            StakingQuery::BondedDenom {}.into(),
+            #[cfg(feature = "stargate")]
+            QueryRequest::Stargate {
+                path: "/cosmos.bank.v1beta1.Query/Balance".to_string(),
+                data: Binary::default(),
+            },
+            #[cfg(feature = "stargate")]
+            IbcQuery::PortId {}.into(),
+            WasmQuery::ContractInfo {
+                contract_addr: "foo".to_string(),
+            }
+            .into(),
+        ];
+        for query in queries {
+            let name = query.variant_name();
+            let json = to_json_string(&query).unwrap();
+            assert!(json.starts_with(&format!(r#"{{"{name}":"#)), "{json}");
+            assert!(QueryRequest::<Empty>::VARIANT_NAMES.contains(&name));
+        }
+    }
+}
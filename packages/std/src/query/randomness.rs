@@ -0,0 +1,68 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::HexBinary;
+
+use super::query_response::QueryResponseType;
+
+/// Queries the randomness beacon of the chain. This requires a chain with a randomness
+/// module that exposes the `randomness` capability.
+///
+/// The randomness for a given height is the same for all contracts and all queries,
+/// so it must not be used in situations where it is known to a party before it commits
+/// to an action, e.g. by querying a height that is already finalized.
+#[non_exhaustive]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RandomnessQuery {
+    /// Returns the most recent randomness available on the chain.
+    /// The response type is [`RandomnessResponse`].
+    Latest {},
+    /// Returns the randomness for the given block height. This errors if the
+    /// randomness for the height is not available (yet).
+    /// The response type is [`RandomnessResponse`].
+    ByHeight { height: u64 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct RandomnessResponse {
+    /// The block height the randomness belongs to
+    pub height: u64,
+    /// A 32 byte random seed. Use [`HexBinary::to_array`] to convert it into a fixed size array.
+    pub seed: HexBinary,
+}
+
+impl_response_constructor!(RandomnessResponse, height: u64, seed: HexBinary);
+impl QueryResponseType for RandomnessResponse {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_json, to_json_binary};
+
+    #[test]
+    fn randomness_query_serializes_correctly() {
+        let query = RandomnessQuery::Latest {};
+        let json = to_json_binary(&query).unwrap();
+        assert_eq!(json.as_slice(), br#"{"latest":{}}"#);
+
+        let query = RandomnessQuery::ByHeight { height: 1234 };
+        let json = to_json_binary(&query).unwrap();
+        assert_eq!(json.as_slice(), br#"{"by_height":{"height":1234}}"#);
+    }
+
+    #[test]
+    fn randomness_response_works() {
+        let response: RandomnessResponse = from_json(
+            br#"{"height":1234,"seed":"2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            response,
+            RandomnessResponse::new(1234, HexBinary::from([42u8; 32]))
+        );
+        assert_eq!(response.seed.to_array::<32>().unwrap(), [42u8; 32]);
+    }
+}
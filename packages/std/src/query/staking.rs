@@ -1,7 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::{Addr, Coin, Decimal};
+use crate::{Addr, Coin, Decimal, Uint128};
 
 use super::query_response::QueryResponseType;
 /// Query types for interacting with the staking module.
@@ -32,6 +32,16 @@ pub enum StakingQuery {
         /// The validator's address (e.g. (e.g. cosmosvaloper1...))
         address: String,
     },
+    /// Returns the amount of bonded and not bonded tokens of the chain.
+    ///
+    /// The query response type is `StakingPoolResponse`.
+    #[cfg(feature = "staking_pool_params")]
+    Pool {},
+    /// Returns the parameters of the staking module.
+    ///
+    /// The query response type is `StakingParamsResponse`.
+    #[cfg(feature = "staking_pool_params")]
+    Params {},
 }
 
 /// BondedDenomResponse is data format returned from StakingRequest::BondedDenom query
@@ -46,6 +56,57 @@ impl QueryResponseType for BondedDenomResponse {}
 
 impl_response_constructor!(BondedDenomResponse, denom: String);
 
+/// StakingPoolResponse is data format returned from StakingRequest::Pool query.
+///
+/// See <https://github.com/cosmos/cosmos-sdk/blob/v0.47.4/proto/cosmos/staking/v1beta1/staking.proto#L299-L315>
+#[cfg(feature = "staking_pool_params")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct StakingPoolResponse {
+    /// The tokens bonded to validators in the active set
+    pub bonded_tokens: Coin,
+    /// The tokens that are not bonded, e.g. delegations to inactive validators or unbonding tokens
+    pub not_bonded_tokens: Coin,
+}
+
+#[cfg(feature = "staking_pool_params")]
+impl QueryResponseType for StakingPoolResponse {}
+
+#[cfg(feature = "staking_pool_params")]
+impl_response_constructor!(
+    StakingPoolResponse,
+    bonded_tokens: Coin,
+    not_bonded_tokens: Coin
+);
+
+/// StakingParamsResponse is data format returned from StakingRequest::Params query.
+///
+/// See <https://github.com/cosmos/cosmos-sdk/blob/v0.47.4/proto/cosmos/staking/v1beta1/staking.proto#L317-L339>
+#[cfg(feature = "staking_pool_params")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct StakingParamsResponse {
+    /// The time it takes to unbond tokens, in seconds
+    pub unbonding_time: u64,
+    /// The maximum number of validators in the active set
+    pub max_validators: u32,
+    /// The maximum number of unbonding or redelegation entries per delegator/validator pair
+    pub max_entries: u32,
+}
+
+#[cfg(feature = "staking_pool_params")]
+impl QueryResponseType for StakingParamsResponse {}
+
+#[cfg(feature = "staking_pool_params")]
+impl_response_constructor!(
+    StakingParamsResponse,
+    unbonding_time: u64,
+    max_validators: u32,
+    max_entries: u32
+);
+
 /// DelegationsResponse is data format returned from StakingRequest::AllDelegations query
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -184,6 +245,22 @@ pub struct Validator {
     pub max_commission: Decimal,
     /// The maximum daily increase of the commission
     pub max_change_rate: Decimal,
+    /// The bonding status of the validator.
+    /// This is `None` if the chain does not provide it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<BondStatus>,
+    /// Whether the validator is jailed and thus excluded from the active set.
+    /// This is `None` if the chain does not provide it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jailed: Option<bool>,
+    /// The amount of bonded tokens in the staking denom (see [`StakingQuery::BondedDenom`]).
+    /// This is `None` if the chain does not provide it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<Uint128>,
+    /// The height at which the validator started unbonding.
+    /// This is `None` if the chain does not provide it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unbonding_height: Option<u64>,
 }
 
 impl_response_constructor!(
@@ -191,7 +268,11 @@ impl_response_constructor!(
     address: String,
     commission: Decimal,
     max_commission: Decimal,
-    max_change_rate: Decimal
+    max_change_rate: Decimal,
+    status: Option<BondStatus>,
+    jailed: Option<bool>,
+    tokens: Option<Uint128>,
+    unbonding_height: Option<u64>
 );
 
 impl Validator {
@@ -210,6 +291,117 @@ impl Validator {
             commission,
             max_commission,
             max_change_rate,
+            status: None,
+            jailed: None,
+            tokens: None,
+            unbonding_height: None,
         }
     }
+
+    /// Returns true if the validator is known to be bonded and not jailed,
+    /// i.e. it is part of the active set and delegations to it earn rewards.
+    ///
+    /// Returns false if the chain does not provide the status.
+    pub fn is_active(&self) -> bool {
+        self.status == Some(BondStatus::Bonded) && self.jailed != Some(true)
+    }
+}
+
+/// The bonding status of a validator.
+///
+/// See <https://github.com/cosmos/cosmos-sdk/blob/v0.47.4/proto/cosmos/staking/v1beta1/staking.proto#L283-L297>
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum BondStatus {
+    /// The validator is not part of the active set and not unbonding.
+    Unbonded,
+    /// The validator is leaving the active set and its delegations are unbonding.
+    Unbonding,
+    /// The validator is part of the active set.
+    Bonded,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "staking_pool_params")]
+    use crate::coin;
+    use crate::{from_json, to_json_vec};
+
+    #[cfg(feature = "staking_pool_params")]
+    #[test]
+    fn pool_and_params_queries_serialize_correctly() {
+        let json = to_json_vec(&StakingQuery::Pool {}).unwrap();
+        assert_eq!(json, br#"{"pool":{}}"#);
+        let json = to_json_vec(&StakingQuery::Params {}).unwrap();
+        assert_eq!(json, br#"{"params":{}}"#);
+    }
+
+    #[cfg(feature = "staking_pool_params")]
+    #[test]
+    fn pool_and_params_responses_deserialize_correctly() {
+        let res: StakingPoolResponse = from_json(
+            br#"{"bonded_tokens":{"denom":"ustake","amount":"100"},"not_bonded_tokens":{"denom":"ustake","amount":"5"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            res,
+            StakingPoolResponse::new(coin(100, "ustake"), coin(5, "ustake"))
+        );
+
+        let res: StakingParamsResponse =
+            from_json(br#"{"unbonding_time":1814400,"max_validators":100,"max_entries":7}"#)
+                .unwrap();
+        assert_eq!(res, StakingParamsResponse::new(1814400, 100, 7));
+    }
+
+    #[test]
+    fn validator_serialization_omits_missing_metadata() {
+        let validator = Validator::create(
+            "validator".to_string(),
+            Decimal::percent(1),
+            Decimal::percent(3),
+            Decimal::percent(1),
+        );
+        let json = to_json_vec(&validator).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"address":"validator","commission":"0.01","max_commission":"0.03","max_change_rate":"0.01"}"#
+        );
+        assert_eq!(from_json::<Validator>(&json).unwrap(), validator);
+    }
+
+    #[test]
+    fn validator_deserialization_works_with_metadata() {
+        let json = br#"{"address":"validator","commission":"0.01","max_commission":"0.03","max_change_rate":"0.01","status":"unbonding","jailed":true,"tokens":"1000","unbonding_height":12345}"#;
+        let validator: Validator = from_json(json).unwrap();
+        assert_eq!(validator.status, Some(BondStatus::Unbonding));
+        assert_eq!(validator.jailed, Some(true));
+        assert_eq!(validator.tokens, Some(Uint128::new(1000)));
+        assert_eq!(validator.unbonding_height, Some(12345));
+        assert_eq!(to_json_vec(&validator).unwrap(), json);
+    }
+
+    #[test]
+    fn validator_is_active_works() {
+        let mut validator = Validator::create(
+            "validator".to_string(),
+            Decimal::percent(1),
+            Decimal::percent(3),
+            Decimal::percent(1),
+        );
+        assert!(!validator.is_active());
+
+        validator.status = Some(BondStatus::Bonded);
+        assert!(validator.is_active());
+        validator.jailed = Some(false);
+        assert!(validator.is_active());
+        validator.jailed = Some(true);
+        assert!(!validator.is_active());
+
+        validator.jailed = None;
+        validator.status = Some(BondStatus::Unbonding);
+        assert!(!validator.is_active());
+    }
 }
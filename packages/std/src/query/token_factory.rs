@@ -0,0 +1,108 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::Coin;
+
+use super::query_response::QueryResponseType;
+
+/// Queries the token factory module, which allows contracts to create their own
+/// native denoms of the form `factory/{creator address}/{subdenom}`.
+///
+/// See https://github.com/osmosis-labs/osmosis/blob/v22.0.0/proto/osmosis/tokenfactory/v1beta1/query.proto
+#[non_exhaustive]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenFactoryQuery {
+    /// Returns the full denom for the given creator and subdenom, e.g.
+    /// `factory/{creator_addr}/{subdenom}`.
+    /// The response type is [`FullDenomResponse`].
+    FullDenom {
+        creator_addr: String,
+        subdenom: String,
+    },
+    /// Returns the admin of the given denom.
+    /// The response type is [`DenomAdminResponse`].
+    Admin { denom: String },
+    /// Returns all denoms created by the given address.
+    /// The response type is [`DenomsByCreatorResponse`].
+    DenomsByCreator { creator: String },
+    /// Returns the parameters of the token factory module.
+    /// The response type is [`TokenFactoryParamsResponse`].
+    Params {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct FullDenomResponse {
+    pub denom: String,
+}
+
+impl_response_constructor!(FullDenomResponse, denom: String);
+impl QueryResponseType for FullDenomResponse {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct DenomAdminResponse {
+    /// The admin of the denom. This is an empty string if the denom has no admin.
+    pub admin: String,
+}
+
+impl_response_constructor!(DenomAdminResponse, admin: String);
+impl QueryResponseType for DenomAdminResponse {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct DenomsByCreatorResponse {
+    pub denoms: Vec<String>,
+}
+
+impl_response_constructor!(DenomsByCreatorResponse, denoms: Vec<String>);
+impl QueryResponseType for DenomsByCreatorResponse {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct TokenFactoryParamsResponse {
+    /// The fee charged for creating a new denom
+    pub denom_creation_fee: Vec<Coin>,
+}
+
+impl_response_constructor!(TokenFactoryParamsResponse, denom_creation_fee: Vec<Coin>);
+impl QueryResponseType for TokenFactoryParamsResponse {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{coins, from_json, to_json_binary};
+
+    #[test]
+    fn token_factory_query_serializes_correctly() {
+        let query = TokenFactoryQuery::FullDenom {
+            creator_addr: "creator".to_string(),
+            subdenom: "sub".to_string(),
+        };
+        let json = to_json_binary(&query).unwrap();
+        assert_eq!(
+            json.as_slice(),
+            br#"{"full_denom":{"creator_addr":"creator","subdenom":"sub"}}"#
+        );
+
+        let query = TokenFactoryQuery::Params {};
+        let json = to_json_binary(&query).unwrap();
+        assert_eq!(json.as_slice(), br#"{"params":{}}"#);
+    }
+
+    #[test]
+    fn token_factory_params_response_works() {
+        let response: TokenFactoryParamsResponse =
+            from_json(br#"{"denom_creation_fee":[{"denom":"uosmo","amount":"10000000"}]}"#)
+                .unwrap();
+        assert_eq!(
+            response,
+            TokenFactoryParamsResponse::new(coins(10_000_000, "uosmo"))
+        );
+    }
+}
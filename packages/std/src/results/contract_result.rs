@@ -132,21 +132,12 @@ mod tests {
 
         // fails for additional attributes
         let parse: StdResult<ContractResult<u64>> = from_json(br#"{"unrelated":321,"ok":4554}"#);
-        match parse.unwrap_err() {
-            StdError::ParseErr { .. } => {}
-            err => panic!("Unexpected error: {err:?}"),
-        }
+        assert!(parse.unwrap_err().is_parse_err());
         let parse: StdResult<ContractResult<u64>> = from_json(br#"{"ok":4554,"unrelated":321}"#);
-        match parse.unwrap_err() {
-            StdError::ParseErr { .. } => {}
-            err => panic!("Unexpected error: {err:?}"),
-        }
+        assert!(parse.unwrap_err().is_parse_err());
         let parse: StdResult<ContractResult<u64>> =
             from_json(br#"{"ok":4554,"error":"What's up now?"}"#);
-        match parse.unwrap_err() {
-            StdError::ParseErr { .. } => {}
-            err => panic!("Unexpected error: {err:?}"),
-        }
+        assert!(parse.unwrap_err().is_parse_err());
     }
 
     #[test]
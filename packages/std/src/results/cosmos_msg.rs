@@ -11,12 +11,19 @@ use crate::ibc::IbcMsg;
 use crate::serde::to_json_binary;
 #[cfg(all(feature = "stargate", feature = "cosmwasm_1_2"))]
 use crate::Decimal;
+#[cfg(feature = "token_factory")]
+use crate::{DenomMetadata, Uint128};
 
 use super::Empty;
 
 /// Like CustomQuery for better type clarity.
 /// Also makes it shorter to use as a trait bound.
-pub trait CustomMsg: Serialize + Clone + fmt::Debug + PartialEq + JsonSchema {}
+pub trait CustomMsg: Serialize + Clone + fmt::Debug + PartialEq + JsonSchema {
+    /// The name of the chain module these messages are routed to, e.g. `"tokenfactory"`.
+    /// This is metadata for tooling like schema generators or multi-test
+    /// and empty if not specified.
+    const MODULE: &'static str = "";
+}
 
 impl CustomMsg for Empty {}
 /// Enumerates the various message types within the Cosmos ecosystem.
@@ -40,6 +47,8 @@ pub enum CosmosMsg<T = Empty> {
     Wasm(WasmMsg),
     #[cfg(feature = "stargate")]
     Gov(GovMsg),
+    #[cfg(feature = "token_factory")]
+    TokenFactory(TokenFactoryMsg),
 }
 
 /// Represents the different types of messages processed by the bank module.
@@ -117,6 +126,49 @@ pub enum DistributionMsg {
     },
 }
 
+/// The messages of the token factory module, which allows contracts to create
+/// their own native denoms of the form `factory/{creator address}/{subdenom}`.
+///
+/// Chains using a token factory module (e.g. Osmosis, Neutron or Injective) translate
+/// these to the corresponding messages of their module.
+/// See https://github.com/osmosis-labs/osmosis/blob/v22.0.0/proto/osmosis/tokenfactory/v1beta1/tx.proto
+#[cfg(feature = "token_factory")]
+#[non_exhaustive]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenFactoryMsg {
+    /// Creates the denom `factory/{contract address}/{subdenom}` with the contract as admin.
+    /// The chain might charge a denom creation fee, see [`TokenFactoryQuery::Params`](crate::TokenFactoryQuery::Params).
+    CreateDenom { subdenom: String },
+    /// Mints `amount` of the given denom to `mint_to_address`.
+    /// The contract must be the admin of the denom.
+    MintTokens {
+        denom: String,
+        amount: Uint128,
+        mint_to_address: String,
+    },
+    /// Burns `amount` of the given denom from `burn_from_address`.
+    /// The contract must be the admin of the denom. Not all chains support burning from
+    /// other addresses than the contract's own address.
+    BurnTokens {
+        denom: String,
+        amount: Uint128,
+        burn_from_address: String,
+    },
+    /// Changes the admin of the given denom. An empty `new_admin_address` removes the admin.
+    /// The contract must be the current admin of the denom.
+    ChangeAdmin {
+        denom: String,
+        new_admin_address: String,
+    },
+    /// Sets the bank metadata of the given denom.
+    /// The contract must be the admin of the denom.
+    SetMetadata {
+        denom: String,
+        metadata: DenomMetadata,
+    },
+}
+
 /// A message encoded the same way as a protobuf [Any](https://github.com/protocolbuffers/protobuf/blob/master/src/google/protobuf/any.proto).
 /// This is the same structure as messages in `TxBody` from [ADR-020](https://github.com/cosmos/cosmos-sdk/blob/master/docs/architecture/adr-020-protobuf-transaction-encoding.md)
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -367,6 +419,88 @@ pub fn wasm_execute(
     })
 }
 
+impl<T> CosmosMsg<T> {
+    /// The names of all variants available with the enabled features,
+    /// as used in the JSON encoding.
+    pub const VARIANT_NAMES: &'static [&'static str] = &[
+        "bank",
+        "custom",
+        #[cfg(feature = "staking")]
+        "staking",
+        #[cfg(feature = "staking")]
+        "distribution",
+        "any",
+        #[cfg(feature = "stargate")]
+        "ibc",
+        "wasm",
+        #[cfg(feature = "stargate")]
+        "gov",
+        #[cfg(feature = "token_factory")]
+        "token_factory",
+    ];
+
+    /// Returns the name of this variant as used in the JSON encoding, e.g. `"bank"`.
+    ///
+    /// This allows building allow-lists or metrics for messages without serializing them.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            CosmosMsg::Bank(_) => "bank",
+            CosmosMsg::Custom(_) => "custom",
+            #[cfg(feature = "staking")]
+            CosmosMsg::Staking(_) => "staking",
+            #[cfg(feature = "staking")]
+            CosmosMsg::Distribution(_) => "distribution",
+            CosmosMsg::Any(_) => "any",
+            #[cfg(feature = "stargate")]
+            CosmosMsg::Ibc(_) => "ibc",
+            CosmosMsg::Wasm(_) => "wasm",
+            #[cfg(feature = "stargate")]
+            CosmosMsg::Gov(_) => "gov",
+            #[cfg(feature = "token_factory")]
+            CosmosMsg::TokenFactory(_) => "token_factory",
+        }
+    }
+
+    /// Converts this message into a message with a different custom message type.
+    /// This allows reusing code written against `CosmosMsg<Empty>` in contracts
+    /// with custom messages.
+    ///
+    /// Returns `None` if this is a [`CosmosMsg::Custom`], since its content cannot be converted.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{coins, BankMsg, CosmosMsg, CustomMsg};
+    /// # use schemars::JsonSchema;
+    /// # use serde::{Deserialize, Serialize};
+    /// #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    /// struct MyMsg {}
+    /// impl CustomMsg for MyMsg {}
+    ///
+    /// let msg: CosmosMsg = BankMsg::Burn { amount: coins(12, "earth") }.into();
+    /// let msg: CosmosMsg<MyMsg> = msg.change_custom().unwrap();
+    /// assert!(CosmosMsg::Custom(MyMsg {}).change_custom::<()>().is_none());
+    /// ```
+    pub fn change_custom<U>(self) -> Option<CosmosMsg<U>> {
+        Some(match self {
+            CosmosMsg::Bank(msg) => CosmosMsg::Bank(msg),
+            CosmosMsg::Custom(_) => return None,
+            #[cfg(feature = "staking")]
+            CosmosMsg::Staking(msg) => CosmosMsg::Staking(msg),
+            #[cfg(feature = "staking")]
+            CosmosMsg::Distribution(msg) => CosmosMsg::Distribution(msg),
+            CosmosMsg::Any(msg) => CosmosMsg::Any(msg),
+            #[cfg(feature = "stargate")]
+            CosmosMsg::Ibc(msg) => CosmosMsg::Ibc(msg),
+            CosmosMsg::Wasm(msg) => CosmosMsg::Wasm(msg),
+            #[cfg(feature = "stargate")]
+            CosmosMsg::Gov(msg) => CosmosMsg::Gov(msg),
+            #[cfg(feature = "token_factory")]
+            CosmosMsg::TokenFactory(msg) => CosmosMsg::TokenFactory(msg),
+        })
+    }
+}
+
 impl<T> From<BankMsg> for CosmosMsg<T> {
     fn from(msg: BankMsg) -> Self {
         CosmosMsg::Bank(msg)
@@ -415,6 +549,13 @@ impl<T> From<GovMsg> for CosmosMsg<T> {
     }
 }
 
+#[cfg(feature = "token_factory")]
+impl<T> From<TokenFactoryMsg> for CosmosMsg<T> {
+    fn from(msg: TokenFactoryMsg) -> Self {
+        CosmosMsg::TokenFactory(msg)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -432,6 +573,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn custom_msg_module_works() {
+        #[derive(Serialize, Clone, Debug, PartialEq, JsonSchema)]
+        struct MyMsg;
+        impl CustomMsg for MyMsg {
+            const MODULE: &'static str = "mymodule";
+        }
+
+        assert_eq!(MyMsg::MODULE, "mymodule");
+        assert_eq!(Empty::MODULE, "");
+    }
+
     #[test]
     fn from_any_msg_works() {
         // should work with AnyMsg
@@ -569,6 +722,30 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(feature = "token_factory")]
+    fn msg_token_factory_serializes_to_correct_json() {
+        let msg: CosmosMsg = TokenFactoryMsg::MintTokens {
+            denom: "factory/creator/sub".to_string(),
+            amount: Uint128::new(100),
+            mint_to_address: "recipient".to_string(),
+        }
+        .into();
+        assert_eq!(
+            to_json_string(&msg).unwrap(),
+            r#"{"token_factory":{"mint_tokens":{"denom":"factory/creator/sub","amount":"100","mint_to_address":"recipient"}}}"#,
+        );
+
+        let msg = TokenFactoryMsg::ChangeAdmin {
+            denom: "factory/creator/sub".to_string(),
+            new_admin_address: "".to_string(),
+        };
+        assert_eq!(
+            to_json_string(&msg).unwrap(),
+            r#"{"change_admin":{"denom":"factory/creator/sub","new_admin_address":""}}"#,
+        );
+    }
+
     #[test]
     fn wasm_msg_debug_decodes_binary_string_when_possible() {
         #[cosmwasm_schema::cw_serde]
@@ -647,4 +824,70 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn change_custom_works() {
+        #[derive(Debug, PartialEq, Eq, Clone)]
+        struct Custom {
+            _a: i32,
+        }
+        let send = BankMsg::Send {
+            to_address: "you".to_string(),
+            amount: coins(1015, "earth"),
+        };
+
+        // Custom to Empty
+        let msg: CosmosMsg<Custom> = send.clone().into();
+        let converted = msg.change_custom::<Empty>().unwrap();
+        assert_eq!(converted, CosmosMsg::Bank(send.clone()));
+
+        // Empty to Custom
+        let msg: CosmosMsg<Empty> = send.clone().into();
+        let converted = msg.change_custom::<Custom>().unwrap();
+        assert_eq!(converted, CosmosMsg::Bank(send));
+
+        // Custom variants cannot be converted
+        let msg = CosmosMsg::Custom(Custom { _a: 5 });
+        assert_eq!(msg.change_custom::<Empty>(), None);
+    }
+
+    #[test]
+    fn variant_name_matches_json_encoding() {
+        let msgs: Vec<CosmosMsg> = vec![
+            BankMsg::Burn { amount: vec![] }.into(),
+            CosmosMsg::Custom(Empty {}),
+            #[cfg(feature = "staking")]
+            StakingMsg::Delegate {
+                validator: "validator".to_string(),
+                amount: coin(1, "stake"),
+            }
+            .into(),
+            #[cfg(feature = "staking")]
+            DistributionMsg::SetWithdrawAddress {
+                address: "address".to_string(),
+            }
+            .into(),
+            AnyMsg {
+                type_url: "/cosmos.foo".to_string(),
+                value: Binary::default(),
+            }
+            .into(),
+            WasmMsg::ClearAdmin {
+                contract_addr: "contract".to_string(),
+            }
+            .into(),
+            #[cfg(feature = "stargate")]
+            GovMsg::Vote {
+                proposal_id: 1,
+                vote: VoteOption::Yes,
+            }
+            .into(),
+        ];
+        for msg in msgs {
+            let name = msg.variant_name();
+            let json = to_json_string(&msg).unwrap();
+            assert!(json.starts_with(&format!(r#"{{"{name}":"#)), "{json}");
+            assert!(CosmosMsg::<Empty>::VARIANT_NAMES.contains(&name));
+        }
+    }
 }
@@ -1,7 +1,10 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::forward_ref_partial_eq;
+use crate::{
+    forward_ref_partial_eq, Addr, Coin, Coins, Decimal, Decimal256, Int128, Int256, Int512, Int64,
+    SignedDecimal, SignedDecimal256, Uint128, Uint256, Uint512, Uint64,
+};
 
 /// A full [*Cosmos SDK* event].
 ///
@@ -85,6 +88,98 @@ impl Attribute {
             value: value.into(),
         }
     }
+
+    /// Creates a new Attribute from a typed value, which is formatted according to
+    /// the rules of [`AttributeValue`].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{coins, Attribute, Decimal, Uint128};
+    /// assert_eq!(Attribute::new_typed("amount", Uint128::new(1500)), ("amount", "1500"));
+    /// assert_eq!(Attribute::new_typed("ratio", Decimal::percent(50)), ("ratio", "0.5"));
+    /// assert_eq!(Attribute::new_typed("funds", coins(12, "uatom")), ("funds", "12uatom"));
+    /// ```
+    pub fn new_typed(key: impl Into<String>, value: impl AttributeValue) -> Self {
+        Self::new(key, value.to_attribute_value())
+    }
+}
+
+/// A type with a canonical string representation for use in attribute values.
+///
+/// The formatting rules are chosen such that the same value always results in the same
+/// string, no matter which contract emitted it:
+/// - integers and `Uint*`/`Int*` types are written in decimal without separators or leading zeros
+/// - decimals are written without trailing zeros, e.g. `0.5` and `2`
+/// - a [`Coin`] is written as amount directly followed by the denom, e.g. `12uatom`
+/// - lists of coins are written as comma separated coins, e.g. `12uatom,3ucosm`
+/// - addresses are written as is
+pub trait AttributeValue {
+    fn to_attribute_value(&self) -> String;
+}
+
+impl<T: AttributeValue + ?Sized> AttributeValue for &T {
+    fn to_attribute_value(&self) -> String {
+        (**self).to_attribute_value()
+    }
+}
+
+macro_rules! impl_attribute_value_display {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl AttributeValue for $t {
+                fn to_attribute_value(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+impl_attribute_value_display!(
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    bool,
+    str,
+    String,
+    Uint64,
+    Uint128,
+    Uint256,
+    Uint512,
+    Int64,
+    Int128,
+    Int256,
+    Int512,
+    Decimal,
+    Decimal256,
+    SignedDecimal,
+    SignedDecimal256,
+    Addr,
+    Coin,
+    Coins,
+);
+
+impl AttributeValue for [Coin] {
+    fn to_attribute_value(&self) -> String {
+        self.iter()
+            .map(Coin::to_string)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl AttributeValue for Vec<Coin> {
+    fn to_attribute_value(&self) -> String {
+        self.as_slice().to_attribute_value()
+    }
 }
 
 impl<K: Into<String>, V: Into<String>> From<(K, V)> for Attribute {
@@ -123,6 +218,19 @@ pub fn attr(key: impl Into<String>, value: impl Into<String>) -> Attribute {
     Attribute::new(key, value)
 }
 
+/// Creates a new Attribute with the given coin as value, e.g. `12uatom`.
+#[inline]
+pub fn attr_coin(key: impl Into<String>, coin: &Coin) -> Attribute {
+    Attribute::new_typed(key, coin)
+}
+
+/// Creates a new Attribute with the given amount as value, written in decimal.
+/// Use [`attr_coin`] if the denom should be part of the value.
+#[inline]
+pub fn attr_amount(key: impl Into<String>, amount: impl Into<Uint256>) -> Attribute {
+    Attribute::new_typed(key, amount.into())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,4 +268,68 @@ mod tests {
         assert_eq!(attr("foo", "42"), expected);
         assert_eq!(attr("foo", Uint128::new(42)), expected);
     }
+
+    #[test]
+    fn attribute_new_typed_works() {
+        assert_eq!(Attribute::new_typed("a", 42u8), ("a", "42"));
+        assert_eq!(Attribute::new_typed("a", -42i64), ("a", "-42"));
+        assert_eq!(
+            Attribute::new_typed("a", u128::MAX),
+            ("a", u128::MAX.to_string())
+        );
+        assert_eq!(Attribute::new_typed("a", Uint128::new(0)), ("a", "0"));
+        assert_eq!(Attribute::new_typed("a", Int256::from(-7)), ("a", "-7"));
+        assert_eq!(
+            Attribute::new_typed("a", Uint512::from(10u128.pow(30))),
+            ("a", "1000000000000000000000000000000")
+        );
+        assert_eq!(Attribute::new_typed("a", true), ("a", "true"));
+        assert_eq!(Attribute::new_typed("a", "text"), ("a", "text"));
+        assert_eq!(
+            Attribute::new_typed("a", Addr::unchecked("cosmos1abc")),
+            ("a", "cosmos1abc")
+        );
+
+        // decimals without trailing zeros
+        assert_eq!(
+            Attribute::new_typed("a", Decimal::percent(150)),
+            ("a", "1.5")
+        );
+        assert_eq!(Attribute::new_typed("a", Decimal::percent(200)), ("a", "2"));
+        assert_eq!(Attribute::new_typed("a", Decimal256::zero()), ("a", "0"));
+        assert_eq!(
+            Attribute::new_typed("a", SignedDecimal::percent(-5)),
+            ("a", "-0.05")
+        );
+
+        // coins
+        let coin = Coin::new(12u128, "uatom");
+        assert_eq!(Attribute::new_typed("a", &coin), ("a", "12uatom"));
+        let list = vec![coin.clone(), Coin::new(3u128, "ucosm")];
+        assert_eq!(Attribute::new_typed("a", &list), ("a", "12uatom,3ucosm"));
+        assert_eq!(
+            Attribute::new_typed("a", list.as_slice()),
+            ("a", "12uatom,3ucosm")
+        );
+        assert_eq!(Attribute::new_typed("a", Vec::<Coin>::new()), ("a", ""));
+        let coins = Coins::try_from(vec![Coin::new(3u128, "ucosm"), coin]).unwrap();
+        assert_eq!(Attribute::new_typed("a", coins), ("a", "12uatom,3ucosm"));
+    }
+
+    #[test]
+    fn attr_coin_and_attr_amount_work() {
+        assert_eq!(
+            attr_coin("fee", &Coin::new(1500u128, "ucosm")),
+            ("fee", "1500ucosm")
+        );
+        assert_eq!(attr_amount("amount", 1500u128), ("amount", "1500"));
+        assert_eq!(
+            attr_amount("amount", Uint128::new(1500)),
+            ("amount", "1500")
+        );
+        assert_eq!(
+            attr_amount("amount", Uint256::MAX),
+            ("amount", Uint256::MAX.to_string())
+        );
+    }
 }
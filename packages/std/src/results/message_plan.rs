@@ -0,0 +1,247 @@
+use alloc::vec::Vec;
+
+use crate::errors::{StdError, StdResult};
+
+use super::submessages::UNUSED_MSG_ID;
+use super::{CosmosMsg, Empty, ReplyOn, SubMsg};
+
+/// A builder for the messages of a [`Response`](crate::Response) that assigns the reply ids
+/// of its submessages.
+///
+/// Reply ids are assigned sequentially, starting at the id passed to [`MessagePlan::new`].
+/// Submessages with an explicit id are checked against all ids already in the plan,
+/// such that no two submessages of a response can be routed to the same `reply` branch.
+/// Since the ids of a response's submessages must not collide with those of
+/// submessages still waiting for a reply from earlier calls (e.g. IBC acknowledgements), the
+/// contract should persist [`MessagePlan::next_id`] and start the next plan from there.
+///
+/// ## Ordering and atomicity
+///
+/// Messages are executed in the order they were added, depth first: a message and all
+/// messages it emits, including the `reply` call of a submessage, are processed before the
+/// next message of the plan. Each submessage is atomic, i.e. if it fails, all of its state
+/// changes are reverted and, depending on its [`ReplyOn`], the error is either passed to
+/// `reply` or aborts the whole transaction. State changes made by the calling contract
+/// before emitting the messages are not reverted when a submessage fails.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{coins, BankMsg, MessagePlan, ReplyOn, Response};
+/// # let send = BankMsg::Send { to_address: String::from("you"), amount: coins(1015, "earth") };
+/// // usually loaded from storage
+/// let next_id = 7;
+///
+/// let mut plan = MessagePlan::new(next_id);
+/// plan.add_message(send.clone());
+/// let first = plan.add_reply(send.clone(), ReplyOn::Success)?;
+/// let second = plan.add_reply(send, ReplyOn::Error)?;
+/// assert_eq!((first, second), (7, 8));
+///
+/// // persist this for the next plan
+/// assert_eq!(plan.next_id(), 9);
+///
+/// let response: Response = Response::new().add_submessages(plan);
+/// assert_eq!(response.messages.len(), 3);
+/// # Ok::<(), cosmwasm_std::StdError>(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessagePlan<T = Empty> {
+    next_id: u64,
+    messages: Vec<SubMsg<T>>,
+}
+
+impl<T> MessagePlan<T> {
+    /// Creates an empty plan which assigns reply ids starting at `next_id`.
+    ///
+    /// Since 0 is used for submessages without a reply (see [`SubMsg::new`]),
+    /// ids start at 1 if `next_id` is 0.
+    pub fn new(next_id: u64) -> Self {
+        Self {
+            next_id: next_id.max(1),
+            messages: vec![],
+        }
+    }
+
+    /// The id that will be assigned to the next submessage with a reply.
+    ///
+    /// Store this after building the plan and pass it to [`MessagePlan::new`]
+    /// when building the next one.
+    pub fn next_id(&self) -> u64 {
+        self.next_id
+    }
+
+    /// Returns true if a submessage with a reply and the given id is part of the plan
+    pub fn contains_id(&self, id: u64) -> bool {
+        self.messages
+            .iter()
+            .any(|msg| msg.reply_on != ReplyOn::Never && msg.id == id)
+    }
+
+    /// Adds a message without a reply
+    pub fn add_message(&mut self, msg: impl Into<CosmosMsg<T>>) -> &mut Self {
+        self.messages.push(SubMsg::new(msg));
+        self
+    }
+
+    /// Adds a submessage with the given reply behaviour and returns the id assigned to it.
+    ///
+    /// If `reply_on` is [`ReplyOn::Never`], no id is consumed and 0 is returned.
+    ///
+    /// Fails if all ids up to `u64::MAX` are used up.
+    pub fn add_reply(&mut self, msg: impl Into<CosmosMsg<T>>, reply_on: ReplyOn) -> StdResult<u64> {
+        let id = if reply_on == ReplyOn::Never {
+            UNUSED_MSG_ID
+        } else {
+            self.next_id
+        };
+        let msg = SubMsg {
+            id,
+            msg: msg.into(),
+            gas_limit: None,
+            reply_on,
+        };
+        self.add_submessage(msg)?;
+        Ok(id)
+    }
+
+    /// Adds a submessage with an explicit id, e.g. to set a gas limit.
+    ///
+    /// Fails if the submessage has a reply and another submessage with a reply and the same id
+    /// is already part of the plan. If the id is not lower than [`MessagePlan::next_id`], the next
+    /// id is moved past it.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{coins, BankMsg, MessagePlan, SubMsg};
+    /// # let send = BankMsg::Send { to_address: String::from("you"), amount: coins(1015, "earth") };
+    /// let mut plan: MessagePlan = MessagePlan::new(1);
+    /// let id = plan.next_id();
+    /// plan.add_submessage(SubMsg::reply_always(send.clone(), id).with_gas_limit(60_000))
+    ///     .unwrap();
+    /// assert_eq!(plan.next_id(), 2);
+    ///
+    /// // ids cannot be used twice
+    /// plan.add_submessage(SubMsg::reply_on_error(send, id)).unwrap_err();
+    /// ```
+    pub fn add_submessage(&mut self, msg: SubMsg<T>) -> StdResult<&mut Self> {
+        if msg.reply_on != ReplyOn::Never {
+            if self.contains_id(msg.id) {
+                return Err(StdError::generic_err(format!(
+                    "Duplicate reply id {} in message plan",
+                    msg.id
+                )));
+            }
+            if msg.id >= self.next_id {
+                self.next_id = msg
+                    .id
+                    .checked_add(1)
+                    .ok_or_else(|| StdError::generic_err("No reply ids left in message plan"))?;
+            }
+        }
+        self.messages.push(msg);
+        Ok(self)
+    }
+
+    /// Returns the submessages of the plan in execution order
+    pub fn messages(&self) -> &[SubMsg<T>] {
+        &self.messages
+    }
+
+    /// Consumes the plan and returns its submessages in execution order
+    pub fn into_messages(self) -> Vec<SubMsg<T>> {
+        self.messages
+    }
+}
+
+impl<T> IntoIterator for MessagePlan<T> {
+    type Item = SubMsg<T>;
+    type IntoIter = alloc::vec::IntoIter<SubMsg<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.messages.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{coins, BankMsg};
+
+    fn send() -> BankMsg {
+        BankMsg::Send {
+            to_address: String::from("you"),
+            amount: coins(1015, "earth"),
+        }
+    }
+
+    #[test]
+    fn message_plan_assigns_sequential_ids() {
+        let mut plan = MessagePlan::<Empty>::new(0);
+        assert_eq!(plan.next_id(), 1);
+
+        plan.add_message(send());
+        assert_eq!(plan.add_reply(send(), ReplyOn::Always).unwrap(), 1);
+        assert_eq!(plan.add_reply(send(), ReplyOn::Never).unwrap(), 0);
+        assert_eq!(plan.add_reply(send(), ReplyOn::Success).unwrap(), 2);
+        assert_eq!(plan.next_id(), 3);
+
+        assert!(plan.contains_id(1));
+        assert!(plan.contains_id(2));
+        assert!(!plan.contains_id(0));
+        assert_eq!(
+            plan.into_messages(),
+            vec![
+                SubMsg::new(send()),
+                SubMsg::reply_always(send(), 1),
+                SubMsg::new(send()),
+                SubMsg::reply_on_success(send(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn message_plan_add_submessage_works() {
+        let mut plan = MessagePlan::<Empty>::new(5);
+
+        // lower ids are accepted, but do not change the next id
+        plan.add_submessage(SubMsg::reply_on_error(send(), 2))
+            .unwrap();
+        assert_eq!(plan.next_id(), 5);
+
+        // higher ids move the next id
+        plan.add_submessage(SubMsg::reply_always(send(), 10))
+            .unwrap();
+        assert_eq!(plan.next_id(), 11);
+        assert_eq!(plan.add_reply(send(), ReplyOn::Error).unwrap(), 11);
+
+        // messages without reply do not conflict
+        plan.add_submessage(SubMsg::new(send())).unwrap();
+        plan.add_submessage(SubMsg::new(send())).unwrap();
+
+        // duplicates are rejected
+        let err = plan
+            .add_submessage(SubMsg::reply_on_success(send(), 10))
+            .unwrap_err();
+        assert!(err.to_string().contains("Duplicate reply id 10"));
+        assert_eq!(plan.messages().len(), 5);
+    }
+
+    #[test]
+    fn message_plan_rejects_id_overflow() {
+        let mut plan = MessagePlan::<Empty>::new(1);
+        let err = plan
+            .add_submessage(SubMsg::reply_always(send(), u64::MAX))
+            .unwrap_err();
+        assert!(err.to_string().contains("No reply ids left"));
+        assert!(plan.messages().is_empty());
+
+        let mut plan = MessagePlan::<Empty>::new(u64::MAX);
+        let err = plan.add_reply(send(), ReplyOn::Always).unwrap_err();
+        assert!(err.to_string().contains("No reply ids left"));
+        // messages without a reply do not need an id
+        assert_eq!(plan.add_reply(send(), ReplyOn::Never).unwrap(), 0);
+        assert_eq!(plan.messages(), [SubMsg::new(send())]);
+    }
+}
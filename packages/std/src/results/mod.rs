@@ -4,12 +4,16 @@ mod contract_result;
 mod cosmos_msg;
 mod empty;
 mod events;
+mod message_plan;
 mod query;
+mod reply_id;
 mod response;
 mod submessages;
 mod system_result;
 
 pub use contract_result::ContractResult;
+#[cfg(feature = "token_factory")]
+pub use cosmos_msg::TokenFactoryMsg;
 #[cfg(all(feature = "stargate", feature = "cosmwasm_1_2"))]
 pub use cosmos_msg::WeightedVoteOption;
 pub use cosmos_msg::{
@@ -20,8 +24,12 @@ pub use cosmos_msg::{DistributionMsg, StakingMsg};
 #[cfg(feature = "stargate")]
 pub use cosmos_msg::{GovMsg, VoteOption};
 pub use empty::Empty;
-pub use events::{attr, Attribute, Event};
+pub use events::{attr, attr_amount, attr_coin, Attribute, AttributeValue, Event};
+pub use message_plan::MessagePlan;
 pub use query::QueryResponse;
+pub use reply_id::ReplyId;
 pub use response::Response;
-pub use submessages::{MsgResponse, Reply, ReplyOn, SubMsg, SubMsgResponse, SubMsgResult};
+pub use submessages::{
+    MsgResponse, Reply, ReplyOn, SubMsg, SubMsgResponse, SubMsgResult, IBC_ACK_TYPE_URL,
+};
 pub use system_result::SystemResult;
@@ -0,0 +1,157 @@
+use crate::errors::{ConversionOverflowError, OverflowError, OverflowOperation};
+
+/// A structured reply id, packed into the `u64` id of a [`SubMsg`](crate::SubMsg).
+///
+/// A reply id consists of
+///
+/// - a `tag` identifying the module (or workflow) of the contract that emitted the submessage,
+/// - an `action` identifying the step within that module and
+/// - an `index`, e.g. a counter or the position of the submessage in a batch.
+///
+/// They are packed as `tag << 48 | action << 32 | index`, such that every `u64` can be unpacked
+/// into a `ReplyId` in the `reply` entry point. Note that the all-zero id is
+/// the id used for submessages without a reply, so tags should start at 1.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{coins, BankMsg, ReplyId, SubMsg};
+/// # let msg = BankMsg::Send { to_address: String::from("you"), amount: coins(1015, "earth") };
+/// const PAYMENTS: u16 = 1;
+/// const REFUND: u16 = 3;
+///
+/// let id = ReplyId::new(PAYMENTS, REFUND, 42);
+/// let sub_msg: SubMsg = SubMsg::reply_on_error(msg, id.pack());
+///
+/// // in `reply`
+/// let id = ReplyId::unpack(sub_msg.id);
+/// match (id.tag(), id.action()) {
+///     (PAYMENTS, REFUND) => assert_eq!(id.index(), 42),
+///     _ => unreachable!(),
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ReplyId {
+    tag: u16,
+    action: u16,
+    index: u32,
+}
+
+impl ReplyId {
+    pub const fn new(tag: u16, action: u16, index: u32) -> Self {
+        Self { tag, action, index }
+    }
+
+    /// Creates a reply id from an index of a wider type, e.g. a `u64` counter in storage.
+    ///
+    /// Fails if the index does not fit into the 32 bits of the packed id.
+    pub fn try_new(tag: u16, action: u16, index: u64) -> Result<Self, ConversionOverflowError> {
+        let index = u32::try_from(index).map_err(|_| ConversionOverflowError::new("u64", "u32"))?;
+        Ok(Self::new(tag, action, index))
+    }
+
+    pub const fn tag(&self) -> u16 {
+        self.tag
+    }
+
+    pub const fn action(&self) -> u16 {
+        self.action
+    }
+
+    pub const fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns the same id with the index incremented by one,
+    /// or an error if the index overflows.
+    pub fn checked_next(self) -> Result<Self, OverflowError> {
+        let index = self
+            .index
+            .checked_add(1)
+            .ok_or_else(|| OverflowError::new(OverflowOperation::Add))?;
+        Ok(Self { index, ..self })
+    }
+
+    /// Packs the reply id into the `u64` used as the id of a [`SubMsg`](crate::SubMsg)
+    pub const fn pack(self) -> u64 {
+        (self.tag as u64) << 48 | (self.action as u64) << 32 | self.index as u64
+    }
+
+    /// Unpacks the id of a [`Reply`](crate::Reply)
+    pub const fn unpack(id: u64) -> Self {
+        Self {
+            tag: (id >> 48) as u16,
+            action: (id >> 32) as u16,
+            index: id as u32,
+        }
+    }
+}
+
+impl From<ReplyId> for u64 {
+    fn from(id: ReplyId) -> Self {
+        id.pack()
+    }
+}
+
+impl From<u64> for ReplyId {
+    fn from(id: u64) -> Self {
+        Self::unpack(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reply_id_pack_works() {
+        assert_eq!(ReplyId::new(0, 0, 0).pack(), 0);
+        assert_eq!(ReplyId::new(0, 0, 7).pack(), 7);
+        assert_eq!(ReplyId::new(0, 1, 0).pack(), 1 << 32);
+        assert_eq!(ReplyId::new(1, 0, 0).pack(), 1 << 48);
+        assert_eq!(
+            ReplyId::new(0x1234, 0x5678, 0x9abcdef0).pack(),
+            0x1234_5678_9abc_def0
+        );
+        assert_eq!(ReplyId::new(u16::MAX, u16::MAX, u32::MAX).pack(), u64::MAX);
+        assert_eq!(u64::from(ReplyId::new(1, 2, 3)), 0x0001_0002_0000_0003);
+    }
+
+    #[test]
+    fn reply_id_unpack_works() {
+        let id = ReplyId::unpack(0x1234_5678_9abc_def0);
+        assert_eq!(id.tag(), 0x1234);
+        assert_eq!(id.action(), 0x5678);
+        assert_eq!(id.index(), 0x9abcdef0);
+
+        for id in [0, 1, 1 << 32, 1 << 48, 0xdead_beef_0000_ffff, u64::MAX] {
+            assert_eq!(ReplyId::unpack(id).pack(), id);
+            assert_eq!(u64::from(ReplyId::from(id)), id);
+        }
+    }
+
+    #[test]
+    fn reply_id_try_new_works() {
+        assert_eq!(ReplyId::try_new(1, 2, 3).unwrap(), ReplyId::new(1, 2, 3));
+        assert_eq!(
+            ReplyId::try_new(1, 2, u32::MAX as u64).unwrap(),
+            ReplyId::new(1, 2, u32::MAX)
+        );
+        assert_eq!(
+            ReplyId::try_new(1, 2, u32::MAX as u64 + 1).unwrap_err(),
+            ConversionOverflowError::new("u64", "u32")
+        );
+    }
+
+    #[test]
+    fn reply_id_checked_next_works() {
+        assert_eq!(
+            ReplyId::new(1, 2, 3).checked_next().unwrap(),
+            ReplyId::new(1, 2, 4)
+        );
+        assert_eq!(
+            ReplyId::new(1, 2, u32::MAX).checked_next().unwrap_err(),
+            OverflowError::new(OverflowOperation::Add)
+        );
+    }
+}
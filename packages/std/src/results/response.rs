@@ -228,6 +228,37 @@ impl<T> Response<T> {
         self.data = Some(data.into());
         self
     }
+
+    /// Converts this response into a response with a different custom message type.
+    /// This allows using libraries written against `Response<Empty>` in contracts
+    /// with custom messages.
+    ///
+    /// Returns `None` if any of the messages is a [`CosmosMsg::Custom`].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{coins, BankMsg, Response};
+    /// fn library_response() -> Response {
+    ///     Response::new().add_message(BankMsg::Burn { amount: coins(12, "earth") })
+    /// }
+    ///
+    /// // in a contract with custom messages of type `String`
+    /// let res: Response<String> = library_response().change_custom().unwrap();
+    /// assert_eq!(res.messages.len(), 1);
+    /// ```
+    pub fn change_custom<U>(self) -> Option<Response<U>> {
+        Some(Response {
+            messages: self
+                .messages
+                .into_iter()
+                .map(|msg| msg.change_custom())
+                .collect::<Option<Vec<_>>>()?,
+            attributes: self.attributes,
+            events: self.events,
+            data: self.data,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -328,4 +359,40 @@ mod tests {
         assert!(failure.is_err());
         assert!(!success.is_err());
     }
+
+    #[test]
+    fn change_custom_works() {
+        let response: Response<Empty> = Response {
+            messages: vec![SubMsg::reply_always(
+                BankMsg::Burn {
+                    amount: coins(12, "earth"),
+                },
+                12,
+            )
+            .with_gas_limit(12345)],
+            attributes: vec![Attribute::new("foo", "bar")],
+            events: vec![Event::new("our_event").add_attribute("msg", "hello")],
+            data: Some(Binary::from(b"data")),
+        };
+        let converted: Response<String> = response.clone().change_custom().unwrap();
+        assert_eq!(
+            converted,
+            Response {
+                messages: vec![SubMsg::reply_always(
+                    BankMsg::Burn {
+                        amount: coins(12, "earth"),
+                    },
+                    12,
+                )
+                .with_gas_limit(12345)],
+                attributes: response.attributes,
+                events: response.events,
+                data: response.data,
+            }
+        );
+
+        let response: Response<String> =
+            Response::new().add_message(CosmosMsg::Custom("custom".to_string()));
+        assert_eq!(response.change_custom::<Empty>(), None);
+    }
 }
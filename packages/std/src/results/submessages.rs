@@ -7,8 +7,11 @@ use super::{CosmosMsg, Empty, Event};
 
 /// Specifies when a contract receives a callback response.
 /// Useful for optimizing gas usage and tailoring response handling.
+///
+/// More variants may be added in the future, so matches on this enum need a wildcard arm.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum ReplyOn {
     /// Always perform a callback after SubMsg is processed
     Always,
@@ -18,8 +21,33 @@ pub enum ReplyOn {
     Success,
     /// Never make a callback - this is like the original CosmosMsg semantics
     Never,
+    /// Only callback once the acknowledgement for the IBC packet sent by the SubMsg
+    /// (e.g. an [`IbcMsg::Transfer`](crate::IbcMsg::Transfer)) was received.
+    /// This happens in a later block, not directly after the SubMsg is processed.
+    ///
+    /// The `result` is [`SubMsgResult::Ok`] with a single entry in `msg_responses`
+    /// of type [`IBC_ACK_TYPE_URL`] containing the protobuf encoded acknowledgement.
+    /// If the SubMsg itself fails, there is no callback and the error is returned
+    /// like for [`ReplyOn::Never`].
+    ///
+    /// This does not require the contract to be IBC-enabled.
+    Ack,
+    /// Only callback if the IBC packet sent by the SubMsg (e.g. an
+    /// [`IbcMsg::Transfer`](crate::IbcMsg::Transfer)) timed out.
+    /// This happens in a later block, not directly after the SubMsg is processed.
+    ///
+    /// The `result` is [`SubMsgResult::Err`] describing the timeout.
+    /// If the SubMsg itself fails, there is no callback and the error is returned
+    /// like for [`ReplyOn::Never`].
+    ///
+    /// This does not require the contract to be IBC-enabled.
+    Timeout,
 }
 
+/// The type URL of the [`MsgResponse`] containing the acknowledgement of an IBC packet
+/// in a reply for [`ReplyOn::Ack`].
+pub const IBC_ACK_TYPE_URL: &str = "/ibc.core.channel.v1.Acknowledgement";
+
 /// A submessage that will guarantee a `reply` call on success or error, depending on
 /// the `reply_on` setting. If you do not need to process the result, use regular messages instead.
 ///
@@ -61,6 +89,18 @@ impl<T> SubMsg<T> {
         Self::reply_on(msg.into(), id, ReplyOn::Always)
     }
 
+    /// create a `SubMsg` that will provide a `reply` with the given id once the acknowledgement
+    /// of the IBC packet sent by the message was received
+    pub fn reply_on_ack(msg: impl Into<CosmosMsg<T>>, id: u64) -> Self {
+        Self::reply_on(msg.into(), id, ReplyOn::Ack)
+    }
+
+    /// create a `SubMsg` that will provide a `reply` with the given id if the IBC packet
+    /// sent by the message timed out
+    pub fn reply_on_timeout(msg: impl Into<CosmosMsg<T>>, id: u64) -> Self {
+        Self::reply_on(msg.into(), id, ReplyOn::Timeout)
+    }
+
     /// create a `SubMsg` that will never `reply`. This is equivalent to standard message semantics.
     pub fn reply_never(msg: impl Into<CosmosMsg<T>>) -> Self {
         Self::reply_on(msg.into(), UNUSED_MSG_ID, ReplyOn::Never)
@@ -84,6 +124,18 @@ impl<T> SubMsg<T> {
         self
     }
 
+    /// Converts this submessage into one with a different custom message type.
+    ///
+    /// Returns `None` if the message is a [`CosmosMsg::Custom`].
+    pub fn change_custom<U>(self) -> Option<SubMsg<U>> {
+        Some(SubMsg {
+            id: self.id,
+            msg: self.msg.change_custom()?,
+            gas_limit: self.gas_limit,
+            reply_on: self.reply_on,
+        })
+    }
+
     fn reply_on(msg: CosmosMsg<T>, id: u64, reply_on: ReplyOn) -> Self {
         SubMsg {
             id,
@@ -103,6 +155,13 @@ pub struct Reply {
     pub id: u64,
     /// The amount of gas used by the submessage,
     /// measured in [Cosmos SDK gas](https://github.com/CosmWasm/cosmwasm/blob/main/docs/GAS.md).
+    /// This includes the gas of all nested calls of the submessage and is set by the host,
+    /// so it can be used to account for the actual cost of the submessage,
+    /// e.g. to reimburse a relayer.
+    ///
+    /// This only contains a useful value on chains running CosmWasm 2.0 or higher.
+    /// On older chains, this field is always 0.
+    #[serde(default)]
     pub gas_used: u64,
     pub result: SubMsgResult,
 }
@@ -251,6 +310,25 @@ mod tests {
         assert_eq!(&to_json_vec(&result).unwrap(), b"{\"error\":\"broken\"}");
     }
 
+    #[test]
+    fn reply_deserialization_works() {
+        let reply: Reply =
+            from_json(br#"{"id":5,"gas_used":1234,"result":{"error":"broken"}}"#).unwrap();
+        assert_eq!(
+            reply,
+            Reply {
+                id: 5,
+                gas_used: 1234,
+                result: SubMsgResult::Err("broken".to_string()),
+            }
+        );
+
+        // should work without `gas_used`
+        // this is the case for pre-2.0 CosmWasm chains
+        let reply: Reply = from_json(br#"{"id":5,"result":{"error":"broken"}}"#).unwrap();
+        assert_eq!(reply.gas_used, 0);
+    }
+
     #[test]
     fn sub_msg_result_deserialization_works() {
         // should work without `msg_responses`
@@ -296,15 +374,9 @@ mod tests {
 
         // fails for additional attributes
         let parse: StdResult<SubMsgResult> = from_json(br#"{"unrelated":321,"error":"broken"}"#);
-        match parse.unwrap_err() {
-            StdError::ParseErr { .. } => {}
-            err => panic!("Unexpected error: {err:?}"),
-        }
+        assert!(parse.unwrap_err().is_parse_err());
         let parse: StdResult<SubMsgResult> = from_json(br#"{"error":"broken","unrelated":321}"#);
-        match parse.unwrap_err() {
-            StdError::ParseErr { .. } => {}
-            err => panic!("Unexpected error: {err:?}"),
-        }
+        assert!(parse.unwrap_err().is_parse_err());
     }
 
     #[test]
@@ -416,4 +488,30 @@ mod tests {
         let converted: Result<SubMsgResponse, String> = original.into();
         assert_eq!(converted, Err("went wrong".to_string()));
     }
+
+    #[test]
+    #[cfg(feature = "stargate")]
+    fn reply_on_ibc_lifecycle_works() {
+        let msg = crate::IbcMsg::Transfer {
+            channel_id: "channel-0".to_string(),
+            to_address: "remote".to_string(),
+            amount: crate::coin(10, "ucosm"),
+            timeout: crate::Timestamp::from_seconds(42).into(),
+            memo: None,
+        };
+
+        let sub_msg: SubMsg = SubMsg::reply_on_ack(msg.clone(), 7);
+        assert_eq!(sub_msg.id, 7);
+        assert_eq!(sub_msg.reply_on, ReplyOn::Ack);
+        let sub_msg: SubMsg = SubMsg::reply_on_timeout(msg, 8);
+        assert_eq!(sub_msg.id, 8);
+        assert_eq!(sub_msg.reply_on, ReplyOn::Timeout);
+
+        assert_eq!(to_json_vec(&ReplyOn::Ack).unwrap(), br#""ack""#);
+        assert_eq!(to_json_vec(&ReplyOn::Timeout).unwrap(), br#""timeout""#);
+        assert_eq!(
+            from_json::<ReplyOn>(br#""timeout""#).unwrap(),
+            ReplyOn::Timeout
+        );
+    }
 }
@@ -0,0 +1,314 @@
+//! Helpers to convert ECDSA secp256k1 signatures into the 64 byte compact format
+//! expected by [`Api::secp256k1_verify`](crate::Api::secp256k1_verify).
+
+use thiserror::Error;
+
+use crate::math::Uint256;
+
+/// Length of a serialized compact signature (`r || s`)
+const COMPACT_SIGNATURE_LEN: usize = 64;
+
+/// The order `n` of the secp256k1 curve
+const CURVE_ORDER: Uint256 = Uint256::from_be_bytes([
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+    0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41,
+]);
+
+/// The ASN.1 tag of a sequence
+const DER_SEQUENCE: u8 = 0x30;
+/// The ASN.1 tag of an integer
+const DER_INTEGER: u8 = 0x02;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Secp256k1SignatureError {
+    #[error("Invalid DER encoding: {reason}")]
+    InvalidDer { reason: &'static str },
+    #[error("Invalid signature length: expected 64 bytes, got {length}")]
+    InvalidLength { length: usize },
+    #[error("Signature values r and s must be between 1 and the curve order")]
+    OutOfRange,
+}
+
+impl Secp256k1SignatureError {
+    fn invalid_der(reason: &'static str) -> Self {
+        Secp256k1SignatureError::InvalidDer { reason }
+    }
+}
+
+/// Converts a DER encoded secp256k1 signature into the 64 byte compact format (`r || s`)
+/// and normalizes it to low-S (see [`secp256k1_normalize_signature`]).
+///
+/// Many external signers like hardware wallets, OpenSSL or cloud key management
+/// services produce signatures in this format. Only strict DER as specified in
+/// [BIP 66](https://github.com/bitcoin/bips/blob/master/bip-0066.mediawiki) is accepted.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::secp256k1_signature_from_der;
+/// let der = [
+///     0x30, 0x06, // sequence of 6 bytes
+///     0x02, 0x01, 0x05, // r = 5
+///     0x02, 0x01, 0x07, // s = 7
+/// ];
+/// let signature = secp256k1_signature_from_der(&der).unwrap();
+/// assert_eq!(signature[31], 5);
+/// assert_eq!(signature[63], 7);
+/// ```
+pub fn secp256k1_signature_from_der(der: &[u8]) -> Result<[u8; 64], Secp256k1SignatureError> {
+    let (tag, content, rest) = read_der_element(der)?;
+    if tag != DER_SEQUENCE {
+        return Err(Secp256k1SignatureError::invalid_der("expected a sequence"));
+    }
+    if !rest.is_empty() {
+        return Err(Secp256k1SignatureError::invalid_der(
+            "trailing data after the sequence",
+        ));
+    }
+
+    let (r, content) = read_der_integer(content)?;
+    let (s, content) = read_der_integer(content)?;
+    if !content.is_empty() {
+        return Err(Secp256k1SignatureError::invalid_der(
+            "trailing data in the sequence",
+        ));
+    }
+
+    let mut signature = [0u8; COMPACT_SIGNATURE_LEN];
+    signature[32 - r.len()..32].copy_from_slice(r);
+    signature[64 - s.len()..].copy_from_slice(s);
+    secp256k1_normalize_signature(&signature)
+}
+
+/// Normalizes a 64 byte compact secp256k1 signature (`r || s`) to low-S, i.e. if `s` is
+/// in the upper half of the curve order, it is replaced by `n - s`.
+///
+/// Both signatures are valid for the same message and public key. [`Api::secp256k1_verify`]
+/// accepts both, but protocols like Cosmos SDK transactions or Ethereum only allow low-S
+/// to avoid signature malleability. Use this to get a unique representation of a signature,
+/// e.g. before storing it to prevent replays.
+///
+/// Errors if the signature is not 64 bytes long or `r` or `s` are not in the range `[1, n-1]`.
+///
+/// [`Api::secp256k1_verify`]: crate::Api::secp256k1_verify
+pub fn secp256k1_normalize_signature(
+    signature: &[u8],
+) -> Result<[u8; 64], Secp256k1SignatureError> {
+    let signature: [u8; COMPACT_SIGNATURE_LEN] =
+        signature
+            .try_into()
+            .map_err(|_| Secp256k1SignatureError::InvalidLength {
+                length: signature.len(),
+            })?;
+
+    let mut r = [0u8; 32];
+    r.copy_from_slice(&signature[..32]);
+    let mut s = [0u8; 32];
+    s.copy_from_slice(&signature[32..]);
+    let r = Uint256::from_be_bytes(r);
+    let s = Uint256::from_be_bytes(s);
+    if r.is_zero() || r >= CURVE_ORDER || s.is_zero() || s >= CURVE_ORDER {
+        return Err(Secp256k1SignatureError::OutOfRange);
+    }
+
+    let mut normalized = signature;
+    if s > CURVE_ORDER >> 1 {
+        normalized[32..].copy_from_slice(&(CURVE_ORDER - s).to_be_bytes());
+    }
+    Ok(normalized)
+}
+
+/// Reads a DER element with a single byte tag and returns the tag, the content and the
+/// remaining data. Signatures are always shorter than 128 bytes, so only the short
+/// length form is supported.
+fn read_der_element(data: &[u8]) -> Result<(u8, &[u8], &[u8]), Secp256k1SignatureError> {
+    let [tag, length, rest @ ..] = data else {
+        return Err(Secp256k1SignatureError::invalid_der(
+            "unexpected end of data",
+        ));
+    };
+    if length & 0x80 != 0 {
+        return Err(Secp256k1SignatureError::invalid_der(
+            "long length form is not supported",
+        ));
+    }
+    let length = *length as usize;
+    if rest.len() < length {
+        return Err(Secp256k1SignatureError::invalid_der(
+            "unexpected end of data",
+        ));
+    }
+    let (content, rest) = rest.split_at(length);
+    Ok((*tag, content, rest))
+}
+
+/// Reads a positive DER integer of up to 32 bytes and returns it without leading zeros,
+/// together with the remaining data.
+fn read_der_integer(data: &[u8]) -> Result<(&[u8], &[u8]), Secp256k1SignatureError> {
+    let (tag, content, rest) = read_der_element(data)?;
+    if tag != DER_INTEGER {
+        return Err(Secp256k1SignatureError::invalid_der("expected an integer"));
+    }
+    let value = match content {
+        [] => return Err(Secp256k1SignatureError::invalid_der("empty integer")),
+        [first, ..] if first & 0x80 != 0 => {
+            return Err(Secp256k1SignatureError::invalid_der("negative integer"))
+        }
+        [0x00, second, ..] if second & 0x80 == 0 => {
+            return Err(Secp256k1SignatureError::invalid_der(
+                "integer with unnecessary leading zero",
+            ))
+        }
+        [0x00, value @ ..] if !value.is_empty() => value,
+        value => value,
+    };
+    if value.len() > 32 {
+        return Err(Secp256k1SignatureError::OutOfRange);
+    }
+    Ok((value, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use hex_literal::hex;
+
+    const SIGNATURE: [u8; 64] = hex!("207082eb2c3dfa0b454e0906051270ba4074ac93760ba9e7110cd9471475111151eb0dbbc9920e72146fb564f99d039802bf6ef2561446eb126ef364d21ee9c4");
+
+    fn der_encode(r: &[u8], s: &[u8]) -> Vec<u8> {
+        let mut out = vec![DER_SEQUENCE, (r.len() + s.len() + 4) as u8];
+        out.extend([DER_INTEGER, r.len() as u8]);
+        out.extend(r);
+        out.extend([DER_INTEGER, s.len() as u8]);
+        out.extend(s);
+        out
+    }
+
+    fn high_s(signature: &[u8; 64]) -> [u8; 64] {
+        let mut s = [0u8; 32];
+        s.copy_from_slice(&signature[32..]);
+        let mut out = *signature;
+        out[32..].copy_from_slice(&(CURVE_ORDER - Uint256::from_be_bytes(s)).to_be_bytes());
+        out
+    }
+
+    #[test]
+    fn secp256k1_signature_from_der_works() {
+        let der = der_encode(&SIGNATURE[..32], &SIGNATURE[32..]);
+        assert_eq!(secp256k1_signature_from_der(&der).unwrap(), SIGNATURE);
+
+        // integers with the high bit set are padded with a zero byte
+        let high = high_s(&SIGNATURE);
+        assert_eq!(high[32], 0xae);
+        let padded_s = [&[0x00], &high[32..]].concat();
+        let der = der_encode(&high[..32], &padded_s);
+        // and the signature is normalized to low-S
+        assert_eq!(secp256k1_signature_from_der(&der).unwrap(), SIGNATURE);
+
+        // short integers are padded to 32 bytes
+        let der = der_encode(&[0x01], &[0x7f]);
+        let mut expected = [0u8; 64];
+        expected[31] = 0x01;
+        expected[63] = 0x7f;
+        assert_eq!(secp256k1_signature_from_der(&der).unwrap(), expected);
+    }
+
+    #[test]
+    fn secp256k1_signature_from_der_rejects_invalid_der() {
+        let valid = der_encode(&SIGNATURE[..32], &SIGNATURE[32..]);
+
+        let err = secp256k1_signature_from_der(&[]).unwrap_err();
+        assert_eq!(
+            err,
+            Secp256k1SignatureError::invalid_der("unexpected end of data")
+        );
+
+        // truncated
+        let err = secp256k1_signature_from_der(&valid[..valid.len() - 1]).unwrap_err();
+        assert_eq!(
+            err,
+            Secp256k1SignatureError::invalid_der("unexpected end of data")
+        );
+
+        // trailing data
+        let err = secp256k1_signature_from_der(&[valid.as_slice(), &[0]].concat()).unwrap_err();
+        assert_eq!(
+            err,
+            Secp256k1SignatureError::invalid_der("trailing data after the sequence")
+        );
+
+        // wrong tags
+        let mut data = valid.clone();
+        data[0] = 0x31;
+        let err = secp256k1_signature_from_der(&data).unwrap_err();
+        assert_eq!(
+            err,
+            Secp256k1SignatureError::invalid_der("expected a sequence")
+        );
+        let mut data = valid;
+        data[2] = 0x03;
+        let err = secp256k1_signature_from_der(&data).unwrap_err();
+        assert_eq!(
+            err,
+            Secp256k1SignatureError::invalid_der("expected an integer")
+        );
+
+        // negative
+        let err = secp256k1_signature_from_der(&der_encode(&[0x80], &[0x01])).unwrap_err();
+        assert_eq!(
+            err,
+            Secp256k1SignatureError::invalid_der("negative integer")
+        );
+
+        // unnecessary leading zero
+        let err = secp256k1_signature_from_der(&der_encode(&[0x00, 0x01], &[0x01])).unwrap_err();
+        assert_eq!(
+            err,
+            Secp256k1SignatureError::invalid_der("integer with unnecessary leading zero")
+        );
+
+        // empty integer
+        let err = secp256k1_signature_from_der(&der_encode(&[], &[0x01])).unwrap_err();
+        assert_eq!(err, Secp256k1SignatureError::invalid_der("empty integer"));
+
+        // long length form
+        let err = secp256k1_signature_from_der(&[0x30, 0x81, 0x00]).unwrap_err();
+        assert_eq!(
+            err,
+            Secp256k1SignatureError::invalid_der("long length form is not supported")
+        );
+    }
+
+    #[test]
+    fn secp256k1_signature_from_der_rejects_out_of_range_values() {
+        let err = secp256k1_signature_from_der(&der_encode(&[0x00], &[0x01])).unwrap_err();
+        assert_eq!(err, Secp256k1SignatureError::OutOfRange);
+
+        let order = CURVE_ORDER.to_be_bytes();
+        let padded_order = [&[0x00], order.as_slice()].concat();
+        let err = secp256k1_signature_from_der(&der_encode(&[0x01], &padded_order)).unwrap_err();
+        assert_eq!(err, Secp256k1SignatureError::OutOfRange);
+
+        let err = secp256k1_signature_from_der(&der_encode(&[0x01], &[0x01; 33])).unwrap_err();
+        assert_eq!(err, Secp256k1SignatureError::OutOfRange);
+    }
+
+    #[test]
+    fn secp256k1_normalize_signature_works() {
+        assert_eq!(
+            secp256k1_normalize_signature(&SIGNATURE).unwrap(),
+            SIGNATURE
+        );
+        assert_eq!(
+            secp256k1_normalize_signature(&high_s(&SIGNATURE)).unwrap(),
+            SIGNATURE
+        );
+
+        let err = secp256k1_normalize_signature(&SIGNATURE[1..]).unwrap_err();
+        assert_eq!(err, Secp256k1SignatureError::InvalidLength { length: 63 });
+
+        let err = secp256k1_normalize_signature(&[0u8; 64]).unwrap_err();
+        assert_eq!(err, Secp256k1SignatureError::OutOfRange);
+    }
+}
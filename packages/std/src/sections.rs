@@ -8,6 +8,21 @@ pub fn decode_sections2(data: Vec<u8>) -> (Vec<u8>, Vec<u8>) {
     (first, second)
 }
 
+/// Decodes sections of data into multiple vectors.
+///
+/// See also: [`encode_sections`].
+#[allow(dead_code)] // used in Wasm and tests only
+pub fn decode_sections(mut data: Vec<u8>) -> Vec<Vec<u8>> {
+    let mut sections = Vec::new();
+    while !data.is_empty() {
+        let (rest, tail) = split_tail(data);
+        sections.push(tail);
+        data = rest;
+    }
+    sections.reverse();
+    sections
+}
+
 /// Encodes multiple sections of data into one vector.
 ///
 /// Each section is suffixed by a section length encoded as big endian uint32.
@@ -106,6 +121,26 @@ mod tests {
         assert_ne!(second.as_ptr(), original_ptr);
     }
 
+    #[test]
+    fn decode_sections_works() {
+        assert_eq!(decode_sections(vec![]), Vec::<Vec<u8>>::new());
+
+        let data = b"\0\0\0\0".to_vec();
+        assert_eq!(decode_sections(data), vec![Vec::<u8>::new()]);
+
+        let data = b"\xAA\0\0\0\x01".to_vec();
+        assert_eq!(decode_sections(data), vec![vec![0xAA]]);
+
+        let data = b"\xAA\0\0\0\x01\xDE\xDE\0\0\0\x02\0\0\0\0".to_vec();
+        assert_eq!(
+            decode_sections(data),
+            vec![vec![0xAA], vec![0xDE, 0xDE], vec![]]
+        );
+
+        let sections: [&[u8]; 3] = [b"one", b"", &[0x9D; 277]];
+        assert_eq!(decode_sections(encode_sections(&sections)), sections);
+    }
+
     #[test]
     fn encode_sections_works_for_empty_sections() {
         let enc = encode_sections(&[]);
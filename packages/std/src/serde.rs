@@ -2,10 +2,12 @@
 // The reason is two fold:
 // 1. To easily ensure that all calling libraries use the same version (minimize code size)
 // 2. To allow us to switch out to eg. serde-json-core more easily
+use alloc::{string::String, vec::Vec};
 use core::any::type_name;
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::binary::Binary;
+use crate::canonical_json::{check_representable, sort_keys};
 use crate::errors::{StdError, StdResult};
 
 #[deprecated = "use from_json instead"]
@@ -66,6 +68,33 @@ where
     to_json_vec(data).map(Binary::new)
 }
 
+/// Serializes the given data structure as canonical JSON bytes.
+///
+/// In contrast to [`to_json_vec`], the output does not depend on the order in which
+/// fields or map entries are serialized: object keys are sorted by their Unicode code points.
+/// This is required e.g. when hashing a message for signature verification.
+///
+/// Errors if the data contains floating point numbers or chars, or if an object
+/// contains the same key twice.
+pub fn to_json_vec_canonical<T>(data: &T) -> StdResult<Vec<u8>>
+where
+    T: Serialize + ?Sized,
+{
+    check_representable(data).map_err(|e| StdError::serialize_err(type_name::<T>(), e))?;
+    let json = to_json_vec(data)?;
+    sort_keys(&json).map_err(|e| StdError::serialize_err(type_name::<T>(), e))
+}
+
+/// Serializes the given data structure as canonical JSON bytes.
+///
+/// See [`to_json_vec_canonical`] for details.
+pub fn to_json_binary_canonical<T>(data: &T) -> StdResult<Binary>
+where
+    T: Serialize + ?Sized,
+{
+    to_json_vec_canonical(data).map(Binary::new)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -181,4 +210,69 @@ mod tests {
             r#"{"release_all":{"image":"foo","amount":42,"time":9007199254740999,"karma":-17}}"#
         );
     }
+
+    #[test]
+    fn to_json_vec_canonical_works() {
+        let msg = SomeMsg::ReleaseAll {
+            image: "foo".to_string(),
+            amount: 42,
+            time: 9007199254740999,
+            karma: -17,
+        };
+        let serialized = to_json_vec_canonical(&msg).unwrap();
+        assert_eq!(
+            serialized,
+            br#"{"release_all":{"amount":42,"image":"foo","karma":-17,"time":9007199254740999}}"#
+        );
+        // the canonical form is still valid input
+        assert_eq!(from_json::<SomeMsg>(&serialized).unwrap(), msg);
+
+        let binary = to_json_binary_canonical(&msg).unwrap();
+        assert_eq!(binary.as_slice(), serialized.as_slice());
+    }
+
+    #[test]
+    fn to_json_vec_canonical_is_independent_of_map_order() {
+        use alloc::collections::BTreeMap;
+
+        #[derive(Serialize)]
+        struct Forward {
+            a: u32,
+            b: u32,
+        }
+        #[derive(Serialize)]
+        struct Backward {
+            b: u32,
+            a: u32,
+        }
+
+        let forward = to_json_vec_canonical(&Forward { a: 1, b: 2 }).unwrap();
+        let backward = to_json_vec_canonical(&Backward { b: 2, a: 1 }).unwrap();
+        assert_eq!(forward, backward);
+
+        let map: BTreeMap<&str, u32> = [("b", 2), ("a", 1)].into_iter().collect();
+        assert_eq!(to_json_vec_canonical(&map).unwrap(), forward);
+    }
+
+    #[test]
+    fn to_json_vec_canonical_rejects_unsupported_values() {
+        #[derive(Serialize)]
+        struct WithFloat {
+            price: f64,
+        }
+        let err = to_json_vec_canonical(&WithFloat {
+            price: f64::INFINITY,
+        })
+        .unwrap_err();
+        assert!(err.is_serialize_err(), "Unexpected error: {err:?}");
+
+        #[derive(Serialize)]
+        struct Duplicate {
+            a: u32,
+            #[serde(rename = "a")]
+            also_a: u32,
+        }
+        let err = to_json_vec_canonical(&Duplicate { a: 1, also_a: 2 }).unwrap_err();
+        assert!(err.to_string().contains("Duplicate key"), "{err}");
+    }
 }
@@ -8,6 +8,8 @@ use core::ops::{Bound, RangeBounds};
 #[cfg(feature = "iterator")]
 use crate::iterator::{Order, Record};
 use crate::traits::Storage;
+#[cfg(feature = "db_size")]
+use crate::traits::StorageSize;
 ///Represents a storage mechanism that exists only in memory (not persisted).
 #[derive(Default)]
 pub struct MemoryStorage {
@@ -25,6 +27,17 @@ impl Storage for MemoryStorage {
         self.data.get(key).cloned()
     }
 
+    fn get_into(&self, key: &[u8], buffer: &mut Vec<u8>) -> bool {
+        buffer.clear();
+        match self.data.get(key) {
+            Some(value) => {
+                buffer.extend_from_slice(value);
+                true
+            }
+            None => false,
+        }
+    }
+
     fn set(&mut self, key: &[u8], value: &[u8]) {
         if value.is_empty() {
             panic!("TL;DR: Value must not be empty in Storage::set but in most cases you can use Storage::remove instead. Long story: Getting empty values from storage is not well supported at the moment. Some of our internal interfaces cannot differentiate between a non-existent key and an empty value. Right now, you cannot rely on the behaviour of empty values. To protect you from trouble later on, we stop here. Sorry for the inconvenience! We highly welcome you to contribute to CosmWasm, making this more solid one way or the other.");
@@ -37,6 +50,18 @@ impl Storage for MemoryStorage {
         self.data.remove(key);
     }
 
+    #[cfg(feature = "db_size")]
+    fn size_hint(&self) -> Option<StorageSize> {
+        Some(StorageSize {
+            keys: self.data.len() as u64,
+            bytes: self
+                .data
+                .iter()
+                .map(|(key, value)| (key.len() + value.len()) as u64)
+                .sum(),
+        })
+    }
+
     #[cfg(feature = "iterator")]
     /// range allows iteration over a set of keys, either forwards or backwards
     /// uses standard rust range notation, and eg db.range(b"foo"..b"bar") also works reverse
@@ -119,6 +144,59 @@ mod tests {
         assert_eq!(store.get(b"food"), None);
     }
 
+    #[test]
+    fn get_into_works() {
+        let mut store = MemoryStorage::new();
+        store.set(b"foo", b"bar");
+        store.set(b"long", &[7; 100]);
+
+        let mut buffer = Vec::new();
+        assert!(store.get_into(b"long", &mut buffer));
+        assert_eq!(buffer, [7; 100]);
+        let capacity = buffer.capacity();
+
+        // the allocation is reused
+        assert!(store.get_into(b"foo", &mut buffer));
+        assert_eq!(buffer, b"bar");
+        assert_eq!(buffer.capacity(), capacity);
+
+        assert!(!store.get_into(b"food", &mut buffer));
+        assert_eq!(buffer, b"");
+    }
+
+    #[test]
+    fn get_into_default_implementation_works() {
+        struct GetOnly(MemoryStorage);
+        impl Storage for GetOnly {
+            fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+                self.0.get(key)
+            }
+            #[cfg(feature = "iterator")]
+            fn range<'a>(
+                &'a self,
+                start: Option<&[u8]>,
+                end: Option<&[u8]>,
+                order: Order,
+            ) -> Box<dyn Iterator<Item = Record> + 'a> {
+                self.0.range(start, end, order)
+            }
+            fn set(&mut self, key: &[u8], value: &[u8]) {
+                self.0.set(key, value)
+            }
+            fn remove(&mut self, key: &[u8]) {
+                self.0.remove(key)
+            }
+        }
+
+        let mut store = GetOnly(MemoryStorage::new());
+        store.set(b"foo", b"bar");
+        let mut buffer = b"previous".to_vec();
+        assert!(store.get_into(b"foo", &mut buffer));
+        assert_eq!(buffer, b"bar");
+        assert!(!store.get_into(b"food", &mut buffer));
+        assert_eq!(buffer, b"");
+    }
+
     #[test]
     #[should_panic(
         expected = "Getting empty values from storage is not well supported at the moment."
@@ -280,6 +358,107 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "db_size")]
+    fn memory_storage_size_hint_works() {
+        let mut store = MemoryStorage::new();
+        assert_eq!(store.size_hint(), Some(StorageSize::default()));
+
+        store.set(b"foo", b"bar");
+        store.set(b"ze", b"bra");
+        assert_eq!(store.size_hint(), Some(StorageSize { keys: 2, bytes: 11 }));
+
+        store.remove(b"foo");
+        assert_eq!(store.size_hint(), Some(StorageSize { keys: 1, bytes: 5 }));
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn range_prefix_works() {
+        let mut store = MemoryStorage::new();
+        store.set(b"a", b"1");
+        store.set(b"b1", b"2");
+        store.set(b"b2", b"3");
+        store.set(b"c", b"4");
+
+        let keys: Vec<_> = store
+            .range_prefix(b"b", Order::Ascending)
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(keys, vec![b"b1".to_vec(), b"b2".to_vec()]);
+
+        // latest entry under prefix
+        let last = store.range_prefix(b"b", Order::Descending).next();
+        assert_eq!(last, Some((b"b2".to_vec(), b"3".to_vec())));
+
+        // empty prefix covers everything
+        assert_eq!(store.range_prefix(b"", Order::Ascending).count(), 4);
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn range_bounded_works() {
+        use crate::iterator::Bound as RawBound;
+
+        let mut store = MemoryStorage::new();
+        store.set(b"a", b"1");
+        store.set(b"b", b"2");
+        store.set(b"b\0", b"3");
+        store.set(b"c", b"4");
+
+        let keys = |min: Option<RawBound>, max: Option<RawBound>, order: Order| -> Vec<Vec<u8>> {
+            store
+                .range_bounded(min, max, order)
+                .map(|(k, _)| k)
+                .collect()
+        };
+
+        // exclusive lower bound
+        assert_eq!(
+            keys(
+                Some(RawBound::exclusive(b"b".to_vec())),
+                None,
+                Order::Ascending
+            ),
+            vec![b"b\0".to_vec(), b"c".to_vec()]
+        );
+        // inclusive upper bound
+        assert_eq!(
+            keys(
+                None,
+                Some(RawBound::inclusive(b"b".to_vec())),
+                Order::Ascending
+            ),
+            vec![b"a".to_vec(), b"b".to_vec()]
+        );
+        // bounds are independent of the order
+        assert_eq!(
+            keys(
+                Some(RawBound::inclusive(b"a".to_vec())),
+                Some(RawBound::exclusive(b"c".to_vec())),
+                Order::Descending
+            ),
+            vec![b"b\0".to_vec(), b"b".to_vec(), b"a".to_vec()]
+        );
+        assert_eq!(
+            keys(
+                None,
+                Some(RawBound::exclusive(b"b".to_vec())),
+                Order::Descending
+            ),
+            vec![b"a".to_vec()]
+        );
+        // empty range
+        assert_eq!(
+            keys(
+                Some(RawBound::exclusive(b"b".to_vec())),
+                Some(RawBound::inclusive(b"b".to_vec())),
+                Order::Ascending
+            ),
+            Vec::<Vec<u8>>::new()
+        );
+    }
+
     #[test]
     fn memory_storage_implements_debug() {
         let store = MemoryStorage::new();
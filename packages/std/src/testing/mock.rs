@@ -34,14 +34,29 @@ use crate::query::{
     AllDelegationsResponse, AllValidatorsResponse, BondedDenomResponse, DelegationResponse,
     FullDelegation, StakingQuery, Validator, ValidatorResponse,
 };
+#[cfg(feature = "chain_queries")]
+use crate::query::{CapabilitiesResponse, ChainQuery};
 #[cfg(feature = "cosmwasm_1_3")]
 use crate::query::{DelegatorWithdrawAddressResponse, DistributionQuery};
+#[cfg(feature = "token_factory")]
+use crate::query::{
+    DenomAdminResponse, DenomsByCreatorResponse, FullDenomResponse, TokenFactoryParamsResponse,
+    TokenFactoryQuery,
+};
+#[cfg(feature = "randomness")]
+use crate::query::{RandomnessQuery, RandomnessResponse};
+#[cfg(feature = "staking_pool_params")]
+use crate::query::{StakingParamsResponse, StakingPoolResponse};
+#[cfg(feature = "emit_event")]
+use crate::results::Event;
 use crate::results::{ContractResult, Empty, SystemResult};
 use crate::serde::{from_json, to_json_binary};
 use crate::storage::MemoryStorage;
 use crate::timestamp::Timestamp;
 use crate::traits::{Api, Querier, QuerierResult};
 use crate::types::{BlockInfo, ContractInfo, Env, MessageInfo, TransactionInfo};
+#[cfg(all(feature = "stargate", feature = "ibc_channel_filters"))]
+use crate::IbcChannelState;
 #[cfg(feature = "cosmwasm_1_3")]
 use crate::{
     query::{AllDenomMetadataResponse, DecCoin, DenomMetadataResponse},
@@ -122,6 +137,9 @@ pub struct MockApi {
     canonical_length: usize,
     /// Prefix used for creating addresses in Bech32 encoding.
     bech32_prefix: &'static str,
+    /// The value returned by [`Api::gas_remaining`]
+    #[cfg(feature = "gas_remaining")]
+    gas_remaining: u64,
 }
 
 impl Default for MockApi {
@@ -129,6 +147,8 @@ impl Default for MockApi {
         MockApi {
             canonical_length: CANONICAL_LENGTH,
             bech32_prefix: BECH32_PREFIX,
+            #[cfg(feature = "gas_remaining")]
+            gas_remaining: u64::MAX,
         }
     }
 }
@@ -248,6 +268,46 @@ impl Api for MockApi {
         )?)
     }
 
+    #[cfg(feature = "ed25519_variants")]
+    fn ed25519ctx_verify(
+        &self,
+        message: &[u8],
+        context: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        Ok(cosmwasm_crypto::ed25519ctx_verify(
+            message, context, signature, public_key,
+        )?)
+    }
+
+    #[cfg(feature = "ed25519_variants")]
+    fn ed25519ph_verify(
+        &self,
+        message_hash: &[u8],
+        context: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        Ok(cosmwasm_crypto::ed25519ph_verify(
+            message_hash,
+            context,
+            signature,
+            public_key,
+        )?)
+    }
+
+    #[cfg(feature = "gas_remaining")]
+    fn gas_remaining(&self) -> StdResult<u64> {
+        Ok(self.gas_remaining)
+    }
+
+    /// Events emitted through the mock are discarded.
+    #[cfg(feature = "emit_event")]
+    fn emit_event(&self, _event: &Event) -> StdResult<()> {
+        Ok(())
+    }
+
     fn debug(&self, message: &str) {
         println!("{message}");
     }
@@ -274,6 +334,17 @@ impl MockApi {
         self
     }
 
+    /// Returns [MockApi] that reports the given amount of gas in [`Api::gas_remaining`].
+    /// The default is `u64::MAX`, i.e. there is no gas limit in unit tests.
+    ///
+    /// The value is constant and does not decrease with usage. Use this to test
+    /// how a contract behaves when gas is running low.
+    #[cfg(feature = "gas_remaining")]
+    pub fn with_gas_remaining(mut self, gas_remaining: u64) -> Self {
+        self.gas_remaining = gas_remaining;
+        self
+    }
+
     /// Returns an address built from provided input string.
     ///
     /// # Example
@@ -314,9 +385,16 @@ pub fn mock_env() -> Env {
             time: Timestamp::from_nanos(1_571_797_419_879_305_533),
             chain_id: "cosmos-testnet-14002".to_string(),
         },
-        transaction: Some(TransactionInfo { index: 3 }),
+        transaction: Some(TransactionInfo {
+            index: 3,
+            hash: None,
+            msg_index: None,
+        }),
         contract: ContractInfo {
             address: Addr::unchecked(MOCK_CONTRACT_ADDR),
+            code_id: None,
+            creator: None,
+            admin: None,
         },
     }
 }
@@ -507,6 +585,12 @@ pub struct MockQuerier<C: DeserializeOwned = Empty> {
     wasm: WasmQuerier,
     #[cfg(feature = "stargate")]
     ibc: IbcQuerier,
+    #[cfg(feature = "chain_queries")]
+    chain: ChainQuerier,
+    #[cfg(feature = "randomness")]
+    randomness: RandomnessQuerier,
+    #[cfg(feature = "token_factory")]
+    token_factory: TokenFactoryQuerier,
     /// A handler to handle custom queries. This is set to a dummy handler that
     /// always errors by default. Update it via `with_custom_handler`.
     ///
@@ -525,6 +609,12 @@ impl<C: DeserializeOwned> MockQuerier<C> {
             wasm: WasmQuerier::default(),
             #[cfg(feature = "stargate")]
             ibc: IbcQuerier::default(),
+            #[cfg(feature = "chain_queries")]
+            chain: ChainQuerier::default(),
+            #[cfg(feature = "randomness")]
+            randomness: RandomnessQuerier::default(),
+            #[cfg(feature = "token_factory")]
+            token_factory: TokenFactoryQuerier::default(),
             // strange argument notation suggested as a workaround here: https://github.com/rust-lang/rust/issues/41078#issuecomment-294296365
             custom_handler: Box::from(|_: &_| -> MockQuerierCustomHandlerResult {
                 SystemResult::Err(SystemError::UnsupportedRequest {
@@ -535,6 +625,7 @@ impl<C: DeserializeOwned> MockQuerier<C> {
     }
 
     // set a new balance for the given address and return the old balance
+    // the supply of the denoms is updated accordingly
     pub fn update_balance(
         &mut self,
         addr: impl Into<String>,
@@ -543,6 +634,8 @@ impl<C: DeserializeOwned> MockQuerier<C> {
         self.bank.update_balance(addr, balance)
     }
 
+    /// Sets the metadata returned by [`BankQuery::DenomMetadata`] and
+    /// [`BankQuery::AllDenomMetadata`], replacing the previous metadata.
     pub fn set_denom_metadata(&mut self, denom_metadata: &[DenomMetadata]) {
         self.bank.set_denom_metadata(denom_metadata);
     }
@@ -582,6 +675,16 @@ impl<C: DeserializeOwned> MockQuerier<C> {
         delegations: &[crate::query::FullDelegation],
     ) {
         self.staking = StakingQuerier::new(denom, validators, delegations);
+        // delegated tokens are still part of the supply
+        let staked: Vec<Coin> = delegations.iter().map(|d| d.amount.clone()).collect();
+        self.bank.set_staked_supply(&staked);
+    }
+
+    /// Sets the parameters returned by [`StakingQuery::Params`].
+    /// By default, the defaults of the Cosmos SDK are returned.
+    #[cfg(feature = "staking_pool_params")]
+    pub fn update_staking_params(&mut self, params: StakingParamsResponse) {
+        self.staking.params = Some(params);
     }
 
     #[cfg(feature = "stargate")]
@@ -589,6 +692,33 @@ impl<C: DeserializeOwned> MockQuerier<C> {
         self.ibc = IbcQuerier::new(port_id, channels);
     }
 
+    /// Sets the capabilities and VM version returned by [`ChainQuery::Capabilities`]
+    #[cfg(feature = "chain_queries")]
+    pub fn update_chain(
+        &mut self,
+        capabilities: impl IntoIterator<Item = impl Into<String>>,
+        vm_version: impl Into<String>,
+    ) {
+        self.chain = ChainQuerier::new(capabilities, vm_version);
+    }
+
+    /// Sets the randomness returned by [`RandomnessQuery`] for the given height.
+    /// [`RandomnessQuery::Latest`] returns the randomness with the highest height.
+    #[cfg(feature = "randomness")]
+    pub fn update_randomness(&mut self, height: u64, seed: [u8; 32]) {
+        self.randomness.update(height, seed);
+    }
+
+    /// Sets the denoms with their admins and the denom creation fee returned by [`TokenFactoryQuery`]
+    #[cfg(feature = "token_factory")]
+    pub fn update_token_factory(
+        &mut self,
+        denoms: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+        denom_creation_fee: &[Coin],
+    ) {
+        self.token_factory = TokenFactoryQuerier::new(denoms, denom_creation_fee);
+    }
+
     pub fn update_wasm<WH: 'static>(&mut self, handler: WH)
     where
         WH: Fn(&WasmQuery) -> QuerierResult,
@@ -624,6 +754,16 @@ impl<C: CustomQuery + DeserializeOwned> Querier for MockQuerier<C> {
         };
         self.handle_query(&request)
     }
+
+    /// The mock querier does not meter gas, so the gas limit is ignored.
+    #[cfg(feature = "query_batch")]
+    fn raw_query_batch_with_gas_limit(
+        &self,
+        bin_requests: &[&[u8]],
+        _gas_limit: u64,
+    ) -> Vec<QuerierResult> {
+        self.raw_query_batch(bin_requests)
+    }
 }
 
 impl<C: CustomQuery + DeserializeOwned> MockQuerier<C> {
@@ -644,6 +784,12 @@ impl<C: CustomQuery + DeserializeOwned> MockQuerier<C> {
             }),
             #[cfg(feature = "stargate")]
             QueryRequest::Ibc(msg) => self.ibc.query(msg),
+            #[cfg(feature = "chain_queries")]
+            QueryRequest::Chain(msg) => self.chain.query(msg),
+            #[cfg(feature = "randomness")]
+            QueryRequest::Randomness(msg) => self.randomness.query(msg),
+            #[cfg(feature = "token_factory")]
+            QueryRequest::TokenFactory(msg) => self.token_factory.query(msg),
         }
     }
 }
@@ -704,6 +850,9 @@ pub struct BankQuerier {
     supplies: HashMap<String, Uint128>,
     /// HashMap<address, coins>
     balances: HashMap<String, Vec<Coin>>,
+    /// Tokens that are part of the supply but not held in any balance, e.g. delegations.
+    /// HashMap<denom, amount>
+    staked: HashMap<String, Uint128>,
     /// Vec<Metadata>
     denom_metadata: BTreeMap<Vec<u8>, DenomMetadata>,
 }
@@ -716,8 +865,9 @@ impl BankQuerier {
             .collect();
 
         BankQuerier {
-            supplies: Self::calculate_supplies(&balances),
+            supplies: Self::calculate_supplies(&balances, &HashMap::new()),
             balances,
+            staked: HashMap::new(),
             denom_metadata: BTreeMap::new(),
         }
     }
@@ -728,11 +878,25 @@ impl BankQuerier {
         balance: Vec<Coin>,
     ) -> Option<Vec<Coin>> {
         let result = self.balances.insert(addr.into(), balance);
-        self.supplies = Self::calculate_supplies(&self.balances);
+        self.supplies = Self::calculate_supplies(&self.balances, &self.staked);
 
         result
     }
 
+    /// Sets the tokens that are counted in the supply in addition to the balances,
+    /// replacing the previous ones. This is used to account for delegated tokens,
+    /// which are removed from the balances but are still part of the supply.
+    pub fn set_staked_supply(&mut self, staked: &[Coin]) {
+        let mut amounts = HashMap::new();
+        for coin in staked {
+            *amounts
+                .entry(coin.denom.clone())
+                .or_insert_with(Uint128::zero) += coin.amount;
+        }
+        self.staked = amounts;
+        self.supplies = Self::calculate_supplies(&self.balances, &self.staked);
+    }
+
     pub fn set_denom_metadata(&mut self, denom_metadata: &[DenomMetadata]) {
         self.denom_metadata = denom_metadata
             .iter()
@@ -740,8 +904,11 @@ impl BankQuerier {
             .collect();
     }
 
-    fn calculate_supplies(balances: &HashMap<String, Vec<Coin>>) -> HashMap<String, Uint128> {
-        let mut supplies = HashMap::new();
+    fn calculate_supplies(
+        balances: &HashMap<String, Vec<Coin>>,
+        staked: &HashMap<String, Uint128>,
+    ) -> HashMap<String, Uint128> {
+        let mut supplies = staked.clone();
 
         let all_coins = balances.iter().flat_map(|(_, coins)| coins);
 
@@ -901,7 +1068,80 @@ impl IbcQuerier {
                     })
                     .cloned()
                     .collect();
-                let res = ListChannelsResponse { channels };
+                let res = ListChannelsResponse {
+                    channels,
+                    next_key: None,
+                };
+                to_json_binary(&res).into()
+            }
+            #[cfg(feature = "ibc_channel_filters")]
+            IbcQuery::ListChannelsFiltered {
+                port_id,
+                counterparty_port_id,
+                connection_id,
+                state,
+                pagination,
+            } => {
+                let default_pagination = crate::PageRequest {
+                    key: None,
+                    limit: 100,
+                    reverse: false,
+                };
+                let pagination = pagination.as_ref().unwrap_or(&default_pagination);
+                let port_id = port_id.as_ref().unwrap_or(&self.port_id);
+
+                let mut channels: Vec<_> = self
+                    .channels
+                    .iter()
+                    .filter(|c| c.endpoint.port_id == *port_id)
+                    .filter(|c| {
+                        counterparty_port_id
+                            .iter()
+                            .all(|p| c.counterparty_endpoint.port_id == *p)
+                    })
+                    .filter(|c| connection_id.iter().all(|id| c.connection_id == *id))
+                    // all channels of the mock querier are open
+                    .filter(|_| state.iter().all(|s| *s == IbcChannelState::Open))
+                    .collect();
+                channels.sort_by(|a, b| a.endpoint.channel_id.cmp(&b.endpoint.channel_id));
+                if pagination.reverse {
+                    channels.reverse();
+                }
+
+                // skip all channels before the given key (or after the key for reverse)
+                let start = match &pagination.key {
+                    None => 0,
+                    Some(key) => channels
+                        .iter()
+                        .position(|c| {
+                            let channel_id = c.endpoint.channel_id.as_bytes();
+                            if pagination.reverse {
+                                channel_id <= key.as_slice()
+                            } else {
+                                channel_id >= key.as_slice()
+                            }
+                        })
+                        .unwrap_or(channels.len()),
+                };
+                let mut channels: Vec<_> = channels
+                    .into_iter()
+                    .skip(start)
+                    // take the requested amount + 1 to get the next key
+                    .take((pagination.limit.saturating_add(1)) as usize)
+                    .cloned()
+                    .collect();
+
+                // if we took more than requested, remove the last element (the next key),
+                // otherwise this is the last batch
+                let next_key = if channels.len() > pagination.limit as usize {
+                    channels
+                        .pop()
+                        .map(|c| Binary::from(c.endpoint.channel_id.as_bytes()))
+                } else {
+                    None
+                };
+
+                let res = ListChannelsResponse { channels, next_key };
                 to_json_binary(&res).into()
             }
             IbcQuery::PortId {} => {
@@ -922,6 +1162,8 @@ pub struct StakingQuerier {
     denom: String,
     validators: Vec<Validator>,
     delegations: Vec<FullDelegation>,
+    #[cfg(feature = "staking_pool_params")]
+    params: Option<StakingParamsResponse>,
 }
 
 #[cfg(feature = "staking")]
@@ -931,6 +1173,8 @@ impl StakingQuerier {
             denom: denom.to_string(),
             validators: validators.to_vec(),
             delegations: delegations.to_vec(),
+            #[cfg(feature = "staking_pool_params")]
+            params: None,
         }
     }
 
@@ -981,6 +1225,201 @@ impl StakingQuerier {
                 };
                 to_json_binary(&res).into()
             }
+            #[cfg(feature = "staking_pool_params")]
+            StakingQuery::Pool {} => {
+                // all delegations are considered bonded
+                let bonded = self
+                    .delegations
+                    .iter()
+                    .filter(|d| d.amount.denom == self.denom)
+                    .map(|d| d.amount.amount)
+                    .sum::<Uint128>();
+                let res = StakingPoolResponse {
+                    bonded_tokens: Coin::new(bonded, &self.denom),
+                    not_bonded_tokens: Coin::new(0u128, &self.denom),
+                };
+                to_json_binary(&res).into()
+            }
+            #[cfg(feature = "staking_pool_params")]
+            StakingQuery::Params {} => {
+                let res = self.params.clone().unwrap_or(StakingParamsResponse {
+                    unbonding_time: 21 * 24 * 60 * 60,
+                    max_validators: 100,
+                    max_entries: 7,
+                });
+                to_json_binary(&res).into()
+            }
+        };
+        // system result is always ok in the mock implementation
+        SystemResult::Ok(contract_result)
+    }
+}
+
+#[cfg(feature = "chain_queries")]
+#[derive(Clone)]
+pub struct ChainQuerier {
+    capabilities: Vec<String>,
+    vm_version: String,
+}
+
+#[cfg(feature = "chain_queries")]
+impl ChainQuerier {
+    pub fn new(
+        capabilities: impl IntoIterator<Item = impl Into<String>>,
+        vm_version: impl Into<String>,
+    ) -> Self {
+        ChainQuerier {
+            capabilities: capabilities.into_iter().map(Into::into).collect(),
+            vm_version: vm_version.into(),
+        }
+    }
+
+    pub fn query(&self, request: &ChainQuery) -> QuerierResult {
+        let contract_result: ContractResult<Binary> = match request {
+            ChainQuery::Capabilities {} => {
+                let res = CapabilitiesResponse {
+                    capabilities: self.capabilities.clone(),
+                    vm_version: self.vm_version.clone(),
+                };
+                to_json_binary(&res).into()
+            }
+        };
+        // system result is always ok in the mock implementation
+        SystemResult::Ok(contract_result)
+    }
+}
+
+#[cfg(feature = "chain_queries")]
+impl Default for ChainQuerier {
+    /// All capabilities built into CosmWasm and the version of this crate
+    fn default() -> Self {
+        ChainQuerier::new(
+            [
+                "iterator",
+                "staking",
+                "stargate",
+                "cosmwasm_1_1",
+                "cosmwasm_1_2",
+                "cosmwasm_1_3",
+                "cosmwasm_1_4",
+                "query_chain_binary",
+                "query_batch",
+                "chunked_results",
+                "db_size",
+                "iterator_seek",
+                "ed25519_variants",
+                "gas_remaining",
+                "emit_event",
+                "chain_queries",
+                "staking_pool_params",
+                "ibc_channel_filters",
+            ],
+            env!("CARGO_PKG_VERSION"),
+        )
+    }
+}
+
+#[cfg(feature = "randomness")]
+#[derive(Clone, Default)]
+pub struct RandomnessQuerier {
+    /// The randomness seeds by block height
+    seeds: BTreeMap<u64, [u8; 32]>,
+}
+
+#[cfg(feature = "randomness")]
+impl RandomnessQuerier {
+    pub fn new(seeds: impl IntoIterator<Item = (u64, [u8; 32])>) -> Self {
+        RandomnessQuerier {
+            seeds: seeds.into_iter().collect(),
+        }
+    }
+
+    /// Sets the randomness seed for the given height
+    pub fn update(&mut self, height: u64, seed: [u8; 32]) {
+        self.seeds.insert(height, seed);
+    }
+
+    pub fn query(&self, request: &RandomnessQuery) -> QuerierResult {
+        let entry = match request {
+            RandomnessQuery::Latest {} => self.seeds.last_key_value(),
+            RandomnessQuery::ByHeight { height } => self.seeds.get_key_value(height),
+        };
+        let contract_result: ContractResult<Binary> = match entry {
+            Some((&height, seed)) => {
+                let res = RandomnessResponse {
+                    height,
+                    seed: seed.into(),
+                };
+                to_json_binary(&res).into()
+            }
+            None => ContractResult::Err("No randomness available for this height".to_string()),
+        };
+        // system result is always ok in the mock implementation
+        SystemResult::Ok(contract_result)
+    }
+}
+
+#[cfg(feature = "token_factory")]
+#[derive(Clone, Default)]
+pub struct TokenFactoryQuerier {
+    /// The admin of each denom. An empty string means the denom has no admin.
+    admins: BTreeMap<String, String>,
+    denom_creation_fee: Vec<Coin>,
+}
+
+#[cfg(feature = "token_factory")]
+impl TokenFactoryQuerier {
+    pub fn new(
+        denoms: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+        denom_creation_fee: &[Coin],
+    ) -> Self {
+        TokenFactoryQuerier {
+            admins: denoms
+                .into_iter()
+                .map(|(denom, admin)| (denom.into(), admin.into()))
+                .collect(),
+            denom_creation_fee: denom_creation_fee.to_vec(),
+        }
+    }
+
+    pub fn query(&self, request: &TokenFactoryQuery) -> QuerierResult {
+        let contract_result: ContractResult<Binary> = match request {
+            TokenFactoryQuery::FullDenom {
+                creator_addr,
+                subdenom,
+            } => {
+                let res = FullDenomResponse {
+                    denom: format!("factory/{creator_addr}/{subdenom}"),
+                };
+                to_json_binary(&res).into()
+            }
+            TokenFactoryQuery::Admin { denom } => match self.admins.get(denom) {
+                Some(admin) => {
+                    let res = DenomAdminResponse {
+                        admin: admin.clone(),
+                    };
+                    to_json_binary(&res).into()
+                }
+                None => ContractResult::Err(format!("Denom not found: {denom}")),
+            },
+            TokenFactoryQuery::DenomsByCreator { creator } => {
+                let prefix = format!("factory/{creator}/");
+                let res = DenomsByCreatorResponse {
+                    denoms: self
+                        .admins
+                        .keys()
+                        .filter(|denom| denom.starts_with(&prefix))
+                        .cloned()
+                        .collect(),
+                };
+                to_json_binary(&res).into()
+            }
+            TokenFactoryQuery::Params {} => {
+                let res = TokenFactoryParamsResponse {
+                    denom_creation_fee: self.denom_creation_fee.clone(),
+                };
+                to_json_binary(&res).into()
+            }
         };
         // system result is always ok in the mock implementation
         SystemResult::Ok(contract_result)
@@ -1425,6 +1864,62 @@ mod tests {
         assert_eq!(res.unwrap_err(), VerificationError::InvalidPubkeyFormat);
     }
 
+    #[cfg(feature = "emit_event")]
+    #[test]
+    fn emit_event_works() {
+        let api = MockApi::default();
+        api.emit_event(&Event::new("progress").add_attribute("item", "1"))
+            .unwrap();
+    }
+
+    #[cfg(feature = "gas_remaining")]
+    #[test]
+    fn gas_remaining_works() {
+        let api = MockApi::default();
+        assert_eq!(api.gas_remaining().unwrap(), u64::MAX);
+
+        let api = MockApi::default().with_gas_remaining(12345);
+        assert_eq!(api.gas_remaining().unwrap(), 12345);
+    }
+
+    // Basic "works" test. Exhaustive tests on VM's side (packages/vm/src/imports.rs)
+    #[cfg(feature = "ed25519_variants")]
+    #[test]
+    fn ed25519ctx_verify_works() {
+        let api = MockApi::default();
+
+        // Test vector from https://www.rfc-editor.org/rfc/rfc8032#section-7.2
+        let msg = hex!("f726936d19c800494e3fdaff20b276a8");
+        let signature = hex!("55a4cc2f70a54e04288c5f4cd1e45a7bb520b36292911876cada7323198dd87a8b36950b95130022907a7fb7c4e9b2d5f6cca685a587b4b21f4b888e4e7edb0d");
+        let public_key = hex!("dfc9425e4f968f7f0c29f0259cf5f9aed6851c2bb4ad8bfb860cfee0ab248292");
+
+        assert!(api
+            .ed25519ctx_verify(&msg, b"foo", &signature, &public_key)
+            .unwrap());
+        assert!(!api
+            .ed25519ctx_verify(&msg, b"bar", &signature, &public_key)
+            .unwrap());
+    }
+
+    // Basic "works" test. Exhaustive tests on VM's side (packages/vm/src/imports.rs)
+    #[cfg(feature = "ed25519_variants")]
+    #[test]
+    fn ed25519ph_verify_works() {
+        let api = MockApi::default();
+
+        // Test vector from https://www.rfc-editor.org/rfc/rfc8032#section-7.3
+        let msg_hash = sha2::Sha512::digest(b"abc");
+        let signature = hex!("98a70222f0b8121aa9d30f813d683f809e462b469c7ff87639499bb94e6dae4131f85042463c2a355a2003d062adf5aaa10b8c61e636062aaad11c2a26083406");
+        let public_key = hex!("ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf");
+
+        assert!(api
+            .ed25519ph_verify(&msg_hash, b"", &signature, &public_key)
+            .unwrap());
+
+        let res = api.ed25519ph_verify(b"abc", b"", &signature, &public_key);
+        assert_eq!(res.unwrap_err(), VerificationError::InvalidHashFormat);
+    }
+
     // Basic "works" test.
     #[test]
     fn ed25519_batch_verify_works() {
@@ -1520,6 +2015,56 @@ mod tests {
         assert_eq!(res.amount, coin(0, "ATOM"));
     }
 
+    #[cfg(feature = "cosmwasm_1_1")]
+    #[test]
+    fn bank_querier_supply_includes_staked_tokens() {
+        let mut bank = BankQuerier::new(&[("foo", &coins(100, "ELF"))]);
+        bank.set_staked_supply(&[coin(20, "ELF"), coin(5, "ELF"), coin(7, "FLY")]);
+
+        let supply = |bank: &BankQuerier, denom: &str| -> Coin {
+            let res = bank
+                .query(&BankQuery::Supply {
+                    denom: denom.to_string(),
+                })
+                .unwrap()
+                .unwrap();
+            from_json::<SupplyResponse>(res).unwrap().amount
+        };
+        assert_eq!(supply(&bank, "ELF"), coin(125, "ELF"));
+        assert_eq!(supply(&bank, "FLY"), coin(7, "FLY"));
+
+        // balance updates keep the staked tokens
+        bank.update_balance("foo", coins(50, "ELF"));
+        bank.update_balance("bar", coins(3, "FLY"));
+        assert_eq!(supply(&bank, "ELF"), coin(75, "ELF"));
+        assert_eq!(supply(&bank, "FLY"), coin(10, "FLY"));
+
+        // staked tokens are replaced
+        bank.set_staked_supply(&[]);
+        assert_eq!(supply(&bank, "ELF"), coin(50, "ELF"));
+        assert_eq!(supply(&bank, "FLY"), coin(3, "FLY"));
+    }
+
+    #[cfg(all(feature = "cosmwasm_1_1", feature = "staking"))]
+    #[test]
+    fn mock_querier_supply_tracks_delegations() {
+        let mut querier: MockQuerier = MockQuerier::new(&[("alice", &coins(1000, "ustake"))]);
+        let delegation = FullDelegation::create(
+            Addr::unchecked("bob"),
+            "validator".to_string(),
+            coin(250, "ustake"),
+            coin(250, "ustake"),
+            vec![],
+        );
+        querier.update_staking("ustake", &[], &[delegation]);
+
+        let querier = crate::QuerierWrapper::<Empty>::new(&querier);
+        assert_eq!(
+            querier.query_supply("ustake").unwrap(),
+            coin(1250, "ustake")
+        );
+    }
+
     #[test]
     fn bank_querier_all_balances() {
         let addr = String::from("foobar");
@@ -1681,6 +2226,118 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "chain_queries")]
+    #[test]
+    fn chain_querier_works() {
+        let chain = ChainQuerier::default();
+        let res = chain.query(&ChainQuery::Capabilities {}).unwrap().unwrap();
+        let res: CapabilitiesResponse = from_json(res).unwrap();
+        assert!(res.has_capability("chain_queries"));
+        assert_eq!(res.vm_version, env!("CARGO_PKG_VERSION"));
+
+        let mut querier: MockQuerier = MockQuerier::new(&[]);
+        querier.update_chain(["iterator"], "1.2.3");
+        let wrapper = crate::QuerierWrapper::<Empty>::new(&querier);
+        let res = wrapper.query_chain_capabilities().unwrap();
+        assert_eq!(res.capabilities, vec!["iterator".to_string()]);
+        assert_eq!(res.vm_version, "1.2.3");
+        assert!(!res.has_capability("chain_queries"));
+    }
+
+    #[cfg(feature = "randomness")]
+    #[test]
+    fn randomness_querier_works() {
+        let randomness = RandomnessQuerier::new([(10, [1; 32]), (12, [2; 32])]);
+        let res = randomness
+            .query(&RandomnessQuery::Latest {})
+            .unwrap()
+            .unwrap();
+        let res: RandomnessResponse = from_json(res).unwrap();
+        assert_eq!(res, RandomnessResponse::new(12, [2; 32].into()));
+
+        let res = randomness
+            .query(&RandomnessQuery::ByHeight { height: 10 })
+            .unwrap()
+            .unwrap();
+        let res: RandomnessResponse = from_json(res).unwrap();
+        assert_eq!(res, RandomnessResponse::new(10, [1; 32].into()));
+
+        let res = randomness
+            .query(&RandomnessQuery::ByHeight { height: 11 })
+            .unwrap();
+        assert!(res.is_err());
+
+        let mut querier: MockQuerier = MockQuerier::new(&[]);
+        let wrapper = crate::QuerierWrapper::<Empty>::new(&querier);
+        wrapper.query_latest_randomness().unwrap_err();
+
+        querier.update_randomness(5, [7; 32]);
+        let wrapper = crate::QuerierWrapper::<Empty>::new(&querier);
+        let res = wrapper.query_latest_randomness().unwrap();
+        assert_eq!(res.height, 5);
+        assert_eq!(res.seed.to_array::<32>().unwrap(), [7; 32]);
+        assert_eq!(wrapper.query_randomness(5).unwrap(), res);
+        wrapper.query_randomness(6).unwrap_err();
+    }
+
+    #[cfg(feature = "token_factory")]
+    #[test]
+    fn token_factory_querier_works() {
+        let token_factory = TokenFactoryQuerier::new(
+            [
+                ("factory/creator/a", "creator"),
+                ("factory/creator/b", ""),
+                ("factory/other/a", "other"),
+            ],
+            &coins(100, "ucosm"),
+        );
+
+        let res = token_factory
+            .query(&TokenFactoryQuery::Admin {
+                denom: "factory/creator/b".to_string(),
+            })
+            .unwrap()
+            .unwrap();
+        let res: DenomAdminResponse = from_json(res).unwrap();
+        assert_eq!(res.admin, "");
+
+        let res = token_factory
+            .query(&TokenFactoryQuery::Admin {
+                denom: "factory/creator/c".to_string(),
+            })
+            .unwrap();
+        assert!(res.is_err());
+
+        let res = token_factory
+            .query(&TokenFactoryQuery::DenomsByCreator {
+                creator: "creator".to_string(),
+            })
+            .unwrap()
+            .unwrap();
+        let res: DenomsByCreatorResponse = from_json(res).unwrap();
+        assert_eq!(res.denoms, ["factory/creator/a", "factory/creator/b"]);
+
+        let mut querier: MockQuerier = MockQuerier::new(&[]);
+        querier.update_token_factory([("factory/creator/a", "creator")], &coins(5, "ucosm"));
+        let wrapper = crate::QuerierWrapper::<Empty>::new(&querier);
+        assert_eq!(
+            wrapper.query_full_denom("creator", "a").unwrap(),
+            "factory/creator/a"
+        );
+        assert_eq!(
+            wrapper.query_denom_admin("factory/creator/a").unwrap(),
+            "creator"
+        );
+        assert_eq!(
+            wrapper.query_denoms_by_creator("creator").unwrap(),
+            ["factory/creator/a"]
+        );
+        assert_eq!(
+            wrapper.query_denom_creation_fee().unwrap(),
+            coins(5, "ucosm")
+        );
+    }
+
     #[cfg(feature = "cosmwasm_1_3")]
     #[test]
     fn distribution_querier_delegator_withdraw_address() {
@@ -1900,6 +2557,85 @@ mod tests {
         assert_eq!(res.channels, vec![]);
     }
 
+    #[cfg(all(feature = "stargate", feature = "ibc_channel_filters"))]
+    #[test]
+    fn ibc_querier_channels_filtering() {
+        let chan1 = mock_ibc_channel("channel-0", IbcOrder::Ordered, "ibc");
+        let mut chan2 = mock_ibc_channel("channel-1", IbcOrder::Ordered, "ibc");
+        chan2.counterparty_endpoint.port_id = "other_port".to_string();
+        let mut chan3 = mock_ibc_channel("channel-2", IbcOrder::Ordered, "ibc");
+        chan3.connection_id = "connection-3".to_string();
+
+        let ibc = IbcQuerier::new("my_port", &[chan1.clone(), chan2.clone(), chan3.clone()]);
+        let list = |counterparty_port_id: Option<&str>,
+                    connection_id: Option<&str>,
+                    state: Option<IbcChannelState>| {
+            let query = &IbcQuery::ListChannelsFiltered {
+                port_id: None,
+                counterparty_port_id: counterparty_port_id.map(ToString::to_string),
+                connection_id: connection_id.map(ToString::to_string),
+                state,
+                pagination: None,
+            };
+            let raw = ibc.query(query).unwrap().unwrap();
+            from_json::<ListChannelsResponse>(raw).unwrap().channels
+        };
+
+        assert_eq!(
+            list(Some("their_port"), None, None),
+            vec![chan1.clone(), chan3]
+        );
+        assert_eq!(list(Some("other_port"), None, None), vec![chan2]);
+        assert_eq!(
+            list(Some("their_port"), Some("connection-2"), None),
+            vec![chan1.clone()]
+        );
+        assert_eq!(
+            list(None, Some("connection-2"), Some(IbcChannelState::Open)).len(),
+            2
+        );
+        assert_eq!(list(None, None, Some(IbcChannelState::Closed)), vec![]);
+    }
+
+    #[cfg(all(feature = "stargate", feature = "ibc_channel_filters"))]
+    #[test]
+    fn ibc_querier_channels_pagination() {
+        let channels: Vec<_> = (0..5)
+            .map(|i| mock_ibc_channel(&format!("channel-{i}"), IbcOrder::Ordered, "ibc"))
+            .collect();
+        let ibc = IbcQuerier::new("my_port", &channels);
+        let page = |key: Option<Binary>, reverse: bool| {
+            let query = &IbcQuery::ListChannelsFiltered {
+                port_id: None,
+                counterparty_port_id: None,
+                connection_id: None,
+                state: None,
+                pagination: Some(crate::PageRequest {
+                    key,
+                    limit: 2,
+                    reverse,
+                }),
+            };
+            let raw = ibc.query(query).unwrap().unwrap();
+            from_json::<ListChannelsResponse>(raw).unwrap()
+        };
+
+        let res = page(None, false);
+        assert_eq!(res.channels, channels[0..2]);
+        assert_eq!(res.next_key, Some(Binary::from(b"channel-2")));
+        let res = page(res.next_key, false);
+        assert_eq!(res.channels, channels[2..4]);
+        let res = page(res.next_key, false);
+        assert_eq!(res.channels, channels[4..]);
+        assert_eq!(res.next_key, None);
+
+        let res = page(None, true);
+        assert_eq!(res.channels, vec![channels[4].clone(), channels[3].clone()]);
+        let res = page(res.next_key, true);
+        assert_eq!(res.channels, vec![channels[2].clone(), channels[1].clone()]);
+        assert_eq!(res.next_key, Some(Binary::from(b"channel-0")));
+    }
+
     #[cfg(feature = "stargate")]
     #[test]
     fn ibc_querier_port() {
@@ -1917,18 +2653,18 @@ mod tests {
     #[cfg(feature = "staking")]
     #[test]
     fn staking_querier_all_validators() {
-        let val1 = Validator {
-            address: String::from("validator-one"),
-            commission: Decimal::percent(1),
-            max_commission: Decimal::percent(3),
-            max_change_rate: Decimal::percent(1),
-        };
-        let val2 = Validator {
-            address: String::from("validator-two"),
-            commission: Decimal::permille(15),
-            max_commission: Decimal::permille(40),
-            max_change_rate: Decimal::permille(5),
-        };
+        let val1 = Validator::create(
+            String::from("validator-one"),
+            Decimal::percent(1),
+            Decimal::percent(3),
+            Decimal::percent(1),
+        );
+        let val2 = Validator::create(
+            String::from("validator-two"),
+            Decimal::permille(15),
+            Decimal::permille(40),
+            Decimal::permille(5),
+        );
 
         let staking = StakingQuerier::new("ustake", &[val1.clone(), val2.clone()], &[]);
 
@@ -1948,18 +2684,18 @@ mod tests {
         let address2 = String::from("validator-two");
         let address_non_existent = String::from("wannabe-validator");
 
-        let val1 = Validator {
-            address: address1.clone(),
-            commission: Decimal::percent(1),
-            max_commission: Decimal::percent(3),
-            max_change_rate: Decimal::percent(1),
-        };
-        let val2 = Validator {
-            address: address2.clone(),
-            commission: Decimal::permille(15),
-            max_commission: Decimal::permille(40),
-            max_change_rate: Decimal::permille(5),
-        };
+        let val1 = Validator::create(
+            address1.clone(),
+            Decimal::percent(1),
+            Decimal::percent(3),
+            Decimal::percent(1),
+        );
+        let val2 = Validator::create(
+            address2.clone(),
+            Decimal::permille(15),
+            Decimal::permille(40),
+            Decimal::permille(5),
+        );
 
         let staking = StakingQuerier::new("ustake", &[val1.clone(), val2.clone()], &[]);
 
@@ -1990,6 +2726,42 @@ mod tests {
         assert_eq!(res.validator, None);
     }
 
+    #[cfg(feature = "staking_pool_params")]
+    #[test]
+    fn staking_querier_pool_and_params() {
+        let delegations = [
+            FullDelegation {
+                delegator: Addr::unchecked("investor"),
+                validator: String::from("validator-one"),
+                amount: coin(100, "ustake"),
+                can_redelegate: coin(100, "ustake"),
+                accumulated_rewards: coins(5, "ustake"),
+            },
+            FullDelegation {
+                delegator: Addr::unchecked("hodler"),
+                validator: String::from("validator-two"),
+                amount: coin(500, "ustake"),
+                can_redelegate: coin(500, "ustake"),
+                accumulated_rewards: vec![],
+            },
+        ];
+        let mut querier: MockQuerier = MockQuerier::new(&[]);
+        querier.update_staking("ustake", &[], &delegations);
+        let wrapper = crate::QuerierWrapper::<Empty>::new(&querier);
+
+        let pool = wrapper.query_staking_pool().unwrap();
+        assert_eq!(pool.bonded_tokens, coin(600, "ustake"));
+        assert_eq!(pool.not_bonded_tokens, coin(0, "ustake"));
+
+        let params = wrapper.query_staking_params().unwrap();
+        assert_eq!(params, StakingParamsResponse::new(1814400, 100, 7));
+
+        querier.update_staking_params(StakingParamsResponse::new(60, 3, 2));
+        let wrapper = crate::QuerierWrapper::<Empty>::new(&querier);
+        let params = wrapper.query_staking_params().unwrap();
+        assert_eq!(params, StakingParamsResponse::new(60, 3, 2));
+    }
+
     #[cfg(feature = "staking")]
     // gets delegators from query or panic
     fn get_all_delegators(
@@ -5,9 +5,9 @@ use serde::{de::DeserializeOwned, Serialize};
 use crate::addresses::{Addr, CanonicalAddr};
 use crate::binary::Binary;
 use crate::coin::Coin;
-use crate::errors::{RecoverPubkeyError, StdError, StdResult, VerificationError};
+use crate::errors::{RecoverPubkeyError, StdError, StdResult, SystemError, VerificationError};
 #[cfg(feature = "iterator")]
-use crate::iterator::{Order, Record};
+use crate::iterator::{prefix_upper_bound, Bound, Order, Record};
 #[cfg(feature = "cosmwasm_1_2")]
 use crate::query::CodeInfoResponse;
 #[cfg(feature = "cosmwasm_1_1")]
@@ -25,6 +25,19 @@ use crate::query::{
     AllDenomMetadataResponse, DelegatorWithdrawAddressResponse, DenomMetadataResponse,
     DistributionQuery,
 };
+#[cfg(feature = "chain_queries")]
+use crate::query::{CapabilitiesResponse, ChainQuery};
+#[cfg(feature = "token_factory")]
+use crate::query::{
+    DenomAdminResponse, DenomsByCreatorResponse, FullDenomResponse, TokenFactoryParamsResponse,
+    TokenFactoryQuery,
+};
+#[cfg(feature = "randomness")]
+use crate::query::{RandomnessQuery, RandomnessResponse};
+#[cfg(feature = "staking_pool_params")]
+use crate::query::{StakingParamsResponse, StakingPoolResponse};
+#[cfg(feature = "emit_event")]
+use crate::results::Event;
 use crate::results::{ContractResult, Empty, SystemResult};
 use crate::serde::{from_json, to_json_binary, to_json_vec};
 use crate::ContractInfoResponse;
@@ -41,6 +54,26 @@ pub trait Storage {
     /// is not great yet and might not be possible in all backends. But we're trying to get there.
     fn get(&self, key: &[u8]) -> Option<Vec<u8>>;
 
+    /// Reads the value of `key` into `buffer`, replacing its previous content.
+    /// Returns `false` and leaves `buffer` empty when the key does not exist.
+    ///
+    /// In contrast to [`Storage::get`], this allows implementations to reuse the allocation
+    /// of `buffer`, which reduces allocator pressure when reading many values in a loop.
+    ///
+    /// The default implementation uses [`Storage::get`] and replaces the buffer.
+    fn get_into(&self, key: &[u8], buffer: &mut Vec<u8>) -> bool {
+        match self.get(key) {
+            Some(value) => {
+                *buffer = value;
+                true
+            }
+            None => {
+                buffer.clear();
+                false
+            }
+        }
+    }
+
     /// Allows iteration over a set of key/value pairs, either forwards or backwards.
     ///
     /// The bound `start` is inclusive and `end` is exclusive.
@@ -87,6 +120,37 @@ pub trait Storage {
         Box::new(self.range(start, end, order).map(|(_k, v)| v))
     }
 
+    /// Allows iteration over all key/value pairs whose key starts with `prefix`, either forwards or backwards.
+    ///
+    /// The default implementation uses [`Storage::range`] with the range covering the prefix.
+    #[cfg(feature = "iterator")]
+    fn range_prefix<'a>(
+        &'a self,
+        prefix: &[u8],
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        let end = prefix_upper_bound(prefix);
+        self.range(Some(prefix), end.as_deref(), order)
+    }
+
+    /// Allows iteration over all key/value pairs between `min` and `max`, either forwards or backwards.
+    ///
+    /// In contrast to [`Storage::range`], both bounds can be inclusive or exclusive, see [`Bound`].
+    /// `min` is always the lower and `max` the upper bound, no matter of the order.
+    ///
+    /// The default implementation uses [`Storage::range`] with the bounds converted accordingly.
+    #[cfg(feature = "iterator")]
+    fn range_bounded<'a>(
+        &'a self,
+        min: Option<Bound>,
+        max: Option<Bound>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        let start = min.as_ref().map(Bound::to_start);
+        let end = max.as_ref().map(Bound::to_end);
+        self.range(start.as_deref(), end.as_deref(), order)
+    }
+
     fn set(&mut self, key: &[u8], value: &[u8]);
 
     /// Removes a database entry at `key`.
@@ -94,6 +158,26 @@ pub trait Storage {
     /// The current interface does not allow to differentiate between a key that existed
     /// before and one that didn't exist. See https://github.com/CosmWasm/cosmwasm/issues/290
     fn remove(&mut self, key: &[u8]);
+
+    /// Returns the approximate number of keys and bytes used by the storage,
+    /// or `None` if the storage implementation does not support this.
+    ///
+    /// This can be used to implement storage rent or self-pruning logic.
+    /// Implementations must return the same value on all nodes for the same state.
+    /// The default implementation returns `None`.
+    #[cfg(feature = "db_size")]
+    fn size_hint(&self) -> Option<StorageSize> {
+        None
+    }
+}
+
+/// The approximate storage usage as returned by [`Storage::size_hint`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageSize {
+    /// The number of keys
+    pub keys: u64,
+    /// The sum of the lengths of all keys and values
+    pub bytes: u64,
 }
 
 /// Api are callbacks to system functions implemented outside of the wasm modules.
@@ -178,6 +262,68 @@ pub trait Api {
         public_keys: &[&[u8]],
     ) -> Result<bool, VerificationError>;
 
+    /// Verifies a message against a signature with a public key, using the Ed25519ctx variant
+    /// of [RFC 8032](https://www.rfc-editor.org/rfc/rfc8032#section-5.1).
+    /// The signature is bound to the `context`, which must be between 1 and 255 bytes long.
+    #[cfg(feature = "ed25519_variants")]
+    #[allow(unused_variables)]
+    fn ed25519ctx_verify(
+        &self,
+        message: &[u8],
+        context: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        Err(VerificationError::unimplemented())
+    }
+
+    /// Verifies the SHA-512 hash of a message (64 bytes) against a signature with a public key,
+    /// using the Ed25519ph variant of [RFC 8032](https://www.rfc-editor.org/rfc/rfc8032#section-5.1).
+    /// The `context` is optional and can be up to 255 bytes long. Use an empty slice for no context.
+    ///
+    /// This is the variant typically used by hardware wallets.
+    #[cfg(feature = "ed25519_variants")]
+    #[allow(unused_variables)]
+    fn ed25519ph_verify(
+        &self,
+        message_hash: &[u8],
+        context: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        Err(VerificationError::unimplemented())
+    }
+
+    /// Returns the gas that is left for the current call, measured in
+    /// [CosmWasm gas](https://github.com/CosmWasm/cosmwasm/blob/main/docs/GAS.md).
+    ///
+    /// This allows processing as many items of a batch as the gas allows instead of
+    /// failing the whole transaction with out of gas. Keep a safety margin for the work
+    /// that follows, e.g. saving state and serializing the response.
+    ///
+    /// The default implementation returns an error, i.e. the Api does not support this.
+    #[cfg(feature = "gas_remaining")]
+    fn gas_remaining(&self) -> StdResult<u64> {
+        Err(StdError::generic_err(
+            "Api::gas_remaining is not implemented",
+        ))
+    }
+
+    /// Passes an event to the host while the contract is still executing.
+    ///
+    /// In contrast to events added to the [`Response`](crate::Response), those do not need to be
+    /// kept in memory until the end of the call, which allows long running batch operations
+    /// to report progress per item. This can only be used in calls that can write to storage,
+    /// i.e. not in queries. The host charges gas per event and limits the number of events
+    /// emitted in a single call.
+    ///
+    /// The default implementation returns an error, i.e. the Api does not support this.
+    #[cfg(feature = "emit_event")]
+    #[allow(unused_variables)]
+    fn emit_event(&self, event: &Event) -> StdResult<()> {
+        Err(StdError::generic_err("Api::emit_event is not implemented"))
+    }
+
     /// Emits a debugging message that is handled depending on the environment (typically printed to console or ignored).
     /// Those messages are not persisted to chain.
     fn debug(&self, message: &str);
@@ -193,6 +339,41 @@ pub trait Querier {
     /// types. People using the querier probably want one of the simpler auto-generated
     /// helper methods
     fn raw_query(&self, bin_request: &[u8]) -> QuerierResult;
+
+    /// Performs multiple raw queries and returns their results in the same order.
+    ///
+    /// The default implementation calls [`Querier::raw_query`] for every request.
+    /// Implementations can override this to process all requests in one call, like the
+    /// querier of a contract does with the `query_batch` feature.
+    fn raw_query_batch(&self, bin_requests: &[&[u8]]) -> Vec<QuerierResult> {
+        bin_requests
+            .iter()
+            .map(|request| self.raw_query(request))
+            .collect()
+    }
+
+    /// Performs multiple raw queries like [`Querier::raw_query_batch`], but each query
+    /// can use at most `gas_limit` gas. A query exceeding the limit results in
+    /// [`SystemError::OutOfGas`] instead of aborting the execution.
+    ///
+    /// The default implementation cannot enforce the gas limit and returns
+    /// [`SystemError::UnsupportedRequest`] for every request.
+    #[cfg(feature = "query_batch")]
+    fn raw_query_batch_with_gas_limit(
+        &self,
+        bin_requests: &[&[u8]],
+        gas_limit: u64,
+    ) -> Vec<QuerierResult> {
+        let _ = gas_limit;
+        bin_requests
+            .iter()
+            .map(|_| {
+                SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "gas limited query".to_string(),
+                })
+            })
+            .collect()
+    }
 }
 /// A wrapper struct that enables querying blockchain state from within a contract.
 #[derive(Clone)]
@@ -240,6 +421,11 @@ impl<'a, C: CustomQuery> QuerierWrapper<'a, C> {
     /// one level. Only use this if you don't need to check the SystemError
     /// eg. If you don't differentiate between contract missing and contract returned error
     pub fn query<U: DeserializeOwned>(&self, request: &QueryRequest<C>) -> StdResult<U> {
+        from_json(self.query_unparsed(request)?)
+    }
+
+    /// Performs the query and returns the response without deserializing it
+    fn query_unparsed<Q: Serialize>(&self, request: &QueryRequest<Q>) -> StdResult<Binary> {
         let raw = to_json_vec(request).map_err(|serialize_err| {
             StdError::generic_err(format!("Serializing QueryRequest: {serialize_err}"))
         })?;
@@ -250,10 +436,108 @@ impl<'a, C: CustomQuery> QuerierWrapper<'a, C> {
             SystemResult::Ok(ContractResult::Err(contract_err)) => Err(StdError::generic_err(
                 format!("Querier contract error: {contract_err}"),
             )),
-            SystemResult::Ok(ContractResult::Ok(value)) => from_json(value),
+            SystemResult::Ok(ContractResult::Ok(value)) => Ok(value),
         }
     }
 
+    /// Performs multiple queries and returns their unparsed results in the same order.
+    ///
+    /// With the `query_batch` feature, all queries are sent to the host in one call,
+    /// which is cheaper than querying them one by one. A request that cannot be serialized
+    /// is not sent and results in a [`SystemError::InvalidRequest`].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{from_json, BalanceResponse, BankQuery, ContractResult, Empty, QuerierWrapper, QueryRequest, SystemResult};
+    /// # use cosmwasm_std::testing::MockQuerier;
+    /// # let querier: MockQuerier = MockQuerier::new(&[]);
+    /// # let querier = QuerierWrapper::<Empty>::new(&querier);
+    /// let requests: Vec<QueryRequest<Empty>> = ["earth", "moon"]
+    ///     .into_iter()
+    ///     .map(|denom| {
+    ///         BankQuery::Balance {
+    ///             address: "alice".to_string(),
+    ///             denom: denom.to_string(),
+    ///         }
+    ///         .into()
+    ///     })
+    ///     .collect();
+    ///
+    /// let results = querier.query_batch(requests);
+    /// assert_eq!(results.len(), 2);
+    /// for result in results {
+    ///     let SystemResult::Ok(ContractResult::Ok(response)) = result else {
+    ///         panic!("query failed");
+    ///     };
+    ///     let balance: BalanceResponse = from_json(response).unwrap();
+    ///     assert!(balance.amount.amount.is_zero());
+    /// }
+    /// ```
+    pub fn query_batch(&self, requests: Vec<QueryRequest<C>>) -> Vec<QuerierResult> {
+        self.query_batch_with(&requests, |bin_requests| self.raw_query_batch(bin_requests))
+    }
+
+    /// Performs multiple queries like [`QuerierWrapper::query_batch`], but each query can use
+    /// at most `gas_limit` gas.
+    ///
+    /// A query exceeding its limit results in [`SystemError::OutOfGas`] and the gas used up to
+    /// this point is charged, but the execution continues. This allows best-effort queries,
+    /// e.g. for optional data. Use [`Api::gas_remaining`] to choose a limit. Running out of
+    /// gas for the execution as a whole still aborts it.
+    ///
+    /// Queriers that cannot enforce the limit return [`SystemError::UnsupportedRequest`]
+    /// instead of running the queries. The mock querier does not meter gas and ignores it.
+    #[cfg(feature = "query_batch")]
+    pub fn query_batch_with_gas_limit(
+        &self,
+        requests: Vec<QueryRequest<C>>,
+        gas_limit: u64,
+    ) -> Vec<QuerierResult> {
+        self.query_batch_with(&requests, |bin_requests| {
+            self.raw_query_batch_with_gas_limit(bin_requests, gas_limit)
+        })
+    }
+
+    /// Performs a single query that can use at most `gas_limit` gas and returns the unparsed
+    /// result. See [`QuerierWrapper::query_batch_with_gas_limit`] for details.
+    #[cfg(feature = "query_batch")]
+    pub fn query_with_gas_limit(&self, request: QueryRequest<C>, gas_limit: u64) -> QuerierResult {
+        self.query_batch_with_gas_limit(vec![request], gas_limit)
+            .pop()
+            .unwrap_or_else(missing_batch_result)
+    }
+
+    /// Serializes the requests and executes all serializable ones using `raw_query_batch`.
+    fn query_batch_with(
+        &self,
+        requests: &[QueryRequest<C>],
+        raw_query_batch: impl FnOnce(&[&[u8]]) -> Vec<QuerierResult>,
+    ) -> Vec<QuerierResult> {
+        let serialized: Vec<Result<Vec<u8>, SystemError>> = requests
+            .iter()
+            .map(|request| {
+                to_json_vec(request).map_err(|serialize_err| SystemError::InvalidRequest {
+                    error: format!("Serializing QueryRequest: {serialize_err}"),
+                    request: Binary::default(),
+                })
+            })
+            .collect();
+        let valid: Vec<&[u8]> = serialized
+            .iter()
+            .filter_map(|request| request.as_deref().ok())
+            .collect();
+
+        let mut results = raw_query_batch(&valid).into_iter();
+        serialized
+            .into_iter()
+            .map(|request| match request {
+                Ok(_) => results.next().unwrap_or_else(missing_batch_result),
+                Err(err) => SystemResult::Err(err),
+            })
+            .collect()
+    }
+
     #[cfg(feature = "cosmwasm_1_1")]
     pub fn query_supply(&self, denom: impl Into<String>) -> StdResult<Coin> {
         let request = BankQuery::Supply {
@@ -300,6 +584,73 @@ impl<'a, C: CustomQuery> QuerierWrapper<'a, C> {
         Ok(res.withdraw_address)
     }
 
+    /// Queries the capabilities available on the chain and the version of the VM.
+    #[cfg(feature = "chain_queries")]
+    pub fn query_chain_capabilities(&self) -> StdResult<CapabilitiesResponse> {
+        let request = ChainQuery::Capabilities {}.into();
+        self.query(&request)
+    }
+
+    /// Queries the most recent randomness of the chain's randomness beacon.
+    #[cfg(feature = "randomness")]
+    pub fn query_latest_randomness(&self) -> StdResult<RandomnessResponse> {
+        let request = RandomnessQuery::Latest {}.into();
+        self.query(&request)
+    }
+
+    /// Queries the randomness of the chain's randomness beacon for the given block height.
+    #[cfg(feature = "randomness")]
+    pub fn query_randomness(&self, height: u64) -> StdResult<RandomnessResponse> {
+        let request = RandomnessQuery::ByHeight { height }.into();
+        self.query(&request)
+    }
+
+    /// Returns the full token factory denom `factory/{creator}/{subdenom}` for the given creator and subdenom.
+    #[cfg(feature = "token_factory")]
+    pub fn query_full_denom(
+        &self,
+        creator: impl Into<String>,
+        subdenom: impl Into<String>,
+    ) -> StdResult<String> {
+        let request = TokenFactoryQuery::FullDenom {
+            creator_addr: creator.into(),
+            subdenom: subdenom.into(),
+        }
+        .into();
+        let res: FullDenomResponse = self.query(&request)?;
+        Ok(res.denom)
+    }
+
+    /// Returns the admin of the given token factory denom or an empty string if it has no admin.
+    #[cfg(feature = "token_factory")]
+    pub fn query_denom_admin(&self, denom: impl Into<String>) -> StdResult<String> {
+        let request = TokenFactoryQuery::Admin {
+            denom: denom.into(),
+        }
+        .into();
+        let res: DenomAdminResponse = self.query(&request)?;
+        Ok(res.admin)
+    }
+
+    /// Returns all token factory denoms created by the given address.
+    #[cfg(feature = "token_factory")]
+    pub fn query_denoms_by_creator(&self, creator: impl Into<String>) -> StdResult<Vec<String>> {
+        let request = TokenFactoryQuery::DenomsByCreator {
+            creator: creator.into(),
+        }
+        .into();
+        let res: DenomsByCreatorResponse = self.query(&request)?;
+        Ok(res.denoms)
+    }
+
+    /// Returns the fee charged by the token factory for creating a new denom.
+    #[cfg(feature = "token_factory")]
+    pub fn query_denom_creation_fee(&self) -> StdResult<Vec<Coin>> {
+        let request = TokenFactoryQuery::Params {}.into();
+        let res: TokenFactoryParamsResponse = self.query(&request)?;
+        Ok(res.denom_creation_fee)
+    }
+
     #[cfg(feature = "cosmwasm_1_3")]
     pub fn query_denom_metadata(&self, denom: impl Into<String>) -> StdResult<DenomMetadata> {
         let request = BankQuery::DenomMetadata {
@@ -382,6 +733,24 @@ impl<'a, C: CustomQuery> QuerierWrapper<'a, C> {
         self.query(&request)
     }
 
+    /// Queries another wasm contract like [`QuerierWrapper::query_wasm_smart`], but takes the
+    /// already serialized query message and returns the response without deserializing it.
+    ///
+    /// This allows proxy contracts to forward queries and responses verbatim without
+    /// paying for a deserialization and serialization roundtrip.
+    pub fn query_wasm_smart_raw(
+        &self,
+        contract_addr: impl Into<String>,
+        msg: impl Into<Binary>,
+    ) -> StdResult<Binary> {
+        let request: QueryRequest<Empty> = WasmQuery::Smart {
+            contract_addr: contract_addr.into(),
+            msg: msg.into(),
+        }
+        .into();
+        self.query_unparsed(&request)
+    }
+
     /// Queries the raw storage from another wasm contract.
     ///
     /// You must know the exact layout and are implementation dependent
@@ -400,25 +769,12 @@ impl<'a, C: CustomQuery> QuerierWrapper<'a, C> {
             key: key.into(),
         }
         .into();
-        // we cannot use query, as it will try to parse the binary data, when we just want to return it,
-        // so a bit of code copy here...
-        let raw = to_json_vec(&request).map_err(|serialize_err| {
-            StdError::generic_err(format!("Serializing QueryRequest: {serialize_err}"))
-        })?;
-        match self.raw_query(&raw) {
-            SystemResult::Err(system_err) => Err(StdError::generic_err(format!(
-                "Querier system error: {system_err}"
-            ))),
-            SystemResult::Ok(ContractResult::Err(contract_err)) => Err(StdError::generic_err(
-                format!("Querier contract error: {contract_err}"),
-            )),
-            SystemResult::Ok(ContractResult::Ok(value)) => {
-                if value.is_empty() {
-                    Ok(None)
-                } else {
-                    Ok(Some(value.into()))
-                }
-            }
+        // we cannot use query, as it will try to parse the binary data, when we just want to return it
+        let value = self.query_unparsed(&request)?;
+        if value.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(value.into()))
         }
     }
 
@@ -492,6 +848,27 @@ impl<'a, C: CustomQuery> QuerierWrapper<'a, C> {
         let res: DelegationResponse = self.query(&request)?;
         Ok(res.delegation)
     }
+
+    /// Queries the amount of bonded and not bonded tokens of the chain.
+    #[cfg(feature = "staking_pool_params")]
+    pub fn query_staking_pool(&self) -> StdResult<StakingPoolResponse> {
+        let request = StakingQuery::Pool {}.into();
+        self.query(&request)
+    }
+
+    /// Queries the parameters of the staking module, e.g. the unbonding time.
+    #[cfg(feature = "staking_pool_params")]
+    pub fn query_staking_params(&self) -> StdResult<StakingParamsResponse> {
+        let request = StakingQuery::Params {}.into();
+        self.query(&request)
+    }
+}
+
+fn missing_batch_result() -> QuerierResult {
+    SystemResult::Err(SystemError::InvalidResponse {
+        error: "Missing result in query batch".to_string(),
+        response: Binary::default(),
+    })
 }
 
 #[cfg(test)]
@@ -620,13 +997,119 @@ mod tests {
         let wrapper = QuerierWrapper::<Empty>::new(&querier);
 
         let err = wrapper.query_wasm_contract_info("unknown").unwrap_err();
-        assert!(matches!(
+        assert_eq!(
             err,
-            StdError::GenericErr {
-                msg,
-                ..
-            } if msg == "Querier system error: No such contract: foobar"
-        ));
+            StdError::generic_err("Querier system error: No such contract: foobar")
+        );
+    }
+
+    #[test]
+    fn query_wasm_smart_raw_works() {
+        const ACCT: &str = "foobar";
+        const RESPONSE: &[u8] = br#"{ "unparsed" : true }"#;
+
+        let mut querier: MockQuerier<Empty> = MockQuerier::new(&[]);
+        querier.update_wasm(|q| -> QuerierResult {
+            match q {
+                WasmQuery::Smart { contract_addr, msg } if contract_addr == ACCT => {
+                    assert_eq!(msg.as_slice(), br#"{"forward":{}}"#);
+                    SystemResult::Ok(ContractResult::Ok(Binary::from(RESPONSE)))
+                }
+                _ => SystemResult::Ok(ContractResult::Err("unexpected query".to_string())),
+            }
+        });
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+
+        let response = wrapper
+            .query_wasm_smart_raw(ACCT, br#"{"forward":{}}"#.to_vec())
+            .unwrap();
+        assert_eq!(response.as_slice(), RESPONSE);
+
+        let err = wrapper
+            .query_wasm_smart_raw("unknown", b"{}".to_vec())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err("Querier contract error: unexpected query")
+        );
+    }
+
+    #[test]
+    fn query_batch_works() {
+        let mut querier: MockQuerier<Empty> = MockQuerier::new(&[("foo", &coins(5, "BTC"))]);
+        querier.update_wasm(|_| SystemResult::Ok(ContractResult::Err("no wasm".to_string())));
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+
+        let results = wrapper.query_batch(vec![
+            BankQuery::Balance {
+                address: "foo".to_string(),
+                denom: "BTC".to_string(),
+            }
+            .into(),
+            WasmQuery::Smart {
+                contract_addr: "bar".to_string(),
+                msg: b"{}".into(),
+            }
+            .into(),
+        ]);
+        assert_eq!(results.len(), 2);
+        let SystemResult::Ok(ContractResult::Ok(balance)) = &results[0] else {
+            panic!("unexpected result: {:?}", results[0]);
+        };
+        let balance: BalanceResponse = from_json(balance).unwrap();
+        assert_eq!(balance.amount, crate::coin(5, "BTC"));
+        assert_eq!(
+            results[1],
+            SystemResult::Ok(ContractResult::Err("no wasm".to_string()))
+        );
+
+        assert_eq!(wrapper.query_batch(vec![]), vec![]);
+    }
+
+    #[cfg(feature = "query_batch")]
+    #[test]
+    fn query_with_gas_limit_works() {
+        let querier: MockQuerier<Empty> = MockQuerier::new(&[("foo", &coins(5, "BTC"))]);
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+        let request: QueryRequest<Empty> = BankQuery::Balance {
+            address: "foo".to_string(),
+            denom: "BTC".to_string(),
+        }
+        .into();
+
+        // the mock querier does not meter gas, so the limit is ignored
+        let result = wrapper.query_with_gas_limit(request.clone(), 0);
+        let SystemResult::Ok(ContractResult::Ok(balance)) = result else {
+            panic!("unexpected result: {result:?}");
+        };
+        let balance: BalanceResponse = from_json(balance).unwrap();
+        assert_eq!(balance.amount, crate::coin(5, "BTC"));
+
+        let results = wrapper.query_batch_with_gas_limit(vec![request.clone(), request], 1000);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0], results[1]);
+    }
+
+    #[cfg(feature = "query_batch")]
+    #[test]
+    fn raw_query_batch_with_gas_limit_default_is_unsupported() {
+        struct MyQuerier;
+        impl Querier for MyQuerier {
+            fn raw_query(&self, _bin_request: &[u8]) -> QuerierResult {
+                SystemResult::Ok(ContractResult::Ok(Binary::default()))
+            }
+        }
+
+        let results = MyQuerier.raw_query_batch_with_gas_limit(&[b"{}", b"{}"], 1000);
+        assert_eq!(
+            results,
+            vec![
+                SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "gas limited query".to_string()
+                });
+                2
+            ]
+        );
     }
 
     #[test]
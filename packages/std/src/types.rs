@@ -2,7 +2,11 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::addresses::Addr;
+use crate::binary::Binary;
 use crate::coin::Coin;
+use crate::coins::Coins;
+use crate::errors::{StdError, StdResult};
+use crate::math::Uint128;
 use crate::timestamp::Timestamp;
 ///Holds the environmental information of the contract's execution context, like block info and transaction details.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -24,6 +28,18 @@ pub struct TransactionInfo {
     /// using the pair (`env.block.height`, `env.transaction.index`).
     ///
     pub index: u32,
+    /// The hash of the transaction.
+    ///
+    /// This is `None` if the chain does not provide it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<Binary>,
+    /// The position of the message that triggered this execution in the transaction.
+    /// The first message has index 0.
+    ///
+    /// Together with `hash`, this allows deduplicating actions per message.
+    /// This is `None` if the chain does not provide it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub msg_index: Option<u32>,
 }
 /// Represents information about a specific blockchain block, such as its height, timestamp, and other relevant data.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -47,9 +63,16 @@ pub struct BlockInfo {
     /// #         time: Timestamp::from_nanos(1_571_797_419_879_305_533),
     /// #         chain_id: "cosmos-testnet-14002".to_string(),
     /// #     },
-    /// #     transaction: Some(TransactionInfo { index: 3 }),
+    /// #     transaction: Some(TransactionInfo {
+    /// #         index: 3,
+    /// #         hash: None,
+    /// #         msg_index: None,
+    /// #     }),
     /// #     contract: ContractInfo {
     /// #         address: Addr::unchecked("contract"),
+    /// #         code_id: None,
+    /// #         creator: None,
+    /// #         admin: None,
     /// #     },
     /// # };
     /// # extern crate chrono;
@@ -69,9 +92,16 @@ pub struct BlockInfo {
     /// #         time: Timestamp::from_nanos(1_571_797_419_879_305_533),
     /// #         chain_id: "cosmos-testnet-14002".to_string(),
     /// #     },
-    /// #     transaction: Some(TransactionInfo { index: 3 }),
+    /// #     transaction: Some(TransactionInfo {
+    /// #         index: 3,
+    /// #         hash: None,
+    /// #         msg_index: None,
+    /// #     }),
     /// #     contract: ContractInfo {
     /// #         address: Addr::unchecked("contract"),
+    /// #         code_id: None,
+    /// #         creator: None,
+    /// #         admin: None,
     /// #     },
     /// # };
     /// let millis = env.block.time.nanos() / 1_000_000;
@@ -104,8 +134,207 @@ pub struct MessageInfo {
     /// is executed such that the new balance is visible during contract execution.
     pub funds: Vec<Coin>,
 }
+
+impl MessageInfo {
+    /// Returns an error if any funds were sent. Use this in messages that are not payable.
+    pub fn assert_no_funds(&self) -> StdResult<()> {
+        if self.funds.iter().any(|coin| !coin.amount.is_zero()) {
+            return Err(StdError::generic_err("This message does not accept funds"));
+        }
+        Ok(())
+    }
+
+    /// Returns the only coin that was sent.
+    ///
+    /// Fails if no funds or more than one denomination were sent.
+    pub fn one_coin(&self) -> StdResult<Coin> {
+        let mut sent = self.funds.iter().filter(|coin| !coin.amount.is_zero());
+        match (sent.next(), sent.next()) {
+            (Some(coin), None) => Ok(coin.clone()),
+            (None, _) => Err(StdError::generic_err("No funds sent")),
+            (Some(_), Some(_)) => Err(StdError::generic_err("Sent more than one denomination")),
+        }
+    }
+
+    /// Returns the amount sent in the given denomination.
+    ///
+    /// Fails if no funds, a different denomination or more than one denomination were sent.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{coins, Addr, MessageInfo, Uint128};
+    /// let info = MessageInfo {
+    ///     sender: Addr::unchecked("sender"),
+    ///     funds: coins(100, "ucosm"),
+    /// };
+    /// assert_eq!(info.require_funds("ucosm").unwrap(), Uint128::new(100));
+    /// info.require_funds("uatom").unwrap_err();
+    /// ```
+    pub fn require_funds(&self, denom: &str) -> StdResult<Uint128> {
+        let coin = self.one_coin()?;
+        if coin.denom != denom {
+            return Err(StdError::generic_err(format!(
+                "Expected funds in {denom} but got {}",
+                coin.denom
+            )));
+        }
+        Ok(coin.amount)
+    }
+
+    /// Returns an error unless exactly the given funds were sent.
+    /// The order of the coins and coins with an amount of zero are ignored.
+    pub fn assert_sent_exact_funds(&self, expected: &[Coin]) -> StdResult<()> {
+        let sent = Coins::try_from(self.funds.as_slice())?;
+        let expected = Coins::try_from(expected)?;
+        if sent != expected {
+            return Err(StdError::generic_err(format!(
+                "Expected funds {expected} but got {sent}"
+            )));
+        }
+        Ok(())
+    }
+}
 ///Stores information about a smart contract, like its creator, admin, and other relevant details.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct ContractInfo {
     pub address: Addr,
+    /// The code ID the contract is currently running.
+    ///
+    /// This is `None` if the chain does not provide it. In this case the code ID can be
+    /// obtained using a [`WasmQuery::ContractInfo`](crate::WasmQuery::ContractInfo) query.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_id: Option<u64>,
+    /// The address that instantiated the contract.
+    ///
+    /// This is `None` if the chain does not provide it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creator: Option<Addr>,
+    /// The admin of the contract, i.e. the address that can migrate it.
+    ///
+    /// This is `None` if the contract has no admin or the chain does not provide it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin: Option<Addr>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{coin, coins, from_json, to_json_string};
+
+    #[test]
+    fn contract_info_serde_works() {
+        // optional fields are skipped if unset
+        let info = ContractInfo {
+            address: Addr::unchecked("contract"),
+            code_id: None,
+            creator: None,
+            admin: None,
+        };
+        let json = to_json_string(&info).unwrap();
+        assert_eq!(json, r#"{"address":"contract"}"#);
+        assert_eq!(from_json::<ContractInfo>(json).unwrap(), info);
+
+        let info = ContractInfo {
+            address: Addr::unchecked("contract"),
+            code_id: Some(17),
+            creator: Some(Addr::unchecked("creator")),
+            admin: Some(Addr::unchecked("admin")),
+        };
+        let json = to_json_string(&info).unwrap();
+        assert_eq!(
+            json,
+            r#"{"address":"contract","code_id":17,"creator":"creator","admin":"admin"}"#
+        );
+        assert_eq!(from_json::<ContractInfo>(json).unwrap(), info);
+    }
+
+    #[test]
+    fn transaction_info_serde_works() {
+        // optional fields are skipped if unset
+        let info = TransactionInfo {
+            index: 3,
+            hash: None,
+            msg_index: None,
+        };
+        let json = to_json_string(&info).unwrap();
+        assert_eq!(json, r#"{"index":3}"#);
+        assert_eq!(from_json::<TransactionInfo>(json).unwrap(), info);
+
+        let info = TransactionInfo {
+            index: 3,
+            hash: Some(Binary::from([0xAA; 4])),
+            msg_index: Some(1),
+        };
+        let json = to_json_string(&info).unwrap();
+        assert_eq!(json, r#"{"index":3,"hash":"qqqqqg==","msg_index":1}"#);
+        assert_eq!(from_json::<TransactionInfo>(json).unwrap(), info);
+    }
+
+    fn info(funds: &[Coin]) -> MessageInfo {
+        MessageInfo {
+            sender: Addr::unchecked("sender"),
+            funds: funds.to_vec(),
+        }
+    }
+
+    #[test]
+    fn message_info_assert_no_funds_works() {
+        info(&[]).assert_no_funds().unwrap();
+        info(&[coin(0, "ucosm")]).assert_no_funds().unwrap();
+        let err = info(&coins(1, "ucosm")).assert_no_funds().unwrap_err();
+        assert!(err.to_string().contains("does not accept funds"));
+    }
+
+    #[test]
+    fn message_info_one_coin_works() {
+        assert_eq!(
+            info(&coins(5, "ucosm")).one_coin().unwrap(),
+            coin(5, "ucosm")
+        );
+        assert_eq!(
+            info(&[coin(0, "uatom"), coin(5, "ucosm")])
+                .one_coin()
+                .unwrap(),
+            coin(5, "ucosm")
+        );
+
+        let err = info(&[]).one_coin().unwrap_err();
+        assert!(err.to_string().contains("No funds sent"));
+        let err = info(&[coin(1, "uatom"), coin(5, "ucosm")])
+            .one_coin()
+            .unwrap_err();
+        assert!(err.to_string().contains("more than one denomination"));
+    }
+
+    #[test]
+    fn message_info_require_funds_works() {
+        let info = info(&coins(5, "ucosm"));
+        assert_eq!(info.require_funds("ucosm").unwrap(), Uint128::new(5));
+        let err = info.require_funds("uatom").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Expected funds in uatom but got ucosm"));
+    }
+
+    #[test]
+    fn message_info_assert_sent_exact_funds_works() {
+        let info = info(&[coin(1, "uatom"), coin(5, "ucosm")]);
+        info.assert_sent_exact_funds(&[coin(5, "ucosm"), coin(1, "uatom")])
+            .unwrap();
+        info.assert_sent_exact_funds(&[coin(5, "ucosm"), coin(1, "uatom"), coin(0, "uxyz")])
+            .unwrap();
+
+        let err = info
+            .assert_sent_exact_funds(&coins(5, "ucosm"))
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Expected funds 5ucosm but got 1uatom,5ucosm"));
+        info.assert_sent_exact_funds(&[coin(1, "uatom"), coin(6, "ucosm")])
+            .unwrap_err();
+        // duplicate denoms are invalid
+        info.assert_sent_exact_funds(&[coin(1, "uatom"), coin(5, "uatom")])
+            .unwrap_err();
+    }
 }
@@ -7,6 +7,7 @@ use std::time::{Duration, SystemTime};
 use tempfile::TempDir;
 
 use cosmwasm_std::{coins, Checksum, Empty};
+use cosmwasm_vm::bench::{instantiated_instance, BenchOptions};
 use cosmwasm_vm::testing::{
     mock_backend, mock_env, mock_info, mock_instance_options, MockApi, MockQuerier, MockStorage,
 };
@@ -18,9 +19,7 @@ use cosmwasm_vm::{
 // Instance
 const DEFAULT_MEMORY_LIMIT: Size = Size::mebi(64);
 const DEFAULT_GAS_LIMIT: u64 = 1_000_000_000; // ~1ms
-const DEFAULT_INSTANCE_OPTIONS: InstanceOptions = InstanceOptions {
-    gas_limit: DEFAULT_GAS_LIMIT,
-};
+const DEFAULT_INSTANCE_OPTIONS: InstanceOptions = InstanceOptions::new(DEFAULT_GAS_LIMIT);
 const HIGH_GAS_LIMIT: u64 = 20_000_000_000_000; // ~20s, allows many calls on one instance
 
 // Cache
@@ -47,9 +46,7 @@ fn bench_instance(c: &mut Criterion) {
 
     group.bench_function("execute init", |b| {
         let backend = mock_backend(&[]);
-        let much_gas: InstanceOptions = InstanceOptions {
-            gas_limit: HIGH_GAS_LIMIT,
-        };
+        let much_gas: InstanceOptions = InstanceOptions::new(HIGH_GAS_LIMIT);
         let mut instance =
             Instance::from_code(CONTRACT, backend, much_gas, Some(DEFAULT_MEMORY_LIMIT)).unwrap();
 
@@ -63,18 +60,10 @@ fn bench_instance(c: &mut Criterion) {
     });
 
     group.bench_function("execute execute (release)", |b| {
-        let backend = mock_backend(&[]);
-        let much_gas: InstanceOptions = InstanceOptions {
-            gas_limit: HIGH_GAS_LIMIT,
-        };
-        let mut instance =
-            Instance::from_code(CONTRACT, backend, much_gas, Some(DEFAULT_MEMORY_LIMIT)).unwrap();
-
         let info = mock_info("creator", &coins(1000, "earth"));
         let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
-        let contract_result =
-            call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg).unwrap();
-        assert!(contract_result.into_result().is_ok());
+        let mut instance =
+            instantiated_instance(CONTRACT, &info, msg, &BenchOptions::default()).unwrap();
 
         b.iter(|| {
             let info = mock_info("verifies", &coins(15, "earth"));
@@ -87,9 +76,7 @@ fn bench_instance(c: &mut Criterion) {
 
     group.bench_function("execute execute (argon2)", |b| {
         let backend = mock_backend(&[]);
-        let much_gas: InstanceOptions = InstanceOptions {
-            gas_limit: HIGH_GAS_LIMIT,
-        };
+        let much_gas: InstanceOptions = InstanceOptions::new(HIGH_GAS_LIMIT);
         let mut instance =
             Instance::from_code(CYBERPUNK, backend, much_gas, Some(DEFAULT_MEMORY_LIMIT)).unwrap();
 
@@ -0,0 +1,137 @@
+//! Benchmarks for storage iteration, used to derive the iterator costs in `GasConfig`.
+//!
+//! The per-item costs of `next` are compared to single reads of the same entries and the
+//! differences between the value sizes show the per-byte part. Divide the measured times by the gas
+//! target of 10^6 gas per µs (see docs/GAS.md) to get the corresponding gas values.
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::time::{Duration, Instant};
+
+use cosmwasm_std::Order;
+use cosmwasm_vm::testing::MockStorage;
+use cosmwasm_vm::Storage;
+
+const ITEMS: u32 = 1_000;
+const VALUE_SIZES: [usize; 3] = [0, 1024, 16 * 1024];
+
+fn key(i: u32) -> Vec<u8> {
+    [b"item".as_slice(), &i.to_be_bytes()].concat()
+}
+
+fn filled_storage(value_size: usize) -> MockStorage {
+    let mut storage = MockStorage::new();
+    for i in 0..ITEMS {
+        storage.set(&key(i), &vec![0xAA; value_size]).0.unwrap();
+    }
+    storage
+}
+
+/// Runs `routine` `iters` times and measures the total time spent in it. A fresh
+/// storage is created by `setup` for every `ITEMS` runs, since the mock storage
+/// keeps all iterators until it is dropped.
+fn iter_with_storage<T>(
+    iters: u64,
+    setup: impl Fn() -> T,
+    mut routine: impl FnMut(&mut T),
+) -> Duration {
+    let mut total = Duration::ZERO;
+    let mut remaining = iters;
+    while remaining > 0 {
+        let runs = remaining.min(ITEMS.into());
+        let mut input = setup();
+        let start = Instant::now();
+        for _ in 0..runs {
+            routine(&mut input);
+        }
+        total += start.elapsed();
+        remaining -= runs;
+    }
+    total
+}
+
+fn bench_storage(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Storage");
+
+    group.bench_function("scan (create iterator)", |b| {
+        let (start, end) = (key(0), key(1));
+        b.iter_custom(|iters| {
+            iter_with_storage(
+                iters,
+                || filled_storage(0),
+                |storage| {
+                    let id = storage
+                        .scan(Some(&start), Some(&end), Order::Ascending)
+                        .0
+                        .unwrap();
+                    black_box(id);
+                },
+            )
+        });
+    });
+
+    group.bench_function("seek", |b| {
+        b.iter_custom(|iters| {
+            iter_with_storage(
+                iters,
+                || {
+                    let mut storage = filled_storage(0);
+                    let id = storage.scan(None, None, Order::Ascending).0.unwrap();
+                    (storage, id, 0u32)
+                },
+                |(storage, id, i)| {
+                    // jump around to avoid measuring a seek to the current position
+                    *i = (*i + 337) % ITEMS;
+                    storage.seek(*id, &key(*i)).0.unwrap();
+                },
+            )
+        });
+    });
+
+    for value_size in VALUE_SIZES {
+        group.bench_with_input(BenchmarkId::new("get", value_size), &value_size, |b, _| {
+            let storage = filled_storage(value_size);
+            let mut i = 0;
+            b.iter(|| {
+                let value = storage.get(&key(i % ITEMS)).0.unwrap();
+                i += 1;
+                black_box(value)
+            });
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("next (per item)", value_size),
+            &value_size,
+            |b, _| {
+                b.iter_custom(|iters| {
+                    iter_with_storage(
+                        iters,
+                        || {
+                            let mut storage = filled_storage(value_size);
+                            let id = storage.scan(None, None, Order::Ascending).0.unwrap();
+                            (storage, id)
+                        },
+                        |(storage, id)| {
+                            black_box(storage.next(*id).0.unwrap().unwrap());
+                        },
+                    )
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn make_config() -> Criterion {
+    Criterion::default()
+        .without_plots()
+        .measurement_time(Duration::new(5, 0))
+        .sample_size(20)
+        .configure_from_args()
+}
+
+criterion_group!(
+    name = storage;
+    config = make_config();
+    targets = bench_storage
+);
+criterion_main!(storage);
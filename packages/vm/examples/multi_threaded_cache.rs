@@ -12,9 +12,7 @@ use cosmwasm_vm::{
 // Instance
 const DEFAULT_MEMORY_LIMIT: Size = Size::mebi(64);
 const DEFAULT_GAS_LIMIT: u64 = 400_000 * 150;
-const DEFAULT_INSTANCE_OPTIONS: InstanceOptions = InstanceOptions {
-    gas_limit: DEFAULT_GAS_LIMIT,
-};
+const DEFAULT_INSTANCE_OPTIONS: InstanceOptions = InstanceOptions::new(DEFAULT_GAS_LIMIT);
 // Cache
 const MEMORY_CACHE_SIZE: Size = Size::mebi(200);
 
@@ -3,9 +3,9 @@ use std::ops::AddAssign;
 use std::string::FromUtf8Error;
 use thiserror::Error;
 
-use cosmwasm_std::{Binary, ContractResult, SystemResult};
 #[cfg(feature = "iterator")]
-use cosmwasm_std::{Order, Record};
+use cosmwasm_std::{prefix_upper_bound, Order, Record};
+use cosmwasm_std::{Binary, ContractResult, Event, SystemResult};
 
 /// A structure that represents gas cost to be deducted from the remaining gas.
 /// This is always needed when computations are performed outside of
@@ -85,6 +85,10 @@ pub struct Backend<A: BackendApi, S: Storage, Q: Querier> {
 }
 
 /// Access to the VM's backend storage, i.e. the chain
+///
+/// A storage is moved into an instance and only accessed by that instance, so implementations
+/// do not need to be `Sync`. In order to execute calls on multiple threads, create one storage
+/// (e.g. a read-only view of the state) per instance.
 pub trait Storage {
     /// Returns Err on error.
     /// Returns Ok(None) when key does not exist.
@@ -122,6 +126,34 @@ pub trait Storage {
     #[cfg(feature = "iterator")]
     fn next(&mut self, iterator_id: u32) -> BackendResult<Option<Record>>;
 
+    /// Like [`Storage::scan`] but iterates over all keys that start with `prefix`.
+    /// Returns an interator ID that is unique within the Storage instance.
+    ///
+    /// The default implementation calls [`Storage::scan`] with the range covering the prefix.
+    #[cfg(feature = "iterator")]
+    fn scan_prefix(&mut self, prefix: &[u8], order: Order) -> BackendResult<u32> {
+        let end = prefix_upper_bound(prefix);
+        self.scan(Some(prefix), end.as_deref(), order)
+    }
+
+    /// Moves the iterator with the given ID such that the next element is the first one whose
+    /// key is at or after `key` in iteration order, i.e. the first key `>= key` for ascending and
+    /// the first key `<= key` for descending iterators. Keys outside of the range the iterator was
+    /// created with are never returned. The iterator can be moved forwards and backwards.
+    ///
+    /// If the ID is not found, a BackendError::IteratorDoesNotExist is returned.
+    ///
+    /// The default implementation returns an error, i.e. the backend does not support seeking.
+    #[cfg(feature = "iterator")]
+    fn seek(&mut self, _iterator_id: u32, _key: &[u8]) -> BackendResult<()> {
+        (
+            Err(BackendError::unknown(
+                "Seeking iterators is not supported by this backend",
+            )),
+            GasInfo::free(),
+        )
+    }
+
     /// Returns the next value of the iterator with the given ID.
     /// Since the iterator is incremented, the corresponding key will never be accessible.
     ///
@@ -150,6 +182,17 @@ pub trait Storage {
         (result, gas_info)
     }
 
+    /// Releases the iterator with the given ID. Using the ID afterwards is not possible.
+    ///
+    /// The VM calls this for iterators it creates internally, e.g. in [`Storage::export`].
+    /// Iterators created by contracts live until the end of the call.
+    ///
+    /// The default implementation does nothing, i.e. the iterator is kept until the storage is dropped.
+    #[cfg(feature = "iterator")]
+    fn free_iterator(&mut self, _iterator_id: u32) -> BackendResult<()> {
+        (Ok(()), GasInfo::free())
+    }
+
     fn set(&mut self, key: &[u8], value: &[u8]) -> BackendResult<()>;
 
     /// Removes a database entry at `key`.
@@ -157,6 +200,106 @@ pub trait Storage {
     /// The current interface does not allow to differentiate between a key that existed
     /// before and one that didn't exist. See https://github.com/CosmWasm/cosmwasm/issues/290
     fn remove(&mut self, key: &[u8]) -> BackendResult<()>;
+
+    /// Returns the approximate number of keys and bytes (keys plus values) used by the contract,
+    /// or `None` if the backend does not support this.
+    ///
+    /// The result is passed to contracts, which can use it for storage rent or self-pruning logic.
+    /// It does not need to be exact, but it must be deterministic, i.e. the same on all nodes
+    /// for the same state. Backends that cannot guarantee this must return `None`.
+    ///
+    /// The default implementation returns `None`.
+    fn size_hint(&self) -> BackendResult<Option<StorageSize>> {
+        (Ok(None), GasInfo::free())
+    }
+
+    /// Streams all records in the given range into `sink` and returns the number of records exported.
+    /// The bounds work like in [`Storage::scan`].
+    ///
+    /// This is intended for tooling like genesis export or state sync, which reads the full state of
+    /// a contract outside of contract execution. If `gas_exempt` is true, no gas is charged for the
+    /// operation, i.e. the returned `cost` is 0. Gas that was metered externally is always reported.
+    ///
+    /// The default implementation uses [`Storage::scan`] and [`Storage::next`].
+    /// Backends bridging to another runtime should override this to transfer the data in bulk.
+    #[cfg(feature = "iterator")]
+    fn export(
+        &mut self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+        gas_exempt: bool,
+        sink: &mut dyn FnMut(Record),
+    ) -> BackendResult<u64> {
+        let (result, mut gas_info) = self.scan(start, end, order);
+        let iterator_id = match result {
+            Ok(id) => id,
+            Err(err) => return (Err(err), exempt_gas(gas_info, gas_exempt)),
+        };
+
+        let mut count = 0u64;
+        let result = loop {
+            let (result, info) = self.next(iterator_id);
+            gas_info += info;
+            match result {
+                Ok(Some(record)) => {
+                    sink(record);
+                    count += 1;
+                }
+                Ok(None) => break Ok(count),
+                Err(err) => break Err(err),
+            }
+        };
+
+        // The iterator is freed on all paths
+        let (free_result, info) = self.free_iterator(iterator_id);
+        gas_info += info;
+        let result = result.and_then(|count| free_result.map(|_| count));
+        (result, exempt_gas(gas_info, gas_exempt))
+    }
+
+    /// Writes all given records to the storage and returns the number of records imported.
+    /// Existing entries with the same key are overwritten.
+    ///
+    /// This is the counterpart of [`Storage::export`] and follows the same gas rules.
+    ///
+    /// The default implementation uses [`Storage::set`] for every record.
+    /// Backends bridging to another runtime should override this to transfer the data in bulk.
+    fn import(
+        &mut self,
+        records: &mut dyn Iterator<Item = (Vec<u8>, Vec<u8>)>,
+        gas_exempt: bool,
+    ) -> BackendResult<u64> {
+        let mut gas_info = GasInfo::free();
+        let mut count = 0u64;
+        for (key, value) in records {
+            let (result, info) = self.set(&key, &value);
+            gas_info += info;
+            if let Err(err) = result {
+                return (Err(err), exempt_gas(gas_info, gas_exempt));
+            }
+            count += 1;
+        }
+        (Ok(count), exempt_gas(gas_info, gas_exempt))
+    }
+}
+
+/// The storage usage of a contract as returned by [`Storage::size_hint`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageSize {
+    /// The number of keys
+    pub keys: u64,
+    /// The sum of the lengths of all keys and values
+    pub bytes: u64,
+}
+
+/// Drops the internally charged gas cost if the operation is exempt from gas.
+fn exempt_gas(gas_info: GasInfo, gas_exempt: bool) -> GasInfo {
+    if gas_exempt {
+        GasInfo::with_externally_used(gas_info.externally_used)
+    } else {
+        gas_info
+    }
 }
 
 /// Callbacks to system functions defined outside of the wasm modules.
@@ -171,8 +314,54 @@ pub trait Storage {
 pub trait BackendApi: Copy + Clone + Send {
     fn canonical_address(&self, human: &str) -> BackendResult<Vec<u8>>;
     fn human_address(&self, canonical: &[u8]) -> BackendResult<String>;
+
+    /// Returns true if contract debug messages should be passed to [`BackendApi::debug`].
+    ///
+    /// Debug messages are only read from the contract's memory if this is enabled or
+    /// a debug handler is set on the instance. The default implementation returns false.
+    fn debug_enabled(&self) -> bool {
+        false
+    }
+
+    /// Called whenever the contract calls the `debug` import and [`BackendApi::debug_enabled`]
+    /// returns true.
+    ///
+    /// This allows embedders to route contract debug logs into their own logging pipeline.
+    /// `contract_address` is the address of the executing contract if it is known to the VM,
+    /// i.e. when the call was made through one of the entry point functions in [`crate::calls`].
+    ///
+    /// The default implementation ignores the message.
+    fn debug(&self, _contract_address: Option<&str>, _message: &str) {}
+
+    /// Called whenever the contract aborts execution through the `abort` import,
+    /// e.g. because of a panic in the contract.
+    ///
+    /// The abort is reported to the caller as [`VmError::Aborted`](crate::VmError::Aborted)
+    /// in any case. This hook only makes the message available to the embedder in a structured way.
+    ///
+    /// The default implementation ignores the message.
+    fn abort(&self, _contract_address: Option<&str>, _message: &str) {}
+
+    /// Called whenever the contract emits an event through the `emit_event` import.
+    ///
+    /// This allows embedders to process events while the contract is still executing, e.g. to
+    /// stream the progress of long running batch operations. Events emitted this way are not
+    /// part of the contract's `Response`.
+    ///
+    /// The default implementation returns an error, i.e. the backend does not support this.
+    fn emit_event(&self, _contract_address: Option<&str>, _event: &Event) -> BackendResult<()> {
+        (
+            Err(BackendError::unknown(
+                "Emitting events is not supported by this backend",
+            )),
+            GasInfo::free(),
+        )
+    }
 }
 
+/// Access to the chain's query system.
+///
+/// Like [`Storage`], a querier is owned by one instance at a time and does not need to be `Sync`.
 pub trait Querier {
     /// This is all that must be implemented for the Querier.
     /// This allows us to pass through binary queries from one level to another without
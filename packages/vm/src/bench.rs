@@ -0,0 +1,295 @@
+//! Performance measurements of contracts.
+//!
+//! This provides the measurements of the VM's criterion benchmarks as a library, such that
+//! chain teams can measure compilation, instantiation and execution of their own contracts
+//! and gate performance regressions of their contract pipeline in CI.
+//!
+//! All measurements use the mock backend from [`testing`](crate::testing) and report wall clock
+//! time, so results are only comparable between runs on the same machine.
+
+use std::time::{Duration, Instant};
+
+use cosmwasm_std::{Empty, MessageInfo};
+use wasmer::{Engine, Module, Store};
+
+use crate::calls::{call_execute, call_instantiate};
+use crate::errors::{VmError, VmResult};
+use crate::instance::Instance;
+use crate::size::Size;
+use crate::testing::{mock_backend, mock_env, MockApi, MockQuerier, MockStorage};
+use crate::wasm_backend::{compile, make_compiling_engine_with_compiler, Compiler};
+
+/// Options for the measurements in this module.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct BenchOptions {
+    /// The number of measured runs. Must be at least 1.
+    pub iterations: u32,
+    /// The compiler used to compile the contract
+    pub compiler: Compiler,
+    /// The memory limit of the instances
+    pub memory_limit: Option<Size>,
+    /// The gas limit of the instances. This must be high enough for all measured calls
+    /// of [`measure_execute`], which share the same instance.
+    pub gas_limit: u64,
+}
+
+impl BenchOptions {
+    pub fn new(iterations: u32) -> Self {
+        Self {
+            iterations,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            iterations: 10,
+            compiler: Compiler::default(),
+            memory_limit: Some(Size::mebi(64)),
+            gas_limit: 20_000_000_000_000, // ~20s, allows many calls on one instance
+        }
+    }
+}
+
+/// Timing statistics of a number of runs of the same operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Measurement {
+    /// The number of measured runs
+    pub iterations: u32,
+    /// The sum of all runs
+    pub total: Duration,
+    /// The fastest run
+    pub min: Duration,
+    /// The median run
+    pub median: Duration,
+    /// The slowest run
+    pub max: Duration,
+}
+
+impl Measurement {
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort_unstable();
+        Self {
+            iterations: samples.len() as u32,
+            total: samples.iter().sum(),
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+
+    /// The average duration of a run
+    pub fn mean(&self) -> Duration {
+        self.total / self.iterations
+    }
+
+    /// The number of runs per second
+    pub fn throughput(&self) -> f64 {
+        f64::from(self.iterations) / self.total.as_secs_f64()
+    }
+}
+
+/// The result of [`measure_execute`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecuteMeasurement {
+    /// Timing of the execute calls
+    pub timing: Measurement,
+    /// Gas used by a single execute call, averaged over all runs
+    pub gas_used: u64,
+}
+
+/// Measures the time it takes to compile the given Wasm blob to a module.
+///
+/// This includes the static validation done by the compiler, but not the checks done by
+/// [`Cache::save_wasm`](crate::Cache::save_wasm) (see [`checker`](crate::checker) for those).
+pub fn measure_compile(wasm: &[u8], options: &BenchOptions) -> VmResult<Measurement> {
+    check_iterations(options)?;
+    let mut samples = Vec::with_capacity(options.iterations as usize);
+    for _ in 0..options.iterations {
+        // An engine's middlewares can only be used for a single module
+        let engine = make_compiling_engine_with_compiler(options.compiler, options.memory_limit);
+        let start = Instant::now();
+        compile(&engine, wasm)?;
+        samples.push(start.elapsed());
+    }
+    Ok(Measurement::from_samples(samples))
+}
+
+/// Measures the time it takes to create an instance from an already compiled module.
+///
+/// This is what happens for every contract call on chain when the module is found in a cache.
+/// No entry point of the contract is called.
+pub fn measure_instantiation(wasm: &[u8], options: &BenchOptions) -> VmResult<Measurement> {
+    check_iterations(options)?;
+    let (engine, module) = compile_module(wasm, options)?;
+    measure(options.iterations, || {
+        instance_from_module(&engine, &module, options).map(|_| ())
+    })
+}
+
+/// Measures the execute entry point of a contract.
+///
+/// A fresh instance is created and instantiated with `instantiate_msg`. Then `execute_msg`
+/// is executed on that instance [`BenchOptions::iterations`] times. Both calls use the given
+/// `info` and must succeed.
+pub fn measure_execute(
+    wasm: &[u8],
+    info: &MessageInfo,
+    instantiate_msg: &[u8],
+    execute_msg: &[u8],
+    options: &BenchOptions,
+) -> VmResult<ExecuteMeasurement> {
+    check_iterations(options)?;
+    let mut instance = instantiated_instance(wasm, info, instantiate_msg, options)?;
+    let env = mock_env();
+
+    let gas_before = instance.get_gas_left();
+    let timing = measure(options.iterations, || {
+        call_execute::<_, _, _, Empty>(&mut instance, &env, info, execute_msg)?
+            .into_result()
+            .map(|_| ())
+            .map_err(|err| VmError::generic_err(format!("Execute failed: {err}")))
+    })?;
+    let gas_used = gas_before.saturating_sub(instance.get_gas_left());
+
+    Ok(ExecuteMeasurement {
+        timing,
+        gas_used: gas_used / u64::from(options.iterations),
+    })
+}
+
+/// Creates an instance of the given contract using the mock backend and calls its
+/// instantiate entry point with `instantiate_msg`, which must succeed.
+///
+/// This is the starting point of [`measure_execute`] and can be used to set up custom
+/// measurements of other entry points.
+pub fn instantiated_instance(
+    wasm: &[u8],
+    info: &MessageInfo,
+    instantiate_msg: &[u8],
+    options: &BenchOptions,
+) -> VmResult<Instance<MockApi, MockStorage, MockQuerier>> {
+    let (engine, module) = compile_module(wasm, options)?;
+    let mut instance = instance_from_module(&engine, &module, options)?;
+    call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), info, instantiate_msg)?
+        .into_result()
+        .map_err(|err| VmError::generic_err(format!("Instantiate failed: {err}")))?;
+    Ok(instance)
+}
+
+fn check_iterations(options: &BenchOptions) -> VmResult<()> {
+    if options.iterations == 0 {
+        return Err(VmError::generic_err("Iterations must be at least 1"));
+    }
+    Ok(())
+}
+
+fn compile_module(wasm: &[u8], options: &BenchOptions) -> VmResult<(Engine, Module)> {
+    let engine = make_compiling_engine_with_compiler(options.compiler, options.memory_limit);
+    let module = compile(&engine, wasm)?;
+    Ok((engine, module))
+}
+
+fn instance_from_module(
+    engine: &Engine,
+    module: &Module,
+    options: &BenchOptions,
+) -> VmResult<Instance<MockApi, MockStorage, MockQuerier>> {
+    Instance::from_module(
+        Store::new(engine.clone()),
+        module,
+        mock_backend(&[]),
+        options.gas_limit,
+        None,
+        None,
+    )
+}
+
+/// Runs `run` the given number of times and collects the durations
+fn measure(iterations: u32, mut run: impl FnMut() -> VmResult<()>) -> VmResult<Measurement> {
+    let mut samples = Vec::with_capacity(iterations as usize);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        run()?;
+        samples.push(start.elapsed());
+    }
+    Ok(Measurement::from_samples(samples))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::mock_info;
+    use cosmwasm_std::coins;
+
+    static CONTRACT: &[u8] = include_bytes!("../testdata/hackatom.wasm");
+
+    #[test]
+    fn measurement_from_samples_works() {
+        let samples = [3, 1, 2, 10].map(Duration::from_millis).to_vec();
+        let measurement = Measurement::from_samples(samples);
+        assert_eq!(
+            measurement,
+            Measurement {
+                iterations: 4,
+                total: Duration::from_millis(16),
+                min: Duration::from_millis(1),
+                median: Duration::from_millis(3),
+                max: Duration::from_millis(10),
+            }
+        );
+        assert_eq!(measurement.mean(), Duration::from_millis(4));
+        assert_eq!(measurement.throughput(), 250.0);
+    }
+
+    #[test]
+    fn measure_compile_works() {
+        let measurement = measure_compile(CONTRACT, &BenchOptions::new(2)).unwrap();
+        assert_eq!(measurement.iterations, 2);
+        assert!(measurement.min <= measurement.max);
+
+        let err = measure_compile(b"not wasm", &BenchOptions::new(2)).unwrap_err();
+        assert!(matches!(err, VmError::CompileErr { .. }));
+    }
+
+    #[test]
+    fn measure_instantiation_works() {
+        let measurement = measure_instantiation(CONTRACT, &BenchOptions::new(3)).unwrap();
+        assert_eq!(measurement.iterations, 3);
+    }
+
+    #[test]
+    fn measure_execute_works() {
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let measurement = measure_execute(
+            CONTRACT,
+            &info,
+            br#"{"verifier": "creator", "beneficiary": "benefits"}"#,
+            br#"{"release":{}}"#,
+            &BenchOptions::new(3),
+        )
+        .unwrap();
+        assert_eq!(measurement.timing.iterations, 3);
+        assert!(measurement.gas_used > 0);
+
+        // contract errors are reported
+        let err = measure_execute(
+            CONTRACT,
+            &info,
+            br#"{"verifier": "someone else", "beneficiary": "benefits"}"#,
+            br#"{"release":{}}"#,
+            &BenchOptions::new(1),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Execute failed"), "{err}");
+    }
+
+    #[test]
+    fn zero_iterations_are_rejected() {
+        let err = measure_compile(CONTRACT, &BenchOptions::new(0)).unwrap_err();
+        assert!(err.to_string().contains("Iterations must be at least 1"));
+    }
+}
@@ -0,0 +1,684 @@
+//! An adapter for storages and queriers that are implemented asynchronously.
+//!
+//! The VM calls into the backend synchronously from the thread executing the contract.
+//! Embedders whose state is only accessible through futures (e.g. RPC-backed forks of a
+//! chain for simulation tools) implement [`AsyncStorage`] and [`AsyncQuerier`] and convert
+//! them into a regular [`Storage`] and [`Querier`] using a [`BlockingBackend`].
+//!
+//! The futures are executed by a [`FutureSpawner`], e.g. a tokio runtime, while the thread
+//! executing the contract waits for the result up to a timeout:
+//!
+//! ```ignore
+//! let handle = tokio::runtime::Handle::current();
+//! let bridge = BlockingBackend::new(
+//!     move |future| {
+//!         handle.spawn(future);
+//!     },
+//!     Duration::from_secs(10),
+//! );
+//! let backend = Backend {
+//!     api: MockApi::default(),
+//!     storage: bridge.storage(rpc_storage),
+//!     querier: bridge.querier(rpc_querier),
+//! };
+//! ```
+//!
+//! Contracts must be executed on a thread that does not drive the spawned futures
+//! (e.g. via `tokio::task::spawn_blocking`). Otherwise every backend call runs into
+//! the timeout.
+#[cfg(feature = "iterator")]
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::num::NonZeroUsize;
+use std::pin::Pin;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Wake, Waker};
+use std::thread;
+use std::time::Duration;
+
+use cosmwasm_std::{Binary, ContractResult, SystemResult};
+#[cfg(feature = "iterator")]
+use cosmwasm_std::{Order, Record};
+
+use crate::{BackendError, BackendResult, GasInfo, Querier, Storage};
+
+/// A future that can be moved to the thread executing it
+pub type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send + 'static>>;
+
+/// Executes futures in the background, e.g. by spawning them on an async runtime.
+///
+/// This is implemented for all `Fn(BoxFuture<()>)` closures.
+pub trait FutureSpawner: Send + Sync {
+    fn spawn(&self, future: BoxFuture<()>);
+}
+
+impl<F> FutureSpawner for F
+where
+    F: Fn(BoxFuture<()>) + Send + Sync,
+{
+    fn spawn(&self, future: BoxFuture<()>) {
+        self(future)
+    }
+}
+
+/// Executes futures on a fixed number of worker threads.
+///
+/// This works for futures that do not depend on a specific async runtime.
+/// Futures that e.g. need a tokio reactor must be spawned on that runtime instead.
+/// A pending future does not occupy a worker. It is polled again once it is woken and
+/// dropped if its waker is dropped without waking it.
+/// The workers stop when the spawner and all its clones are dropped and no futures are
+/// pending anymore.
+#[derive(Clone)]
+pub struct ThreadSpawner {
+    // `Sender` is only `Sync` since Rust 1.72
+    queue: Arc<Mutex<Sender<Arc<Task>>>>,
+}
+
+impl ThreadSpawner {
+    /// Starts a spawner with the given number of worker threads
+    pub fn new(threads: NonZeroUsize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Arc<Task>>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for _ in 0..threads.get() {
+            let receiver = Arc::clone(&receiver);
+            thread::spawn(move || run_worker(&receiver));
+        }
+        Self {
+            queue: Arc::new(Mutex::new(sender)),
+        }
+    }
+}
+
+impl Default for ThreadSpawner {
+    /// Starts a spawner with one worker thread per available CPU
+    fn default() -> Self {
+        Self::new(thread::available_parallelism().unwrap_or(NonZeroUsize::MIN))
+    }
+}
+
+impl FutureSpawner for ThreadSpawner {
+    fn spawn(&self, future: BoxFuture<()>) {
+        let queue = self.queue.lock().unwrap();
+        let task = Task {
+            future: Mutex::new(Some(future)),
+            queue: Mutex::new(queue.clone()),
+        };
+        // The workers only stop once all senders are gone, so this cannot fail
+        let _ = queue.send(Arc::new(task));
+    }
+}
+
+/// A future spawned on a [`ThreadSpawner`]
+struct Task {
+    /// `None` once the future finished
+    future: Mutex<Option<BoxFuture<()>>>,
+    queue: Mutex<Sender<Arc<Task>>>,
+}
+
+impl Wake for Task {
+    fn wake(self: Arc<Self>) {
+        let queue = self.queue.lock().unwrap().clone();
+        let _ = queue.send(self);
+    }
+}
+
+/// Polls the queued tasks until all senders are dropped
+fn run_worker(receiver: &Mutex<Receiver<Arc<Task>>>) {
+    loop {
+        let task = match receiver.lock().unwrap().recv() {
+            Ok(task) => task,
+            Err(_) => return,
+        };
+        let mut slot = task.future.lock().unwrap();
+        if let Some(mut future) = slot.take() {
+            let waker = Waker::from(Arc::clone(&task));
+            let mut context = Context::from_waker(&waker);
+            if future.as_mut().poll(&mut context).is_pending() {
+                *slot = Some(future);
+            }
+        }
+    }
+}
+
+/// The asynchronous counterpart of [`Storage`].
+///
+/// The returned futures must not borrow from `self`, so implementations usually keep
+/// their state in an `Arc` and clone it into the futures. In contrast to [`Storage`],
+/// ranges are returned in pages and iterated by the VM.
+pub trait AsyncStorage: Send + Sync + 'static {
+    fn get(&self, key: Vec<u8>) -> BoxFuture<BackendResult<Option<Vec<u8>>>>;
+
+    /// Returns the first `limit` records of the range in iteration order, i.e. the ones
+    /// with the highest keys for [`Order::Descending`]. Fewer records must only be returned
+    /// if the range does not contain more.
+    /// The bound `start` is inclusive and `end` is exclusive.
+    #[cfg(feature = "iterator")]
+    fn range(
+        &self,
+        start: Option<Vec<u8>>,
+        end: Option<Vec<u8>>,
+        order: Order,
+        limit: usize,
+    ) -> BoxFuture<BackendResult<Vec<Record>>>;
+
+    fn set(&self, key: Vec<u8>, value: Vec<u8>) -> BoxFuture<BackendResult<()>>;
+
+    fn remove(&self, key: Vec<u8>) -> BoxFuture<BackendResult<()>>;
+}
+
+/// The asynchronous counterpart of [`Querier`]
+pub trait AsyncQuerier: Send + Sync + 'static {
+    fn query_raw(
+        &self,
+        request: Vec<u8>,
+        gas_limit: u64,
+    ) -> BoxFuture<BackendResult<SystemResult<ContractResult<Binary>>>>;
+}
+
+/// Bridges [`AsyncStorage`] and [`AsyncQuerier`] implementations to the synchronous
+/// backend traits by executing their futures with a [`FutureSpawner`] and waiting for
+/// the results.
+///
+/// If a future does not finish within the timeout, the backend call fails with
+/// [`BackendError::Unknown`]. The future itself is not cancelled.
+#[derive(Clone)]
+pub struct BlockingBackend {
+    spawner: Arc<dyn FutureSpawner>,
+    timeout: Duration,
+    #[cfg(feature = "iterator")]
+    page_size: usize,
+}
+
+impl BlockingBackend {
+    /// The number of records loaded per [`AsyncStorage::range`] call by default
+    #[cfg(feature = "iterator")]
+    pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+    pub fn new(spawner: impl FutureSpawner + 'static, timeout: Duration) -> Self {
+        Self {
+            spawner: Arc::new(spawner),
+            timeout,
+            #[cfg(feature = "iterator")]
+            page_size: Self::DEFAULT_PAGE_SIZE,
+        }
+    }
+
+    /// Sets the number of records loaded per [`AsyncStorage::range`] call when iterating
+    #[cfg(feature = "iterator")]
+    pub fn with_page_size(mut self, page_size: NonZeroUsize) -> Self {
+        self.page_size = page_size.get();
+        self
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn storage<S: AsyncStorage>(&self, storage: S) -> BlockingStorage<S> {
+        BlockingStorage {
+            bridge: self.clone(),
+            inner: storage,
+            #[cfg(feature = "iterator")]
+            iterators: HashMap::new(),
+            #[cfg(feature = "iterator")]
+            last_iterator_id: 0,
+        }
+    }
+
+    pub fn querier<Q: AsyncQuerier>(&self, querier: Q) -> BlockingQuerier<Q> {
+        BlockingQuerier {
+            bridge: self.clone(),
+            inner: querier,
+        }
+    }
+
+    /// Executes the future and waits for its result. `operation` is used in error messages.
+    pub fn block_on<T: Send + 'static>(
+        &self,
+        operation: &str,
+        future: BoxFuture<BackendResult<T>>,
+    ) -> BackendResult<T> {
+        let (sender, receiver) = mpsc::sync_channel(1);
+        self.spawner.spawn(Box::pin(async move {
+            // The receiver is gone if the call timed out already
+            let _ = sender.send(future.await);
+        }));
+        match receiver.recv_timeout(self.timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => (
+                Err(BackendError::unknown(format!(
+                    "Async backend call {operation} timed out after {} ms",
+                    self.timeout.as_millis()
+                ))),
+                GasInfo::free(),
+            ),
+            Err(RecvTimeoutError::Disconnected) => (
+                Err(BackendError::unknown(format!(
+                    "Async backend call {operation} was dropped before it finished"
+                ))),
+                GasInfo::free(),
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "iterator")]
+struct Iter {
+    /// The range the iterator was created for
+    start: Option<Vec<u8>>,
+    end: Option<Vec<u8>>,
+    order: Order,
+    /// The part of the range that was not loaded yet
+    remaining_start: Option<Vec<u8>>,
+    remaining_end: Option<Vec<u8>>,
+    /// The loaded records that were not returned yet, in iteration order
+    buffer: VecDeque<Record>,
+    /// True if the remaining part of the range is known to be empty
+    exhausted: bool,
+}
+
+#[cfg(feature = "iterator")]
+impl Iter {
+    fn new(start: Option<&[u8]>, end: Option<&[u8]>, order: Order) -> Self {
+        let start = start.map(<[u8]>::to_vec);
+        let end = end.map(<[u8]>::to_vec);
+        Iter {
+            remaining_start: start.clone(),
+            remaining_end: end.clone(),
+            start,
+            end,
+            order,
+            buffer: VecDeque::new(),
+            exhausted: false,
+        }
+    }
+
+    /// Adds a page loaded for the remaining range and shrinks the remaining range accordingly
+    fn load(&mut self, records: Vec<Record>, limit: usize) {
+        if records.len() < limit {
+            self.exhausted = true;
+        }
+        if let Some((last_key, _)) = records.last() {
+            match self.order {
+                Order::Ascending => self.remaining_start = Some(key_after(last_key)),
+                Order::Descending => self.remaining_end = Some(last_key.clone()),
+            }
+        }
+        self.buffer.extend(records);
+    }
+
+    /// Moves the iterator to the first key that is not before `key` in iteration order
+    fn seek(&mut self, key: &[u8]) {
+        self.buffer.clear();
+        self.exhausted = false;
+        match self.order {
+            Order::Ascending => {
+                self.remaining_start = Some(match &self.start {
+                    Some(start) if start.as_slice() > key => start.clone(),
+                    _ => key.to_vec(),
+                });
+                self.remaining_end = self.end.clone();
+            }
+            Order::Descending => {
+                self.remaining_start = self.start.clone();
+                // the end is exclusive, so the smallest key after `key` includes it
+                let key_end = key_after(key);
+                self.remaining_end = Some(match &self.end {
+                    Some(end) if *end < key_end => end.clone(),
+                    _ => key_end,
+                });
+            }
+        }
+    }
+}
+
+/// Returns the smallest key that is greater than `key`
+#[cfg(feature = "iterator")]
+fn key_after(key: &[u8]) -> Vec<u8> {
+    [key, &[0]].concat()
+}
+
+/// A [`Storage`] backed by an [`AsyncStorage`], created by [`BlockingBackend::storage`]
+pub struct BlockingStorage<S> {
+    bridge: BlockingBackend,
+    inner: S,
+    #[cfg(feature = "iterator")]
+    iterators: HashMap<u32, Iter>,
+    /// The ID of the last iterator created. IDs are not reused after freeing an iterator.
+    #[cfg(feature = "iterator")]
+    last_iterator_id: u32,
+}
+
+impl<S: AsyncStorage> Storage for BlockingStorage<S> {
+    fn get(&self, key: &[u8]) -> BackendResult<Option<Vec<u8>>> {
+        self.bridge.block_on("get", self.inner.get(key.to_vec()))
+    }
+
+    #[cfg(feature = "iterator")]
+    fn scan(
+        &mut self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> BackendResult<u32> {
+        // The records are loaded in pages by `next`
+        // IDs are assigned sequentially, starting at 1
+        let id = self
+            .last_iterator_id
+            .checked_add(1)
+            .expect("Found more iterator IDs than supported");
+        self.last_iterator_id = id;
+        self.iterators.insert(id, Iter::new(start, end, order));
+        (Ok(id), GasInfo::free())
+    }
+
+    #[cfg(feature = "iterator")]
+    fn next(&mut self, iterator_id: u32) -> BackendResult<Option<Record>> {
+        let Some(iterator) = self.iterators.get_mut(&iterator_id) else {
+            return (
+                Err(BackendError::iterator_does_not_exist(iterator_id)),
+                GasInfo::free(),
+            );
+        };
+        // Only loading a page costs gas
+        let mut gas_info = GasInfo::free();
+        if iterator.buffer.is_empty() && !iterator.exhausted {
+            let page_size = self.bridge.page_size;
+            let future = self.inner.range(
+                iterator.remaining_start.clone(),
+                iterator.remaining_end.clone(),
+                iterator.order,
+                page_size,
+            );
+            let (result, page_gas_info) = self.bridge.block_on("range", future);
+            gas_info = page_gas_info;
+            match result {
+                Ok(records) => iterator.load(records, page_size),
+                Err(err) => return (Err(err), gas_info),
+            }
+        }
+        (Ok(iterator.buffer.pop_front()), gas_info)
+    }
+
+    #[cfg(feature = "iterator")]
+    fn free_iterator(&mut self, iterator_id: u32) -> BackendResult<()> {
+        match self.iterators.remove(&iterator_id) {
+            Some(_) => (Ok(()), GasInfo::free()),
+            None => (
+                Err(BackendError::iterator_does_not_exist(iterator_id)),
+                GasInfo::free(),
+            ),
+        }
+    }
+
+    #[cfg(feature = "iterator")]
+    fn seek(&mut self, iterator_id: u32, key: &[u8]) -> BackendResult<()> {
+        let Some(iterator) = self.iterators.get_mut(&iterator_id) else {
+            return (
+                Err(BackendError::iterator_does_not_exist(iterator_id)),
+                GasInfo::free(),
+            );
+        };
+        iterator.seek(key);
+        (Ok(()), GasInfo::free())
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) -> BackendResult<()> {
+        self.bridge
+            .block_on("set", self.inner.set(key.to_vec(), value.to_vec()))
+    }
+
+    fn remove(&mut self, key: &[u8]) -> BackendResult<()> {
+        self.bridge
+            .block_on("remove", self.inner.remove(key.to_vec()))
+    }
+}
+
+/// A [`Querier`] backed by an [`AsyncQuerier`], created by [`BlockingBackend::querier`]
+pub struct BlockingQuerier<Q> {
+    bridge: BlockingBackend,
+    inner: Q,
+}
+
+impl<Q: AsyncQuerier> Querier for BlockingQuerier<Q> {
+    fn query_raw(
+        &self,
+        request: &[u8],
+        gas_limit: u64,
+    ) -> BackendResult<SystemResult<ContractResult<Binary>>> {
+        let future = self.inner.query_raw(request.to_vec(), gas_limit);
+        self.bridge.block_on("query_raw", future)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::task::Poll;
+
+    /// An async storage keeping the data in memory
+    #[derive(Clone, Default)]
+    struct MemoryStorage {
+        data: Arc<Mutex<BTreeMap<Vec<u8>, Vec<u8>>>>,
+        range_calls: Arc<AtomicUsize>,
+    }
+
+    impl AsyncStorage for MemoryStorage {
+        fn get(&self, key: Vec<u8>) -> BoxFuture<BackendResult<Option<Vec<u8>>>> {
+            let data = Arc::clone(&self.data);
+            Box::pin(async move {
+                let value = data.lock().unwrap().get(&key).cloned();
+                (Ok(value), GasInfo::with_externally_used(5))
+            })
+        }
+
+        #[cfg(feature = "iterator")]
+        fn range(
+            &self,
+            start: Option<Vec<u8>>,
+            end: Option<Vec<u8>>,
+            order: Order,
+            limit: usize,
+        ) -> BoxFuture<BackendResult<Vec<Record>>> {
+            let data = Arc::clone(&self.data);
+            self.range_calls.fetch_add(1, Ordering::Relaxed);
+            Box::pin(async move {
+                let data = data.lock().unwrap();
+                let in_range = data
+                    .iter()
+                    .filter(|(k, _)| start.as_ref().map_or(true, |start| *k >= start))
+                    .filter(|(k, _)| end.as_ref().map_or(true, |end| *k < end))
+                    .map(|(k, v)| (k.clone(), v.clone()));
+                let records: Vec<Record> = match order {
+                    Order::Ascending => in_range.take(limit).collect(),
+                    Order::Descending => in_range.rev().take(limit).collect(),
+                };
+                (Ok(records), GasInfo::with_externally_used(11))
+            })
+        }
+
+        fn set(&self, key: Vec<u8>, value: Vec<u8>) -> BoxFuture<BackendResult<()>> {
+            let data = Arc::clone(&self.data);
+            Box::pin(async move {
+                data.lock().unwrap().insert(key, value);
+                (Ok(()), GasInfo::with_externally_used(7))
+            })
+        }
+
+        fn remove(&self, key: Vec<u8>) -> BoxFuture<BackendResult<()>> {
+            let data = Arc::clone(&self.data);
+            Box::pin(async move {
+                data.lock().unwrap().remove(&key);
+                (Ok(()), GasInfo::free())
+            })
+        }
+    }
+
+    /// A querier whose futures never finish. Like a future waiting for I/O, they keep
+    /// their wakers registered.
+    #[derive(Default)]
+    struct PendingQuerier {
+        wakers: Arc<Mutex<Vec<Waker>>>,
+    }
+
+    impl AsyncQuerier for PendingQuerier {
+        fn query_raw(
+            &self,
+            _request: Vec<u8>,
+            _gas_limit: u64,
+        ) -> BoxFuture<BackendResult<SystemResult<ContractResult<Binary>>>> {
+            let wakers = Arc::clone(&self.wakers);
+            Box::pin(std::future::poll_fn(move |context| {
+                wakers.lock().unwrap().push(context.waker().clone());
+                Poll::Pending
+            }))
+        }
+    }
+
+    #[test]
+    fn blocking_storage_works() {
+        let bridge = BlockingBackend::new(ThreadSpawner::default(), Duration::from_secs(5));
+        let mut storage = bridge.storage(MemoryStorage::default());
+
+        let (result, gas_info) = storage.set(b"foo", b"bar");
+        result.unwrap();
+        assert_eq!(gas_info, GasInfo::with_externally_used(7));
+
+        let (result, gas_info) = storage.get(b"foo");
+        assert_eq!(result.unwrap(), Some(b"bar".to_vec()));
+        assert_eq!(gas_info, GasInfo::with_externally_used(5));
+
+        storage.remove(b"foo").0.unwrap();
+        assert_eq!(storage.get(b"foo").0.unwrap(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn blocking_storage_iterators_work() {
+        let bridge = BlockingBackend::new(ThreadSpawner::default(), Duration::from_secs(5));
+        let mut storage = bridge.storage(MemoryStorage::default());
+        for key in [b"a", b"b", b"c"] {
+            storage.set(key, b"value").0.unwrap();
+        }
+
+        let (result, gas_info) = storage.scan(Some(b"b"), None, Order::Ascending);
+        let id = result.unwrap();
+        assert_eq!(id, 1);
+        assert_eq!(gas_info, GasInfo::free());
+        let (result, gas_info) = storage.next_key(id);
+        assert_eq!(result.unwrap(), Some(b"b".to_vec()));
+        assert_eq!(gas_info, GasInfo::with_externally_used(11));
+        assert_eq!(storage.next_key(id).0.unwrap(), Some(b"c".to_vec()));
+        assert_eq!(storage.next_key(id).0.unwrap(), None);
+
+        let id = storage.scan(None, None, Order::Descending).0.unwrap();
+        assert_eq!(id, 2);
+        storage.seek(id, b"b").0.unwrap();
+        assert_eq!(storage.next_key(id).0.unwrap(), Some(b"b".to_vec()));
+        assert_eq!(storage.next_key(id).0.unwrap(), Some(b"a".to_vec()));
+
+        match storage.next(42).0.unwrap_err() {
+            BackendError::IteratorDoesNotExist { id } => assert_eq!(id, 42),
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn blocking_storage_loads_ranges_in_pages() {
+        let bridge = BlockingBackend::new(ThreadSpawner::default(), Duration::from_secs(5))
+            .with_page_size(NonZeroUsize::new(2).unwrap());
+        let inner = MemoryStorage::default();
+        let mut storage = bridge.storage(inner.clone());
+        for key in [b"a", b"b", b"c", b"d", b"e"] {
+            storage.set(key, b"value").0.unwrap();
+        }
+        let collect = |storage: &mut BlockingStorage<MemoryStorage>, id| {
+            let mut keys = vec![];
+            while let Some(key) = storage.next_key(id).0.unwrap() {
+                keys.push(key);
+            }
+            keys
+        };
+
+        // nothing is loaded before the first record is requested
+        let id = storage.scan(Some(b"b"), None, Order::Ascending).0.unwrap();
+        assert_eq!(inner.range_calls.load(Ordering::Relaxed), 0);
+        assert_eq!(storage.next_key(id).0.unwrap(), Some(b"b".to_vec()));
+        assert_eq!(inner.range_calls.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            collect(&mut storage, id),
+            [b"c", b"d", b"e"].map(|key| key.to_vec())
+        );
+        assert_eq!(inner.range_calls.load(Ordering::Relaxed), 3);
+
+        let id = storage.scan(None, Some(b"e"), Order::Descending).0.unwrap();
+        assert_eq!(
+            collect(&mut storage, id),
+            [b"d", b"c", b"b", b"a"].map(|key| key.to_vec())
+        );
+
+        // seeking stays within the range
+        let id = storage
+            .scan(Some(b"b"), Some(b"d"), Order::Ascending)
+            .0
+            .unwrap();
+        storage.next_key(id).0.unwrap();
+        storage.seek(id, b"a").0.unwrap();
+        assert_eq!(
+            collect(&mut storage, id),
+            [b"b", b"c"].map(|key| key.to_vec())
+        );
+        let id = storage
+            .scan(Some(b"b"), Some(b"d"), Order::Descending)
+            .0
+            .unwrap();
+        storage.seek(id, b"z").0.unwrap();
+        assert_eq!(
+            collect(&mut storage, id),
+            [b"c", b"b"].map(|key| key.to_vec())
+        );
+        storage.seek(id, b"bb").0.unwrap();
+        assert_eq!(collect(&mut storage, id), [b"b".to_vec()]);
+    }
+
+    #[test]
+    fn thread_spawner_does_not_block_workers_with_pending_futures() {
+        let spawner = ThreadSpawner::new(NonZeroUsize::MIN);
+        spawner.spawn(Box::pin(std::future::pending()));
+        let bridge = BlockingBackend::new(spawner, Duration::from_secs(5));
+        let (result, _) = bridge.block_on("get", Box::pin(async { (Ok(42), GasInfo::free()) }));
+        assert_eq!(result.unwrap(), 42);
+    }
+
+    #[test]
+    fn blocking_querier_times_out() {
+        let bridge = BlockingBackend::new(ThreadSpawner::default(), Duration::from_millis(20));
+        let querier = bridge.querier(PendingQuerier::default());
+        let (result, gas_info) = querier.query_raw(b"{}", 1000);
+        match result.unwrap_err() {
+            BackendError::Unknown { msg } => {
+                assert_eq!(msg, "Async backend call query_raw timed out after 20 ms")
+            }
+            e => panic!("Unexpected error: {e:?}"),
+        }
+        assert_eq!(gas_info, GasInfo::free());
+    }
+
+    #[test]
+    fn block_on_fails_for_dropped_future() {
+        // a spawner that drops all futures, e.g. because the runtime shut down
+        let bridge = BlockingBackend::new(|_future: BoxFuture<()>| {}, Duration::from_secs(5));
+        let (result, _) = bridge.block_on("get", Box::pin(async { (Ok(()), GasInfo::free()) }));
+        match result.unwrap_err() {
+            BackendError::Unknown { msg } => {
+                assert_eq!(msg, "Async backend call get was dropped before it finished")
+            }
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+}
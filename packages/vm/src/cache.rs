@@ -1,25 +1,38 @@
+use serde::de::DeserializeOwned;
 use std::collections::{BTreeSet, HashSet};
 use std::fs::{self, File, OpenOptions};
 use std::io::{Read, Write};
 use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
-use std::str::FromStr;
-use std::sync::Mutex;
-use wasmer::{Engine, Store};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use wasmer::{Engine, Module, Store};
 
-use cosmwasm_std::Checksum;
+use cosmwasm_std::{Checksum, CustomMsg, Env, MessageInfo};
 
 use crate::backend::{Backend, BackendApi, Querier, Storage};
-use crate::capabilities::required_capabilities_from_module;
-use crate::compatibility::check_wasm;
+use crate::calls::{call_execute, call_query};
+use crate::capabilities::Capability;
+use crate::compatibility::{check_parsed_wasm, WasmFeatures};
+use crate::concurrency_limit::ConcurrencyLimiter;
 use crate::errors::{VmError, VmResult};
 use crate::filesystem::mkdir_p;
+use crate::fingerprint::ModuleFingerprint;
 use crate::instance::{Instance, InstanceOptions};
-use crate::modules::{CachedModule, FileSystemCache, InMemoryCache, PinnedMemoryCache};
+use crate::modules::{
+    read_pinned_snapshot, write_pinned_snapshot, CachedModule, ContractMetadata, FileSystemCache,
+    InMemoryCache, MetadataCache, PinnedMemoryCache, PinnedSnapshotEntry, WasmMetadata,
+};
 use crate::parsed_wasm::ParsedWasm;
+use crate::pin_file::{modified, read_pin_file, PinFile, PinFileReport, PinFileWatcher};
+use crate::query_cache::{MemoizedQuery, QueryCache};
+use crate::serde::to_vec;
+use crate::simulation::{Simulation, StorageOverlay};
 use crate::size::Size;
-use crate::static_analysis::{Entrypoint, ExportInfo, REQUIRED_IBC_EXPORTS};
-use crate::wasm_backend::{compile, make_compiling_engine, make_runtime_engine};
+use crate::static_analysis::Entrypoint;
+use crate::wasm_backend::{
+    compile, make_compiling_engine_with_compiler, make_runtime_engine, Compiler,
+};
 
 const STATE_DIR: &str = "state";
 // Things related to the state of the blockchain.
@@ -28,6 +41,7 @@ const WASM_DIR: &str = "wasm";
 const CACHE_DIR: &str = "cache";
 // Cacheable things.
 const MODULES_DIR: &str = "modules";
+const METADATA_DIR: &str = "metadata";
 
 /// Statistics about the usage of a cache instance. Those values are node
 /// specific and must not be used in a consensus critical context.
@@ -51,6 +65,8 @@ pub struct Metrics {
     pub elements_memory_cache: usize,
     pub size_pinned_memory_cache: usize,
     pub size_memory_cache: usize,
+    pub elements_query_cache: usize,
+    pub size_query_cache: usize,
 }
 
 #[derive(Clone, Debug)]
@@ -61,25 +77,47 @@ pub struct CacheOptions {
     /// If this does not exist, it will be created. Not sure if this behaviour
     /// is desired but wasmd relies on it.
     pub base_dir: PathBuf,
-    pub available_capabilities: HashSet<String>,
+    pub available_capabilities: HashSet<Capability>,
     pub memory_cache_size: Size,
     /// Memory limit for instances, in bytes. Use a value that is divisible by the Wasm page size 65536,
     /// e.g. full MiBs.
     pub instance_memory_limit: Size,
+    /// The maximum number of instances of the same contract (checksum) that can exist at
+    /// the same time. If the limit is reached, [`Cache::get_instance`] returns
+    /// [`VmError::TooManyInstances`] instead of waiting for an instance to be dropped.
+    /// Instances kept in an [`InstancePool`](crate::InstancePool) count towards the limit.
+    ///
+    /// `None` (the default) means there is no limit.
+    pub max_concurrent_instances: Option<u32>,
+    /// The compiler used to compile Wasm to modules. Modules of different compilers are
+    /// stored in separate directories, so the same base directory can be used with both.
+    ///
+    /// Defaults to [`Compiler::default`], which is Singlepass unless the `cranelift`
+    /// feature is enabled. Validators should use Singlepass since it compiles in linear time.
+    /// Nodes that only serve queries can use Cranelift for faster execution.
+    pub compiler: Compiler,
+    /// Size of the in-memory cache of smart query results used by [`Cache::query_memoized`],
+    /// in bytes.
+    ///
+    /// Defaults to 0, which disables memoization.
+    pub query_cache_size: Size,
 }
 
 impl CacheOptions {
     pub fn new(
         base_dir: impl Into<PathBuf>,
-        available_capabilities: impl Into<HashSet<String>>,
+        available_capabilities: impl IntoIterator<Item = impl Into<Capability>>,
         memory_cache_size: Size,
         instance_memory_limit: Size,
     ) -> Self {
         Self {
             base_dir: base_dir.into(),
-            available_capabilities: available_capabilities.into(),
+            available_capabilities: available_capabilities.into_iter().map(Into::into).collect(),
             memory_cache_size,
             instance_memory_limit,
+            max_concurrent_instances: None,
+            compiler: Compiler::default(),
+            query_cache_size: Size::new(0),
         }
     }
 }
@@ -88,8 +126,14 @@ pub struct CacheInner {
     /// The directory in which the Wasm blobs are stored in the file system.
     wasm_path: PathBuf,
     pinned_memory_cache: PinnedMemoryCache,
+    /// Checksums pinned via [`Cache::pin`] or [`Cache::load_pinned`]. Modules pinned because
+    /// of the pin file are tracked in [`PinFile::pinned`]. A module stays pinned as long as
+    /// one of them holds it.
+    pinned_explicitly: HashSet<Checksum>,
     memory_cache: InMemoryCache,
     fs_cache: FileSystemCache,
+    /// Static information about the stored Wasm blobs to avoid parsing them again
+    metadata_cache: MetadataCache,
     stats: Stats,
     /// A single engine to execute all contracts in this cache instance (usually
     /// this means all contracts in the process).
@@ -104,10 +148,19 @@ pub struct CacheInner {
     runtime_engine: Engine,
 }
 
+/// A cache of Wasm blobs and compiled modules.
+///
+/// The cache is `Send + Sync` and can be shared between threads (e.g. via an `Arc`).
+/// Each thread then creates its own instances via [`Cache::get_instance`]. This allows
+/// executing read-only calls such as queries in parallel. Instances themselves are `Send`
+/// but not `Sync`, i.e. they can be moved to another thread but must not be used by
+/// multiple threads at the same time.
 pub struct Cache<A: BackendApi, S: Storage, Q: Querier> {
     /// Available capabilities are immutable for the lifetime of the cache,
     /// i.e. any number of read-only references is allowed to access it concurrently.
-    available_capabilities: HashSet<String>,
+    available_capabilities: HashSet<Capability>,
+    /// The compiler used for all compilations of this cache
+    compiler: Compiler,
     inner: Mutex<CacheInner>,
     // Those two don't store data but only fix type information
     type_api: PhantomData<A>,
@@ -115,6 +168,12 @@ pub struct Cache<A: BackendApi, S: Storage, Q: Querier> {
     type_querier: PhantomData<Q>,
     /// To prevent concurrent access to `WasmerInstance::new`
     instantiation_lock: Mutex<()>,
+    /// Limits the number of instances per checksum if configured
+    concurrency_limiter: Option<Arc<ConcurrencyLimiter>>,
+    /// Memoized smart query results. This uses its own lock to not block module loading.
+    query_cache: Mutex<QueryCache>,
+    /// The pin file applied via [`Cache::pin_from_file`], if any
+    pin_file: Mutex<Option<PinFile>>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -126,6 +185,9 @@ pub struct AnalysisReport {
     pub entrypoints: BTreeSet<Entrypoint>,
     /// The set of capabilities the contract requires.
     pub required_capabilities: BTreeSet<String>,
+    /// The interface version the contract was compiled for, as specified by its
+    /// `interface_version_*` marker export. `None` if there is no or more than one marker export.
+    pub interface_version: Option<u32>,
 }
 
 impl<A, S, Q> Cache<A, S, Q>
@@ -147,6 +209,9 @@ where
             available_capabilities,
             memory_cache_size,
             instance_memory_limit,
+            max_concurrent_instances,
+            compiler,
+            query_cache_size,
         } = options;
 
         let state_path = base_dir.join(STATE_DIR);
@@ -159,15 +224,18 @@ where
         mkdir_p(&cache_path).map_err(|_e| VmError::cache_err("Error creating cache directory"))?;
         mkdir_p(&wasm_path).map_err(|_e| VmError::cache_err("Error creating wasm directory"))?;
 
-        let fs_cache = FileSystemCache::new(cache_path.join(MODULES_DIR), false)
+        let fs_cache = FileSystemCache::new(cache_path.join(MODULES_DIR), false, compiler)
             .map_err(|e| VmError::cache_err(format!("Error file system cache: {e}")))?;
         Ok(Cache {
             available_capabilities,
+            compiler,
             inner: Mutex::new(CacheInner {
                 wasm_path,
                 pinned_memory_cache: PinnedMemoryCache::new(),
+                pinned_explicitly: HashSet::new(),
                 memory_cache: InMemoryCache::new(memory_cache_size),
                 fs_cache,
+                metadata_cache: MetadataCache::new(cache_path.join(METADATA_DIR)),
                 stats: Stats::default(),
                 runtime_engine: make_runtime_engine(Some(instance_memory_limit)),
             }),
@@ -175,6 +243,10 @@ where
             type_api: PhantomData::<A>,
             type_querier: PhantomData::<Q>,
             instantiation_lock: Mutex::new(()),
+            concurrency_limiter: max_concurrent_instances
+                .map(|limit| Arc::new(ConcurrencyLimiter::new(limit))),
+            query_cache: Mutex::new(QueryCache::new(query_cache_size)),
+            pin_file: Mutex::new(None),
         })
     }
 
@@ -188,18 +260,30 @@ where
             .set_module_unchecked(unchecked);
     }
 
+    /// Rebuilds the in-memory index of compiled modules in the file system cache.
+    ///
+    /// The index is kept up to date by the cache itself and modules missing from it are
+    /// looked up on disk. Refreshing it is only useful to drop modules that were removed
+    /// by other means, e.g. by another process.
+    pub fn refresh_index(&self) -> VmResult<()> {
+        self.inner.lock().unwrap().fs_cache.refresh_index()
+    }
+
     pub fn stats(&self) -> Stats {
         self.inner.lock().unwrap().stats
     }
 
     pub fn metrics(&self) -> Metrics {
         let cache = self.inner.lock().unwrap();
+        let query_cache = self.query_cache.lock().unwrap();
         Metrics {
             stats: cache.stats,
             elements_pinned_memory_cache: cache.pinned_memory_cache.len(),
             elements_memory_cache: cache.memory_cache.len(),
             size_pinned_memory_cache: cache.pinned_memory_cache.size(),
             size_memory_cache: cache.memory_cache.size(),
+            elements_query_cache: query_cache.len(),
+            size_query_cache: query_cache.size(),
         }
     }
 
@@ -211,8 +295,13 @@ where
     /// This does the same as [`save_wasm_unchecked`] plus the static checks.
     /// When a Wasm blob is stored the first time, use this function.
     pub fn save_wasm(&self, wasm: &[u8]) -> VmResult<Checksum> {
-        check_wasm(wasm, &self.available_capabilities)?;
-        self.save_wasm_unchecked(wasm)
+        let module = ParsedWasm::parse(wasm)?;
+        check_parsed_wasm(
+            &module,
+            &self.available_capabilities,
+            &WasmFeatures::default(),
+        )?;
+        self.save_wasm_parsed(wasm, Some(&module))
     }
 
     /// Takes a Wasm bytecode and stores it to the cache.
@@ -224,17 +313,43 @@ where
     /// When a Wasm blob is stored which was previously checked (e.g. as part of state sync),
     /// use this function.
     pub fn save_wasm_unchecked(&self, wasm: &[u8]) -> VmResult<Checksum> {
+        // Parsing errors are reported by `analyze` since no metadata is cached
+        let parsed = ParsedWasm::parse(wasm).ok();
+        self.save_wasm_parsed(wasm, parsed.as_ref())
+    }
+
+    /// Compiles and stores the Wasm. `parsed` is the result of parsing the same Wasm, if available.
+    fn save_wasm_parsed(&self, wasm: &[u8], parsed: Option<&ParsedWasm>) -> VmResult<Checksum> {
         // We need a new engine for each Wasm -> module compilation due to the metering middleware.
-        let compiling_engine = make_compiling_engine(None);
+        let compiling_engine = make_compiling_engine_with_compiler(self.compiler, None);
         // This module cannot be executed directly as it was not created with the runtime engine
         let module = compile(&compiling_engine, wasm)?;
-
+        let fingerprint = match parsed {
+            Some(parsed) => parsed.fingerprint,
+            None => ModuleFingerprint::generate(wasm)?,
+        };
+        let metadata = parsed.map(WasmMetadata::from_module);
+
+        // The Wasm blob and the metadata are written without holding the lock. Files with the
+        // same name always have the same content, so concurrent writes do not conflict.
+        let (wasm_path, metadata_cache) = self.paths();
+        let checksum = save_wasm_to_disk(&wasm_path, wasm)?;
+        if let Some(metadata) = metadata {
+            metadata_cache.store(&checksum, &metadata)?;
+        }
+        // The file system cache keeps an index of the stored modules, which needs the lock
         let mut cache = self.inner.lock().unwrap();
-        let checksum = save_wasm_to_disk(&cache.wasm_path, wasm)?;
-        cache.fs_cache.store(&checksum, &module)?;
+        cache.fs_cache.store(&checksum, &module, &fingerprint)?;
         Ok(checksum)
     }
 
+    /// Returns the directory of the Wasm blobs and the metadata cache, such that they can
+    /// be accessed without holding the lock of the cache.
+    fn paths(&self) -> (PathBuf, MetadataCache) {
+        let cache = self.inner.lock().unwrap();
+        (cache.wasm_path.clone(), cache.metadata_cache.clone())
+    }
+
     /// Removes the Wasm blob for the given checksum from disk and its
     /// compiled module from the file system cache.
     ///
@@ -248,6 +363,7 @@ where
         // necessary as they are pushed out from the LRU over time or disappear
         // when the node process restarts.
         cache.fs_cache.remove(checksum)?;
+        cache.metadata_cache.remove(checksum)?;
 
         let path = &cache.wasm_path;
         remove_wasm_from_disk(path, checksum)?;
@@ -260,7 +376,8 @@ where
     ///
     /// If the given ID is not found or the content does not match the hash (=ID), an error is returned.
     pub fn load_wasm(&self, checksum: &Checksum) -> VmResult<Vec<u8>> {
-        self.load_wasm_with_path(&self.inner.lock().unwrap().wasm_path, checksum)
+        let (wasm_path, _) = self.paths();
+        self.load_wasm_with_path(&wasm_path, checksum)
     }
 
     fn load_wasm_with_path(&self, wasm_path: &Path, checksum: &Checksum) -> VmResult<Vec<u8>> {
@@ -276,29 +393,59 @@ where
     /// Performs static anlyzation on this Wasm without compiling or instantiating it.
     ///
     /// Once the contract was stored via [`save_wasm`], this can be called at any point in time.
-    /// It does not depend on any caching of the contract.
+    /// The result is cached on disk, such that the Wasm only needs to be parsed once. If the
+    /// cached result is not available, it is created from the stored Wasm.
     pub fn analyze(&self, checksum: &Checksum) -> VmResult<AnalysisReport> {
-        // Here we could use a streaming deserializer to slightly improve performance. However, this way it is DRYer.
-        let wasm = self.load_wasm(checksum)?;
-        let module = ParsedWasm::parse(&wasm)?;
-        let exports = module.exported_function_names(None);
-
-        let entrypoints = exports
-            .iter()
-            .filter_map(|export| Entrypoint::from_str(export).ok())
-            .collect();
+        let metadata = self.load_metadata(checksum)?;
 
         Ok(AnalysisReport {
-            has_ibc_entry_points: REQUIRED_IBC_EXPORTS
-                .iter()
-                .all(|required| exports.contains(required.as_ref())),
-            entrypoints,
-            required_capabilities: required_capabilities_from_module(&module)
-                .into_iter()
-                .collect(),
+            has_ibc_entry_points: metadata.has_ibc_entry_points,
+            entrypoints: metadata.entrypoints,
+            required_capabilities: metadata.required_capabilities,
+            interface_version: metadata.interface_version,
         })
     }
 
+    /// Returns the provenance information the contract author embedded in the Wasm
+    /// as custom sections (see [`ContractMetadata`]), without compiling or instantiating it.
+    ///
+    /// Like [`analyze`](Self::analyze), this uses the metadata cached on disk if available.
+    pub fn metadata(&self, checksum: &Checksum) -> VmResult<ContractMetadata> {
+        Ok(self.load_metadata(checksum)?.contract)
+    }
+
+    /// Returns the fingerprint of the stored Wasm (see [`ModuleFingerprint`]).
+    pub fn fingerprint(&self, checksum: &Checksum) -> VmResult<ModuleFingerprint> {
+        let wasm = self.load_wasm(checksum)?;
+        ModuleFingerprint::generate(&wasm)
+    }
+
+    /// Returns the fingerprint stored along with the compiled artifact on disk, or `None`
+    /// if no artifact was stored for this checksum.
+    ///
+    /// The fingerprint is kept when the artifact becomes incompatible after a VM upgrade,
+    /// until it is recompiled. If this differs from [`fingerprint`](Self::fingerprint), the artifact was not compiled
+    /// from the stored Wasm.
+    pub fn artifact_fingerprint(&self, checksum: &Checksum) -> VmResult<Option<ModuleFingerprint>> {
+        self.inner.lock().unwrap().fs_cache.fingerprint(checksum)
+    }
+
+    /// Loads the cached metadata for the given checksum or creates it from the stored Wasm.
+    fn load_metadata(&self, checksum: &Checksum) -> VmResult<WasmMetadata> {
+        // Only file system operations follow, which do not need the lock
+        let (wasm_path, metadata_cache) = self.paths();
+        match metadata_cache.load(checksum) {
+            Some(metadata) => Ok(metadata),
+            None => {
+                // Here we could use a streaming deserializer to slightly improve performance. However, this way it is DRYer.
+                let wasm = self.load_wasm_with_path(&wasm_path, checksum)?;
+                let metadata = WasmMetadata::from_module(&ParsedWasm::parse(&wasm)?);
+                metadata_cache.store(checksum, &metadata)?;
+                Ok(metadata)
+            }
+        }
+    }
+
     /// Pins a Module that was previously stored via save_wasm.
     ///
     /// The module is lookup first in the file system cache. If not found,
@@ -308,6 +455,17 @@ where
     /// If the given contract for the given checksum is not found, or the content
     /// does not match the checksum, an error is returned.
     pub fn pin(&self, checksum: &Checksum) -> VmResult<()> {
+        self.pin_module(checksum)?;
+        self.inner
+            .lock()
+            .unwrap()
+            .pinned_explicitly
+            .insert(*checksum);
+        Ok(())
+    }
+
+    /// Stores the module in the pinned memory cache without recording why it is pinned
+    fn pin_module(&self, checksum: &Checksum) -> VmResult<()> {
         let mut cache = self.inner.lock().unwrap();
         if cache.pinned_memory_cache.has(checksum) {
             return Ok(());
@@ -318,7 +476,9 @@ where
         // for a not-so-relevant use case.
 
         // Try to get module from file system cache
-        if let Some((module, module_size)) = cache.fs_cache.load(checksum, &cache.runtime_engine)? {
+        if let Some((module, module_size)) =
+            load_compatible_module(&cache.fs_cache, checksum, &cache.runtime_engine)?
+        {
             cache.stats.hits_fs_cache = cache.stats.hits_fs_cache.saturating_add(1);
             return cache
                 .pinned_memory_cache
@@ -330,10 +490,11 @@ where
         cache.stats.misses = cache.stats.misses.saturating_add(1);
         {
             // Module will run with a different engine, so we can set memory limit to None
-            let compiling_engine = make_compiling_engine(None);
+            let compiling_engine = make_compiling_engine_with_compiler(self.compiler, None);
             // This module cannot be executed directly as it was not created with the runtime engine
             let module = compile(&compiling_engine, &wasm)?;
-            cache.fs_cache.store(checksum, &module)?;
+            let fingerprint = ModuleFingerprint::generate(&wasm)?;
+            cache.fs_cache.store(checksum, &module, &fingerprint)?;
         }
 
         // This time we'll hit the file-system cache.
@@ -352,26 +513,220 @@ where
     /// Unpins a Module, i.e. removes it from the pinned memory cache.
     ///
     /// Not found IDs are silently ignored, and no integrity check (checksum validation) is done
-    /// on the removed value.
+    /// on the removed value. Modules listed in the pin file (see [`Cache::pin_from_file`])
+    /// stay pinned until they are removed from the file.
     pub fn unpin(&self, checksum: &Checksum) -> VmResult<()> {
-        self.inner
-            .lock()
-            .unwrap()
+        let pin_file = self.pin_file.lock().unwrap();
+        let mut cache = self.inner.lock().unwrap();
+        cache.pinned_explicitly.remove(checksum);
+        if pin_file
+            .as_ref()
+            .is_some_and(|pin_file| pin_file.pinned.contains(checksum))
+        {
+            return Ok(());
+        }
+        cache.pinned_memory_cache.remove(checksum)
+    }
+
+    /// Pins all modules listed in a pin file, which contains one hex encoded checksum
+    /// per line. This allows operators to manage a set of pinned contracts in addition to
+    /// the ones pinned on-chain.
+    ///
+    /// Modules that were pinned because of a previously applied pin file but are no longer
+    /// listed are unpinned. Modules that are also pinned via [`Cache::pin`] are never unpinned
+    /// by the file, no matter if they were pinned before or after they were added to it.
+    /// The file is remembered for [`Cache::reload_pin_file`] and [`Cache::watch_pin_file`].
+    ///
+    /// If some of the listed modules cannot be pinned, all other entries are still applied
+    /// and an error listing the failed entries is returned. The file is then applied again
+    /// on the next reload, even if it was not modified.
+    pub fn pin_from_file(&self, path: impl AsRef<Path>) -> VmResult<PinFileReport> {
+        let mut pin_file = self.pin_file.lock().unwrap();
+        let pinned = pin_file
+            .take()
+            .map(|previous| previous.pinned)
+            .unwrap_or_default();
+        let applied = pin_file.insert(PinFile {
+            path: path.as_ref().to_path_buf(),
+            modified: None,
+            pinned,
+        });
+        self.apply_pin_file(applied)
+    }
+
+    /// Applies the pin file set via [`Cache::pin_from_file`] again if it was modified
+    /// since it was read last. Returns `None` if there is no pin file or it is unchanged.
+    pub fn reload_pin_file(&self) -> VmResult<Option<PinFileReport>> {
+        let mut pin_file = self.pin_file.lock().unwrap();
+        let Some(applied) = pin_file.as_mut() else {
+            return Ok(None);
+        };
+        let modified = modified(&applied.path)?;
+        if modified.is_some() && modified == applied.modified {
+            return Ok(None);
+        }
+        self.apply_pin_file(applied).map(Some)
+    }
+
+    /// Calls [`Cache::reload_pin_file`] in the given interval in a background thread,
+    /// such that changes to the pin file are applied without restarting the node.
+    /// The thread runs until the returned watcher is dropped.
+    pub fn watch_pin_file(self: &Arc<Self>, interval: Duration) -> PinFileWatcher
+    where
+        Self: Send + Sync,
+    {
+        let cache = Arc::clone(self);
+        PinFileWatcher::spawn(interval, move || cache.reload_pin_file())
+    }
+
+    fn apply_pin_file(&self, pin_file: &mut PinFile) -> VmResult<PinFileReport> {
+        // Read before the content, such that changes made in between are applied by the next reload
+        let modified = modified(&pin_file.path)?;
+        let listed = read_pin_file(&pin_file.path)?;
+
+        let mut report = PinFileReport::default();
+        let mut errors = Vec::new();
+        for checksum in &listed {
+            if pin_file.pinned.contains(checksum) {
+                continue;
+            }
+            let already_pinned = self.inner.lock().unwrap().pinned_memory_cache.has(checksum);
+            match self.pin_module(checksum) {
+                Ok(()) => {
+                    pin_file.pinned.insert(*checksum);
+                    if !already_pinned {
+                        report.pinned += 1;
+                    }
+                }
+                Err(e) => errors.push(format!("{checksum}: {e}")),
+            }
+        }
+
+        let removed: Vec<Checksum> = pin_file
+            .pinned
+            .iter()
+            .filter(|checksum| !listed.contains(checksum))
+            .copied()
+            .collect();
+        for checksum in removed {
+            pin_file.pinned.remove(&checksum);
+            let mut cache = self.inner.lock().unwrap();
+            if !cache.pinned_explicitly.contains(&checksum) {
+                cache.pinned_memory_cache.remove(&checksum)?;
+                report.unpinned += 1;
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(VmError::cache_err(format!(
+                "Error pinning modules from pin file: {}",
+                errors.join("; ")
+            )));
+        }
+        // Only remember the file as applied if all entries are pinned, such that failed
+        // entries are retried by the next reload
+        pin_file.modified = modified;
+        Ok(report)
+    }
+
+    /// Writes all pinned modules along with their metadata into a single snapshot file,
+    /// which can be loaded into another cache via [`Cache::load_pinned`].
+    /// This allows shipping a set of warm modules with a node image.
+    ///
+    /// The snapshot can only be loaded by a VM with the same compiler, target and Wasmer
+    /// version. Returns the number of modules written.
+    pub fn dump_pinned(&self, path: impl AsRef<Path>) -> VmResult<usize> {
+        let cache = self.inner.lock().unwrap();
+        let mut entries = cache
             .pinned_memory_cache
-            .remove(checksum)
+            .iter()
+            .map(|(checksum, cached)| {
+                let artifact = cached.module.serialize().map_err(|e| {
+                    VmError::cache_err(format!("Error serializing pinned module: {e}"))
+                })?;
+                Ok(PinnedSnapshotEntry {
+                    checksum: *checksum,
+                    metadata: cache.metadata_cache.load(checksum),
+                    artifact: artifact.to_vec(),
+                })
+            })
+            .collect::<VmResult<Vec<_>>>()?;
+        entries.sort_by(|a, b| a.checksum.as_slice().cmp(b.checksum.as_slice()));
+
+        write_pinned_snapshot(path.as_ref(), cache.fs_cache.artifact_tag(), &entries)?;
+        Ok(entries.len())
+    }
+
+    /// Pins all modules of a snapshot created by [`Cache::dump_pinned`].
+    ///
+    /// Modules that are already pinned are kept. The metadata of the snapshot is stored
+    /// in the metadata cache if none exists for the checksum. Returns the number of
+    /// modules that were pinned.
+    ///
+    /// # Safety
+    ///
+    /// The artifacts in the snapshot are loaded without recompilation. There is no way to
+    /// ensure the snapshot has not been corrupted or tampered with, so it must come from
+    /// a trusted source.
+    pub unsafe fn load_pinned(&self, path: impl AsRef<Path>) -> VmResult<usize> {
+        let mut cache = self.inner.lock().unwrap();
+        let entries = read_pinned_snapshot(path.as_ref(), cache.fs_cache.artifact_tag())?;
+
+        let mut pinned = 0;
+        for entry in entries {
+            if cache.pinned_memory_cache.has(&entry.checksum) {
+                cache.pinned_explicitly.insert(entry.checksum);
+                continue;
+            }
+            let module = Module::deserialize(&cache.runtime_engine, entry.artifact.as_slice())
+                .map_err(|e| {
+                    VmError::cache_err(format!("Error deserializing pinned module: {e}"))
+                })?;
+            if let Some(metadata) = entry.metadata {
+                if cache.metadata_cache.load(&entry.checksum).is_none() {
+                    cache.metadata_cache.store(&entry.checksum, &metadata)?;
+                }
+            }
+            cache
+                .pinned_memory_cache
+                .store(&entry.checksum, module, entry.artifact.len())?;
+            cache.pinned_explicitly.insert(entry.checksum);
+            pinned += 1;
+        }
+        Ok(pinned)
     }
 
     /// Returns an Instance tied to a previously saved Wasm.
     ///
     /// It takes a module from cache or Wasm code and instantiates it.
+    ///
+    /// If [`CacheOptions::max_concurrent_instances`] is set and the limit is reached for
+    /// this checksum, [`VmError::TooManyInstances`] is returned.
     pub fn get_instance(
         &self,
         checksum: &Checksum,
         backend: Backend<A, S, Q>,
         options: InstanceOptions,
     ) -> VmResult<Instance<A, S, Q>> {
+        self.instantiate(checksum, backend, options)
+    }
+
+    /// Implementation of [`Cache::get_instance`], which also allows storages
+    /// other than the cache's storage type, e.g. a [`StorageOverlay`] for simulations.
+    fn instantiate<T: Storage + 'static>(
+        &self,
+        checksum: &Checksum,
+        backend: Backend<A, T, Q>,
+        options: InstanceOptions,
+    ) -> VmResult<Instance<A, T, Q>> {
+        options.response_limits.validate()?;
+        let permit = self
+            .concurrency_limiter
+            .as_ref()
+            .map(|limiter| limiter.try_acquire(checksum))
+            .transpose()?;
         let (cached, store) = self.get_module(checksum)?;
-        let instance = Instance::from_module(
+        let mut instance = Instance::from_module(
             store,
             &cached.module,
             backend,
@@ -379,9 +734,142 @@ where
             None,
             Some(&self.instantiation_lock),
         )?;
+        instance.set_tracing(options.trace);
+        instance.set_instrumentation(options.instrumentation);
+        instance.set_response_limits(options.response_limits)?;
+        if let Some(permit) = permit {
+            instance.set_permit(permit);
+        }
         Ok(instance)
     }
 
+    /// Executes the contract like [`call_execute`] but without committing any state changes.
+    ///
+    /// The storage of `backend` is wrapped in a [`StorageOverlay`], so all writes of the contract
+    /// are collected in the returned [`Simulation::state_diff`] instead. Writes are charged with
+    /// the default [`WriteGasCosts`](crate::WriteGasCosts). The unmodified backend
+    /// is returned for reuse.
+    ///
+    /// This counts towards [`CacheOptions::max_concurrent_instances`] like [`Cache::get_instance`].
+    pub fn simulate_execute<U>(
+        &self,
+        checksum: &Checksum,
+        backend: Backend<A, S, Q>,
+        options: InstanceOptions,
+        env: &Env,
+        info: &MessageInfo,
+        msg: &[u8],
+    ) -> VmResult<(Simulation<U>, Backend<A, S, Q>)>
+    where
+        U: DeserializeOwned + CustomMsg,
+    {
+        let Backend {
+            api,
+            storage,
+            querier,
+        } = backend;
+        let backend = Backend {
+            api,
+            storage: StorageOverlay::new(storage),
+            querier,
+        };
+
+        let mut instance = self.instantiate(checksum, backend, options)?;
+        let result = call_execute(&mut instance, env, info, msg)?;
+        let gas_report = instance.create_gas_report();
+        let Backend {
+            api,
+            storage,
+            querier,
+        } = instance
+            .recycle()
+            .expect("Backend is only moved out of the instance by recycle");
+        let (storage, state_diff) = storage.into_parts();
+
+        let simulation = Simulation {
+            result,
+            state_diff,
+            gas_report,
+        };
+        let backend = Backend {
+            api,
+            storage,
+            querier,
+        };
+        Ok((simulation, backend))
+    }
+
+    /// Executes a smart query like [`call_query`] and memoizes the result if
+    /// [`CacheOptions::query_cache_size`] is set.
+    ///
+    /// Results are keyed by the checksum, the env, the query message and `storage_version`.
+    /// The storage version is provided by the host and must change whenever the state the query
+    /// can observe changes, i.e. on every write to the contract. Once a newer storage version of
+    /// a contract is seen, all of its memoized results are removed. Queries that depend on the
+    /// state of other contracts are only memoized correctly if the host accounts for that
+    /// in the storage version.
+    ///
+    /// The backend is returned for reuse. It is not used if the result is served from memory.
+    /// In that case, the returned gas report is the one of the call that computed the result.
+    pub fn query_memoized(
+        &self,
+        checksum: &Checksum,
+        backend: Backend<A, S, Q>,
+        options: InstanceOptions,
+        env: &Env,
+        msg: &[u8],
+        storage_version: u64,
+    ) -> VmResult<(MemoizedQuery, Backend<A, S, Q>)> {
+        let contract = env.contract.address.as_str();
+        let serialized_env = to_vec(env)?;
+        let cached = self.query_cache.lock().unwrap().load(
+            checksum,
+            contract,
+            storage_version,
+            &serialized_env,
+            msg,
+        );
+        if let Some((result, gas_report)) = cached {
+            let query = MemoizedQuery {
+                result,
+                gas_report,
+                from_cache: true,
+            };
+            return Ok((query, backend));
+        }
+
+        let mut instance = self.get_instance(checksum, backend, options)?;
+        let result = call_query(&mut instance, env, msg)?;
+        let gas_report = instance.create_gas_report();
+        let backend = instance
+            .recycle()
+            .expect("Backend is only moved out of the instance by recycle");
+
+        self.query_cache.lock().unwrap().store(
+            checksum,
+            contract,
+            storage_version,
+            &serialized_env,
+            msg,
+            result.clone(),
+            gas_report,
+        );
+        let query = MemoizedQuery {
+            result,
+            gas_report,
+            from_cache: false,
+        };
+        Ok((query, backend))
+    }
+
+    /// Removes all memoized query results of the given contract address.
+    ///
+    /// This is only needed for hosts that do not provide a storage version to
+    /// [`Cache::query_memoized`] that changes on every write.
+    pub fn invalidate_query_cache(&self, contract: &str) {
+        self.query_cache.lock().unwrap().invalidate(contract);
+    }
+
     /// Returns a module tied to a previously saved Wasm.
     /// Depending on availability, this is either generated from a memory cache, file system cache or Wasm code.
     /// This is part of `get_instance` but pulled out to reduce the locking time.
@@ -403,7 +891,9 @@ where
         }
 
         // Get module from file system cache
-        if let Some((module, module_size)) = cache.fs_cache.load(checksum, &cache.runtime_engine)? {
+        if let Some((module, module_size)) =
+            load_compatible_module(&cache.fs_cache, checksum, &cache.runtime_engine)?
+        {
             cache.stats.hits_fs_cache = cache.stats.hits_fs_cache.saturating_add(1);
 
             cache
@@ -426,10 +916,11 @@ where
         cache.stats.misses = cache.stats.misses.saturating_add(1);
         {
             // Module will run with a different engine, so we can set memory limit to None
-            let compiling_engine = make_compiling_engine(None);
+            let compiling_engine = make_compiling_engine_with_compiler(self.compiler, None);
             // This module cannot be executed directly as it was not created with the runtime engine
             let module = compile(&compiling_engine, &wasm)?;
-            cache.fs_cache.store(checksum, &module)?;
+            let fingerprint = ModuleFingerprint::generate(&wasm)?;
+            cache.fs_cache.store(checksum, &module, &fingerprint)?;
         }
 
         // This time we'll hit the file-system cache.
@@ -467,6 +958,22 @@ where
 {
 }
 
+/// Loads a module from the file system cache.
+///
+/// Modules that were stored by an incompatible version of the VM or Wasmer (or for a different
+/// target) are treated like missing modules, such that the caller recompiles them from the
+/// original Wasm and overwrites the stored artifact.
+fn load_compatible_module(
+    fs_cache: &FileSystemCache,
+    checksum: &Checksum,
+    engine: &Engine,
+) -> VmResult<Option<(Module, usize)>> {
+    match fs_cache.load(checksum, engine) {
+        Err(VmError::WrongArtifactVersion { .. }) => Ok(None),
+        result => result,
+    }
+}
+
 /// save stores the wasm code in the given directory and returns an ID for lookup.
 /// It will create the directory if it doesn't exist.
 /// Saving the same byte code multiple times is allowed.
@@ -537,19 +1044,26 @@ fn remove_wasm_from_disk(dir: impl Into<PathBuf>, checksum: &Checksum) -> VmResu
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::calls::{call_execute, call_instantiate};
+    use crate::calls::{call_execute, call_instantiate, call_query};
     use crate::capabilities::capabilities_from_csv;
     use crate::errors::VmError;
+    use crate::instance::memory_initialization_cost;
+    use crate::response_limits::ResponseLimits;
+    use crate::static_analysis::REQUIRED_IBC_EXPORTS;
     use crate::testing::{mock_backend, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
     use cosmwasm_std::{coins, Empty};
     use std::fs::{create_dir_all, remove_dir_all, OpenOptions};
     use std::io::Write;
+    use std::sync::Arc;
     use tempfile::TempDir;
 
     const TESTING_GAS_LIMIT: u64 = 500_000_000; // ~0.5ms
     const TESTING_MEMORY_LIMIT: Size = Size::mebi(16);
     const TESTING_OPTIONS: InstanceOptions = InstanceOptions {
         gas_limit: TESTING_GAS_LIMIT,
+        trace: false,
+        response_limits: ResponseLimits::DEFAULT,
+        instrumentation: None,
     };
     const TESTING_MEMORY_CACHE_SIZE: Size = Size::mebi(200);
 
@@ -565,7 +1079,7 @@ mod tests {
             i32.add))
     "#;
 
-    fn default_capabilities() -> HashSet<String> {
+    fn default_capabilities() -> HashSet<Capability> {
         capabilities_from_csv("iterator,staking")
     }
 
@@ -575,6 +1089,9 @@ mod tests {
             available_capabilities: default_capabilities(),
             memory_cache_size: TESTING_MEMORY_CACHE_SIZE,
             instance_memory_limit: TESTING_MEMORY_LIMIT,
+            max_concurrent_instances: None,
+            compiler: Compiler::default(),
+            query_cache_size: Size::new(0),
         }
     }
 
@@ -586,6 +1103,9 @@ mod tests {
             available_capabilities: capabilities,
             memory_cache_size: TESTING_MEMORY_CACHE_SIZE,
             instance_memory_limit: TESTING_MEMORY_LIMIT,
+            max_concurrent_instances: None,
+            compiler: Compiler::default(),
+            query_cache_size: Size::new(0),
         }
     }
 
@@ -714,6 +1234,9 @@ mod tests {
                 available_capabilities: default_capabilities(),
                 memory_cache_size: TESTING_MEMORY_CACHE_SIZE,
                 instance_memory_limit: TESTING_MEMORY_LIMIT,
+                max_concurrent_instances: None,
+                compiler: Compiler::default(),
+                query_cache_size: Size::new(0),
             };
             let cache1: Cache<MockApi, MockStorage, MockQuerier> =
                 unsafe { Cache::new(options1).unwrap() };
@@ -726,6 +1249,9 @@ mod tests {
                 available_capabilities: default_capabilities(),
                 memory_cache_size: TESTING_MEMORY_CACHE_SIZE,
                 instance_memory_limit: TESTING_MEMORY_LIMIT,
+                max_concurrent_instances: None,
+                compiler: Compiler::default(),
+                query_cache_size: Size::new(0),
             };
             let cache2: Cache<MockApi, MockStorage, MockQuerier> =
                 unsafe { Cache::new(options2).unwrap() };
@@ -759,6 +1285,9 @@ mod tests {
             available_capabilities: default_capabilities(),
             memory_cache_size: TESTING_MEMORY_CACHE_SIZE,
             instance_memory_limit: TESTING_MEMORY_LIMIT,
+            max_concurrent_instances: None,
+            compiler: Compiler::default(),
+            query_cache_size: Size::new(0),
         };
         let cache: Cache<MockApi, MockStorage, MockQuerier> =
             unsafe { Cache::new(options).unwrap() };
@@ -827,6 +1356,53 @@ mod tests {
         assert_eq!(cache.stats().misses, 0);
     }
 
+    #[test]
+    fn get_instance_finds_modules_stored_by_other_cache() {
+        let options = make_testing_options();
+        let cache1: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options.clone()).unwrap() };
+        let cache2: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options).unwrap() };
+
+        // populates the index of cache2 before the module exists
+        let checksum = Checksum::generate(CONTRACT);
+        let result = cache2.get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS);
+        assert!(result.is_err());
+
+        cache1.save_wasm(CONTRACT).unwrap();
+        let _instance = cache2
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache2.stats().hits_fs_cache, 1);
+        assert_eq!(cache2.stats().misses, 0);
+    }
+
+    #[test]
+    fn compilers_use_separate_module_directories() {
+        let mut options = make_testing_options();
+        options.compiler = Compiler::Cranelift;
+        let cranelift_cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options.clone()).unwrap() };
+        options.compiler = Compiler::Singlepass;
+        let singlepass_cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options).unwrap() };
+
+        let checksum = cranelift_cache.save_wasm(CONTRACT).unwrap();
+        let mut instance = cranelift_cache
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cranelift_cache.stats().hits_fs_cache, 1);
+        test_hackatom_instance_execution(&mut instance);
+
+        // The Wasm is shared but the module has to be compiled with Singlepass
+        let mut instance = singlepass_cache
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(singlepass_cache.stats().hits_fs_cache, 0);
+        assert_eq!(singlepass_cache.stats().misses, 1);
+        test_hackatom_instance_execution(&mut instance);
+    }
+
     #[test]
     fn get_instance_finds_cached_modules_and_stores_to_memory() {
         let cache = unsafe { Cache::new(make_testing_options()).unwrap() };
@@ -920,6 +1496,45 @@ mod tests {
         assert_eq!(cache.stats().misses, 1);
     }
 
+    #[test]
+    fn get_instance_recompiles_module_with_wrong_artifact_version() {
+        let options = make_testing_options();
+        let cache = unsafe { Cache::new(options.clone()).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+
+        // Corrupt the trailer of the compiled module on disk
+        let pattern = format!(
+            "{}/**/{}.module",
+            options
+                .base_dir
+                .join(CACHE_DIR)
+                .join(MODULES_DIR)
+                .to_string_lossy(),
+            checksum
+        );
+        let module_path = glob::glob(&pattern).unwrap().next().unwrap().unwrap();
+        let mut module = fs::read(&module_path).unwrap();
+        module.pop();
+        fs::write(&module_path, module).unwrap();
+
+        // The first get_instance recompiles the Wasm (miss)
+        let backend = mock_backend(&[]);
+        let _instance = cache
+            .get_instance(&checksum, backend, TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache.stats().hits_fs_cache, 0);
+        assert_eq!(cache.stats().misses, 1);
+
+        // The module was overwritten with a compatible one
+        let cache = unsafe { Cache::new(options).unwrap() };
+        let backend = mock_backend(&[]);
+        let _instance = cache
+            .get_instance(&checksum, backend, TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache.stats().hits_fs_cache, 1);
+        assert_eq!(cache.stats().misses, 0);
+    }
+
     #[test]
     fn call_instantiate_on_cached_contract() {
         let cache = unsafe { Cache::new(make_testing_options()).unwrap() };
@@ -985,6 +1600,90 @@ mod tests {
         }
     }
 
+    #[test]
+    fn query_memoized_works() {
+        let mut options = make_testing_options();
+        options.query_cache_size = Size::kibi(100);
+        let cache = unsafe { Cache::new(options).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+
+        // instantiate
+        let mut instance = cache
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+        let backend = instance.recycle().unwrap();
+
+        // first query executes the contract
+        let msg = br#"{"verifier":{}}"#;
+        let (query, backend) = cache
+            .query_memoized(&checksum, backend, TESTING_OPTIONS, &mock_env(), msg, 1)
+            .unwrap();
+        assert_eq!(
+            query.result.unwrap(),
+            br#"{"verifier":"verifies"}"#.as_slice()
+        );
+        assert!(!query.from_cache);
+        let gas_used = query.gas_report.used_internally;
+        assert!(gas_used > 0);
+        assert_eq!(cache.metrics().elements_query_cache, 1);
+        assert!(cache.metrics().size_query_cache > 0);
+
+        // second query is served from memory
+        let (query, backend) = cache
+            .query_memoized(&checksum, backend, TESTING_OPTIONS, &mock_env(), msg, 1)
+            .unwrap();
+        assert_eq!(
+            query.result.unwrap(),
+            br#"{"verifier":"verifies"}"#.as_slice()
+        );
+        assert!(query.from_cache);
+        assert_eq!(query.gas_report.used_internally, gas_used);
+
+        // a new storage version executes the contract again
+        let (query, backend) = cache
+            .query_memoized(&checksum, backend, TESTING_OPTIONS, &mock_env(), msg, 2)
+            .unwrap();
+        assert!(!query.from_cache);
+        assert_eq!(cache.metrics().elements_query_cache, 1);
+
+        // explicit invalidation
+        cache.invalidate_query_cache(mock_env().contract.address.as_str());
+        assert_eq!(cache.metrics().elements_query_cache, 0);
+        let (query, _backend) = cache
+            .query_memoized(&checksum, backend, TESTING_OPTIONS, &mock_env(), msg, 2)
+            .unwrap();
+        assert!(!query.from_cache);
+    }
+
+    #[test]
+    fn query_memoized_is_disabled_by_default() {
+        let cache = unsafe { Cache::new(make_testing_options()).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+
+        let msg = br#"{"verifier":{}}"#;
+        let (query, backend) = cache
+            .query_memoized(
+                &checksum,
+                mock_backend(&[]),
+                TESTING_OPTIONS,
+                &mock_env(),
+                msg,
+                1,
+            )
+            .unwrap();
+        assert!(!query.from_cache);
+        let (query, _backend) = cache
+            .query_memoized(&checksum, backend, TESTING_OPTIONS, &mock_env(), msg, 1)
+            .unwrap();
+        assert!(!query.from_cache);
+        assert_eq!(cache.metrics().elements_query_cache, 0);
+    }
+
     #[test]
     fn call_execute_on_cached_contract() {
         let cache = unsafe { Cache::new(make_testing_options()).unwrap() };
@@ -1077,6 +1776,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn simulate_execute_works() {
+        let cache = unsafe { Cache::new(make_testing_options()).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+
+        // instantiate
+        let mut instance = cache
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+        let backend = instance.recycle().unwrap();
+        let (state_before, _) = backend.storage.get(b"config");
+
+        // simulate execute
+        let info = mock_info("verifies", &coins(15, "earth"));
+        let msg = br#"{"release":{}}"#;
+        let (simulation, backend) = cache
+            .simulate_execute::<Empty>(&checksum, backend, TESTING_OPTIONS, &mock_env(), &info, msg)
+            .unwrap();
+        let response = simulation.result.unwrap();
+        assert_eq!(response.messages.len(), 1);
+        assert!(simulation.state_diff.is_empty());
+        assert!(simulation.gas_report.used_internally > 0);
+        assert_eq!(backend.storage.get(b"config").0, state_before);
+
+        // errors are returned like in call_execute
+        let info = mock_info("unknown", &[]);
+        let (simulation, _backend) = cache
+            .simulate_execute::<Empty>(&checksum, backend, TESTING_OPTIONS, &mock_env(), &info, msg)
+            .unwrap();
+        assert!(simulation.result.is_err());
+    }
+
     #[test]
     fn call_execute_on_recompiled_contract() {
         let options = make_testing_options();
@@ -1186,7 +1922,7 @@ mod tests {
         assert_eq!(cache.stats().hits_memory_cache, 1);
         assert_eq!(cache.stats().hits_fs_cache, 1);
         assert_eq!(cache.stats().misses, 0);
-        assert_eq!(instance2.get_gas_left(), TESTING_GAS_LIMIT);
+        assert_eq!(instance2.get_gas_left(), original_gas);
     }
 
     #[test]
@@ -1198,7 +1934,12 @@ mod tests {
         let backend2 = mock_backend(&[]);
 
         // Init from module cache
-        let options = InstanceOptions { gas_limit: 10 };
+        let options = InstanceOptions {
+            gas_limit: 10,
+            trace: false,
+            response_limits: ResponseLimits::DEFAULT,
+            instrumentation: None,
+        };
         let mut instance1 = cache.get_instance(&checksum, backend1, options).unwrap();
         assert_eq!(cache.stats().hits_fs_cache, 1);
         assert_eq!(cache.stats().misses, 0);
@@ -1217,13 +1958,20 @@ mod tests {
         // Init from memory cache
         let options = InstanceOptions {
             gas_limit: TESTING_GAS_LIMIT,
+            trace: false,
+            response_limits: ResponseLimits::DEFAULT,
+            instrumentation: None,
         };
         let mut instance2 = cache.get_instance(&checksum, backend2, options).unwrap();
         assert_eq!(cache.stats().hits_pinned_memory_cache, 0);
         assert_eq!(cache.stats().hits_memory_cache, 1);
         assert_eq!(cache.stats().hits_fs_cache, 1);
         assert_eq!(cache.stats().misses, 0);
-        assert_eq!(instance2.get_gas_left(), TESTING_GAS_LIMIT);
+        let initial_pages = instance2.memory_pages() as u32;
+        assert_eq!(
+            instance2.get_gas_left(),
+            TESTING_GAS_LIMIT - memory_initialization_cost(initial_pages)
+        );
 
         // Now it works
         let info2 = mock_info("owner2", &coins(500, "earth"));
@@ -1233,6 +1981,42 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn cache_and_instance_can_be_used_from_multiple_threads() {
+        fn assert_send<T: Send>() {}
+        fn assert_sync<T: Sync>() {}
+        assert_send::<Cache<MockApi, MockStorage, MockQuerier>>();
+        assert_sync::<Cache<MockApi, MockStorage, MockQuerier>>();
+        assert_send::<Instance<MockApi, MockStorage, MockQuerier>>();
+
+        let cache: Arc<Cache<MockApi, MockStorage, MockQuerier>> =
+            Arc::new(unsafe { Cache::new(make_testing_options()).unwrap() });
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let cache = Arc::clone(&cache);
+                std::thread::spawn(move || {
+                    let mut instance = cache
+                        .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+                        .unwrap();
+                    let info = mock_info("creator", &coins(1000, "earth"));
+                    let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+                    call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+                        .unwrap()
+                        .unwrap();
+                    call_query(&mut instance, &mock_env(), br#"{"verifier":{}}"#)
+                        .unwrap()
+                        .unwrap()
+                })
+            })
+            .collect();
+        for thread in threads {
+            let response = thread.join().unwrap();
+            assert_eq!(response, br#"{"verifier":"verifies"}"#);
+        }
+    }
+
     #[test]
     fn save_wasm_to_disk_works_for_same_data_multiple_times() {
         let tmp_dir = TempDir::new().unwrap();
@@ -1312,6 +2096,7 @@ mod tests {
                     E::Query
                 ]),
                 required_capabilities: BTreeSet::new(),
+                interface_version: Some(8),
             }
         );
 
@@ -1329,6 +2114,7 @@ mod tests {
                     "iterator".to_string(),
                     "stargate".to_string()
                 ]),
+                interface_version: Some(8),
             }
         );
 
@@ -1340,10 +2126,105 @@ mod tests {
                 has_ibc_entry_points: false,
                 entrypoints: BTreeSet::new(),
                 required_capabilities: BTreeSet::from(["iterator".to_string()]),
+                interface_version: Some(8),
             }
         );
     }
 
+    #[test]
+    fn analyze_works_without_cached_metadata() {
+        let options = make_testing_options();
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options.clone()).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        let report = cache.analyze(&checksum).unwrap();
+
+        // Remove cached metadata from disk
+        let metadata_dir = options.base_dir.join(CACHE_DIR).join(METADATA_DIR);
+        remove_dir_all(&metadata_dir).unwrap();
+
+        // The Wasm is analyzed again and the metadata is stored
+        assert_eq!(cache.analyze(&checksum).unwrap(), report);
+        assert!(metadata_dir.exists());
+        assert_eq!(cache.analyze(&checksum).unwrap(), report);
+
+        // Removing the Wasm removes the metadata
+        cache.remove_wasm(&checksum).unwrap();
+        cache.analyze(&checksum).unwrap_err();
+    }
+
+    #[test]
+    fn fingerprint_works() {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_testing_options()).unwrap() };
+
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        let fingerprint = cache.fingerprint(&checksum).unwrap();
+        assert_eq!(fingerprint, ModuleFingerprint::generate(CONTRACT).unwrap());
+        assert_eq!(
+            cache.artifact_fingerprint(&checksum).unwrap(),
+            Some(fingerprint)
+        );
+
+        // The artifact is stored again with the fingerprint when recompiled
+        cache
+            .inner
+            .lock()
+            .unwrap()
+            .fs_cache
+            .remove(&checksum)
+            .unwrap();
+        assert_eq!(cache.artifact_fingerprint(&checksum).unwrap(), None);
+        cache.pin(&checksum).unwrap();
+        assert_eq!(
+            cache.artifact_fingerprint(&checksum).unwrap(),
+            Some(fingerprint)
+        );
+
+        cache.remove_wasm(&checksum).unwrap();
+        cache.fingerprint(&checksum).unwrap_err();
+    }
+
+    #[test]
+    fn metadata_works() {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_testing_options()).unwrap() };
+
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        assert_eq!(
+            cache.metadata(&checksum).unwrap(),
+            ContractMetadata::default()
+        );
+
+        let wasm = wat::parse_str(
+            r#"(module
+                (@custom "contract_name" "crates.io:provenance")
+                (@custom "contract_version" "1.2.3")
+            )"#,
+        )
+        .unwrap();
+        let checksum = cache.save_wasm_unchecked(&wasm).unwrap();
+        let expected = ContractMetadata {
+            cargo_version: None,
+            contract_name: Some("crates.io:provenance".to_string()),
+            contract_version: Some("1.2.3".to_string()),
+        };
+        assert_eq!(cache.metadata(&checksum).unwrap(), expected);
+
+        // Works without cached metadata too
+        cache
+            .inner
+            .lock()
+            .unwrap()
+            .metadata_cache
+            .remove(&checksum)
+            .unwrap();
+        assert_eq!(cache.metadata(&checksum).unwrap(), expected);
+
+        cache.remove_wasm(&checksum).unwrap();
+        cache.metadata(&checksum).unwrap_err();
+    }
+
     #[test]
     fn pin_unpin_works() {
         let cache = unsafe { Cache::new(make_testing_options()).unwrap() };
@@ -1436,6 +2317,314 @@ mod tests {
         test_hackatom_instance_execution(&mut instance);
     }
 
+    #[test]
+    fn get_instance_respects_max_concurrent_instances() {
+        let mut options = make_testing_options();
+        options.max_concurrent_instances = Some(2);
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+
+        let instance1 = cache
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        let mut instance2 = cache
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        match cache.get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS) {
+            Err(VmError::TooManyInstances { limit, .. }) => assert_eq!(limit, 2),
+            Err(e) => panic!("Unexpected error: {e:?}"),
+            Ok(_) => panic!("Expected error"),
+        }
+        test_hackatom_instance_execution(&mut instance2);
+
+        // dropping an instance frees a slot
+        drop(instance1);
+        let mut instance3 = cache
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        test_hackatom_instance_execution(&mut instance3);
+
+        // recycling releases the slot too
+        let _backend = instance2.recycle().unwrap();
+        let _instance4 = cache
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+    }
+
+    #[test]
+    fn dump_pinned_load_pinned_works() {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_testing_options()).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        let snapshot_dir = TempDir::new().unwrap();
+        let snapshot = snapshot_dir.path().join("pinned.snapshot");
+
+        // nothing pinned
+        assert_eq!(cache.dump_pinned(&snapshot).unwrap(), 0);
+        cache.pin(&checksum).unwrap();
+        assert_eq!(cache.dump_pinned(&snapshot).unwrap(), 1);
+
+        // load into a fresh cache
+        let options = make_testing_options();
+        let other: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options.clone()).unwrap() };
+        assert_eq!(unsafe { other.load_pinned(&snapshot) }.unwrap(), 1);
+        assert_eq!(other.metrics().elements_pinned_memory_cache, 1);
+        // already pinned modules are kept
+        assert_eq!(unsafe { other.load_pinned(&snapshot) }.unwrap(), 0);
+
+        // the module is executed from the pinned memory cache
+        let backend = mock_backend(&[]);
+        let mut instance = other
+            .get_instance(&checksum, backend, TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(other.stats().hits_pinned_memory_cache, 1);
+        assert_eq!(other.stats().hits_fs_cache, 0);
+        assert_eq!(other.stats().misses, 0);
+        test_hackatom_instance_execution(&mut instance);
+
+        // the metadata was restored too
+        let metadata_cache =
+            MetadataCache::new(options.base_dir.join(CACHE_DIR).join(METADATA_DIR));
+        assert_eq!(
+            metadata_cache.load(&checksum),
+            Some(WasmMetadata::from_module(
+                &ParsedWasm::parse(CONTRACT).unwrap()
+            ))
+        );
+    }
+
+    #[test]
+    fn load_pinned_fails_for_missing_file() {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_testing_options()).unwrap() };
+        let tmp_dir = TempDir::new().unwrap();
+        match unsafe { cache.load_pinned(tmp_dir.path().join("missing")) }.unwrap_err() {
+            VmError::CacheErr { msg, .. } => assert_eq!(msg, "Error opening pinned snapshot file"),
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    /// Writes the pin file and forgets the modification time of the applied pin file,
+    /// such that changes are detected independent of the file system's time resolution
+    fn write_pin_file(
+        cache: &Cache<MockApi, MockStorage, MockQuerier>,
+        path: &Path,
+        content: &str,
+    ) {
+        fs::write(path, content).unwrap();
+        if let Some(applied) = cache.pin_file.lock().unwrap().as_mut() {
+            applied.modified = None;
+        }
+    }
+
+    #[test]
+    fn pin_from_file_works() {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_testing_options()).unwrap() };
+        let checksum1 = cache.save_wasm(CONTRACT).unwrap();
+        let checksum2 = cache.save_wasm(EMPTY_CONTRACT).unwrap();
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.path().join("pinned.txt");
+
+        write_pin_file(&cache, &path, &format!("# hackatom\n{checksum1}\n"));
+        let report = cache.pin_from_file(&path).unwrap();
+        assert_eq!(
+            report,
+            PinFileReport {
+                pinned: 1,
+                unpinned: 0
+            }
+        );
+        assert!(cache
+            .inner
+            .lock()
+            .unwrap()
+            .pinned_memory_cache
+            .has(&checksum1));
+
+        // modules pinned by other means are not owned by the file
+        cache.pin(&checksum2).unwrap();
+        write_pin_file(&cache, &path, &format!("{checksum1}\n{checksum2}\n"));
+        let report = cache.pin_from_file(&path).unwrap();
+        assert_eq!(report, PinFileReport::default());
+
+        // removing an entry unpins the module
+        write_pin_file(&cache, &path, &format!("{checksum2}\n"));
+        let report = cache.pin_from_file(&path).unwrap();
+        assert_eq!(
+            report,
+            PinFileReport {
+                pinned: 0,
+                unpinned: 1
+            }
+        );
+        assert!(!cache
+            .inner
+            .lock()
+            .unwrap()
+            .pinned_memory_cache
+            .has(&checksum1));
+
+        // unless it was pinned before it was added to the file
+        write_pin_file(&cache, &path, "");
+        let report = cache.pin_from_file(&path).unwrap();
+        assert_eq!(report, PinFileReport::default());
+        assert!(cache
+            .inner
+            .lock()
+            .unwrap()
+            .pinned_memory_cache
+            .has(&checksum2));
+    }
+
+    #[test]
+    fn pin_from_file_fails_for_invalid_file() {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_testing_options()).unwrap() };
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.path().join("pinned.txt");
+
+        match cache.pin_from_file(&path).unwrap_err() {
+            VmError::CacheErr { msg, .. } => {
+                assert!(msg.starts_with("Error reading pin file metadata"), "{msg}")
+            }
+            e => panic!("Unexpected error: {e:?}"),
+        }
+
+        write_pin_file(&cache, &path, "not a checksum\n");
+        match cache.pin_from_file(&path).unwrap_err() {
+            VmError::CacheErr { msg, .. } => {
+                assert_eq!(
+                    msg,
+                    "Invalid checksum in pin file at line 1: not a checksum"
+                )
+            }
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn pin_from_file_keeps_modules_pinned_by_other_means() {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_testing_options()).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.path().join("pinned.txt");
+
+        write_pin_file(&cache, &path, &format!("{checksum}\n"));
+        cache.pin_from_file(&path).unwrap();
+
+        // unpinning does not remove a module listed in the file
+        cache.unpin(&checksum).unwrap();
+        assert_eq!(cache.metrics().elements_pinned_memory_cache, 1);
+
+        // pinned after it was added to the file
+        cache.pin(&checksum).unwrap();
+        write_pin_file(&cache, &path, "");
+        let report = cache.pin_from_file(&path).unwrap();
+        assert_eq!(report, PinFileReport::default());
+        assert_eq!(cache.metrics().elements_pinned_memory_cache, 1);
+
+        cache.unpin(&checksum).unwrap();
+        assert_eq!(cache.metrics().elements_pinned_memory_cache, 0);
+    }
+
+    #[test]
+    fn pin_from_file_applies_other_entries_on_error() {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_testing_options()).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        // not stored yet
+        let missing = Checksum::generate(EMPTY_CONTRACT);
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.path().join("pinned.txt");
+
+        write_pin_file(&cache, &path, &format!("{missing}\n{checksum}\n"));
+        match cache.pin_from_file(&path).unwrap_err() {
+            VmError::CacheErr { msg, .. } => {
+                assert!(
+                    msg.starts_with(&format!("Error pinning modules from pin file: {missing}: ")),
+                    "{msg}"
+                )
+            }
+            e => panic!("Unexpected error: {e:?}"),
+        }
+        assert_eq!(cache.metrics().elements_pinned_memory_cache, 1);
+
+        // the unchanged file is applied again
+        cache.reload_pin_file().unwrap_err();
+        cache.save_wasm(EMPTY_CONTRACT).unwrap();
+        assert_eq!(
+            cache.reload_pin_file().unwrap(),
+            Some(PinFileReport {
+                pinned: 1,
+                unpinned: 0
+            })
+        );
+        assert_eq!(cache.metrics().elements_pinned_memory_cache, 2);
+    }
+
+    #[test]
+    fn reload_pin_file_works() {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_testing_options()).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.path().join("pinned.txt");
+
+        // no pin file
+        assert_eq!(cache.reload_pin_file().unwrap(), None);
+
+        write_pin_file(&cache, &path, "");
+        cache.pin_from_file(&path).unwrap();
+        // unchanged
+        assert_eq!(cache.reload_pin_file().unwrap(), None);
+
+        write_pin_file(&cache, &path, &format!("{checksum}\n"));
+        assert_eq!(
+            cache.reload_pin_file().unwrap(),
+            Some(PinFileReport {
+                pinned: 1,
+                unpinned: 0
+            })
+        );
+        assert_eq!(cache.reload_pin_file().unwrap(), None);
+        assert_eq!(cache.metrics().elements_pinned_memory_cache, 1);
+    }
+
+    #[test]
+    fn watch_pin_file_works() {
+        let cache: Arc<Cache<MockApi, MockStorage, MockQuerier>> =
+            Arc::new(unsafe { Cache::new(make_testing_options()).unwrap() });
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.path().join("pinned.txt");
+
+        write_pin_file(&cache, &path, "");
+        cache.pin_from_file(&path).unwrap();
+        let watcher = cache.watch_pin_file(Duration::from_millis(5));
+
+        write_pin_file(&cache, &path, &format!("{checksum}\n"));
+        let mut attempts = 0;
+        while cache.metrics().elements_pinned_memory_cache == 0 {
+            attempts += 1;
+            assert!(attempts < 1000, "Pin file was not reloaded");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(watcher.last_error(), None);
+
+        write_pin_file(&cache, &path, "nope\n");
+        while watcher.last_error().is_none() {
+            attempts += 1;
+            assert!(attempts < 1000, "Pin file was not reloaded");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        drop(watcher);
+        assert_eq!(cache.metrics().elements_pinned_memory_cache, 1);
+    }
+
     #[test]
     fn loading_without_extension_works() {
         let tmp_dir = TempDir::new().unwrap();
@@ -1444,6 +2633,9 @@ mod tests {
             available_capabilities: default_capabilities(),
             memory_cache_size: TESTING_MEMORY_CACHE_SIZE,
             instance_memory_limit: TESTING_MEMORY_LIMIT,
+            max_concurrent_instances: None,
+            compiler: Compiler::default(),
+            query_cache_size: Size::new(0),
         };
         let cache: Cache<MockApi, MockStorage, MockQuerier> =
             unsafe { Cache::new(options).unwrap() };
@@ -0,0 +1,142 @@
+use std::time::{Duration, Instant};
+
+use wasmer::FunctionEnvMut;
+
+use crate::backend::{BackendApi, Querier, Storage};
+use crate::environment::Environment;
+use crate::errors::VmResult;
+use crate::memory::region_length;
+
+/// A single call from the contract into a host function (import)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HostCall {
+    /// The name of the import, e.g. `db_read`
+    pub name: &'static str,
+    /// The sizes (in bytes) of the data in the regions passed as arguments.
+    /// Arguments that are not regions (like iterator IDs) are not included.
+    pub arg_sizes: Vec<usize>,
+    /// Gas left before the call, measured in [CosmWasm gas](https://github.com/CosmWasm/cosmwasm/blob/main/docs/GAS.md)
+    pub gas_before: u64,
+    /// Gas left after the call, measured in [CosmWasm gas](https://github.com/CosmWasm/cosmwasm/blob/main/docs/GAS.md)
+    pub gas_after: u64,
+    /// The time spent in the host function
+    pub duration: Duration,
+}
+
+impl HostCall {
+    /// The gas used by this call
+    pub fn gas_used(&self) -> u64 {
+        self.gas_before.saturating_sub(self.gas_after)
+    }
+}
+
+/// The host function calls recorded by an instance with tracing enabled.
+///
+/// See [`InstanceOptions::trace`](crate::InstanceOptions::trace) and [`Instance::take_call_trace`](crate::Instance::take_call_trace).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CallTrace {
+    /// All host function calls in the order they were executed
+    pub calls: Vec<HostCall>,
+}
+
+impl CallTrace {
+    /// The gas used by all host function calls
+    pub fn total_gas_used(&self) -> u64 {
+        self.calls.iter().map(HostCall::gas_used).sum()
+    }
+
+    /// The time spent in all host function calls
+    pub fn total_duration(&self) -> Duration {
+        self.calls.iter().map(|call| call.duration).sum()
+    }
+}
+
+/// Executes the host function `call` and records it in the call trace if tracing
+/// is enabled for the instance. `region_ptrs` are the arguments pointing to regions.
+/// The call is reported to the instrumentation hooks of the instance if set.
+pub(crate) fn trace_host_call<A, S, Q, R>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    name: &'static str,
+    region_ptrs: &[u32],
+    call: impl FnOnce(FunctionEnvMut<Environment<A, S, Q>>) -> VmResult<R>,
+) -> VmResult<R>
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+{
+    let tracing = env.data().is_tracing();
+    let hooks = env.data().instrumentation();
+    if !tracing && hooks.is_none() {
+        return call(env);
+    }
+
+    let (data, mut store) = env.data_and_store_mut();
+    let arg_sizes = if tracing {
+        region_ptrs
+            .iter()
+            .map(|&ptr| region_length(&data.memory(&store), ptr).unwrap_or_default())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let gas_before = data.get_gas_left(&mut store);
+    if let Some(hooks) = &hooks {
+        hooks.on_host_call_start(name, gas_before);
+    }
+
+    let start = Instant::now();
+    let result = call(env.as_mut());
+    let duration = start.elapsed();
+
+    let (data, mut store) = env.data_and_store_mut();
+    let gas_after = data.get_gas_left(&mut store);
+    if let Some(hooks) = hooks {
+        hooks.on_host_call_end(name, gas_after, duration, result.is_ok());
+    }
+    if tracing {
+        data.record_host_call(HostCall {
+            name,
+            arg_sizes,
+            gas_before,
+            gas_after,
+            duration,
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host_call(gas_before: u64, gas_after: u64, millis: u64) -> HostCall {
+        HostCall {
+            name: "db_read",
+            arg_sizes: vec![3],
+            gas_before,
+            gas_after,
+            duration: Duration::from_millis(millis),
+        }
+    }
+
+    #[test]
+    fn host_call_gas_used_works() {
+        assert_eq!(host_call(100, 70, 0).gas_used(), 30);
+        assert_eq!(host_call(100, 100, 0).gas_used(), 0);
+        assert_eq!(host_call(100, 120, 0).gas_used(), 0);
+    }
+
+    #[test]
+    fn call_trace_totals_work() {
+        let trace = CallTrace::default();
+        assert_eq!(trace.total_gas_used(), 0);
+        assert_eq!(trace.total_duration(), Duration::ZERO);
+
+        let trace = CallTrace {
+            calls: vec![host_call(100, 70, 3), host_call(70, 50, 4)],
+        };
+        assert_eq!(trace.total_gas_used(), 50);
+        assert_eq!(trace.total_duration(), Duration::from_millis(7));
+    }
+}
@@ -12,7 +12,7 @@ use cosmwasm_std::{
 use crate::backend::{BackendApi, Querier, Storage};
 use crate::conversion::ref_to_u32;
 use crate::errors::{VmError, VmResult};
-use crate::instance::Instance;
+use crate::instance::{CallInfo, Instance};
 use crate::serde::{from_slice, to_vec};
 
 /// The limits in here protect the host from allocating an unreasonable amount of memory
@@ -54,45 +54,19 @@ mod read_limits {
     /// Max length (in bytes) of the result data from a ibc_packet_timeout call.
     #[cfg(feature = "stargate")]
     pub const RESULT_IBC_PACKET_TIMEOUT: usize = 64 * MI;
+    /// Max length (in bytes) of result data that is written in chunks. This applies to all calls.
+    pub const RESULT_CHUNKED: usize = 512 * MI;
 }
 
 /// The limits for the JSON deserialization.
 ///
 /// Those limits are not used when the Rust JSON deserializer is bypassed by using the
 /// public `call_*_raw` functions directly.
-mod deserialization_limits {
+pub(crate) mod deserialization_limits {
     /// A kibi (kilo binary)
     const KI: usize = 1024;
-    /// Max length (in bytes) of the result data from an instantiate call.
-    pub const RESULT_INSTANTIATE: usize = 256 * KI;
-    /// Max length (in bytes) of the result data from an execute call.
-    pub const RESULT_EXECUTE: usize = 256 * KI;
-    /// Max length (in bytes) of the result data from a migrate call.
-    pub const RESULT_MIGRATE: usize = 256 * KI;
-    /// Max length (in bytes) of the result data from a sudo call.
-    pub const RESULT_SUDO: usize = 256 * KI;
-    /// Max length (in bytes) of the result data from a reply call.
-    pub const RESULT_REPLY: usize = 256 * KI;
-    /// Max length (in bytes) of the result data from a query call.
-    pub const RESULT_QUERY: usize = 256 * KI;
-    /// Max length (in bytes) of the result data from a ibc_channel_open call.
-    #[cfg(feature = "stargate")]
-    pub const RESULT_IBC_CHANNEL_OPEN: usize = 256 * KI;
-    /// Max length (in bytes) of the result data from a ibc_channel_connect call.
-    #[cfg(feature = "stargate")]
-    pub const RESULT_IBC_CHANNEL_CONNECT: usize = 256 * KI;
-    /// Max length (in bytes) of the result data from a ibc_channel_close call.
-    #[cfg(feature = "stargate")]
-    pub const RESULT_IBC_CHANNEL_CLOSE: usize = 256 * KI;
-    /// Max length (in bytes) of the result data from a ibc_packet_receive call.
-    #[cfg(feature = "stargate")]
-    pub const RESULT_IBC_PACKET_RECEIVE: usize = 256 * KI;
-    /// Max length (in bytes) of the result data from a ibc_packet_ack call.
-    #[cfg(feature = "stargate")]
-    pub const RESULT_IBC_PACKET_ACK: usize = 256 * KI;
-    /// Max length (in bytes) of the result data from a ibc_packet_timeout call.
-    #[cfg(feature = "stargate")]
-    pub const RESULT_IBC_PACKET_TIMEOUT: usize = 256 * KI;
+    /// Max length (in bytes) of the env argument when extracting the contract address from it.
+    pub const ENV: usize = 256 * KI;
 }
 
 pub fn call_instantiate<A, S, Q, U>(
@@ -109,9 +83,9 @@ where
 {
     let env = to_vec(env)?;
     let info = to_vec(info)?;
+    let limits = instance.response_limits();
     let data = call_instantiate_raw(instance, &env, &info, msg)?;
-    let result: ContractResult<Response<U>> =
-        from_slice(&data, deserialization_limits::RESULT_INSTANTIATE)?;
+    let result: ContractResult<Response<U>> = from_slice(&data, limits.max_response_length)?;
     Ok(result)
 }
 
@@ -129,9 +103,9 @@ where
 {
     let env = to_vec(env)?;
     let info = to_vec(info)?;
+    let limits = instance.response_limits();
     let data = call_execute_raw(instance, &env, &info, msg)?;
-    let result: ContractResult<Response<U>> =
-        from_slice(&data, deserialization_limits::RESULT_EXECUTE)?;
+    let result: ContractResult<Response<U>> = from_slice(&data, limits.max_response_length)?;
     Ok(result)
 }
 
@@ -147,9 +121,9 @@ where
     U: DeserializeOwned + CustomMsg,
 {
     let env = to_vec(env)?;
+    let limits = instance.response_limits();
     let data = call_migrate_raw(instance, &env, msg)?;
-    let result: ContractResult<Response<U>> =
-        from_slice(&data, deserialization_limits::RESULT_MIGRATE)?;
+    let result: ContractResult<Response<U>> = from_slice(&data, limits.max_response_length)?;
     Ok(result)
 }
 
@@ -165,9 +139,9 @@ where
     U: DeserializeOwned + CustomMsg,
 {
     let env = to_vec(env)?;
+    let limits = instance.response_limits();
     let data = call_sudo_raw(instance, &env, msg)?;
-    let result: ContractResult<Response<U>> =
-        from_slice(&data, deserialization_limits::RESULT_SUDO)?;
+    let result: ContractResult<Response<U>> = from_slice(&data, limits.max_response_length)?;
     Ok(result)
 }
 
@@ -184,9 +158,9 @@ where
 {
     let env = to_vec(env)?;
     let msg = to_vec(msg)?;
+    let limits = instance.response_limits();
     let data = call_reply_raw(instance, &env, &msg)?;
-    let result: ContractResult<Response<U>> =
-        from_slice(&data, deserialization_limits::RESULT_REPLY)?;
+    let result: ContractResult<Response<U>> = from_slice(&data, limits.max_response_length)?;
     Ok(result)
 }
 
@@ -201,9 +175,9 @@ where
     Q: Querier + 'static,
 {
     let env = to_vec(env)?;
+    let limits = instance.response_limits();
     let data = call_query_raw(instance, &env, msg)?;
-    let result: ContractResult<QueryResponse> =
-        from_slice(&data, deserialization_limits::RESULT_QUERY)?;
+    let result: ContractResult<QueryResponse> = from_slice(&data, limits.max_query_result_length)?;
     // Ensure query response is valid JSON
     if let ContractResult::Ok(binary_response) = &result {
         serde_json::from_slice::<serde_json::Value>(binary_response.as_slice())
@@ -226,9 +200,10 @@ where
 {
     let env = to_vec(env)?;
     let msg = to_vec(msg)?;
+    let limits = instance.response_limits();
     let data = call_ibc_channel_open_raw(instance, &env, &msg)?;
     let result: ContractResult<Option<Ibc3ChannelOpenResponse>> =
-        from_slice(&data, deserialization_limits::RESULT_IBC_CHANNEL_OPEN)?;
+        from_slice(&data, limits.max_response_length)?;
     Ok(result)
 }
 
@@ -246,8 +221,10 @@ where
 {
     let env = to_vec(env)?;
     let msg = to_vec(msg)?;
+    let limits = instance.response_limits();
     let data = call_ibc_channel_connect_raw(instance, &env, &msg)?;
-    let result = from_slice(&data, deserialization_limits::RESULT_IBC_CHANNEL_CONNECT)?;
+    let result: ContractResult<IbcBasicResponse<U>> =
+        from_slice(&data, limits.max_response_length)?;
     Ok(result)
 }
 
@@ -265,8 +242,10 @@ where
 {
     let env = to_vec(env)?;
     let msg = to_vec(msg)?;
+    let limits = instance.response_limits();
     let data = call_ibc_channel_close_raw(instance, &env, &msg)?;
-    let result = from_slice(&data, deserialization_limits::RESULT_IBC_CHANNEL_CLOSE)?;
+    let result: ContractResult<IbcBasicResponse<U>> =
+        from_slice(&data, limits.max_response_length)?;
     Ok(result)
 }
 
@@ -284,8 +263,10 @@ where
 {
     let env = to_vec(env)?;
     let msg = to_vec(msg)?;
+    let limits = instance.response_limits();
     let data = call_ibc_packet_receive_raw(instance, &env, &msg)?;
-    let result = from_slice(&data, deserialization_limits::RESULT_IBC_PACKET_RECEIVE)?;
+    let result: ContractResult<IbcReceiveResponse<U>> =
+        from_slice(&data, limits.max_response_length)?;
     Ok(result)
 }
 
@@ -303,8 +284,10 @@ where
 {
     let env = to_vec(env)?;
     let msg = to_vec(msg)?;
+    let limits = instance.response_limits();
     let data = call_ibc_packet_ack_raw(instance, &env, &msg)?;
-    let result = from_slice(&data, deserialization_limits::RESULT_IBC_PACKET_ACK)?;
+    let result: ContractResult<IbcBasicResponse<U>> =
+        from_slice(&data, limits.max_response_length)?;
     Ok(result)
 }
 
@@ -322,13 +305,16 @@ where
 {
     let env = to_vec(env)?;
     let msg = to_vec(msg)?;
+    let limits = instance.response_limits();
     let data = call_ibc_packet_timeout_raw(instance, &env, &msg)?;
-    let result = from_slice(&data, deserialization_limits::RESULT_IBC_PACKET_TIMEOUT)?;
+    let result: ContractResult<IbcBasicResponse<U>> =
+        from_slice(&data, limits.max_response_length)?;
     Ok(result)
 }
 
 /// Calls Wasm export "instantiate" and returns raw data from the contract.
-/// The result is length limited to prevent abuse but otherwise unchecked.
+/// The result is checked against the [`ResponseLimits`](crate::ResponseLimits) of the
+/// instance but otherwise unparsed.
 pub fn call_instantiate_raw<A, S, Q>(
     instance: &mut Instance<A, S, Q>,
     env: &[u8],
@@ -341,16 +327,19 @@ where
     Q: Querier + 'static,
 {
     instance.set_storage_readonly(false);
-    call_raw(
+    let data = call_raw(
         instance,
         "instantiate",
         &[env, info, msg],
         read_limits::RESULT_INSTANTIATE,
-    )
+    )?;
+    instance.response_limits().check_response_result(&data)?;
+    Ok(data)
 }
 
 /// Calls Wasm export "execute" and returns raw data from the contract.
-/// The result is length limited to prevent abuse but otherwise unchecked.
+/// The result is checked against the [`ResponseLimits`](crate::ResponseLimits) of the
+/// instance but otherwise unparsed.
 pub fn call_execute_raw<A, S, Q>(
     instance: &mut Instance<A, S, Q>,
     env: &[u8],
@@ -363,16 +352,19 @@ where
     Q: Querier + 'static,
 {
     instance.set_storage_readonly(false);
-    call_raw(
+    let data = call_raw(
         instance,
         "execute",
         &[env, info, msg],
         read_limits::RESULT_EXECUTE,
-    )
+    )?;
+    instance.response_limits().check_response_result(&data)?;
+    Ok(data)
 }
 
 /// Calls Wasm export "migrate" and returns raw data from the contract.
-/// The result is length limited to prevent abuse but otherwise unchecked.
+/// The result is checked against the [`ResponseLimits`](crate::ResponseLimits) of the
+/// instance but otherwise unparsed.
 pub fn call_migrate_raw<A, S, Q>(
     instance: &mut Instance<A, S, Q>,
     env: &[u8],
@@ -384,16 +376,19 @@ where
     Q: Querier + 'static,
 {
     instance.set_storage_readonly(false);
-    call_raw(
+    let data = call_raw(
         instance,
         "migrate",
         &[env, msg],
         read_limits::RESULT_MIGRATE,
-    )
+    )?;
+    instance.response_limits().check_response_result(&data)?;
+    Ok(data)
 }
 
 /// Calls Wasm export "sudo" and returns raw data from the contract.
-/// The result is length limited to prevent abuse but otherwise unchecked.
+/// The result is checked against the [`ResponseLimits`](crate::ResponseLimits) of the
+/// instance but otherwise unparsed.
 pub fn call_sudo_raw<A, S, Q>(
     instance: &mut Instance<A, S, Q>,
     env: &[u8],
@@ -405,11 +400,14 @@ where
     Q: Querier + 'static,
 {
     instance.set_storage_readonly(false);
-    call_raw(instance, "sudo", &[env, msg], read_limits::RESULT_SUDO)
+    let data = call_raw(instance, "sudo", &[env, msg], read_limits::RESULT_SUDO)?;
+    instance.response_limits().check_response_result(&data)?;
+    Ok(data)
 }
 
 /// Calls Wasm export "reply" and returns raw data from the contract.
-/// The result is length limited to prevent abuse but otherwise unchecked.
+/// The result is checked against the [`ResponseLimits`](crate::ResponseLimits) of the
+/// instance but otherwise unparsed.
 pub fn call_reply_raw<A, S, Q>(
     instance: &mut Instance<A, S, Q>,
     env: &[u8],
@@ -421,11 +419,14 @@ where
     Q: Querier + 'static,
 {
     instance.set_storage_readonly(false);
-    call_raw(instance, "reply", &[env, msg], read_limits::RESULT_REPLY)
+    let data = call_raw(instance, "reply", &[env, msg], read_limits::RESULT_REPLY)?;
+    instance.response_limits().check_response_result(&data)?;
+    Ok(data)
 }
 
 /// Calls Wasm export "query" and returns raw data from the contract.
-/// The result is length limited to prevent abuse but otherwise unchecked.
+/// The result is checked against the [`ResponseLimits`](crate::ResponseLimits) of the
+/// instance but otherwise unparsed.
 pub fn call_query_raw<A, S, Q>(
     instance: &mut Instance<A, S, Q>,
     env: &[u8],
@@ -437,7 +438,9 @@ where
     Q: Querier + 'static,
 {
     instance.set_storage_readonly(true);
-    call_raw(instance, "query", &[env, msg], read_limits::RESULT_QUERY)
+    let data = call_raw(instance, "query", &[env, msg], read_limits::RESULT_QUERY)?;
+    instance.response_limits().check_query_result(&data)?;
+    Ok(data)
 }
 
 #[cfg(feature = "stargate")]
@@ -452,12 +455,14 @@ where
     Q: Querier + 'static,
 {
     instance.set_storage_readonly(false);
-    call_raw(
+    let data = call_raw(
         instance,
         "ibc_channel_open",
         &[env, msg],
         read_limits::RESULT_IBC_CHANNEL_OPEN,
-    )
+    )?;
+    instance.response_limits().check_result_length(&data)?;
+    Ok(data)
 }
 
 #[cfg(feature = "stargate")]
@@ -472,12 +477,14 @@ where
     Q: Querier + 'static,
 {
     instance.set_storage_readonly(false);
-    call_raw(
+    let data = call_raw(
         instance,
         "ibc_channel_connect",
         &[env, msg],
         read_limits::RESULT_IBC_CHANNEL_CONNECT,
-    )
+    )?;
+    instance.response_limits().check_response_result(&data)?;
+    Ok(data)
 }
 
 #[cfg(feature = "stargate")]
@@ -492,12 +499,14 @@ where
     Q: Querier + 'static,
 {
     instance.set_storage_readonly(false);
-    call_raw(
+    let data = call_raw(
         instance,
         "ibc_channel_close",
         &[env, msg],
         read_limits::RESULT_IBC_CHANNEL_CLOSE,
-    )
+    )?;
+    instance.response_limits().check_response_result(&data)?;
+    Ok(data)
 }
 
 #[cfg(feature = "stargate")]
@@ -512,12 +521,14 @@ where
     Q: Querier + 'static,
 {
     instance.set_storage_readonly(false);
-    call_raw(
+    let data = call_raw(
         instance,
         "ibc_packet_receive",
         &[env, msg],
         read_limits::RESULT_IBC_PACKET_RECEIVE,
-    )
+    )?;
+    instance.response_limits().check_response_result(&data)?;
+    Ok(data)
 }
 
 #[cfg(feature = "stargate")]
@@ -532,12 +543,14 @@ where
     Q: Querier + 'static,
 {
     instance.set_storage_readonly(false);
-    call_raw(
+    let data = call_raw(
         instance,
         "ibc_packet_ack",
         &[env, msg],
         read_limits::RESULT_IBC_PACKET_ACK,
-    )
+    )?;
+    instance.response_limits().check_response_result(&data)?;
+    Ok(data)
 }
 
 #[cfg(feature = "stargate")]
@@ -552,16 +565,24 @@ where
     Q: Querier + 'static,
 {
     instance.set_storage_readonly(false);
-    call_raw(
+    let data = call_raw(
         instance,
         "ibc_packet_timeout",
         &[env, msg],
         read_limits::RESULT_IBC_PACKET_TIMEOUT,
-    )
+    )?;
+    instance.response_limits().check_response_result(&data)?;
+    Ok(data)
 }
 
 /// Calls a function with the given arguments.
 /// The exported function must return exactly one result (an offset to the result Region).
+///
+/// If the function returns the null pointer instead, the result data is the data written
+/// in chunks using the `write_region_chunked` import during the call. This allows results
+/// longer than `result_max_length`, up to [`read_limits::RESULT_CHUNKED`].
+///
+/// The outcome of the call is recorded and available via [`Instance::last_call_info`].
 pub(crate) fn call_raw<A, S, Q>(
     instance: &mut Instance<A, S, Q>,
     name: &str,
@@ -573,14 +594,50 @@ where
     S: Storage + 'static,
     Q: Querier + 'static,
 {
+    let gas_before = instance.get_gas_left();
+    let result = call_raw_unrecorded(instance, name, args, result_max_length);
+    let gas_used = gas_before.saturating_sub(instance.get_gas_left());
+    instance.set_last_call_info(CallInfo {
+        entry_point: name.to_string(),
+        gas_used,
+        success: result.is_ok(),
+    });
+    result
+}
+
+fn call_raw_unrecorded<A, S, Q>(
+    instance: &mut Instance<A, S, Q>,
+    name: &str,
+    args: &[&[u8]],
+    result_max_length: usize,
+) -> VmResult<Vec<u8>>
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+{
+    // All entry points receive the env as the first argument
+    instance.set_call_env(args.first().map(|env| env.to_vec()));
+
     let mut arg_region_ptrs = Vec::<Value>::with_capacity(args.len());
     for arg in args {
         let region_ptr = instance.allocate(arg.len())?;
         instance.write_memory(region_ptr, arg)?;
         arg_region_ptrs.push(region_ptr.into());
     }
-    let result = instance.call_function1(name, &arg_region_ptrs)?;
-    let res_region_ptr = ref_to_u32(&result)?;
+    instance.start_chunked_response(read_limits::RESULT_CHUNKED);
+    let result = instance.call_function1(name, &arg_region_ptrs);
+    let chunked_data = instance.finish_chunked_response().unwrap_or_default();
+    let res_region_ptr = ref_to_u32(&result?)?;
+    if res_region_ptr == 0 {
+        return Ok(chunked_data);
+    }
+    if !chunked_data.is_empty() {
+        instance.deallocate(res_region_ptr)?;
+        return Err(VmError::generic_err(
+            "Result data was written in chunks but a result region was returned",
+        ));
+    }
     let data = instance.read_memory(res_region_ptr, result_max_length)?;
     // free return value in wasm (arguments were freed in wasm code)
     instance.deallocate(res_region_ptr)?;
@@ -591,10 +648,13 @@ where
 mod tests {
     use super::*;
     use crate::testing::{
-        mock_env, mock_info, mock_instance, mock_instance_with_options, MockInstanceOptions,
+        mock_env, mock_info, mock_instance, mock_instance_options, mock_instance_with_options,
+        MockApi, MockInstanceOptions, MockQuerier, MockStorage,
     };
+    use crate::{Backend, BackendResult, InstanceOptions, ResponseLimits};
     use cosmwasm_std::{coins, from_json, to_json_string, Empty};
     use sha2::{Digest, Sha256};
+    use std::cell::RefCell;
 
     static CONTRACT: &[u8] = include_bytes!("../testdata/hackatom.wasm");
     static CYBERPUNK: &[u8] = include_bytes!("../testdata/cyberpunk.wasm");
@@ -654,6 +714,41 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn call_execute_raw_checks_response_limits() {
+        let mut instance = mock_instance(CONTRACT, &[]);
+
+        // init
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+
+        // release sends the funds using one message
+        instance
+            .set_response_limits(ResponseLimits {
+                max_messages: 0,
+                ..ResponseLimits::DEFAULT
+            })
+            .unwrap();
+        let env = to_vec(&mock_env()).unwrap();
+        let info = to_vec(&mock_info("verifies", &[])).unwrap();
+        let err = call_execute_raw(&mut instance, &env, &info, br#"{"release":{}}"#).unwrap_err();
+        assert!(matches!(
+            err,
+            VmError::ResponseLimitExceeded { kind, count: 1, limit: 0, .. } if kind == "messages"
+        ));
+
+        // invalid limits are rejected
+        instance
+            .set_response_limits(ResponseLimits {
+                max_response_length: 0,
+                ..ResponseLimits::DEFAULT
+            })
+            .unwrap_err();
+    }
+
     #[test]
     fn call_execute_runs_out_of_gas() {
         let mut instance = mock_instance(CYBERPUNK, &[]);
@@ -672,6 +767,29 @@ mod tests {
         assert!(matches!(err, VmError::GasDepletion { .. }));
     }
 
+    #[test]
+    fn call_raw_records_call_info() {
+        let mut instance = mock_instance(CYBERPUNK, &[]);
+        assert_eq!(instance.last_call_info(), None);
+
+        let info = mock_info("creator", &[]);
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, br#"{}"#)
+            .unwrap()
+            .unwrap();
+        let call_info = instance.last_call_info().unwrap();
+        assert_eq!(call_info.entry_point, "instantiate");
+        assert!(call_info.success);
+        assert!(call_info.gas_used > 0);
+
+        let info = mock_info("looper", &[]);
+        let msg = br#"{"cpu_loop":{}}"#;
+        call_execute::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg).unwrap_err();
+        let call_info = instance.last_call_info().unwrap();
+        assert_eq!(call_info.entry_point, "execute");
+        assert!(!call_info.success);
+        assert!(call_info.gas_used > 0);
+    }
+
     #[test]
     fn call_execute_handles_panic() {
         let mut instance = mock_instance(CYBERPUNK, &[]);
@@ -696,6 +814,95 @@ mod tests {
         }
     }
 
+    #[test]
+    fn call_execute_calls_backend_api_hooks() {
+        thread_local! {
+            static LOGS: RefCell<Vec<(&'static str, Option<String>, String)>> = RefCell::default();
+        }
+
+        #[derive(Copy, Clone, Default)]
+        struct RecordingApi(MockApi);
+
+        impl BackendApi for RecordingApi {
+            fn canonical_address(&self, human: &str) -> BackendResult<Vec<u8>> {
+                self.0.canonical_address(human)
+            }
+
+            fn human_address(&self, canonical: &[u8]) -> BackendResult<String> {
+                self.0.human_address(canonical)
+            }
+
+            fn debug_enabled(&self) -> bool {
+                true
+            }
+
+            fn debug(&self, contract_address: Option<&str>, message: &str) {
+                LOGS.with(|logs| {
+                    logs.borrow_mut().push((
+                        "debug",
+                        contract_address.map(String::from),
+                        message.to_string(),
+                    ))
+                });
+            }
+
+            fn abort(&self, contract_address: Option<&str>, message: &str) {
+                LOGS.with(|logs| {
+                    logs.borrow_mut().push((
+                        "abort",
+                        contract_address.map(String::from),
+                        message.to_string(),
+                    ))
+                });
+            }
+        }
+
+        let backend = Backend {
+            api: RecordingApi::default(),
+            storage: MockStorage::default(),
+            querier: MockQuerier::<Empty>::new(&[]),
+        };
+        let (_, memory_limit) = mock_instance_options();
+        let options = InstanceOptions {
+            gas_limit: 70_000_000_000,
+            trace: false,
+            response_limits: ResponseLimits::DEFAULT,
+            instrumentation: None,
+        };
+        let mut instance = Instance::from_code(CYBERPUNK, backend, options, memory_limit).unwrap();
+
+        let info = mock_info("creator", &[]);
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, br#"{}"#)
+            .unwrap()
+            .unwrap();
+
+        let msg = br#"{"debug":{}}"#;
+        call_execute::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+        let msg = br#"{"panic":{}}"#;
+        call_execute::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg).unwrap_err();
+
+        let logs = LOGS.with(|logs| logs.take());
+        let contract_address = Some(mock_env().contract.address.into_string());
+        assert_eq!(
+            logs.first().unwrap(),
+            &(
+                "debug",
+                contract_address.clone(),
+                "Hey, ho – let's go".to_string()
+            )
+        );
+        assert_eq!(
+            logs.iter().filter(|(kind, ..)| *kind == "debug").count(),
+            20
+        );
+        let (kind, address, message) = logs.last().unwrap();
+        assert_eq!(*kind, "abort");
+        assert_eq!(*address, contract_address);
+        assert!(message.starts_with("panicked at 'This page intentionally faulted'"));
+    }
+
     #[test]
     fn call_execute_handles_unreachable() {
         let mut instance = mock_instance(CYBERPUNK, &[]);
@@ -761,6 +968,65 @@ mod tests {
         assert_eq!(query_response.as_slice(), b"{\"verifier\":\"verifies\"}");
     }
 
+    /// A contract with a query export that writes its result in two chunks and then
+    /// returns `result`. All arguments are written into the same region.
+    fn chunked_query_contract(result: u32) -> Vec<u8> {
+        wat::parse_str(format!(
+            r#"(module
+                (import "env" "write_region_chunked" (func $write_region_chunked (param i32) (result i32)))
+                (memory 1)
+                (export "memory" (memory 0))
+                ;; Region {{ offset: 64, capacity: 5, length: 5 }}
+                (data (i32.const 8) "\40\00\00\00\05\00\00\00\05\00\00\00")
+                (data (i32.const 64) "Hello")
+                ;; Region {{ offset: 2048, capacity: 60000, length: 0 }}
+                (data (i32.const 1024) "\00\08\00\00\60\ea\00\00\00\00\00\00")
+
+                (func (export "interface_version_8"))
+                (func (export "allocate") (param i32) (result i32) i32.const 1024)
+                (func (export "deallocate") (param i32))
+                (func (export "query") (param i32 i32) (result i32)
+                    i32.const 8
+                    call $write_region_chunked
+                    drop
+                    i32.const 8
+                    call $write_region_chunked
+                    drop
+                    i32.const {result}
+                )
+            )"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn call_query_raw_works_for_chunked_result() {
+        let wasm = chunked_query_contract(0);
+        let mut instance = mock_instance(&wasm, &[]);
+
+        let result = call_query_raw(&mut instance, b"{}", b"{}").unwrap();
+        assert_eq!(result, b"HelloHello");
+
+        // chunks do not leak into the next call
+        let result = call_query_raw(&mut instance, b"{}", b"{}").unwrap();
+        assert_eq!(result, b"HelloHello");
+    }
+
+    #[test]
+    fn call_query_raw_fails_for_chunks_and_result_region() {
+        let wasm = chunked_query_contract(8);
+        let mut instance = mock_instance(&wasm, &[]);
+
+        let err = call_query_raw(&mut instance, b"{}", b"{}").unwrap_err();
+        match err {
+            VmError::GenericErr { msg, .. } => assert_eq!(
+                msg,
+                "Result data was written in chunks but a result region was returned"
+            ),
+            err => panic!("Unexpected error: {err:?}"),
+        }
+    }
+
     #[test]
     fn float_instrs_are_deterministic() {
         #[derive(Debug, serde::Serialize, serde::Deserialize)]
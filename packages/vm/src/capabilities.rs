@@ -1,15 +1,119 @@
 use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
 
 use crate::static_analysis::ExportInfo;
 
 const REQUIRES_PREFIX: &str = "requires_";
+const VERSION_SEPARATOR: &str = ":v";
+
+/// A capability the chain provides to contracts, optionally with a version.
+///
+/// Capabilities are plain strings like `iterator` or `cosmwasm_1_4`. Chains can define
+/// their own capabilities for custom host features. Those can be versioned using the
+/// format `<name>:v<version>`, e.g. `token_factory:v1`. Contracts require them by exporting
+/// a marker function with the same name, e.g. `requires_token_factory:v1`.
+///
+/// An available versioned capability satisfies requirements of the same name with an equal
+/// or lower version as well as unversioned requirements of the same name.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Capability {
+    name: String,
+    version: Option<u32>,
+}
+
+impl Capability {
+    pub fn new(name: impl Into<String>, version: Option<u32>) -> Self {
+        Self {
+            name: name.into(),
+            version,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> Option<u32> {
+        self.version
+    }
+
+    /// Returns true if this (available) capability satisfies the given requirement.
+    pub fn satisfies(&self, required: &Capability) -> bool {
+        if self.name != required.name {
+            return false;
+        }
+        match (self.version, required.version) {
+            (_, None) => true,
+            (Some(available), Some(required)) => available >= required,
+            (None, Some(_)) => false,
+        }
+    }
+}
+
+impl FromStr for Capability {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (name, version) = match input.rsplit_once(VERSION_SEPARATOR) {
+            Some((name, version)) => {
+                let version = version
+                    .parse::<u32>()
+                    .map_err(|_| format!("Invalid capability version in '{input}'"))?;
+                (name, Some(version))
+            }
+            None => (input, None),
+        };
+        if name.is_empty() || name.contains(':') {
+            return Err(format!("Invalid capability name in '{input}'"));
+        }
+        Ok(Capability::new(name, version))
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.version {
+            Some(version) => write!(f, "{}{VERSION_SEPARATOR}{version}", self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// Converts a string to a capability. In contrast to [`Capability::from_str`], this
+/// never fails: strings that are not valid versioned capabilities become unversioned
+/// capabilities that only match the exact same string.
+impl From<&str> for Capability {
+    fn from(input: &str) -> Self {
+        Capability::from_str(input).unwrap_or_else(|_| Capability::new(input, None))
+    }
+}
+
+impl From<String> for Capability {
+    fn from(input: String) -> Self {
+        Capability::from(input.as_str())
+    }
+}
+
+/// Returns true if the required capability is contained in the available ones, either as an
+/// exact match or as a higher version of the same capability (see [`Capability::satisfies`]).
+pub fn is_capability_available(
+    required: &str,
+    available_capabilities: &HashSet<Capability>,
+) -> bool {
+    let required = Capability::from(required);
+    available_capabilities
+        .iter()
+        .any(|available| available.satisfies(&required))
+}
 
 /// Takes a comma-separated string, splits it by commas, removes empty elements and returns a set of capabilities.
 /// This can be used e.g. to initialize the cache.
-pub fn capabilities_from_csv(csv: &str) -> HashSet<String> {
+pub fn capabilities_from_csv(csv: &str) -> HashSet<Capability> {
     csv.split(',')
-        .map(|x| x.trim().to_string())
+        .map(|x| x.trim())
         .filter(|f| !f.is_empty())
+        .map(Capability::from)
         .collect()
 }
 
@@ -40,9 +144,9 @@ mod tests {
     fn capabilities_from_csv_works() {
         let set = capabilities_from_csv("foo, bar,baz ");
         assert_eq!(set.len(), 3);
-        assert!(set.contains("foo"));
-        assert!(set.contains("bar"));
-        assert!(set.contains("baz"));
+        assert!(set.contains(&Capability::from("foo")));
+        assert!(set.contains(&Capability::from("bar")));
+        assert!(set.contains(&Capability::from("baz")));
     }
 
     #[test]
@@ -51,12 +155,76 @@ mod tests {
         assert_eq!(set.len(), 0);
         let set = capabilities_from_csv("a,,b");
         assert_eq!(set.len(), 2);
-        assert!(set.contains("a"));
-        assert!(set.contains("b"));
+        assert!(set.contains(&Capability::from("a")));
+        assert!(set.contains(&Capability::from("b")));
         let set = capabilities_from_csv("a,b,");
         assert_eq!(set.len(), 2);
-        assert!(set.contains("a"));
-        assert!(set.contains("b"));
+        assert!(set.contains(&Capability::from("a")));
+        assert!(set.contains(&Capability::from("b")));
+    }
+
+    #[test]
+    fn capability_from_str_works() {
+        assert_eq!(
+            "iterator".parse::<Capability>().unwrap(),
+            Capability::new("iterator", None)
+        );
+        assert_eq!(
+            "cosmwasm_1_4".parse::<Capability>().unwrap(),
+            Capability::new("cosmwasm_1_4", None)
+        );
+        assert_eq!(
+            "token_factory:v1".parse::<Capability>().unwrap(),
+            Capability::new("token_factory", Some(1))
+        );
+        assert_eq!(
+            "token_factory:v12".parse::<Capability>().unwrap(),
+            Capability::new("token_factory", Some(12))
+        );
+
+        "".parse::<Capability>().unwrap_err();
+        ":v1".parse::<Capability>().unwrap_err();
+        "token_factory:v".parse::<Capability>().unwrap_err();
+        "token_factory:vx".parse::<Capability>().unwrap_err();
+        "token_factory:1".parse::<Capability>().unwrap_err();
+        "a:b:v1".parse::<Capability>().unwrap_err();
+    }
+
+    #[test]
+    fn capability_display_works() {
+        assert_eq!(Capability::new("iterator", None).to_string(), "iterator");
+        assert_eq!(
+            Capability::new("token_factory", Some(3)).to_string(),
+            "token_factory:v3"
+        );
+    }
+
+    #[test]
+    fn capability_satisfies_works() {
+        let v2 = Capability::new("token_factory", Some(2));
+        assert!(v2.satisfies(&Capability::new("token_factory", None)));
+        assert!(v2.satisfies(&Capability::new("token_factory", Some(1))));
+        assert!(v2.satisfies(&Capability::new("token_factory", Some(2))));
+        assert!(!v2.satisfies(&Capability::new("token_factory", Some(3))));
+        assert!(!v2.satisfies(&Capability::new("token", Some(1))));
+
+        let unversioned = Capability::new("token_factory", None);
+        assert!(unversioned.satisfies(&Capability::new("token_factory", None)));
+        assert!(!unversioned.satisfies(&Capability::new("token_factory", Some(1))));
+    }
+
+    #[test]
+    fn is_capability_available_works() {
+        let available = capabilities_from_csv("iterator,token_factory:v2,foo:bar");
+        assert!(is_capability_available("iterator", &available));
+        assert!(is_capability_available("token_factory", &available));
+        assert!(is_capability_available("token_factory:v1", &available));
+        assert!(is_capability_available("token_factory:v2", &available));
+        assert!(!is_capability_available("token_factory:v3", &available));
+        assert!(!is_capability_available("staking", &available));
+        // invalid capabilities only match exactly
+        assert!(is_capability_available("foo:bar", &available));
+        assert!(!is_capability_available("foo", &available));
     }
 
     #[test]
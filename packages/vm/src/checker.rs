@@ -0,0 +1,182 @@
+//! Validation of contracts outside of a chain.
+//!
+//! This performs the same checks as [`Cache::save_wasm`](crate::Cache::save_wasm) does
+//! when a contract is uploaded, without the need to set up a cache. It is intended for
+//! CI pipelines and other tools that want to validate contracts before uploading them.
+
+use std::collections::{BTreeSet, HashSet};
+
+use cosmwasm_std::Checksum;
+
+use crate::capabilities::{is_capability_available, Capability};
+use crate::compatibility::{check_wasm_with_features, WasmFeatures};
+use crate::errors::VmError;
+use crate::modules::WasmMetadata;
+use crate::parsed_wasm::ParsedWasm;
+use crate::size::Size;
+use crate::static_analysis::Entrypoint;
+use crate::wasm_backend::{compile, make_compiling_engine};
+
+/// Limits used by [`check_contract`].
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct CheckLimits {
+    /// The memory limit of the engine used to compile the contract
+    pub memory_limit: Option<Size>,
+    /// The Wasm proposals that are accepted during static validation
+    pub wasm_features: WasmFeatures,
+}
+
+impl CheckLimits {
+    pub fn new(memory_limit: Option<Size>, wasm_features: WasmFeatures) -> Self {
+        Self {
+            memory_limit,
+            wasm_features,
+        }
+    }
+}
+
+/// The result of [`check_contract`]
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct CheckReport {
+    /// The checksum of the checked Wasm blob
+    pub checksum: Checksum,
+    /// The error of the static validation, `None` if the validation passed
+    pub validation_error: Option<VmError>,
+    /// The error of the compilation, `None` if the compilation passed
+    pub compile_error: Option<VmError>,
+    /// All entry points exported by the contract
+    pub entrypoints: BTreeSet<Entrypoint>,
+    /// `true` if and only if all IBC entry points are exported
+    pub has_ibc_entry_points: bool,
+    /// The set of capabilities the contract requires
+    pub required_capabilities: BTreeSet<String>,
+    /// The capabilities the contract requires which are not in the set of available capabilities
+    pub missing_capabilities: BTreeSet<String>,
+    /// The interface version the contract was compiled for
+    pub interface_version: Option<u32>,
+}
+
+impl CheckReport {
+    /// Returns true if the contract passed all checks, i.e. it can be stored
+    /// on a chain with the given capabilities.
+    pub fn is_ok(&self) -> bool {
+        self.validation_error.is_none() && self.compile_error.is_none()
+    }
+}
+
+/// Checks a contract like a chain does on upload and collects information about it.
+///
+/// In contrast to [`Cache::save_wasm`](crate::Cache::save_wasm), this does not stop at the
+/// first problem. Static validation and compilation are always both performed, such that
+/// all problems can be reported at once.
+pub fn check_contract(
+    wasm: &[u8],
+    available_capabilities: &HashSet<Capability>,
+    limits: &CheckLimits,
+) -> CheckReport {
+    let validation_error =
+        check_wasm_with_features(wasm, available_capabilities, &limits.wasm_features).err();
+
+    let engine = make_compiling_engine(limits.memory_limit);
+    let compile_error = compile(&engine, wasm).err();
+
+    let metadata = ParsedWasm::parse_with_features(wasm, &limits.wasm_features)
+        .map(|module| WasmMetadata::from_module(&module));
+    let (entrypoints, has_ibc_entry_points, required_capabilities, interface_version) =
+        match metadata {
+            Ok(metadata) => (
+                metadata.entrypoints,
+                metadata.has_ibc_entry_points,
+                metadata.required_capabilities,
+                metadata.interface_version,
+            ),
+            // The parsing error is part of the validation error
+            Err(_) => (BTreeSet::new(), false, BTreeSet::new(), None),
+        };
+    let missing_capabilities = required_capabilities
+        .iter()
+        .filter(|capability| !is_capability_available(capability, available_capabilities))
+        .cloned()
+        .collect();
+
+    CheckReport {
+        checksum: Checksum::generate(wasm),
+        validation_error,
+        compile_error,
+        entrypoints,
+        has_ibc_entry_points,
+        required_capabilities,
+        missing_capabilities,
+        interface_version,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capabilities_from_csv;
+
+    static CONTRACT: &[u8] = include_bytes!("../testdata/hackatom.wasm");
+    static IBC_CONTRACT: &[u8] = include_bytes!("../testdata/ibc_reflect.wasm");
+    static CORRUPTED: &[u8] = include_bytes!("../testdata/corrupted.wasm");
+
+    #[test]
+    fn check_contract_works() {
+        let capabilities = capabilities_from_csv("iterator,staking,stargate");
+        let report = check_contract(CONTRACT, &capabilities, &CheckLimits::default());
+        assert!(report.is_ok());
+        assert_eq!(report.checksum, Checksum::generate(CONTRACT));
+        assert!(report.entrypoints.contains(&Entrypoint::Execute));
+        assert!(!report.has_ibc_entry_points);
+        assert!(report.missing_capabilities.is_empty());
+        assert_eq!(report.interface_version, Some(8));
+
+        let report = check_contract(IBC_CONTRACT, &capabilities, &CheckLimits::default());
+        assert!(report.is_ok());
+        assert!(report.has_ibc_entry_points);
+    }
+
+    #[test]
+    fn check_contract_reports_missing_capabilities() {
+        let capabilities = capabilities_from_csv("staking");
+        let report = check_contract(IBC_CONTRACT, &capabilities, &CheckLimits::default());
+        assert!(!report.is_ok());
+        assert!(matches!(
+            report.validation_error,
+            Some(VmError::CapabilitiesMismatch { .. })
+        ));
+        assert!(report.compile_error.is_none());
+        assert_eq!(
+            report.missing_capabilities,
+            BTreeSet::from(["iterator".to_string(), "stargate".to_string()])
+        );
+    }
+
+    #[test]
+    fn check_contract_accepts_higher_capability_versions() {
+        let wasm = wat::parse_str(
+            r#"(module
+                (func (export "requires_token_factory:v1"))
+                (func (export "requires_oracle:v3")))"#,
+        )
+        .unwrap();
+        let capabilities = capabilities_from_csv("token_factory:v2,oracle:v2");
+        let report = check_contract(&wasm, &capabilities, &CheckLimits::default());
+        assert_eq!(
+            report.missing_capabilities,
+            BTreeSet::from(["oracle:v3".to_string()])
+        );
+    }
+
+    #[test]
+    fn check_contract_reports_invalid_wasm() {
+        let report = check_contract(CORRUPTED, &HashSet::new(), &CheckLimits::default());
+        assert!(!report.is_ok());
+        assert!(report.validation_error.is_some());
+        assert!(report.compile_error.is_some());
+        assert!(report.entrypoints.is_empty());
+        assert_eq!(report.interface_version, None);
+    }
+}
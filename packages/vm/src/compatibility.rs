@@ -3,8 +3,9 @@ use std::collections::HashSet;
 
 use wasmer::wasmparser::Import;
 use wasmer::wasmparser::TypeRef;
+use wasmer::wasmparser::ValType;
 
-use crate::capabilities::required_capabilities_from_module;
+use crate::capabilities::{is_capability_available, required_capabilities_from_module, Capability};
 use crate::errors::{VmError, VmResult};
 use crate::limited::LimitedDisplay;
 use crate::parsed_wasm::ParsedWasm;
@@ -17,6 +18,7 @@ const SUPPORTED_IMPORTS: &[&str] = &[
     "env.db_read",
     "env.db_write",
     "env.db_remove",
+    "env.db_size",
     "env.addr_validate",
     "env.addr_canonicalize",
     "env.addr_humanize",
@@ -24,8 +26,16 @@ const SUPPORTED_IMPORTS: &[&str] = &[
     "env.secp256k1_recover_pubkey",
     "env.ed25519_verify",
     "env.ed25519_batch_verify",
+    "env.ed25519ctx_verify",
+    "env.ed25519ph_verify",
     "env.debug",
+    "env.gas_remaining",
+    "env.emit_event",
     "env.query_chain",
+    "env.query_chain_binary",
+    "env.query_chain_batch",
+    "env.query_chain_batch_with_gas_limit",
+    "env.write_region_chunked",
     #[cfg(feature = "iterator")]
     "env.db_scan",
     #[cfg(feature = "iterator")]
@@ -34,6 +44,10 @@ const SUPPORTED_IMPORTS: &[&str] = &[
     "env.db_next_key",
     #[cfg(feature = "iterator")]
     "env.db_next_value",
+    #[cfg(feature = "iterator")]
+    "env.db_scan_prefix",
+    #[cfg(feature = "iterator")]
+    "env.db_seek",
 ];
 
 /// Lists all entry points we expect to be present when calling a contract.
@@ -65,22 +79,94 @@ const MEMORY_LIMIT: u32 = 512; // in pages
 /// is 485. Most are between 100 and 300.
 const TABLE_SIZE_LIMIT: u32 = 2500; // entries
 
+/// The upper limit for the number of element segments. Rust contracts
+/// usually contain exactly one segment initializing the function table.
+const ELEMENT_SEGMENTS_LIMIT: usize = 100;
+
 /// If the contract has more than this amount of imports, it will be rejected
 /// during static validation before even looking into the imports. We keep this
 /// number high since failing early gives less detailed error messages. Especially
 /// when a user accidentally includes wasm-bindgen, they get a bunch of unsupported imports.
 const MAX_IMPORTS: usize = 100;
 
+/// The Wasm proposals that are accepted in static validation on top of the
+/// Wasm 1.0 feature set (plus some small proposals that are always enabled).
+///
+/// This allows chains to adopt new proposals deliberately instead of whenever
+/// the defaults of the Wasm parser change. By default only bulk memory operations are enabled,
+/// which were accepted by static validation before this configuration existed.
+///
+/// Note that the compiler enforces its own set of supported operations, so enabling
+/// a proposal here does not make it usable in contracts on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct WasmFeatures {
+    /// The [bulk memory operations](https://github.com/WebAssembly/bulk-memory-operations/blob/master/proposals/bulk-memory-operations/Overview.md) proposal
+    pub bulk_memory: bool,
+    /// The [fixed-width SIMD](https://github.com/WebAssembly/simd/blob/master/proposals/simd/SIMD.md) proposal
+    pub simd: bool,
+    /// The [multi-memory](https://github.com/WebAssembly/multi-memory/blob/main/proposals/multi-memory/Overview.md) proposal.
+    /// If disabled, contracts must contain exactly one memory.
+    ///
+    /// Neither the compiler nor the host functions support multiple memories yet.
+    pub multi_memory: bool,
+    /// The [memory64](https://github.com/WebAssembly/memory64/blob/main/proposals/memory64/Overview.md) proposal.
+    /// If disabled, contracts must use a 32-bit memory.
+    ///
+    /// Neither the compiler nor the host functions support 64-bit memories yet.
+    pub memory64: bool,
+}
+
+impl Default for WasmFeatures {
+    fn default() -> Self {
+        Self {
+            bulk_memory: true,
+            simd: false,
+            multi_memory: false,
+            memory64: false,
+        }
+    }
+}
+
+impl WasmFeatures {
+    pub fn new(bulk_memory: bool, simd: bool) -> Self {
+        Self {
+            bulk_memory,
+            simd,
+            ..Default::default()
+        }
+    }
+}
+
 /// Checks if the data is valid wasm and compatibility with the CosmWasm API (imports and exports)
-pub fn check_wasm(wasm_code: &[u8], available_capabilities: &HashSet<String>) -> VmResult<()> {
-    let module = ParsedWasm::parse(wasm_code)?;
+pub fn check_wasm(wasm_code: &[u8], available_capabilities: &HashSet<Capability>) -> VmResult<()> {
+    check_wasm_with_features(wasm_code, available_capabilities, &WasmFeatures::default())
+}
 
-    check_wasm_tables(&module)?;
-    check_wasm_memories(&module)?;
-    check_interface_version(&module)?;
-    check_wasm_exports(&module)?;
-    check_wasm_imports(&module, SUPPORTED_IMPORTS)?;
-    check_wasm_capabilities(&module, available_capabilities)?;
+/// Like [`check_wasm`] but allows configuring the accepted Wasm proposals
+pub fn check_wasm_with_features(
+    wasm_code: &[u8],
+    available_capabilities: &HashSet<Capability>,
+    features: &WasmFeatures,
+) -> VmResult<()> {
+    let module = ParsedWasm::parse_with_features(wasm_code, features)?;
+    check_parsed_wasm(&module, available_capabilities, features)
+}
+
+/// Runs the checks of [`check_wasm_with_features`] on a module that was parsed before,
+/// such that callers that need the parsed module anyways do not have to parse it twice.
+pub(crate) fn check_parsed_wasm(
+    module: &ParsedWasm,
+    available_capabilities: &HashSet<Capability>,
+    features: &WasmFeatures,
+) -> VmResult<()> {
+    check_wasm_tables(module)?;
+    check_wasm_elements(module)?;
+    check_wasm_memories(module, features)?;
+    check_interface_version(module)?;
+    check_wasm_exports(module)?;
+    check_wasm_imports(module, SUPPORTED_IMPORTS)?;
+    check_wasm_capabilities(module, available_capabilities)?;
 
     Ok(())
 }
@@ -90,6 +176,11 @@ fn check_wasm_tables(module: &ParsedWasm) -> VmResult<()> {
         0 => Ok(()),
         1 => {
             let limits = &module.tables[0];
+            if limits.element_type != ValType::FuncRef {
+                return Err(VmError::static_validation_err(
+                    "Wasm contract's table must contain function references",
+                ));
+            }
             if let Some(maximum) = limits.maximum {
                 if maximum > TABLE_SIZE_LIMIT {
                     return Err(VmError::static_validation_err(
@@ -109,24 +200,56 @@ fn check_wasm_tables(module: &ParsedWasm) -> VmResult<()> {
     }
 }
 
-fn check_wasm_memories(module: &ParsedWasm) -> VmResult<()> {
-    if module.memories.len() != 1 {
-        return Err(VmError::static_validation_err(
-            "Wasm contract must contain exactly one memory",
-        ));
+fn check_wasm_elements(module: &ParsedWasm) -> VmResult<()> {
+    if module.elements.len() > ELEMENT_SEGMENTS_LIMIT {
+        return Err(VmError::static_validation_err(format!(
+            "Wasm contract must not have more than {ELEMENT_SEGMENTS_LIMIT} element segments"
+        )));
     }
-    let memory = &module.memories[0];
-
-    if memory.initial > MEMORY_LIMIT as u64 {
+    let total_items = module
+        .elements
+        .iter()
+        .map(|segment| segment.count as u64)
+        .sum::<u64>();
+    if total_items > TABLE_SIZE_LIMIT as u64 {
         return Err(VmError::static_validation_err(format!(
-            "Wasm contract memory's minimum must not exceed {MEMORY_LIMIT} pages."
+            "Wasm contract's element segments must not contain more than {TABLE_SIZE_LIMIT} items in total"
         )));
     }
+    Ok(())
+}
 
-    if memory.maximum.is_some() {
-        return Err(VmError::static_validation_err(
-            "Wasm contract memory's maximum must be unset. The host will set it for you.",
-        ));
+fn check_wasm_memories(module: &ParsedWasm, features: &WasmFeatures) -> VmResult<()> {
+    match module.memories.len() {
+        0 => {
+            return Err(VmError::static_validation_err(
+                "Wasm contract must contain exactly one memory",
+            ))
+        }
+        1 => {}
+        count => {
+            if !features.multi_memory {
+                return Err(VmError::multi_memory_not_supported(count));
+            }
+        }
+    }
+
+    for memory in &module.memories {
+        if memory.memory64 && !features.memory64 {
+            return Err(VmError::memory64_not_supported());
+        }
+
+        if memory.initial > MEMORY_LIMIT as u64 {
+            return Err(VmError::static_validation_err(format!(
+                "Wasm contract memory's minimum must not exceed {MEMORY_LIMIT} pages."
+            )));
+        }
+
+        if memory.maximum.is_some() {
+            return Err(VmError::static_validation_err(
+                "Wasm contract memory's maximum must be unset. The host will set it for you.",
+            ));
+        }
     }
     Ok(())
 }
@@ -212,18 +335,22 @@ fn full_import_name(ie: &Import) -> String {
 
 fn check_wasm_capabilities(
     module: &ParsedWasm,
-    available_capabilities: &HashSet<String>,
+    available_capabilities: &HashSet<Capability>,
 ) -> VmResult<()> {
     let required_capabilities = required_capabilities_from_module(module);
-    if !required_capabilities.is_subset(available_capabilities) {
-        // We switch to BTreeSet to get a sorted error message
-        let unavailable: BTreeSet<_> = required_capabilities
-            .difference(available_capabilities)
-            .collect();
-        return Err(VmError::static_validation_err(format!(
-            "Wasm contract requires unavailable capabilities: {}",
-            unavailable.to_string_limited(200)
-        )));
+    // We switch to BTreeSet to get a sorted error message
+    let unavailable: BTreeSet<String> = required_capabilities
+        .into_iter()
+        .filter(|required| !is_capability_available(required, available_capabilities))
+        .collect();
+    if !unavailable.is_empty() {
+        return Err(VmError::capabilities_mismatch(
+            unavailable,
+            available_capabilities
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        ));
     }
     Ok(())
 }
@@ -231,7 +358,9 @@ fn check_wasm_capabilities(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::capabilities::capabilities_from_csv;
     use crate::errors::VmError;
+    use crate::parsed_wasm::ElementSegment;
 
     static CONTRACT_0_7: &[u8] = include_bytes!("../testdata/hackatom_0.7.wasm");
     static CONTRACT_0_12: &[u8] = include_bytes!("../testdata/hackatom_0.12.wasm");
@@ -240,8 +369,8 @@ mod tests {
     static CONTRACT: &[u8] = include_bytes!("../testdata/hackatom.wasm");
     static CONTRACT_RUST_170: &[u8] = include_bytes!("../testdata/cyberpunk_rust170.wasm");
 
-    fn default_capabilities() -> HashSet<String> {
-        ["staking".to_string()].into_iter().collect()
+    fn default_capabilities() -> HashSet<Capability> {
+        ["staking"].into_iter().map(Capability::from).collect()
     }
 
     #[test]
@@ -330,16 +459,118 @@ mod tests {
             .contains("Wasm contract must not have unbound table section"));
     }
 
+    #[test]
+    fn check_wasm_tables_rejects_non_function_tables() {
+        let wasm = wat::parse_str("(module (table $name 10 10 externref))").unwrap();
+        let err = check_wasm_tables(&ParsedWasm::parse(&wasm).unwrap()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Wasm contract's table must contain function references"));
+    }
+
+    #[test]
+    fn check_wasm_elements_works() {
+        // No elements is fine
+        let wasm = wat::parse_str("(module)").unwrap();
+        check_wasm_elements(&ParsedWasm::parse(&wasm).unwrap()).unwrap();
+
+        // One segment
+        let wasm =
+            wat::parse_str("(module (table 3 3 funcref) (func $f) (elem (i32.const 0) $f $f $f))")
+                .unwrap();
+        let module = ParsedWasm::parse(&wasm).unwrap();
+        assert_eq!(
+            module.elements,
+            [ElementSegment {
+                table_index: Some(0),
+                count: 3
+            }]
+        );
+        check_wasm_elements(&module).unwrap();
+
+        // Too many segments
+        let segments = "(elem (i32.const 0) $f)".repeat(ELEMENT_SEGMENTS_LIMIT + 1);
+        let wasm =
+            wat::parse_str(format!("(module (table 1 1 funcref) (func $f) {segments})")).unwrap();
+        let err = check_wasm_elements(&ParsedWasm::parse(&wasm).unwrap()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Wasm contract must not have more than 100 element segments"));
+
+        // Too many items
+        let items = " $f".repeat(TABLE_SIZE_LIMIT as usize + 1);
+        let wasm = wat::parse_str(format!(
+            "(module (table 1 1 funcref) (func $f) (elem (i32.const 0){items}))"
+        ))
+        .unwrap();
+        let err = check_wasm_elements(&ParsedWasm::parse(&wasm).unwrap()).unwrap_err();
+        assert!(err.to_string().contains(
+            "Wasm contract's element segments must not contain more than 2500 items in total"
+        ));
+    }
+
+    #[test]
+    fn check_wasm_with_features_works() {
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory 1)
+                (func (param $dst i32) (param $src i32) (param $size i32)
+                    local.get $dst
+                    local.get $src
+                    local.get $size
+                    memory.copy))"#,
+        )
+        .unwrap();
+
+        // bulk memory is accepted by default but can be disabled
+        ParsedWasm::parse(&wasm).unwrap();
+        let err =
+            ParsedWasm::parse_with_features(&wasm, &WasmFeatures::new(false, false)).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("bulk memory support is not enabled"));
+
+        let wasm = wat::parse_str(
+            r#"(module
+                (func (result v128)
+                    v128.const i64x2 0 0))"#,
+        )
+        .unwrap();
+
+        // SIMD is rejected by default
+        let err = ParsedWasm::parse(&wasm).unwrap_err();
+        assert!(err.to_string().contains("SIMD support is not enabled"));
+        ParsedWasm::parse_with_features(&wasm, &WasmFeatures::new(false, true)).unwrap();
+
+        // the features are used by check_wasm_with_features
+        let err = check_wasm_with_features(
+            &wasm,
+            &default_capabilities(),
+            &WasmFeatures::new(true, false),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("SIMD support is not enabled"));
+        let err = check_wasm_with_features(
+            &wasm,
+            &default_capabilities(),
+            &WasmFeatures::new(false, true),
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Wasm contract must contain exactly one memory"));
+    }
+
     #[test]
     fn check_wasm_memories_ok() {
         let wasm = wat::parse_str("(module (memory 1))").unwrap();
-        check_wasm_memories(&ParsedWasm::parse(&wasm).unwrap()).unwrap()
+        check_wasm_memories(&ParsedWasm::parse(&wasm).unwrap(), &WasmFeatures::default()).unwrap()
     }
 
     #[test]
     fn check_wasm_memories_no_memory() {
         let wasm = wat::parse_str("(module)").unwrap();
-        match check_wasm_memories(&ParsedWasm::parse(&wasm).unwrap()) {
+        match check_wasm_memories(&ParsedWasm::parse(&wasm).unwrap(), &WasmFeatures::default()) {
             Err(VmError::StaticValidationErr { msg, .. }) => {
                 assert!(msg.starts_with("Wasm contract must contain exactly one memory"));
             }
@@ -363,10 +594,44 @@ mod tests {
         ))
         .unwrap();
 
-        // wrong number of memories should be caught by the validator
-        match ParsedWasm::parse(&wasm) {
+        let module = ParsedWasm::parse(&wasm).unwrap();
+        match check_wasm_memories(&module, &WasmFeatures::default()) {
+            Err(VmError::MultiMemoryNotSupported { count, .. }) => assert_eq!(count, 2),
+            Err(e) => panic!("Unexpected error {e:?}"),
+            Ok(_) => panic!("Didn't reject wasm with invalid api"),
+        }
+
+        // allowed if the proposal is enabled
+        let features = WasmFeatures {
+            multi_memory: true,
+            ..Default::default()
+        };
+        check_wasm_memories(&module, &features).unwrap();
+    }
+
+    #[test]
+    fn check_wasm_memories_memory64() {
+        let wasm = wat::parse_str("(module (memory i64 1))").unwrap();
+        let module = ParsedWasm::parse(&wasm).unwrap();
+        match check_wasm_memories(&module, &WasmFeatures::default()) {
+            Err(VmError::Memory64NotSupported { .. }) => {}
+            Err(e) => panic!("Unexpected error {e:?}"),
+            Ok(_) => panic!("Didn't reject wasm with invalid api"),
+        }
+
+        // allowed if the proposal is enabled
+        let features = WasmFeatures {
+            memory64: true,
+            ..Default::default()
+        };
+        check_wasm_memories(&module, &features).unwrap();
+
+        // limits still apply
+        let wasm = wat::parse_str("(module (memory i64 513))").unwrap();
+        let module = ParsedWasm::parse(&wasm).unwrap();
+        match check_wasm_memories(&module, &features) {
             Err(VmError::StaticValidationErr { msg, .. }) => {
-                assert!(msg.contains("multiple memories"));
+                assert!(msg.starts_with("Wasm contract memory's minimum must not exceed 512 pages"));
             }
             Err(e) => panic!("Unexpected error {e:?}"),
             Ok(_) => panic!("Didn't reject wasm with invalid api"),
@@ -385,7 +650,7 @@ mod tests {
         ))
         .unwrap();
 
-        match check_wasm_memories(&ParsedWasm::parse(&wasm).unwrap()) {
+        match check_wasm_memories(&ParsedWasm::parse(&wasm).unwrap(), &WasmFeatures::default()) {
             Err(VmError::StaticValidationErr { msg, .. }) => {
                 assert!(msg.starts_with("Wasm contract must contain exactly one memory"));
             }
@@ -397,10 +662,17 @@ mod tests {
     #[test]
     fn check_wasm_memories_initial_size() {
         let wasm_ok = wat::parse_str("(module (memory 512))").unwrap();
-        check_wasm_memories(&ParsedWasm::parse(&wasm_ok).unwrap()).unwrap();
+        check_wasm_memories(
+            &ParsedWasm::parse(&wasm_ok).unwrap(),
+            &WasmFeatures::default(),
+        )
+        .unwrap();
 
         let wasm_too_big = wat::parse_str("(module (memory 513))").unwrap();
-        match check_wasm_memories(&ParsedWasm::parse(&wasm_too_big).unwrap()) {
+        match check_wasm_memories(
+            &ParsedWasm::parse(&wasm_too_big).unwrap(),
+            &WasmFeatures::default(),
+        ) {
             Err(VmError::StaticValidationErr { msg, .. }) => {
                 assert!(msg.starts_with("Wasm contract memory's minimum must not exceed 512 pages"));
             }
@@ -412,7 +684,10 @@ mod tests {
     #[test]
     fn check_wasm_memories_maximum_size() {
         let wasm_max = wat::parse_str("(module (memory 1 5))").unwrap();
-        match check_wasm_memories(&ParsedWasm::parse(&wasm_max).unwrap()) {
+        match check_wasm_memories(
+            &ParsedWasm::parse(&wasm_max).unwrap(),
+            &WasmFeatures::default(),
+        ) {
             Err(VmError::StaticValidationErr { msg, .. }) => {
                 assert!(msg.starts_with("Wasm contract memory's maximum must be unset"));
             }
@@ -816,6 +1091,7 @@ mod tests {
             "freedom".to_string(),
         ]
         .into_iter()
+        .map(Capability::from)
         .collect();
         check_wasm_capabilities(&module, &available).unwrap();
     }
@@ -844,12 +1120,23 @@ mod tests {
             "freedom".to_string(),
         ]
         .into_iter()
+        .map(Capability::from)
         .collect();
         match check_wasm_capabilities(&module, &available).unwrap_err() {
-            VmError::StaticValidationErr { msg, .. } => assert_eq!(
-                msg,
-                "Wasm contract requires unavailable capabilities: {\"sun\"}"
-            ),
+            VmError::CapabilitiesMismatch {
+                required,
+                available: reported,
+                ..
+            } => {
+                assert_eq!(required, BTreeSet::from(["sun".to_string()]));
+                assert_eq!(
+                    reported,
+                    available
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<BTreeSet<_>>()
+                );
+            }
             _ => panic!("Got unexpected error"),
         }
 
@@ -860,32 +1147,116 @@ mod tests {
             "Water".to_string(), // capabilities are case sensitive (and lowercase by convention)
         ]
         .into_iter()
+        .map(Capability::from)
         .collect();
         match check_wasm_capabilities(&module, &available).unwrap_err() {
-            VmError::StaticValidationErr { msg, .. } => assert_eq!(
-                msg,
-                "Wasm contract requires unavailable capabilities: {\"sun\", \"water\"}"
-            ),
+            VmError::CapabilitiesMismatch {
+                required,
+                available: reported,
+                ..
+            } => {
+                assert_eq!(
+                    required,
+                    BTreeSet::from(["sun".to_string(), "water".to_string()])
+                );
+                assert_eq!(
+                    reported,
+                    available
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<BTreeSet<_>>()
+                );
+            }
             _ => panic!("Got unexpected error"),
         }
 
         // Available set 3
-        let available = ["freedom".to_string()].into_iter().collect();
+        let available = ["freedom"].into_iter().map(Capability::from).collect();
         match check_wasm_capabilities(&module, &available).unwrap_err() {
-            VmError::StaticValidationErr { msg, .. } => assert_eq!(
-                msg,
-                "Wasm contract requires unavailable capabilities: {\"nutrients\", \"sun\", \"water\"}"
-            ),
+            VmError::CapabilitiesMismatch {
+                required,
+                available: reported,
+                ..
+            } => {
+                assert_eq!(
+                    required,
+                    BTreeSet::from([
+                        "nutrients".to_string(),
+                        "sun".to_string(),
+                        "water".to_string()
+                    ])
+                );
+                assert_eq!(
+                    reported,
+                    available
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<BTreeSet<_>>()
+                );
+            }
             _ => panic!("Got unexpected error"),
         }
 
         // Available set 4
         let available = [].into_iter().collect();
         match check_wasm_capabilities(&module, &available).unwrap_err() {
-            VmError::StaticValidationErr { msg, .. } => assert_eq!(
-                msg,
-                "Wasm contract requires unavailable capabilities: {\"nutrients\", \"sun\", \"water\"}"
-            ),
+            VmError::CapabilitiesMismatch {
+                required,
+                available: reported,
+                ..
+            } => {
+                assert_eq!(
+                    required,
+                    BTreeSet::from([
+                        "nutrients".to_string(),
+                        "sun".to_string(),
+                        "water".to_string()
+                    ])
+                );
+                assert_eq!(
+                    reported,
+                    available
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<BTreeSet<_>>()
+                );
+            }
+            _ => panic!("Got unexpected error"),
+        }
+    }
+
+    #[test]
+    fn check_wasm_capabilities_supports_versions() {
+        let wasm = wat::parse_str(
+            r#"(module
+            (type (func))
+            (func (type 0) nop)
+            (export "requires_iterator" (func 0))
+            (export "requires_token_factory:v2" (func 0))
+        )"#,
+        )
+        .unwrap();
+        let module = ParsedWasm::parse(&wasm).unwrap();
+
+        let available = capabilities_from_csv("iterator,token_factory:v3");
+        check_wasm_capabilities(&module, &available).unwrap();
+
+        let available = capabilities_from_csv("iterator,token_factory:v1");
+        match check_wasm_capabilities(&module, &available).unwrap_err() {
+            VmError::CapabilitiesMismatch {
+                required,
+                available: reported,
+                ..
+            } => {
+                assert_eq!(required, BTreeSet::from(["token_factory:v2".to_string()]));
+                assert_eq!(
+                    reported,
+                    available
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<BTreeSet<_>>()
+                );
+            }
             _ => panic!("Got unexpected error"),
         }
     }
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use cosmwasm_std::Checksum;
+
+use crate::errors::{VmError, VmResult};
+
+/// Limits the number of instances that exist at the same time per checksum.
+///
+/// This does not block when the limit is reached but returns an error instead. Waiting
+/// could dead-lock because a contract can query itself, which creates a new instance of
+/// the same checksum while the outer instance is still alive.
+pub(crate) struct ConcurrencyLimiter {
+    limit: u32,
+    active: Mutex<HashMap<Checksum, u32>>,
+}
+
+impl ConcurrencyLimiter {
+    pub fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            active: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a slot for the given checksum, which is released when the returned
+    /// permit is dropped.
+    pub fn try_acquire(self: &Arc<Self>, checksum: &Checksum) -> VmResult<InstancePermit> {
+        let mut active = self.active.lock().unwrap();
+        let count = active.entry(*checksum).or_insert(0);
+        if *count >= self.limit {
+            return Err(VmError::too_many_instances(checksum.to_hex(), self.limit));
+        }
+        *count += 1;
+        Ok(InstancePermit {
+            limiter: Arc::clone(self),
+            checksum: *checksum,
+        })
+    }
+
+    /// The number of active permits for the given checksum
+    #[cfg(test)]
+    fn active(&self, checksum: &Checksum) -> u32 {
+        self.active
+            .lock()
+            .unwrap()
+            .get(checksum)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// A slot of a [`ConcurrencyLimiter`]. The slot is released on drop.
+pub(crate) struct InstancePermit {
+    limiter: Arc<ConcurrencyLimiter>,
+    checksum: Checksum,
+}
+
+impl Drop for InstancePermit {
+    fn drop(&mut self) {
+        let mut active = self.limiter.active.lock().unwrap();
+        if let Some(count) = active.get_mut(&self.checksum) {
+            *count -= 1;
+            if *count == 0 {
+                active.remove(&self.checksum);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrency_limiter_works() {
+        let limiter = Arc::new(ConcurrencyLimiter::new(2));
+        let checksum = Checksum::generate(b"one");
+        let other = Checksum::generate(b"two");
+
+        let first = limiter.try_acquire(&checksum).unwrap();
+        let second = limiter.try_acquire(&checksum).unwrap();
+        assert_eq!(limiter.active(&checksum), 2);
+        match limiter.try_acquire(&checksum) {
+            Err(VmError::TooManyInstances {
+                checksum: hex,
+                limit,
+                ..
+            }) => {
+                assert_eq!(hex, checksum.to_hex());
+                assert_eq!(limit, 2);
+            }
+            Err(e) => panic!("Unexpected error: {e:?}"),
+            Ok(_) => panic!("Expected error"),
+        }
+
+        // other checksums are not affected
+        let _other = limiter.try_acquire(&other).unwrap();
+
+        // dropping a permit frees a slot
+        drop(first);
+        assert_eq!(limiter.active(&checksum), 1);
+        let third = limiter.try_acquire(&checksum).unwrap();
+
+        drop(second);
+        drop(third);
+        assert_eq!(limiter.active(&checksum), 0);
+        assert_eq!(limiter.active(&other), 1);
+    }
+}
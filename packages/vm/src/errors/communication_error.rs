@@ -28,6 +28,8 @@ pub enum CommunicationError {
     /// Whenever UTF-8 bytes cannot be decoded into a unicode string, e.g. in String::from_utf8 or str::from_utf8.
     #[error("Cannot decode UTF8 bytes into string: {}", msg)]
     InvalidUtf8 { msg: String },
+    #[error("Got invalid sections: {}", msg)]
+    InvalidSections { msg: String },
     #[error("Region length too big. Got {}, limit {}", length, max_length)]
     // Note: this only checks length, not capacity
     RegionLengthTooBig { length: usize, max_length: usize },
@@ -63,6 +65,10 @@ impl CommunicationError {
         }
     }
 
+    pub(crate) fn invalid_sections(msg: impl Into<String>) -> Self {
+        CommunicationError::InvalidSections { msg: msg.into() }
+    }
+
     pub(crate) fn region_length_too_big(length: usize, max_length: usize) -> Self {
         CommunicationError::RegionLengthTooBig { length, max_length }
     }
@@ -119,6 +125,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn invalid_sections_works() {
+        let error = CommunicationError::invalid_sections("broken");
+        match error {
+            CommunicationError::InvalidSections { msg, .. } => {
+                assert_eq!(msg, "broken");
+            }
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
     #[test]
     fn region_length_too_big_works() {
         let error = CommunicationError::region_length_too_big(50, 20);
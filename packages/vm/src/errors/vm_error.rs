@@ -1,4 +1,5 @@
 use super::{impl_from_err, BT};
+use std::collections::BTreeSet;
 use std::fmt::{Debug, Display};
 use thiserror::Error;
 
@@ -6,6 +7,7 @@ use cosmwasm_crypto::CryptoError;
 
 use super::communication_error::CommunicationError;
 use crate::backend::BackendError;
+use crate::limited::LimitedDisplay;
 
 #[derive(Error, Debug)]
 #[non_exhaustive]
@@ -41,11 +43,17 @@ pub enum VmError {
     InstantiationErr { msg: String, backtrace: BT },
     #[error("Hash doesn't match stored data")]
     IntegrityErr { backtrace: BT },
-    #[error("Error parsing into type {target_type}: {msg}")]
+    #[error("Error parsing into type {target_type}: {msg} (path: {path}, offset: {offset}, near: `{snippet}`)")]
     ParseErr {
         /// the target type that was attempted
         target_type: String,
         msg: String,
+        /// the path of the field that failed to parse, e.g. `ok.messages[0].msg`
+        path: String,
+        /// the byte offset in the input at which the error occurred
+        offset: usize,
+        /// a truncated excerpt of the input around the offset
+        snippet: String,
         backtrace: BT,
     },
     #[error("Data too long for deserialization. Got: {length} bytes; limit: {max_length} bytes")]
@@ -86,6 +94,63 @@ pub enum VmError {
     WriteAccessDenied { backtrace: BT },
     #[error("Maximum call depth exceeded.")]
     MaxCallDepthExceeded { backtrace: BT },
+    #[error("Maximum Wasm stack depth exceeded.")]
+    StackDepthExceeded { backtrace: BT },
+    #[error("Memory usage of {used} bytes exceeds the limit of {limit} bytes.")]
+    MemoryLimitExceeded {
+        used: usize,
+        limit: usize,
+        backtrace: BT,
+    },
+    /// A stored module artifact was created by an incompatible version of the VM, Wasmer
+    /// or for a different target
+    #[error("Wrong artifact version. Expected: {expected}; found: {found}")]
+    WrongArtifactVersion {
+        expected: String,
+        found: String,
+        backtrace: BT,
+    },
+    /// The contract uses the multi-memory proposal, which is not enabled
+    #[error("Wasm contract contains {count} memories but the multi-memory proposal is not supported. Contracts must contain exactly one memory.")]
+    MultiMemoryNotSupported { count: usize, backtrace: BT },
+    /// The contract uses the memory64 proposal, which is not enabled
+    #[error("Wasm contract uses a 64-bit memory but the memory64 proposal is not supported. Contracts must use a 32-bit memory.")]
+    Memory64NotSupported { backtrace: BT },
+    /// The limit of concurrent instances per checksum was reached
+    #[error("Too many concurrent instances of contract {checksum}. Limit: {limit}")]
+    TooManyInstances {
+        checksum: String,
+        limit: u32,
+        backtrace: BT,
+    },
+    /// A contract response exceeds one of the configured [`ResponseLimits`](crate::ResponseLimits)
+    #[error("Response contains {count} {kind}, which exceeds the limit of {limit}")]
+    ResponseLimitExceeded {
+        kind: String,
+        count: usize,
+        limit: usize,
+        backtrace: BT,
+    },
+    /// A contract tried to change the state while the instance is in
+    /// [`ExecutionMode::Query`](crate::ExecutionMode::Query)
+    #[error("Must not change the state in query execution mode.")]
+    ReadOnlyExecution { backtrace: BT },
+    /// A contract used a storage iterator after writing to or removing a key in its range
+    #[error("Iterator {id} was invalidated by a write to its range.")]
+    IteratorInvalidated { id: u32, backtrace: BT },
+    /// A contract requires capabilities the chain does not provide
+    #[error(
+        "Wasm contract requires unavailable capabilities: {}. Available capabilities: {}",
+        required.to_string_limited(200),
+        available.to_string_limited(200)
+    )]
+    CapabilitiesMismatch {
+        /// The required capabilities that are not available
+        required: BTreeSet<String>,
+        /// All capabilities available to contracts
+        available: BTreeSet<String>,
+        backtrace: BT,
+    },
 }
 
 impl VmError {
@@ -163,10 +228,19 @@ impl VmError {
         }
     }
 
-    pub(crate) fn parse_err(target: impl Into<String>, msg: impl Display) -> Self {
+    pub(crate) fn parse_err(
+        target: impl Into<String>,
+        msg: impl Display,
+        path: impl Into<String>,
+        offset: usize,
+        snippet: impl Into<String>,
+    ) -> Self {
         VmError::ParseErr {
             target_type: target.into(),
             msg: msg.to_string(),
+            path: path.into(),
+            offset,
+            snippet: snippet.into(),
             backtrace: BT::capture(),
         }
     }
@@ -242,6 +316,131 @@ impl VmError {
             backtrace: BT::capture(),
         }
     }
+
+    pub(crate) fn stack_depth_exceeded() -> Self {
+        VmError::StackDepthExceeded {
+            backtrace: BT::capture(),
+        }
+    }
+
+    pub(crate) fn memory_limit_exceeded(used: usize, limit: usize) -> Self {
+        VmError::MemoryLimitExceeded {
+            used,
+            limit,
+            backtrace: BT::capture(),
+        }
+    }
+
+    pub(crate) fn wrong_artifact_version(
+        expected: impl Into<String>,
+        found: impl Into<String>,
+    ) -> Self {
+        VmError::WrongArtifactVersion {
+            expected: expected.into(),
+            found: found.into(),
+            backtrace: BT::capture(),
+        }
+    }
+
+    pub(crate) fn multi_memory_not_supported(count: usize) -> Self {
+        VmError::MultiMemoryNotSupported {
+            count,
+            backtrace: BT::capture(),
+        }
+    }
+
+    pub(crate) fn memory64_not_supported() -> Self {
+        VmError::Memory64NotSupported {
+            backtrace: BT::capture(),
+        }
+    }
+
+    pub(crate) fn too_many_instances(checksum: impl Into<String>, limit: u32) -> Self {
+        VmError::TooManyInstances {
+            checksum: checksum.into(),
+            limit,
+            backtrace: BT::capture(),
+        }
+    }
+
+    pub(crate) fn response_limit_exceeded(
+        kind: impl Into<String>,
+        count: usize,
+        limit: usize,
+    ) -> Self {
+        VmError::ResponseLimitExceeded {
+            kind: kind.into(),
+            count,
+            limit,
+            backtrace: BT::capture(),
+        }
+    }
+
+    pub(crate) fn read_only_execution() -> Self {
+        VmError::ReadOnlyExecution {
+            backtrace: BT::capture(),
+        }
+    }
+
+    #[cfg(feature = "iterator")]
+    pub(crate) fn iterator_invalidated(id: u32) -> Self {
+        VmError::IteratorInvalidated {
+            id,
+            backtrace: BT::capture(),
+        }
+    }
+
+    pub(crate) fn capabilities_mismatch(
+        required: BTreeSet<String>,
+        available: BTreeSet<String>,
+    ) -> Self {
+        VmError::CapabilitiesMismatch {
+            required,
+            available,
+            backtrace: BT::capture(),
+        }
+    }
+
+    /// Numeric error code that identifies the error variant.
+    ///
+    /// In contrast to the error message, the codes are stable across versions and can be
+    /// used to translate errors into error codes of other systems. Codes of removed variants
+    /// are never reused.
+    pub fn code(&self) -> u32 {
+        match self {
+            VmError::GenericErr { .. } => 1,
+            VmError::Aborted { .. } => 2,
+            VmError::BackendErr { .. } => 3,
+            VmError::CacheErr { .. } => 4,
+            VmError::CommunicationErr { .. } => 5,
+            VmError::CompileErr { .. } => 6,
+            VmError::ConversionErr { .. } => 7,
+            VmError::CryptoErr { .. } => 8,
+            VmError::GasDepletion { .. } => 9,
+            VmError::InstantiationErr { .. } => 10,
+            VmError::IntegrityErr { .. } => 11,
+            VmError::ParseErr { .. } => 12,
+            VmError::DeserializationLimitExceeded { .. } => 13,
+            VmError::SerializeErr { .. } => 14,
+            VmError::ResolveErr { .. } => 15,
+            VmError::ResultMismatch { .. } => 16,
+            VmError::RuntimeErr { .. } => 17,
+            VmError::StaticValidationErr { .. } => 18,
+            VmError::UninitializedContextData { .. } => 19,
+            VmError::WriteAccessDenied { .. } => 20,
+            VmError::MaxCallDepthExceeded { .. } => 21,
+            VmError::StackDepthExceeded { .. } => 22,
+            VmError::MemoryLimitExceeded { .. } => 23,
+            VmError::WrongArtifactVersion { .. } => 24,
+            VmError::MultiMemoryNotSupported { .. } => 25,
+            VmError::Memory64NotSupported { .. } => 26,
+            VmError::TooManyInstances { .. } => 27,
+            VmError::ResponseLimitExceeded { .. } => 28,
+            VmError::ReadOnlyExecution { .. } => 29,
+            VmError::IteratorInvalidated { .. } => 30,
+            VmError::CapabilitiesMismatch { .. } => 31,
+        }
+    }
 }
 
 impl_from_err!(CommunicationError, VmError, VmError::CommunicationErr);
@@ -431,13 +630,21 @@ mod tests {
 
     #[test]
     fn parse_err_works() {
-        let error = VmError::parse_err("Book", "Missing field: title");
+        let error = VmError::parse_err("Book", "Missing field: title", "author", 17, "{}");
         match error {
             VmError::ParseErr {
-                target_type, msg, ..
+                target_type,
+                msg,
+                path,
+                offset,
+                snippet,
+                ..
             } => {
                 assert_eq!(target_type, "Book");
                 assert_eq!(msg, "Missing field: title");
+                assert_eq!(path, "author");
+                assert_eq!(offset, 17);
+                assert_eq!(snippet, "{}");
             }
             e => panic!("Unexpected error: {e:?}"),
         }
@@ -519,4 +726,156 @@ mod tests {
             e => panic!("Unexpected error: {e:?}"),
         }
     }
+
+    #[test]
+    fn stack_depth_exceeded_works() {
+        let error = VmError::stack_depth_exceeded();
+        match error {
+            VmError::StackDepthExceeded { .. } => {}
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn memory_limit_exceeded_works() {
+        let error = VmError::memory_limit_exceeded(2 * 65536, 65536);
+        match error {
+            VmError::MemoryLimitExceeded { used, limit, .. } => {
+                assert_eq!(used, 2 * 65536);
+                assert_eq!(limit, 65536);
+            }
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn wrong_artifact_version_works() {
+        let error = VmError::wrong_artifact_version("v2", "v1");
+        match error {
+            VmError::WrongArtifactVersion {
+                expected, found, ..
+            } => {
+                assert_eq!(expected, "v2");
+                assert_eq!(found, "v1");
+            }
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn multi_memory_not_supported_works() {
+        let error = VmError::multi_memory_not_supported(3);
+        match error {
+            VmError::MultiMemoryNotSupported { count, .. } => assert_eq!(count, 3),
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn memory64_not_supported_works() {
+        let error = VmError::memory64_not_supported();
+        match error {
+            VmError::Memory64NotSupported { .. } => {}
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn too_many_instances_works() {
+        let error = VmError::too_many_instances("aabb", 3);
+        match error {
+            VmError::TooManyInstances {
+                checksum, limit, ..
+            } => {
+                assert_eq!(checksum, "aabb");
+                assert_eq!(limit, 3);
+            }
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn response_limit_exceeded_works() {
+        let error = VmError::response_limit_exceeded("messages", 12, 10);
+        match error {
+            VmError::ResponseLimitExceeded {
+                kind, count, limit, ..
+            } => {
+                assert_eq!(kind, "messages");
+                assert_eq!(count, 12);
+                assert_eq!(limit, 10);
+            }
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn read_only_execution_works() {
+        let error = VmError::read_only_execution();
+        match error {
+            VmError::ReadOnlyExecution { .. } => {}
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn iterator_invalidated_works() {
+        let error = VmError::iterator_invalidated(3);
+        match error {
+            VmError::IteratorInvalidated { id, .. } => assert_eq!(id, 3),
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn capabilities_mismatch_works() {
+        let error = VmError::capabilities_mismatch(
+            BTreeSet::from(["sun".to_string()]),
+            BTreeSet::from(["water".to_string(), "nutrients".to_string()]),
+        );
+        assert_eq!(
+            error.to_string(),
+            "Wasm contract requires unavailable capabilities: {\"sun\"}. Available capabilities: {\"nutrients\", \"water\"}"
+        );
+        match error {
+            VmError::CapabilitiesMismatch {
+                required,
+                available,
+                ..
+            } => {
+                assert_eq!(required, BTreeSet::from(["sun".to_string()]));
+                assert_eq!(available.len(), 2);
+            }
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn code_works() {
+        assert_eq!(VmError::generic_err("broken").code(), 1);
+        assert_eq!(VmError::aborted("panic").code(), 2);
+        assert_eq!(
+            VmError::backend_err(BackendError::unknown("broken")).code(),
+            3
+        );
+        assert_eq!(VmError::cache_err("broken").code(), 4);
+        assert_eq!(VmError::from(CommunicationError::zero_address()).code(), 5);
+        assert_eq!(VmError::gas_depletion().code(), 9);
+        assert_eq!(VmError::write_access_denied().code(), 20);
+        assert_eq!(VmError::stack_depth_exceeded().code(), 22);
+        assert_eq!(VmError::memory_limit_exceeded(2, 1).code(), 23);
+        assert_eq!(VmError::wrong_artifact_version("v2", "v1").code(), 24);
+        assert_eq!(VmError::multi_memory_not_supported(2).code(), 25);
+        assert_eq!(VmError::memory64_not_supported().code(), 26);
+        assert_eq!(VmError::too_many_instances("aabb", 3).code(), 27);
+        assert_eq!(VmError::response_limit_exceeded("events", 2, 1).code(), 28);
+        assert_eq!(VmError::read_only_execution().code(), 29);
+        #[cfg(feature = "iterator")]
+        assert_eq!(VmError::iterator_invalidated(1).code(), 30);
+        assert_eq!(
+            VmError::capabilities_mismatch(BTreeSet::new(), BTreeSet::new()).code(),
+            31
+        );
+    }
 }
@@ -0,0 +1,170 @@
+use std::fmt;
+
+use sha2::{Digest, Sha256};
+use wasmer::wasmparser::{Parser, Payload, SectionReader};
+
+use crate::errors::VmResult;
+
+/// Prefix of the hashed data, such that fingerprints cannot be confused with other hashes
+const FINGERPRINT_DOMAIN: &[u8] = b"cosmwasm-module-fingerprint-v1";
+
+/// A fingerprint of the code of a Wasm module.
+///
+/// It is derived from the original Wasm only, i.e. it does not depend on the compiler or
+/// the VM version, and stored next to every compiled artifact. This allows operators to
+/// verify that an artifact was compiled from a specific Wasm, also across bumps of the
+/// module serialization version.
+///
+/// The fingerprint commits to the imports, the exports and a hash of every function body,
+/// as well as all other non-custom sections. Custom sections (e.g. debug information) do
+/// not affect the fingerprint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ModuleFingerprint([u8; 32]);
+
+impl ModuleFingerprint {
+    /// Calculates the fingerprint of the given Wasm. The Wasm is parsed but not validated.
+    pub fn generate(wasm: &[u8]) -> VmResult<Self> {
+        let mut hasher = FingerprintHasher::new();
+        for payload in Parser::new(0).parse_all(wasm) {
+            hasher.update(wasm, &payload?);
+        }
+        Ok(hasher.finalize())
+    }
+
+    pub fn to_hex(self) -> String {
+        hex::encode(self.0)
+    }
+}
+
+/// Calculates a [`ModuleFingerprint`] from the payloads of a Wasm, such that it can be
+/// created while parsing the Wasm for other purposes.
+pub(crate) struct FingerprintHasher {
+    imports: Sha256,
+    exports: Sha256,
+    functions: Sha256,
+    other_sections: Sha256,
+}
+
+impl FingerprintHasher {
+    pub fn new() -> Self {
+        Self {
+            imports: Sha256::new(),
+            exports: Sha256::new(),
+            functions: Sha256::new(),
+            other_sections: Sha256::new(),
+        }
+    }
+
+    /// Adds a payload of the given Wasm. The payloads must be parsed from `wasm` with offset 0.
+    pub fn update(&mut self, wasm: &[u8], payload: &Payload) {
+        match payload {
+            Payload::ImportSection(reader) => self.imports.update(&wasm[reader.range()]),
+            Payload::ExportSection(reader) => self.exports.update(&wasm[reader.range()]),
+            Payload::CodeSectionEntry(body) => {
+                self.functions.update(Sha256::digest(&wasm[body.range()]))
+            }
+            // The function bodies are hashed individually
+            Payload::CodeSectionStart { .. } | Payload::CustomSection(_) => {}
+            payload => {
+                if let Some((id, range)) = payload.as_section() {
+                    self.other_sections.update([id]);
+                    self.other_sections
+                        .update((range.len() as u64).to_le_bytes());
+                    self.other_sections.update(&wasm[range]);
+                }
+            }
+        }
+    }
+
+    pub fn finalize(self) -> ModuleFingerprint {
+        let mut hasher = Sha256::new();
+        hasher.update(FINGERPRINT_DOMAIN);
+        hasher.update(self.imports.finalize());
+        hasher.update(self.exports.finalize());
+        hasher.update(self.functions.finalize());
+        hasher.update(self.other_sections.finalize());
+        ModuleFingerprint(hasher.finalize().into())
+    }
+}
+
+impl fmt::Display for ModuleFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl From<[u8; 32]> for ModuleFingerprint {
+    fn from(data: [u8; 32]) -> Self {
+        Self(data)
+    }
+}
+
+impl From<ModuleFingerprint> for [u8; 32] {
+    fn from(fingerprint: ModuleFingerprint) -> Self {
+        fingerprint.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parsed_wasm::ParsedWasm;
+
+    const WAT: &str = r#"(module
+        (import "env" "abort" (func $abort (param i32)))
+        (func $add_one (export "add_one") (param $p0 i32) (result i32)
+            local.get $p0
+            i32.const 1
+            i32.add))
+    "#;
+
+    #[test]
+    fn generate_works() {
+        let wasm = wat::parse_str(WAT).unwrap();
+        let fingerprint = ModuleFingerprint::generate(&wasm).unwrap();
+        assert_eq!(ModuleFingerprint::generate(&wasm).unwrap(), fingerprint);
+        assert_eq!(fingerprint.to_hex().len(), 64);
+        assert_eq!(fingerprint.to_string(), fingerprint.to_hex());
+    }
+
+    #[test]
+    fn generate_ignores_custom_sections() {
+        let wasm = wat::parse_str(WAT).unwrap();
+        // custom section with id 0, size 5 and name "abc"
+        let mut with_custom_section = wasm.clone();
+        with_custom_section.extend([0, 5, 3, b'a', b'b', b'c', 0xff]);
+        assert_eq!(
+            ModuleFingerprint::generate(&with_custom_section).unwrap(),
+            ModuleFingerprint::generate(&wasm).unwrap()
+        );
+    }
+
+    #[test]
+    fn generate_depends_on_code() {
+        let wasm = wat::parse_str(WAT).unwrap();
+        let fingerprint = ModuleFingerprint::generate(&wasm).unwrap();
+
+        let changed_body = wat::parse_str(WAT.replace("i32.const 1", "i32.const 2")).unwrap();
+        let changed_export = wat::parse_str(WAT.replace("add_one\"", "add_two\"")).unwrap();
+        let changed_import = wat::parse_str(WAT.replace("\"abort\"", "\"panic\"")).unwrap();
+        for other in [changed_body, changed_export, changed_import] {
+            assert_ne!(ModuleFingerprint::generate(&other).unwrap(), fingerprint);
+        }
+    }
+
+    #[test]
+    fn generate_fails_for_invalid_wasm() {
+        assert!(ModuleFingerprint::generate(b"not wasm").is_err());
+    }
+
+    #[test]
+    fn parsed_wasm_contains_fingerprint() {
+        let wasm = wat::parse_str(WAT).unwrap();
+        let parsed = ParsedWasm::parse(&wasm).unwrap();
+        assert_eq!(
+            parsed.fingerprint,
+            ModuleFingerprint::generate(&wasm).unwrap()
+        );
+    }
+}
@@ -4,27 +4,34 @@ use std::cmp::max;
 use std::marker::PhantomData;
 
 use cosmwasm_crypto::{
-    ed25519_batch_verify, ed25519_verify, secp256k1_recover_pubkey, secp256k1_verify, CryptoError,
+    ed25519_batch_verify, ed25519_verify, ed25519ctx_verify, ed25519ph_verify,
+    secp256k1_recover_pubkey, secp256k1_verify, CryptoError,
 };
 use cosmwasm_crypto::{
-    ECDSA_PUBKEY_MAX_LEN, ECDSA_SIGNATURE_LEN, EDDSA_PUBKEY_LEN, MESSAGE_HASH_MAX_LEN,
+    ECDSA_PUBKEY_MAX_LEN, ECDSA_SIGNATURE_LEN, EDDSA_CONTEXT_MAX_LEN, EDDSA_PREHASH_LEN,
+    EDDSA_PUBKEY_LEN, MESSAGE_HASH_MAX_LEN,
 };
 
 #[cfg(feature = "iterator")]
 use cosmwasm_std::Order;
+use cosmwasm_std::{Binary, ContractResult, Event, SystemError, SystemResult};
 use wasmer::{AsStoreMut, FunctionEnvMut};
 
 use crate::backend::{BackendApi, BackendError, Querier, Storage};
 use crate::conversion::{ref_to_u32, to_u32};
-use crate::environment::{process_gas_info, DebugInfo, Environment};
+#[cfg(feature = "iterator")]
+use crate::environment::GasConfig;
+use crate::environment::{process_gas_info, DebugInfo, Environment, ExecutionMode};
 use crate::errors::{CommunicationError, VmError, VmResult};
 #[cfg(feature = "iterator")]
+use crate::iterator_tracker::IteratorRange;
+#[cfg(feature = "iterator")]
 use crate::memory::maybe_read_region;
 use crate::memory::{read_region, write_region};
-use crate::sections::decode_sections;
 #[allow(unused_imports)]
 use crate::sections::encode_sections;
-use crate::serde::to_vec;
+use crate::sections::{decode_sections, decode_sections_checked};
+use crate::serde::{from_slice, to_vec};
 use crate::GasInfo;
 
 /// A kibi (kilo binary)
@@ -42,6 +49,8 @@ const MAX_LENGTH_CANONICAL_ADDRESS: usize = 64;
 /// is 90 characters and we're adding some safety margin around that for other formats.
 const MAX_LENGTH_HUMAN_ADDRESS: usize = 256;
 const MAX_LENGTH_QUERY_CHAIN_REQUEST: usize = 64 * KI;
+/// Max number of queries in a single query_chain_batch call
+const MAX_COUNT_QUERY_CHAIN_BATCH: usize = 64;
 /// Length of a serialized Ed25519  signature
 const MAX_LENGTH_ED25519_SIGNATURE: usize = 64;
 /// Max length of a Ed25519 message in bytes.
@@ -59,6 +68,15 @@ const MAX_LENGTH_DEBUG: usize = 2 * MI;
 /// Max length for an abort message
 const MAX_LENGTH_ABORT: usize = 2 * MI;
 
+/// Max length of a serialized event emitted through `emit_event`
+const MAX_LENGTH_EVENT: usize = 64 * KI;
+
+/// Max number of events that can be emitted through `emit_event` in a single call
+const MAX_COUNT_EMITTED_EVENTS: usize = 1024;
+
+/// Max length of a single chunk of a response written in chunks
+const MAX_LENGTH_RESPONSE_CHUNK: usize = 8 * MI;
+
 // Import implementations
 //
 // This block of do_* prefixed functions is tailored for Wasmer's
@@ -94,9 +112,7 @@ pub fn do_db_write<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + '
 ) -> VmResult<()> {
     let (data, mut store) = env.data_and_store_mut();
 
-    if data.is_storage_readonly() {
-        return Err(VmError::write_access_denied());
-    }
+    data.check_storage_writable()?;
 
     /// Converts a region length error to a different variant for better understandability
     fn convert_error(e: VmError, kind: &'static str) -> VmError {
@@ -122,6 +138,8 @@ pub fn do_db_write<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + '
         data.with_storage_from_context::<_, _>(|store| Ok(store.set(&key, &value)))?;
     process_gas_info(data, &mut store, gas_info)?;
     result?;
+    #[cfg(feature = "iterator")]
+    data.invalidate_iterators(&key);
 
     Ok(())
 }
@@ -132,9 +150,7 @@ pub fn do_db_remove<A: BackendApi + 'static, S: Storage + 'static, Q: Querier +
 ) -> VmResult<()> {
     let (data, mut store) = env.data_and_store_mut();
 
-    if data.is_storage_readonly() {
-        return Err(VmError::write_access_denied());
-    }
+    data.check_storage_writable()?;
 
     let key = read_region(&data.memory(&store), key_ptr, MAX_LENGTH_DB_KEY)?;
 
@@ -142,10 +158,35 @@ pub fn do_db_remove<A: BackendApi + 'static, S: Storage + 'static, Q: Querier +
         data.with_storage_from_context::<_, _>(|store| Ok(store.remove(&key)))?;
     process_gas_info(data, &mut store, gas_info)?;
     result?;
+    #[cfg(feature = "iterator")]
+    data.invalidate_iterators(&key);
 
     Ok(())
 }
 
+/// Returns a region with the approximate storage usage of the contract,
+/// encoded as `keys || bytes` (both u64 big endian), or 0 if the backend does not support this.
+pub fn do_db_size<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let gas_info = GasInfo::with_cost(data.gas_config.db_size_cost);
+    process_gas_info(data, &mut store, gas_info)?;
+
+    let (result, gas_info) =
+        data.with_storage_from_context::<_, _>(|store| Ok(store.size_hint()))?;
+    process_gas_info(data, &mut store, gas_info)?;
+    let Some(size) = result? else {
+        return Ok(0);
+    };
+
+    let mut out_data = Vec::with_capacity(16);
+    out_data.extend_from_slice(&size.keys.to_be_bytes());
+    out_data.extend_from_slice(&size.bytes.to_be_bytes());
+    write_to_contract(data, &mut store, &out_data)
+}
+
 pub fn do_addr_validate<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
     mut env: FunctionEnvMut<Environment<A, S, Q>>,
     source_ptr: u32,
@@ -379,6 +420,95 @@ pub fn do_ed25519_verify<A: BackendApi + 'static, S: Storage + 'static, Q: Queri
     Ok(code)
 }
 
+pub fn do_ed25519ctx_verify<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    message_ptr: u32,
+    context_ptr: u32,
+    signature_ptr: u32,
+    pubkey_ptr: u32,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let message = read_region(
+        &data.memory(&store),
+        message_ptr,
+        MAX_LENGTH_ED25519_MESSAGE,
+    )?;
+    let context = read_region(&data.memory(&store), context_ptr, EDDSA_CONTEXT_MAX_LEN)?;
+    let signature = read_region(
+        &data.memory(&store),
+        signature_ptr,
+        MAX_LENGTH_ED25519_SIGNATURE,
+    )?;
+    let pubkey = read_region(&data.memory(&store), pubkey_ptr, EDDSA_PUBKEY_LEN)?;
+
+    let gas_info = GasInfo::with_cost(data.gas_config.ed25519_verify_cost);
+    process_gas_info(data, &mut store, gas_info)?;
+    let result = ed25519ctx_verify(&message, &context, &signature, &pubkey);
+    let code = match result {
+        Ok(valid) => {
+            if valid {
+                ED25519_VERIFY_CODE_VALID
+            } else {
+                ED25519_VERIFY_CODE_INVALID
+            }
+        }
+        Err(err) => match err {
+            CryptoError::InvalidPubkeyFormat { .. }
+            | CryptoError::InvalidSignatureFormat { .. }
+            | CryptoError::GenericErr { .. } => err.code(),
+            CryptoError::BatchErr { .. }
+            | CryptoError::InvalidHashFormat { .. }
+            | CryptoError::InvalidRecoveryParam { .. } => {
+                panic!("Error must not happen for this call")
+            }
+        },
+    };
+    Ok(code)
+}
+
+pub fn do_ed25519ph_verify<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    message_hash_ptr: u32,
+    context_ptr: u32,
+    signature_ptr: u32,
+    pubkey_ptr: u32,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let message_hash = read_region(&data.memory(&store), message_hash_ptr, EDDSA_PREHASH_LEN)?;
+    let context = read_region(&data.memory(&store), context_ptr, EDDSA_CONTEXT_MAX_LEN)?;
+    let signature = read_region(
+        &data.memory(&store),
+        signature_ptr,
+        MAX_LENGTH_ED25519_SIGNATURE,
+    )?;
+    let pubkey = read_region(&data.memory(&store), pubkey_ptr, EDDSA_PUBKEY_LEN)?;
+
+    let gas_info = GasInfo::with_cost(data.gas_config.ed25519_verify_cost);
+    process_gas_info(data, &mut store, gas_info)?;
+    let result = ed25519ph_verify(&message_hash, &context, &signature, &pubkey);
+    let code = match result {
+        Ok(valid) => {
+            if valid {
+                ED25519_VERIFY_CODE_VALID
+            } else {
+                ED25519_VERIFY_CODE_INVALID
+            }
+        }
+        Err(err) => match err {
+            CryptoError::InvalidHashFormat { .. }
+            | CryptoError::InvalidPubkeyFormat { .. }
+            | CryptoError::InvalidSignatureFormat { .. }
+            | CryptoError::GenericErr { .. } => err.code(),
+            CryptoError::BatchErr { .. } | CryptoError::InvalidRecoveryParam { .. } => {
+                panic!("Error must not happen for this call")
+            }
+        },
+    };
+    Ok(code)
+}
+
 pub fn do_ed25519_batch_verify<
     A: BackendApi + 'static,
     S: Storage + 'static,
@@ -440,6 +570,89 @@ pub fn do_ed25519_batch_verify<
     Ok(code)
 }
 
+/// Passes an event to the backend while the contract is executing.
+/// This is only allowed in calls that can write to storage.
+pub fn do_emit_event<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    event_ptr: u32,
+) -> VmResult<()> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    if data.execution_mode() == ExecutionMode::Query {
+        return Err(VmError::read_only_execution());
+    }
+    if data.is_storage_readonly() {
+        return Err(VmError::generic_err(
+            "Events cannot be emitted in read-only calls",
+        ));
+    }
+
+    let event_data = read_region(&data.memory(&store), event_ptr, MAX_LENGTH_EVENT)?;
+    let gas_info = GasInfo::with_cost(
+        data.gas_config.emit_event_cost.saturating_add(
+            data.gas_config
+                .emit_event_cost_per_byte
+                .saturating_mul(event_data.len() as u64),
+        ),
+    );
+    process_gas_info(data, &mut store, gas_info)?;
+
+    let event: Event = from_slice(&event_data, MAX_LENGTH_EVENT)?;
+    validate_event(&event)?;
+    if data.increment_emitted_events() > MAX_COUNT_EMITTED_EVENTS {
+        return Err(VmError::generic_err(format!(
+            "Too many events emitted in this call. Max: {MAX_COUNT_EMITTED_EVENTS}"
+        )));
+    }
+
+    let (result, gas_info) = data
+        .api
+        .emit_event(data.contract_address().as_deref(), &event);
+    process_gas_info(data, &mut store, gas_info)?;
+    result?;
+
+    Ok(())
+}
+
+/// Checks an event emitted through `emit_event` by the same rules the chain applies to the
+/// events of a contract's `Response`
+fn validate_event(event: &Event) -> VmResult<()> {
+    if event.ty.trim().len() < 2 {
+        return Err(VmError::generic_err(format!(
+            "Event type too short: '{}'",
+            event.ty
+        )));
+    }
+    for attribute in &event.attributes {
+        let key = attribute.key.trim();
+        if key.is_empty() {
+            return Err(VmError::generic_err(format!(
+                "Empty attribute key in event '{}'",
+                event.ty
+            )));
+        }
+        if key.starts_with('_') {
+            return Err(VmError::generic_err(format!(
+                "Attribute key '{key}' is reserved in event '{}'",
+                event.ty
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the gas that is left for the current call, after charging the cost of this call
+pub fn do_gas_remaining<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+) -> VmResult<u64> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let gas_info = GasInfo::with_cost(data.gas_config.gas_remaining_cost);
+    process_gas_info(data, &mut store, gas_info)?;
+
+    Ok(data.get_gas_left(&mut store))
+}
+
 /// Prints a debug message to console.
 /// This does not charge gas, so debug printing should be disabled when used in a blockchain module.
 pub fn do_debug<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
@@ -448,11 +661,21 @@ pub fn do_debug<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'sta
 ) -> VmResult<()> {
     let (data, mut store) = env.data_and_store_mut();
 
-    if let Some(debug_handler) = data.debug_handler() {
-        let message_data = read_region(&data.memory(&store), message_ptr, MAX_LENGTH_DEBUG)?;
-        let msg = String::from_utf8_lossy(&message_data);
+    let debug_handler = data.debug_handler();
+    let api_debug_enabled = data.api.debug_enabled();
+    if debug_handler.is_none() && !api_debug_enabled {
+        return Ok(());
+    }
+
+    let message_data = read_region(&data.memory(&store), message_ptr, MAX_LENGTH_DEBUG)?;
+    let msg = String::from_utf8_lossy(&message_data);
+    if api_debug_enabled {
+        data.api.debug(data.contract_address().as_deref(), &msg);
+    }
+
+    if let Some(debug_handler) = debug_handler {
         let gas_remaining = data.get_gas_left(&mut store);
-        debug_handler.borrow_mut()(
+        debug_handler.lock().unwrap()(
             &msg,
             DebugInfo {
                 gas_remaining,
@@ -472,6 +695,7 @@ pub fn do_abort<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'sta
 
     let message_data = read_region(&data.memory(&store), message_ptr, MAX_LENGTH_ABORT)?;
     let msg = String::from_utf8_lossy(&message_data);
+    data.api.abort(data.contract_address().as_deref(), &msg);
     Err(VmError::aborted(msg))
 }
 
@@ -496,6 +720,171 @@ pub fn do_query_chain<A: BackendApi + 'static, S: Storage + 'static, Q: Querier
     write_to_contract(data, &mut store, &serialized)
 }
 
+/// Executes a query like [`do_query_chain`] but returns the result in a binary encoding
+/// instead of JSON. This avoids base64 encoding the query response, which is especially
+/// relevant for large protobuf encoded responses.
+///
+/// The encoding is a one byte tag followed by the payload:
+///
+/// - `0x00 || data` for a successful query with the response data
+/// - `0x01 || message` for an error returned by the queried contract/module (UTF-8)
+/// - `0x02 || error` for a system error (JSON encoded [`SystemError`](cosmwasm_std::SystemError))
+pub fn do_query_chain_binary<
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    request_ptr: u32,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let request = read_region(
+        &data.memory(&store),
+        request_ptr,
+        MAX_LENGTH_QUERY_CHAIN_REQUEST,
+    )?;
+
+    let gas_remaining = data.get_gas_left(&mut store);
+    let (result, gas_info) = data.with_querier_from_context::<_, _>(|querier| {
+        Ok(querier.query_raw(&request, gas_remaining))
+    })?;
+    process_gas_info(data, &mut store, gas_info)?;
+    let encoded = encode_query_result(result?)?;
+    write_to_contract(data, &mut store, &encoded)
+}
+
+/// Executes multiple queries like [`do_query_chain_binary`] in one host call.
+///
+/// The requests are passed as sections (see [`encode_sections`]) and the results are
+/// returned as sections in the same order, each in the encoding of [`do_query_chain_binary`].
+/// Gas is charged for every query individually, so running out of gas aborts the whole batch.
+pub fn do_query_chain_batch<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    env: FunctionEnvMut<Environment<A, S, Q>>,
+    requests_ptr: u32,
+) -> VmResult<u32> {
+    query_chain_batch(env, requests_ptr, None)
+}
+
+/// Executes multiple queries like [`do_query_chain_batch`], but each query can use at most
+/// `gas_limit` gas.
+///
+/// A query exceeding its limit results in [`SystemError::OutOfGas`]
+/// for this query only and the gas used up to the limit is charged. If the remaining gas of the
+/// call is lower than the limit, running out of gas aborts the execution like in
+/// [`do_query_chain_batch`].
+pub fn do_query_chain_batch_with_gas_limit<
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+>(
+    env: FunctionEnvMut<Environment<A, S, Q>>,
+    requests_ptr: u32,
+    gas_limit: u64,
+) -> VmResult<u32> {
+    query_chain_batch(env, requests_ptr, Some(gas_limit))
+}
+
+fn query_chain_batch<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    requests_ptr: u32,
+    query_gas_limit: Option<u64>,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let requests = read_region(
+        &data.memory(&store),
+        requests_ptr,
+        (MAX_LENGTH_QUERY_CHAIN_REQUEST + 4) * MAX_COUNT_QUERY_CHAIN_BATCH,
+    )?;
+    let requests = decode_sections_checked(&requests)?;
+    if requests.len() > MAX_COUNT_QUERY_CHAIN_BATCH {
+        return Err(VmError::generic_err(format!(
+            "Too many queries in batch. Got {}, limit {}",
+            requests.len(),
+            MAX_COUNT_QUERY_CHAIN_BATCH
+        )));
+    }
+
+    let mut results = Vec::with_capacity(requests.len());
+    for request in requests {
+        if request.len() > MAX_LENGTH_QUERY_CHAIN_REQUEST {
+            return Err(CommunicationError::region_length_too_big(
+                request.len(),
+                MAX_LENGTH_QUERY_CHAIN_REQUEST,
+            )
+            .into());
+        }
+        let gas_remaining = data.get_gas_left(&mut store);
+        let gas_limit = match query_gas_limit {
+            Some(limit) if limit < gas_remaining => limit,
+            _ => gas_remaining,
+        };
+        let (result, mut gas_info) = data.with_querier_from_context::<_, _>(|querier| {
+            Ok(querier.query_raw(request, gas_limit))
+        })?;
+        let result = match result {
+            // Only the query ran out of gas, not the call
+            Err(BackendError::OutOfGas {}) if gas_limit < gas_remaining => {
+                gas_info.externally_used = gas_info.externally_used.min(gas_limit);
+                Ok(SystemResult::Err(SystemError::OutOfGas { gas_limit }))
+            }
+            result => result,
+        };
+        process_gas_info(data, &mut store, gas_info)?;
+        results.push(encode_query_result(result?)?);
+    }
+    let encoded = encode_sections(&results)?;
+    write_to_contract(data, &mut store, &encoded)
+}
+
+/// Encodes a query result for [`do_query_chain_binary`]
+fn encode_query_result(result: SystemResult<ContractResult<Binary>>) -> VmResult<Vec<u8>> {
+    let (tag, payload) = match result {
+        SystemResult::Ok(ContractResult::Ok(data)) => (0u8, data.into()),
+        SystemResult::Ok(ContractResult::Err(msg)) => (1u8, msg.into_bytes()),
+        SystemResult::Err(err) => (2u8, to_vec(&err)?),
+    };
+    let mut out = Vec::with_capacity(1 + payload.len());
+    out.push(tag);
+    out.extend(payload);
+    Ok(out)
+}
+
+/// Appends a chunk to the response of the current call.
+///
+/// A contract that returns the null pointer from an export instead of a result region
+/// has its response assembled from the chunks written here. This allows responses larger
+/// than the read limit for a single region.
+///
+/// Returns the number of bytes that can still be written (saturating at `u32::MAX`).
+/// Passing the null pointer writes nothing, which allows the contract to query the available
+/// capacity before writing.
+pub fn do_write_region_chunked<
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    chunk_ptr: u32,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let remaining = if chunk_ptr == 0 {
+        data.chunked_response_capacity()
+    } else {
+        let chunk = read_region(&data.memory(&store), chunk_ptr, MAX_LENGTH_RESPONSE_CHUNK)?;
+        let gas_info = GasInfo::with_cost(
+            data.gas_config
+                .response_chunk_cost_per_byte
+                .saturating_mul(chunk.len() as u64),
+        );
+        process_gas_info(data, &mut store, gas_info)?;
+        data.append_response_chunk(&chunk)?
+    };
+    Ok(remaining.try_into().unwrap_or(u32::MAX))
+}
+
 #[cfg(feature = "iterator")]
 pub fn do_db_scan<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
     mut env: FunctionEnvMut<Environment<A, S, Q>>,
@@ -511,14 +900,64 @@ pub fn do_db_scan<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 's
         .try_into()
         .map_err(|_| CommunicationError::invalid_order(order))?;
 
+    let gas_info = GasInfo::with_cost(data.gas_config.iterator_create_cost);
+    process_gas_info(data, &mut store, gas_info)?;
+
     let (result, gas_info) = data.with_storage_from_context::<_, _>(|store| {
         Ok(store.scan(start.as_deref(), end.as_deref(), order))
     })?;
     process_gas_info(data, &mut store, gas_info)?;
     let iterator_id = result?;
+    data.track_iterator(iterator_id, IteratorRange::Range { start, end });
+    Ok(iterator_id)
+}
+
+#[cfg(feature = "iterator")]
+pub fn do_db_scan_prefix<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    prefix_ptr: u32,
+    order: i32,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let prefix = read_region(&data.memory(&store), prefix_ptr, MAX_LENGTH_DB_KEY)?;
+    let order: Order = order
+        .try_into()
+        .map_err(|_| CommunicationError::invalid_order(order))?;
+
+    let gas_info = GasInfo::with_cost(data.gas_config.iterator_create_cost);
+    process_gas_info(data, &mut store, gas_info)?;
+
+    let (result, gas_info) =
+        data.with_storage_from_context::<_, _>(|store| Ok(store.scan_prefix(&prefix, order)))?;
+    process_gas_info(data, &mut store, gas_info)?;
+    let iterator_id = result?;
+    data.track_iterator(iterator_id, IteratorRange::Prefix(prefix));
     Ok(iterator_id)
 }
 
+#[cfg(feature = "iterator")]
+pub fn do_db_seek<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    iterator_id: u32,
+    key_ptr: u32,
+) -> VmResult<()> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let key = read_region(&data.memory(&store), key_ptr, MAX_LENGTH_DB_KEY)?;
+
+    data.check_iterator(iterator_id)?;
+
+    let gas_info = GasInfo::with_cost(data.gas_config.iterator_seek_cost);
+    process_gas_info(data, &mut store, gas_info)?;
+
+    let (result, gas_info) =
+        data.with_storage_from_context::<_, _>(|store| Ok(store.seek(iterator_id, &key)))?;
+    process_gas_info(data, &mut store, gas_info)?;
+    result?;
+    Ok(())
+}
+
 #[cfg(feature = "iterator")]
 pub fn do_db_next<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
     mut env: FunctionEnvMut<Environment<A, S, Q>>,
@@ -526,6 +965,8 @@ pub fn do_db_next<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 's
 ) -> VmResult<u32> {
     let (data, mut store) = env.data_and_store_mut();
 
+    data.check_iterator(iterator_id)?;
+
     let (result, gas_info) =
         data.with_storage_from_context::<_, _>(|store| Ok(store.next(iterator_id)))?;
 
@@ -533,6 +974,8 @@ pub fn do_db_next<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 's
 
     // Empty key will later be treated as _no more element_.
     let (key, value) = result?.unwrap_or_else(|| (Vec::<u8>::new(), Vec::<u8>::new()));
+    let gas_info = iterator_next_gas_info(&data.gas_config, key.len() + value.len());
+    process_gas_info(data, &mut store, gas_info)?;
 
     let out_data = encode_sections(&[key, value])?;
     write_to_contract(data, &mut store, &out_data)
@@ -545,6 +988,8 @@ pub fn do_db_next_key<A: BackendApi + 'static, S: Storage + 'static, Q: Querier
 ) -> VmResult<u32> {
     let (data, mut store) = env.data_and_store_mut();
 
+    data.check_iterator(iterator_id)?;
+
     let (result, gas_info) =
         data.with_storage_from_context::<_, _>(|store| Ok(store.next_key(iterator_id)))?;
 
@@ -552,8 +997,14 @@ pub fn do_db_next_key<A: BackendApi + 'static, S: Storage + 'static, Q: Querier
 
     let key = match result? {
         Some(key) => key,
-        None => return Ok(0),
+        None => {
+            let gas_info = iterator_next_gas_info(&data.gas_config, 0);
+            process_gas_info(data, &mut store, gas_info)?;
+            return Ok(0);
+        }
     };
+    let gas_info = iterator_next_gas_info(&data.gas_config, key.len());
+    process_gas_info(data, &mut store, gas_info)?;
 
     write_to_contract(data, &mut store, &key)
 }
@@ -565,6 +1016,8 @@ pub fn do_db_next_value<A: BackendApi + 'static, S: Storage + 'static, Q: Querie
 ) -> VmResult<u32> {
     let (data, mut store) = env.data_and_store_mut();
 
+    data.check_iterator(iterator_id)?;
+
     let (result, gas_info) =
         data.with_storage_from_context::<_, _>(|store| Ok(store.next_value(iterator_id)))?;
 
@@ -572,14 +1025,33 @@ pub fn do_db_next_value<A: BackendApi + 'static, S: Storage + 'static, Q: Querie
 
     let value = match result? {
         Some(value) => value,
-        None => return Ok(0),
+        None => {
+            let gas_info = iterator_next_gas_info(&data.gas_config, 0);
+            process_gas_info(data, &mut store, gas_info)?;
+            return Ok(0);
+        }
     };
+    let gas_info = iterator_next_gas_info(&data.gas_config, value.len());
+    process_gas_info(data, &mut store, gas_info)?;
 
     write_to_contract(data, &mut store, &value)
 }
 
-/// Creates a Region in the contract, writes the given data to it and returns the memory location
-fn write_to_contract<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+/// Gas charged by the VM for advancing a storage iterator that returned `returned_bytes`
+/// bytes of key and value data. This comes on top of the gas reported by the backend.
+#[cfg(feature = "iterator")]
+fn iterator_next_gas_info(gas_config: &GasConfig, returned_bytes: usize) -> GasInfo {
+    let returned_bytes = u64::try_from(returned_bytes).unwrap_or(u64::MAX);
+    GasInfo::with_cost(
+        gas_config
+            .iterator_next_cost_per_byte
+            .saturating_mul(returned_bytes)
+            .saturating_add(gas_config.iterator_next_cost),
+    )
+}
+
+/// Creates a Region in the contract, writes the given data to it and returns the memory location
+fn write_to_contract<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
     data: &Environment<A, S, Q>,
     store: &mut impl AsStoreMut,
     input: &[u8],
@@ -623,6 +1095,7 @@ mod tests {
     };
     use hex_literal::hex;
     use std::ptr::NonNull;
+    use std::sync::{Arc, Mutex};
     use wasmer::{imports, Function, FunctionEnv, Instance as WasmerInstance, Store};
 
     use crate::backend::{BackendError, Storage};
@@ -655,6 +1128,17 @@ mod tests {
     const EDDSA_PUBKEY_HEX: &str =
         "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
 
+    // Ed25519ctx and Ed25519ph test vectors from https://www.rfc-editor.org/rfc/rfc8032#section-7.2
+    const EDDSA_CTX_MSG_HEX: &str = "f726936d19c800494e3fdaff20b276a8";
+    const EDDSA_CTX_CONTEXT_HEX: &str = "666f6f";
+    const EDDSA_CTX_SIG_HEX: &str = "55a4cc2f70a54e04288c5f4cd1e45a7bb520b36292911876cada7323198dd87a8b36950b95130022907a7fb7c4e9b2d5f6cca685a587b4b21f4b888e4e7edb0d";
+    const EDDSA_CTX_PUBKEY_HEX: &str =
+        "dfc9425e4f968f7f0c29f0259cf5f9aed6851c2bb4ad8bfb860cfee0ab248292";
+    const EDDSA_PH_HASH_HEX: &str = "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f";
+    const EDDSA_PH_SIG_HEX: &str = "98a70222f0b8121aa9d30f813d683f809e462b469c7ff87639499bb94e6dae4131f85042463c2a355a2003d062adf5aaa10b8c61e636062aaad11c2a26083406";
+    const EDDSA_PH_PUBKEY_HEX: &str =
+        "ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf";
+
     fn make_instance(
         api: MockApi,
     ) -> (
@@ -939,6 +1423,240 @@ mod tests {
         }
     }
 
+    #[test]
+    fn do_db_size_works() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+        leave_default_data(&mut fe_mut);
+
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        let gas_before = env.get_gas_left(&mut store);
+        let db_size_cost = env.gas_config.db_size_cost;
+
+        let size_ptr = do_db_size(fe_mut.as_mut()).unwrap();
+        let data = force_read(&mut fe_mut, size_ptr);
+        // 2 keys with 3+6 and 4+5 bytes
+        assert_eq!(data, [0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 18]);
+
+        // allocating the result region uses some Wasm gas on top
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        assert!(env.get_gas_left(&mut store) <= gas_before - db_size_cost);
+
+        // out of gas before asking the backend
+        env.set_gas_left(&mut store, db_size_cost - 1);
+        match do_db_size(fe_mut.as_mut()).unwrap_err() {
+            VmError::GasDepletion { .. } => {}
+            err => panic!("Unexpected error: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn do_emit_event_works() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let event_ptr = write_data(
+            &mut fe_mut,
+            br#"{"type":"progress","attributes":[{"key":"item","value":"1"}]}"#,
+        );
+        leave_default_data(&mut fe_mut);
+
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        let gas_before = env.get_gas_left(&mut store);
+        let gas_config = env.gas_config.clone();
+
+        do_emit_event(fe_mut.as_mut(), event_ptr).unwrap();
+
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        let event_len = 61;
+        assert_eq!(
+            env.get_gas_left(&mut store),
+            gas_before
+                - gas_config.emit_event_cost
+                - event_len * gas_config.emit_event_cost_per_byte
+        );
+    }
+
+    #[test]
+    fn do_emit_event_validates_event() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let short_type = write_data(&mut fe_mut, br#"{"type":" a ","attributes":[]}"#);
+        let empty_key = write_data(
+            &mut fe_mut,
+            br#"{"type":"progress","attributes":[{"key":" ","value":"1"}]}"#,
+        );
+        let reserved_key = write_data(
+            &mut fe_mut,
+            br#"{"type":"progress","attributes":[{"key":"_contract_address","value":"1"}]}"#,
+        );
+        leave_default_data(&mut fe_mut);
+
+        let err = do_emit_event(fe_mut.as_mut(), short_type).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Event type too short: ' a '"
+        );
+        let err = do_emit_event(fe_mut.as_mut(), empty_key).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Empty attribute key in event 'progress'"
+        );
+        let err = do_emit_event(fe_mut.as_mut(), reserved_key).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Attribute key '_contract_address' is reserved in event 'progress'"
+        );
+    }
+
+    #[test]
+    fn do_emit_event_limits_number_of_events() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let event_ptr = write_data(&mut fe_mut, br#"{"type":"progress","attributes":[]}"#);
+        leave_default_data(&mut fe_mut);
+
+        // make sure we hit the count limit before running out of gas
+        let (env, _store) = fe_mut.data_and_store_mut();
+        env.gas_config.emit_event_cost = 0;
+        env.gas_config.emit_event_cost_per_byte = 0;
+
+        for _ in 0..MAX_COUNT_EMITTED_EVENTS {
+            do_emit_event(fe_mut.as_mut(), event_ptr).unwrap();
+        }
+        let err = do_emit_event(fe_mut.as_mut(), event_ptr).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Too many events emitted in this call. Max: 1024"
+        );
+
+        // the count is reset for the next call
+        fe_mut.data().set_call_env(None);
+        do_emit_event(fe_mut.as_mut(), event_ptr).unwrap();
+    }
+
+    #[test]
+    fn do_emit_event_fails_for_invalid_event() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let event_ptr = write_data(&mut fe_mut, b"not an event");
+        leave_default_data(&mut fe_mut);
+
+        let result = do_emit_event(fe_mut.as_mut(), event_ptr);
+        match result.unwrap_err() {
+            VmError::ParseErr { .. } => {}
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn do_emit_event_fails_for_backend_error() {
+        let api = MockApi::new_failing("Temporarily unavailable");
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let event_ptr = write_data(&mut fe_mut, br#"{"type":"progress","attributes":[]}"#);
+        leave_default_data(&mut fe_mut);
+
+        let result = do_emit_event(fe_mut.as_mut(), event_ptr);
+        match result.unwrap_err() {
+            VmError::BackendErr {
+                source: BackendError::Unknown { msg, .. },
+                ..
+            } => assert_eq!(msg, "Temporarily unavailable"),
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn do_emit_event_is_prohibited_in_readonly_contexts() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let event_ptr = write_data(&mut fe_mut, br#"{"type":"progress","attributes":[]}"#);
+        leave_default_data(&mut fe_mut);
+        fe_mut.data().set_storage_readonly(true);
+
+        let result = do_emit_event(fe_mut.as_mut(), event_ptr);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Generic error: Events cannot be emitted in read-only calls"
+        );
+    }
+
+    #[test]
+    fn do_emit_event_is_prohibited_in_query_execution_mode() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let event_ptr = write_data(&mut fe_mut, br#"{"type":"progress","attributes":[]}"#);
+        leave_default_data(&mut fe_mut);
+        // the storage flag allows writes, but the execution mode does not
+        fe_mut.data().set_storage_readonly(false);
+        fe_mut.data().set_execution_mode(ExecutionMode::Query);
+
+        match do_emit_event(fe_mut.as_mut(), event_ptr).unwrap_err() {
+            VmError::ReadOnlyExecution { .. } => {}
+            e => panic!("Unexpected error: {e:?}"),
+        }
+
+        fe_mut.data().set_execution_mode(ExecutionMode::Execute);
+        do_emit_event(fe_mut.as_mut(), event_ptr).unwrap();
+    }
+
+    #[test]
+    fn do_debug_ignores_message_when_debug_is_disabled() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        // The region is not read, so an invalid pointer does not abort execution
+        do_debug(fe_mut.as_mut(), 0xFFFF_FFF0).unwrap();
+
+        let (env, _store) = fe_mut.data_and_store_mut();
+        env.set_debug_handler(Some(Arc::new(Mutex::new(|_: &str, _: DebugInfo<'_>| {}))));
+        do_debug(fe_mut.as_mut(), 0xFFFF_FFF0).unwrap_err();
+    }
+
+    #[test]
+    fn do_gas_remaining_works() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        let gas_left = env.get_gas_left(&mut store);
+        let cost = env.gas_config.gas_remaining_cost;
+        assert!(cost > 0);
+        assert_eq!(do_gas_remaining(fe_mut.as_mut()).unwrap(), gas_left - cost);
+
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        assert_eq!(env.get_gas_left(&mut store), gas_left - cost);
+        env.decrease_gas_left(&mut store, 1000).unwrap();
+        assert_eq!(
+            do_gas_remaining(fe_mut.as_mut()).unwrap(),
+            gas_left - 1000 - 2 * cost
+        );
+
+        // out of gas
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        env.set_gas_left(&mut store, cost - 1);
+        match do_gas_remaining(fe_mut.as_mut()).unwrap_err() {
+            VmError::GasDepletion { .. } => {}
+            err => panic!("Unexpected error: {err:?}"),
+        }
+    }
+
     #[test]
     fn do_db_remove_works() {
         let api = MockApi::default();
@@ -1044,6 +1762,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn do_db_write_and_remove_are_prohibited_in_query_execution_mode() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let key_ptr = write_data(&mut fe_mut, KEY1);
+        let value_ptr = write_data(&mut fe_mut, VALUE2);
+
+        leave_default_data(&mut fe_mut);
+        // the storage flag allows writes, but the execution mode does not
+        fe_mut.data().set_storage_readonly(false);
+        fe_mut.data().set_execution_mode(ExecutionMode::Query);
+
+        match do_db_write(fe_mut.as_mut(), key_ptr, value_ptr).unwrap_err() {
+            VmError::ReadOnlyExecution { .. } => {}
+            e => panic!("Unexpected error: {e:?}"),
+        }
+        match do_db_remove(fe_mut.as_mut(), key_ptr).unwrap_err() {
+            VmError::ReadOnlyExecution { .. } => {}
+            e => panic!("Unexpected error: {e:?}"),
+        }
+
+        // state is unchanged
+        let val = fe_mut
+            .data()
+            .with_storage_from_context::<_, _>(|store| Ok(store.get(KEY1).0.unwrap()))
+            .unwrap();
+        assert_eq!(val, Some(VALUE1.to_vec()));
+
+        fe_mut.data().set_execution_mode(ExecutionMode::Execute);
+        do_db_write(fe_mut.as_mut(), key_ptr, value_ptr).unwrap();
+    }
+
     #[test]
     fn do_addr_validate_works() {
         let api = MockApi::default();
@@ -1912,94 +2664,554 @@ mod tests {
     }
 
     #[test]
-    fn do_query_chain_works() {
+    fn do_ed25519ctx_verify_works() {
         let api = MockApi::default();
         let (fe, mut store, _instance) = make_instance(api);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let request: QueryRequest<Empty> = QueryRequest::Bank(BankQuery::AllBalances {
-            address: INIT_ADDR.to_string(),
-        });
-        let request_data = cosmwasm_std::to_json_vec(&request).unwrap();
-        let request_ptr = write_data(&mut fe_mut, &request_data);
-
-        leave_default_data(&mut fe_mut);
-
-        let response_ptr = do_query_chain(fe_mut.as_mut(), request_ptr).unwrap();
-        let response = force_read(&mut fe_mut, response_ptr);
+        let msg = hex::decode(EDDSA_CTX_MSG_HEX).unwrap();
+        let msg_ptr = write_data(&mut fe_mut, &msg);
+        let context = hex::decode(EDDSA_CTX_CONTEXT_HEX).unwrap();
+        let context_ptr = write_data(&mut fe_mut, &context);
+        let sig = hex::decode(EDDSA_CTX_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = hex::decode(EDDSA_CTX_PUBKEY_HEX).unwrap();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
 
-        let query_result: cosmwasm_std::QuerierResult = cosmwasm_std::from_json(response).unwrap();
-        let query_result_inner = query_result.unwrap();
-        let query_result_inner_inner = query_result_inner.unwrap();
-        let parsed_again: AllBalanceResponse = from_json(query_result_inner_inner).unwrap();
-        assert_eq!(parsed_again.amount, coins(INIT_AMOUNT, INIT_DENOM));
+        assert_eq!(
+            do_ed25519ctx_verify(fe_mut, msg_ptr, context_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            0
+        );
     }
 
     #[test]
-    fn do_query_chain_fails_for_broken_request() {
+    fn do_ed25519ctx_verify_wrong_context_verify_fails() {
         let api = MockApi::default();
         let (fe, mut store, _instance) = make_instance(api);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let request = b"Not valid JSON for sure";
-        let request_ptr = write_data(&mut fe_mut, request);
-
-        leave_default_data(&mut fe_mut);
-
-        let response_ptr = do_query_chain(fe_mut.as_mut(), request_ptr).unwrap();
-        let response = force_read(&mut fe_mut, response_ptr);
+        let msg = hex::decode(EDDSA_CTX_MSG_HEX).unwrap();
+        let msg_ptr = write_data(&mut fe_mut, &msg);
+        let context_ptr = write_data(&mut fe_mut, b"bar");
+        let sig = hex::decode(EDDSA_CTX_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = hex::decode(EDDSA_CTX_PUBKEY_HEX).unwrap();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
 
-        let query_result: cosmwasm_std::QuerierResult = cosmwasm_std::from_json(response).unwrap();
-        match query_result {
-            SystemResult::Ok(_) => panic!("This must not succeed"),
-            SystemResult::Err(SystemError::InvalidRequest { request: err, .. }) => {
-                assert_eq!(err.as_slice(), request)
-            }
-            SystemResult::Err(err) => panic!("Unexpected error: {err:?}"),
-        }
+        assert_eq!(
+            do_ed25519ctx_verify(fe_mut, msg_ptr, context_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            1
+        );
     }
 
     #[test]
-    fn do_query_chain_fails_for_missing_contract() {
+    fn do_ed25519ctx_verify_empty_context_fails() {
         let api = MockApi::default();
         let (fe, mut store, _instance) = make_instance(api);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let request: QueryRequest<Empty> = QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: String::from("non-existent"),
-            msg: Binary::from(b"{}" as &[u8]),
-        });
-        let request_data = cosmwasm_std::to_json_vec(&request).unwrap();
-        let request_ptr = write_data(&mut fe_mut, &request_data);
+        let msg = hex::decode(EDDSA_CTX_MSG_HEX).unwrap();
+        let msg_ptr = write_data(&mut fe_mut, &msg);
+        let context_ptr = write_data(&mut fe_mut, b"");
+        let sig = hex::decode(EDDSA_CTX_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = hex::decode(EDDSA_CTX_PUBKEY_HEX).unwrap();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
 
-        leave_default_data(&mut fe_mut);
+        assert_eq!(
+            do_ed25519ctx_verify(fe_mut, msg_ptr, context_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            10 // generic error
+        );
+    }
 
-        let response_ptr = do_query_chain(fe_mut.as_mut(), request_ptr).unwrap();
-        let response = force_read(&mut fe_mut, response_ptr);
+    #[test]
+    fn do_ed25519ctx_verify_larger_context_fails() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
 
-        let query_result: cosmwasm_std::QuerierResult = cosmwasm_std::from_json(response).unwrap();
-        match query_result {
-            SystemResult::Ok(_) => panic!("This must not succeed"),
-            SystemResult::Err(SystemError::NoSuchContract { addr }) => {
-                assert_eq!(addr, "non-existent")
-            }
-            SystemResult::Err(err) => panic!("Unexpected error: {err:?}"),
+        let msg = hex::decode(EDDSA_CTX_MSG_HEX).unwrap();
+        let msg_ptr = write_data(&mut fe_mut, &msg);
+        let context_ptr = write_data(&mut fe_mut, &[0x66; EDDSA_CONTEXT_MAX_LEN + 1]);
+        let sig = hex::decode(EDDSA_CTX_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = hex::decode(EDDSA_CTX_PUBKEY_HEX).unwrap();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+
+        let result = do_ed25519ctx_verify(fe_mut, msg_ptr, context_ptr, sig_ptr, pubkey_ptr);
+        match result.unwrap_err() {
+            VmError::CommunicationErr {
+                source: CommunicationError::RegionLengthTooBig { length, .. },
+                ..
+            } => assert_eq!(length, EDDSA_CONTEXT_MAX_LEN + 1),
+            e => panic!("Unexpected error: {e:?}"),
         }
     }
 
     #[test]
-    #[cfg(feature = "iterator")]
-    fn do_db_scan_unbound_works() {
+    fn do_ed25519ph_verify_works() {
         let api = MockApi::default();
         let (fe, mut store, _instance) = make_instance(api);
         let mut fe_mut = fe.into_mut(&mut store);
-        leave_default_data(&mut fe_mut);
 
-        // set up iterator over all space
-        let id = do_db_scan(fe_mut.as_mut(), 0, 0, Order::Ascending.into()).unwrap();
-        assert_eq!(1, id);
+        let hash = hex::decode(EDDSA_PH_HASH_HEX).unwrap();
+        let hash_ptr = write_data(&mut fe_mut, &hash);
+        let context_ptr = write_data(&mut fe_mut, b"");
+        let sig = hex::decode(EDDSA_PH_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = hex::decode(EDDSA_PH_PUBKEY_HEX).unwrap();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
 
-        let item = fe_mut
+        assert_eq!(
+            do_ed25519ph_verify(fe_mut, hash_ptr, context_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn do_ed25519ph_verify_wrong_hash_verify_fails() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let mut hash = hex::decode(EDDSA_PH_HASH_HEX).unwrap();
+        // alter hash
+        hash[0] ^= 0x01;
+        let hash_ptr = write_data(&mut fe_mut, &hash);
+        let context_ptr = write_data(&mut fe_mut, b"");
+        let sig = hex::decode(EDDSA_PH_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = hex::decode(EDDSA_PH_PUBKEY_HEX).unwrap();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+
+        assert_eq!(
+            do_ed25519ph_verify(fe_mut, hash_ptr, context_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn do_ed25519ph_verify_shorter_hash_fails() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let mut hash = hex::decode(EDDSA_PH_HASH_HEX).unwrap();
+        // reduce / break hash
+        hash.pop();
+        let hash_ptr = write_data(&mut fe_mut, &hash);
+        let context_ptr = write_data(&mut fe_mut, b"");
+        let sig = hex::decode(EDDSA_PH_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = hex::decode(EDDSA_PH_PUBKEY_HEX).unwrap();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+
+        assert_eq!(
+            do_ed25519ph_verify(fe_mut, hash_ptr, context_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            3 // mapped InvalidHashFormat
+        );
+    }
+
+    #[test]
+    fn do_query_chain_works() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let request: QueryRequest<Empty> = QueryRequest::Bank(BankQuery::AllBalances {
+            address: INIT_ADDR.to_string(),
+        });
+        let request_data = cosmwasm_std::to_json_vec(&request).unwrap();
+        let request_ptr = write_data(&mut fe_mut, &request_data);
+
+        leave_default_data(&mut fe_mut);
+
+        let response_ptr = do_query_chain(fe_mut.as_mut(), request_ptr).unwrap();
+        let response = force_read(&mut fe_mut, response_ptr);
+
+        let query_result: cosmwasm_std::QuerierResult = cosmwasm_std::from_json(response).unwrap();
+        let query_result_inner = query_result.unwrap();
+        let query_result_inner_inner = query_result_inner.unwrap();
+        let parsed_again: AllBalanceResponse = from_json(query_result_inner_inner).unwrap();
+        assert_eq!(parsed_again.amount, coins(INIT_AMOUNT, INIT_DENOM));
+    }
+
+    #[test]
+    fn do_query_chain_binary_works() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let request: QueryRequest<Empty> = QueryRequest::Bank(BankQuery::AllBalances {
+            address: INIT_ADDR.to_string(),
+        });
+        let request_data = cosmwasm_std::to_json_vec(&request).unwrap();
+        let request_ptr = write_data(&mut fe_mut, &request_data);
+
+        leave_default_data(&mut fe_mut);
+
+        let response_ptr = do_query_chain_binary(fe_mut.as_mut(), request_ptr).unwrap();
+        let response = force_read(&mut fe_mut, response_ptr);
+
+        assert_eq!(response[0], 0);
+        let parsed: AllBalanceResponse = from_json(&response[1..]).unwrap();
+        assert_eq!(parsed.amount, coins(INIT_AMOUNT, INIT_DENOM));
+    }
+
+    #[test]
+    fn do_query_chain_binary_fails_for_missing_contract() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let request: QueryRequest<Empty> = QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: String::from("non-existent"),
+            msg: Binary::from(b"{}" as &[u8]),
+        });
+        let request_data = cosmwasm_std::to_json_vec(&request).unwrap();
+        let request_ptr = write_data(&mut fe_mut, &request_data);
+
+        leave_default_data(&mut fe_mut);
+
+        let response_ptr = do_query_chain_binary(fe_mut.as_mut(), request_ptr).unwrap();
+        let response = force_read(&mut fe_mut, response_ptr);
+
+        assert_eq!(response[0], 2);
+        let err: SystemError = from_json(&response[1..]).unwrap();
+        assert_eq!(
+            err,
+            SystemError::NoSuchContract {
+                addr: "non-existent".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn do_query_chain_batch_works() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let balance_request: QueryRequest<Empty> = QueryRequest::Bank(BankQuery::AllBalances {
+            address: INIT_ADDR.to_string(),
+        });
+        let missing_request: QueryRequest<Empty> = QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: String::from("non-existent"),
+            msg: Binary::from(b"{}" as &[u8]),
+        });
+        let requests_data = encode_sections(&[
+            cosmwasm_std::to_json_vec(&balance_request).unwrap(),
+            cosmwasm_std::to_json_vec(&missing_request).unwrap(),
+        ])
+        .unwrap();
+        let requests_ptr = write_data(&mut fe_mut, &requests_data);
+
+        leave_default_data(&mut fe_mut);
+
+        let response_ptr = do_query_chain_batch(fe_mut.as_mut(), requests_ptr).unwrap();
+        let response = force_read(&mut fe_mut, response_ptr);
+        let results = decode_sections(&response);
+        assert_eq!(results.len(), 2);
+
+        assert_eq!(results[0][0], 0);
+        let parsed: AllBalanceResponse = from_json(&results[0][1..]).unwrap();
+        assert_eq!(parsed.amount, coins(INIT_AMOUNT, INIT_DENOM));
+
+        assert_eq!(results[1][0], 2);
+        let err: SystemError = from_json(&results[1][1..]).unwrap();
+        assert_eq!(
+            err,
+            SystemError::NoSuchContract {
+                addr: "non-existent".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn do_query_chain_batch_with_gas_limit_works() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let request: QueryRequest<Empty> = QueryRequest::Bank(BankQuery::AllBalances {
+            address: INIT_ADDR.to_string(),
+        });
+        let requests_data =
+            encode_sections(&[cosmwasm_std::to_json_vec(&request).unwrap()]).unwrap();
+        let requests_ptr = write_data(&mut fe_mut, &requests_data);
+
+        leave_default_data(&mut fe_mut);
+
+        // enough gas
+        let response_ptr =
+            do_query_chain_batch_with_gas_limit(fe_mut.as_mut(), requests_ptr, 10_000_000).unwrap();
+        let response = force_read(&mut fe_mut, response_ptr);
+        let results = decode_sections(&response);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0][0], 0);
+        let parsed: AllBalanceResponse = from_json(&results[0][1..]).unwrap();
+        assert_eq!(parsed.amount, coins(INIT_AMOUNT, INIT_DENOM));
+
+        // query runs out of gas but the call continues
+        let used_before = fe_mut
+            .data()
+            .with_gas_state(|gas_state| gas_state.externally_used_gas);
+        let response_ptr =
+            do_query_chain_batch_with_gas_limit(fe_mut.as_mut(), requests_ptr, 10).unwrap();
+        let response = force_read(&mut fe_mut, response_ptr);
+        let results = decode_sections(&response);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0][0], 2);
+        let err: SystemError = from_json(&results[0][1..]).unwrap();
+        assert_eq!(err, SystemError::OutOfGas { gas_limit: 10 });
+        // only the gas up to the limit is charged
+        let used_after = fe_mut
+            .data()
+            .with_gas_state(|gas_state| gas_state.externally_used_gas);
+        assert_eq!(used_after - used_before, 10);
+    }
+
+    #[test]
+    fn do_query_chain_batch_works_for_empty_batch() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let requests_ptr = write_data(&mut fe_mut, b"");
+
+        leave_default_data(&mut fe_mut);
+
+        let response_ptr = do_query_chain_batch(fe_mut.as_mut(), requests_ptr).unwrap();
+        let response = force_read(&mut fe_mut, response_ptr);
+        assert!(response.is_empty());
+    }
+
+    #[test]
+    fn do_query_chain_batch_fails_for_malformed_sections() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let requests_ptr = write_data(&mut fe_mut, b"{}\0\0\0\x09");
+
+        leave_default_data(&mut fe_mut);
+
+        let result = do_query_chain_batch(fe_mut.as_mut(), requests_ptr);
+        match result.unwrap_err() {
+            VmError::CommunicationErr {
+                source: CommunicationError::InvalidSections { .. },
+                ..
+            } => {}
+            err => panic!("Incorrect error returned: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn do_query_chain_batch_fails_for_too_many_queries() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let requests_data =
+            encode_sections(&vec![b"{}".to_vec(); MAX_COUNT_QUERY_CHAIN_BATCH + 1]).unwrap();
+        let requests_ptr = write_data(&mut fe_mut, &requests_data);
+
+        leave_default_data(&mut fe_mut);
+
+        let result = do_query_chain_batch(fe_mut.as_mut(), requests_ptr);
+        match result.unwrap_err() {
+            VmError::GenericErr { msg, .. } => {
+                assert_eq!(msg, "Too many queries in batch. Got 65, limit 64")
+            }
+            err => panic!("Incorrect error returned: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn encode_query_result_works() {
+        let encoded = encode_query_result(SystemResult::Ok(ContractResult::Ok(Binary::from(
+            b"\xFF\x00",
+        ))))
+        .unwrap();
+        assert_eq!(encoded, b"\x00\xFF\x00");
+        let encoded =
+            encode_query_result(SystemResult::Ok(ContractResult::Err("broken".to_string())))
+                .unwrap();
+        assert_eq!(encoded, b"\x01broken");
+        let encoded = encode_query_result(SystemResult::Err(SystemError::Unknown {})).unwrap();
+        assert_eq!(encoded, b"\x02{\"unknown\":{}}");
+    }
+
+    #[test]
+    fn do_write_region_chunked_works() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let chunk1_ptr = write_data(&mut fe_mut, b"Hello, ");
+        let chunk2_ptr = write_data(&mut fe_mut, b"world");
+
+        let (env, _store) = fe_mut.data_and_store_mut();
+        env.start_chunked_response(20);
+
+        // query capacity
+        let remaining = do_write_region_chunked(fe_mut.as_mut(), 0).unwrap();
+        assert_eq!(remaining, 20);
+
+        let remaining = do_write_region_chunked(fe_mut.as_mut(), chunk1_ptr).unwrap();
+        assert_eq!(remaining, 13);
+        let remaining = do_write_region_chunked(fe_mut.as_mut(), chunk2_ptr).unwrap();
+        assert_eq!(remaining, 8);
+
+        let (env, _store) = fe_mut.data_and_store_mut();
+        assert_eq!(env.finish_chunked_response().unwrap(), b"Hello, world");
+        assert_eq!(env.chunked_response_capacity(), 0);
+    }
+
+    #[test]
+    fn do_write_region_chunked_charges_gas_per_byte() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let chunk_ptr = write_data(&mut fe_mut, b"Hello, ");
+
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        env.gas_config.response_chunk_cost_per_byte = 1000;
+        env.start_chunked_response(20);
+        let gas_before = env.get_gas_left(&mut store);
+
+        // querying the capacity is free
+        do_write_region_chunked(fe_mut.as_mut(), 0).unwrap();
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        assert_eq!(env.get_gas_left(&mut store), gas_before);
+
+        do_write_region_chunked(fe_mut.as_mut(), chunk_ptr).unwrap();
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        assert_eq!(env.get_gas_left(&mut store), gas_before - 7 * 1000);
+
+        // out of gas before appending
+        env.set_gas_left(&mut store, 6 * 1000);
+        match do_write_region_chunked(fe_mut.as_mut(), chunk_ptr).unwrap_err() {
+            VmError::GasDepletion { .. } => {}
+            err => panic!("unexpected error: {err:?}"),
+        }
+        let (env, _store) = fe_mut.data_and_store_mut();
+        assert_eq!(env.finish_chunked_response().unwrap(), b"Hello, ");
+    }
+
+    #[test]
+    fn do_write_region_chunked_fails_when_exceeding_limit() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let chunk_ptr = write_data(&mut fe_mut, b"Hello, ");
+
+        let (env, _store) = fe_mut.data_and_store_mut();
+        env.start_chunked_response(10);
+
+        do_write_region_chunked(fe_mut.as_mut(), chunk_ptr).unwrap();
+        let result = do_write_region_chunked(fe_mut.as_mut(), chunk_ptr);
+        match result.unwrap_err() {
+            VmError::CommunicationErr {
+                source:
+                    CommunicationError::RegionLengthTooBig {
+                        length, max_length, ..
+                    },
+                ..
+            } => {
+                assert_eq!(length, 14);
+                assert_eq!(max_length, 10);
+            }
+            err => panic!("unexpected error: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn do_write_region_chunked_fails_when_not_started() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let chunk_ptr = write_data(&mut fe_mut, b"Hello, ");
+
+        let result = do_write_region_chunked(fe_mut.as_mut(), chunk_ptr);
+        match result.unwrap_err() {
+            VmError::GenericErr { msg, .. } => {
+                assert_eq!(msg, "Chunked responses are not supported in this call")
+            }
+            err => panic!("unexpected error: {err:?}"),
+        }
+        // capacity is 0
+        assert_eq!(do_write_region_chunked(fe_mut.as_mut(), 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn do_query_chain_fails_for_broken_request() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let request = b"Not valid JSON for sure";
+        let request_ptr = write_data(&mut fe_mut, request);
+
+        leave_default_data(&mut fe_mut);
+
+        let response_ptr = do_query_chain(fe_mut.as_mut(), request_ptr).unwrap();
+        let response = force_read(&mut fe_mut, response_ptr);
+
+        let query_result: cosmwasm_std::QuerierResult = cosmwasm_std::from_json(response).unwrap();
+        match query_result {
+            SystemResult::Ok(_) => panic!("This must not succeed"),
+            SystemResult::Err(SystemError::InvalidRequest { request: err, .. }) => {
+                assert_eq!(err.as_slice(), request)
+            }
+            SystemResult::Err(err) => panic!("Unexpected error: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn do_query_chain_fails_for_missing_contract() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let request: QueryRequest<Empty> = QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: String::from("non-existent"),
+            msg: Binary::from(b"{}" as &[u8]),
+        });
+        let request_data = cosmwasm_std::to_json_vec(&request).unwrap();
+        let request_ptr = write_data(&mut fe_mut, &request_data);
+
+        leave_default_data(&mut fe_mut);
+
+        let response_ptr = do_query_chain(fe_mut.as_mut(), request_ptr).unwrap();
+        let response = force_read(&mut fe_mut, response_ptr);
+
+        let query_result: cosmwasm_std::QuerierResult = cosmwasm_std::from_json(response).unwrap();
+        match query_result {
+            SystemResult::Ok(_) => panic!("This must not succeed"),
+            SystemResult::Err(SystemError::NoSuchContract { addr }) => {
+                assert_eq!(addr, "non-existent")
+            }
+            SystemResult::Err(err) => panic!("Unexpected error: {err:?}"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn do_db_scan_unbound_works() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+        leave_default_data(&mut fe_mut);
+
+        // set up iterator over all space
+        let id = do_db_scan(fe_mut.as_mut(), 0, 0, Order::Ascending.into()).unwrap();
+        assert_eq!(1, id);
+
+        let item = fe_mut
             .data()
             .with_storage_from_context::<_, _>(|store| Ok(store.next(id)))
             .unwrap();
@@ -2196,6 +3408,89 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn do_db_scan_prefix_works() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+        leave_default_data(&mut fe_mut);
+
+        let prefix_ptr = write_data(&mut fe_mut, b"tr");
+        let id = do_db_scan_prefix(fe_mut.as_mut(), prefix_ptr, Order::Descending.into()).unwrap();
+
+        let key_region_ptr = do_db_next_key(fe_mut.as_mut(), id).unwrap();
+        assert_eq!(force_read(&mut fe_mut, key_region_ptr), KEY2);
+        assert_eq!(do_db_next_key(fe_mut.as_mut(), id).unwrap(), 0);
+
+        // empty prefix covers everything
+        let prefix_ptr = write_data(&mut fe_mut, b"");
+        let id = do_db_scan_prefix(fe_mut.as_mut(), prefix_ptr, Order::Ascending.into()).unwrap();
+        let key_region_ptr = do_db_next_key(fe_mut.as_mut(), id).unwrap();
+        assert_eq!(force_read(&mut fe_mut, key_region_ptr), KEY1);
+        let key_region_ptr = do_db_next_key(fe_mut.as_mut(), id).unwrap();
+        assert_eq!(force_read(&mut fe_mut, key_region_ptr), KEY2);
+        assert_eq!(do_db_next_key(fe_mut.as_mut(), id).unwrap(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn do_db_scan_prefix_fails_for_invalid_order() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+        leave_default_data(&mut fe_mut);
+
+        let prefix_ptr = write_data(&mut fe_mut, b"tr");
+        let result = do_db_scan_prefix(fe_mut, prefix_ptr, 42);
+        match result.unwrap_err() {
+            VmError::CommunicationErr {
+                source: CommunicationError::InvalidOrder { .. },
+                ..
+            } => {}
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn do_db_seek_works() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+        leave_default_data(&mut fe_mut);
+
+        let id = do_db_scan(fe_mut.as_mut(), 0, 0, Order::Ascending.into()).unwrap();
+        let key_ptr = write_data(&mut fe_mut, b"b");
+        do_db_seek(fe_mut.as_mut(), id, key_ptr).unwrap();
+        let key_region_ptr = do_db_next_key(fe_mut.as_mut(), id).unwrap();
+        assert_eq!(force_read(&mut fe_mut, key_region_ptr), KEY2);
+
+        // seeking backwards
+        let key_ptr = write_data(&mut fe_mut, KEY1);
+        do_db_seek(fe_mut.as_mut(), id, key_ptr).unwrap();
+        let key_region_ptr = do_db_next_key(fe_mut.as_mut(), id).unwrap();
+        assert_eq!(force_read(&mut fe_mut, key_region_ptr), KEY1);
+
+        // descending finds the last key before
+        let id = do_db_scan(fe_mut.as_mut(), 0, 0, Order::Descending.into()).unwrap();
+        let key_ptr = write_data(&mut fe_mut, b"b");
+        do_db_seek(fe_mut.as_mut(), id, key_ptr).unwrap();
+        let key_region_ptr = do_db_next_key(fe_mut.as_mut(), id).unwrap();
+        assert_eq!(force_read(&mut fe_mut, key_region_ptr), KEY1);
+        assert_eq!(do_db_next_key(fe_mut.as_mut(), id).unwrap(), 0);
+
+        // non-existent iterator
+        let key_ptr = write_data(&mut fe_mut, b"b");
+        match do_db_seek(fe_mut.as_mut(), 42, key_ptr).unwrap_err() {
+            VmError::BackendErr {
+                source: BackendError::IteratorDoesNotExist { id, .. },
+                ..
+            } => assert_eq!(id, 42),
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
     #[test]
     #[cfg(feature = "iterator")]
     fn do_db_next_key_works() {
@@ -2267,4 +3562,142 @@ mod tests {
         let kv_region_ptr = do_db_next(fe_mut.as_mut(), id).unwrap();
         assert_eq!(force_read(&mut fe_mut, kv_region_ptr), b"\0\0\0\0\0\0\0\0");
     }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn do_db_scan_seek_and_next_charge_iterator_gas() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        leave_default_data(&mut fe_mut);
+
+        let gas_config = &mut fe_mut.data_mut().gas_config;
+        gas_config.iterator_create_cost = 1000;
+        gas_config.iterator_seek_cost = 500;
+        gas_config.iterator_next_cost = 200;
+        gas_config.iterator_next_cost_per_byte = 3;
+
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        let gas_before = env.get_gas_left(&mut store);
+        let id = do_db_scan(fe_mut.as_mut(), 0, 0, Order::Ascending.into()).unwrap();
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        let gas_after = env.get_gas_left(&mut store);
+        // creation cost plus 11 gas charged by the mock storage for the range
+        assert_eq!(gas_before - gas_after, 1000 + 11);
+
+        // seek to the first key, which keeps the position
+        let key_ptr = write_data(&mut fe_mut, b"a");
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        let gas_before = env.get_gas_left(&mut store);
+        do_db_seek(fe_mut.as_mut(), id, key_ptr).unwrap();
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        let gas_after = env.get_gas_left(&mut store);
+        // seek cost plus 11 gas charged by the mock storage for the seek
+        assert_eq!(gas_before - gas_after, 500 + 11);
+
+        // skip to the end, which does not allocate memory in the contract
+        do_db_next(fe_mut.as_mut(), id).unwrap();
+        do_db_next(fe_mut.as_mut(), id).unwrap();
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        let gas_before = env.get_gas_left(&mut store);
+        assert_eq!(do_db_next_key(fe_mut.as_mut(), id).unwrap(), 0);
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        let gas_after = env.get_gas_left(&mut store);
+        // flat cost plus 37 gas charged by the mock storage for the last iteration
+        assert_eq!(gas_before - gas_after, 200 + 37);
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn iterator_next_gas_info_works() {
+        let mut gas_config = GasConfig {
+            iterator_next_cost: 200,
+            iterator_next_cost_per_byte: 3,
+            ..GasConfig::default()
+        };
+        assert_eq!(
+            iterator_next_gas_info(&gas_config, 0),
+            GasInfo::with_cost(200)
+        );
+        assert_eq!(
+            iterator_next_gas_info(&gas_config, 9),
+            GasInfo::with_cost(200 + 27)
+        );
+
+        // saturates instead of overflowing
+        gas_config.iterator_next_cost_per_byte = u64::MAX;
+        assert_eq!(
+            iterator_next_gas_info(&gas_config, 2),
+            GasInfo::with_cost(u64::MAX)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn do_db_write_invalidates_overlapping_iterators() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        leave_default_data(&mut fe_mut);
+
+        // KEY1 ("ant") is in both ranges, "zebra" only in the unbounded one
+        let start_ptr = write_data(&mut fe_mut, b"a");
+        let end_ptr = write_data(&mut fe_mut, b"b");
+        let bounded =
+            do_db_scan(fe_mut.as_mut(), start_ptr, end_ptr, Order::Ascending.into()).unwrap();
+        let unbounded = do_db_scan(fe_mut.as_mut(), 0, 0, Order::Ascending.into()).unwrap();
+
+        let key_ptr = write_data(&mut fe_mut, b"zebra");
+        let value_ptr = write_data(&mut fe_mut, b"animal");
+        do_db_write(fe_mut.as_mut(), key_ptr, value_ptr).unwrap();
+
+        let key_region_ptr = do_db_next_key(fe_mut.as_mut(), bounded).unwrap();
+        assert_eq!(force_read(&mut fe_mut, key_region_ptr), KEY1);
+        match do_db_next_key(fe_mut.as_mut(), unbounded).unwrap_err() {
+            VmError::IteratorInvalidated { id, .. } => assert_eq!(id, unbounded),
+            e => panic!("Unexpected error: {e:?}"),
+        }
+
+        let key_ptr = write_data(&mut fe_mut, KEY1);
+        let value_ptr = write_data(&mut fe_mut, b"bug");
+        do_db_write(fe_mut.as_mut(), key_ptr, value_ptr).unwrap();
+        match do_db_next(fe_mut.as_mut(), bounded).unwrap_err() {
+            VmError::IteratorInvalidated { id, .. } => assert_eq!(id, bounded),
+            e => panic!("Unexpected error: {e:?}"),
+        }
+
+        // iterators opened after the write are not affected
+        let id = do_db_scan(fe_mut.as_mut(), 0, 0, Order::Ascending.into()).unwrap();
+        let value_region_ptr = do_db_next_value(fe_mut.as_mut(), id).unwrap();
+        assert_eq!(force_read(&mut fe_mut, value_region_ptr), b"bug");
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn do_db_remove_invalidates_overlapping_iterators() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        leave_default_data(&mut fe_mut);
+
+        let prefix_ptr = write_data(&mut fe_mut, b"tr");
+        let id = do_db_scan_prefix(fe_mut.as_mut(), prefix_ptr, Order::Ascending.into()).unwrap();
+
+        // removing a key outside of the prefix keeps the iterator usable
+        let key_ptr = write_data(&mut fe_mut, KEY1);
+        do_db_remove(fe_mut.as_mut(), key_ptr).unwrap();
+        let key_ptr = write_data(&mut fe_mut, b"tr");
+        do_db_seek(fe_mut.as_mut(), id, key_ptr).unwrap();
+
+        let key_ptr = write_data(&mut fe_mut, KEY2);
+        do_db_remove(fe_mut.as_mut(), key_ptr).unwrap();
+        let key_ptr = write_data(&mut fe_mut, b"tr");
+        match do_db_seek(fe_mut.as_mut(), id, key_ptr).unwrap_err() {
+            VmError::IteratorInvalidated { id: invalid, .. } => assert_eq!(invalid, id),
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
 }
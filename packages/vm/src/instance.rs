@@ -1,28 +1,35 @@
-use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::ptr::NonNull;
-use std::rc::Rc;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 use wasmer::{
-    Exports, Function, FunctionEnv, Imports, Instance as WasmerInstance, Module, Store, Value,
+    AsStoreMut, Exports, Function, FunctionEnv, FunctionEnvMut, Imports,
+    Instance as WasmerInstance, Module, Store, Value, WASM_PAGE_SIZE,
 };
 
 use crate::backend::{Backend, BackendApi, Querier, Storage};
+use crate::call_trace::{trace_host_call, CallTrace};
 use crate::capabilities::required_capabilities_from_module;
+use crate::concurrency_limit::InstancePermit;
 use crate::conversion::{ref_to_u32, to_u32};
-use crate::environment::Environment;
+use crate::environment::{Environment, ExecutionMode, GasConfig, GasState};
 use crate::errors::{CommunicationError, VmError, VmResult};
 use crate::imports::{
     do_abort, do_addr_canonicalize, do_addr_humanize, do_addr_validate, do_db_read, do_db_remove,
-    do_db_write, do_debug, do_ed25519_batch_verify, do_ed25519_verify, do_query_chain,
-    do_secp256k1_recover_pubkey, do_secp256k1_verify,
+    do_db_size, do_db_write, do_debug, do_ed25519_batch_verify, do_ed25519_verify,
+    do_ed25519ctx_verify, do_ed25519ph_verify, do_emit_event, do_gas_remaining, do_query_chain,
+    do_query_chain_batch, do_query_chain_batch_with_gas_limit, do_query_chain_binary,
+    do_secp256k1_recover_pubkey, do_secp256k1_verify, do_write_region_chunked,
 };
 #[cfg(feature = "iterator")]
-use crate::imports::{do_db_next, do_db_next_key, do_db_next_value, do_db_scan};
+use crate::imports::{
+    do_db_next, do_db_next_key, do_db_next_value, do_db_scan, do_db_scan_prefix, do_db_seek,
+};
+use crate::instrumentation::InstrumentationHooks;
 use crate::memory::{read_region, write_region};
+use crate::response_limits::ResponseLimits;
 use crate::size::Size;
-use crate::wasm_backend::{compile, make_compiling_engine};
+use crate::wasm_backend::{compile, make_compiling_engine, MEMORY_PAGE_COST};
 
 pub use crate::environment::DebugInfo; // Re-exported as public via to be usable for set_debug_handler
 
@@ -39,10 +46,85 @@ pub struct GasReport {
     pub used_internally: u64,
 }
 
-#[derive(Copy, Clone, Debug)]
+/// Information about the most recent entry point call of an instance,
+/// see [`Instance::last_call_info`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallInfo {
+    /// The name of the called entry point, e.g. `"execute"`
+    pub entry_point: String,
+    /// The gas used by the call, including gas used externally (e.g. by queries)
+    pub gas_used: u64,
+    /// Whether the call succeeded on the VM level.
+    ///
+    /// An error returned by the contract in its result (e.g. `ContractResult::Err`) is still
+    /// a successful call in this sense. It is false for failures like running out of gas,
+    /// aborts or results that cannot be read.
+    pub success: bool,
+}
+
+/// A copy of the linear memory and the globals of an instance right after instantiation.
+/// Used to bring a recycled instance back into its initial state.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) struct InstanceSnapshot {
+    pub(crate) memory: Vec<u8>,
+    /// The raw values of all globals in the instance's store, including the ones
+    /// that are not exported (e.g. the stack pointer)
+    pub(crate) globals: Vec<u128>,
+}
+
+/// Options for creating an [`Instance`].
+///
+/// Use [`InstanceOptions::new`] and the `with_*` methods to create it, such that
+/// adding options is not a breaking change.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
 pub struct InstanceOptions {
     /// Gas limit measured in [CosmWasm gas](https://github.com/CosmWasm/cosmwasm/blob/main/docs/GAS.md).
     pub gas_limit: u64,
+    /// Records all host function calls of the instance, which can then be retrieved
+    /// using [`Instance::take_call_trace`]. This is intended for profiling and debugging
+    /// and should not be enabled in production.
+    pub trace: bool,
+    /// The limits checked for the results of contract calls.
+    /// Chains can configure them, e.g. via governance, without changing the VM.
+    /// Use [`InstanceOptions::with_response_limits`] to set them.
+    pub response_limits: ResponseLimits,
+    /// Hooks called during execution, e.g. to attach APM tooling.
+    /// Use [`InstanceOptions::with_instrumentation`] to set them.
+    pub instrumentation: Option<Arc<dyn InstrumentationHooks>>,
+}
+
+impl InstanceOptions {
+    /// Creates options with the given gas limit. All other options use their defaults:
+    /// tracing is disabled, [`ResponseLimits::DEFAULT`] apply and no instrumentation is used.
+    pub const fn new(gas_limit: u64) -> Self {
+        Self {
+            gas_limit,
+            trace: false,
+            response_limits: ResponseLimits::DEFAULT,
+            instrumentation: None,
+        }
+    }
+
+    /// Enables or disables recording of host function calls, see [`InstanceOptions::trace`].
+    pub const fn with_trace(mut self, trace: bool) -> Self {
+        self.trace = trace;
+        self
+    }
+
+    /// Sets the limits checked for the results of contract calls.
+    /// Fails if the limits are invalid (see [`ResponseLimits::validate`]).
+    pub fn with_response_limits(mut self, response_limits: ResponseLimits) -> VmResult<Self> {
+        response_limits.validate()?;
+        self.response_limits = response_limits;
+        Ok(self)
+    }
+
+    /// Sets the hooks called during execution, see [`InstanceOptions::instrumentation`].
+    pub fn with_instrumentation(mut self, hooks: Arc<dyn InstrumentationHooks>) -> Self {
+        self.instrumentation = Some(hooks);
+        self
+    }
 }
 
 pub struct Instance<A: BackendApi, S: Storage, Q: Querier> {
@@ -54,6 +136,10 @@ pub struct Instance<A: BackendApi, S: Storage, Q: Querier> {
     _inner: Box<WasmerInstance>,
     fe: FunctionEnv<Environment<A, S, Q>>,
     store: Store,
+    /// Counts this instance towards the concurrency limit of the cache it was created by
+    permit: Option<InstancePermit>,
+    last_call_info: Option<CallInfo>,
+    response_limits: ResponseLimits,
 }
 
 impl<A, S, Q> Instance<A, S, Q>
@@ -73,7 +159,12 @@ where
         let engine = make_compiling_engine(memory_limit);
         let module = compile(&engine, code)?;
         let store = Store::new(engine);
-        Instance::from_module(store, &module, backend, options.gas_limit, None, None)
+        let mut instance =
+            Instance::from_module(store, &module, backend, options.gas_limit, None, None)?;
+        instance.set_tracing(options.trace);
+        instance.set_instrumentation(options.instrumentation);
+        instance.set_response_limits(options.response_limits)?;
+        Ok(instance)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -96,14 +187,28 @@ where
         // Ownership of the value pointer is transferred to the contract.
         env_imports.insert(
             "db_read",
-            Function::new_typed_with_env(&mut store, &fe, do_db_read),
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>, key_ptr: u32| {
+                    trace_host_call(env, "db_read", &[key_ptr], |env| do_db_read(env, key_ptr))
+                },
+            ),
         );
 
         // Writes the given value into the database entry at the given key.
         // Ownership of both input and output pointer is not transferred to the host.
         env_imports.insert(
             "db_write",
-            Function::new_typed_with_env(&mut store, &fe, do_db_write),
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>, key_ptr: u32, value_ptr: u32| {
+                    trace_host_call(env, "db_write", &[key_ptr, value_ptr], |env| {
+                        do_db_write(env, key_ptr, value_ptr)
+                    })
+                },
+            ),
         );
 
         // Removes the value at the given key. Different than writing &[] as future
@@ -112,7 +217,29 @@ where
         // Ownership of both key pointer is not transferred to the host.
         env_imports.insert(
             "db_remove",
-            Function::new_typed_with_env(&mut store, &fe, do_db_remove),
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>, key_ptr: u32| {
+                    trace_host_call(env, "db_remove", &[key_ptr], |env| {
+                        do_db_remove(env, key_ptr)
+                    })
+                },
+            ),
+        );
+
+        // Returns a region with the approximate storage usage of the contract,
+        // encoded as `keys || bytes` (both u64 big endian).
+        // Ownership of the result region is transferred to the contract.
+        env_imports.insert(
+            "db_size",
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>| {
+                    trace_host_call(env, "db_size", &[], do_db_size)
+                },
+            ),
         );
 
         // Reads human address from source_ptr and checks if it is valid.
@@ -120,7 +247,15 @@ where
         // Ownership of the input pointer is not transferred to the host.
         env_imports.insert(
             "addr_validate",
-            Function::new_typed_with_env(&mut store, &fe, do_addr_validate),
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>, source_ptr: u32| {
+                    trace_host_call(env, "addr_validate", &[source_ptr], |env| {
+                        do_addr_validate(env, source_ptr)
+                    })
+                },
+            ),
         );
 
         // Reads human address from source_ptr and writes canonicalized representation to destination_ptr.
@@ -129,7 +264,17 @@ where
         // Ownership of both input and output pointer is not transferred to the host.
         env_imports.insert(
             "addr_canonicalize",
-            Function::new_typed_with_env(&mut store, &fe, do_addr_canonicalize),
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>,
+                 source_ptr: u32,
+                 destination_ptr: u32| {
+                    trace_host_call(env, "addr_canonicalize", &[source_ptr], |env| {
+                        do_addr_canonicalize(env, source_ptr, destination_ptr)
+                    })
+                },
+            ),
         );
 
         // Reads canonical address from source_ptr and writes humanized representation to destination_ptr.
@@ -138,7 +283,17 @@ where
         // Ownership of both input and output pointer is not transferred to the host.
         env_imports.insert(
             "addr_humanize",
-            Function::new_typed_with_env(&mut store, &fe, do_addr_humanize),
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>,
+                 source_ptr: u32,
+                 destination_ptr: u32| {
+                    trace_host_call(env, "addr_humanize", &[source_ptr], |env| {
+                        do_addr_humanize(env, source_ptr, destination_ptr)
+                    })
+                },
+            ),
         );
 
         // Verifies message hashes against a signature with a public key, using the secp256k1 ECDSA parametrization.
@@ -146,12 +301,47 @@ where
         // Ownership of input pointers is not transferred to the host.
         env_imports.insert(
             "secp256k1_verify",
-            Function::new_typed_with_env(&mut store, &fe, do_secp256k1_verify),
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>,
+                 hash_ptr: u32,
+                 signature_ptr: u32,
+                 pubkey_ptr: u32| {
+                    trace_host_call(
+                        env,
+                        "secp256k1_verify",
+                        &[hash_ptr, signature_ptr, pubkey_ptr],
+                        |env| do_secp256k1_verify(env, hash_ptr, signature_ptr, pubkey_ptr),
+                    )
+                },
+            ),
         );
 
         env_imports.insert(
             "secp256k1_recover_pubkey",
-            Function::new_typed_with_env(&mut store, &fe, do_secp256k1_recover_pubkey),
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>,
+                 hash_ptr: u32,
+                 signature_ptr: u32,
+                 recovery_param: u32| {
+                    trace_host_call(
+                        env,
+                        "secp256k1_recover_pubkey",
+                        &[hash_ptr, signature_ptr],
+                        |env| {
+                            do_secp256k1_recover_pubkey(
+                                env,
+                                hash_ptr,
+                                signature_ptr,
+                                recovery_param,
+                            )
+                        },
+                    )
+                },
+            ),
         );
 
         // Verifies a message against a signature with a public key, using the ed25519 EdDSA scheme.
@@ -159,7 +349,85 @@ where
         // Ownership of input pointers is not transferred to the host.
         env_imports.insert(
             "ed25519_verify",
-            Function::new_typed_with_env(&mut store, &fe, do_ed25519_verify),
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>,
+                 message_ptr: u32,
+                 signature_ptr: u32,
+                 pubkey_ptr: u32| {
+                    trace_host_call(
+                        env,
+                        "ed25519_verify",
+                        &[message_ptr, signature_ptr, pubkey_ptr],
+                        |env| do_ed25519_verify(env, message_ptr, signature_ptr, pubkey_ptr),
+                    )
+                },
+            ),
+        );
+
+        // Verifies a message against a signature with a public key, using the Ed25519ctx variant
+        // of the ed25519 EdDSA scheme which binds the signature to a non-empty context.
+        // Returns 0 on verification success, 1 on verification failure, and values greater than 1 in case of error.
+        // Ownership of input pointers is not transferred to the host.
+        env_imports.insert(
+            "ed25519ctx_verify",
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>,
+                 message_ptr: u32,
+                 context_ptr: u32,
+                 signature_ptr: u32,
+                 pubkey_ptr: u32| {
+                    trace_host_call(
+                        env,
+                        "ed25519ctx_verify",
+                        &[message_ptr, context_ptr, signature_ptr, pubkey_ptr],
+                        |env| {
+                            do_ed25519ctx_verify(
+                                env,
+                                message_ptr,
+                                context_ptr,
+                                signature_ptr,
+                                pubkey_ptr,
+                            )
+                        },
+                    )
+                },
+            ),
+        );
+
+        // Verifies a SHA-512 message hash against a signature with a public key, using the Ed25519ph
+        // variant of the ed25519 EdDSA scheme with an optional context.
+        // Returns 0 on verification success, 1 on verification failure, and values greater than 1 in case of error.
+        // Ownership of input pointers is not transferred to the host.
+        env_imports.insert(
+            "ed25519ph_verify",
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>,
+                 message_hash_ptr: u32,
+                 context_ptr: u32,
+                 signature_ptr: u32,
+                 pubkey_ptr: u32| {
+                    trace_host_call(
+                        env,
+                        "ed25519ph_verify",
+                        &[message_hash_ptr, context_ptr, signature_ptr, pubkey_ptr],
+                        |env| {
+                            do_ed25519ph_verify(
+                                env,
+                                message_hash_ptr,
+                                context_ptr,
+                                signature_ptr,
+                                pubkey_ptr,
+                            )
+                        },
+                    )
+                },
+            ),
         );
 
         // Verifies a batch of messages against a batch of signatures with a batch of public keys,
@@ -169,7 +437,23 @@ where
         // Ownership of input pointers is not transferred to the host.
         env_imports.insert(
             "ed25519_batch_verify",
-            Function::new_typed_with_env(&mut store, &fe, do_ed25519_batch_verify),
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>,
+                 messages_ptr: u32,
+                 signatures_ptr: u32,
+                 pubkeys_ptr: u32| {
+                    trace_host_call(
+                        env,
+                        "ed25519_batch_verify",
+                        &[messages_ptr, signatures_ptr, pubkeys_ptr],
+                        |env| {
+                            do_ed25519_batch_verify(env, messages_ptr, signatures_ptr, pubkeys_ptr)
+                        },
+                    )
+                },
+            ),
         );
 
         // Allows the contract to emit debug logs that the host can either process or ignore.
@@ -178,7 +462,43 @@ where
         // Ownership of both input and output pointer is not transferred to the host.
         env_imports.insert(
             "debug",
-            Function::new_typed_with_env(&mut store, &fe, do_debug),
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>, message_ptr: u32| {
+                    trace_host_call(env, "debug", &[message_ptr], |env| {
+                        do_debug(env, message_ptr)
+                    })
+                },
+            ),
+        );
+
+        // Passes an event to the host while the contract is executing.
+        // Takes a pointer argument of a memory region that must contain a JSON encoded event.
+        // Ownership of the input pointer is not transferred to the host.
+        env_imports.insert(
+            "emit_event",
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>, event_ptr: u32| {
+                    trace_host_call(env, "emit_event", &[event_ptr], |env| {
+                        do_emit_event(env, event_ptr)
+                    })
+                },
+            ),
+        );
+
+        // Returns the gas that is left for the current call.
+        env_imports.insert(
+            "gas_remaining",
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>| {
+                    trace_host_call(env, "gas_remaining", &[], do_gas_remaining)
+                },
+            ),
         );
 
         // Aborts the contract execution with an error message provided by the contract.
@@ -186,12 +506,99 @@ where
         // Ownership of both input and output pointer is not transferred to the host.
         env_imports.insert(
             "abort",
-            Function::new_typed_with_env(&mut store, &fe, do_abort),
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>, message_ptr: u32| {
+                    trace_host_call(env, "abort", &[message_ptr], |env| {
+                        do_abort(env, message_ptr)
+                    })
+                },
+            ),
         );
 
         env_imports.insert(
             "query_chain",
-            Function::new_typed_with_env(&mut store, &fe, do_query_chain),
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>, request_ptr: u32| {
+                    trace_host_call(env, "query_chain", &[request_ptr], |env| {
+                        do_query_chain(env, request_ptr)
+                    })
+                },
+            ),
+        );
+
+        // Executes a query like query_chain but returns the result in a compact binary
+        // encoding instead of JSON. See do_query_chain_binary for the format.
+        // Ownership of the request pointer is not transferred to the host.
+        // Ownership of the result region is transferred to the contract.
+        env_imports.insert(
+            "query_chain_binary",
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>, request_ptr: u32| {
+                    trace_host_call(env, "query_chain_binary", &[request_ptr], |env| {
+                        do_query_chain_binary(env, request_ptr)
+                    })
+                },
+            ),
+        );
+
+        // Executes multiple queries like query_chain_binary in one call. The requests and the
+        // results are encoded as sections. See do_query_chain_batch for details.
+        // Ownership of the requests pointer is not transferred to the host.
+        // Ownership of the result region is transferred to the contract.
+        env_imports.insert(
+            "query_chain_batch",
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>, requests_ptr: u32| {
+                    trace_host_call(env, "query_chain_batch", &[requests_ptr], |env| {
+                        do_query_chain_batch(env, requests_ptr)
+                    })
+                },
+            ),
+        );
+
+        // Executes multiple queries like query_chain_batch, but each query can use at most
+        // gas_limit gas. A query exceeding its limit returns an OutOfGas system error.
+        // Ownership of the requests pointer is not transferred to the host.
+        // Ownership of the result region is transferred to the contract.
+        env_imports.insert(
+            "query_chain_batch_with_gas_limit",
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>, requests_ptr: u32, gas_limit: u64| {
+                    trace_host_call(
+                        env,
+                        "query_chain_batch_with_gas_limit",
+                        &[requests_ptr],
+                        |env| do_query_chain_batch_with_gas_limit(env, requests_ptr, gas_limit),
+                    )
+                },
+            ),
+        );
+
+        // Appends a chunk to the response of the current call. Used by contracts that return
+        // the null pointer from an export to return responses larger than a single region.
+        // Returns the remaining capacity. A null pointer only queries the capacity.
+        // Ownership of the chunk pointer is not transferred to the host.
+        env_imports.insert(
+            "write_region_chunked",
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>, chunk_ptr: u32| {
+                    trace_host_call(env, "write_region_chunked", &[chunk_ptr], |env| {
+                        do_write_region_chunked(env, chunk_ptr)
+                    })
+                },
+            ),
         );
 
         // Creates an iterator that will go from start to end.
@@ -203,7 +610,18 @@ where
         #[cfg(feature = "iterator")]
         env_imports.insert(
             "db_scan",
-            Function::new_typed_with_env(&mut store, &fe, do_db_scan),
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>,
+                 start_ptr: u32,
+                 end_ptr: u32,
+                 order: i32| {
+                    trace_host_call(env, "db_scan", &[start_ptr, end_ptr], |env| {
+                        do_db_scan(env, start_ptr, end_ptr, order)
+                    })
+                },
+            ),
         );
 
         // Get next element of iterator with ID `iterator_id`.
@@ -214,7 +632,13 @@ where
         #[cfg(feature = "iterator")]
         env_imports.insert(
             "db_next",
-            Function::new_typed_with_env(&mut store, &fe, do_db_next),
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>, iterator_id: u32| {
+                    trace_host_call(env, "db_next", &[], |env| do_db_next(env, iterator_id))
+                },
+            ),
         );
 
         // Get next key of iterator with ID `iterator_id`.
@@ -223,7 +647,15 @@ where
         #[cfg(feature = "iterator")]
         env_imports.insert(
             "db_next_key",
-            Function::new_typed_with_env(&mut store, &fe, do_db_next_key),
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>, iterator_id: u32| {
+                    trace_host_call(env, "db_next_key", &[], |env| {
+                        do_db_next_key(env, iterator_id)
+                    })
+                },
+            ),
         );
 
         // Get next value of iterator with ID `iterator_id`.
@@ -232,7 +664,50 @@ where
         #[cfg(feature = "iterator")]
         env_imports.insert(
             "db_next_value",
-            Function::new_typed_with_env(&mut store, &fe, do_db_next_value),
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>, iterator_id: u32| {
+                    trace_host_call(env, "db_next_value", &[], |env| {
+                        do_db_next_value(env, iterator_id)
+                    })
+                },
+            ),
+        );
+
+        // Creates an iterator over all keys starting with the prefix.
+        // Order is defined in cosmwasm_std::Order and may be 1 (ascending) or 2 (descending). All other values result in an error.
+        // Ownership of the prefix pointer is not transferred to the host.
+        // Returns an iterator ID.
+        #[cfg(feature = "iterator")]
+        env_imports.insert(
+            "db_scan_prefix",
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>, prefix_ptr: u32, order: i32| {
+                    trace_host_call(env, "db_scan_prefix", &[prefix_ptr], |env| {
+                        do_db_scan_prefix(env, prefix_ptr, order)
+                    })
+                },
+            ),
+        );
+
+        // Moves the iterator with ID `iterator_id` such that the next entry is the first one
+        // at or after the key in iteration order.
+        // Ownership of the key pointer is not transferred to the host.
+        #[cfg(feature = "iterator")]
+        env_imports.insert(
+            "db_seek",
+            Function::new_typed_with_env(
+                &mut store,
+                &fe,
+                |env: FunctionEnvMut<Environment<A, S, Q>>, iterator_id: u32, key_ptr: u32| {
+                    trace_host_call(env, "db_seek", &[key_ptr], |env| {
+                        do_db_seek(env, iterator_id, key_ptr)
+                    })
+                },
+            ),
         );
 
         import_obj.register_namespace("env", env_imports);
@@ -269,7 +744,11 @@ where
 
             env.memory = Some(memory);
             env.set_wasmer_instance(Some(instance_ptr));
-            env.set_gas_left(&mut store, gas_limit);
+            let initial_pages = env.memory(&store).size().0;
+            env.set_gas_left(
+                &mut store,
+                gas_limit.saturating_sub(memory_initialization_cost(initial_pages)),
+            );
             env.move_in(backend.storage, backend.querier);
         }
 
@@ -277,6 +756,9 @@ where
             _inner: wasmer_instance,
             fe,
             store,
+            permit: None,
+            last_call_info: None,
+            response_limits: ResponseLimits::DEFAULT,
         })
     }
 
@@ -305,19 +787,143 @@ where
         }
     }
 
+    /// Attaches a permit which is released when this instance is dropped
+    pub(crate) fn set_permit(&mut self, permit: InstancePermit) {
+        self.permit = Some(permit);
+    }
+
+    /// Creates a copy of the current memory and globals. To be useful for resetting an instance,
+    /// this must be called before the first call into the instance.
+    ///
+    /// Every instance has its own store, so the globals of the store are exactly the globals
+    /// of this instance.
+    pub(crate) fn snapshot(&mut self) -> VmResult<InstanceSnapshot> {
+        let mut fe_mut = self.fe.clone().into_mut(&mut self.store);
+        let (env, mut store) = fe_mut.data_and_store_mut();
+
+        let memory = env.memory(&store).copy_to_vec().map_err(|original| {
+            VmError::generic_err(format!("Could not copy instance memory: {original}"))
+        })?;
+        let globals = store.objects_mut().as_u128_globals();
+        Ok(InstanceSnapshot { memory, globals })
+    }
+
+    /// Moves the backend out of this instance and resets the instance to the state
+    /// described by the snapshot, such that it can be used for another call.
+    ///
+    /// The memory and all globals are restored. Wasm memory cannot be shrunk, so instances
+    /// whose memory grew after the snapshot was taken are not reset. Neither are instances
+    /// whose memory exceeds `max_memory`.
+    ///
+    /// Returns the backend as well as a flag indicating whether or not the reset succeeded.
+    /// Instances that were not reset successfully must not be used anymore.
+    /// This is the case if a call failed, leaving the Wasm state undefined.
+    pub(crate) fn reset(
+        &mut self,
+        snapshot: &InstanceSnapshot,
+        max_memory: Size,
+    ) -> (Option<Backend<A, S, Q>>, bool) {
+        self.last_call_info = None;
+        let mut fe_mut = self.fe.clone().into_mut(&mut self.store);
+        let (env, mut store) = fe_mut.data_and_store_mut();
+
+        let backend = match env.move_out() {
+            (Some(storage), Some(querier)) => Some(Backend {
+                api: env.api,
+                storage,
+                querier,
+            }),
+            _ => None,
+        };
+        env.set_debug_handler(None);
+        env.set_call_env(None);
+        env.set_memory_usage_limit(&mut store, None);
+        env.set_execution_mode(ExecutionMode::Execute);
+        env.set_tracing(false);
+        env.set_instrumentation(None);
+
+        if env.is_tainted() {
+            return (backend, false);
+        }
+        let memory = env.memory(&store);
+        let memory_size = memory.data_size() as usize;
+        if memory_size > max_memory.0 || memory_size != snapshot.memory.len() {
+            return (backend, false);
+        }
+        if memory.write(0, &snapshot.memory).is_err() {
+            return (backend, false);
+        }
+
+        let objects = store.objects_mut();
+        if objects.iter_globals().len() != snapshot.globals.len() {
+            return (backend, false);
+        }
+        for (index, value) in snapshot.globals.iter().enumerate() {
+            // The values were read from the same globals, so they have the correct types
+            objects.set_global_unchecked(index, *value);
+        }
+        (backend, true)
+    }
+
+    /// Moves a new backend into a previously reset instance and applies the options
+    /// for the next call.
+    ///
+    /// The initialization of the memory is charged like for a new instance, such that
+    /// reusing an instance does not change the gas usage.
+    pub(crate) fn reuse(
+        &mut self,
+        backend: Backend<A, S, Q>,
+        options: InstanceOptions,
+        snapshot: &InstanceSnapshot,
+    ) {
+        self.response_limits = options.response_limits;
+        let gas_limit = options.gas_limit;
+        let initial_pages = (snapshot.memory.len() / WASM_PAGE_SIZE) as u32;
+        let mut fe_mut = self.fe.clone().into_mut(&mut self.store);
+        let (env, mut store) = fe_mut.data_and_store_mut();
+
+        env.set_tracing(options.trace);
+        env.set_instrumentation(options.instrumentation);
+        env.api = backend.api;
+        env.with_gas_state_mut(|gas_state| *gas_state = GasState::with_limit(gas_limit));
+        env.set_gas_left(
+            &mut store,
+            gas_limit.saturating_sub(memory_initialization_cost(initial_pages)),
+        );
+        env.set_storage_readonly(true);
+        env.move_in(backend.storage, backend.querier);
+    }
+
     pub fn set_debug_handler<H>(&mut self, debug_handler: H)
     where
-        H: for<'a, 'b> FnMut(/* msg */ &'a str, DebugInfo<'b>) + 'static,
+        H: for<'a, 'b> FnMut(/* msg */ &'a str, DebugInfo<'b>) + Send + 'static,
     {
         self.fe
             .as_ref(&self.store)
-            .set_debug_handler(Some(Rc::new(RefCell::new(debug_handler))));
+            .set_debug_handler(Some(Arc::new(Mutex::new(debug_handler))));
     }
 
     pub fn unset_debug_handler(&mut self) {
         self.fe.as_ref(&self.store).set_debug_handler(None);
     }
 
+    /// Sets the JSON serialized env of the following calls, from which the contract address
+    /// passed to the [`BackendApi::debug`] and [`BackendApi::abort`] hooks is parsed.
+    pub(crate) fn set_call_env(&mut self, call_env: Option<Vec<u8>>) {
+        self.fe.as_ref(&self.store).set_call_env(call_env);
+    }
+
+    /// Starts accepting response chunks written by the contract via `write_region_chunked`,
+    /// with a total length of up to `limit` bytes.
+    pub(crate) fn start_chunked_response(&mut self, limit: usize) {
+        self.fe.as_ref(&self.store).start_chunked_response(limit);
+    }
+
+    /// Stops accepting response chunks and returns the ones written so far.
+    pub(crate) fn finish_chunked_response(&mut self) -> Option<Vec<u8>> {
+        self.fe.as_ref(&self.store).finish_chunked_response()
+    }
+
     /// Returns the features required by this contract.
     ///
     /// This is not needed for production because we can do static analysis
@@ -338,6 +944,90 @@ where
         env.memory(&store).size().0 as _
     }
 
+    /// Returns the size of the default memory in bytes.
+    /// This is always a multiple of the page size (64 KiB).
+    pub fn memory_bytes(&mut self) -> usize {
+        let mut fe_mut = self.fe.clone().into_mut(&mut self.store);
+        let (env, store) = fe_mut.data_and_store_mut();
+
+        env.memory(&store).data_size() as _
+    }
+
+    /// Enables or disables tracing of host function calls.
+    /// This discards all previously recorded calls.
+    pub(crate) fn set_tracing(&mut self, enabled: bool) {
+        self.fe.as_ref(&self.store).set_tracing(enabled);
+    }
+
+    /// Sets the hooks called during execution.
+    /// This overrides the hooks of the [`InstanceOptions`] the instance was created with.
+    pub fn set_instrumentation(&mut self, hooks: Option<Arc<dyn InstrumentationHooks>>) {
+        self.fe.as_ref(&self.store).set_instrumentation(hooks);
+    }
+
+    /// Returns the host function calls recorded since the instance was created or since
+    /// the last call of this function. Returns `None` if tracing is not enabled
+    /// (see [`InstanceOptions::trace`]).
+    pub fn take_call_trace(&mut self) -> Option<CallTrace> {
+        self.fe.as_ref(&self.store).take_call_trace()
+    }
+
+    /// Returns information about the most recent entry point call of this instance,
+    /// like the entry point name, the gas used and whether the call succeeded.
+    /// Returns `None` if no entry point was called yet.
+    ///
+    /// This allows embedders to collect consistent telemetry for all calls without
+    /// wrapping every call site.
+    pub fn last_call_info(&self) -> Option<&CallInfo> {
+        self.last_call_info.as_ref()
+    }
+
+    pub(crate) fn set_last_call_info(&mut self, info: CallInfo) {
+        self.last_call_info = Some(info);
+    }
+
+    /// Returns the limits checked for the results of contract calls.
+    pub fn response_limits(&self) -> ResponseLimits {
+        self.response_limits
+    }
+
+    /// Sets the limits checked for the results of contract calls.
+    /// This overrides the limits of the [`InstanceOptions`] the instance was created with.
+    ///
+    /// Fails if the limits are invalid (see [`ResponseLimits::validate`]).
+    pub fn set_response_limits(&mut self, limits: ResponseLimits) -> VmResult<()> {
+        limits.validate()?;
+        self.response_limits = limits;
+        Ok(())
+    }
+
+    /// Returns the gas costs of host functions provided by the VM.
+    pub fn gas_config(&self) -> GasConfig {
+        self.fe.as_ref(&self.store).gas_config.clone()
+    }
+
+    /// Sets the gas costs of host functions provided by the VM, e.g. to adjust the
+    /// storage iterator costs to benchmark results of the chain's storage backend.
+    pub fn set_gas_config(&mut self, gas_config: GasConfig) {
+        self.fe.as_mut(&mut self.store).gas_config = gas_config;
+    }
+
+    /// Sets a limit for the memory usage of this instance in bytes, in addition to the
+    /// memory limit that the instance was created with.
+    ///
+    /// The limit is enforced when the contract grows its memory. A `memory.grow` beyond the
+    /// limit aborts the call with [`VmError::MemoryLimitExceeded`]. The memory size is
+    /// checked again after every call into the contract, which also covers an initial memory
+    /// that is already larger than the limit. After this error, the instance should not be
+    /// used anymore. This allows embedders to apply a stricter policy for some calls,
+    /// e.g. for simulations, than the hard limit enforced by Wasm.
+    pub fn set_memory_usage_limit(&mut self, limit: Option<Size>) {
+        let mut fe_mut = self.fe.clone().into_mut(&mut self.store);
+        let (env, mut store) = fe_mut.data_and_store_mut();
+
+        env.set_memory_usage_limit(&mut store, limit);
+    }
+
     /// Returns the currently remaining gas.
     pub fn get_gas_left(&mut self) -> u64 {
         let mut fe_mut = self.fe.clone().into_mut(&mut self.store);
@@ -346,6 +1036,35 @@ where
         env.get_gas_left(&mut store)
     }
 
+    /// Runs `func`, usually one of the `call_*` functions, with at most `gas_limit` gas.
+    ///
+    /// This allows enforcing a lower limit for some calls (e.g. queries) than for others
+    /// without creating a new instance. The limit of the instance still applies, i.e. the
+    /// gas available to the call is the minimum of `gas_limit` and the remaining gas.
+    /// All gas used by the call is deducted from the remaining gas of the instance.
+    pub fn with_gas_limit<F: FnOnce(&mut Self) -> VmResult<T>, T>(
+        &mut self,
+        gas_limit: u64,
+        func: F,
+    ) -> VmResult<T> {
+        let remaining = self.get_gas_left();
+        let available = remaining.min(gas_limit);
+        self.set_gas_left(available);
+
+        let result = func(self);
+
+        let used = available.saturating_sub(self.get_gas_left());
+        self.set_gas_left(remaining - used);
+        result
+    }
+
+    fn set_gas_left(&mut self, new_value: u64) {
+        let mut fe_mut = self.fe.clone().into_mut(&mut self.store);
+        let (env, mut store) = fe_mut.data_and_store_mut();
+
+        env.set_gas_left(&mut store, new_value);
+    }
+
     /// Creates and returns a gas report.
     /// This is a snapshot and multiple reports can be created during the lifetime of
     /// an instance.
@@ -376,6 +1095,17 @@ where
         env.is_storage_readonly()
     }
 
+    pub fn execution_mode(&self) -> ExecutionMode {
+        self.fe.as_ref(&self.store).execution_mode()
+    }
+
+    /// Sets the execution mode of this instance. In contrast to the readonly storage flag,
+    /// the mode is not changed by the `call_*` functions. Use [`ExecutionMode::Query`] to
+    /// reject all state changes when calling any entry point through a query path.
+    pub fn set_execution_mode(&mut self, mode: ExecutionMode) {
+        self.fe.as_ref(&self.store).set_execution_mode(mode);
+    }
+
     /// Sets the readonly storage flag on this instance. Since one instance can be used
     /// for multiple calls in integration tests, this should be set to the desired value
     /// right before every call.
@@ -453,6 +1183,12 @@ where
     }
 }
 
+/// The gas charged for initializing the linear memory of a new instance, including copying
+/// the data segments. This is the same per page as for growing the memory.
+pub(crate) fn memory_initialization_cost(initial_pages: u32) -> u64 {
+    initial_pages as u64 * MEMORY_PAGE_COST
+}
+
 /// This exists only to be exported through `internals` for use by crates that are
 /// part of Cosmwasm.
 pub fn instance_from_module<A, S, Q>(
@@ -473,11 +1209,12 @@ where
 #[cfg(test)]
 mod tests {
     use std::sync::atomic::{AtomicBool, Ordering};
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
     use std::time::SystemTime;
 
     use super::*;
-    use crate::backend::Storage;
+    use crate::backend::{GasInfo, Storage};
     use crate::calls::{call_execute, call_instantiate, call_query};
     use crate::errors::VmError;
     use crate::testing::{
@@ -672,6 +1409,8 @@ mod tests {
             CONTRACT,
             MockInstanceOptions {
                 memory_limit: Some(Size::mebi(500)),
+                // enough gas to grow the memory up to the limit of 8000 pages
+                gas_limit: 8000 * MEMORY_PAGE_COST + 500_000_000,
                 ..Default::default()
             },
         );
@@ -829,11 +1568,224 @@ mod tests {
         assert_eq!(instance.memory_pages(), 19);
     }
 
+    #[test]
+    fn call_function_fails_for_deep_recursion() {
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory 0)
+                (export "memory" (memory 0))
+
+                (type (func))
+                (func (type 0) nop)
+                (export "interface_version_8" (func 0))
+                (export "instantiate" (func 0))
+                (export "allocate" (func 0))
+                (export "deallocate" (func 0))
+
+                (func $recurse (type 0) call $recurse)
+                (export "recurse" (func $recurse))
+            )"#,
+        )
+        .unwrap();
+        let mut instance = mock_instance(&wasm, &[]);
+
+        let err = instance.call_function0("recurse", &[]).unwrap_err();
+        assert!(matches!(err, VmError::StackDepthExceeded { .. }));
+
+        // The instance can still be called and fails the same way
+        let err = instance.call_function0("recurse", &[]).unwrap_err();
+        assert!(matches!(err, VmError::StackDepthExceeded { .. }));
+    }
+
+    #[test]
+    fn call_function_fails_for_native_stack_overflow() {
+        // Frames of 4000 locals overflow the native stack long before the depth limit
+        let wasm = wat::parse_str(format!(
+            r#"(module
+                (memory 0)
+                (export "memory" (memory 0))
+
+                (type (func))
+                (func (type 0) nop)
+                (export "interface_version_8" (func 0))
+                (export "instantiate" (func 0))
+                (export "allocate" (func 0))
+                (export "deallocate" (func 0))
+
+                (func $recurse (type 0) (local {locals}) call $recurse)
+                (export "recurse" (func $recurse))
+            )"#,
+            locals = ["i64"; 4000].join(" ")
+        ))
+        .unwrap();
+        let mut instance = mock_instance(&wasm, &[]);
+
+        let err = instance.call_function0("recurse", &[]).unwrap_err();
+        assert!(matches!(err, VmError::StackDepthExceeded { .. }));
+    }
+
+    #[test]
+    fn memory_bytes_works() {
+        let mut instance = mock_instance(CONTRACT, &[]);
+        assert_eq!(instance.memory_bytes(), 17 * 65536);
+
+        let _region_ptr = instance.allocate(100 * 1024).expect("error allocating");
+        assert_eq!(instance.memory_bytes(), 19 * 65536);
+    }
+
+    #[test]
+    fn set_memory_usage_limit_works() {
+        let mut instance = mock_instance(CONTRACT, &[]);
+        instance.set_memory_usage_limit(Some(Size::kibi(18 * 64)));
+
+        // 100 KiB require two more pages
+        let err = instance.allocate(100 * 1024).unwrap_err();
+        match err {
+            VmError::MemoryLimitExceeded { used, limit, .. } => {
+                assert_eq!(used, 19 * 65536);
+                assert_eq!(limit, 18 * 65536);
+            }
+            err => panic!("unexpected error: {err:?}"),
+        }
+
+        // Lifting the limit
+        let mut instance = mock_instance(CONTRACT, &[]);
+        instance.set_memory_usage_limit(Some(Size::kibi(18 * 64)));
+        instance.set_memory_usage_limit(None);
+        instance.allocate(100 * 1024).expect("error allocating");
+    }
+
+    #[test]
+    fn take_call_trace_works() {
+        let backend = mock_backend(&[]);
+        let (mut instance_options, memory_limit) = mock_instance_options();
+        instance_options.trace = true;
+        let mut instance =
+            Instance::from_code(CONTRACT, backend, instance_options, memory_limit).unwrap();
+
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+
+        let trace = instance.take_call_trace().unwrap();
+        let names: Vec<_> = trace.calls.iter().map(|call| call.name).collect();
+        assert_eq!(
+            names,
+            ["debug", "addr_validate", "addr_validate", "db_write"]
+        );
+        assert_eq!(trace.calls[0].arg_sizes, ["here we go 🚀".len()]);
+        assert_eq!(trace.calls[1].arg_sizes, ["verifies".len()]);
+        let db_write = &trace.calls[3];
+        assert_eq!(db_write.arg_sizes.len(), 2);
+        assert!(db_write.gas_used() > 0);
+        assert!(trace.total_gas_used() >= db_write.gas_used());
+
+        // the trace starts over
+        assert_eq!(instance.take_call_trace(), Some(CallTrace::default()));
+    }
+
+    #[test]
+    fn instrumentation_hooks_work() {
+        #[derive(Default)]
+        struct RecordingHooks {
+            events: Mutex<Vec<String>>,
+        }
+
+        impl InstrumentationHooks for RecordingHooks {
+            fn on_host_call_start(&self, name: &'static str, _gas_left: u64) {
+                self.events.lock().unwrap().push(format!("start {name}"));
+            }
+
+            fn on_host_call_end(
+                &self,
+                name: &'static str,
+                _gas_left: u64,
+                _duration: Duration,
+                success: bool,
+            ) {
+                self.events
+                    .lock()
+                    .unwrap()
+                    .push(format!("end {name} {success}"));
+            }
+
+            fn on_gas_checkpoint(&self, gas_info: GasInfo, _gas_left: u64) {
+                assert!(gas_info.cost > 0 || gas_info.externally_used > 0);
+                self.events.lock().unwrap().push("gas".to_string());
+            }
+        }
+
+        let hooks = Arc::new(RecordingHooks::default());
+        let backend = mock_backend(&[]);
+        let (instance_options, memory_limit) = mock_instance_options();
+        let instance_options = instance_options.with_instrumentation(hooks.clone());
+        let mut instance =
+            Instance::from_code(CONTRACT, backend, instance_options, memory_limit).unwrap();
+
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+
+        let events = hooks.events.lock().unwrap().clone();
+        assert_eq!(events.first().unwrap(), "start debug");
+        assert_eq!(events.last().unwrap(), "end db_write true");
+        let host_calls: Vec<_> = events
+            .iter()
+            .filter(|event| event.starts_with("start "))
+            .collect();
+        assert_eq!(
+            host_calls,
+            [
+                "start debug",
+                "start addr_validate",
+                "start addr_validate",
+                "start db_write"
+            ]
+        );
+        assert!(events.iter().any(|event| event == "gas"));
+        // tracing is independent of the hooks
+        assert_eq!(instance.take_call_trace(), None);
+    }
+
+    #[test]
+    fn take_call_trace_returns_none_when_disabled() {
+        let mut instance = mock_instance(CONTRACT, &[]);
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(instance.take_call_trace(), None);
+    }
+
     #[test]
     fn get_gas_left_works() {
-        let mut instance = mock_instance_with_gas_limit(CONTRACT, 123321);
+        let mut instance = mock_instance_with_gas_limit(CONTRACT, 500_000_123_321);
         let orig_gas = instance.get_gas_left();
-        assert_eq!(orig_gas, 123321);
+        // the initial memory is paid for on instantiation
+        let initial_pages = instance.memory_pages() as u32;
+        assert_eq!(
+            orig_gas,
+            500_000_123_321 - memory_initialization_cost(initial_pages)
+        );
+    }
+
+    #[test]
+    fn set_gas_config_works() {
+        let mut instance = mock_instance(CONTRACT, &[]);
+        assert_eq!(instance.gas_config(), GasConfig::default());
+
+        let gas_config = GasConfig {
+            iterator_next_cost_per_byte: 42,
+            ..GasConfig::default()
+        };
+        instance.set_gas_config(gas_config.clone());
+        assert_eq!(instance.gas_config(), gas_config);
     }
 
     #[test]
@@ -842,10 +1794,11 @@ mod tests {
         let mut instance = mock_instance_with_gas_limit(CONTRACT, LIMIT);
 
         let report1 = instance.create_gas_report();
+        let initialization_cost = memory_initialization_cost(instance.memory_pages() as u32);
         assert_eq!(report1.used_externally, 0);
-        assert_eq!(report1.used_internally, 0);
+        assert_eq!(report1.used_internally, initialization_cost);
         assert_eq!(report1.limit, LIMIT);
-        assert_eq!(report1.remaining, LIMIT);
+        assert_eq!(report1.remaining, LIMIT - initialization_cost);
 
         // init contract
         let info = mock_info("creator", &coins(1000, "earth"));
@@ -856,7 +1809,7 @@ mod tests {
 
         let report2 = instance.create_gas_report();
         assert_eq!(report2.used_externally, 73);
-        assert_eq!(report2.used_internally, 5765148);
+        assert_eq!(report2.used_internally, initialization_cost + 8765148);
         assert_eq!(report2.limit, LIMIT);
         assert_eq!(
             report2.remaining,
@@ -864,6 +1817,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn with_gas_limit_works() {
+        const LIMIT: u64 = 700_000_000;
+        let mut instance = mock_instance_with_gas_limit(CONTRACT, LIMIT);
+
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+        let gas_before_query = instance.get_gas_left();
+
+        // the query fits into the lower limit and its gas is deducted from the instance
+        let res = instance
+            .with_gas_limit(DEFAULT_QUERY_GAS_LIMIT * 100, |instance| {
+                call_query(instance, &mock_env(), br#"{"verifier":{}}"#)
+            })
+            .unwrap();
+        assert_eq!(res.unwrap(), br#"{"verifier":"verifies"}"#.as_slice());
+        let used = gas_before_query - instance.get_gas_left();
+        assert!(used > 0);
+        assert!(instance.get_gas_left() > DEFAULT_QUERY_GAS_LIMIT * 100);
+
+        // the query runs out of gas with a very low limit
+        let gas_before_query = instance.get_gas_left();
+        let err = instance
+            .with_gas_limit(used / 2, |instance| {
+                call_query(instance, &mock_env(), br#"{"verifier":{}}"#)
+            })
+            .unwrap_err();
+        assert!(matches!(err, VmError::GasDepletion { .. }));
+        // only the gas of the override was used
+        assert_eq!(instance.get_gas_left(), gas_before_query - used / 2);
+    }
+
     #[test]
     fn set_storage_readonly_works() {
         let mut instance = mock_instance(CONTRACT, &[]);
@@ -1045,7 +2033,7 @@ mod tests {
             .unwrap();
 
         let init_used = orig_gas - instance.get_gas_left();
-        assert_eq!(init_used, 5765221);
+        assert_eq!(init_used, 8765221);
     }
 
     #[test]
@@ -1104,4 +2092,29 @@ mod tests {
         let query_used = gas_before_query - instance.get_gas_left();
         assert_eq!(query_used, 4493706);
     }
+
+    #[test]
+    fn reset_restores_globals() {
+        let mut instance = mock_instance(CONTRACT, &[]);
+        let snapshot = instance.snapshot().unwrap();
+        assert!(!snapshot.globals.is_empty());
+
+        // Modify all globals, including non-exported ones like the stack pointer
+        let objects = instance.store.objects_mut();
+        for (index, value) in snapshot.globals.iter().enumerate() {
+            objects.set_global_unchecked(index, value + 1);
+        }
+        assert_ne!(
+            instance.store.objects_mut().as_u128_globals(),
+            snapshot.globals
+        );
+
+        let (backend, reset) = instance.reset(&snapshot, Size::mebi(16));
+        assert!(backend.is_some());
+        assert!(reset);
+        assert_eq!(
+            instance.store.objects_mut().as_u128_globals(),
+            snapshot.globals
+        );
+    }
 }
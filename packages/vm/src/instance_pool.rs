@@ -0,0 +1,389 @@
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+
+use cosmwasm_std::Checksum;
+
+use crate::backend::{Backend, BackendApi, Querier, Storage};
+use crate::cache::Cache;
+use crate::errors::VmResult;
+use crate::instance::{Instance, InstanceOptions, InstanceSnapshot};
+use crate::size::Size;
+
+/// An idle instance together with the memory it had right after instantiation
+struct IdleInstance<A: BackendApi, S: Storage, Q: Querier> {
+    instance: Instance<A, S, Q>,
+    snapshot: InstanceSnapshot,
+}
+
+/// The idle instances per checksum
+type IdleInstances<A, S, Q> = HashMap<Checksum, Vec<IdleInstance<A, S, Q>>>;
+
+/// An instance that was handed out by an [`InstancePool`].
+///
+/// It dereferences to [`Instance`], so it can be used with all `call_*` functions.
+/// Once done, it should be given back via [`InstancePool::recycle`].
+pub struct PooledInstance<A: BackendApi, S: Storage, Q: Querier> {
+    checksum: Checksum,
+    instance: Instance<A, S, Q>,
+    /// The memory of the instance right after instantiation
+    snapshot: InstanceSnapshot,
+}
+
+impl<A: BackendApi, S: Storage, Q: Querier> PooledInstance<A, S, Q> {
+    /// The checksum of the Wasm code this instance was created from
+    pub fn checksum(&self) -> &Checksum {
+        &self.checksum
+    }
+}
+
+impl<A: BackendApi, S: Storage, Q: Querier> Deref for PooledInstance<A, S, Q> {
+    type Target = Instance<A, S, Q>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.instance
+    }
+}
+
+impl<A: BackendApi, S: Storage, Q: Querier> DerefMut for PooledInstance<A, S, Q> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.instance
+    }
+}
+
+/// A pool of initialized instances per checksum.
+///
+/// Creating an instance from a cached module still requires linking and allocating the
+/// Wasm memory, which dominates latency for small contracts that are called very often
+/// (e.g. queries on RPC nodes). The pool keeps instances around after a call and resets
+/// them to their initial state instead.
+///
+/// Resetting restores the linear memory and all globals from a copy taken right after
+/// instantiation as well as the gas state, such that a recycled instance behaves like a
+/// fresh one.
+///
+/// Instances that cannot be reset reliably are dropped. This is the case when a call into the
+/// instance failed or when its memory grew, since Wasm memory cannot shrink and a larger memory
+/// would change the outcome of later calls (e.g. when reaching the memory limit).
+pub struct InstancePool<A: BackendApi, S: Storage, Q: Querier> {
+    /// The maximum number of idle instances kept per checksum
+    max_idle_per_checksum: usize,
+    /// Instances with a larger memory are not kept
+    max_memory: Size,
+    idle: Mutex<IdleInstances<A, S, Q>>,
+}
+
+impl<A, S, Q> InstancePool<A, S, Q>
+where
+    A: BackendApi + 'static, // 'static is needed by `impl<…> Instance`
+    S: Storage + 'static,    // 'static is needed by `impl<…> Instance`
+    Q: Querier + 'static,    // 'static is needed by `impl<…> Instance`
+{
+    pub fn new(max_idle_per_checksum: usize, max_memory: Size) -> Self {
+        InstancePool {
+            max_idle_per_checksum,
+            max_memory,
+            idle: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns an instance for the given checksum.
+    ///
+    /// If an idle instance is available, it is reused. Otherwise a new instance
+    /// is created using the cache.
+    pub fn get_instance(
+        &self,
+        cache: &Cache<A, S, Q>,
+        checksum: &Checksum,
+        backend: Backend<A, S, Q>,
+        options: InstanceOptions,
+    ) -> VmResult<PooledInstance<A, S, Q>> {
+        let idle = self
+            .idle
+            .lock()
+            .unwrap()
+            .get_mut(checksum)
+            .and_then(|instances| instances.pop());
+
+        if let Some(IdleInstance {
+            mut instance,
+            snapshot,
+        }) = idle
+        {
+            instance.reuse(backend, options, &snapshot);
+            return Ok(PooledInstance {
+                checksum: *checksum,
+                instance,
+                snapshot,
+            });
+        }
+
+        let mut instance = cache.get_instance(checksum, backend, options)?;
+        let snapshot = instance.snapshot()?;
+        Ok(PooledInstance {
+            checksum: *checksum,
+            instance,
+            snapshot,
+        })
+    }
+
+    /// Takes back an instance after use and returns its backend.
+    ///
+    /// The instance is reset and kept for later use if possible. Otherwise it is dropped.
+    #[must_use = "Calling ::recycle() without reusing the returned backend just drops the backend"]
+    pub fn recycle(&self, pooled: PooledInstance<A, S, Q>) -> Option<Backend<A, S, Q>> {
+        let PooledInstance {
+            checksum,
+            mut instance,
+            snapshot,
+        } = pooled;
+
+        let (backend, reusable) = instance.reset(&snapshot, self.max_memory);
+        if reusable {
+            let mut idle = self.idle.lock().unwrap();
+            let instances = idle.entry(checksum).or_default();
+            if instances.len() < self.max_idle_per_checksum {
+                instances.push(IdleInstance { instance, snapshot });
+            }
+        }
+        backend
+    }
+
+    /// Returns the number of idle instances for the given checksum.
+    pub fn idle_count(&self, checksum: &Checksum) -> usize {
+        self.idle
+            .lock()
+            .unwrap()
+            .get(checksum)
+            .map_or(0, |instances| instances.len())
+    }
+
+    /// Drops all idle instances for the given checksum.
+    ///
+    /// This should be called when the code is removed or unpinned.
+    pub fn remove(&self, checksum: &Checksum) {
+        self.idle.lock().unwrap().remove(checksum);
+    }
+
+    /// Drops all idle instances.
+    pub fn clear(&self) {
+        self.idle.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheOptions;
+    use crate::calls::{call_instantiate, call_query_raw};
+    use crate::capabilities::capabilities_from_csv;
+    use crate::errors::VmError;
+    use crate::response_limits::ResponseLimits;
+    use crate::testing::{mock_backend, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{coins, Empty};
+    use tempfile::TempDir;
+
+    const TESTING_GAS_LIMIT: u64 = 500_000_000; // ~0.5ms
+    const TESTING_OPTIONS: InstanceOptions = InstanceOptions {
+        gas_limit: TESTING_GAS_LIMIT,
+        trace: false,
+        response_limits: ResponseLimits::DEFAULT,
+        instrumentation: None,
+    };
+
+    static CONTRACT: &[u8] = include_bytes!("../testdata/hackatom.wasm");
+
+    /// A contract with a bump allocator that never grows its memory and a query export
+    /// returning "Hello". Rust contracts grow their memory on the first allocation, which
+    /// prevents their instances from being reused.
+    fn static_memory_contract() -> Vec<u8> {
+        wat::parse_str(
+            r#"(module
+                (memory 1)
+                (export "memory" (memory 0))
+                (global $heap (mut i32) (i32.const 1024))
+                ;; Region { offset: 64, capacity: 5, length: 5 }
+                (data (i32.const 8) "\40\00\00\00\05\00\00\00\05\00\00\00")
+                (data (i32.const 64) "Hello")
+
+                (func (export "interface_version_8"))
+                (func (export "allocate") (param $size i32) (result i32)
+                    (local $region i32)
+                    (local.set $region (global.get $heap))
+                    (i32.store (local.get $region) (i32.add (local.get $region) (i32.const 12)))
+                    (i32.store offset=4 (local.get $region) (local.get $size))
+                    (i32.store offset=8 (local.get $region) (i32.const 0))
+                    ;; keep regions aligned to 8 bytes
+                    (global.set $heap
+                        (i32.add
+                            (i32.add (local.get $region) (i32.const 16))
+                            (i32.and (i32.add (local.get $size) (i32.const 7)) (i32.const -8))))
+                    (local.get $region)
+                )
+                (func (export "deallocate") (param i32))
+                (func (export "query") (param i32 i32) (result i32)
+                    i32.const 8
+                )
+            )"#,
+        )
+        .unwrap()
+    }
+
+    fn make_cache() -> Cache<MockApi, MockStorage, MockQuerier> {
+        let options = CacheOptions::new(
+            TempDir::new().unwrap().into_path(),
+            capabilities_from_csv("iterator,staking"),
+            Size::mebi(200),
+            Size::mebi(16),
+        );
+        unsafe { Cache::new(options).unwrap() }
+    }
+
+    #[test]
+    fn instance_pool_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<InstancePool<MockApi, MockStorage, MockQuerier>>();
+    }
+
+    #[test]
+    fn get_instance_reuses_recycled_instances() {
+        let cache = make_cache();
+        let checksum = cache.save_wasm(&static_memory_contract()).unwrap();
+        let pool = InstancePool::new(2, Size::mebi(16));
+
+        let mut instance = pool
+            .get_instance(&cache, &checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(instance.checksum(), &checksum);
+        let initial_gas = instance.get_gas_left();
+        let result = call_query_raw(&mut instance, b"{}", b"{}").unwrap();
+        assert_eq!(result, b"Hello");
+        assert!(instance.get_gas_left() < initial_gas);
+        let backend = pool.recycle(instance).unwrap();
+        assert_eq!(pool.idle_count(&checksum), 1);
+        assert_eq!(cache.stats().hits_fs_cache, 1);
+
+        // The second instance comes from the pool
+        let mut instance = pool
+            .get_instance(&cache, &checksum, backend, TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(pool.idle_count(&checksum), 0);
+        assert_eq!(cache.stats().hits_memory_cache, 0);
+        // the same as for a fresh instance, including the memory initialization
+        assert_eq!(instance.get_gas_left(), initial_gas);
+        assert_eq!(instance.create_gas_report().used_externally, 0);
+        let result = call_query_raw(&mut instance, b"{}", b"{}").unwrap();
+        assert_eq!(result, b"Hello");
+        let _ = pool.recycle(instance).unwrap();
+        assert_eq!(pool.idle_count(&checksum), 1);
+    }
+
+    #[test]
+    fn recycle_restores_memory_and_globals() {
+        let cache = make_cache();
+        let checksum = cache.save_wasm(&static_memory_contract()).unwrap();
+        let pool = InstancePool::new(1, Size::mebi(16));
+
+        let mut instance = pool
+            .get_instance(&cache, &checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        let original = instance.snapshot().unwrap();
+        let region_ptr = instance.allocate(64).unwrap();
+        instance.write_memory(region_ptr, &[0xAA; 64]).unwrap();
+        assert!(instance.snapshot().unwrap().memory != original.memory);
+        let backend = pool.recycle(instance).unwrap();
+
+        assert_eq!(pool.idle_count(&checksum), 1);
+        let mut instance = pool
+            .get_instance(&cache, &checksum, backend, TESTING_OPTIONS)
+            .unwrap();
+        assert!(instance.snapshot().unwrap().memory == original.memory);
+        // The heap pointer of the allocator is restored
+        assert_eq!(instance.allocate(64).unwrap(), region_ptr);
+    }
+
+    #[test]
+    fn recycle_drops_instances_with_grown_memory() {
+        let cache = make_cache();
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        let pool = InstancePool::new(1, Size::mebi(16));
+
+        let mut instance = pool
+            .get_instance(&cache, &checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        let initial_pages = instance.memory_pages();
+        // Larger than the free memory, so the contract has to grow its memory
+        instance.allocate(4 * 1024 * 1024).unwrap();
+        assert!(instance.memory_pages() > initial_pages);
+        let backend = pool.recycle(instance).unwrap();
+        assert_eq!(pool.idle_count(&checksum), 0);
+
+        // The next instance is a fresh one
+        let mut instance = pool
+            .get_instance(&cache, &checksum, backend, TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache.stats().hits_memory_cache, 1);
+        assert_eq!(instance.memory_pages(), initial_pages);
+    }
+
+    #[test]
+    fn recycle_drops_instances_exceeding_max_memory() {
+        let cache = make_cache();
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        let pool = InstancePool::new(1, Size::kibi(64));
+
+        let instance = pool
+            .get_instance(&cache, &checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        let _ = pool.recycle(instance).unwrap();
+        assert_eq!(pool.idle_count(&checksum), 0);
+    }
+
+    #[test]
+    fn recycle_drops_failed_instances() {
+        let cache = make_cache();
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        let pool = InstancePool::new(1, Size::mebi(16));
+
+        let options = InstanceOptions {
+            gas_limit: 10,
+            trace: false,
+            response_limits: ResponseLimits::DEFAULT,
+            instrumentation: None,
+        };
+        let mut instance = pool
+            .get_instance(&cache, &checksum, mock_backend(&[]), options)
+            .unwrap();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        match call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap_err()
+        {
+            VmError::GasDepletion { .. } => (),
+            e => panic!("unexpected error, {e:?}"),
+        }
+        // backend is returned but the instance is not kept
+        let _ = pool.recycle(instance).unwrap();
+        assert_eq!(pool.idle_count(&checksum), 0);
+    }
+
+    #[test]
+    fn recycle_respects_max_idle_per_checksum() {
+        let cache = make_cache();
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        let pool = InstancePool::new(1, Size::mebi(16));
+
+        let instance1 = pool
+            .get_instance(&cache, &checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        let instance2 = pool
+            .get_instance(&cache, &checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        let _ = pool.recycle(instance1).unwrap();
+        let _ = pool.recycle(instance2).unwrap();
+        assert_eq!(pool.idle_count(&checksum), 1);
+
+        pool.remove(&checksum);
+        assert_eq!(pool.idle_count(&checksum), 0);
+    }
+}
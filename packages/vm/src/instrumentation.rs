@@ -0,0 +1,38 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::backend::GasInfo;
+
+/// Callbacks invoked by the VM during contract execution, e.g. to attach APM tooling.
+///
+/// Hooks are set via [`InstanceOptions::instrumentation`](crate::InstanceOptions::instrumentation).
+/// All methods have empty default implementations, so implementors only need to override the
+/// ones they are interested in. The hooks are called synchronously during execution and should
+/// return quickly. They must not affect the execution, i.e. they only observe it.
+pub trait InstrumentationHooks: Send + Sync {
+    /// Called before a host function (import) is executed.
+    ///
+    /// `name` is the name of the import, e.g. `db_read`. `gas_left` is measured in
+    /// [CosmWasm gas](https://github.com/CosmWasm/cosmwasm/blob/main/docs/GAS.md).
+    fn on_host_call_start(&self, _name: &'static str, _gas_left: u64) {}
+
+    /// Called after a host function (import) returned, no matter if it succeeded.
+    fn on_host_call_end(
+        &self,
+        _name: &'static str,
+        _gas_left: u64,
+        _duration: Duration,
+        _success: bool,
+    ) {
+    }
+
+    /// Called whenever gas used outside of Wasm execution is charged, e.g. for storage
+    /// access or crypto operations. `gas_left` is the gas left after charging `gas_info`.
+    fn on_gas_checkpoint(&self, _gas_info: GasInfo, _gas_left: u64) {}
+}
+
+impl fmt::Debug for dyn InstrumentationHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("InstrumentationHooks")
+    }
+}
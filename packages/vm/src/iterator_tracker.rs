@@ -0,0 +1,146 @@
+//! Tracking of the storage iterators opened by a contract.
+//!
+//! Storage backends behave differently when a contract writes to the range of an open
+//! iterator: some return the new state, some a snapshot from the time the iterator was
+//! created. To keep the behaviour deterministic, the VM applies the following rule
+//! independent of the backend:
+//!
+//! Writing or removing a key that lies within the range of an open iterator invalidates
+//! this iterator. Every later use of an invalidated iterator (`db_next`, `db_next_key`,
+//! `db_next_value` and `db_seek`) fails with [`VmError::IteratorInvalidated`]. Iterators
+//! whose range does not contain the key remain usable. The tracking is reset whenever
+//! the storage is moved into or out of the environment.
+use std::collections::HashMap;
+
+use crate::errors::{VmError, VmResult};
+
+/// The keys an iterator can return
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IteratorRange {
+    /// All keys in `start..end`, where `None` means unbounded
+    Range {
+        start: Option<Vec<u8>>,
+        end: Option<Vec<u8>>,
+    },
+    /// All keys starting with the prefix
+    Prefix(Vec<u8>),
+}
+
+impl IteratorRange {
+    pub fn contains(&self, key: &[u8]) -> bool {
+        match self {
+            IteratorRange::Range { start, end } => {
+                start.as_deref().map_or(true, |start| key >= start)
+                    && end.as_deref().map_or(true, |end| key < end)
+            }
+            IteratorRange::Prefix(prefix) => key.starts_with(prefix),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TrackedIterator {
+    range: IteratorRange,
+    invalidated: bool,
+}
+
+/// The storage iterators opened in the current call
+#[derive(Debug, Default)]
+pub struct IteratorTracker {
+    iterators: HashMap<u32, TrackedIterator>,
+}
+
+impl IteratorTracker {
+    pub fn open(&mut self, id: u32, range: IteratorRange) {
+        self.iterators.insert(
+            id,
+            TrackedIterator {
+                range,
+                invalidated: false,
+            },
+        );
+    }
+
+    /// Invalidates all open iterators whose range contains the given key
+    pub fn invalidate_overlapping(&mut self, key: &[u8]) {
+        for iterator in self.iterators.values_mut() {
+            if iterator.range.contains(key) {
+                iterator.invalidated = true;
+            }
+        }
+    }
+
+    /// Returns an error if the iterator was invalidated.
+    ///
+    /// Iterators that are not tracked are left to the backend, which reports
+    /// unknown iterator IDs.
+    pub fn check(&self, id: u32) -> VmResult<()> {
+        match self.iterators.get(&id) {
+            Some(iterator) if iterator.invalidated => Err(VmError::iterator_invalidated(id)),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.iterators.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iterator_range_contains_works() {
+        let unbounded = IteratorRange::Range {
+            start: None,
+            end: None,
+        };
+        assert!(unbounded.contains(b""));
+        assert!(unbounded.contains(b"foo"));
+
+        let bounded = IteratorRange::Range {
+            start: Some(b"b".to_vec()),
+            end: Some(b"d".to_vec()),
+        };
+        assert!(!bounded.contains(b"a"));
+        assert!(bounded.contains(b"b"));
+        assert!(bounded.contains(b"c\xff"));
+        assert!(!bounded.contains(b"d"));
+        assert!(!bounded.contains(b"e"));
+
+        let prefix = IteratorRange::Prefix(b"ab".to_vec());
+        assert!(prefix.contains(b"ab"));
+        assert!(prefix.contains(b"abc"));
+        assert!(!prefix.contains(b"a"));
+        assert!(!prefix.contains(b"ac"));
+    }
+
+    #[test]
+    fn invalidate_overlapping_works() {
+        let mut tracker = IteratorTracker::default();
+        tracker.open(1, IteratorRange::Prefix(b"a".to_vec()));
+        tracker.open(
+            2,
+            IteratorRange::Range {
+                start: Some(b"b".to_vec()),
+                end: None,
+            },
+        );
+        assert!(tracker.check(1).is_ok());
+        assert!(tracker.check(2).is_ok());
+
+        tracker.invalidate_overlapping(b"bar");
+        assert!(tracker.check(1).is_ok());
+        match tracker.check(2).unwrap_err() {
+            VmError::IteratorInvalidated { id, .. } => assert_eq!(id, 2),
+            e => panic!("Unexpected error: {e:?}"),
+        }
+
+        // unknown iterators are left to the backend
+        assert!(tracker.check(42).is_ok());
+
+        tracker.clear();
+        assert!(tracker.check(2).is_ok());
+    }
+}
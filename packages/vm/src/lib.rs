@@ -1,29 +1,48 @@
 mod backend;
+pub mod bench;
+mod blocking_backend;
 mod cache;
+mod call_trace;
 mod calls;
 mod capabilities;
+pub mod checker;
 mod compatibility;
+mod concurrency_limit;
 mod conversion;
 mod environment;
 mod errors;
 mod filesystem;
+mod fingerprint;
 mod imports;
 mod instance;
+mod instance_pool;
+mod instrumentation;
+#[cfg(feature = "iterator")]
+mod iterator_tracker;
 mod limited;
 mod memory;
 mod modules;
 mod parsed_wasm;
+mod pin_file;
+mod query_cache;
+mod response_limits;
 mod sections;
 mod serde;
+mod simulation;
 mod size;
 mod static_analysis;
 pub mod testing;
 mod wasm_backend;
 
 pub use crate::backend::{
-    Backend, BackendApi, BackendError, BackendResult, GasInfo, Querier, Storage,
+    Backend, BackendApi, BackendError, BackendResult, GasInfo, Querier, Storage, StorageSize,
+};
+pub use crate::blocking_backend::{
+    AsyncQuerier, AsyncStorage, BlockingBackend, BlockingQuerier, BlockingStorage, BoxFuture,
+    FutureSpawner, ThreadSpawner,
 };
 pub use crate::cache::{AnalysisReport, Cache, CacheOptions, Metrics, Stats};
+pub use crate::call_trace::{CallTrace, HostCall};
 pub use crate::calls::{
     call_execute, call_execute_raw, call_instantiate, call_instantiate_raw, call_migrate,
     call_migrate_raw, call_query, call_query_raw, call_reply, call_reply_raw, call_sudo,
@@ -36,14 +55,29 @@ pub use crate::calls::{
     call_ibc_packet_ack, call_ibc_packet_ack_raw, call_ibc_packet_receive,
     call_ibc_packet_receive_raw, call_ibc_packet_timeout, call_ibc_packet_timeout_raw,
 };
-pub use crate::capabilities::capabilities_from_csv;
+pub use crate::capabilities::{capabilities_from_csv, is_capability_available, Capability};
+pub use crate::compatibility::WasmFeatures;
+pub use crate::environment::{ExecutionMode, GasConfig};
 pub use crate::errors::{
     CommunicationError, CommunicationResult, RegionValidationError, RegionValidationResult,
     VmError, VmResult,
 };
-pub use crate::instance::{DebugInfo, GasReport, Instance, InstanceOptions};
+pub use crate::fingerprint::ModuleFingerprint;
+pub use crate::instance::{CallInfo, DebugInfo, GasReport, Instance, InstanceOptions};
+pub use crate::instance_pool::{InstancePool, PooledInstance};
+pub use crate::instrumentation::InstrumentationHooks;
+pub use crate::modules::ContractMetadata;
+pub use crate::pin_file::{PinFileReport, PinFileWatcher};
+pub use crate::query_cache::MemoizedQuery;
+pub use crate::response_limits::ResponseLimits;
 pub use crate::serde::{from_slice, to_vec};
+pub use crate::simulation::{Simulation, StateDiff, StorageOverlay, WriteGasCosts};
 pub use crate::size::Size;
+pub use crate::static_analysis::Entrypoint;
+pub use crate::wasm_backend::Compiler;
+
+// The checksum type is shared with contracts and therefore lives in cosmwasm-std
+pub use cosmwasm_std::Checksum;
 
 #[doc(hidden)]
 pub mod internals {
@@ -52,7 +86,9 @@ pub mod internals {
     //! Please don't use any of these types directly, as
     //! they might change frequently or be removed in the future.
 
-    pub use crate::compatibility::check_wasm;
+    pub use crate::compatibility::{check_wasm, check_wasm_with_features};
     pub use crate::instance::instance_from_module;
-    pub use crate::wasm_backend::{compile, make_compiling_engine, make_runtime_engine};
+    pub use crate::wasm_backend::{
+        compile, make_compiling_engine, make_compiling_engine_with_compiler, make_runtime_engine,
+    };
 }
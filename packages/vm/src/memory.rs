@@ -68,6 +68,16 @@ pub fn maybe_read_region(
     }
 }
 
+/// Returns the length of the data in the Region at ptr without copying the data.
+/// A pointer to 0 is treated as an empty region.
+pub fn region_length(memory: &wasmer::MemoryView, ptr: u32) -> VmResult<usize> {
+    if ptr == 0 {
+        return Ok(0);
+    }
+    let region = get_region(memory, ptr)?;
+    Ok(region.length as usize)
+}
+
 /// A prepared and sufficiently large memory Region is expected at ptr that points to pre-allocated memory.
 ///
 /// Returns number of bytes written on success.
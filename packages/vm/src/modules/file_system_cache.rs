@@ -1,7 +1,10 @@
+use std::cell::OnceCell;
+use std::collections::HashSet;
 use std::fs;
 use std::hash::Hash;
-use std::io;
+use std::io::{self, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
 use thiserror::Error;
 
 use wasmer::{AsEngineRef, DeserializeError, Module, Target};
@@ -10,7 +13,9 @@ use cosmwasm_std::Checksum;
 
 use crate::errors::{VmError, VmResult};
 use crate::filesystem::mkdir_p;
+use crate::fingerprint::ModuleFingerprint;
 use crate::modules::current_wasmer_module_version;
+use crate::wasm_backend::Compiler;
 
 /// Bump this version whenever the module system changes in a way
 /// that old stored modules would be corrupt when loaded in the new system.
@@ -52,13 +57,54 @@ use crate::modules::current_wasmer_module_version;
 /// - **v8**:<br>
 ///   New version because of Wasmer 4.1.2 -> 4.2.2 upgrade.
 ///   Module compatibility between Wasmer versions is not guaranteed.
-const MODULE_SERIALIZATION_VERSION: &str = "v8";
+/// - **v9**:<br>
+///   New version because of several changes to how modules are compiled and stored:
+///   - modules are compiled with a deterministic stack limit
+///     (see [`MAX_STACK_DEPTH`](crate::wasm_backend::stack_limiter::MAX_STACK_DEPTH))
+///   - the compiler is selectable at runtime and part of the folder name, such that modules
+///     of different compilers are stored separately
+///   - `memory.grow` costs gas per requested page
+///     (see [`MEMORY_PAGE_COST`](crate::wasm_backend::MEMORY_PAGE_COST))
+///   - `memory.grow` checks the memory usage limit of the instance
+///     (see [`Instance::set_memory_usage_limit`](crate::Instance::set_memory_usage_limit))
+///   - stored modules end with a trailer identifying the versions they are compatible with
+///     (see [`ARTIFACT_TRAILER_MAGIC`])
+const MODULE_SERIALIZATION_VERSION: &str = "v9";
+
+/// The folder in the base path that contains the [`ModuleFingerprint`]s of the stored modules.
+///
+/// It is not versioned, such that fingerprints remain available when the modules are
+/// recompiled after a bump of [`MODULE_SERIALIZATION_VERSION`] or a Wasmer upgrade.
+const FINGERPRINTS_DIR: &str = "fingerprints";
+
+/// Marks the end of the trailer that is appended to every stored module.
+///
+/// The trailer is `tag || len(tag) as u32 (little endian) || ARTIFACT_TRAILER_MAGIC`, where
+/// the tag identifies the serialization version, the Wasmer module version, the compiler
+/// and the target (see [`artifact_tag`]). Wasmer ignores data after the serialized module,
+/// so the trailer can be checked before handing the file to Wasmer.
+const ARTIFACT_TRAILER_MAGIC: &[u8; 8] = b"cosmwasm";
+
+/// Max length of the tag in the artifact trailer
+const MAX_LENGTH_ARTIFACT_TAG: usize = 512;
 
 /// Representation of a directory that contains compiled Wasm artifacts.
 pub struct FileSystemCache {
     modules_path: PathBuf,
+    /// The unversioned folder for the fingerprints, see [`FINGERPRINTS_DIR`]
+    fingerprints_path: PathBuf,
+    /// The tag that identifies artifacts compatible with this VM. See [`ARTIFACT_TRAILER_MAGIC`].
+    artifact_tag: String,
     /// If true, the cache uses the `*_unchecked` wasmer functions for loading modules from disk.
     unchecked_modules: bool,
+    /// The checksums of the modules known to be in `modules_path`. This allows loading
+    /// known modules without probing the file system first.
+    ///
+    /// The index is populated lazily on the first load and then kept up to date by `store`
+    /// and `remove`. On a miss, the file system is checked, such that modules added by
+    /// other processes are found and added to the index. It is `None` if the directory
+    /// could not be read, in which case the file system is always checked.
+    index: OnceCell<Option<Mutex<HashSet<Checksum>>>>,
 }
 
 /// An error type that hides system specific error information
@@ -77,7 +123,8 @@ pub enum NewFileSystemCacheError {
 
 impl FileSystemCache {
     /// Construct a new `FileSystemCache` around the specified directory.
-    /// The contents of the cache are stored in sub-versioned directories.
+    /// The contents of the cache are stored in sub-versioned directories,
+    /// separately for each compiler.
     /// If `unchecked_modules` is set to true, it uses the `*_unchecked`
     /// wasmer functions for loading modules from disk (no validity checks).
     ///
@@ -88,6 +135,7 @@ impl FileSystemCache {
     pub unsafe fn new(
         base_path: impl Into<PathBuf>,
         unchecked_modules: bool,
+        compiler: Compiler,
     ) -> Result<Self, NewFileSystemCacheError> {
         let base_path: PathBuf = base_path.into();
         if base_path.exists() {
@@ -105,13 +153,14 @@ impl FileSystemCache {
             mkdir_p(&base_path).map_err(|_e| NewFileSystemCacheError::CouldntCreatePath)?;
         }
 
+        let wasmer_module_version = current_wasmer_module_version();
+        let target = Target::default();
         Ok(Self {
-            modules_path: modules_path(
-                &base_path,
-                current_wasmer_module_version(),
-                &Target::default(),
-            ),
+            modules_path: modules_path(&base_path, wasmer_module_version, compiler, &target),
+            fingerprints_path: base_path.join(FINGERPRINTS_DIR),
+            artifact_tag: artifact_tag(wasmer_module_version, compiler, &target),
             unchecked_modules,
+            index: OnceCell::new(),
         })
     }
 
@@ -121,6 +170,31 @@ impl FileSystemCache {
         self.unchecked_modules = unchecked;
     }
 
+    /// The tag that identifies artifacts compatible with this VM
+    pub fn artifact_tag(&self) -> &str {
+        &self.artifact_tag
+    }
+
+    /// Rebuilds the index of stored modules from the file system.
+    ///
+    /// Modules missing from the index are looked up on disk anyway, so this is only useful
+    /// to drop modules that were removed by other means than [`FileSystemCache::remove`],
+    /// e.g. by another process.
+    pub fn refresh_index(&mut self) -> VmResult<()> {
+        let index = read_index(&self.modules_path)
+            .map_err(|_e| VmError::cache_err("Error reading modules directory"))?;
+        self.index = OnceCell::from(Some(Mutex::new(index)));
+        Ok(())
+    }
+
+    /// Returns the index of stored modules, populating it if needed.
+    fn index(&self) -> Option<MutexGuard<'_, HashSet<Checksum>>> {
+        self.index
+            .get_or_init(|| read_index(&self.modules_path).ok().map(Mutex::new))
+            .as_ref()
+            .map(|index| index.lock().unwrap())
+    }
+
     /// Returns the path to the serialized module with the given checksum.
     fn module_file(&self, checksum: &Checksum) -> PathBuf {
         let mut path = self.modules_path.clone();
@@ -129,8 +203,21 @@ impl FileSystemCache {
         path
     }
 
+    /// Returns the path to the fingerprint of the module with the given checksum.
+    fn fingerprint_file(&self, checksum: &Checksum) -> PathBuf {
+        let mut path = self.fingerprints_path.clone();
+        path.push(checksum.to_hex());
+        path.set_extension("fingerprint");
+        path
+    }
+
     /// Loads a serialized module from the file system and returns a module (i.e. artifact + store),
     /// along with the size of the serialized module.
+    ///
+    /// Before the module is deserialized, its trailer is checked. If the module was stored by
+    /// an incompatible version of the VM or Wasmer, or for a different compiler or target,
+    /// this returns [`VmError::WrongArtifactVersion`]. The caller should then recompile
+    /// the module from the original Wasm and store it again.
     pub fn load(
         &self,
         checksum: &Checksum,
@@ -138,6 +225,36 @@ impl FileSystemCache {
     ) -> VmResult<Option<(Module, usize)>> {
         let file_path = self.module_file(checksum);
 
+        if let Some(mut index) = self.index() {
+            if !index.contains(checksum) {
+                // The module might have been stored by another process
+                if !file_path.exists() {
+                    return Ok(None);
+                }
+                index.insert(*checksum);
+            }
+        }
+
+        let found_tag = match read_artifact_tag(&file_path) {
+            Ok(tag) => tag,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(VmError::cache_err(format!(
+                    "Error opening module file: {err}"
+                )))
+            }
+        };
+        match found_tag {
+            Some(tag) if tag == self.artifact_tag => {}
+            Some(tag) => return Err(VmError::wrong_artifact_version(&self.artifact_tag, tag)),
+            None => {
+                return Err(VmError::wrong_artifact_version(
+                    &self.artifact_tag,
+                    "no tag",
+                ))
+            }
+        }
+
         let result = if self.unchecked_modules {
             unsafe { Module::deserialize_from_file_unchecked(engine, &file_path) }
         } else {
@@ -154,21 +271,64 @@ impl FileSystemCache {
                     "Error opening module file: {err}"
                 ))),
             },
+            Err(DeserializeError::Incompatible(msg)) => Err(VmError::wrong_artifact_version(
+                &self.artifact_tag,
+                format!("incompatible Wasmer artifact ({msg})"),
+            )),
             Err(err) => Err(VmError::cache_err(format!(
                 "Error deserializing module: {err}"
             ))),
         }
     }
 
-    /// Stores a serialized module to the file system. Returns the size of the serialized module.
-    pub fn store(&mut self, checksum: &Checksum, module: &Module) -> VmResult<usize> {
+    /// Returns the fingerprint of the Wasm the stored module was compiled from.
+    ///
+    /// Fingerprints are stored outside of the versioned module folders, so this also works for
+    /// modules that were stored by an incompatible version and not recompiled yet.
+    /// Returns `None` if no module was stored for this checksum.
+    pub fn fingerprint(&self, checksum: &Checksum) -> VmResult<Option<ModuleFingerprint>> {
+        let data = match fs::read(self.fingerprint_file(checksum)) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(VmError::cache_err(format!(
+                    "Error opening fingerprint file: {err}"
+                )))
+            }
+        };
+        let fingerprint: [u8; 32] = hex::decode(data)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| VmError::cache_err("Invalid fingerprint file"))?;
+        Ok(Some(ModuleFingerprint::from(fingerprint)))
+    }
+
+    /// Stores a serialized module to the file system along with the fingerprint of the Wasm
+    /// it was compiled from. Returns the size of the serialized module.
+    pub fn store(
+        &mut self,
+        checksum: &Checksum,
+        module: &Module,
+        fingerprint: &ModuleFingerprint,
+    ) -> VmResult<usize> {
         mkdir_p(&self.modules_path)
             .map_err(|_e| VmError::cache_err("Error creating modules directory"))?;
+        mkdir_p(&self.fingerprints_path)
+            .map_err(|_e| VmError::cache_err("Error creating fingerprints directory"))?;
 
         let path = self.module_file(checksum);
-        module
-            .serialize_to_file(&path)
-            .map_err(|e| VmError::cache_err(format!("Error writing module to disk: {e}")))?;
+        let mut serialized = module
+            .serialize()
+            .map_err(|e| VmError::cache_err(format!("Error writing module to disk: {e}")))?
+            .to_vec();
+        serialized.extend(artifact_trailer(&self.artifact_tag));
+        fs::write(&path, serialized)
+            .map_err(|_e| VmError::cache_err("Error writing module to disk"))?;
+        fs::write(self.fingerprint_file(checksum), fingerprint.to_hex())
+            .map_err(|_e| VmError::cache_err("Error writing fingerprint to disk"))?;
+        if let Some(Some(index)) = self.index.get_mut() {
+            index.get_mut().unwrap().insert(*checksum);
+        }
         let module_size = module_size(&path)?;
         Ok(module_size)
     }
@@ -177,8 +337,16 @@ impl FileSystemCache {
     ///
     /// Returns true if the file existed and false if the file did not exist.
     pub fn remove(&mut self, checksum: &Checksum) -> VmResult<bool> {
-        let file_path = self.module_file(checksum);
+        if let Some(Some(index)) = self.index.get_mut() {
+            index.get_mut().unwrap().remove(checksum);
+        }
+        let fingerprint_path = self.fingerprint_file(checksum);
+        if fingerprint_path.exists() {
+            fs::remove_file(fingerprint_path)
+                .map_err(|_e| VmError::cache_err("Error deleting fingerprint from disk"))?;
+        }
 
+        let file_path = self.module_file(checksum);
         if file_path.exists() {
             fs::remove_file(file_path)
                 .map_err(|_e| VmError::cache_err("Error deleting module from disk"))?;
@@ -189,6 +357,31 @@ impl FileSystemCache {
     }
 }
 
+/// Reads the checksums of all modules in the given directory.
+/// A missing directory is treated like an empty one.
+fn read_index(modules_path: &Path) -> io::Result<HashSet<Checksum>> {
+    let entries = match fs::read_dir(modules_path) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(HashSet::new()),
+        Err(err) => return Err(err),
+    };
+    let mut index = HashSet::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("module") {
+            continue;
+        }
+        let checksum = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| Checksum::from_hex(stem).ok());
+        if let Some(checksum) = checksum {
+            index.insert(checksum);
+        }
+    }
+    Ok(index)
+}
+
 /// Returns the size of the module stored on disk
 fn module_size(module_path: &Path) -> VmResult<usize> {
     let module_size: usize = module_path
@@ -211,9 +404,61 @@ fn target_id(target: &Target) -> String {
     format!("{}-{:08X}", target.triple(), hash) // print 4 byte hash as 8 hex characters
 }
 
-/// The path to the latest version of the modules.
-fn modules_path(base_path: &Path, wasmer_module_version: u32, target: &Target) -> PathBuf {
-    let version_dir = format!("{MODULE_SERIALIZATION_VERSION}-wasmer{wasmer_module_version}");
+/// Creates the tag that identifies compatible artifacts.
+/// Artifacts are only compatible if they were stored with the same serialization version,
+/// Wasmer module version, compiler and target.
+fn artifact_tag(wasmer_module_version: u32, compiler: Compiler, target: &Target) -> String {
+    format!(
+        "{MODULE_SERIALIZATION_VERSION}-wasmer{wasmer_module_version}-{compiler}-{}",
+        target_id(target)
+    )
+}
+
+/// Creates the trailer that is appended to stored modules (see [`ARTIFACT_TRAILER_MAGIC`])
+fn artifact_trailer(tag: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(tag.len() + 4 + ARTIFACT_TRAILER_MAGIC.len());
+    out.extend_from_slice(tag.as_bytes());
+    out.extend_from_slice(&(tag.len() as u32).to_le_bytes());
+    out.extend_from_slice(ARTIFACT_TRAILER_MAGIC);
+    out
+}
+
+/// Reads the tag from the trailer of a stored module.
+/// Returns `None` if the file has no valid trailer.
+fn read_artifact_tag(module_path: &Path) -> io::Result<Option<String>> {
+    let mut file = fs::File::open(module_path)?;
+    let file_len = file.metadata()?.len();
+
+    let fixed_len = (4 + ARTIFACT_TRAILER_MAGIC.len()) as u64;
+    if file_len < fixed_len {
+        return Ok(None);
+    }
+    file.seek(SeekFrom::Start(file_len - fixed_len))?;
+    let mut fixed = [0u8; 12];
+    file.read_exact(&mut fixed)?;
+    if &fixed[4..] != ARTIFACT_TRAILER_MAGIC {
+        return Ok(None);
+    }
+
+    let tag_len = u32::from_le_bytes(fixed[..4].try_into().unwrap()) as u64;
+    if tag_len > MAX_LENGTH_ARTIFACT_TAG as u64 || file_len < fixed_len + tag_len {
+        return Ok(None);
+    }
+    file.seek(SeekFrom::Start(file_len - fixed_len - tag_len))?;
+    let mut tag = vec![0u8; tag_len as usize];
+    file.read_exact(&mut tag)?;
+    Ok(String::from_utf8(tag).ok())
+}
+
+/// The path to the latest version of the modules compiled with the given compiler.
+fn modules_path(
+    base_path: &Path,
+    wasmer_module_version: u32,
+    compiler: Compiler,
+    target: &Target,
+) -> PathBuf {
+    let version_dir =
+        format!("{MODULE_SERIALIZATION_VERSION}-wasmer{wasmer_module_version}-{compiler}");
     let target_dir = target_id(target);
     base_path.join(version_dir).join(target_dir)
 }
@@ -245,11 +490,13 @@ mod tests {
     #[test]
     fn file_system_cache_run() {
         let tmp_dir = TempDir::new().unwrap();
-        let mut cache = unsafe { FileSystemCache::new(tmp_dir.path(), false).unwrap() };
+        let mut cache =
+            unsafe { FileSystemCache::new(tmp_dir.path(), false, Compiler::default()).unwrap() };
 
         // Create module
         let wasm = wat::parse_str(SOME_WAT).unwrap();
         let checksum = Checksum::generate(&wasm);
+        let fingerprint = ModuleFingerprint::generate(&wasm).unwrap();
 
         // Module does not exist
         let runtime_engine = make_runtime_engine(TESTING_MEMORY_LIMIT);
@@ -259,7 +506,7 @@ mod tests {
         // Store module
         let compiling_engine = make_compiling_engine(TESTING_MEMORY_LIMIT);
         let module = compile(&compiling_engine, &wasm).unwrap();
-        cache.store(&checksum, &module).unwrap();
+        cache.store(&checksum, &module, &fingerprint).unwrap();
 
         // Load module
         let cached = cache.load(&checksum, &runtime_engine).unwrap();
@@ -269,7 +516,10 @@ mod tests {
         // This is not really testing the cache API but better safe than sorry.
         {
             let (cached_module, module_size) = cached.unwrap();
-            assert_eq!(module_size, module.serialize().unwrap().len());
+            assert_eq!(
+                module_size,
+                module.serialize().unwrap().len() + artifact_trailer(&cache.artifact_tag).len()
+            );
             let import_object = imports! {};
             let mut store = Store::new(runtime_engine);
             let instance = WasmerInstance::new(&mut store, &cached_module, &import_object).unwrap();
@@ -283,20 +533,23 @@ mod tests {
     #[test]
     fn file_system_cache_store_uses_expected_path() {
         let tmp_dir = TempDir::new().unwrap();
-        let mut cache = unsafe { FileSystemCache::new(tmp_dir.path(), false).unwrap() };
+        let mut cache =
+            unsafe { FileSystemCache::new(tmp_dir.path(), false, Compiler::default()).unwrap() };
 
         // Create module
         let wasm = wat::parse_str(SOME_WAT).unwrap();
         let checksum = Checksum::generate(&wasm);
+        let fingerprint = ModuleFingerprint::generate(&wasm).unwrap();
 
         // Store module
         let engine = make_compiling_engine(TESTING_MEMORY_LIMIT);
         let module = compile(&engine, &wasm).unwrap();
-        cache.store(&checksum, &module).unwrap();
+        cache.store(&checksum, &module, &fingerprint).unwrap();
 
         let mut globber = glob::glob(&format!(
-            "{}/v8-wasmer5/**/{}.module",
+            "{}/v9-wasmer5-{}/**/{}.module",
             tmp_dir.path().to_string_lossy(),
+            Compiler::default(),
             checksum
         ))
         .expect("Failed to read glob pattern");
@@ -304,19 +557,118 @@ mod tests {
         let _serialized_module = fs::read(file_path).unwrap();
     }
 
+    #[test]
+    fn file_system_cache_load_fails_for_wrong_artifact_tag() {
+        let tmp_dir = TempDir::new().unwrap();
+        let mut cache =
+            unsafe { FileSystemCache::new(tmp_dir.path(), false, Compiler::default()).unwrap() };
+
+        let wasm = wat::parse_str(SOME_WAT).unwrap();
+        let checksum = Checksum::generate(&wasm);
+        let fingerprint = ModuleFingerprint::generate(&wasm).unwrap();
+        let engine = make_compiling_engine(TESTING_MEMORY_LIMIT);
+        let module = compile(&engine, &wasm).unwrap();
+        let runtime_engine = make_runtime_engine(TESTING_MEMORY_LIMIT);
+
+        // Module with a trailer of another version
+        let mut serialized = module.serialize().unwrap().to_vec();
+        serialized.extend(artifact_trailer("v1-wasmer1-singlepass-foo"));
+        mkdir_p(&cache.modules_path).unwrap();
+        fs::write(cache.module_file(&checksum), serialized).unwrap();
+        match cache.load(&checksum, &runtime_engine).unwrap_err() {
+            VmError::WrongArtifactVersion {
+                expected, found, ..
+            } => {
+                assert_eq!(expected, cache.artifact_tag);
+                assert_eq!(found, "v1-wasmer1-singlepass-foo");
+            }
+            e => panic!("Unexpected error: {e:?}"),
+        }
+
+        // Module without trailer
+        fs::write(cache.module_file(&checksum), module.serialize().unwrap()).unwrap();
+        match cache.load(&checksum, &runtime_engine).unwrap_err() {
+            VmError::WrongArtifactVersion { found, .. } => assert_eq!(found, "no tag"),
+            e => panic!("Unexpected error: {e:?}"),
+        }
+
+        // Storing again fixes it
+        cache.store(&checksum, &module, &fingerprint).unwrap();
+        assert!(cache.load(&checksum, &runtime_engine).unwrap().is_some());
+    }
+
+    #[test]
+    fn file_system_cache_fingerprint_works() {
+        let tmp_dir = TempDir::new().unwrap();
+        let mut cache =
+            unsafe { FileSystemCache::new(tmp_dir.path(), false, Compiler::default()).unwrap() };
+
+        let wasm = wat::parse_str(SOME_WAT).unwrap();
+        let checksum = Checksum::generate(&wasm);
+        let fingerprint = ModuleFingerprint::generate(&wasm).unwrap();
+        let engine = make_compiling_engine(TESTING_MEMORY_LIMIT);
+        let module = compile(&engine, &wasm).unwrap();
+
+        // Module does not exist
+        assert_eq!(cache.fingerprint(&checksum).unwrap(), None);
+
+        cache.store(&checksum, &module, &fingerprint).unwrap();
+        assert_eq!(cache.fingerprint(&checksum).unwrap(), Some(fingerprint));
+
+        // The fingerprint survives a version change of the modules
+        let mut other_version =
+            unsafe { FileSystemCache::new(tmp_dir.path(), false, Compiler::default()).unwrap() };
+        other_version.modules_path = tmp_dir.path().join("v1-wasmer1-singlepass");
+        let runtime_engine = make_runtime_engine(TESTING_MEMORY_LIMIT);
+        assert!(other_version
+            .load(&checksum, &runtime_engine)
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            other_version.fingerprint(&checksum).unwrap(),
+            Some(fingerprint)
+        );
+
+        // Removing the module removes the fingerprint
+        assert!(cache.remove(&checksum).unwrap());
+        assert_eq!(cache.fingerprint(&checksum).unwrap(), None);
+    }
+
+    #[test]
+    fn artifact_tag_works() {
+        let triple = wasmer::Triple {
+            architecture: wasmer::Architecture::X86_64,
+            vendor: target_lexicon::Vendor::Nintendo,
+            operating_system: target_lexicon::OperatingSystem::Fuchsia,
+            environment: target_lexicon::Environment::Gnu,
+            binary_format: target_lexicon::BinaryFormat::Coff,
+        };
+        let target = Target::new(triple, wasmer::CpuFeature::POPCNT.into());
+        assert_eq!(
+            artifact_tag(17, Compiler::Singlepass, &target),
+            "v9-wasmer17-singlepass-x86_64-nintendo-fuchsia-gnu-coff-01E9F9FE"
+        );
+        assert_eq!(
+            artifact_tag(17, Compiler::Cranelift, &target),
+            "v9-wasmer17-cranelift-x86_64-nintendo-fuchsia-gnu-coff-01E9F9FE"
+        );
+    }
+
     #[test]
     fn file_system_cache_remove_works() {
         let tmp_dir = TempDir::new().unwrap();
-        let mut cache = unsafe { FileSystemCache::new(tmp_dir.path(), false).unwrap() };
+        let mut cache =
+            unsafe { FileSystemCache::new(tmp_dir.path(), false, Compiler::default()).unwrap() };
 
         // Create module
         let wasm = wat::parse_str(SOME_WAT).unwrap();
         let checksum = Checksum::generate(&wasm);
+        let fingerprint = ModuleFingerprint::generate(&wasm).unwrap();
 
         // Store module
         let engine1 = make_compiling_engine(TESTING_MEMORY_LIMIT);
         let module = compile(&engine1, &wasm).unwrap();
-        cache.store(&checksum, &module).unwrap();
+        cache.store(&checksum, &module, &fingerprint).unwrap();
 
         // It's there
         let engine2 = make_runtime_engine(TESTING_MEMORY_LIMIT);
@@ -334,6 +686,51 @@ mod tests {
         assert!(!existed);
     }
 
+    #[test]
+    fn file_system_cache_index_works() {
+        let tmp_dir = TempDir::new().unwrap();
+        let mut cache =
+            unsafe { FileSystemCache::new(tmp_dir.path(), false, Compiler::default()).unwrap() };
+        let mut other =
+            unsafe { FileSystemCache::new(tmp_dir.path(), false, Compiler::default()).unwrap() };
+
+        let wasm = wat::parse_str(SOME_WAT).unwrap();
+        let checksum = Checksum::generate(&wasm);
+        let fingerprint = ModuleFingerprint::generate(&wasm).unwrap();
+        let engine = make_compiling_engine(TESTING_MEMORY_LIMIT);
+        let module = compile(&engine, &wasm).unwrap();
+        let runtime_engine = make_runtime_engine(TESTING_MEMORY_LIMIT);
+
+        // Populates the index of both caches
+        assert!(cache.load(&checksum, &runtime_engine).unwrap().is_none());
+        assert!(other.load(&checksum, &runtime_engine).unwrap().is_none());
+        assert_eq!(cache.index().as_deref(), Some(&HashSet::new()));
+
+        // Storing updates the index
+        cache.store(&checksum, &module, &fingerprint).unwrap();
+        assert_eq!(cache.index().as_deref(), Some(&HashSet::from([checksum])));
+        assert!(cache.load(&checksum, &runtime_engine).unwrap().is_some());
+
+        // The other cache finds the module on disk and adds it to its index
+        assert_eq!(other.index().as_deref(), Some(&HashSet::new()));
+        assert!(other.load(&checksum, &runtime_engine).unwrap().is_some());
+        assert_eq!(other.index().as_deref(), Some(&HashSet::from([checksum])));
+
+        // Removing updates the index
+        cache.remove(&checksum).unwrap();
+        assert_eq!(cache.index().as_deref(), Some(&HashSet::new()));
+
+        // Refreshing rebuilds the index from disk
+        other.refresh_index().unwrap();
+        assert_eq!(other.index().as_deref(), Some(&HashSet::new()));
+
+        // An unpopulated index is read from disk
+        cache.store(&checksum, &module, &fingerprint).unwrap();
+        let cache =
+            unsafe { FileSystemCache::new(tmp_dir.path(), false, Compiler::default()).unwrap() };
+        assert_eq!(cache.index().as_deref(), Some(&HashSet::from([checksum])));
+    }
+
     #[test]
     fn target_id_works() {
         let triple = wasmer::Triple {
@@ -369,13 +766,22 @@ mod tests {
             binary_format: target_lexicon::BinaryFormat::Coff,
         };
         let target = Target::new(triple, wasmer::CpuFeature::POPCNT.into());
-        let p = modules_path(&base, 17, &target);
+        let p = modules_path(&base, 17, Compiler::Singlepass, &target);
+        assert_eq!(
+            p.as_os_str(),
+            if cfg!(windows) {
+                "modules\\v9-wasmer17-singlepass\\x86_64-nintendo-fuchsia-gnu-coff-01E9F9FE"
+            } else {
+                "modules/v9-wasmer17-singlepass/x86_64-nintendo-fuchsia-gnu-coff-01E9F9FE"
+            }
+        );
+        let p = modules_path(&base, 17, Compiler::Cranelift, &target);
         assert_eq!(
             p.as_os_str(),
             if cfg!(windows) {
-                "modules\\v8-wasmer17\\x86_64-nintendo-fuchsia-gnu-coff-01E9F9FE"
+                "modules\\v9-wasmer17-cranelift\\x86_64-nintendo-fuchsia-gnu-coff-01E9F9FE"
             } else {
-                "modules/v8-wasmer17/x86_64-nintendo-fuchsia-gnu-coff-01E9F9FE"
+                "modules/v9-wasmer17-cranelift/x86_64-nintendo-fuchsia-gnu-coff-01E9F9FE"
             }
         );
     }
@@ -0,0 +1,271 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::Checksum;
+
+use crate::capabilities::required_capabilities_from_module;
+use crate::errors::{VmError, VmResult};
+use crate::filesystem::mkdir_p;
+use crate::parsed_wasm::ParsedWasm;
+use crate::static_analysis::{Entrypoint, ExportInfo, REQUIRED_IBC_EXPORTS};
+
+/// Bump this version whenever the format of [`WasmMetadata`] changes.
+/// The string is used as a folder name, such that old metadata is ignored.
+const METADATA_VERSION: &str = "v2";
+
+const INTERFACE_VERSION_PREFIX: &str = "interface_version_";
+
+/// Values of provenance custom sections longer than this are ignored
+const MAX_LENGTH_PROVENANCE_VALUE: usize = 256;
+
+/// Provenance information about a contract, taken from the well-known custom sections
+/// `cargo_version`, `contract_name` and `contract_version` of the Wasm.
+///
+/// Those sections are embedded by the contract author (e.g. with the same values as the
+/// cw2 contract version) and are not verified in any way. A field is `None` if the section
+/// does not exist, exists more than once, is not valid UTF-8 or is too long.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct ContractMetadata {
+    /// The version of cargo (and rustc) the contract was built with
+    pub cargo_version: Option<String>,
+    /// The name of the contract, e.g. `crates.io:cw20-base`
+    pub contract_name: Option<String>,
+    /// The version of the contract, e.g. `1.1.0`
+    pub contract_version: Option<String>,
+}
+
+impl ContractMetadata {
+    pub fn from_module(module: &ParsedWasm) -> Self {
+        let section = |name: &str| {
+            let mut values = module
+                .custom_sections
+                .iter()
+                .filter(|(section_name, _)| *section_name == name);
+            match (values.next(), values.next()) {
+                (Some((_, data)), None) if data.len() <= MAX_LENGTH_PROVENANCE_VALUE => {
+                    String::from_utf8(data.to_vec()).ok()
+                }
+                _ => None,
+            }
+        };
+
+        ContractMetadata {
+            cargo_version: section("cargo_version"),
+            contract_name: section("contract_name"),
+            contract_version: section("contract_version"),
+        }
+    }
+}
+
+/// Static information about a Wasm blob, which is cached to avoid parsing the Wasm again.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WasmMetadata {
+    /// All entry points exported by the contract
+    pub entrypoints: BTreeSet<Entrypoint>,
+    /// `true` if and only if all [`REQUIRED_IBC_EXPORTS`] exist as exported functions
+    pub has_ibc_entry_points: bool,
+    /// The set of capabilities the contract requires
+    pub required_capabilities: BTreeSet<String>,
+    /// The interface version of the `interface_version_*` marker export, if there is exactly one
+    pub interface_version: Option<u32>,
+    /// Provenance information from the contract's custom sections
+    pub contract: ContractMetadata,
+}
+
+impl WasmMetadata {
+    pub fn from_module(module: &ParsedWasm) -> Self {
+        let exports = module.exported_function_names(None);
+        let entrypoints = exports
+            .iter()
+            .filter_map(|export| Entrypoint::from_str(export).ok())
+            .collect();
+        let mut interface_versions = module
+            .exported_function_names(Some(INTERFACE_VERSION_PREFIX))
+            .into_iter()
+            .filter_map(|export| export[INTERFACE_VERSION_PREFIX.len()..].parse::<u32>().ok());
+        let interface_version = match (interface_versions.next(), interface_versions.next()) {
+            (Some(version), None) => Some(version),
+            _ => None,
+        };
+
+        WasmMetadata {
+            has_ibc_entry_points: REQUIRED_IBC_EXPORTS
+                .iter()
+                .all(|required| exports.contains(required.as_ref())),
+            entrypoints,
+            required_capabilities: required_capabilities_from_module(module)
+                .into_iter()
+                .collect(),
+            interface_version,
+            contract: ContractMetadata::from_module(module),
+        }
+    }
+}
+
+/// A directory that contains [`WasmMetadata`] files, one per checksum.
+///
+/// The metadata can always be derived from the Wasm blob, so missing or unreadable
+/// files are not an error but reported as a cache miss.
+#[derive(Clone)]
+pub struct MetadataCache {
+    metadata_path: PathBuf,
+}
+
+impl MetadataCache {
+    pub fn new(base_path: impl Into<PathBuf>) -> Self {
+        Self {
+            metadata_path: base_path.into().join(METADATA_VERSION),
+        }
+    }
+
+    fn metadata_file(&self, checksum: &Checksum) -> PathBuf {
+        let mut path = self.metadata_path.clone();
+        path.push(checksum.to_hex());
+        path.set_extension("json");
+        path
+    }
+
+    /// Loads the metadata for the given checksum.
+    /// Returns `None` if it was not stored before or cannot be read.
+    pub fn load(&self, checksum: &Checksum) -> Option<WasmMetadata> {
+        let data = fs::read(self.metadata_file(checksum)).ok()?;
+        serde_json::from_slice(&data).ok()
+    }
+
+    pub fn store(&self, checksum: &Checksum, metadata: &WasmMetadata) -> VmResult<()> {
+        mkdir_p(&self.metadata_path)
+            .map_err(|_e| VmError::cache_err("Error creating metadata directory"))?;
+        let data = serde_json::to_vec(metadata)
+            .map_err(|e| VmError::cache_err(format!("Error serializing metadata: {e}")))?;
+        fs::write(self.metadata_file(checksum), data)
+            .map_err(|_e| VmError::cache_err("Error writing metadata to disk"))
+    }
+
+    /// Removes the metadata for the given checksum if it exists.
+    pub fn remove(&self, checksum: &Checksum) -> VmResult<()> {
+        let path = self.metadata_file(checksum);
+        if path.exists() {
+            fs::remove_file(path)
+                .map_err(|_e| VmError::cache_err("Error deleting metadata from disk"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    static CONTRACT: &[u8] = include_bytes!("../../testdata/hackatom.wasm");
+    static IBC_CONTRACT: &[u8] = include_bytes!("../../testdata/ibc_reflect.wasm");
+
+    #[test]
+    fn wasm_metadata_from_module_works() {
+        let module = ParsedWasm::parse(CONTRACT).unwrap();
+        let metadata = WasmMetadata::from_module(&module);
+        assert!(!metadata.has_ibc_entry_points);
+        assert!(metadata.entrypoints.contains(&Entrypoint::Instantiate));
+        assert!(!metadata.entrypoints.contains(&Entrypoint::IbcChannelOpen));
+        assert_eq!(metadata.interface_version, Some(8));
+
+        let module = ParsedWasm::parse(IBC_CONTRACT).unwrap();
+        let metadata = WasmMetadata::from_module(&module);
+        assert!(metadata.has_ibc_entry_points);
+        assert!(metadata.required_capabilities.contains("stargate"));
+    }
+
+    /// Appends a custom section to the given Wasm
+    fn with_custom_section(wasm: &[u8], name: &str, data: &[u8]) -> Vec<u8> {
+        fn leb128(mut value: usize, out: &mut Vec<u8>) {
+            loop {
+                let byte = (value & 0x7f) as u8;
+                value >>= 7;
+                if value == 0 {
+                    out.push(byte);
+                    return;
+                }
+                out.push(byte | 0x80);
+            }
+        }
+
+        let mut content = vec![];
+        leb128(name.len(), &mut content);
+        content.extend_from_slice(name.as_bytes());
+        content.extend_from_slice(data);
+
+        let mut out = wasm.to_vec();
+        out.push(0); // custom section id
+        leb128(content.len(), &mut out);
+        out.extend(content);
+        out
+    }
+
+    #[test]
+    fn contract_metadata_from_module_works() {
+        // no custom sections
+        let module = ParsedWasm::parse(CONTRACT).unwrap();
+        assert_eq!(
+            ContractMetadata::from_module(&module),
+            ContractMetadata::default()
+        );
+
+        let wasm = with_custom_section(CONTRACT, "contract_name", b"crates.io:hackatom");
+        let wasm = with_custom_section(&wasm, "contract_version", b"0.0.0");
+        let wasm = with_custom_section(&wasm, "cargo_version", b"cargo 1.73.0");
+        let wasm = with_custom_section(&wasm, "something_else", b"ignored");
+        let module = ParsedWasm::parse(&wasm).unwrap();
+        assert_eq!(
+            ContractMetadata::from_module(&module),
+            ContractMetadata {
+                cargo_version: Some("cargo 1.73.0".to_string()),
+                contract_name: Some("crates.io:hackatom".to_string()),
+                contract_version: Some("0.0.0".to_string()),
+            }
+        );
+
+        // duplicate, non UTF-8 and too long values are ignored
+        let wasm = with_custom_section(CONTRACT, "contract_name", b"one");
+        let wasm = with_custom_section(&wasm, "contract_name", b"two");
+        let wasm = with_custom_section(&wasm, "contract_version", b"\xff\xfe");
+        let wasm = with_custom_section(&wasm, "cargo_version", &[b'a'; 257]);
+        let module = ParsedWasm::parse(&wasm).unwrap();
+        assert_eq!(
+            ContractMetadata::from_module(&module),
+            ContractMetadata::default()
+        );
+    }
+
+    #[test]
+    fn metadata_cache_works() {
+        let tmp_dir = TempDir::new().unwrap();
+        let cache = MetadataCache::new(tmp_dir.path());
+        let checksum = Checksum::generate(CONTRACT);
+
+        assert_eq!(cache.load(&checksum), None);
+
+        let metadata = WasmMetadata::from_module(&ParsedWasm::parse(CONTRACT).unwrap());
+        cache.store(&checksum, &metadata).unwrap();
+        assert_eq!(cache.load(&checksum), Some(metadata));
+
+        cache.remove(&checksum).unwrap();
+        assert_eq!(cache.load(&checksum), None);
+        // removing again is fine
+        cache.remove(&checksum).unwrap();
+    }
+
+    #[test]
+    fn metadata_cache_load_ignores_corrupt_files() {
+        let tmp_dir = TempDir::new().unwrap();
+        let cache = MetadataCache::new(tmp_dir.path());
+        let checksum = Checksum::generate(CONTRACT);
+
+        mkdir_p(&cache.metadata_path).unwrap();
+        fs::write(cache.metadata_file(&checksum), b"{\"entrypoints\":").unwrap();
+        assert_eq!(cache.load(&checksum), None);
+    }
+}
@@ -1,11 +1,15 @@
 mod cached_module;
 mod file_system_cache;
 mod in_memory_cache;
+mod metadata_cache;
 mod pinned_memory_cache;
+mod pinned_snapshot;
 mod versioning;
 
 pub use cached_module::CachedModule;
 pub use file_system_cache::{FileSystemCache, NewFileSystemCacheError};
 pub use in_memory_cache::InMemoryCache;
+pub use metadata_cache::{ContractMetadata, MetadataCache, WasmMetadata};
 pub use pinned_memory_cache::PinnedMemoryCache;
+pub use pinned_snapshot::{read_pinned_snapshot, write_pinned_snapshot, PinnedSnapshotEntry};
 pub use versioning::current_wasmer_module_version;
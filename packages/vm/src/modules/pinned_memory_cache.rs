@@ -49,6 +49,11 @@ impl PinnedMemoryCache {
         }
     }
 
+    /// Returns an iterator over all entries in the cache, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&Checksum, &CachedModule)> {
+        self.modules.iter()
+    }
+
     /// Returns true if and only if this cache has an entry identified by the given checksum
     pub fn has(&self, checksum: &Checksum) -> bool {
         self.modules.contains_key(checksum)
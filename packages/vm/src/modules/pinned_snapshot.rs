@@ -0,0 +1,273 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use cosmwasm_std::Checksum;
+
+use super::metadata_cache::WasmMetadata;
+use crate::errors::{VmError, VmResult};
+
+/// Identifies a pinned cache snapshot file
+const SNAPSHOT_MAGIC: &[u8; 8] = b"cwpinned";
+
+/// Bump this version whenever the layout of the snapshot file changes.
+const SNAPSHOT_FORMAT_VERSION: u32 = 2;
+
+/// Max length of the artifact tag in the snapshot header
+const MAX_LENGTH_ARTIFACT_TAG: usize = 512;
+
+/// Max length of the serialized metadata of one entry
+const MAX_LENGTH_METADATA: usize = 1024 * 1024;
+
+/// A single pinned module in a snapshot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PinnedSnapshotEntry {
+    pub checksum: Checksum,
+    /// The static information about the Wasm. Optional since it can always be
+    /// derived from the Wasm blob again.
+    pub metadata: Option<WasmMetadata>,
+    /// The serialized module, as produced by `Module::serialize`
+    pub artifact: Vec<u8>,
+}
+
+/// Writes a snapshot of pinned modules to a single file.
+///
+/// The file layout is (all integers little endian):
+///
+/// ```text
+/// "cwpinned" || format version: u32 || tag length: u32 || tag || entry count: u32 || entries
+/// ```
+///
+/// where every entry is
+///
+/// ```text
+/// checksum: [u8; 32] || metadata length: u32 || metadata JSON || artifact length: u64 || artifact
+/// ```
+///
+/// A metadata length of 0 means the entry has no metadata. The tag identifies the compiler,
+/// target and Wasmer version the artifacts were created with (see `FileSystemCache`).
+pub fn write_pinned_snapshot(
+    path: &Path,
+    artifact_tag: &str,
+    entries: &[PinnedSnapshotEntry],
+) -> VmResult<()> {
+    let file = File::create(path)
+        .map_err(|_e| VmError::cache_err("Error creating pinned snapshot file"))?;
+    let mut writer = BufWriter::new(file);
+    write_snapshot_to(&mut writer, artifact_tag, entries)
+        .and_then(|_| writer.flush())
+        .map_err(|_e| VmError::cache_err("Error writing pinned snapshot to disk"))
+}
+
+fn write_snapshot_to(
+    writer: &mut impl Write,
+    artifact_tag: &str,
+    entries: &[PinnedSnapshotEntry],
+) -> io::Result<()> {
+    writer.write_all(SNAPSHOT_MAGIC)?;
+    writer.write_all(&SNAPSHOT_FORMAT_VERSION.to_le_bytes())?;
+    writer.write_all(&(artifact_tag.len() as u32).to_le_bytes())?;
+    writer.write_all(artifact_tag.as_bytes())?;
+    writer.write_all(&(entries.len() as u32).to_le_bytes())?;
+    for entry in entries {
+        let metadata = match &entry.metadata {
+            Some(metadata) => serde_json::to_vec(metadata)?,
+            None => Vec::new(),
+        };
+        writer.write_all(entry.checksum.as_slice())?;
+        writer.write_all(&(metadata.len() as u32).to_le_bytes())?;
+        writer.write_all(&metadata)?;
+        writer.write_all(&(entry.artifact.len() as u64).to_le_bytes())?;
+        writer.write_all(&entry.artifact)?;
+    }
+    Ok(())
+}
+
+/// Reads a snapshot written by [`write_pinned_snapshot`].
+///
+/// If the snapshot was created for a different artifact tag, [`VmError::WrongArtifactVersion`]
+/// is returned since the contained artifacts cannot be loaded by this VM.
+pub fn read_pinned_snapshot(path: &Path, artifact_tag: &str) -> VmResult<Vec<PinnedSnapshotEntry>> {
+    let file =
+        File::open(path).map_err(|_e| VmError::cache_err("Error opening pinned snapshot file"))?;
+    let mut reader = BufReader::new(file);
+    let read_err = |_e: io::Error| VmError::cache_err("Error reading pinned snapshot");
+
+    let magic: [u8; 8] = read_array(&mut reader).map_err(read_err)?;
+    if &magic != SNAPSHOT_MAGIC {
+        return Err(VmError::cache_err("File is not a pinned snapshot"));
+    }
+    let version = read_u32(&mut reader).map_err(read_err)?;
+    if version != SNAPSHOT_FORMAT_VERSION {
+        return Err(VmError::cache_err(format!(
+            "Unsupported pinned snapshot format version {version}"
+        )));
+    }
+    let tag_len = read_u32(&mut reader).map_err(read_err)? as usize;
+    if tag_len > MAX_LENGTH_ARTIFACT_TAG {
+        return Err(VmError::cache_err(
+            "Artifact tag in pinned snapshot too long",
+        ));
+    }
+    let tag = read_vec(&mut reader, tag_len).map_err(read_err)?;
+    let tag = String::from_utf8_lossy(&tag);
+    if tag != artifact_tag {
+        return Err(VmError::wrong_artifact_version(artifact_tag, tag));
+    }
+
+    let count = read_u32(&mut reader).map_err(read_err)?;
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let checksum = Checksum::from(read_array::<32>(&mut reader).map_err(read_err)?);
+        let metadata_len = read_u32(&mut reader).map_err(read_err)? as usize;
+        if metadata_len > MAX_LENGTH_METADATA {
+            return Err(VmError::cache_err("Metadata in pinned snapshot too long"));
+        }
+        let metadata = match metadata_len {
+            0 => None,
+            len => {
+                let data = read_vec(&mut reader, len).map_err(read_err)?;
+                Some(serde_json::from_slice(&data).map_err(|e| {
+                    VmError::cache_err(format!("Error parsing metadata in pinned snapshot: {e}"))
+                })?)
+            }
+        };
+        let artifact_len = read_u64(&mut reader).map_err(read_err)?;
+        let artifact_len = usize::try_from(artifact_len)
+            .map_err(|_e| VmError::cache_err("Artifact in pinned snapshot too long"))?;
+        let artifact = read_vec(&mut reader, artifact_len).map_err(read_err)?;
+        entries.push(PinnedSnapshotEntry {
+            checksum,
+            metadata,
+            artifact,
+        });
+    }
+
+    let mut rest = [0u8; 1];
+    if reader.read(&mut rest).map_err(read_err)? != 0 {
+        return Err(VmError::cache_err("Trailing data in pinned snapshot"));
+    }
+    Ok(entries)
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut out = [0u8; N];
+    reader.read_exact(&mut out)?;
+    Ok(out)
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    read_array(reader).map(u32::from_le_bytes)
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    read_array(reader).map(u64::from_le_bytes)
+}
+
+/// Reads exactly `len` bytes. The buffer grows with the data read, such that a corrupt
+/// length does not cause a huge allocation up front.
+fn read_vec(reader: &mut impl Read, len: usize) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    reader.take(len as u64).read_to_end(&mut out)?;
+    if out.len() != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn make_entries() -> Vec<PinnedSnapshotEntry> {
+        vec![
+            PinnedSnapshotEntry {
+                checksum: Checksum::generate(b"first"),
+                metadata: Some(WasmMetadata {
+                    entrypoints: BTreeSet::new(),
+                    has_ibc_entry_points: false,
+                    required_capabilities: BTreeSet::from(["iterator".to_string()]),
+                    interface_version: Some(8),
+                    contract: Default::default(),
+                }),
+                artifact: b"artifact one".to_vec(),
+            },
+            PinnedSnapshotEntry {
+                checksum: Checksum::generate(b"second"),
+                metadata: None,
+                artifact: vec![],
+            },
+        ]
+    }
+
+    #[test]
+    fn pinned_snapshot_roundtrip_works() {
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.path().join("pinned.snapshot");
+
+        let entries = make_entries();
+        write_pinned_snapshot(&path, "some-tag", &entries).unwrap();
+        assert_eq!(read_pinned_snapshot(&path, "some-tag").unwrap(), entries);
+
+        write_pinned_snapshot(&path, "some-tag", &[]).unwrap();
+        assert_eq!(read_pinned_snapshot(&path, "some-tag").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn read_pinned_snapshot_fails_for_wrong_tag() {
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.path().join("pinned.snapshot");
+
+        write_pinned_snapshot(&path, "other-tag", &make_entries()).unwrap();
+        match read_pinned_snapshot(&path, "some-tag").unwrap_err() {
+            VmError::WrongArtifactVersion {
+                expected, found, ..
+            } => {
+                assert_eq!(expected, "some-tag");
+                assert_eq!(found, "other-tag");
+            }
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn read_pinned_snapshot_fails_for_corrupt_file() {
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.path().join("pinned.snapshot");
+
+        // wrong magic
+        fs::write(&path, b"something else").unwrap();
+        match read_pinned_snapshot(&path, "some-tag").unwrap_err() {
+            VmError::CacheErr { msg, .. } => assert_eq!(msg, "File is not a pinned snapshot"),
+            e => panic!("Unexpected error: {e:?}"),
+        }
+
+        // truncated
+        write_pinned_snapshot(&path, "some-tag", &make_entries()).unwrap();
+        let data = fs::read(&path).unwrap();
+        fs::write(&path, &data[..data.len() - 3]).unwrap();
+        match read_pinned_snapshot(&path, "some-tag").unwrap_err() {
+            VmError::CacheErr { msg, .. } => assert_eq!(msg, "Error reading pinned snapshot"),
+            e => panic!("Unexpected error: {e:?}"),
+        }
+
+        // trailing data
+        let mut data = data;
+        data.push(0);
+        fs::write(&path, &data).unwrap();
+        match read_pinned_snapshot(&path, "some-tag").unwrap_err() {
+            VmError::CacheErr { msg, .. } => assert_eq!(msg, "Trailing data in pinned snapshot"),
+            e => panic!("Unexpected error: {e:?}"),
+        }
+
+        // missing file
+        fs::remove_file(&path).unwrap();
+        match read_pinned_snapshot(&path, "some-tag").unwrap_err() {
+            VmError::CacheErr { msg, .. } => assert_eq!(msg, "Error opening pinned snapshot file"),
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+}
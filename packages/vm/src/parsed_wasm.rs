@@ -1,9 +1,22 @@
 use wasmer::wasmparser::{
-    Export, Import, MemoryType, Parser, TableType, ValidPayload, Validator, WasmFeatures,
+    ElementKind, Export, Import, MemoryType, Parser, TableType, ValidPayload, Validator,
+    WasmFeatures as ValidatorFeatures,
 };
 
+use crate::compatibility::WasmFeatures;
+use crate::fingerprint::{FingerprintHasher, ModuleFingerprint};
 use crate::VmResult;
 
+/// The parts of an element segment that are important for our compatibility checks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElementSegment {
+    /// The index of the table this segment initializes.
+    /// `None` for passive and declared segments.
+    pub table_index: Option<u32>,
+    /// The number of items in this segment
+    pub count: u32,
+}
+
 /// A parsed and validated wasm module.
 /// It keeps track of the parts that are important for our static analysis and compatibility checks.
 #[derive(Debug)]
@@ -12,28 +25,48 @@ pub struct ParsedWasm<'a> {
     pub exports: Vec<Export<'a>>,
     pub imports: Vec<Import<'a>>,
     pub tables: Vec<TableType>,
+    pub elements: Vec<ElementSegment>,
     pub memories: Vec<MemoryType>,
+    /// Name and data of all custom sections
+    pub custom_sections: Vec<(&'a str, &'a [u8])>,
+    /// The fingerprint of the module, calculated while parsing
+    pub fingerprint: ModuleFingerprint,
 }
 
 impl<'a> ParsedWasm<'a> {
+    /// Parses and validates the Wasm using the default [`WasmFeatures`]
     pub fn parse(wasm: &'a [u8]) -> VmResult<Self> {
-        let mut validator = Validator::new_with_features(WasmFeatures {
+        Self::parse_with_features(wasm, &WasmFeatures::default())
+    }
+
+    /// Parses and validates the Wasm. Uses of Wasm proposals that are not enabled
+    /// in `features` are rejected.
+    ///
+    /// The multi-memory and memory64 proposals are always accepted by the parser, such that
+    /// the compatibility checks can reject them with a dedicated error.
+    pub fn parse_with_features(wasm: &'a [u8], features: &WasmFeatures) -> VmResult<Self> {
+        let mut validator = Validator::new_with_features(ValidatorFeatures {
             deterministic_only: true,
             component_model: false,
-            simd: false,
+            bulk_memory: features.bulk_memory,
+            simd: features.simd,
             relaxed_simd: false,
             threads: false,
-            multi_memory: false,
-            memory64: false,
+            multi_memory: true,
+            memory64: true,
             ..Default::default()
         });
 
+        let mut fingerprint = FingerprintHasher::new();
         let mut this = Self {
             version: 0,
             exports: vec![],
             imports: vec![],
             tables: vec![],
+            elements: vec![],
             memories: vec![],
+            custom_sections: vec![],
+            fingerprint: ModuleFingerprint::from([0; 32]),
         };
 
         let mut fun_allocations = Default::default();
@@ -46,6 +79,7 @@ impl<'a> ParsedWasm<'a> {
                 fun_validator.validate(&body)?;
                 fun_allocations = fun_validator.into_allocations();
             }
+            fingerprint.update(wasm, &p);
 
             match p {
                 wasmer::wasmparser::Payload::Version { num, .. } => this.version = num,
@@ -55,15 +89,30 @@ impl<'a> ParsedWasm<'a> {
                 wasmer::wasmparser::Payload::TableSection(t) => {
                     this.tables = t.into_iter().collect::<Result<Vec<_>, _>>()?;
                 }
+                wasmer::wasmparser::Payload::ElementSection(e) => {
+                    for element in e {
+                        let element = element?;
+                        let table_index = match element.kind {
+                            ElementKind::Active { table_index, .. } => Some(table_index),
+                            ElementKind::Passive | ElementKind::Declared => None,
+                        };
+                        let count = element.items.get_items_reader()?.get_count();
+                        this.elements.push(ElementSegment { table_index, count });
+                    }
+                }
                 wasmer::wasmparser::Payload::MemorySection(m) => {
                     this.memories = m.into_iter().collect::<Result<Vec<_>, _>>()?;
                 }
                 wasmer::wasmparser::Payload::ExportSection(e) => {
                     this.exports = e.into_iter().collect::<Result<Vec<_>, _>>()?;
                 }
+                wasmer::wasmparser::Payload::CustomSection(c) => {
+                    this.custom_sections.push((c.name(), c.data()));
+                }
                 _ => {} // ignore everything else
             }
         }
+        this.fingerprint = fingerprint.finalize();
 
         Ok(this)
     }
@@ -0,0 +1,167 @@
+//! A file listing the checksums of contracts that should be pinned.
+//!
+//! The file contains one hex encoded checksum per line. Empty lines and everything
+//! after a `#` are ignored, e.g.
+//!
+//! ```text
+//! # cw20-base
+//! 13a1fc994cc6d1c81b746ee0c0ff6f90043875e0bf1d9be6b7d779fc978dc2a5
+//! ```
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+use cosmwasm_std::Checksum;
+
+use crate::errors::{VmError, VmResult};
+
+/// The result of applying a pin file to the cache
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PinFileReport {
+    /// Number of modules that were pinned because they were added to the file
+    pub pinned: usize,
+    /// Number of modules that were unpinned because they were removed from the file
+    /// and are not pinned by other means
+    pub unpinned: usize,
+}
+
+/// The pin file that is currently applied to a cache
+#[derive(Debug)]
+pub(crate) struct PinFile {
+    pub path: PathBuf,
+    /// The modification time of the file when it was read last
+    pub modified: Option<SystemTime>,
+    /// Checksums pinned because they are listed in the file. Removing them from the
+    /// file unpins them unless they are also pinned via [`Cache::pin`](crate::Cache::pin).
+    pub pinned: HashSet<Checksum>,
+}
+
+/// Returns the modification time of the file, if the platform supports it
+pub(crate) fn modified(path: &Path) -> VmResult<Option<SystemTime>> {
+    let metadata = fs::metadata(path)
+        .map_err(|e| VmError::cache_err(format!("Error reading pin file metadata: {e}")))?;
+    Ok(metadata.modified().ok())
+}
+
+/// Reads the checksums listed in a pin file, in the order of the file without duplicates
+pub(crate) fn read_pin_file(path: &Path) -> VmResult<Vec<Checksum>> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| VmError::cache_err(format!("Error reading pin file: {e}")))?;
+    parse_pin_file(&content)
+}
+
+fn parse_pin_file(content: &str) -> VmResult<Vec<Checksum>> {
+    let mut seen = HashSet::new();
+    let mut checksums = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let entry = line.split('#').next().unwrap_or_default().trim();
+        if entry.is_empty() {
+            continue;
+        }
+        let checksum = Checksum::from_hex(entry).map_err(|_| {
+            VmError::cache_err(format!(
+                "Invalid checksum in pin file at line {}: {entry}",
+                index + 1
+            ))
+        })?;
+        if seen.insert(checksum) {
+            checksums.push(checksum);
+        }
+    }
+    Ok(checksums)
+}
+
+/// Reloads the pin file of a cache in a background thread, see
+/// [`Cache::watch_pin_file`](crate::Cache::watch_pin_file).
+///
+/// The file is polled in a fixed interval. The thread is stopped when the watcher
+/// is dropped.
+pub struct PinFileWatcher {
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+    last_error: Arc<Mutex<Option<String>>>,
+}
+
+impl PinFileWatcher {
+    pub(crate) fn spawn(
+        interval: Duration,
+        mut reload: impl FnMut() -> VmResult<Option<PinFileReport>> + Send + 'static,
+    ) -> Self {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let last_error = Arc::new(Mutex::new(None));
+        let handle = {
+            let last_error = Arc::clone(&last_error);
+            thread::spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                    match reload() {
+                        Ok(None) => {}
+                        Ok(Some(_)) => *last_error.lock().unwrap() = None,
+                        Err(e) => *last_error.lock().unwrap() = Some(e.to_string()),
+                    }
+                }
+            })
+        };
+        Self {
+            stop: Some(stop),
+            handle: Some(handle),
+            last_error,
+        }
+    }
+
+    /// Returns the error of the last failed reload. This is reset once the file
+    /// is applied successfully.
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().unwrap().clone()
+    }
+}
+
+impl Drop for PinFileWatcher {
+    fn drop(&mut self) {
+        // Dropping the sender wakes up the thread
+        drop(self.stop.take());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHECKSUM1: &str = "13a1fc994cc6d1c81b746ee0c0ff6f90043875e0bf1d9be6b7d779fc978dc2a5";
+    const CHECKSUM2: &str = "aabbccddeeff00112233445566778899aabbccddeeff00112233445566778899";
+
+    #[test]
+    fn parse_pin_file_works() {
+        let content = format!(
+            "# pinned contracts\n\n{CHECKSUM1}\n  {CHECKSUM2}  # with comment\n{CHECKSUM1}\n"
+        );
+        let checksums = parse_pin_file(&content).unwrap();
+        assert_eq!(
+            checksums,
+            [
+                Checksum::from_hex(CHECKSUM1).unwrap(),
+                Checksum::from_hex(CHECKSUM2).unwrap()
+            ]
+        );
+
+        assert_eq!(parse_pin_file("").unwrap(), []);
+        assert_eq!(parse_pin_file("# nothing\n").unwrap(), []);
+    }
+
+    #[test]
+    fn parse_pin_file_fails_for_invalid_checksum() {
+        let content = format!("{CHECKSUM1}\nfoobar\n");
+        match parse_pin_file(&content).unwrap_err() {
+            VmError::CacheErr { msg, .. } => {
+                assert_eq!(msg, "Invalid checksum in pin file at line 2: foobar")
+            }
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+}
@@ -0,0 +1,475 @@
+use clru::{CLruCache, CLruCacheConfig, WeightScale};
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use cosmwasm_std::{Checksum, ContractResult, QueryResponse};
+
+use crate::instance::GasReport;
+use crate::size::Size;
+
+/// The result of [`Cache::query_memoized`](crate::Cache::query_memoized).
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct MemoizedQuery {
+    /// The result returned by the contract
+    pub result: ContractResult<QueryResponse>,
+    /// The gas report of the call. For results served from the query cache, this is the report
+    /// of the call that computed the result, such that the host can charge the same amount of gas.
+    pub gas_report: GasReport,
+    /// True if the result was served from the query cache, i.e. the contract was not executed
+    pub from_cache: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct QueryKey {
+    checksum: Checksum,
+    contract: String,
+    storage_version: u64,
+    /// The serialized env, such that results depending on block data are not
+    /// shared between blocks.
+    env: Vec<u8>,
+    msg: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedQuery {
+    result: ContractResult<QueryResponse>,
+    gas_report: GasReport,
+}
+
+/// The results of a single contract in the cache
+#[derive(Debug, Default)]
+struct ContractResults {
+    /// The latest storage version seen for the contract
+    latest_version: u64,
+    /// The keys of the contract's results. This may contain keys of results that were
+    /// evicted from the cache in the meantime, which are removed by [`QueryCache::prune`].
+    keys: HashSet<Arc<QueryKey>>,
+}
+
+/// The number of index entries that may refer to evicted results or contracts without
+/// results before the index is pruned
+const PRUNE_SLACK: usize = 1024;
+
+#[derive(Debug)]
+struct SizeScale;
+
+impl WeightScale<Arc<QueryKey>, CachedQuery> for SizeScale {
+    #[inline]
+    fn weight(&self, key: &Arc<QueryKey>, value: &CachedQuery) -> usize {
+        let value_size = match &value.result {
+            ContractResult::Ok(data) => data.len(),
+            ContractResult::Err(msg) => msg.len(),
+        };
+        std::mem::size_of::<QueryKey>()
+            + std::mem::size_of::<CachedQuery>()
+            + key.contract.len()
+            + key.env.len()
+            + key.msg.len()
+            + value_size
+    }
+}
+
+/// An in-memory cache of smart query results.
+///
+/// Results are keyed by the contract's checksum and address, a storage version provided
+/// by the host, the env and the query message. Once a newer storage version of a contract
+/// is seen, all results of that contract are removed.
+pub struct QueryCache {
+    results: Option<CLruCache<Arc<QueryKey>, CachedQuery, RandomState, SizeScale>>,
+    /// The results stored per contract. This allows removing the results of a contract
+    /// without scanning the whole cache.
+    ///
+    /// Contracts without results are removed when pruning, so the size of the index
+    /// is bounded by the number of results.
+    contracts: HashMap<String, ContractResults>,
+    /// The total number of keys in `contracts`
+    indexed_keys: usize,
+}
+
+impl QueryCache {
+    /// Creates a new cache with the given size (in bytes).
+    /// A size of 0 disables the cache.
+    pub fn new(size: Size) -> Self {
+        QueryCache {
+            results: NonZeroUsize::new(size.0).map(|size| {
+                CLruCache::with_config(CLruCacheConfig::new(size).with_scale(SizeScale))
+            }),
+            contracts: HashMap::new(),
+            indexed_keys: 0,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.results.is_some()
+    }
+
+    /// Returns the result and the gas report of the call that computed it, if cached
+    pub fn load(
+        &mut self,
+        checksum: &Checksum,
+        contract: &str,
+        storage_version: u64,
+        env: &[u8],
+        msg: &[u8],
+    ) -> Option<(ContractResult<QueryResponse>, GasReport)> {
+        self.observe_version(contract, storage_version);
+        let results = self.results.as_mut()?;
+        let key = QueryKey {
+            checksum: *checksum,
+            contract: contract.to_string(),
+            storage_version,
+            env: env.to_vec(),
+            msg: msg.to_vec(),
+        };
+        results
+            .get(&key)
+            .map(|cached| (cached.result.clone(), cached.gas_report))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn store(
+        &mut self,
+        checksum: &Checksum,
+        contract: &str,
+        storage_version: u64,
+        env: &[u8],
+        msg: &[u8],
+        result: ContractResult<QueryResponse>,
+        gas_report: GasReport,
+    ) {
+        self.observe_version(contract, storage_version);
+        let Some(results) = &mut self.results else {
+            return;
+        };
+        let Some(entry) = self.contracts.get_mut(contract) else {
+            return;
+        };
+        if entry.latest_version != storage_version {
+            // The result was computed on outdated state
+            return;
+        }
+
+        let key = Arc::new(QueryKey {
+            checksum: *checksum,
+            contract: contract.to_string(),
+            storage_version,
+            env: env.to_vec(),
+            msg: msg.to_vec(),
+        });
+        let value = CachedQuery { result, gas_report };
+        // Results larger than the cache are not stored
+        if results.put_with_weight(Arc::clone(&key), value).is_ok() && entry.keys.insert(key) {
+            self.indexed_keys += 1;
+        }
+        self.prune_if_needed();
+    }
+
+    /// Removes all results of the given contract.
+    pub fn invalidate(&mut self, contract: &str) {
+        if let Some(entry) = self.contracts.remove(contract) {
+            self.remove_results(entry.keys);
+        }
+    }
+
+    /// Returns the number of elements in the cache.
+    pub fn len(&self) -> usize {
+        self.results
+            .as_ref()
+            .map(|results| results.len())
+            .unwrap_or_default()
+    }
+
+    /// Returns cumulative size of all elements in the cache.
+    pub fn size(&self) -> usize {
+        self.results
+            .as_ref()
+            .map(|results| results.weight())
+            .unwrap_or_default()
+    }
+
+    fn observe_version(&mut self, contract: &str, storage_version: u64) {
+        if !self.is_enabled() {
+            return;
+        }
+        match self.contracts.get_mut(contract) {
+            Some(entry) if entry.latest_version >= storage_version => {}
+            Some(entry) => {
+                entry.latest_version = storage_version;
+                let keys = std::mem::take(&mut entry.keys);
+                self.remove_results(keys);
+            }
+            None => {
+                self.contracts.insert(
+                    contract.to_string(),
+                    ContractResults {
+                        latest_version: storage_version,
+                        keys: HashSet::new(),
+                    },
+                );
+                self.prune_if_needed();
+            }
+        }
+    }
+
+    fn remove_results(&mut self, keys: HashSet<Arc<QueryKey>>) {
+        self.indexed_keys -= keys.len();
+        if let Some(results) = &mut self.results {
+            for key in keys {
+                results.pop(&key);
+            }
+        }
+    }
+
+    /// Prunes the index once it holds considerably more entries than the cache
+    fn prune_if_needed(&mut self) {
+        let len = self.len();
+        if self.indexed_keys > len.saturating_mul(2).saturating_add(PRUNE_SLACK)
+            || self.contracts.len() > len.saturating_add(PRUNE_SLACK)
+        {
+            self.prune();
+        }
+    }
+
+    /// Removes the keys of evicted results and contracts without results from the index.
+    ///
+    /// Forgetting the latest version of a contract without results is fine, since the
+    /// version is part of the key, such that outdated results are never returned for
+    /// newer versions.
+    fn prune(&mut self) {
+        let Some(results) = &self.results else {
+            return;
+        };
+        self.contracts.retain(|_, entry| {
+            entry.keys.retain(|key| results.peek(key).is_some());
+            !entry.keys.is_empty()
+        });
+        self.indexed_keys = self.contracts.values().map(|entry| entry.keys.len()).sum();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::Binary;
+
+    fn checksum() -> Checksum {
+        Checksum::from([7; 32])
+    }
+
+    fn ok(data: &[u8]) -> ContractResult<QueryResponse> {
+        ContractResult::Ok(Binary::from(data))
+    }
+
+    fn gas() -> GasReport {
+        GasReport {
+            limit: 1000,
+            remaining: 400,
+            used_externally: 100,
+            used_internally: 500,
+        }
+    }
+
+    /// Loads a result, ignoring the gas report
+    fn load(
+        cache: &mut QueryCache,
+        contract: &str,
+        storage_version: u64,
+        msg: &[u8],
+    ) -> Option<ContractResult<QueryResponse>> {
+        cache
+            .load(&checksum(), contract, storage_version, b"env", msg)
+            .map(|(result, _)| result)
+    }
+
+    #[test]
+    fn store_and_load_works() {
+        let mut cache = QueryCache::new(Size::kibi(1));
+        assert_eq!(load(&mut cache, "contract", 1, b"msg"), None);
+
+        cache.store(
+            &checksum(),
+            "contract",
+            1,
+            b"env",
+            b"msg",
+            ok(b"result"),
+            gas(),
+        );
+        assert_eq!(load(&mut cache, "contract", 1, b"msg"), Some(ok(b"result")));
+        assert_eq!(cache.len(), 1);
+
+        // every part of the key matters
+        assert_eq!(load(&mut cache, "other", 1, b"msg"), None);
+        assert!(cache
+            .load(&checksum(), "contract", 1, b"env2", b"msg")
+            .is_none());
+        assert_eq!(load(&mut cache, "contract", 1, b"msg2"), None);
+        let other_checksum = Checksum::from([8; 32]);
+        assert!(cache
+            .load(&other_checksum, "contract", 1, b"env", b"msg")
+            .is_none());
+
+        // errors are cached as well
+        let err = ContractResult::Err("not found".to_string());
+        cache.store(
+            &checksum(),
+            "contract",
+            1,
+            b"env",
+            b"other",
+            err.clone(),
+            gas(),
+        );
+        assert_eq!(load(&mut cache, "contract", 1, b"other"), Some(err));
+    }
+
+    #[test]
+    fn newer_storage_version_invalidates_contract() {
+        let mut cache = QueryCache::new(Size::kibi(1));
+        cache.store(
+            &checksum(),
+            "contract",
+            1,
+            b"env",
+            b"msg",
+            ok(b"result"),
+            gas(),
+        );
+        cache.store(
+            &checksum(),
+            "other",
+            1,
+            b"env",
+            b"msg",
+            ok(b"result"),
+            gas(),
+        );
+        assert_eq!(cache.len(), 2);
+
+        assert_eq!(load(&mut cache, "contract", 2, b"msg"), None);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(load(&mut cache, "other", 1, b"msg"), Some(ok(b"result")));
+
+        // results computed on outdated state are not stored
+        cache.store(
+            &checksum(),
+            "contract",
+            1,
+            b"env",
+            b"msg",
+            ok(b"result"),
+            gas(),
+        );
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn invalidate_works() {
+        let mut cache = QueryCache::new(Size::kibi(1));
+        cache.store(
+            &checksum(),
+            "contract",
+            1,
+            b"env",
+            b"msg",
+            ok(b"result"),
+            gas(),
+        );
+        cache.store(
+            &checksum(),
+            "other",
+            1,
+            b"env",
+            b"msg",
+            ok(b"result"),
+            gas(),
+        );
+
+        cache.invalidate("contract");
+        assert_eq!(load(&mut cache, "contract", 1, b"msg"), None);
+        assert_eq!(load(&mut cache, "other", 1, b"msg"), Some(ok(b"result")));
+    }
+
+    #[test]
+    fn disabled_cache_stores_nothing() {
+        let mut cache = QueryCache::new(Size(0));
+        assert!(!cache.is_enabled());
+        cache.store(
+            &checksum(),
+            "contract",
+            1,
+            b"env",
+            b"msg",
+            ok(b"result"),
+            gas(),
+        );
+        assert_eq!(load(&mut cache, "contract", 1, b"msg"), None);
+        assert_eq!(cache.len(), 0);
+        assert_eq!(cache.size(), 0);
+    }
+
+    #[test]
+    fn load_returns_gas_report_of_stored_result() {
+        let mut cache = QueryCache::new(Size::kibi(1));
+        cache.store(
+            &checksum(),
+            "contract",
+            1,
+            b"env",
+            b"msg",
+            ok(b"result"),
+            gas(),
+        );
+        let (result, gas_report) = cache
+            .load(&checksum(), "contract", 1, b"env", b"msg")
+            .unwrap();
+        assert_eq!(result, ok(b"result"));
+        assert_eq!(gas_report.limit, 1000);
+        assert_eq!(gas_report.remaining, 400);
+        assert_eq!(gas_report.used_externally, 100);
+        assert_eq!(gas_report.used_internally, 500);
+    }
+
+    #[test]
+    fn index_is_pruned() {
+        let mut cache = QueryCache::new(Size::kibi(1));
+
+        // loading results of many contracts does not grow the index unboundedly
+        for i in 0..10 * PRUNE_SLACK {
+            load(&mut cache, &format!("contract{i}"), 1, b"msg");
+        }
+        assert!(cache.contracts.len() <= PRUNE_SLACK + 1);
+
+        // keys of evicted results are removed from the index
+        for i in 0..10 * PRUNE_SLACK {
+            let msg = format!("msg{i}");
+            cache.store(
+                &checksum(),
+                "contract",
+                1,
+                b"env",
+                msg.as_bytes(),
+                ok(b"result"),
+                gas(),
+            );
+        }
+        assert!(cache.len() < 10);
+        assert!(cache.indexed_keys <= 2 * cache.len() + PRUNE_SLACK + 1);
+        assert_eq!(
+            cache.indexed_keys,
+            cache
+                .contracts
+                .values()
+                .map(|entry| entry.keys.len())
+                .sum::<usize>()
+        );
+
+        // invalidation still removes all results of the contract
+        cache.invalidate("contract");
+        assert_eq!(cache.len(), 0);
+        assert_eq!(load(&mut cache, "contract", 1, b"msg9"), None);
+    }
+}
@@ -0,0 +1,319 @@
+use cosmwasm_std::ContractResult;
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+
+use crate::errors::{VmError, VmResult};
+
+/// A kibi (kilo binary)
+const KI: usize = 1024;
+/// A mibi (mega binary)
+const MI: usize = 1024 * KI;
+
+/// Limits for the results of contract calls, checked by the VM after every call.
+///
+/// Those limits apply to the typed `call_*` functions as well as the `call_*_raw` functions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ResponseLimits {
+    /// Max length (in bytes) of the JSON result of an instantiate, execute, migrate, sudo,
+    /// reply or IBC call.
+    pub max_response_length: usize,
+    /// Max length (in bytes) of the JSON result of a query call.
+    pub max_query_result_length: usize,
+    /// Max number of submessages in a response.
+    pub max_messages: usize,
+    /// Max number of events in a response.
+    pub max_events: usize,
+    /// Max number of attributes in a response, including the attributes of all events.
+    pub max_attributes: usize,
+}
+
+/// The parts of a `Response`, `IbcBasicResponse` or `IbcReceiveResponse` that are limited
+/// by the counts of [`ResponseLimits`]. All other fields are ignored.
+#[derive(Deserialize)]
+struct ResponseCounts {
+    #[serde(default)]
+    messages: Vec<IgnoredAny>,
+    #[serde(default)]
+    attributes: Vec<IgnoredAny>,
+    #[serde(default)]
+    events: Vec<EventCounts>,
+}
+
+#[derive(Deserialize)]
+struct EventCounts {
+    #[serde(default)]
+    attributes: Vec<IgnoredAny>,
+}
+
+impl ResponseLimits {
+    /// The default limits. The lengths are limited to 256 KiB, the counts are not limited.
+    pub const DEFAULT: ResponseLimits = ResponseLimits {
+        max_response_length: 256 * KI,
+        max_query_result_length: 256 * KI,
+        max_messages: usize::MAX,
+        max_events: usize::MAX,
+        max_attributes: usize::MAX,
+    };
+
+    /// The highest length limit that can be configured. This is the maximum length of
+    /// a result that is written in chunks.
+    pub const MAX_LENGTH: usize = 512 * MI;
+
+    /// Checks that the limits can be fulfilled, i.e. the lengths are not 0 and do not
+    /// exceed [`ResponseLimits::MAX_LENGTH`].
+    pub fn validate(&self) -> VmResult<()> {
+        for (name, length) in [
+            ("max_response_length", self.max_response_length),
+            ("max_query_result_length", self.max_query_result_length),
+        ] {
+            if length == 0 || length > Self::MAX_LENGTH {
+                return Err(VmError::generic_err(format!(
+                    "Invalid response limits: {name} must be between 1 and {}, got {length}",
+                    Self::MAX_LENGTH
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the JSON result of a call that returns a `Response`, `IbcBasicResponse`
+    /// or `IbcReceiveResponse`.
+    pub(crate) fn check_response_result(&self, data: &[u8]) -> VmResult<()> {
+        self.check_result_length(data)?;
+        if self.max_messages == usize::MAX
+            && self.max_events == usize::MAX
+            && self.max_attributes == usize::MAX
+        {
+            // avoid parsing the result if the counts are not limited
+            return Ok(());
+        }
+        // Errors and invalid results are not limited. The latter fail when the caller
+        // deserializes them.
+        match serde_json::from_slice::<ContractResult<ResponseCounts>>(data) {
+            Ok(ContractResult::Ok(counts)) => self.check_counts(&counts),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks the length of the JSON result of a call that does not return a query result.
+    pub(crate) fn check_result_length(&self, data: &[u8]) -> VmResult<()> {
+        Self::check_length(data, self.max_response_length)
+    }
+
+    /// Checks the length of the JSON result of a query call.
+    pub(crate) fn check_query_result(&self, data: &[u8]) -> VmResult<()> {
+        Self::check_length(data, self.max_query_result_length)
+    }
+
+    fn check_length(data: &[u8], max_length: usize) -> VmResult<()> {
+        if data.len() > max_length {
+            return Err(VmError::response_limit_exceeded(
+                "bytes",
+                data.len(),
+                max_length,
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_counts(&self, counts: &ResponseCounts) -> VmResult<()> {
+        let messages = counts.messages.len();
+        if messages > self.max_messages {
+            return Err(VmError::response_limit_exceeded(
+                "messages",
+                messages,
+                self.max_messages,
+            ));
+        }
+        let events = counts.events.len();
+        if events > self.max_events {
+            return Err(VmError::response_limit_exceeded(
+                "events",
+                events,
+                self.max_events,
+            ));
+        }
+        let attributes = counts.attributes.len()
+            + counts
+                .events
+                .iter()
+                .map(|event| event.attributes.len())
+                .sum::<usize>();
+        if attributes > self.max_attributes {
+            return Err(VmError::response_limit_exceeded(
+                "attributes",
+                attributes,
+                self.max_attributes,
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for ResponseLimits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{to_json_vec, BankMsg, Empty, Event, Response};
+    #[cfg(feature = "stargate")]
+    use cosmwasm_std::{IbcBasicResponse, IbcReceiveResponse};
+
+    fn limits() -> ResponseLimits {
+        ResponseLimits {
+            max_messages: 2,
+            max_events: 1,
+            max_attributes: 3,
+            ..ResponseLimits::DEFAULT
+        }
+    }
+
+    fn send() -> BankMsg {
+        BankMsg::Burn { amount: vec![] }
+    }
+
+    fn result<T: serde::Serialize>(response: T) -> Vec<u8> {
+        to_json_vec(&ContractResult::Ok(response)).unwrap()
+    }
+
+    #[test]
+    fn validate_works() {
+        ResponseLimits::DEFAULT.validate().unwrap();
+        limits().validate().unwrap();
+        ResponseLimits {
+            max_response_length: ResponseLimits::MAX_LENGTH,
+            max_messages: 0,
+            ..ResponseLimits::DEFAULT
+        }
+        .validate()
+        .unwrap();
+
+        let err = ResponseLimits {
+            max_response_length: 0,
+            ..ResponseLimits::DEFAULT
+        }
+        .validate()
+        .unwrap_err();
+        assert!(err.to_string().contains("max_response_length"));
+        let err = ResponseLimits {
+            max_query_result_length: ResponseLimits::MAX_LENGTH + 1,
+            ..ResponseLimits::DEFAULT
+        }
+        .validate()
+        .unwrap_err();
+        assert!(err.to_string().contains("max_query_result_length"));
+    }
+
+    #[test]
+    fn check_response_result_works() {
+        let response = Response::<Empty>::new()
+            .add_message(send())
+            .add_message(send())
+            .add_attribute("a", "1")
+            .add_event(
+                Event::new("e")
+                    .add_attribute("b", "2")
+                    .add_attribute("c", "3"),
+            );
+        limits().check_response_result(&result(&response)).unwrap();
+        ResponseLimits::DEFAULT
+            .check_response_result(&result(&response))
+            .unwrap();
+
+        // errors are not limited
+        let error = to_json_vec(&ContractResult::<Response>::Err("foo".to_string())).unwrap();
+        limits().check_response_result(&error).unwrap();
+    }
+
+    #[test]
+    fn check_response_result_fails_for_too_many_messages() {
+        let response = Response::<Empty>::new()
+            .add_message(send())
+            .add_message(send())
+            .add_message(send());
+        let err = limits()
+            .check_response_result(&result(response))
+            .unwrap_err();
+        match err {
+            VmError::ResponseLimitExceeded {
+                kind, count, limit, ..
+            } => {
+                assert_eq!(kind, "messages");
+                assert_eq!(count, 3);
+                assert_eq!(limit, 2);
+            }
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn check_response_result_fails_for_too_many_events() {
+        let response = Response::<Empty>::new().add_events([Event::new("a"), Event::new("b")]);
+        let err = limits()
+            .check_response_result(&result(response))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            VmError::ResponseLimitExceeded { kind, count: 2, limit: 1, .. } if kind == "events"
+        ));
+    }
+
+    #[test]
+    fn check_response_result_counts_event_attributes() {
+        let response = Response::<Empty>::new()
+            .add_attributes([("a", "1"), ("b", "2")])
+            .add_event(Event::new("e").add_attributes([("c", "3"), ("d", "4")]));
+        let err = limits()
+            .check_response_result(&result(response))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            VmError::ResponseLimitExceeded { kind, count: 4, limit: 3, .. } if kind == "attributes"
+        ));
+    }
+
+    #[test]
+    fn check_result_length_works() {
+        let limits = ResponseLimits {
+            max_response_length: 10,
+            max_query_result_length: 5,
+            ..ResponseLimits::DEFAULT
+        };
+        limits.check_result_length(b"0123456789").unwrap();
+        let err = limits.check_result_length(b"0123456789a").unwrap_err();
+        assert!(matches!(
+            err,
+            VmError::ResponseLimitExceeded { kind, count: 11, limit: 10, .. } if kind == "bytes"
+        ));
+        limits.check_response_result(b"0123456789a").unwrap_err();
+
+        limits.check_query_result(b"01234").unwrap();
+        limits.check_query_result(b"012345").unwrap_err();
+    }
+
+    #[cfg(feature = "stargate")]
+    #[test]
+    fn check_ibc_response_results_works() {
+        let response = IbcBasicResponse::<Empty>::new()
+            .add_message(send())
+            .add_message(send())
+            .add_message(send());
+        limits()
+            .check_response_result(&result(response))
+            .unwrap_err();
+
+        let response = IbcReceiveResponse::<Empty>::new(b"ack").add_attributes([
+            ("a", "1"),
+            ("b", "2"),
+            ("c", "3"),
+            ("d", "4"),
+        ]);
+        limits()
+            .check_response_result(&result(response))
+            .unwrap_err();
+    }
+}
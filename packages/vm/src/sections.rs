@@ -1,5 +1,5 @@
 use crate::conversion::to_u32;
-use crate::errors::VmResult;
+use crate::errors::{CommunicationError, CommunicationResult, VmResult};
 
 /// Decodes sections of data into multiple slices.
 ///
@@ -24,6 +24,32 @@ pub fn decode_sections(data: &[u8]) -> Vec<&[u8]> {
     result
 }
 
+/// Decodes sections of data like [`decode_sections`], but fails instead of panicking
+/// if the data is malformed. Use this for data provided by the contract.
+pub fn decode_sections_checked(data: &[u8]) -> CommunicationResult<Vec<&[u8]>> {
+    let mut result: Vec<&[u8]> = vec![];
+    let mut remaining = data;
+    while !remaining.is_empty() {
+        let Some(len_start) = remaining.len().checked_sub(4) else {
+            return Err(CommunicationError::invalid_sections(
+                "Cannot read section length",
+            ));
+        };
+        let (rest, len_bytes) = remaining.split_at(len_start);
+        let section_len = u32::from_be_bytes(len_bytes.try_into().unwrap()) as usize;
+        let Some(section_start) = rest.len().checked_sub(section_len) else {
+            return Err(CommunicationError::invalid_sections(
+                "Section length exceeds data",
+            ));
+        };
+        let (rest, section) = rest.split_at(section_start);
+        result.push(section);
+        remaining = rest;
+    }
+    result.reverse();
+    Ok(result)
+}
+
 /// Encodes multiple sections of data into one vector.
 ///
 /// Each section is suffixed by a section length encoded as big endian uint32.
@@ -55,6 +81,33 @@ pub fn encode_sections(sections: &[Vec<u8>]) -> VmResult<Vec<u8>> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn decode_sections_checked_works() {
+        let data = b"\xAA\0\0\0\x01\xDE\xDE\0\0\0\x02\0\0\0\0";
+        assert_eq!(
+            decode_sections_checked(data).unwrap(),
+            decode_sections(data)
+        );
+        assert_eq!(decode_sections_checked(&[]).unwrap(), Vec::<&[u8]>::new());
+        assert_eq!(
+            decode_sections_checked(b"\0\0\0\0").unwrap(),
+            [&[] as &[u8]]
+        );
+    }
+
+    #[test]
+    fn decode_sections_checked_fails_for_malformed_data() {
+        for data in [
+            b"\0\0\x01" as &[u8],
+            b"\xAA\0\0\0\x02",
+            b"\xAA\0\0\0\x01\0\0\0",
+            b"\xFF\xFF\xFF\xFF",
+        ] {
+            let err = decode_sections_checked(data).unwrap_err();
+            assert!(matches!(err, CommunicationError::InvalidSections { .. }));
+        }
+    }
+
     #[test]
     fn decode_sections_works_for_empty_sections() {
         let dec = decode_sections(&[]);
@@ -21,7 +21,121 @@ where
             deserialization_limit,
         ));
     }
-    serde_json::from_slice(value).map_err(|e| VmError::parse_err(type_name::<T>(), e))
+    serde_json::from_slice(value).map_err(|e| {
+        let offset = error_offset(value, e.line(), e.column());
+        VmError::parse_err(
+            type_name::<T>(),
+            e,
+            json_path(&value[..offset]),
+            offset,
+            snippet(value, offset),
+        )
+    })
+}
+
+/// Max number of bytes shown on each side of the error location in a parse error.
+const SNIPPET_CONTEXT: usize = 32;
+
+/// Converts the 1-based line and column reported by serde_json into a byte offset.
+/// The column counts the bytes consumed in the line, so the offset points right
+/// after the last byte the parser looked at.
+fn error_offset(value: &[u8], line: usize, column: usize) -> usize {
+    if line == 0 {
+        return 0;
+    }
+    let line_start = value
+        .split_inclusive(|&b| b == b'\n')
+        .take(line - 1)
+        .map(|l| l.len())
+        .sum::<usize>();
+    (line_start + column).min(value.len())
+}
+
+enum PathSegment {
+    Object { key: Option<String>, in_key: bool },
+    Array { index: usize },
+}
+
+/// Returns the path of the field that was being parsed at the end of the given
+/// JSON prefix, e.g. `ok.messages[0].msg`. The root is represented as `.`.
+fn json_path(prefix: &[u8]) -> String {
+    let mut stack: Vec<PathSegment> = Vec::new();
+    let mut iter = prefix.iter().copied();
+    while let Some(byte) = iter.next() {
+        match byte {
+            b'"' => {
+                let mut string = Vec::new();
+                while let Some(b) = iter.next() {
+                    match b {
+                        b'\\' => {
+                            iter.next();
+                        }
+                        b'"' => break,
+                        _ => string.push(b),
+                    }
+                }
+                if let Some(PathSegment::Object { key, in_key: true }) = stack.last_mut() {
+                    *key = Some(String::from_utf8_lossy(&string).into_owned());
+                }
+            }
+            b'{' => stack.push(PathSegment::Object {
+                key: None,
+                in_key: true,
+            }),
+            b'[' => stack.push(PathSegment::Array { index: 0 }),
+            b'}' | b']' => {
+                stack.pop();
+            }
+            b':' => {
+                if let Some(PathSegment::Object { in_key, .. }) = stack.last_mut() {
+                    *in_key = false;
+                }
+            }
+            b',' => match stack.last_mut() {
+                Some(PathSegment::Object { key, in_key }) => {
+                    *key = None;
+                    *in_key = true;
+                }
+                Some(PathSegment::Array { index }) => *index += 1,
+                None => {}
+            },
+            _ => {}
+        }
+    }
+
+    let mut path = String::new();
+    for segment in stack {
+        match segment {
+            PathSegment::Object { key: Some(key), .. } => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(&key);
+            }
+            PathSegment::Object { key: None, .. } => {}
+            PathSegment::Array { index } => path.push_str(&format!("[{index}]")),
+        }
+    }
+    if path.is_empty() {
+        path.push('.');
+    }
+    path
+}
+
+/// Returns an excerpt of the input around the given offset, truncated to
+/// [`SNIPPET_CONTEXT`] bytes on each side.
+fn snippet(value: &[u8], offset: usize) -> String {
+    let start = offset.saturating_sub(SNIPPET_CONTEXT);
+    let end = (offset + SNIPPET_CONTEXT).min(value.len());
+    let mut out = String::new();
+    if start > 0 {
+        out.push_str("...");
+    }
+    out.push_str(&String::from_utf8_lossy(&value[start..end]));
+    if end < value.len() {
+        out.push_str("...");
+    }
+    out
 }
 
 pub fn to_vec<T>(data: &T) -> VmResult<Vec<u8>>
@@ -99,6 +213,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn from_slice_reports_error_location() {
+        #[derive(Deserialize, Debug)]
+        struct Outer {
+            #[allow(dead_code)]
+            items: Vec<SomeMsg>,
+        }
+
+        let data = br#"{"items":[{"refund":{}},{"cowsay":{"text":17}}]}"#;
+        match from_slice::<Outer>(data, LIMIT).unwrap_err() {
+            VmError::ParseErr {
+                path,
+                offset,
+                snippet,
+                ..
+            } => {
+                assert_eq!(path, "items[1].cowsay.text");
+                assert_eq!(offset, 44);
+                assert_eq!(snippet, r#"...refund":{}},{"cowsay":{"text":17}}]}"#);
+            }
+            err => panic!("Unexpected error: {err:?}"),
+        }
+
+        // missing field is reported at the end of the object
+        match from_slice::<SomeMsg>(br#"{"cowsay":{}}"#, LIMIT).unwrap_err() {
+            VmError::ParseErr { msg, path, .. } => {
+                assert!(msg.starts_with("missing field `text`"), "{msg}");
+                assert_eq!(path, "cowsay");
+            }
+            err => panic!("Unexpected error: {err:?}"),
+        }
+
+        // invalid JSON at the root
+        match from_slice::<SomeMsg>(b"nope", LIMIT).unwrap_err() {
+            VmError::ParseErr { path, offset, .. } => {
+                assert_eq!(path, ".");
+                assert_eq!(offset, 1);
+            }
+            err => panic!("Unexpected error: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn snippet_truncates_long_input() {
+        let data = [b'a'; 100];
+        let out = snippet(&data, 50);
+        assert_eq!(out, format!("...{}...", "a".repeat(64)));
+        assert_eq!(snippet(b"abc", 1), "abc");
+    }
+
     #[test]
     fn to_vec_works() {
         let msg = SomeMsg::Refund {};
@@ -0,0 +1,476 @@
+use std::collections::BTreeMap;
+#[cfg(feature = "iterator")]
+use std::collections::HashMap;
+
+use cosmwasm_std::{ContractResult, Response};
+#[cfg(feature = "iterator")]
+use cosmwasm_std::{Order, Record};
+
+#[cfg(feature = "iterator")]
+use crate::backend::BackendError;
+use crate::backend::{BackendResult, GasInfo, Storage, StorageSize};
+use crate::instance::GasReport;
+
+/// The changes a call would have applied to the storage, sorted by key.
+/// A value of `None` means the key was removed.
+pub type StateDiff = BTreeMap<Vec<u8>, Option<Vec<u8>>>;
+
+/// The outcome of a call executed by [`Cache::simulate_execute`](crate::Cache::simulate_execute).
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Simulation<U> {
+    /// The result returned by the contract
+    pub result: ContractResult<Response<U>>,
+    /// The writes that would have been committed to the storage
+    pub state_diff: StateDiff,
+    pub gas_report: GasReport,
+}
+
+#[cfg(feature = "iterator")]
+#[derive(Debug)]
+struct Iter {
+    /// The ID of the iterator in the underlying storage
+    inner_id: u32,
+    /// The next record of the underlying iterator that was not returned yet
+    peeked: Option<Record>,
+    inner_done: bool,
+    /// The changes in the range of the iterator, sorted in iteration order
+    changes: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+    position: usize,
+    descending: bool,
+}
+
+/// The gas charged by a [`StorageOverlay`] for writes, since those never reach the
+/// underlying storage. The costs are reported as externally used gas, like the gas
+/// reported by a storage backend.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct WriteGasCosts {
+    /// The flat cost of writing a value
+    pub write_flat: u64,
+    /// The cost per byte of key and value when writing a value
+    pub write_per_byte: u64,
+    /// The cost of removing a value
+    pub delete: u64,
+}
+
+impl Default for WriteGasCosts {
+    /// The write costs of a Cosmos SDK KV store (2000 + 30 per byte for writes, 1000 for deletes)
+    /// in CosmWasm gas, using the gas multiplier of wasmd (see GAS.md)
+    fn default() -> Self {
+        const GAS_MULTIPLIER: u64 = 140_000;
+        Self {
+            write_flat: 2000 * GAS_MULTIPLIER,
+            write_per_byte: 30 * GAS_MULTIPLIER,
+            delete: 1000 * GAS_MULTIPLIER,
+        }
+    }
+}
+
+/// A copy-on-write view of a storage.
+///
+/// Reads go to the underlying storage, such that they are charged like in a regular call,
+/// and are then patched with the changes written so far. Writes are only recorded in
+/// the overlay and never reach the underlying storage. They are charged according to
+/// the overlay's [`WriteGasCosts`] instead, which should match the underlying storage.
+#[derive(Debug)]
+pub struct StorageOverlay<S: Storage> {
+    inner: S,
+    changes: StateDiff,
+    write_costs: WriteGasCosts,
+    #[cfg(feature = "iterator")]
+    iterators: HashMap<u32, Iter>,
+    /// The ID of the last iterator created. IDs are not reused after freeing an iterator.
+    #[cfg(feature = "iterator")]
+    last_iterator_id: u32,
+}
+
+impl<S: Storage> StorageOverlay<S> {
+    pub fn new(inner: S) -> Self {
+        StorageOverlay {
+            inner,
+            changes: StateDiff::new(),
+            write_costs: WriteGasCosts::default(),
+            #[cfg(feature = "iterator")]
+            iterators: HashMap::new(),
+            #[cfg(feature = "iterator")]
+            last_iterator_id: 0,
+        }
+    }
+
+    /// Sets the gas charged for writes. Defaults to [`WriteGasCosts::default`].
+    pub fn with_write_costs(mut self, write_costs: WriteGasCosts) -> Self {
+        self.write_costs = write_costs;
+        self
+    }
+
+    /// The changes recorded so far
+    pub fn changes(&self) -> &StateDiff {
+        &self.changes
+    }
+
+    /// Returns the unmodified underlying storage and the recorded changes.
+    pub fn into_parts(self) -> (S, StateDiff) {
+        (self.inner, self.changes)
+    }
+}
+
+impl<S: Storage> Storage for StorageOverlay<S> {
+    fn get(&self, key: &[u8]) -> BackendResult<Option<Vec<u8>>> {
+        let (result, gas_info) = self.inner.get(key);
+        match self.changes.get(key) {
+            Some(change) => (result.map(|_| change.clone()), gas_info),
+            None => (result, gas_info),
+        }
+    }
+
+    #[cfg(feature = "iterator")]
+    fn scan(
+        &mut self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> BackendResult<u32> {
+        let (result, gas_info) = self.inner.scan(start, end, order);
+        let inner_id = match result {
+            Ok(id) => id,
+            Err(err) => return (Err(err), gas_info),
+        };
+
+        let mut changes: Vec<_> = self
+            .changes
+            .iter()
+            .filter(|(key, _)| {
+                let key = key.as_slice();
+                !matches!(start, Some(start) if key < start)
+                    && !matches!(end, Some(end) if key >= end)
+            })
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        if order == Order::Descending {
+            changes.reverse();
+        }
+
+        let new_id = self
+            .last_iterator_id
+            .checked_add(1)
+            .expect("Found more iterator IDs than supported");
+        self.last_iterator_id = new_id;
+        let iter = Iter {
+            inner_id,
+            peeked: None,
+            inner_done: false,
+            changes,
+            position: 0,
+            descending: order == Order::Descending,
+        };
+        self.iterators.insert(new_id, iter);
+
+        (Ok(new_id), gas_info)
+    }
+
+    #[cfg(feature = "iterator")]
+    fn next(&mut self, iterator_id: u32) -> BackendResult<Option<Record>> {
+        let iterator = match self.iterators.get_mut(&iterator_id) {
+            Some(i) => i,
+            None => {
+                return (
+                    Err(BackendError::iterator_does_not_exist(iterator_id)),
+                    GasInfo::free(),
+                )
+            }
+        };
+
+        let mut gas_info = GasInfo::free();
+        loop {
+            if iterator.peeked.is_none() && !iterator.inner_done {
+                let (result, info) = self.inner.next(iterator.inner_id);
+                gas_info += info;
+                match result {
+                    Ok(Some(record)) => iterator.peeked = Some(record),
+                    Ok(None) => iterator.inner_done = true,
+                    Err(err) => return (Err(err), gas_info),
+                }
+            }
+
+            let change_first = match (&iterator.peeked, iterator.changes.get(iterator.position)) {
+                (None, None) => return (Ok(None), gas_info),
+                (Some(_), None) => false,
+                (None, Some(_)) => true,
+                (Some((inner_key, _)), Some((change_key, _))) => {
+                    if inner_key == change_key {
+                        // The change shadows the original record
+                        iterator.peeked = None;
+                        true
+                    } else {
+                        (change_key < inner_key) != iterator.descending
+                    }
+                }
+            };
+            if !change_first {
+                return (Ok(iterator.peeked.take()), gas_info);
+            }
+
+            let (key, value) = iterator.changes[iterator.position].clone();
+            iterator.position += 1;
+            if let Some(value) = value {
+                return (Ok(Some((key, value))), gas_info);
+            }
+            // The key was removed, so continue with the next one
+        }
+    }
+
+    #[cfg(feature = "iterator")]
+    fn free_iterator(&mut self, iterator_id: u32) -> BackendResult<()> {
+        match self.iterators.remove(&iterator_id) {
+            Some(iterator) => self.inner.free_iterator(iterator.inner_id),
+            None => (
+                Err(BackendError::iterator_does_not_exist(iterator_id)),
+                GasInfo::free(),
+            ),
+        }
+    }
+
+    #[cfg(feature = "iterator")]
+    fn seek(&mut self, iterator_id: u32, key: &[u8]) -> BackendResult<()> {
+        let iterator = match self.iterators.get_mut(&iterator_id) {
+            Some(i) => i,
+            None => {
+                return (
+                    Err(BackendError::iterator_does_not_exist(iterator_id)),
+                    GasInfo::free(),
+                )
+            }
+        };
+
+        let (result, gas_info) = self.inner.seek(iterator.inner_id, key);
+        if result.is_ok() {
+            iterator.peeked = None;
+            iterator.inner_done = false;
+            // The changes are sorted in iteration order
+            iterator.position = if iterator.descending {
+                iterator
+                    .changes
+                    .partition_point(|(k, _)| k.as_slice() > key)
+            } else {
+                iterator
+                    .changes
+                    .partition_point(|(k, _)| k.as_slice() < key)
+            };
+        }
+        (result, gas_info)
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) -> BackendResult<()> {
+        let len = (key.len() + value.len()) as u64;
+        let gas = self
+            .write_costs
+            .write_flat
+            .saturating_add(self.write_costs.write_per_byte.saturating_mul(len));
+        self.changes.insert(key.to_vec(), Some(value.to_vec()));
+        (Ok(()), GasInfo::with_externally_used(gas))
+    }
+
+    fn remove(&mut self, key: &[u8]) -> BackendResult<()> {
+        self.changes.insert(key.to_vec(), None);
+        (
+            Ok(()),
+            GasInfo::with_externally_used(self.write_costs.delete),
+        )
+    }
+
+    /// Returns the size of the underlying storage, i.e. the recorded changes are not included.
+    fn size_hint(&self) -> BackendResult<Option<StorageSize>> {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockStorage;
+
+    fn storage() -> MockStorage {
+        let mut storage = MockStorage::new();
+        storage.set(b"a", b"1").0.unwrap();
+        storage.set(b"c", b"3").0.unwrap();
+        storage.set(b"e", b"5").0.unwrap();
+        storage
+    }
+
+    #[test]
+    fn get_set_remove_works() {
+        let mut overlay = StorageOverlay::new(storage());
+        overlay.set(b"b", b"2").0.unwrap();
+        overlay.set(b"c", b"33").0.unwrap();
+        overlay.remove(b"e").0.unwrap();
+
+        assert_eq!(overlay.get(b"a").0.unwrap(), Some(b"1".to_vec()));
+        assert_eq!(overlay.get(b"b").0.unwrap(), Some(b"2".to_vec()));
+        assert_eq!(overlay.get(b"c").0.unwrap(), Some(b"33".to_vec()));
+        assert_eq!(overlay.get(b"e").0.unwrap(), None);
+
+        let (inner, diff) = overlay.into_parts();
+        assert_eq!(
+            diff,
+            StateDiff::from([
+                (b"b".to_vec(), Some(b"2".to_vec())),
+                (b"c".to_vec(), Some(b"33".to_vec())),
+                (b"e".to_vec(), None),
+            ])
+        );
+        // underlying storage is untouched
+        assert_eq!(inner.get(b"b").0.unwrap(), None);
+        assert_eq!(inner.get(b"c").0.unwrap(), Some(b"3".to_vec()));
+        assert_eq!(inner.get(b"e").0.unwrap(), Some(b"5".to_vec()));
+    }
+
+    #[test]
+    fn set_remove_charge_write_costs() {
+        let costs = WriteGasCosts {
+            write_flat: 100,
+            write_per_byte: 10,
+            delete: 50,
+        };
+        let mut overlay = StorageOverlay::new(storage()).with_write_costs(costs);
+
+        // write a new key
+        let (result, gas_info) = overlay.set(b"b", b"2");
+        result.unwrap();
+        assert_eq!(gas_info, GasInfo::with_externally_used(100 + 2 * 10));
+
+        // overwrite an existing key and a key written before
+        let (result, gas_info) = overlay.set(b"c", b"333");
+        result.unwrap();
+        assert_eq!(gas_info, GasInfo::with_externally_used(100 + 4 * 10));
+        let (result, gas_info) = overlay.set(b"b", b"22");
+        result.unwrap();
+        assert_eq!(gas_info, GasInfo::with_externally_used(100 + 3 * 10));
+
+        // delete an existing key, a key written before and a missing key
+        for key in [b"a", b"b", b"x"] {
+            let (result, gas_info) = overlay.remove(key);
+            result.unwrap();
+            assert_eq!(gas_info, GasInfo::with_externally_used(50));
+        }
+
+        assert_eq!(
+            overlay.changes(),
+            &StateDiff::from([
+                (b"a".to_vec(), None),
+                (b"b".to_vec(), None),
+                (b"c".to_vec(), Some(b"333".to_vec())),
+                (b"x".to_vec(), None),
+            ])
+        );
+    }
+
+    #[test]
+    fn write_costs_default_to_cosmos_sdk_costs() {
+        let mut overlay = StorageOverlay::new(storage());
+        let (_, gas_info) = overlay.set(b"b", b"2");
+        assert_eq!(gas_info, GasInfo::with_externally_used(2060 * 140_000));
+        let (_, gas_info) = overlay.remove(b"b");
+        assert_eq!(gas_info, GasInfo::with_externally_used(1000 * 140_000));
+    }
+
+    #[test]
+    fn get_charges_underlying_storage() {
+        let mut overlay = StorageOverlay::new(storage());
+        overlay.set(b"abc", b"new").0.unwrap();
+        let (_, gas_info) = overlay.get(b"abc");
+        assert_eq!(gas_info, MockStorage::new().get(b"abc").1);
+    }
+
+    #[cfg(feature = "iterator")]
+    fn all(overlay: &mut StorageOverlay<MockStorage>, iterator_id: u32) -> Vec<Record> {
+        let mut out = Vec::new();
+        while let Some(record) = overlay.next(iterator_id).0.unwrap() {
+            out.push(record);
+        }
+        out
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn iterator_merges_changes() {
+        let mut overlay = StorageOverlay::new(storage());
+        overlay.set(b"b", b"2").0.unwrap();
+        overlay.set(b"c", b"33").0.unwrap();
+        overlay.remove(b"e").0.unwrap();
+        overlay.set(b"f", b"6").0.unwrap();
+
+        let id = overlay.scan(None, None, Order::Ascending).0.unwrap();
+        assert_eq!(
+            all(&mut overlay, id),
+            vec![
+                (b"a".to_vec(), b"1".to_vec()),
+                (b"b".to_vec(), b"2".to_vec()),
+                (b"c".to_vec(), b"33".to_vec()),
+                (b"f".to_vec(), b"6".to_vec()),
+            ]
+        );
+
+        let id = overlay.scan(None, None, Order::Descending).0.unwrap();
+        assert_eq!(
+            all(&mut overlay, id),
+            vec![
+                (b"f".to_vec(), b"6".to_vec()),
+                (b"c".to_vec(), b"33".to_vec()),
+                (b"b".to_vec(), b"2".to_vec()),
+                (b"a".to_vec(), b"1".to_vec()),
+            ]
+        );
+
+        let id = overlay
+            .scan(Some(b"b"), Some(b"f"), Order::Ascending)
+            .0
+            .unwrap();
+        assert_eq!(
+            all(&mut overlay, id),
+            vec![
+                (b"b".to_vec(), b"2".to_vec()),
+                (b"c".to_vec(), b"33".to_vec()),
+            ]
+        );
+
+        // unknown iterator
+        let (result, _) = overlay.next(42);
+        assert!(matches!(
+            result.unwrap_err(),
+            BackendError::IteratorDoesNotExist { id: 42 }
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn seek_works() {
+        let mut overlay = StorageOverlay::new(storage());
+        overlay.set(b"b", b"2").0.unwrap();
+        overlay.remove(b"c").0.unwrap();
+
+        let id = overlay.scan(None, None, Order::Ascending).0.unwrap();
+        overlay.seek(id, b"b").0.unwrap();
+        assert_eq!(
+            all(&mut overlay, id),
+            vec![
+                (b"b".to_vec(), b"2".to_vec()),
+                (b"e".to_vec(), b"5".to_vec())
+            ]
+        );
+        overlay.seek(id, b"a").0.unwrap();
+        assert_eq!(
+            overlay.next(id).0.unwrap(),
+            Some((b"a".to_vec(), b"1".to_vec()))
+        );
+
+        let id = overlay.scan(None, None, Order::Descending).0.unwrap();
+        overlay.seek(id, b"d").0.unwrap();
+        assert_eq!(
+            all(&mut overlay, id),
+            vec![
+                (b"b".to_vec(), b"2".to_vec()),
+                (b"a".to_vec(), b"1".to_vec())
+            ]
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 
+use serde::{Deserialize, Serialize};
 use strum::{AsRefStr, Display, EnumString};
 use wasmer::wasmparser::ExternalKind;
 
@@ -7,7 +8,10 @@ use crate::parsed_wasm::ParsedWasm;
 
 /// An enum containing all available contract entrypoints.
 /// This also provides conversions to and from strings.
-#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash, EnumString, Display, AsRefStr)]
+#[derive(
+    PartialEq, Eq, Debug, Clone, Copy, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
 pub enum Entrypoint {
     #[strum(serialize = "instantiate")]
     Instantiate,
@@ -4,9 +4,10 @@
 use cosmwasm_std::Coin;
 use std::collections::HashSet;
 
-use crate::capabilities::capabilities_from_csv;
+use crate::capabilities::{capabilities_from_csv, Capability};
 use crate::compatibility::check_wasm;
 use crate::instance::{Instance, InstanceOptions};
+use crate::response_limits::ResponseLimits;
 use crate::size::Size;
 use crate::{Backend, BackendApi, Querier, Storage};
 
@@ -86,7 +87,7 @@ pub struct MockInstanceOptions<'a> {
     pub backend_error: Option<&'static str>,
 
     // instance
-    pub available_capabilities: HashSet<String>,
+    pub available_capabilities: HashSet<Capability>,
     /// Gas limit measured in [CosmWasm gas](https://github.com/CosmWasm/cosmwasm/blob/main/docs/GAS.md).
     pub gas_limit: u64,
     /// Memory limit in bytes. Use a value that is divisible by the Wasm page size 65536, e.g. full MiBs.
@@ -94,13 +95,13 @@ pub struct MockInstanceOptions<'a> {
 }
 
 impl MockInstanceOptions<'_> {
-    fn default_capabilities() -> HashSet<String> {
+    fn default_capabilities() -> HashSet<Capability> {
         #[allow(unused_mut)]
         let mut out = capabilities_from_csv(
-            "iterator,staking,cosmwasm_1_1,cosmwasm_1_2,cosmwasm_1_3,cosmwasm_1_4",
+            "iterator,staking,cosmwasm_1_1,cosmwasm_1_2,cosmwasm_1_3,cosmwasm_1_4,query_chain_binary,query_batch,chunked_results,db_size,iterator_seek,ed25519_variants,gas_remaining,emit_event,chain_queries,staking_pool_params,ibc_channel_filters",
         );
         #[cfg(feature = "stargate")]
-        out.insert("stargate".to_string());
+        out.insert(Capability::from("stargate"));
         out
     }
 }
@@ -152,6 +153,9 @@ pub fn mock_instance_with_options(
     let memory_limit = options.memory_limit;
     let options = InstanceOptions {
         gas_limit: options.gas_limit,
+        trace: false,
+        response_limits: ResponseLimits::DEFAULT,
+        instrumentation: None,
     };
     Instance::from_code(wasm, backend, options, memory_limit).unwrap()
 }
@@ -161,6 +165,9 @@ pub fn mock_instance_options() -> (InstanceOptions, Option<Size>) {
     (
         InstanceOptions {
             gas_limit: DEFAULT_GAS_LIMIT,
+            trace: false,
+            response_limits: ResponseLimits::DEFAULT,
+            instrumentation: None,
         },
         DEFAULT_MEMORY_LIMIT,
     )
@@ -1,6 +1,6 @@
 use cosmwasm_std::testing::{digit_sum, riffle_shuffle};
 use cosmwasm_std::{
-    Addr, BlockInfo, Coin, ContractInfo, Env, MessageInfo, Timestamp, TransactionInfo,
+    Addr, BlockInfo, Coin, ContractInfo, Env, Event, MessageInfo, Timestamp, TransactionInfo,
 };
 
 use super::querier::MockQuerier;
@@ -155,6 +155,14 @@ impl BackendApi for MockApi {
         };
         (result, gas_info)
     }
+
+    /// Accepts all events and discards them.
+    fn emit_event(&self, _contract_address: Option<&str>, _event: &Event) -> BackendResult<()> {
+        if let Some(backend_error) = self.backend_error {
+            return (Err(BackendError::unknown(backend_error)), GasInfo::free());
+        }
+        (Ok(()), GasInfo::free())
+    }
 }
 
 /// Returns a default enviroment with height, time, chain_id, and contract address
@@ -169,9 +177,16 @@ pub fn mock_env() -> Env {
             time: Timestamp::from_nanos(1_571_797_419_879_305_533),
             chain_id: "cosmos-testnet-14002".to_string(),
         },
-        transaction: Some(TransactionInfo { index: 3 }),
+        transaction: Some(TransactionInfo {
+            index: 3,
+            hash: None,
+            msg_index: None,
+        }),
         contract: ContractInfo {
             address: Addr::unchecked(MOCK_CONTRACT_ADDR),
+            code_id: None,
+            creator: None,
+            admin: None,
         },
     }
 }
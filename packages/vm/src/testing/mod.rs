@@ -5,6 +5,7 @@ mod instance;
 mod mock;
 mod querier;
 mod storage;
+pub mod storage_conformance;
 
 pub use calls::{execute, instantiate, migrate, query, reply, sudo};
 #[cfg(feature = "stargate")]
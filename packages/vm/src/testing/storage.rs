@@ -9,7 +9,7 @@ use cosmwasm_std::{Order, Record};
 
 #[cfg(feature = "iterator")]
 use crate::BackendError;
-use crate::{BackendResult, GasInfo, Storage};
+use crate::{BackendResult, GasInfo, Storage, StorageSize};
 
 #[cfg(feature = "iterator")]
 const GAS_COST_LAST_ITERATION: u64 = 37;
@@ -22,6 +22,7 @@ const GAS_COST_RANGE: u64 = 11;
 struct Iter {
     data: Vec<Record>,
     position: usize,
+    descending: bool,
 }
 
 #[derive(Default, Debug)]
@@ -29,6 +30,9 @@ pub struct MockStorage {
     data: BTreeMap<Vec<u8>, Vec<u8>>,
     #[cfg(feature = "iterator")]
     iterators: HashMap<u32, Iter>,
+    /// The ID of the last iterator created. IDs are not reused after freeing an iterator.
+    #[cfg(feature = "iterator")]
+    last_iterator_id: u32,
 }
 
 impl MockStorage {
@@ -64,6 +68,18 @@ impl Storage for MockStorage {
         (Ok(self.data.get(key).cloned()), gas_info)
     }
 
+    fn size_hint(&self) -> BackendResult<Option<StorageSize>> {
+        let size = StorageSize {
+            keys: self.data.len() as u64,
+            bytes: self
+                .data
+                .iter()
+                .map(|(key, value)| (key.len() + value.len()) as u64)
+                .sum(),
+        };
+        (Ok(Some(size)), GasInfo::free())
+    }
+
     #[cfg(feature = "iterator")]
     fn scan(
         &mut self,
@@ -84,15 +100,15 @@ impl Storage for MockStorage {
             },
         };
 
-        let last_id: u32 = self
-            .iterators
-            .len()
-            .try_into()
+        let new_id = self
+            .last_iterator_id
+            .checked_add(1)
             .expect("Found more iterator IDs than supported");
-        let new_id = last_id + 1;
+        self.last_iterator_id = new_id;
         let iter = Iter {
             data: values,
             position: 0,
+            descending: order == Order::Descending,
         };
         self.iterators.insert(new_id, iter);
 
@@ -124,6 +140,38 @@ impl Storage for MockStorage {
         (Ok(value), gas_info)
     }
 
+    #[cfg(feature = "iterator")]
+    fn free_iterator(&mut self, iterator_id: u32) -> BackendResult<()> {
+        match self.iterators.remove(&iterator_id) {
+            Some(_) => (Ok(()), GasInfo::free()),
+            None => (
+                Err(BackendError::iterator_does_not_exist(iterator_id)),
+                GasInfo::free(),
+            ),
+        }
+    }
+
+    #[cfg(feature = "iterator")]
+    fn seek(&mut self, iterator_id: u32, key: &[u8]) -> BackendResult<()> {
+        let iterator = match self.iterators.get_mut(&iterator_id) {
+            Some(i) => i,
+            None => {
+                return (
+                    Err(BackendError::iterator_does_not_exist(iterator_id)),
+                    GasInfo::free(),
+                )
+            }
+        };
+
+        // The data is sorted in iteration order
+        iterator.position = if iterator.descending {
+            iterator.data.partition_point(|(k, _)| k.as_slice() > key)
+        } else {
+            iterator.data.partition_point(|(k, _)| k.as_slice() < key)
+        };
+        (Ok(()), GasInfo::with_externally_used(GAS_COST_RANGE))
+    }
+
     fn set(&mut self, key: &[u8], value: &[u8]) -> BackendResult<()> {
         self.data.insert(key.to_vec(), value.to_vec());
         let gas_info = GasInfo::with_externally_used((key.len() + value.len()) as u64);
@@ -339,4 +387,188 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn scan_prefix_and_seek_work() {
+        let mut store = MockStorage::new();
+        store.set(b"a", b"1").0.unwrap();
+        store.set(b"b1", b"2").0.unwrap();
+        store.set(b"b3", b"3").0.unwrap();
+        store.set(b"b5", b"4").0.unwrap();
+        store.set(b"c", b"5").0.unwrap();
+
+        let id = store.scan_prefix(b"b", Order::Ascending).0.unwrap();
+        let keys: Vec<_> = store
+            .all(id)
+            .0
+            .unwrap()
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(keys, vec![b"b1".to_vec(), b"b3".to_vec(), b"b5".to_vec()]);
+
+        // ascending seek finds the first key >= the given key
+        let id = store.scan_prefix(b"b", Order::Ascending).0.unwrap();
+        store.seek(id, b"b2").0.unwrap();
+        assert_eq!(store.next(id).0.unwrap().unwrap().0, b"b3");
+        store.seek(id, b"b5").0.unwrap();
+        assert_eq!(store.next(id).0.unwrap().unwrap().0, b"b5");
+        // never leaves the range
+        store.seek(id, b"a").0.unwrap();
+        assert_eq!(store.next(id).0.unwrap().unwrap().0, b"b1");
+        store.seek(id, b"bz").0.unwrap();
+        assert_eq!(store.next(id).0.unwrap(), None);
+
+        // descending seek finds the last key <= the given key
+        let id = store.scan_prefix(b"b", Order::Descending).0.unwrap();
+        store.seek(id, b"b4").0.unwrap();
+        assert_eq!(store.next(id).0.unwrap().unwrap().0, b"b3");
+        assert_eq!(store.next(id).0.unwrap().unwrap().0, b"b1");
+        assert_eq!(store.next(id).0.unwrap(), None);
+
+        match store.seek(42, b"b").0.unwrap_err() {
+            BackendError::IteratorDoesNotExist { id } => assert_eq!(id, 42),
+            e => panic!("Unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn size_hint_works() {
+        let mut store = MockStorage::new();
+        assert_eq!(store.size_hint().0.unwrap(), Some(StorageSize::default()));
+
+        store.set(b"foo", b"bar").0.unwrap();
+        store.set(b"ze", b"bra").0.unwrap();
+        assert_eq!(
+            store.size_hint().0.unwrap(),
+            Some(StorageSize { keys: 2, bytes: 11 })
+        );
+
+        store.remove(b"foo").0.unwrap();
+        assert_eq!(
+            store.size_hint().0.unwrap(),
+            Some(StorageSize { keys: 1, bytes: 5 })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn export_works() {
+        let mut store = MockStorage::new();
+        store.set(b"ant", b"hill").0.unwrap();
+        store.set(b"foo", b"bar").0.unwrap();
+        store.set(b"ze", b"bra").0.unwrap();
+
+        let mut exported = Vec::new();
+        let (result, gas_info) = store.export(None, None, Order::Ascending, false, &mut |record| {
+            exported.push(record)
+        });
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(
+            exported,
+            vec![
+                (b"ant".to_vec(), b"hill".to_vec()),
+                (b"foo".to_vec(), b"bar".to_vec()),
+                (b"ze".to_vec(), b"bra".to_vec()),
+            ]
+        );
+        assert_eq!(
+            gas_info,
+            GasInfo::new(7 + 6 + 5, GAS_COST_RANGE + GAS_COST_LAST_ITERATION)
+        );
+
+        // bounded and gas exempt
+        let mut exported = Vec::new();
+        let (result, gas_info) = store.export(
+            Some(b"b"),
+            Some(b"z"),
+            Order::Descending,
+            true,
+            &mut |record| exported.push(record),
+        );
+        assert_eq!(result.unwrap(), 1);
+        assert_eq!(exported, vec![(b"foo".to_vec(), b"bar".to_vec())]);
+        assert_eq!(
+            gas_info,
+            GasInfo::with_externally_used(GAS_COST_RANGE + GAS_COST_LAST_ITERATION)
+        );
+
+        // the iterators used for exporting are freed
+        assert!(store.iterators.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn free_iterator_works() {
+        let mut store = MockStorage::new();
+        store.set(b"foo", b"bar").0.unwrap();
+
+        let id1 = store.scan(None, None, Order::Ascending).0.unwrap();
+        let id2 = store.scan(None, None, Order::Ascending).0.unwrap();
+        store.free_iterator(id1).0.unwrap();
+        match store.next(id1).0.unwrap_err() {
+            BackendError::IteratorDoesNotExist { id, .. } => assert_eq!(id, id1),
+            e => panic!("Unexpected error: {e:?}"),
+        }
+        // freeing twice fails
+        store.free_iterator(id1).0.unwrap_err();
+
+        // other iterators are not affected and IDs are not reused
+        let id3 = store.scan(None, None, Order::Ascending).0.unwrap();
+        assert_ne!(id3, id2);
+        assert_eq!(
+            store.next(id2).0.unwrap(),
+            Some((b"foo".to_vec(), b"bar".to_vec()))
+        );
+        assert_eq!(
+            store.next(id3).0.unwrap(),
+            Some((b"foo".to_vec(), b"bar".to_vec()))
+        );
+    }
+
+    #[test]
+    fn import_works() {
+        let mut store = MockStorage::new();
+        store.set(b"foo", b"old").0.unwrap();
+
+        let records = vec![
+            (b"foo".to_vec(), b"bar".to_vec()),
+            (b"ze".to_vec(), b"bra".to_vec()),
+        ];
+        let (result, gas_info) = store.import(&mut records.into_iter(), false);
+        assert_eq!(result.unwrap(), 2);
+        assert_eq!(gas_info, GasInfo::with_externally_used(6 + 5));
+        assert_eq!(store.get(b"foo").0.unwrap(), Some(b"bar".to_vec()));
+        assert_eq!(store.get(b"ze").0.unwrap(), Some(b"bra".to_vec()));
+
+        // empty
+        let (result, _) = store.import(&mut std::iter::empty(), true);
+        assert_eq!(result.unwrap(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn export_import_roundtrip() {
+        let mut original = MockStorage::new();
+        for i in 0..50u8 {
+            original.set(&[i], &[i, i]).0.unwrap();
+        }
+
+        let mut exported = Vec::new();
+        let (result, _) = original.export(None, None, Order::Ascending, true, &mut |record| {
+            exported.push(record)
+        });
+        assert_eq!(result.unwrap(), 50);
+
+        let mut copy = MockStorage::new();
+        let (result, gas_info) = copy.import(&mut exported.into_iter(), true);
+        assert_eq!(result.unwrap(), 50);
+        assert_eq!(gas_info.cost, 0);
+
+        let iter_id = copy.scan(None, None, Order::Ascending).0.unwrap();
+        let all = copy.all(iter_id).0.unwrap();
+        assert_eq!(all.len(), 50);
+        assert_eq!(all[7], (vec![7], vec![7, 7]));
+    }
 }
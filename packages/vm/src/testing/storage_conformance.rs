@@ -0,0 +1,329 @@
+//! A conformance test suite for [`Storage`] implementations.
+//!
+//! The semantics checked here are observable by contracts and thus consensus relevant.
+//! Embedders should run [`check_all`] against their storage implementation to ensure
+//! it behaves exactly like [`MockStorage`](super::MockStorage), which is used in
+//! contract unit tests.
+//!
+//! All checks panic with a descriptive message if the storage does not conform.
+//! Each check gets a fresh storage from the `new_storage` factory.
+
+#[cfg(feature = "iterator")]
+use cosmwasm_std::{Order, Record};
+
+#[cfg(feature = "iterator")]
+use crate::BackendError;
+use crate::Storage;
+
+/// Runs all checks of this module.
+pub fn check_all<S: Storage>(new_storage: impl Fn() -> S) {
+    check_get_set_remove(new_storage());
+    #[cfg(feature = "iterator")]
+    {
+        check_iteration_order(new_storage());
+        check_iteration_bounds(new_storage());
+        check_prefix_iteration(new_storage());
+        check_write_during_iteration(new_storage());
+        check_iterator_ids(new_storage(), new_storage());
+    }
+}
+
+/// Checks that values can be written, overwritten and removed.
+pub fn check_get_set_remove<S: Storage>(mut storage: S) {
+    assert_eq!(
+        get(&storage, b"foo"),
+        None,
+        "empty storage must not contain keys"
+    );
+
+    set(&mut storage, b"foo", b"bar");
+    assert_eq!(
+        get(&storage, b"foo"),
+        Some(b"bar".to_vec()),
+        "set value must be returned"
+    );
+    assert_eq!(get(&storage, b"fo"), None, "prefix of a key must not exist");
+    assert_eq!(
+        get(&storage, b"food"),
+        None,
+        "extension of a key must not exist"
+    );
+
+    set(&mut storage, b"foo", b"baz");
+    assert_eq!(
+        get(&storage, b"foo"),
+        Some(b"baz".to_vec()),
+        "set must overwrite"
+    );
+
+    remove(&mut storage, b"foo");
+    assert_eq!(get(&storage, b"foo"), None, "removed key must not exist");
+
+    // removing a non-existent key is not an error
+    remove(&mut storage, b"foo");
+    remove(&mut storage, b"never set");
+}
+
+/// Checks that keys are iterated in lexicographical byte order, both ascending and descending.
+#[cfg(feature = "iterator")]
+pub fn check_iteration_order<S: Storage>(mut storage: S) {
+    // Keys are inserted out of order and contain prefixes of each other as well as 0x00 and 0xff bytes.
+    // The empty key is not included, since storages are not required to support it.
+    let keys: [&[u8]; 6] = [b"b", b"\xff", b"a\x00", b"ab", b"a", b"\x00"];
+    for key in keys {
+        set(&mut storage, key, b"v");
+    }
+
+    let mut expected: Vec<Record> = keys.iter().map(|k| (k.to_vec(), b"v".to_vec())).collect();
+    expected.sort();
+    assert_eq!(
+        scan_all(&mut storage, None, None, Order::Ascending),
+        expected,
+        "ascending iteration must be sorted by key bytes"
+    );
+
+    expected.reverse();
+    assert_eq!(
+        scan_all(&mut storage, None, None, Order::Descending),
+        expected,
+        "descending iteration must be sorted by key bytes in reverse"
+    );
+}
+
+/// Checks that the start bound is inclusive, the end bound exclusive and that
+/// empty ranges do not error.
+#[cfg(feature = "iterator")]
+pub fn check_iteration_bounds<S: Storage>(mut storage: S) {
+    for key in [b"a", b"b", b"c", b"d"] {
+        set(&mut storage, key, key);
+    }
+    let records =
+        |keys: &[&[u8]]| -> Vec<Record> { keys.iter().map(|k| (k.to_vec(), k.to_vec())).collect() };
+
+    for order in [Order::Ascending, Order::Descending] {
+        let name = match order {
+            Order::Ascending => "ascending",
+            Order::Descending => "descending",
+        };
+        let sorted = |mut records: Vec<Record>| {
+            if order == Order::Descending {
+                records.reverse();
+            }
+            records
+        };
+
+        assert_eq!(
+            scan_all(&mut storage, Some(b"b"), Some(b"d"), order),
+            sorted(records(&[b"b", b"c"])),
+            "start must be inclusive and end exclusive ({name})"
+        );
+        assert_eq!(
+            scan_all(&mut storage, Some(b"bb"), Some(b"cc"), order),
+            sorted(records(&[b"c"])),
+            "bounds must not need to exist ({name})"
+        );
+        assert_eq!(
+            scan_all(&mut storage, Some(b"c"), None, order),
+            sorted(records(&[b"c", b"d"])),
+            "missing end must be unbounded ({name})"
+        );
+        assert_eq!(
+            scan_all(&mut storage, None, Some(b"b"), order),
+            sorted(records(&[b"a"])),
+            "missing start must be unbounded ({name})"
+        );
+        assert_eq!(
+            scan_all(&mut storage, Some(b"b"), Some(b"b"), order),
+            vec![],
+            "start equal to end must be empty ({name})"
+        );
+        assert_eq!(
+            scan_all(&mut storage, Some(b"c"), Some(b"b"), order),
+            vec![],
+            "start greater than end must be empty ({name})"
+        );
+    }
+}
+
+/// Checks that prefix iteration includes exactly the keys starting with the prefix,
+/// including prefixes ending in 0xff bytes.
+#[cfg(feature = "iterator")]
+pub fn check_prefix_iteration<S: Storage>(mut storage: S) {
+    let keys: [&[u8]; 6] = [b"a", b"ab", b"abc", b"ac", b"a\xff", b"a\xff\x01"];
+    for key in keys {
+        set(&mut storage, key, b"v");
+    }
+    let records = |keys: &[&[u8]]| -> Vec<Record> {
+        keys.iter().map(|k| (k.to_vec(), b"v".to_vec())).collect()
+    };
+
+    let id = ok(storage.scan_prefix(b"ab", Order::Ascending));
+    assert_eq!(
+        collect(&mut storage, id),
+        records(&[b"ab", b"abc"]),
+        "prefix iteration must include the prefix itself and all extensions"
+    );
+    let id = ok(storage.scan_prefix(b"a\xff", Order::Descending));
+    assert_eq!(
+        collect(&mut storage, id),
+        records(&[b"a\xff\x01", b"a\xff"]),
+        "prefix iteration must support prefixes ending in 0xff"
+    );
+}
+
+/// Checks that an iterator is not affected by writes after its creation, i.e. it
+/// iterates over a snapshot of the state at the time it was created.
+#[cfg(feature = "iterator")]
+pub fn check_write_during_iteration<S: Storage>(mut storage: S) {
+    for key in [b"a", b"b", b"c"] {
+        set(&mut storage, key, b"old");
+    }
+
+    let id = ok(storage.scan(None, None, Order::Ascending));
+    assert_eq!(ok(storage.next(id)), Some((b"a".to_vec(), b"old".to_vec())));
+
+    // Overwrite a key ahead of the iterator, remove one and insert new ones before and after
+    set(&mut storage, b"b", b"new");
+    remove(&mut storage, b"c");
+    set(&mut storage, b"0", b"new");
+    set(&mut storage, b"d", b"new");
+
+    assert_eq!(
+        collect(&mut storage, id),
+        vec![
+            (b"b".to_vec(), b"old".to_vec()),
+            (b"c".to_vec(), b"old".to_vec()),
+        ],
+        "iterator must not see writes after its creation"
+    );
+
+    // A new iterator sees the new state
+    assert_eq!(
+        scan_all(&mut storage, None, None, Order::Ascending),
+        vec![
+            (b"0".to_vec(), b"new".to_vec()),
+            (b"a".to_vec(), b"old".to_vec()),
+            (b"b".to_vec(), b"new".to_vec()),
+            (b"d".to_vec(), b"new".to_vec()),
+        ],
+        "new iterator must see all writes"
+    );
+}
+
+/// Checks that iterator IDs are deterministic, iterators are independent of each other
+/// and exhausted iterators keep returning `None`.
+///
+/// The two storages must be fresh instances of the same implementation.
+#[cfg(feature = "iterator")]
+pub fn check_iterator_ids<S: Storage>(mut storage: S, mut other: S) {
+    for storage in [&mut storage, &mut other] {
+        set(storage, b"a", b"1");
+        set(storage, b"b", b"2");
+    }
+
+    let ids: Vec<u32> = (0..3)
+        .map(|_| ok(storage.scan(None, None, Order::Ascending)))
+        .collect();
+    let other_ids: Vec<u32> = (0..3)
+        .map(|_| ok(other.scan(None, None, Order::Ascending)))
+        .collect();
+    assert_eq!(ids, other_ids, "iterator IDs must be deterministic");
+    assert!(
+        ids[0] != ids[1] && ids[1] != ids[2] && ids[0] != ids[2],
+        "iterator IDs must be unique"
+    );
+
+    // Interleaved use of two iterators
+    assert_eq!(
+        ok(storage.next(ids[0])),
+        Some((b"a".to_vec(), b"1".to_vec()))
+    );
+    assert_eq!(
+        ok(storage.next(ids[1])),
+        Some((b"a".to_vec(), b"1".to_vec()))
+    );
+    assert_eq!(
+        ok(storage.next(ids[0])),
+        Some((b"b".to_vec(), b"2".to_vec()))
+    );
+    assert_eq!(ok(storage.next(ids[0])), None);
+    assert_eq!(
+        ok(storage.next(ids[0])),
+        None,
+        "exhausted iterator must stay exhausted"
+    );
+    assert_eq!(
+        ok(storage.next(ids[1])),
+        Some((b"b".to_vec(), b"2".to_vec()))
+    );
+
+    let unknown = ids.iter().max().unwrap() + 1000;
+    let (result, _gas_info) = storage.next(unknown);
+    assert!(
+        matches!(result, Err(BackendError::IteratorDoesNotExist { id }) if id == unknown),
+        "unknown iterator ID must return IteratorDoesNotExist"
+    );
+}
+
+fn get<S: Storage>(storage: &S, key: &[u8]) -> Option<Vec<u8>> {
+    storage.get(key).0.expect("get must not fail")
+}
+
+fn set<S: Storage>(storage: &mut S, key: &[u8], value: &[u8]) {
+    storage.set(key, value).0.expect("set must not fail");
+}
+
+fn remove<S: Storage>(storage: &mut S, key: &[u8]) {
+    storage.remove(key).0.expect("remove must not fail");
+}
+
+#[cfg(feature = "iterator")]
+fn ok<T>(result: crate::BackendResult<T>) -> T {
+    result.0.expect("storage call must not fail")
+}
+
+#[cfg(feature = "iterator")]
+fn collect<S: Storage>(storage: &mut S, iterator_id: u32) -> Vec<Record> {
+    let mut out = Vec::new();
+    while let Some(record) = ok(storage.next(iterator_id)) {
+        out.push(record);
+    }
+    out
+}
+
+#[cfg(feature = "iterator")]
+fn scan_all<S: Storage>(
+    storage: &mut S,
+    start: Option<&[u8]>,
+    end: Option<&[u8]>,
+    order: Order,
+) -> Vec<Record> {
+    let id = ok(storage.scan(start, end, order));
+    collect(storage, id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockStorage;
+    use crate::StorageOverlay;
+
+    #[test]
+    fn mock_storage_conforms() {
+        check_all(MockStorage::new);
+    }
+
+    #[test]
+    fn storage_overlay_conforms() {
+        check_all(|| StorageOverlay::new(MockStorage::new()));
+
+        // with changes and underlying data mixed
+        check_all(|| {
+            let mut inner = MockStorage::new();
+            inner.set(b"zzz", b"inner").0.unwrap();
+            let mut overlay = StorageOverlay::new(inner);
+            overlay.remove(b"zzz").0.unwrap();
+            overlay
+        });
+    }
+}
@@ -1,11 +1,9 @@
+use std::fmt;
 use std::sync::Arc;
-#[cfg(feature = "cranelift")]
-use wasmer::Cranelift;
 use wasmer::NativeEngineExt;
-#[cfg(not(feature = "cranelift"))]
-use wasmer::Singlepass;
 use wasmer::{
-    wasmparser::Operator, BaseTunables, CompilerConfig, Engine, Pages, Target, WASM_PAGE_SIZE,
+    wasmparser::Operator, BaseTunables, CompilerConfig, Cranelift, Engine, Pages, Singlepass,
+    Target, WASM_PAGE_SIZE,
 };
 use wasmer_middlewares::Metering;
 
@@ -13,6 +11,9 @@ use crate::size::Size;
 
 use super::gatekeeper::Gatekeeper;
 use super::limiting_tunables::LimitingTunables;
+use super::memory_limiter::MemoryLimiter;
+use super::memory_metering::MemoryMetering;
+use super::stack_limiter::StackLimiter;
 
 /// WebAssembly linear memory objects have sizes measured in pages. Each page
 /// is 65536 (2^16) bytes. In WebAssembly version 1, a linear memory can have at
@@ -20,6 +21,45 @@ use super::limiting_tunables::LimitingTunables;
 /// https://github.com/WebAssembly/memory64/blob/master/proposals/memory64/Overview.md
 const MAX_WASM_PAGES: u32 = 65536;
 
+/// The compiler used to compile Wasm to modules.
+///
+/// Both compilers produce modules with the same gas metering. Singlepass compiles fast
+/// and in linear time, which makes it safe to use on untrusted code and is the right
+/// choice for validators. Cranelift takes longer to compile but produces faster code,
+/// which can be used on nodes that only serve queries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compiler {
+    Singlepass,
+    Cranelift,
+}
+
+impl Compiler {
+    /// The name of the compiler as used in artifact directories and tags
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Compiler::Singlepass => "singlepass",
+            Compiler::Cranelift => "cranelift",
+        }
+    }
+}
+
+impl Default for Compiler {
+    /// Singlepass, or Cranelift if the `cranelift` feature is enabled
+    fn default() -> Self {
+        if cfg!(feature = "cranelift") {
+            Compiler::Cranelift
+        } else {
+            Compiler::Singlepass
+        }
+    }
+}
+
+impl fmt::Display for Compiler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 fn cost(_operator: &Operator) -> u64 {
     // A flat fee for each operation
     // The target is 1 Teragas per second (see GAS.md).
@@ -42,22 +82,20 @@ pub fn make_runtime_engine(memory_limit: Option<Size>) -> Engine {
     engine
 }
 
-/// Creates an Engine with a compiler attached. Use this when compiling Wasm to a module.
+/// Creates an Engine with the default compiler attached. Use this when compiling Wasm to a module.
 pub fn make_compiling_engine(memory_limit: Option<Size>) -> Engine {
-    let gas_limit = 0;
-    let deterministic = Arc::new(Gatekeeper::default());
-    let metering = Arc::new(Metering::new(gas_limit, cost));
-
-    #[cfg(feature = "cranelift")]
-    let mut compiler = Cranelift::default();
-
-    #[cfg(not(feature = "cranelift"))]
-    let mut compiler = Singlepass::default();
+    make_compiling_engine_with_compiler(Compiler::default(), memory_limit)
+}
 
-    compiler.canonicalize_nans(true);
-    compiler.push_middleware(deterministic);
-    compiler.push_middleware(metering);
-    let mut engine = Engine::from(compiler);
+/// Creates an Engine with the given compiler attached. Use this when compiling Wasm to a module.
+pub fn make_compiling_engine_with_compiler(
+    compiler: Compiler,
+    memory_limit: Option<Size>,
+) -> Engine {
+    let mut engine = match compiler {
+        Compiler::Singlepass => Engine::from(configure_compiler(Singlepass::default())),
+        Compiler::Cranelift => Engine::from(configure_compiler(Cranelift::default())),
+    };
     if let Some(limit) = memory_limit {
         let base = BaseTunables::for_target(&Target::default());
         let tunables = LimitingTunables::new(base, limit_to_pages(limit));
@@ -66,6 +104,27 @@ pub fn make_compiling_engine(memory_limit: Option<Size>) -> Engine {
     engine
 }
 
+/// Adds the middlewares and settings required for deterministic execution and gas metering
+fn configure_compiler<C: CompilerConfig>(mut compiler: C) -> C {
+    let gas_limit = 0;
+    let deterministic = Arc::new(Gatekeeper::default());
+    let metering = Arc::new(Metering::new(gas_limit, cost));
+    let memory_metering = Arc::new(MemoryMetering::default());
+    let memory_limiter = Arc::new(MemoryLimiter::new());
+    let stack_limiter = Arc::new(StackLimiter::default());
+
+    compiler.canonicalize_nans(true);
+    compiler.push_middleware(deterministic);
+    compiler.push_middleware(metering);
+    // Uses the globals of the metering middleware, so it must run after it
+    compiler.push_middleware(memory_metering);
+    // Checks the limit after the gas for the requested pages was charged
+    compiler.push_middleware(memory_limiter);
+    // The stack limiter runs after metering such that its instructions are not metered
+    compiler.push_middleware(stack_limiter);
+    compiler
+}
+
 fn limit_to_pages(limit: Size) -> Pages {
     // round down to ensure the limit is less than or equal to the config
     let limit_in_pages: usize = limit.0 / WASM_PAGE_SIZE;
@@ -0,0 +1,255 @@
+use std::sync::Mutex;
+
+use wasmer::wasmparser::{BlockType, Operator};
+use wasmer::{
+    AsStoreMut, ExportIndex, FunctionMiddleware, GlobalInit, GlobalType, Instance,
+    LocalFunctionIndex, MiddlewareError, MiddlewareReaderState, ModuleMiddleware, Mutability, Type,
+    Value,
+};
+use wasmer_types::{GlobalIndex, ModuleInfo};
+
+const MEMORY_LIMIT_EXPORT: &str = "cosmwasm_memory_limit_pages";
+const MEMORY_LIMIT_EXCEEDED_EXPORT: &str = "cosmwasm_memory_limit_exceeded";
+const MEMORY_GROW_PAGES_EXPORT: &str = "cosmwasm_memory_grow_pages";
+
+/// The value of the limit global if no limit is set
+const NO_LIMIT: i64 = i64::MAX;
+
+#[derive(Debug, Clone, Copy)]
+struct MemoryLimiterGlobalIndexes {
+    /// The maximum number of pages the memory may be grown to
+    limit: GlobalIndex,
+    /// 1 if a `memory.grow` exceeded the limit, 0 otherwise
+    exceeded: GlobalIndex,
+    /// Holds the argument of the last `memory.grow` while the limit is checked
+    pages: GlobalIndex,
+}
+
+/// A middleware that enforces a per instance limit on the number of memory pages at
+/// `memory.grow` time.
+///
+/// In contrast to the limit set via the tunables, this limit can be changed at runtime
+/// (see [`set_memory_limit`]). A `memory.grow` that would make the memory larger than the
+/// limit aborts execution, such that the contract cannot allocate more memory than allowed
+/// by the embedder.
+///
+/// Like Wasmer's metering middleware, an instance must only be used for a single module.
+#[derive(Debug, Default)]
+pub struct MemoryLimiter {
+    global_indexes: Mutex<Option<MemoryLimiterGlobalIndexes>>,
+}
+
+impl MemoryLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ModuleMiddleware for MemoryLimiter {
+    fn generate_function_middleware(&self, _: LocalFunctionIndex) -> Box<dyn FunctionMiddleware> {
+        Box::new(FunctionMemoryLimiter {
+            global_indexes: self.global_indexes.lock().unwrap().unwrap(),
+        })
+    }
+
+    fn transform_module_info(&self, module_info: &mut ModuleInfo) {
+        let mut global_indexes = self.global_indexes.lock().unwrap();
+        if global_indexes.is_some() {
+            panic!("MemoryLimiter::transform_module_info: Attempting to use a `MemoryLimiter` middleware from multiple modules.");
+        }
+
+        let mut push_global = |name: &str, ty: Type, init: GlobalInit| {
+            let index = module_info
+                .globals
+                .push(GlobalType::new(ty, Mutability::Var));
+            module_info.global_initializers.push(init);
+            module_info
+                .exports
+                .insert(name.to_string(), ExportIndex::Global(index));
+            index
+        };
+
+        // The limit is stored as i64 such that the default is larger than any possible memory size
+        let limit = push_global(
+            MEMORY_LIMIT_EXPORT,
+            Type::I64,
+            GlobalInit::I64Const(NO_LIMIT),
+        );
+        let exceeded = push_global(
+            MEMORY_LIMIT_EXCEEDED_EXPORT,
+            Type::I32,
+            GlobalInit::I32Const(0),
+        );
+        let pages = push_global(MEMORY_GROW_PAGES_EXPORT, Type::I32, GlobalInit::I32Const(0));
+
+        *global_indexes = Some(MemoryLimiterGlobalIndexes {
+            limit,
+            exceeded,
+            pages,
+        });
+    }
+}
+
+#[derive(Debug)]
+struct FunctionMemoryLimiter {
+    global_indexes: MemoryLimiterGlobalIndexes,
+}
+
+impl FunctionMiddleware for FunctionMemoryLimiter {
+    fn feed<'a>(
+        &mut self,
+        operator: Operator<'a>,
+        state: &mut MiddlewareReaderState<'a>,
+    ) -> Result<(), MiddlewareError> {
+        if let Operator::MemoryGrow { mem, mem_byte } = operator {
+            let pages = self.global_indexes.pages.as_u32();
+
+            state.extend(&[
+                // globals[pages] = the number of pages to grow by
+                Operator::GlobalSet {
+                    global_index: pages,
+                },
+                // if memory.size + globals[pages] > globals[limit] { globals[exceeded] = 1; throw(); }
+                // The sum is calculated in 64 bit to avoid overflows.
+                Operator::MemorySize { mem, mem_byte },
+                Operator::I64ExtendI32U,
+                Operator::GlobalGet {
+                    global_index: pages,
+                },
+                Operator::I64ExtendI32U,
+                Operator::I64Add,
+                Operator::GlobalGet {
+                    global_index: self.global_indexes.limit.as_u32(),
+                },
+                Operator::I64GtU,
+                Operator::If {
+                    blockty: BlockType::Empty,
+                },
+                Operator::I32Const { value: 1 },
+                Operator::GlobalSet {
+                    global_index: self.global_indexes.exceeded.as_u32(),
+                },
+                Operator::Unreachable,
+                Operator::End,
+                // restore the argument of memory.grow
+                Operator::GlobalGet {
+                    global_index: pages,
+                },
+            ]);
+        }
+
+        state.push_operator(operator);
+        Ok(())
+    }
+}
+
+/// Sets the maximum number of pages the memory of the instance can be grown to.
+/// `None` removes the limit.
+///
+/// This has no effect for instances of modules compiled without the [`MemoryLimiter`].
+pub fn set_memory_limit(store: &mut impl AsStoreMut, instance: &Instance, limit: Option<u32>) {
+    if let Ok(global) = instance.exports.get_global(MEMORY_LIMIT_EXPORT) {
+        let value = limit.map_or(NO_LIMIT, i64::from);
+        // The global is created as mutable i64 above, so this cannot fail
+        global.set(store, Value::I64(value)).unwrap();
+    }
+}
+
+/// If the last call into the instance was stopped because a `memory.grow` exceeded the limit,
+/// this returns the number of pages the memory was requested to grow by.
+///
+/// Returns `None` for instances of modules compiled without the [`MemoryLimiter`].
+pub fn memory_limit_exceeded(store: &mut impl AsStoreMut, instance: &Instance) -> Option<u32> {
+    let exceeded = instance
+        .exports
+        .get_global(MEMORY_LIMIT_EXCEEDED_EXPORT)
+        .ok()?;
+    if exceeded.get(store) != Value::I32(1) {
+        return None;
+    }
+    match instance
+        .exports
+        .get_global(MEMORY_GROW_PAGES_EXPORT)
+        .ok()?
+        .get(store)
+    {
+        Value::I32(pages) => Some(pages as u32),
+        _ => None,
+    }
+}
+
+/// Resets the flag that is set when a `memory.grow` exceeded the limit.
+pub fn reset_memory_limit_exceeded(store: &mut impl AsStoreMut, instance: &Instance) {
+    if let Ok(global) = instance.exports.get_global(MEMORY_LIMIT_EXCEEDED_EXPORT) {
+        // The global is created as mutable i32 above, so this cannot fail
+        global.set(store, Value::I32(0)).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use wasmer::{imports, CompilerConfig, Cranelift, Module, Store, TypedFunction};
+
+    fn instantiate(wasm: &str) -> (Store, Instance) {
+        let wasm = wat::parse_str(wasm).unwrap();
+        let mut compiler = Cranelift::default();
+        compiler.push_middleware(Arc::new(MemoryLimiter::new()));
+        let mut store = Store::new(compiler);
+        let module = Module::new(&store, wasm).unwrap();
+        let instance = Instance::new(&mut store, &module, &imports! {}).unwrap();
+        (store, instance)
+    }
+
+    const GROW: &str = r#"
+        (module
+            (memory (export "memory") 1 100)
+            (func (export "grow") (param i32) (result i32)
+                local.get 0
+                memory.grow
+            ))
+        "#;
+
+    #[test]
+    fn memory_limiter_is_unlimited_by_default() {
+        let (mut store, instance) = instantiate(GROW);
+        let grow: TypedFunction<i32, i32> =
+            instance.exports.get_typed_function(&store, "grow").unwrap();
+
+        assert_eq!(grow.call(&mut store, 50).unwrap(), 1);
+        // growing beyond the maximum of the memory type fails as usual
+        assert_eq!(grow.call(&mut store, 200).unwrap(), -1);
+        assert_eq!(grow.call(&mut store, -1).unwrap(), -1);
+        assert_eq!(memory_limit_exceeded(&mut store, &instance), None);
+    }
+
+    #[test]
+    fn memory_limiter_stops_growth_beyond_limit() {
+        let (mut store, instance) = instantiate(GROW);
+        let grow: TypedFunction<i32, i32> =
+            instance.exports.get_typed_function(&store, "grow").unwrap();
+        set_memory_limit(&mut store, &instance, Some(5));
+
+        // growing up to the limit works
+        assert_eq!(grow.call(&mut store, 3).unwrap(), 1);
+        assert_eq!(grow.call(&mut store, 1).unwrap(), 4);
+        assert_eq!(memory_limit_exceeded(&mut store, &instance), None);
+
+        grow.call(&mut store, 2).unwrap_err();
+        assert_eq!(memory_limit_exceeded(&mut store, &instance), Some(2));
+        let memory = instance.exports.get_memory("memory").unwrap();
+        assert_eq!(memory.view(&store).size().0, 5);
+
+        // huge requests do not overflow
+        reset_memory_limit_exceeded(&mut store, &instance);
+        assert_eq!(memory_limit_exceeded(&mut store, &instance), None);
+        grow.call(&mut store, -1).unwrap_err();
+        assert_eq!(memory_limit_exceeded(&mut store, &instance), Some(u32::MAX));
+
+        // lifting the limit
+        reset_memory_limit_exceeded(&mut store, &instance);
+        set_memory_limit(&mut store, &instance, None);
+        assert_eq!(grow.call(&mut store, 2).unwrap(), 5);
+    }
+}
@@ -2,7 +2,15 @@ mod compile;
 mod engine;
 mod gatekeeper;
 mod limiting_tunables;
+mod memory_limiter;
+mod memory_metering;
+mod stack_limiter;
 
 pub use compile::compile;
-pub use engine::{make_compiling_engine, make_runtime_engine};
+pub use engine::{
+    make_compiling_engine, make_compiling_engine_with_compiler, make_runtime_engine, Compiler,
+};
 pub use limiting_tunables::LimitingTunables;
+pub use memory_limiter::{memory_limit_exceeded, reset_memory_limit_exceeded, set_memory_limit};
+pub use memory_metering::MEMORY_PAGE_COST;
+pub use stack_limiter::{is_stack_exhausted, reset_stack_depth};
@@ -0,0 +1,486 @@
+use std::sync::Mutex;
+
+use wasmer::wasmparser::{BlockType, Operator};
+use wasmer::{
+    AsStoreMut, ExportIndex, FunctionMiddleware, GlobalInit, GlobalType, Instance,
+    LocalFunctionIndex, MiddlewareError, MiddlewareReaderState, ModuleMiddleware, Mutability, Type,
+    Value,
+};
+use wasmer_types::{GlobalIndex, ModuleInfo};
+
+/// The maximum number of nested Wasm function calls.
+///
+/// This limits the number of calls, not the size of their frames. Native stack usage per
+/// Wasm frame depends on the compiler, the platform and the number of locals and operands of
+/// the function. For typical functions, the native stack Wasmer executes Wasm code on does not
+/// overflow before this limit is reached, such that the behaviour of deep recursion is the same
+/// on all nodes. Functions with very large frames can still overflow the native stack at a
+/// lower depth. Such a trap is reported as [`VmError::StackDepthExceeded`](crate::VmError)
+/// as well, but the depth at which it happens may differ between nodes.
+///
+/// Changing this value changes the behaviour of compiled modules and requires a bump of
+/// the module serialization version.
+pub const MAX_STACK_DEPTH: u32 = 1024;
+
+const STACK_DEPTH_EXPORT: &str = "cosmwasm_stack_depth";
+const STACK_EXHAUSTED_EXPORT: &str = "cosmwasm_stack_exhausted";
+
+#[derive(Debug, Clone, Copy)]
+struct StackLimiterGlobalIndexes {
+    /// The current number of nested calls
+    depth: GlobalIndex,
+    /// 1 if the limit was exceeded, 0 otherwise
+    exhausted: GlobalIndex,
+    /// Holds the operand of a `br_if` or `br_table` while the depth is updated
+    scratch: GlobalIndex,
+}
+
+/// A middleware that limits the number of nested Wasm function calls to a fixed value.
+///
+/// The depth is tracked in a global, which is incremented when entering a function and decremented
+/// when leaving it, i.e. at the end of the function body, at `return` and at branches to the
+/// outermost label of the function. This makes stack overflows deterministic instead of depending
+/// on the native stack size of the operating system thread.
+///
+/// Like Wasmer's metering middleware, an instance must only be used for a single module.
+#[derive(Debug)]
+pub struct StackLimiter {
+    limit: u32,
+    global_indexes: Mutex<Option<StackLimiterGlobalIndexes>>,
+}
+
+impl StackLimiter {
+    pub fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            global_indexes: Mutex::new(None),
+        }
+    }
+}
+
+impl Default for StackLimiter {
+    fn default() -> Self {
+        Self::new(MAX_STACK_DEPTH)
+    }
+}
+
+impl ModuleMiddleware for StackLimiter {
+    fn generate_function_middleware(&self, _: LocalFunctionIndex) -> Box<dyn FunctionMiddleware> {
+        Box::new(FunctionStackLimiter {
+            limit: self.limit,
+            global_indexes: self.global_indexes.lock().unwrap().unwrap(),
+            entered: false,
+            block_depth: 0,
+        })
+    }
+
+    fn transform_module_info(&self, module_info: &mut ModuleInfo) {
+        let mut global_indexes = self.global_indexes.lock().unwrap();
+        if global_indexes.is_some() {
+            panic!("StackLimiter::transform_module_info: Attempting to use a `StackLimiter` middleware from multiple modules.");
+        }
+
+        let depth = module_info
+            .globals
+            .push(GlobalType::new(Type::I32, Mutability::Var));
+        module_info
+            .global_initializers
+            .push(GlobalInit::I32Const(0));
+        module_info
+            .exports
+            .insert(STACK_DEPTH_EXPORT.to_string(), ExportIndex::Global(depth));
+
+        let exhausted = module_info
+            .globals
+            .push(GlobalType::new(Type::I32, Mutability::Var));
+        module_info
+            .global_initializers
+            .push(GlobalInit::I32Const(0));
+        module_info.exports.insert(
+            STACK_EXHAUSTED_EXPORT.to_string(),
+            ExportIndex::Global(exhausted),
+        );
+
+        let scratch = module_info
+            .globals
+            .push(GlobalType::new(Type::I32, Mutability::Var));
+        module_info
+            .global_initializers
+            .push(GlobalInit::I32Const(0));
+
+        *global_indexes = Some(StackLimiterGlobalIndexes {
+            depth,
+            exhausted,
+            scratch,
+        });
+    }
+}
+
+#[derive(Debug)]
+struct FunctionStackLimiter {
+    limit: u32,
+    global_indexes: StackLimiterGlobalIndexes,
+    /// True once the function prologue was emitted
+    entered: bool,
+    /// The number of currently open blocks. The final `end` of the function body closes level 0.
+    block_depth: u32,
+}
+
+impl FunctionStackLimiter {
+    /// Returns true if a branch with the given relative depth leaves the function
+    fn is_outermost(&self, relative_depth: u32) -> bool {
+        relative_depth == self.block_depth
+    }
+
+    fn leave(&self, state: &mut MiddlewareReaderState) {
+        let depth = self.global_indexes.depth.as_u32();
+        state.extend(&[
+            Operator::GlobalGet {
+                global_index: depth,
+            },
+            Operator::I32Const { value: 1 },
+            Operator::I32Sub,
+            Operator::GlobalSet {
+                global_index: depth,
+            },
+        ]);
+    }
+
+    /// Like `leave`, but only if the i32 pushed onto the stack by `condition` is not 0.
+    ///
+    /// The operand on top of the stack is stored in the scratch global before and restored
+    /// afterwards, such that `condition` can access it.
+    fn leave_if(
+        &self,
+        state: &mut MiddlewareReaderState,
+        condition: impl FnOnce(&mut MiddlewareReaderState, u32),
+    ) {
+        let depth = self.global_indexes.depth.as_u32();
+        let scratch = self.global_indexes.scratch.as_u32();
+        state.extend(&[
+            Operator::GlobalSet {
+                global_index: scratch,
+            },
+            // globals[depth] -= (condition != 0)
+            Operator::GlobalGet {
+                global_index: depth,
+            },
+        ]);
+        condition(state, scratch);
+        state.extend(&[
+            Operator::I32Eqz,
+            Operator::I32Eqz,
+            Operator::I32Sub,
+            Operator::GlobalSet {
+                global_index: depth,
+            },
+            Operator::GlobalGet {
+                global_index: scratch,
+            },
+        ]);
+    }
+}
+
+impl FunctionMiddleware for FunctionStackLimiter {
+    fn feed<'a>(
+        &mut self,
+        operator: Operator<'a>,
+        state: &mut MiddlewareReaderState<'a>,
+    ) -> Result<(), MiddlewareError> {
+        if !self.entered {
+            self.entered = true;
+            let depth = self.global_indexes.depth.as_u32();
+            let exhausted = self.global_indexes.exhausted.as_u32();
+            state.extend(&[
+                // globals[depth] += 1
+                Operator::GlobalGet {
+                    global_index: depth,
+                },
+                Operator::I32Const { value: 1 },
+                Operator::I32Add,
+                Operator::GlobalSet {
+                    global_index: depth,
+                },
+                // if unsigned(globals[depth]) > limit { globals[exhausted] = 1; throw(); }
+                Operator::GlobalGet {
+                    global_index: depth,
+                },
+                Operator::I32Const {
+                    value: self.limit as i32,
+                },
+                Operator::I32GtU,
+                Operator::If {
+                    blockty: BlockType::Empty,
+                },
+                Operator::I32Const { value: 1 },
+                Operator::GlobalSet {
+                    global_index: exhausted,
+                },
+                Operator::Unreachable,
+                Operator::End,
+            ]);
+        }
+
+        match operator {
+            Operator::Block { .. }
+            | Operator::Loop { .. }
+            | Operator::If { .. }
+            | Operator::Try { .. } => {
+                self.block_depth += 1;
+            }
+            Operator::End => {
+                if self.block_depth == 0 {
+                    // end of the function body
+                    self.leave(state);
+                } else {
+                    self.block_depth -= 1;
+                }
+            }
+            Operator::Return => {
+                self.leave(state);
+            }
+            Operator::Br { relative_depth } if self.is_outermost(relative_depth) => {
+                self.leave(state);
+            }
+            Operator::BrIf { relative_depth } if self.is_outermost(relative_depth) => {
+                // the branch is taken if the condition is not 0
+                self.leave_if(state, |state, scratch| {
+                    state.push_operator(Operator::GlobalGet {
+                        global_index: scratch,
+                    });
+                });
+            }
+            Operator::BrTable { ref targets } => {
+                let default_is_outermost = self.is_outermost(targets.default());
+                let outermost_indexes: Vec<u32> = targets
+                    .targets()
+                    .enumerate()
+                    .filter_map(|(index, target)| match target {
+                        Ok(target) if self.is_outermost(target) => Some(Ok(index as u32)),
+                        Ok(_) => None,
+                        Err(err) => Some(Err(err)),
+                    })
+                    .collect::<Result<_, _>>()
+                    .map_err(|err| MiddlewareError::new("StackLimiter", err.to_string()))?;
+                if default_is_outermost || !outermost_indexes.is_empty() {
+                    let len = targets.len();
+                    // the branch leaves the function if the index selects one of the outermost
+                    // targets or the default target is outermost and the index is out of range
+                    self.leave_if(state, |state, scratch| {
+                        state.push_operator(Operator::I32Const { value: 0 });
+                        for index in outermost_indexes {
+                            state.extend(&[
+                                Operator::GlobalGet {
+                                    global_index: scratch,
+                                },
+                                Operator::I32Const {
+                                    value: index as i32,
+                                },
+                                Operator::I32Eq,
+                                Operator::I32Or,
+                            ]);
+                        }
+                        if default_is_outermost {
+                            state.extend(&[
+                                Operator::GlobalGet {
+                                    global_index: scratch,
+                                },
+                                Operator::I32Const { value: len as i32 },
+                                Operator::I32GeU,
+                                Operator::I32Or,
+                            ]);
+                        }
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        state.push_operator(operator);
+        Ok(())
+    }
+}
+
+/// Returns true if the last call into the instance was stopped because it exceeded
+/// the stack limit.
+///
+/// Returns false for instances of modules compiled without the [`StackLimiter`].
+pub fn is_stack_exhausted(store: &mut impl AsStoreMut, instance: &Instance) -> bool {
+    instance
+        .exports
+        .get_global(STACK_EXHAUSTED_EXPORT)
+        .map(|global| global.get(store) == Value::I32(1))
+        .unwrap_or(false)
+}
+
+/// Resets the stack depth tracking of the instance.
+///
+/// This is needed after a call was aborted by a trap because the depth is not decremented
+/// for the functions that did not return.
+pub fn reset_stack_depth(store: &mut impl AsStoreMut, instance: &Instance) {
+    for name in [STACK_DEPTH_EXPORT, STACK_EXHAUSTED_EXPORT] {
+        if let Ok(global) = instance.exports.get_global(name) {
+            // The globals are created as mutable i32 above, so this cannot fail
+            global.set(store, Value::I32(0)).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use wasmer::{imports, CompilerConfig, Cranelift, Module, Store, TypedFunction};
+
+    fn instantiate(wasm: &str, limit: u32) -> (Store, Instance) {
+        let wasm = wat::parse_str(wasm).unwrap();
+        let mut compiler = Cranelift::default();
+        compiler.push_middleware(Arc::new(StackLimiter::new(limit)));
+        let mut store = Store::new(compiler);
+        let module = Module::new(&store, wasm).unwrap();
+        let instance = Instance::new(&mut store, &module, &imports! {}).unwrap();
+        (store, instance)
+    }
+
+    const RECURSION: &str = r#"
+        (module
+            (func $recurse (export "recurse") (param i32) (result i32)
+                local.get 0
+                i32.eqz
+                if
+                    i32.const 0
+                    return
+                end
+                local.get 0
+                i32.const 1
+                i32.sub
+                call $recurse
+                i32.const 1
+                i32.add
+            ))
+        "#;
+
+    #[test]
+    fn stack_limiter_allows_calls_up_to_limit() {
+        let (mut store, instance) = instantiate(RECURSION, 10);
+        let recurse: TypedFunction<i32, i32> = instance
+            .exports
+            .get_typed_function(&store, "recurse")
+            .unwrap();
+
+        // depth 10 (arguments 9..=0)
+        assert_eq!(recurse.call(&mut store, 9).unwrap(), 9);
+        // the depth is back to 0 after returning, so this can be repeated
+        assert_eq!(recurse.call(&mut store, 9).unwrap(), 9);
+        assert!(!is_stack_exhausted(&mut store, &instance));
+    }
+
+    #[test]
+    fn stack_limiter_stops_deep_recursion() {
+        let (mut store, instance) = instantiate(RECURSION, 10);
+        let recurse: TypedFunction<i32, i32> = instance
+            .exports
+            .get_typed_function(&store, "recurse")
+            .unwrap();
+
+        recurse.call(&mut store, 10).unwrap_err();
+        assert!(is_stack_exhausted(&mut store, &instance));
+
+        // infinite recursion is stopped by the limiter and not by the native stack
+        let (mut store, instance) = instantiate(RECURSION, MAX_STACK_DEPTH);
+        let recurse: TypedFunction<i32, i32> = instance
+            .exports
+            .get_typed_function(&store, "recurse")
+            .unwrap();
+        recurse.call(&mut store, -1).unwrap_err();
+        assert!(is_stack_exhausted(&mut store, &instance));
+    }
+
+    fn stack_depth(store: &mut Store, instance: &Instance) -> Value {
+        instance
+            .exports
+            .get_global(STACK_DEPTH_EXPORT)
+            .unwrap()
+            .get(store)
+    }
+
+    const BRANCHES: &str = r#"
+        (module
+            (func (export "br") (param i32) (result i32)
+                block
+                    local.get 0
+                    br 1
+                end
+                i32.const -1
+            )
+            (func (export "br_if") (param i32) (result i32)
+                block
+                    i32.const 1
+                    local.get 0
+                    br_if 1
+                    drop
+                end
+                i32.const 2
+            )
+            (func (export "br_table") (param i32) (result i32)
+                block (result i32)
+                    block (result i32)
+                        i32.const 3
+                        local.get 0
+                        ;; 0 and 3 leave the function, 1 and 2 continue, larger values leave
+                        br_table 2 0 1 2 2
+                    end
+                    drop
+                    i32.const 4
+                    return
+                end
+                drop
+                i32.const 5
+            ))
+        "#;
+
+    #[test]
+    fn stack_limiter_handles_branches_out_of_function() {
+        let (mut store, instance) = instantiate(BRANCHES, 10);
+        let functions: Vec<TypedFunction<i32, i32>> = ["br", "br_if", "br_table"]
+            .iter()
+            .map(|name| instance.exports.get_typed_function(&store, name).unwrap())
+            .collect();
+
+        let cases = [
+            (0, 0, 0),
+            (0, 7, 7),
+            (1, 0, 2),
+            (1, 1, 1),
+            (2, 0, 3),
+            (2, 1, 4),
+            (2, 2, 5),
+            (2, 3, 3),
+            (2, 100, 3),
+        ];
+        // called more often than the limit to ensure the depth is decremented on all paths
+        for _ in 0..20 {
+            for (function, arg, expected) in cases {
+                assert_eq!(functions[function].call(&mut store, arg).unwrap(), expected);
+                assert_eq!(stack_depth(&mut store, &instance), Value::I32(0));
+            }
+        }
+        assert!(!is_stack_exhausted(&mut store, &instance));
+    }
+
+    #[test]
+    fn reset_stack_depth_works() {
+        let (mut store, instance) = instantiate(RECURSION, 10);
+        let recurse: TypedFunction<i32, i32> = instance
+            .exports
+            .get_typed_function(&store, "recurse")
+            .unwrap();
+
+        recurse.call(&mut store, 100).unwrap_err();
+        assert!(is_stack_exhausted(&mut store, &instance));
+
+        reset_stack_depth(&mut store, &instance);
+        assert!(!is_stack_exhausted(&mut store, &instance));
+        assert_eq!(recurse.call(&mut store, 9).unwrap(), 9);
+    }
+}